@@ -0,0 +1,94 @@
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::hint::black_box;
+
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+use hnefatafl_copenhagen::{
+    bench::{PLIES, legal_plays, positions},
+    board::{Board, BoardSize},
+    play::Play,
+};
+
+const BOARD_SIZES: [BoardSize; 2] = [BoardSize::_11, BoardSize::_13];
+
+fn all_legal_moves(c: &mut Criterion) {
+    let mut group = c.benchmark_group("all_legal_moves");
+
+    for board_size in BOARD_SIZES {
+        for (plies, game) in PLIES.iter().zip(positions(board_size)) {
+            group.bench_with_input(
+                BenchmarkId::new(board_size.to_string(), plies),
+                &game,
+                |b, game| b.iter(|| black_box(game.all_legal_moves())),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+fn resolve_captures(c: &mut Criterion) {
+    let mut group = c.benchmark_group("resolve_captures");
+
+    for board_size in BOARD_SIZES {
+        for (plies, game) in PLIES.iter().zip(positions(board_size)) {
+            let moved: Vec<(Board, Play)> = legal_plays(&game)
+                .into_iter()
+                .filter_map(|play| {
+                    game.board
+                        .legal_move(&play, &game.status, &game.turn, &game.previous_boards)
+                        .ok()
+                        .map(|board| (board, play))
+                })
+                .collect();
+
+            group.bench_with_input(
+                BenchmarkId::new(board_size.to_string(), plies),
+                &moved,
+                |b, moved| {
+                    b.iter_batched(
+                        || moved.clone(),
+                        |moved| {
+                            for (mut board, play) in moved {
+                                black_box(board.resolve_captures(&play));
+                            }
+                        },
+                        BatchSize::SmallInput,
+                    );
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+fn flood_fill(c: &mut Criterion) {
+    let mut group = c.benchmark_group("flood_fill");
+
+    for board_size in BOARD_SIZES {
+        for (plies, game) in PLIES.iter().zip(positions(board_size)) {
+            let board = game.board;
+
+            group.bench_with_input(
+                BenchmarkId::new(format!("attacker {board_size}"), plies),
+                &board,
+                |b, board| b.iter(|| black_box(board.surrounded())),
+            );
+
+            if let Some(king) = board.king {
+                group.bench_with_input(
+                    BenchmarkId::new(format!("defender {board_size}"), plies),
+                    &board,
+                    |b, board| b.iter(|| black_box(board.flood_fill_defender_wins(&king))),
+                );
+            }
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, all_legal_moves, resolve_captures, flood_fill);
+criterion_main!(benches);
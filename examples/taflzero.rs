@@ -35,9 +35,11 @@ use hnefatafl_copenhagen::{
     COPYRIGHT, SOFTWARE_ID, VERSION_ID,
     ai::{AI, AiMonteCarlo},
     game::Game,
+    game_tree::MEMORY,
     opentafl::{OpenTaflGame, OpenTaflMoves},
     play::{Plae, Play, Vertex},
     role::Role,
+    rule_set::RuleSet,
     server_game::NewGame,
     time::{Time, TimeSettings},
     tournament::TournamentFull,
@@ -223,7 +225,11 @@ fn main() -> anyhow::Result<()> {
 
     thread::spawn(move || {
         let mut engine = Engine::new(onnx_path.to_string());
-        let mut ai = AiMonteCarlo::new(Duration::from_secs(MONTE_CARLO_SECONDS), MONTE_CARLO_DEPTH);
+        let mut ai = AiMonteCarlo::new(
+            Duration::from_secs(MONTE_CARLO_SECONDS),
+            MONTE_CARLO_DEPTH,
+            MEMORY,
+        );
 
         loop {
             let (username, opentafl_game) = rx.recv().unwrap();
@@ -341,6 +347,9 @@ impl TaflZero {
                     milliseconds_left: 900_000,
                 }),
                 board_size: 11,
+                established_only: false,
+                zen: false,
+                rule_set: RuleSet::default(),
             };
 
             let new_game = serde_json::ser::to_string(&new_game)?;
@@ -16,14 +16,38 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 // SPDX-FileCopyrightText: 2025 David Campbell <david@hnefatafl.org>
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
-use crate::{email::Email, glicko::Rating};
+use crate::{
+    analysis::Accuracy,
+    clock,
+    email::{Email, EmailPreferences},
+    glicko::Rating,
+    locale::Locale,
+    profile::Profile,
+    puzzle::Streak,
+    rating::{RatingPeriod, Ratings},
+    settings::Settings,
+    vacation::Vacation,
+};
 use jiff::Timestamp;
 use serde::{Deserialize, Serialize};
 
 use crate::Id;
 
+/// All that a kid-safe account may say in the chat.
+pub const KID_SAFE_PHRASES: [&str; 8] = [
+    "Hello!",
+    "Good luck!",
+    "Have fun!",
+    "Nice move!",
+    "Oops!",
+    "Good game!",
+    "Thanks!",
+    "Bye!",
+];
+
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct Account {
     #[serde(default)]
@@ -42,10 +66,24 @@ pub struct Account {
     pub wins: u64,
     #[serde(default)]
     pub losses: u64,
+    /// Counted since the server started keeping track.
+    #[serde(default)]
+    pub rated_games: u64,
+    /// How close the account's plays were to the engine's.
+    #[serde(default)]
+    pub accuracy: Accuracy,
     #[serde(default)]
     pub rating: Rating,
+    /// The ratings by board size and speed.
+    #[serde(default)]
+    pub ratings: Ratings,
+    /// The rated games that count at the end of the rating period.
+    #[serde(default)]
+    pub rating_period: RatingPeriod,
     #[serde(default)]
     pub send_emails: bool,
+    #[serde(default)]
+    pub email_preferences: EmailPreferences,
     #[serde(skip)]
     pub pending_games: HashSet<Id>,
     #[serde(default)]
@@ -54,6 +92,41 @@ pub struct Account {
     pub last_logged_in: DateTimeUtc,
     #[serde(default)]
     pub software_id: String,
+    /// The locale the server writes emails and other messages in.
+    #[serde(default)]
+    pub locale: Locale,
+    /// Don't mask the profanity in the chat sent to this account.
+    #[serde(default)]
+    pub show_profanity: bool,
+    /// Plays rated games against the new accounts in the provisional pool.
+    #[serde(default)]
+    pub mentor: bool,
+    #[serde(default)]
+    pub profile: Profile,
+    #[serde(default)]
+    pub vacation: Vacation,
+    #[serde(default)]
+    pub puzzle_streak: Streak,
+    /// Only chats in [`KID_SAFE_PHRASES`], gets no emails to everyone, keeps
+    /// its email out of the exports, and only plays and messages the friends an
+    /// admin approved.
+    #[serde(default)]
+    pub kid_safe: bool,
+    /// The friends a kid-safe account may play and message.
+    #[serde(default)]
+    pub friends: BTreeSet<String>,
+    /// The users who may not send this account direct messages, nor get them
+    /// from it.
+    #[serde(default)]
+    pub blocked: BTreeSet<String>,
+    /// How many of the messages in the chat of each of their games they have
+    /// seen.
+    #[serde(default)]
+    pub texts_read: HashMap<Id, usize>,
+    /// The copy of the client settings that follows the account between
+    /// computers.
+    #[serde(default)]
+    pub settings: Settings,
 }
 
 impl PartialEq for Account {
@@ -64,20 +137,61 @@ impl PartialEq for Account {
             && self.draws == other.draws
             && self.wins == other.wins
             && self.losses == other.losses
+            && self.accuracy == other.accuracy
             && self.rating == other.rating
+            && self.ratings == other.ratings
+            && self.rating_period == other.rating_period
             && self.send_emails == other.send_emails
+            && self.email_preferences == other.email_preferences
+            && self.show_profanity == other.show_profanity
+            && self.mentor == other.mentor
+            && self.profile == other.profile
+            && self.vacation == other.vacation
+            && self.puzzle_streak == other.puzzle_streak
+            && self.kid_safe == other.kid_safe
+            && self.friends == other.friends
             && self.creation_date == other.creation_date
             && self.last_logged_in == other.last_logged_in
             && self.software_id == other.software_id
     }
 }
 
+impl Account {
+    /// Whether the account may play and message `username`.
+    #[must_use]
+    pub fn kid_safe_allows(&self, username: &str) -> bool {
+        !self.kid_safe || self.friends.contains(username)
+    }
+
+    #[must_use]
+    pub fn provisional_pool(&self) -> ProvisionalPool {
+        ProvisionalPool {
+            new: self.rating.provisional(),
+            mentor: self.mentor,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ProvisionalPool {
+    pub new: bool,
+    pub mentor: bool,
+}
+
+impl ProvisionalPool {
+    /// New accounts only play rated games against mentors and each other.
+    #[must_use]
+    pub fn allows(self, other: Self) -> bool {
+        self.new == other.new || (self.new && other.mentor) || (other.new && self.mentor)
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct DateTimeUtc(pub Timestamp);
 
 impl Default for DateTimeUtc {
     fn default() -> Self {
-        Self(Timestamp::now())
+        Self(clock::now())
     }
 }
 
@@ -87,8 +201,27 @@ pub struct User {
     pub wins: u64,
     pub losses: u64,
     pub draws: u64,
+    #[serde(default)]
+    pub accuracy: Accuracy,
     pub rating: Rating,
+    #[serde(default)]
+    pub ratings: Ratings,
     pub logged_in: bool,
+    #[serde(default)]
+    pub mentor: bool,
+    /// When their vacation ends, in unix milliseconds.
+    #[serde(default)]
+    pub vacation_until: Option<i64>,
+}
+
+impl User {
+    #[must_use]
+    pub fn provisional_pool(&self) -> ProvisionalPool {
+        ProvisionalPool {
+            new: self.rating.provisional(),
+            mentor: self.mentor,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
@@ -109,8 +242,12 @@ impl From<&Accounts> for Users {
                     wins: account.wins,
                     losses: account.losses,
                     draws: account.draws,
+                    accuracy: account.accuracy.clone(),
                     rating: account.rating.clone(),
+                    ratings: account.ratings.clone(),
                     logged_in,
+                    mentor: account.mentor,
+                    vacation_until: account.vacation.until,
                 },
             );
         }
@@ -16,7 +16,15 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 // SPDX-FileCopyrightText: 2025 David Campbell <david@hnefatafl.org>
 
-use std::{fmt, sync::mpsc::channel, time::Duration};
+use std::{
+    fmt,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{Receiver, channel},
+    },
+    time::{Duration, Instant},
+};
 
 use jiff::Timestamp;
 use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
@@ -25,7 +33,7 @@ use rustc_hash::FxHashMap;
 use crate::{
     board::InvalidMove,
     game::{EscapeVec, Game},
-    game_tree::{Node, Tree},
+    game_tree::{MEMORY, Node, Tree},
     heat_map::HeatMap,
     play::Plae,
     role::Role,
@@ -37,6 +45,24 @@ pub trait AI: Send {
     ///
     /// When the game is already over.
     fn generate_move(&mut self, game: &mut Game) -> anyhow::Result<GenerateMove>;
+    /// Like `generate_move`, but calls `progress` every so often while it
+    /// searches. Only the monte-carlo AI has anything to say before it is done.
+    ///
+    /// # Errors
+    ///
+    /// When the game is already over.
+    fn generate_move_progress(
+        &mut self,
+        game: &mut Game,
+        _progress: &mut dyn FnMut(Progress),
+    ) -> anyhow::Result<GenerateMove> {
+        self.generate_move(game)
+    }
+    /// Something to stop the search with from another thread, if the AI can be
+    /// stopped.
+    fn cancel_token(&self) -> Option<Cancel> {
+        None
+    }
     #[allow(clippy::missing_errors_doc)]
     fn play(&mut self, game: &mut Game, play: &Plae) -> anyhow::Result<()> {
         game.play(play)?;
@@ -70,6 +96,41 @@ impl fmt::Display for GenerateMove {
     }
 }
 
+/// Tells a search to stop. The clones all share the same flag and a search
+/// clears it when it starts.
+#[derive(Clone, Debug, Default)]
+pub struct Cancel(Arc<AtomicBool>);
+
+impl Cancel {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn reset(&self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+}
+
+/// How often a search says how far along it is.
+pub const PROGRESS_EVERY: Duration = Duration::from_millis(250);
+
+/// How far along a search is.
+#[derive(Clone, Debug)]
+pub struct Progress {
+    pub loops: u64,
+    /// From 0 to 1.
+    pub done: f32,
+    /// The best play so far.
+    pub play: Option<Plae>,
+    pub score: f64,
+    pub heat_map: HeatMap,
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct AiBanal;
 
@@ -187,6 +248,9 @@ impl AI for AiBasic {
 pub struct AiMonteCarlo {
     duration: Duration,
     depth: u8,
+    /// How many bytes all of the trees may use together.
+    memory: usize,
+    cancel: Cancel,
 }
 
 impl Default for AiMonteCarlo {
@@ -194,67 +258,69 @@ impl Default for AiMonteCarlo {
         Self {
             duration: Duration::from_secs(1),
             depth: 80,
+            memory: MEMORY,
+            cancel: Cancel::default(),
         }
     }
 }
 
 impl AI for AiMonteCarlo {
     fn generate_move(&mut self, game: &mut Game) -> anyhow::Result<GenerateMove> {
+        self.generate_move_progress(game, &mut |_| {})
+    }
+
+    fn generate_move_progress(
+        &mut self,
+        game: &mut Game,
+        progress: &mut dyn FnMut(Progress),
+    ) -> anyhow::Result<GenerateMove> {
         if game.status != Status::Ongoing {
             return Err(InvalidMove::GameOver.into());
         }
 
+        self.cancel.reset();
         let t0 = Timestamp::now().as_millisecond();
-        let mut trees = AiMonteCarlo::make_trees(game)?;
-        let (tx, rx) = channel();
+        let started = Instant::now();
+        let thinking = self.duration.mul_f64(game.phase().time_share());
+        let mut trees = self.make_trees(game)?;
+        let mut loops_total = 0;
 
-        trees.par_iter_mut().try_for_each_with(tx, |tx, tree| {
-            let nodes = tree.monte_carlo_tree_search(self.duration, self.depth);
-            tx.send(nodes)
-        })?;
+        let nodes = loop {
+            let duration = thinking
+                .saturating_sub(started.elapsed())
+                .min(PROGRESS_EVERY);
 
-        let mut loops_total = 0;
-        let mut nodes_master = FxHashMap::default();
+            let (tx, rx) = channel();
+            trees.par_iter_mut().try_for_each_with(tx, |tx, tree| {
+                let nodes = tree.monte_carlo_tree_search(duration, self.depth);
+                tx.send(nodes)
+            })?;
 
-        while let Ok((loops, nodes)) = rx.recv() {
-            loops_total += loops;
-            for mut node in nodes {
-                if let Some(Plae::Play(play)) = node.clone().play {
-                    nodes_master
-                        .entry(play)
-                        .and_modify(|node_master: &mut Node| {
-                            if node_master.count == 0.0 {
-                                node_master.count = 1.0;
-                                node_master.score = node.score;
-                            } else {
-                                node_master.count += 1.0;
-                                node_master.score += node.score;
-                            }
-                        })
-                        .or_insert({
-                            node.count = 1.0;
-                            node
-                        });
-                }
+            if self.cancel.is_cancelled() {
+                return Err(anyhow::Error::msg("The search was cancelled."));
             }
-        }
 
-        for node in nodes_master.values_mut() {
-            node.score /= node.count;
-            node.count = 1.0;
-        }
+            let (loops, nodes) = AiMonteCarlo::merge(&rx);
+            loops_total += loops;
 
-        let mut nodes: Vec<_> = nodes_master.values().collect();
-        nodes.sort_by(|a, b| a.score.total_cmp(&b.score));
+            let elapsed = started.elapsed();
+            if thinking <= elapsed {
+                break nodes;
+            }
 
-        let turn = game.turn;
-        let message = anyhow::Error::msg("The nodes are empty.");
-        let node = match turn {
-            Role::Attacker => nodes.last().ok_or(message)?,
-            Role::Defender => nodes.first().ok_or(message)?,
-            Role::Roleless => unreachable!(),
+            let node = AiMonteCarlo::best(game.turn, &nodes);
+            progress(Progress {
+                loops: loops_total,
+                done: elapsed.as_secs_f32() / thinking.as_secs_f32(),
+                play: node.and_then(|node| node.play.clone()),
+                score: node.map_or(0.0, |node| node.score),
+                heat_map: HeatMap::from(&nodes.iter().collect::<Vec<_>>()),
+            });
         };
 
+        let node = AiMonteCarlo::best(game.turn, &nodes)
+            .ok_or(anyhow::Error::msg("The nodes are empty."))?;
+
         let play = node
             .play
             .as_ref()
@@ -269,7 +335,7 @@ impl AI for AiMonteCarlo {
 
         let t1 = Timestamp::now().as_millisecond();
         let delay_milliseconds = t1 - t0;
-        let heat_map = HeatMap::from(&nodes);
+        let heat_map = HeatMap::from(&nodes.iter().collect::<Vec<_>>());
 
         Ok(GenerateMove {
             play: play.clone(),
@@ -280,22 +346,86 @@ impl AI for AiMonteCarlo {
             escape_vec: None,
         })
     }
+
+    fn cancel_token(&self) -> Option<Cancel> {
+        Some(self.cancel.clone())
+    }
 }
 
 impl AiMonteCarlo {
-    fn make_trees(game: &Game) -> anyhow::Result<Vec<Tree>> {
+    /// The best node for `turn` out of nodes sorted by score.
+    fn best(turn: Role, nodes: &[Node]) -> Option<&Node> {
+        match turn {
+            Role::Attacker => nodes.last(),
+            Role::Defender => nodes.first(),
+            Role::Roleless => unreachable!(),
+        }
+    }
+
+    /// Averages what each of the trees found for every play and sorts the
+    /// plays by score.
+    fn merge(rx: &Receiver<(u64, Vec<Node>)>) -> (u64, Vec<Node>) {
+        let mut loops_total = 0;
+        let mut nodes_master = FxHashMap::default();
+
+        while let Ok((loops, nodes)) = rx.recv() {
+            loops_total += loops;
+            for mut node in nodes {
+                if let Some(Plae::Play(play)) = node.clone().play {
+                    nodes_master
+                        .entry(play)
+                        .and_modify(|node_master: &mut Node| {
+                            if node_master.count == 0.0 {
+                                node_master.count = 1.0;
+                                node_master.score = node.score;
+                            } else {
+                                node_master.count += 1.0;
+                                node_master.score += node.score;
+                            }
+                        })
+                        .or_insert({
+                            node.count = 1.0;
+                            node
+                        });
+                }
+            }
+        }
+
+        let mut nodes: Vec<_> = nodes_master
+            .into_values()
+            .map(|mut node| {
+                node.score /= node.count;
+                node.count = 1.0;
+                node
+            })
+            .collect();
+
+        nodes.sort_by(|a, b| a.score.total_cmp(&b.score));
+        (loops_total, nodes)
+    }
+
+    fn make_trees(&self, game: &Game) -> anyhow::Result<Vec<Tree>> {
         let count = std::thread::available_parallelism()?.get();
         let mut trees = Vec::with_capacity(count);
 
         for _ in 0..count {
-            trees.push(Tree::new(game.clone()));
+            let mut tree = Tree::with_memory(game.clone(), self.memory / count);
+            tree.cancel = self.cancel.clone();
+            trees.push(tree);
         }
 
         Ok(trees)
     }
 
+    /// `memory` is how many bytes the search may use, split between the
+    /// threads.
     #[must_use]
-    pub fn new(duration: Duration, depth: u8) -> Self {
-        Self { duration, depth }
+    pub fn new(duration: Duration, depth: u8, memory: usize) -> Self {
+        Self {
+            duration,
+            depth,
+            memory,
+            cancel: Cancel::default(),
+        }
     }
 }
@@ -0,0 +1,414 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! Looks over a finished game move by move, comparing each play to the best
+//! play the engine finds, for the coach's summary after the game.
+
+use std::collections::VecDeque;
+
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::BoardSize,
+    game::{Game, Phase},
+    play::{Plae, Plays},
+    role::Role,
+    status::Status,
+    time::TimeSettings,
+};
+
+/// How many plies the engine looks ahead from each position.
+const DEPTH: u8 = 2;
+
+/// The loss of a play that throws away a win, or lets the opponent win.
+pub const LOSS_MAX: f64 = 20_000.0;
+
+const LOSS_INACCURACY: f64 = 1_000.0;
+const LOSS_MISTAKE: f64 = 3_000.0;
+
+/// The utility counts a move closer to escaping as 1,000.
+const LOSS_HALF_ACCURACY: f64 = 1_000.0;
+
+/// How many plays the summary talks about.
+const KEY_MOVES: usize = 3;
+
+/// How many of a player's latest games count towards their recent accuracy.
+const RECENT_GAMES: usize = 20;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Judgement {
+    Best,
+    Good,
+    Inaccuracy,
+    Mistake,
+    /// The opponent can win with their next move.
+    Blunder,
+    /// There was a play that won right away.
+    MissedWin,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MoveAnalysis {
+    /// Starts at one for the first play of the game.
+    pub number: usize,
+    pub role: Role,
+    pub play: Plae,
+    pub best: Plae,
+    /// How much worse the play was than the best play, zero for the best
+    /// play and at most [`LOSS_MAX`].
+    pub loss: f64,
+    pub judgement: Judgement,
+    /// How far along the game was before the play.
+    #[serde(default)]
+    pub phase: Phase,
+}
+
+impl MoveAnalysis {
+    /// 100.0 for the best play, 50.0 for losing a move towards escape.
+    #[must_use]
+    pub fn accuracy(&self) -> f64 {
+        100.0 / (1.0 + self.loss / LOSS_HALF_ACCURACY)
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Analysis {
+    pub attacker_accuracy: Option<f64>,
+    pub defender_accuracy: Option<f64>,
+    /// The worst plays, in the order they were played.
+    pub key_moves: Vec<MoveAnalysis>,
+    /// The accuracies in each phase of the game that was played.
+    #[serde(default)]
+    pub phases: Vec<PhaseAccuracy>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PhaseAccuracy {
+    pub phase: Phase,
+    pub attacker_accuracy: Option<f64>,
+    pub defender_accuracy: Option<f64>,
+}
+
+impl Analysis {
+    #[must_use]
+    pub fn new(board_size: BoardSize, plays: &Plays) -> Self {
+        Self::from_moves(&analyze(board_size, plays))
+    }
+
+    /// Sums up plays already judged by [`analyze`].
+    #[must_use]
+    pub fn from_moves(moves: &[MoveAnalysis]) -> Self {
+        let mut key_moves: Vec<_> = moves
+            .iter()
+            .filter(|analysis| {
+                matches!(
+                    analysis.judgement,
+                    Judgement::Blunder | Judgement::MissedWin | Judgement::Mistake
+                )
+            })
+            .cloned()
+            .collect();
+
+        key_moves.sort_by(|a, b| b.loss.total_cmp(&a.loss).then(a.number.cmp(&b.number)));
+        key_moves.truncate(KEY_MOVES);
+        key_moves.sort_by_key(|analysis| analysis.number);
+
+        let phases = [Phase::Opening, Phase::Middlegame, Phase::Endgame]
+            .into_iter()
+            .filter_map(|phase| {
+                let moves: Vec<_> = moves
+                    .iter()
+                    .filter(|analysis| analysis.phase == phase)
+                    .cloned()
+                    .collect();
+
+                (!moves.is_empty()).then(|| PhaseAccuracy {
+                    phase,
+                    attacker_accuracy: accuracy(&moves, Role::Attacker),
+                    defender_accuracy: accuracy(&moves, Role::Defender),
+                })
+            })
+            .collect();
+
+        Self {
+            attacker_accuracy: accuracy(moves, Role::Attacker),
+            defender_accuracy: accuracy(moves, Role::Defender),
+            key_moves,
+            phases,
+        }
+    }
+
+    /// A few sentences on how each player did, in the current locale.
+    #[must_use]
+    pub fn summary(&self, attacker: &str, defender: &str) -> Vec<String> {
+        let username = |role| match role {
+            Role::Attacker => attacker,
+            Role::Defender | Role::Roleless => defender,
+        };
+
+        let mut summary = Vec::new();
+
+        for (role, accuracy) in [
+            (Role::Attacker, self.attacker_accuracy),
+            (Role::Defender, self.defender_accuracy),
+        ] {
+            if let Some(accuracy) = accuracy {
+                summary.push(
+                    t!(
+                        "%{username} played with %{accuracy}% accuracy.",
+                        username = username(role),
+                        accuracy = format!("{accuracy:.0}"),
+                    )
+                    .to_string(),
+                );
+            }
+        }
+
+        if self.phases.len() > 1 {
+            for phase in &self.phases {
+                for (role, accuracy) in [
+                    (Role::Attacker, phase.attacker_accuracy),
+                    (Role::Defender, phase.defender_accuracy),
+                ] {
+                    if let Some(accuracy) = accuracy {
+                        summary.push(
+                            t!(
+                                "%{phase}: %{username} played with %{accuracy}% accuracy.",
+                                phase = phase.phase,
+                                username = username(role),
+                                accuracy = format!("{accuracy:.0}"),
+                            )
+                            .to_string(),
+                        );
+                    }
+                }
+            }
+        }
+
+        if self.key_moves.is_empty() {
+            summary.push(t!("Neither player made a serious mistake.").to_string());
+        }
+
+        for analysis in &self.key_moves {
+            let number = analysis.number;
+            let username = username(analysis.role);
+            let play = short(&analysis.play);
+            let best = short(&analysis.best);
+
+            let sentence = match analysis.judgement {
+                Judgement::MissedWin => t!(
+                    "Move %{number}: %{username} played %{play}, but %{best} would have won.",
+                    number = number,
+                    username = username,
+                    play = play,
+                    best = best,
+                ),
+                Judgement::Blunder => t!(
+                    "Move %{number}: %{username} played %{play}, which let the opponent win. %{best} was better.",
+                    number = number,
+                    username = username,
+                    play = play,
+                    best = best,
+                ),
+                Judgement::Best | Judgement::Good | Judgement::Inaccuracy | Judgement::Mistake => {
+                    t!(
+                        "Move %{number}: %{username} played %{play}, but %{best} was better.",
+                        number = number,
+                        username = username,
+                        play = play,
+                        best = best,
+                    )
+                }
+            };
+
+            summary.push(format!("[{}] {sentence}", analysis.phase));
+        }
+
+        summary
+    }
+}
+
+/// A player's accuracy over all of their analyzed games, and over their latest
+/// games, so they can see whether they're improving.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Accuracy {
+    pub games: u64,
+    /// The average accuracy over all of the games.
+    pub average: f64,
+    /// The accuracies of the latest games, the oldest first.
+    pub recent: VecDeque<f64>,
+}
+
+impl Accuracy {
+    #[allow(clippy::cast_precision_loss)]
+    pub fn add(&mut self, accuracy: f64) {
+        self.games += 1;
+        self.average += (accuracy - self.average) / self.games as f64;
+
+        self.recent.push_back(accuracy);
+        if self.recent.len() > RECENT_GAMES {
+            self.recent.pop_front();
+        }
+    }
+
+    #[must_use]
+    pub fn average(&self) -> Option<f64> {
+        (self.games > 0).then_some(self.average)
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    #[must_use]
+    pub fn recent_average(&self) -> Option<f64> {
+        if self.recent.is_empty() {
+            return None;
+        }
+
+        Some(self.recent.iter().sum::<f64>() / self.recent.len() as f64)
+    }
+}
+
+/// Plays the game over from the start, judging each play until a resignation
+/// or the end of the game.
+#[must_use]
+pub fn analyze(board_size: BoardSize, plays: &Plays) -> Vec<MoveAnalysis> {
+    let plays: Vec<Plae> = match plays {
+        Plays::PlayRecordsTimed(plays) => plays
+            .iter()
+            .filter_map(|play_record| play_record.play.clone())
+            .collect(),
+        Plays::PlayRecords(plays) => plays.iter().flatten().cloned().collect(),
+    };
+
+    let mut game = Game::make(board_size, &TimeSettings::UnTimed);
+    let mut moves = Vec::new();
+
+    for (index, play) in plays.into_iter().enumerate() {
+        if !matches!(play, Plae::Play(_)) || game.status != Status::Ongoing {
+            break;
+        }
+
+        let role = game.turn;
+        let phase = game.phase();
+        let (best, best_value, _) = game.alpha_beta(
+            usize::from(DEPTH),
+            DEPTH,
+            None,
+            -f64::INFINITY,
+            f64::INFINITY,
+        );
+
+        let Some((child, value)) = value_after(&game, &play) else {
+            break;
+        };
+
+        // The attacker wants a higher value and the defender a lower one.
+        let (best_value, value) = match role {
+            Role::Attacker => (best_value, value),
+            Role::Defender | Role::Roleless => (-best_value, -value),
+        };
+
+        let judgement = if best_value == f64::INFINITY && value != f64::INFINITY {
+            Judgement::MissedWin
+        } else if value == -f64::INFINITY && best_value != -f64::INFINITY {
+            Judgement::Blunder
+        } else if value >= best_value || best_value.is_infinite() {
+            Judgement::Best
+        } else if best_value - value < LOSS_INACCURACY {
+            Judgement::Good
+        } else if best_value - value < LOSS_MISTAKE {
+            Judgement::Inaccuracy
+        } else {
+            Judgement::Mistake
+        };
+
+        let loss = match judgement {
+            Judgement::Best => 0.0,
+            Judgement::Blunder | Judgement::MissedWin => LOSS_MAX,
+            Judgement::Good | Judgement::Inaccuracy | Judgement::Mistake => {
+                (best_value - value).min(LOSS_MAX)
+            }
+        };
+
+        moves.push(MoveAnalysis {
+            number: index + 1,
+            role,
+            best: best.unwrap_or_else(|| play.clone()),
+            play,
+            loss,
+            judgement,
+            phase,
+        });
+
+        game = child;
+    }
+
+    moves
+}
+
+/// The game after `play` and how good it is for the attacker, `None` if the
+/// play is illegal.
+#[must_use]
+pub fn value_after(game: &Game, play: &Plae) -> Option<(Game, f64)> {
+    let mut child = game.clone();
+    child.play(play).ok()?;
+
+    let value = match child.status {
+        Status::AttackerWins => f64::INFINITY,
+        Status::DefenderWins => -f64::INFINITY,
+        Status::Draw => 0.0,
+        Status::Ongoing => {
+            child
+                .alpha_beta(
+                    usize::from(DEPTH - 1),
+                    DEPTH - 1,
+                    None,
+                    -f64::INFINITY,
+                    f64::INFINITY,
+                )
+                .1
+        }
+    };
+
+    Some((child, value))
+}
+
+/// The average accuracy of the role's plays, `None` if they didn't play.
+#[allow(clippy::cast_precision_loss)]
+#[must_use]
+pub fn accuracy(moves: &[MoveAnalysis], role: Role) -> Option<f64> {
+    let accuracies: Vec<_> = moves
+        .iter()
+        .filter(|analysis| analysis.role == role)
+        .map(MoveAnalysis::accuracy)
+        .collect();
+
+    if accuracies.is_empty() {
+        return None;
+    }
+
+    Some(accuracies.iter().sum::<f64>() / accuracies.len() as f64)
+}
+
+/// Such as `d1-d3`.
+fn short(play: &Plae) -> String {
+    match play {
+        Plae::Play(play) => format!("{}-{}", play.from, play.to),
+        Plae::AttackerResigns | Plae::DefenderResigns => play.to_string(),
+    }
+}
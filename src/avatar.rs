@@ -0,0 +1,27 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! The small pictures shown next to the players' names. They go over the
+//! protocol as base64 encoded PNG files, one upload per line, and the server
+//! turns away anything bigger than these.
+
+/// The most bytes an avatar may take up, before it's base64 encoded.
+pub const AVATAR_BYTES: usize = 16 * 1024;
+
+/// The widest and tallest an avatar may be, in pixels.
+pub const AVATAR_SIZE: u32 = 64;
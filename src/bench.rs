@@ -0,0 +1,367 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! Positions and playouts for measuring the engine, behind the `bench`
+//! feature, so that changes to how the moves are found can be compared
+//! before and after. A [`gauntlet`] plays an AI against the baselines to see
+//! how strong it is.
+
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedRandom};
+
+use crate::{
+    ai::AI,
+    board::BoardSize,
+    game::Game,
+    play::{Plae, Play},
+    role::Role,
+    status::Status,
+    time::TimeSettings,
+    utils::choose_ai,
+};
+
+/// How many plies into a game [`positions`] takes its positions from.
+pub const PLIES: [usize; 3] = [0, 20, 60];
+
+/// The opening and two positions further along in a game, the later ones
+/// with fewer pieces and more room to move.
+#[must_use]
+pub fn positions(board_size: BoardSize) -> Vec<Game> {
+    let mut positions = Vec::new();
+    let mut game = Game::make(board_size, &TimeSettings::UnTimed);
+    let mut played = 0;
+
+    for plies in PLIES {
+        while played < plies && game.status == Status::Ongoing {
+            let plays = legal_plays(&game);
+            let Some(play) = plays.get((played * 7) % plays.len().max(1)) else {
+                break;
+            };
+
+            if game.play(&Plae::Play(play.clone())).is_err() {
+                break;
+            }
+            played += 1;
+        }
+
+        positions.push(game.clone());
+    }
+
+    positions
+}
+
+/// Every legal play for the side whose turn it is, in the same order each
+/// time.
+#[must_use]
+pub fn legal_plays(game: &Game) -> Vec<Play> {
+    let legal_moves = game.all_legal_moves();
+    let mut plays: Vec<_> = legal_moves
+        .moves
+        .iter()
+        .flat_map(|(from, tos)| {
+            tos.iter().map(|to| Play {
+                role: legal_moves.role,
+                from: *from,
+                to: *to,
+            })
+        })
+        .collect();
+
+    plays.sort();
+    plays
+}
+
+/// Plays random moves from `game` until it ends or there have been
+/// `max_plies` of them, and returns how many plies it played.
+pub fn playout<R: Rng + ?Sized>(game: &mut Game, max_plies: usize, rng: &mut R) -> usize {
+    let mut plies = 0;
+
+    while plies < max_plies && game.status == Status::Ongoing {
+        let legal_moves = game.all_legal_moves();
+        let froms: Vec<_> = legal_moves.moves.iter().collect();
+
+        let Some((from, tos)) = froms.choose(rng) else {
+            break;
+        };
+        let Some(to) = tos.choose(rng) else {
+            break;
+        };
+
+        let play = Play {
+            role: legal_moves.role,
+            from: **from,
+            to: *to,
+        };
+
+        if game.play(&Plae::Play(play)).is_err() {
+            break;
+        }
+        plies += 1;
+    }
+
+    plies
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Throughput {
+    pub playouts: u64,
+    pub plies: u64,
+    pub elapsed: Duration,
+}
+
+impl Throughput {
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn playouts_per_second(&self) -> f64 {
+        self.playouts as f64 / self.elapsed.as_secs_f64()
+    }
+
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn plies_per_second(&self) -> f64 {
+        self.plies as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+/// Runs playouts from the opening for `duration`.
+#[must_use]
+pub fn throughput(board_size: BoardSize, max_plies: usize, duration: Duration) -> Throughput {
+    let mut rng = rand::rng();
+    let mut throughput = Throughput::default();
+    let start = Instant::now();
+
+    while start.elapsed() < duration {
+        let mut game = Game::make(board_size, &TimeSettings::UnTimed);
+        throughput.plies += playout(&mut game, max_plies, &mut rng) as u64;
+        throughput.playouts += 1;
+    }
+
+    throughput.elapsed = start.elapsed();
+    throughput
+}
+
+/// The AIs a candidate plays in a [`gauntlet`], weakest first.
+pub const BASELINES: [&str; 2] = ["banal", "basic"];
+
+/// How many random plies each game of a [`gauntlet`] starts with, so the
+/// same two AIs don't play the same game over and over.
+pub const OPENING_PLIES: usize = 4;
+
+/// Plays `attacker` against `defender` from `game` until it ends. It's a
+/// draw when it goes on past `max_plies`.
+///
+/// # Errors
+///
+/// If an AI can't find a move.
+pub fn play_match(
+    mut game: Game,
+    attacker: &mut dyn AI,
+    defender: &mut dyn AI,
+    max_plies: usize,
+) -> anyhow::Result<Status> {
+    for _ in 0..max_plies {
+        if game.status != Status::Ongoing {
+            return Ok(game.status);
+        }
+
+        match game.turn {
+            Role::Attacker => attacker.generate_move(&mut game)?,
+            Role::Defender | Role::Roleless => defender.generate_move(&mut game)?,
+        };
+    }
+
+    if game.status == Status::Ongoing {
+        Ok(Status::Draw)
+    } else {
+        Ok(game.status)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GauntletSettings {
+    /// banal, basic, or monte-carlo.
+    pub candidate: String,
+    pub depth: Option<u8>,
+    /// In mebibytes.
+    pub memory: Option<usize>,
+    pub baselines: Vec<String>,
+    /// The seconds a move may take, one time control each.
+    pub seconds: Vec<u64>,
+    /// How many games to play against each baseline at each time control,
+    /// half of them as the attacker.
+    pub games: usize,
+    pub board_size: BoardSize,
+    pub max_plies: usize,
+    pub seed: u64,
+}
+
+/// How the candidate did against one baseline at one time control.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Pairing {
+    pub baseline: String,
+    pub seconds: u64,
+    pub wins: u64,
+    pub draws: u64,
+    pub losses: u64,
+}
+
+impl Pairing {
+    #[must_use]
+    pub fn games(&self) -> u64 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// The points scored out of every point there was to get, a draw being
+    /// half a point.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn score(&self) -> f64 {
+        if self.games() == 0 {
+            return 0.0;
+        }
+
+        (self.wins as f64 + self.draws as f64 / 2.0) / self.games() as f64
+    }
+}
+
+impl fmt::Display for Pairing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at {} s: +{} ={} -{}, {:.1}%",
+            self.baseline,
+            self.seconds,
+            self.wins,
+            self.draws,
+            self.losses,
+            self.score() * 100.0
+        )
+    }
+}
+
+/// The strength report of a [`gauntlet`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Gauntlet(pub Vec<Pairing>);
+
+impl Gauntlet {
+    #[must_use]
+    pub fn total(&self) -> Pairing {
+        let mut total = Pairing {
+            baseline: "everyone".to_string(),
+            ..Pairing::default()
+        };
+
+        for pairing in &self.0 {
+            total.wins += pairing.wins;
+            total.draws += pairing.draws;
+            total.losses += pairing.losses;
+        }
+
+        total
+    }
+}
+
+impl fmt::Display for Gauntlet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for pairing in &self.0 {
+            writeln!(f, "{pairing}")?;
+        }
+
+        let total = self.total();
+        write!(
+            f,
+            "total: +{} ={} -{}, {:.1}%",
+            total.wins,
+            total.draws,
+            total.losses,
+            total.score() * 100.0
+        )
+    }
+}
+
+/// Plays the candidate against every baseline at every time control. Each
+/// opening gets played twice, the candidate taking each side once.
+///
+/// # Errors
+///
+/// If an AI isn't banal, basic, or monte-carlo, or can't find a move.
+pub fn gauntlet(settings: &GauntletSettings) -> anyhow::Result<Gauntlet> {
+    let mut rng = StdRng::seed_from_u64(settings.seed);
+    let mut gauntlet = Gauntlet::default();
+
+    for seconds in &settings.seconds {
+        for baseline in &settings.baselines {
+            let mut pairing = Pairing {
+                baseline: baseline.clone(),
+                seconds: *seconds,
+                ..Pairing::default()
+            };
+            let mut opening = Game::make(settings.board_size, &TimeSettings::UnTimed);
+
+            for i in 0..settings.games {
+                let mut candidate = choose_ai(
+                    &settings.candidate,
+                    Some(*seconds),
+                    settings.depth,
+                    settings.memory,
+                    true,
+                )?;
+                let mut baseline = choose_ai(baseline, Some(*seconds), None, None, true)?;
+
+                let candidate_role = if i % 2 == 0 {
+                    opening = Game::make(settings.board_size, &TimeSettings::UnTimed);
+                    playout(&mut opening, OPENING_PLIES, &mut rng);
+                    Role::Attacker
+                } else {
+                    Role::Defender
+                };
+
+                let status = if candidate_role == Role::Attacker {
+                    play_match(
+                        opening.clone(),
+                        candidate.as_mut(),
+                        baseline.as_mut(),
+                        settings.max_plies,
+                    )?
+                } else {
+                    play_match(
+                        opening.clone(),
+                        baseline.as_mut(),
+                        candidate.as_mut(),
+                        settings.max_plies,
+                    )?
+                };
+
+                match (status, candidate_role) {
+                    (Status::AttackerWins, Role::Attacker)
+                    | (Status::DefenderWins, Role::Defender) => pairing.wins += 1,
+                    (Status::AttackerWins | Status::DefenderWins, _) => pairing.losses += 1,
+                    (Status::Draw | Status::Ongoing, _) => pairing.draws += 1,
+                }
+            }
+
+            gauntlet.0.push(pairing);
+        }
+    }
+
+    Ok(gauntlet)
+}
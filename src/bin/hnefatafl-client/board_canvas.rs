@@ -0,0 +1,791 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! The board, drawn on a canvas in layers: the squares, the last move, the
+//! heat map, the coordinates, the pieces, then the piece being moved.
+//!
+//! On small screens the board zooms in with a pinch or with the scroll wheel
+//! while holding control, and pans with two fingers, the scroll wheel, or by
+//! dragging with the right or middle mouse button. A mini-map in the corner
+//! shows which part of the board you see.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use hnefatafl_copenhagen::{board::BoardSize, game::LegalMoves, play::Vertex};
+use iced::{
+    Color, Event, Font, Point, Rectangle, Renderer, Size, Theme, Vector,
+    alignment::Vertical,
+    keyboard, mouse, touch,
+    widget::{
+        Action,
+        canvas::{Frame, Geometry, Program, Stroke, Text},
+        text::Alignment,
+    },
+    window,
+};
+
+use crate::enums::{Message, Move};
+
+/// How long a piece takes to slide to the square it moved to.
+const ANIMATION: Duration = Duration::from_millis(200);
+
+/// How far the board zooms in, at most.
+const ZOOM_MAX: f32 = 4.0;
+
+/// How much one pixel of scrolling zooms the board while holding control.
+const ZOOM_PER_PIXEL: f32 = 1.002;
+
+#[derive(Clone, Debug)]
+pub(crate) struct Square {
+    pub glyph: String,
+    /// When there is no color the glyph gets the theme's text color.
+    pub color: Option<Color>,
+    /// A heat map fill over the square.
+    pub heat: Option<Color>,
+    pub highlight: bool,
+    pub board_move: Move,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct BoardCanvas {
+    pub board_size: BoardSize,
+    /// Ordered by row, then column.
+    pub squares: Vec<Square>,
+    pub legal_moves: Option<LegalMoves>,
+    pub last_move: Option<(Vertex, Vertex)>,
+    pub letters: Vec<(char, bool)>,
+    /// Whether each number, from the bottom row up, was typed.
+    pub numbers: Vec<bool>,
+    pub square_size: f32,
+    pub spacing: f32,
+    pub piece_size: f32,
+    pub letter_size: f32,
+    /// With a touch screen a finger leaves the cursor where it last touched,
+    /// so the squares don't light up when hovered.
+    pub touch: bool,
+    /// Turned halfway around, so the other side of the board is at the bottom.
+    pub flipped: bool,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct BoardState {
+    dragging: Option<Drag>,
+    /// Whether the board has been drawn yet.
+    drawn: bool,
+    last_move: Option<(Vertex, Vertex)>,
+    animation_start: Option<Instant>,
+    now: Option<Instant>,
+    view: View,
+    /// Where the mouse was while panning with the right or middle button.
+    panning: Option<Point>,
+    /// The fingers touching the board, two of them pinch.
+    fingers: HashMap<touch::Finger, Point>,
+    modifiers: keyboard::Modifiers,
+}
+
+/// How far the board is zoomed in and where it is panned to. A point on the
+/// board lands at `offset + scale * point` on the canvas.
+#[derive(Clone, Copy, Debug)]
+struct View {
+    scale: f32,
+    offset: Vector,
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            offset: Vector::ZERO,
+        }
+    }
+}
+
+impl View {
+    fn zoomed(self) -> bool {
+        self.scale > 1.0
+    }
+
+    fn to_canvas(self, point: Point) -> Point {
+        Point::new(
+            self.offset.x + self.scale * point.x,
+            self.offset.y + self.scale * point.y,
+        )
+    }
+
+    fn to_board(self, point: Point) -> Point {
+        Point::new(
+            (point.x - self.offset.x) / self.scale,
+            (point.y - self.offset.y) / self.scale,
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Drag {
+    from: Vertex,
+    /// The piece was already selected, so letting go on it deselects it.
+    selected: bool,
+}
+
+impl BoardCanvas {
+    fn cell(&self) -> f32 {
+        self.square_size + self.spacing
+    }
+
+    fn margin(&self) -> f32 {
+        self.letter_size * 1.5
+    }
+
+    /// The squares without the coordinates around them.
+    fn squares_width(&self) -> f32 {
+        let size: usize = self.board_size.into();
+        #[allow(clippy::cast_precision_loss)]
+        let size = size as f32;
+
+        self.cell() * size
+    }
+
+    pub(crate) fn width(&self) -> f32 {
+        self.margin() * 2.0 + self.squares_width()
+    }
+
+    /// Where the squares are, which a zoomed in board stays inside of.
+    fn squares_region(&self) -> Rectangle {
+        Rectangle::new(
+            Point::new(self.margin(), self.margin()),
+            Size::new(self.squares_width(), self.squares_width()),
+        )
+    }
+
+    /// The view, moved back so the squares fill their region if the board
+    /// got smaller.
+    fn view(&self, state: &BoardState) -> View {
+        let mut view = state.view;
+        self.pan(&mut view, Vector::ZERO);
+        view
+    }
+
+    /// Zooms to `scale`, keeping `anchor` on the canvas over the same point
+    /// on the board.
+    fn zoom(&self, view: &mut View, scale: f32, anchor: Point) {
+        let board = view.to_board(anchor);
+        view.scale = scale.clamp(1.0, ZOOM_MAX);
+        view.offset = Vector::new(
+            anchor.x - view.scale * board.x,
+            anchor.y - view.scale * board.y,
+        );
+        self.pan(view, Vector::ZERO);
+    }
+
+    fn pan(&self, view: &mut View, delta: Vector) {
+        let near = self.margin();
+        let far = near + self.squares_width();
+        let (min, max) = (far - view.scale * far, near - view.scale * near);
+
+        view.offset = Vector::new(
+            (view.offset.x + delta.x).clamp(min, max),
+            (view.offset.y + delta.y).clamp(min, max),
+        );
+    }
+
+    /// Where the cursor is on the unzoomed board, if it is over the squares
+    /// you can see.
+    fn board_position(
+        &self,
+        state: &BoardState,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<Point> {
+        let position = cursor.position_in(bounds)?;
+        let view = self.view(state);
+
+        if view.zoomed() && !self.squares_region().contains(position) {
+            return None;
+        }
+
+        Some(view.to_board(position))
+    }
+
+    /// Keeps track of the fingers on the board, so two of them can pinch to
+    /// zoom and pan. Returns an action when it takes over the event.
+    fn fingers(
+        &self,
+        state: &mut BoardState,
+        event: &Event,
+        bounds: Rectangle,
+    ) -> Option<Action<Message>> {
+        match event {
+            Event::Touch(touch::Event::FingerPressed { id, position })
+                if bounds.contains(*position) =>
+            {
+                state.fingers.insert(*id, *position);
+
+                if state.fingers.len() < 2 {
+                    return None;
+                }
+
+                state.dragging = None;
+                Some(Action::request_redraw().and_capture())
+            }
+            Event::Touch(touch::Event::FingerMoved { id, position })
+                if state.fingers.contains_key(id) =>
+            {
+                let before = pinch(&state.fingers);
+                state.fingers.insert(*id, *position);
+                let ((middle_1, apart_1), (middle_2, apart_2)) =
+                    before.zip(pinch(&state.fingers))?;
+
+                let mut view = self.view(state);
+                if apart_1 > 0.0 {
+                    let anchor = Point::new(middle_2.x - bounds.x, middle_2.y - bounds.y);
+                    let scale = view.scale * apart_2 / apart_1;
+                    self.zoom(&mut view, scale, anchor);
+                }
+                self.pan(&mut view, middle_2 - middle_1);
+                state.view = view;
+
+                Some(Action::request_redraw().and_capture())
+            }
+            Event::Touch(
+                touch::Event::FingerLifted { id, .. } | touch::Event::FingerLost { id, .. },
+            ) => {
+                state.fingers.remove(id);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Where the vertex is drawn, which is across the board when flipped.
+    fn turned(&self, x: usize, y: usize) -> (usize, usize) {
+        if self.flipped {
+            let size: usize = self.board_size.into();
+            (size.saturating_sub(x + 1), size.saturating_sub(y + 1))
+        } else {
+            (x, y)
+        }
+    }
+
+    fn origin(&self, vertex: &Vertex) -> Point {
+        let (x, y) = self.turned(vertex.x, vertex.y);
+
+        #[allow(clippy::cast_precision_loss)]
+        Point::new(
+            self.margin() + self.cell() * x as f32,
+            self.margin() + self.cell() * y as f32,
+        )
+    }
+
+    fn center(&self, vertex: &Vertex) -> Point {
+        let origin = self.origin(vertex);
+        let half = self.square_size / 2.0;
+
+        Point::new(origin.x + half, origin.y + half)
+    }
+
+    fn square(&self, vertex: &Vertex) -> Option<&Square> {
+        let size: usize = self.board_size.into();
+        self.squares.get(vertex.y * size + vertex.x)
+    }
+
+    fn square_color(
+        &self,
+        theme: &Theme,
+        vertex: &Vertex,
+        square: &Square,
+        hovered: bool,
+    ) -> Color {
+        let palette = theme.extended_palette();
+
+        if square.highlight {
+            return palette.success.base.color;
+        }
+
+        if self
+            .last_move
+            .is_some_and(|(from, to)| from == *vertex || to == *vertex)
+        {
+            return palette.primary.strong.color;
+        }
+
+        match square.board_move {
+            Move::None => palette.primary.base.color.scale_alpha(0.5),
+            Move::Repeat => palette.secondary.base.color.scale_alpha(0.5),
+            Move::From | Move::To | Move::Revert if hovered => palette.primary.weak.color,
+            Move::From | Move::To | Move::Revert => palette.primary.base.color,
+        }
+    }
+
+    fn vertex_at(&self, position: Point) -> Option<Vertex> {
+        let size: usize = self.board_size.into();
+        let x = (position.x - self.margin()) / self.cell();
+        let y = (position.y - self.margin()) / self.cell();
+
+        if x < 0.0 || y < 0.0 {
+            return None;
+        }
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let (x, y) = (x as usize, y as usize);
+
+        if x < size && y < size {
+            let (x, y) = self.turned(x, y);
+
+            Some(Vertex {
+                size: self.board_size,
+                x,
+                y,
+            })
+        } else {
+            None
+        }
+    }
+
+    fn can_move(&self, from: &Vertex, to: &Vertex) -> bool {
+        self.legal_moves
+            .as_ref()
+            .and_then(|legal_moves| legal_moves.moves.get(from))
+            .is_some_and(|vertexes| vertexes.contains(to))
+    }
+
+    fn draw_coordinates(&self, frame: &mut Frame, theme: &Theme, view: &View) {
+        let palette = theme.extended_palette();
+        let size: usize = self.board_size.into();
+        let region = self.squares_region();
+
+        for (x, (letter, pressed)) in self.letters.iter().enumerate() {
+            let color = if *pressed {
+                palette.success.base.color
+            } else {
+                palette.background.base.text
+            };
+
+            let center = view.to_canvas(self.center(&Vertex {
+                size: self.board_size,
+                x,
+                y: 0,
+            }));
+
+            // Zoomed in, only the columns you can see get a letter.
+            if center.x < region.x || center.x > region.x + region.width {
+                continue;
+            }
+
+            let top = self.margin() / 2.0;
+            let bottom = self.margin() * 1.5 + self.squares_width();
+            for y in [top, bottom] {
+                frame.fill_text(text(
+                    letter.to_string(),
+                    Point::new(center.x, y),
+                    color,
+                    self.letter_size,
+                ));
+            }
+        }
+
+        for (i, pressed) in self.numbers.iter().enumerate() {
+            let color = if *pressed {
+                palette.success.base.color
+            } else {
+                palette.background.base.text
+            };
+
+            let center = view.to_canvas(self.center(&Vertex {
+                size: self.board_size,
+                x: 0,
+                y: size.saturating_sub(i + 1),
+            }));
+
+            if center.y < region.y || center.y > region.y + region.height {
+                continue;
+            }
+
+            let left = self.margin() / 2.0;
+            let right = self.width() - self.margin() / 2.0;
+            for x in [left, right] {
+                frame.fill_text(text(
+                    (i + 1).to_string(),
+                    Point::new(x, center.y),
+                    color,
+                    self.letter_size,
+                ));
+            }
+        }
+    }
+
+    /// Shows which part of the board you see when zoomed in.
+    fn draw_mini_map(&self, frame: &mut Frame, theme: &Theme, view: &View) {
+        let palette = theme.extended_palette();
+        let region = self.squares_region();
+        let side = region.width / 5.0;
+        let corner = Point::new(
+            region.x + region.width - side - self.spacing,
+            region.y + region.height - side - self.spacing,
+        );
+        let ratio = side / region.width;
+
+        let top_left = view.to_board(region.position());
+        let bottom_right = view.to_board(Point::new(
+            region.x + region.width,
+            region.y + region.height,
+        ));
+
+        frame.fill_rectangle(
+            corner,
+            Size::new(side, side),
+            palette.background.base.color.scale_alpha(0.8),
+        );
+        frame.stroke_rectangle(
+            corner,
+            Size::new(side, side),
+            Stroke::default().with_color(palette.background.base.text),
+        );
+        frame.stroke_rectangle(
+            Point::new(
+                corner.x + (top_left.x - region.x) * ratio,
+                corner.y + (top_left.y - region.y) * ratio,
+            ),
+            Size::new(
+                (bottom_right.x - top_left.x) * ratio,
+                (bottom_right.y - top_left.y) * ratio,
+            ),
+            Stroke::default()
+                .with_color(palette.primary.strong.color)
+                .with_width(2.0),
+        );
+    }
+
+    /// How far along the last move's animation is, from 0 to 1.
+    fn animation(state: &BoardState) -> Option<f32> {
+        let (Some(start), Some(now)) = (state.animation_start, state.now) else {
+            return None;
+        };
+
+        let elapsed = now.saturating_duration_since(start);
+        if elapsed < ANIMATION {
+            Some(elapsed.as_secs_f32() / ANIMATION.as_secs_f32())
+        } else {
+            None
+        }
+    }
+}
+
+impl Program<Message> for BoardCanvas {
+    type State = BoardState;
+
+    #[allow(clippy::too_many_lines)]
+    fn update(
+        &self,
+        state: &mut Self::State,
+        event: &Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<Action<Message>> {
+        if let Some(action) = self.fingers(state, event, bounds) {
+            return Some(action);
+        }
+
+        match event {
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                state.modifiers = *modifiers;
+                None
+            }
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                let position = cursor.position_in(bounds)?;
+                let (x, y) = match delta {
+                    mouse::ScrollDelta::Lines { x, y } => (x * self.cell(), y * self.cell()),
+                    mouse::ScrollDelta::Pixels { x, y } => (*x, *y),
+                };
+                let mut view = self.view(state);
+
+                if state.modifiers.command() {
+                    let scale = view.scale * ZOOM_PER_PIXEL.powf(y);
+                    self.zoom(&mut view, scale, position);
+                } else if view.zoomed() {
+                    self.pan(&mut view, Vector::new(x, y));
+                } else {
+                    // Let the page scroll.
+                    return None;
+                }
+
+                state.view = view;
+                Some(Action::request_redraw().and_capture())
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(
+                mouse::Button::Right | mouse::Button::Middle,
+            )) if self.view(state).zoomed() && cursor.is_over(bounds) => {
+                state.panning = cursor.position();
+                Some(Action::capture())
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(
+                mouse::Button::Right | mouse::Button::Middle,
+            )) if state.panning.is_some() => {
+                state.panning = None;
+                Some(Action::request_redraw().and_capture())
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position }) if state.panning.is_some() => {
+                let last = state.panning.replace(*position)?;
+                let mut view = self.view(state);
+                self.pan(&mut view, *position - last);
+                state.view = view;
+
+                Some(Action::request_redraw())
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+            | Event::Touch(touch::Event::FingerPressed { .. }) => {
+                let vertex = self.vertex_at(self.board_position(state, bounds, cursor)?)?;
+
+                match self.square(&vertex)?.board_move {
+                    Move::From => {
+                        state.dragging = Some(Drag {
+                            from: vertex,
+                            selected: false,
+                        });
+
+                        Some(Action::publish(Message::PlayMoveFrom(vertex)).and_capture())
+                    }
+                    Move::Revert => {
+                        state.dragging = Some(Drag {
+                            from: vertex,
+                            selected: true,
+                        });
+
+                        Some(Action::request_redraw().and_capture())
+                    }
+                    Move::To => Some(Action::publish(Message::PlayMoveTo(vertex)).and_capture()),
+                    Move::None | Move::Repeat => None,
+                }
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
+            | Event::Touch(touch::Event::FingerLifted { .. }) => {
+                let drag = state.dragging.take()?;
+                let vertex = self
+                    .board_position(state, bounds, cursor)
+                    .and_then(|position| self.vertex_at(position));
+
+                // Letting go anywhere else leaves the piece selected.
+                match vertex {
+                    Some(vertex) if vertex == drag.from && drag.selected => {
+                        Some(Action::publish(Message::PlayMoveRevert))
+                    }
+                    Some(vertex) if self.can_move(&drag.from, &vertex) => {
+                        Some(Action::publish(Message::PlayMoveTo(vertex)))
+                    }
+                    _ => Some(Action::request_redraw()),
+                }
+            }
+            Event::Touch(touch::Event::FingerLost { .. }) => {
+                state.dragging = None;
+                Some(Action::request_redraw())
+            }
+            Event::Mouse(mouse::Event::CursorMoved { .. })
+            | Event::Touch(touch::Event::FingerMoved { .. })
+                if state.dragging.is_some() =>
+            {
+                Some(Action::request_redraw())
+            }
+            Event::Window(window::Event::RedrawRequested(now)) => {
+                state.now = Some(*now);
+
+                if !state.drawn || state.last_move != self.last_move {
+                    // Don't animate the move that was on the board when it
+                    // was first drawn.
+                    if state.drawn && self.last_move.is_some() {
+                        state.animation_start = Some(*now);
+                    }
+
+                    state.drawn = true;
+                    state.last_move = self.last_move;
+                }
+
+                if Self::animation(state).is_some() {
+                    Some(Action::request_redraw())
+                } else {
+                    state.animation_start = None;
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn draw(
+        &self,
+        state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let palette = theme.extended_palette();
+        let text_color = palette.primary.base.text;
+        let view = self.view(state);
+        let region = self.squares_region();
+        let position = cursor.position_in(bounds);
+        let hovered = self
+            .board_position(state, bounds, cursor)
+            .filter(|_| !self.touch)
+            .and_then(|position| self.vertex_at(position));
+        let size: usize = self.board_size.into();
+        let square_size = Size::new(self.square_size * view.scale, self.square_size * view.scale);
+        let animation = Self::animation(state).zip(self.last_move);
+
+        self.draw_coordinates(&mut frame, theme, &view);
+
+        let mut pieces = Vec::new();
+
+        for y in 0..size {
+            for x in 0..size {
+                let vertex = Vertex {
+                    size: self.board_size,
+                    x,
+                    y,
+                };
+
+                let Some(square) = self.square(&vertex) else {
+                    continue;
+                };
+
+                // Zoomed in, the squares at the edges are cut off.
+                let Some(square_area) =
+                    Rectangle::new(view.to_canvas(self.origin(&vertex)), square_size)
+                        .intersection(&region)
+                else {
+                    continue;
+                };
+
+                let mut color = self.square_color(theme, &vertex, square, hovered == Some(vertex));
+
+                if let Some(drag) = &state.dragging
+                    && self.can_move(&drag.from, &vertex)
+                {
+                    color = palette.success.base.color;
+                }
+
+                frame.fill_rectangle(square_area.position(), square_area.size(), color);
+
+                if let Some(heat) = square.heat {
+                    frame.fill_rectangle(square_area.position(), square_area.size(), heat);
+                }
+
+                let dragged = state.dragging.is_some_and(|drag| drag.from == vertex);
+                let mut center = self.center(&vertex);
+
+                if let Some((t, (from, to))) = animation {
+                    if from == vertex {
+                        // The arrow shows where the piece went once it gets there.
+                        continue;
+                    }
+
+                    if to == vertex {
+                        let from = self.center(&from);
+                        let t = 1.0 - (1.0 - t) * (1.0 - t);
+                        center = Point::new(
+                            from.x + (center.x - from.x) * t,
+                            from.y + (center.y - from.y) * t,
+                        );
+                    }
+                }
+
+                let center = view.to_canvas(center);
+                if !region.contains(center) && !dragged {
+                    continue;
+                }
+
+                let glyph = text(
+                    square.glyph.clone(),
+                    center,
+                    square.color.unwrap_or(text_color),
+                    self.piece_size * view.scale,
+                );
+
+                if dragged {
+                    if let Some(position) = position {
+                        pieces.push(Text { position, ..glyph });
+                    }
+                } else if animation.is_some_and(|(_, (_, to))| to == vertex) {
+                    pieces.push(glyph);
+                } else {
+                    frame.fill_text(glyph);
+                }
+            }
+        }
+
+        // The moving pieces go over everything else.
+        for piece in pieces {
+            frame.fill_text(piece);
+        }
+
+        if view.zoomed() {
+            self.draw_mini_map(&mut frame, theme, &view);
+        }
+
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        state: &Self::State,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        if state.dragging.is_some() || state.panning.is_some() {
+            return mouse::Interaction::Grabbing;
+        }
+
+        let square = self
+            .board_position(state, bounds, cursor)
+            .and_then(|position| self.vertex_at(position))
+            .and_then(|vertex| self.square(&vertex));
+
+        match square.map(|square| &square.board_move) {
+            Some(Move::From | Move::Revert) => mouse::Interaction::Grab,
+            Some(Move::To) => mouse::Interaction::Pointer,
+            Some(Move::Repeat) => mouse::Interaction::NotAllowed,
+            Some(Move::None) | None => mouse::Interaction::default(),
+        }
+    }
+}
+
+/// Where two fingers are, between them, and how far apart.
+fn pinch(fingers: &HashMap<touch::Finger, Point>) -> Option<(Point, f32)> {
+    let mut points = fingers.values();
+    let (a, b) = (points.next()?, points.next()?);
+
+    Some((
+        Point::new(f32::midpoint(a.x, b.x), f32::midpoint(a.y, b.y)),
+        a.distance(*b),
+    ))
+}
+
+fn text(content: String, position: Point, color: Color, size: f32) -> Text {
+    Text {
+        content,
+        position,
+        color,
+        size: size.into(),
+        font: Font::MONOSPACE,
+        align_x: Alignment::Center,
+        align_y: Vertical::Center,
+        ..Text::default()
+    }
+}
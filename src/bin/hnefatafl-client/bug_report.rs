@@ -0,0 +1,85 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! Report Issue: what helps find a bug gathered into a block of text you can
+//! add to, then copy or send to the server.
+
+use std::{
+    collections::VecDeque,
+    env::consts::{ARCH, OS},
+    fmt::Write as _,
+    io::{self, Write},
+    sync::Mutex,
+};
+
+use hnefatafl_copenhagen::{Id, SOFTWARE_ID, play::Plae};
+
+/// How many lines of the log go in a bug report.
+const LOG_LINES: usize = 50;
+
+static LOG: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Writes the log to stderr, keeping the end of it for a bug report.
+pub(crate) struct LogTail;
+
+impl Write for LogTail {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let Ok(mut log) = LOG.lock() {
+            for line in String::from_utf8_lossy(buf).lines() {
+                if log.len() == LOG_LINES {
+                    log.pop_front();
+                }
+
+                log.push_back(line.to_string());
+            }
+        }
+
+        io::stderr().write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()
+    }
+}
+
+/// The bug report as it starts out, with room at the top to say what went
+/// wrong. `game` is the game you're in or looking over and the plays up to
+/// where you are in it.
+pub(crate) fn gather(server: &str, game: Option<(Id, &[&Plae])>) -> String {
+    let mut report = format!(
+        "What went wrong:\n\n\nhnefatafl-client: {SOFTWARE_ID}\nserver: {server}\nOS: {OS} {ARCH}\n"
+    );
+
+    if let Some((id, plays)) = game {
+        let _ = writeln!(report, "game: {id}, after {} plays", plays.len());
+
+        for play in plays {
+            let _ = writeln!(report, "{play}");
+        }
+    }
+
+    report.push_str("log:\n");
+    if let Ok(log) = LOG.lock() {
+        for line in log.iter() {
+            let _ = writeln!(report, "{line}");
+        }
+    }
+
+    report
+}
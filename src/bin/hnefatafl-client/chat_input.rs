@@ -0,0 +1,180 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! What the chat boxes share: the messages you sent, to get back with the up
+//! and down arrows, and finishing the `@username` you're typing.
+
+use std::collections::VecDeque;
+
+use iced::widget::Id;
+
+/// How many of the messages you sent are remembered.
+const HISTORY: usize = 50;
+
+/// How many usernames are offered at once.
+const COMPLETIONS: usize = 5;
+
+/// The text input every chat box uses, so the up and down arrows only recall
+/// messages while you're typing in it.
+pub(crate) fn id() -> Id {
+    Id::new("chat_input")
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ChatInput {
+    /// The messages you sent, the newest last.
+    history: VecDeque<String>,
+    /// Where you are in the history, `None` when you aren't in it.
+    recalled: Option<usize>,
+    /// What you were typing before going back through the history.
+    draft: String,
+}
+
+impl ChatInput {
+    /// Remembers the message, and leaves the history.
+    pub fn sent(&mut self, text: &str) {
+        self.recalled = None;
+        self.draft.clear();
+
+        let text = text.trim();
+        if text.is_empty() || self.history.back().is_some_and(|last| last == text) {
+            return;
+        }
+
+        if self.history.len() == HISTORY {
+            self.history.pop_front();
+        }
+
+        self.history.push_back(text.to_string());
+    }
+
+    /// The message sent before the one shown, `None` when there isn't one.
+    pub fn up(&mut self, typing: &str) -> Option<String> {
+        let i = match self.recalled {
+            None => {
+                self.draft = typing.to_string();
+                self.history.len().checked_sub(1)?
+            }
+            Some(i) => i.checked_sub(1)?,
+        };
+
+        self.recalled = Some(i);
+        self.history.get(i).cloned()
+    }
+
+    /// The message sent after the one shown, or what you were typing once you
+    /// get past the newest one.
+    pub fn down(&mut self) -> Option<String> {
+        let i = self.recalled? + 1;
+
+        if i < self.history.len() {
+            self.recalled = Some(i);
+            self.history.get(i).cloned()
+        } else {
+            self.recalled = None;
+            Some(std::mem::take(&mut self.draft))
+        }
+    }
+
+    /// Typing something new leaves the history.
+    pub fn changed(&mut self) {
+        self.recalled = None;
+    }
+}
+
+/// The usernames the `@` word at the end of `text` could be, in order.
+pub(crate) fn completions<'a>(
+    text: &str,
+    usernames: impl Iterator<Item = &'a String>,
+) -> Vec<&'a str> {
+    let Some(prefix) = mention(text) else {
+        return Vec::new();
+    };
+
+    let prefix = prefix.to_lowercase();
+    let mut usernames: Vec<_> = usernames
+        .filter(|username| {
+            let username = username.to_lowercase();
+            username.starts_with(&prefix) && username != prefix
+        })
+        .map(String::as_str)
+        .collect();
+
+    usernames.sort_unstable();
+    usernames.truncate(COMPLETIONS);
+    usernames
+}
+
+/// Swaps the `@` word at the end of `text` for `@username `.
+pub(crate) fn complete(text: &str, username: &str) -> String {
+    let start = mention(text).map_or(text.len(), |prefix| text.len() - prefix.len() - 1);
+    format!("{}@{username} ", &text[..start])
+}
+
+/// What comes after the `@` in the last word, if it starts with one.
+fn mention(text: &str) -> Option<&str> {
+    let word = text.rsplit(char::is_whitespace).next()?;
+    word.strip_prefix('@')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChatInput, complete, completions};
+
+    #[test]
+    fn completions_and_complete() {
+        let usernames: Vec<_> = ["abby", "Abel", "alice", "bob", "ab", "abc", "abd", "abe"]
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+
+        assert!(completions("hello", usernames.iter()).is_empty());
+        assert_eq!(completions("hello @", usernames.iter()).len(), 5);
+        assert_eq!(
+            completions("hello @AB", usernames.iter()),
+            ["Abel", "abby", "abc", "abd", "abe"]
+        );
+        assert_eq!(completions("@bo", usernames.iter()), ["bob"]);
+        // You've typed the whole name already.
+        assert!(completions("@bob", usernames.iter()).is_empty());
+        assert!(completions("@bo ", usernames.iter()).is_empty());
+        assert!(completions("bob@bo", usernames.iter()).is_empty());
+
+        assert_eq!(complete("hello @ab", "abby"), "hello @abby ");
+        assert_eq!(complete("@", "bob"), "@bob ");
+        assert_eq!(complete("hello ", "bob"), "hello @bob ");
+    }
+
+    #[test]
+    fn history() {
+        let mut chat_input = ChatInput::default();
+        assert_eq!(chat_input.up("typing"), None);
+
+        chat_input.sent("one");
+        chat_input.sent(" two ");
+        chat_input.sent("two");
+        chat_input.sent("  ");
+
+        assert_eq!(chat_input.up("typing").as_deref(), Some("two"));
+        assert_eq!(chat_input.up("two").as_deref(), Some("one"));
+        assert_eq!(chat_input.up("one"), None);
+        assert_eq!(chat_input.down().as_deref(), Some("two"));
+        assert_eq!(chat_input.down().as_deref(), Some("typing"));
+        assert_eq!(chat_input.down(), None);
+    }
+}
@@ -16,8 +16,10 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 // SPDX-FileCopyrightText: 2025 David Campbell <david@hnefatafl.org>
 
+use std::path::PathBuf;
+
 use clap::Parser;
-use hnefatafl_copenhagen::LONG_VERSION;
+use hnefatafl_copenhagen::{LONG_VERSION, PORT};
 
 /// Hnefatafl Copenhagen Client
 ///
@@ -30,6 +32,10 @@ pub(crate) struct Args {
     #[arg(default_value = "hnefatafl.org", long)]
     pub host: String,
 
+    /// Connect to the server on port
+    #[arg(default_value_t = PORT, long)]
+    pub port: u16,
+
     /// Whether to log on the debug level
     #[arg(long)]
     pub debug: bool,
@@ -46,6 +52,10 @@ pub(crate) struct Args {
     #[arg(long)]
     pub depth: Option<u8>,
 
+    /// How many mebibytes the monte-carlo AI may use
+    #[arg(long)]
+    pub memory: Option<usize>,
+
     /// Make the window size tiny
     #[arg(long)]
     pub tiny_window: bool,
@@ -58,6 +68,22 @@ pub(crate) struct Args {
     #[arg(long)]
     pub ascii: bool,
 
+    /// Show only the board, clocks and names of the game you are watching on
+    /// a green background, for streaming
+    #[arg(long)]
+    pub overlay: bool,
+
+    /// Play the moves made on an electronic board, which sends `lift SQUARE`
+    /// and `place SQUARE` lines over a serial or Bluetooth serial port like
+    /// /dev/ttyUSB0 or /dev/rfcomm0
+    #[arg(long)]
+    pub physical_board: Option<PathBuf>,
+
+    /// The text to speech program that says the moves, it's given the words
+    /// to say as its argument [default: espeak-ng, or say on macOS]
+    #[arg(long)]
+    pub voice_program: Option<String>,
+
     /// Build the manpage
     #[arg(long)]
     pub man: bool,
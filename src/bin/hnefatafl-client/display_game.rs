@@ -18,7 +18,8 @@
 
 use std::collections::VecDeque;
 
-use hnefatafl_copenhagen::{board::Board, server_game::Message, status::Status};
+use hnefatafl_copenhagen::{board::Board, role::Role, server_game::Message, status::Status};
+use rust_i18n::t;
 
 #[derive(Clone, Debug)]
 pub(crate) struct DisplayGame {
@@ -31,6 +32,62 @@ pub(crate) struct DisplayGame {
     pub defender_rating: String,
     pub board: Board,
     pub play: usize,
+    /// The opening the plays so far are in.
+    pub opening: Option<String>,
     pub status: Status,
     pub messages: VecDeque<Message>,
+    /// The opponent zen mode hides until the game ends.
+    pub anonymous: Option<Role>,
+}
+
+impl DisplayGame {
+    #[must_use]
+    pub fn attacker_shown(&self) -> String {
+        if self.anonymous == Some(Role::Attacker) {
+            t!("Anonymous").to_string()
+        } else {
+            self.attacker.clone()
+        }
+    }
+
+    /// Whose avatar is shown for the attacker, no one's while zen mode hides
+    /// them.
+    #[must_use]
+    pub fn attacker_avatar(&self) -> Option<&str> {
+        (self.anonymous != Some(Role::Attacker)).then_some(self.attacker.as_str())
+    }
+
+    #[must_use]
+    pub fn attacker_rating_shown(&self) -> String {
+        if self.anonymous == Some(Role::Attacker) {
+            String::new()
+        } else {
+            self.attacker_rating.clone()
+        }
+    }
+
+    #[must_use]
+    pub fn defender_shown(&self) -> String {
+        if self.anonymous == Some(Role::Defender) {
+            t!("Anonymous").to_string()
+        } else {
+            self.defender.clone()
+        }
+    }
+
+    /// Whose avatar is shown for the defender, no one's while zen mode hides
+    /// them.
+    #[must_use]
+    pub fn defender_avatar(&self) -> Option<&str> {
+        (self.anonymous != Some(Role::Defender)).then_some(self.defender.as_str())
+    }
+
+    #[must_use]
+    pub fn defender_rating_shown(&self) -> String {
+        if self.anonymous == Some(Role::Defender) {
+            String::new()
+        } else {
+            self.defender_rating.clone()
+        }
+    }
 }
@@ -16,25 +16,34 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 // SPDX-FileCopyrightText: 2025 David Campbell <david@hnefatafl.org>
 
-use std::{ops::Not, sync::mpsc};
+use std::{fmt, ops::Not, sync::mpsc};
 
 use hnefatafl_copenhagen::{
     Id,
-    ai::GenerateMove,
-    board::BoardSize,
+    ai::{Cancel, GenerateMove, Progress},
+    board::{Board, BoardSize},
     draw::Draw,
+    email::EmailCategory,
+    game::Game,
     locale::Locale,
     play::Vertex,
+    rating::RatingCategory,
     role::Role,
-    server_game::ArchivedGame,
+    rule_set::RuleSet,
+    server_game::{ArchivedGame, ServerStatus, Tags},
     time::TimeEnum,
-    tree::{Node, Tree},
 };
-use iced::{widget::text_editor, window};
+use iced::{Point, widget::text_editor, window};
 use iced_aw::date_picker::Date;
+use rust_i18n::t;
 use serde::{Deserialize, Serialize};
 
-use crate::tabs::TabId;
+use crate::{
+    key_bindings::{Action, KeyBinding},
+    onboarding::Onboarding,
+    tabs::TabId,
+    ui_scale::{TextSize, UiScale},
+};
 
 #[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
 pub(crate) enum Coordinates {
@@ -80,51 +89,125 @@ pub(crate) enum JoinGame {
 
 #[derive(Clone, Debug)]
 pub(crate) enum Message {
+    Announce(bool),
+    AnnouncementDismiss,
+    AnnouncementTick,
     ArchivedGames(Vec<ArchivedGame>),
     ArchivedGamesPress,
     ArchivedGamesRelease,
     ArchivedGameSelected(ArchivedGame),
+    /// Draw the pieces with letters instead of symbols.
+    Ascii(bool),
+    AvatarClear(String),
+    AvatarRemove,
+    #[cfg(not(target_os = "redox"))]
+    AvatarUpload,
+    Berserk,
+    Block(String),
     BoardSizeSelected(BoardSize),
+    BugReport,
+    BugReportCancel,
+    BugReportCopy,
+    BugReportEdit(text_editor::Action),
+    BugReportSend,
     CancelGame(Id),
+    ChallengeAccept,
+    ChallengeDecline,
+    ChallengeFlash,
+    /// Opens Create Game to challenge the player.
+    ChallengeUser(String),
     ChangeTheme(Theme),
+    ClaimDraw,
+    ClaimWin,
+    ClubClose,
+    ClubDelete,
+    ClubDescriptionChanged(String),
+    ClubDescriptionSave,
+    /// Invites the player typed in to the club that's open.
+    ClubInvite,
+    ClubInviteChanged(String),
+    ClubJoin(String),
+    ClubLeave(String),
+    ClubNameChanged(String),
+    ClubNew,
+    ClubOpen(String),
+    /// Takes the member out of the club that's open.
+    ClubRemove(String),
+    CommandChanged(String),
+    CommandSend,
+    CommunityGameAi,
+    CommunityGameNew,
+    ConfirmMoves(bool),
     ConnectedTo(String),
+    ConversationClose,
+    ConversationNew,
+    ConversationNewChanged(String),
+    ConversationOpen(String),
+    DailyPuzzle,
     DateChoose,
     DateCancel,
     DateSubmit(Date),
     DeleteAccount,
     EmailChanged(String),
     EmailEveryone,
+    EmailPreference(EmailCategory, bool),
     EmailReset,
+    EstablishedOnlySelected(bool),
     EstimateScore,
-    EstimateScoreConnected(mpsc::Sender<Tree>),
-    EstimateScoreDisplay((Node, GenerateMove)),
+    EstimateScoreCancelled,
+    EstimateScoreConnected((mpsc::Sender<Game>, Option<Cancel>)),
+    EstimateScoreDisplay((Board, GenerateMove)),
+    EstimateScoreProgress((Board, Progress)),
     Exit,
     #[cfg(not(target_os = "redox"))]
+    ExportCalendar,
+    #[cfg(not(target_os = "redox"))]
     ExportPGN,
+    #[cfg(not(target_os = "redox"))]
+    ExportPNG,
     FocusPrevious,
     FocusNext,
+    Follow(String),
+    FollowTheAction(bool),
+    FollowTheActionNext,
     GameAccept(Id),
     GameCancel(Id),
     GameDecline(Id),
     GameJoin(Id),
     GameResume(Id),
+    GameResumeDismiss,
     GameSubmit,
     GameWatch(Id),
     GroupSizeChanged(f64),
     HeatMap(bool),
     #[cfg(not(target_os = "redox"))]
     ImportPGN,
+    KeyBindingChange(Action),
+    KeyBindingsReset,
+    KeyPressed(KeyBinding),
+    KibitzAnalyze,
+    KibitzShare,
+    KidSafeCreate(bool),
     Leave,
     LeaveSoft,
     LocaleSelected(Locale),
+    Mentor(bool),
     MyGamesOnly(bool),
+    NoteEdit(text_editor::Action),
+    NotesSearch(String),
     NumberOfGames(f64),
+    /// Go to a step of the first launch wizard, or finish it.
+    Onboarding(Option<Onboarding>),
+    OpeningFilter(Option<String>),
     OpenUrl(String),
     PasswordChanged(String),
     PasswordSave(bool),
     PasswordShow(bool),
+    PhysicalBoardMove(String, String),
     PlayDraw,
     PlayDrawDecision(Draw),
+    PlayMoveCancel,
+    PlayMoveConfirm,
     PlayMoveFrom(Vertex),
     PlayMoveTo(Vertex),
     PlayMoveRevert,
@@ -168,49 +251,117 @@ pub(crate) enum Message {
     Press0,
     PressMinus,
     PressPlus,
+    ProfileBio(String),
+    ProfileClose,
+    ProfileCountry(String),
+    ProfileRole(Role),
+    ProfileSave,
+    ProfileView(String),
+    ProofOfWorkProgress(u8),
+    ProofOfWorkSolved(u64),
+    Puzzle,
+    QuizToggled(bool),
     RatedSelected(bool),
     RatingMaximumChanged(f64),
     RatingMaximum,
     RatingMinimumChanged(f64),
     RatingMinimum,
+    RatingShownSelected(RatingShown),
+    ReportCancel,
+    ReportReason(String),
+    ReportSend,
+    ReportUser(String),
     ResetPassword,
     ReviewGame,
     ReviewGameBackward,
     ReviewGameBackwardAll,
     ReviewGameChildNext,
+    ReviewGameFlip(bool),
     ReviewGameForward,
     ReviewGameForwardAll,
+    ReviewGameGuess,
+    ReviewGameWindow,
+    ReviewWindowBackward(window::Id),
+    ReviewWindowBackwardAll(window::Id),
+    ReviewWindowForward(window::Id),
+    ReviewWindowForwardAll(window::Id),
     RoleSelected(Role),
+    RuleSetSelected(RuleSet),
+    RuleSetShownSelected(RuleSetShown),
+    Scout(String),
+    ScoutClose,
     SetScreenSize(Option<window::Id>),
     SetScreenSizeGetWindow,
     ServerShutdown,
+    ServerStatus(Result<ServerStatus, String>),
+    ShowProfanity(bool),
+    SimulJoin(u64),
+    SimulLeave(u64),
+    SimulNew,
+    SimulNext,
+    SimulStart(u64),
+    SlowModeTick,
     StreamConnected(mpsc::Sender<String>),
     TabSelected(TabId),
+    TagFilter(Option<String>),
+    TagsChanged(Tags),
+    TagsLabels(String),
+    TagsSave,
     TcpConnectFailed,
     TcpDisconnect,
     TextChanged(String),
+    TextComplete(String),
     TextEdit(text_editor::Action),
+    TextPhrase(&'static str),
     TextReceived(String),
+    /// The up or down arrow, and whether the chat box is focused.
+    TextRecall(KeyBinding, bool),
     TextSend,
     TextSendEmail,
     TextSendEmailCode,
     TextSendCreateAccount,
     TextSendLogin,
+    TextSizeSelected(TextSize),
     Tick,
     Time(TimeEnum),
     ToggleFullscreen(Option<window::Id>),
     ToggleFullscreenGetWindow,
+    /// Zero means groups instead.
+    TournamentArena(f64),
     TournamentBoardSize(BoardSize),
+    /// Empty means any player may join.
+    TournamentClub(String),
+    TournamentRated(bool),
+    /// Zero means no limit.
+    TournamentRequirement(Requirement, f64),
+    /// Zero means no limit.
+    TournamentRoundDays(f64),
     TournamentTime(TimeEnum),
+    TournamentTimeOfDay(String),
     Tournaments,
     TournamentJoin,
     TournamentLeave,
     TournamentStart,
     TournamentDelete,
     TournamentTreeDelete,
+    TournamentWithdraw,
+    /// The first time someone touches the screen.
+    TouchDetected,
+    /// Back to the real position from the moves you're trying out.
+    TrialReset,
+    Tutorial,
+    UiScaleSelected(UiScale),
+    Unblock(String),
+    Unfollow(String),
     UsersSortedBy(SortBy),
+    VacationDays(String),
+    VacationEnd,
+    VacationStart,
     VolumeChanged(u32),
-    WindowResized((f32, f32)),
+    WindowClosed(window::Id),
+    WindowMoved(window::Id, Point),
+    WindowResized(window::Id, (f32, f32)),
+    ZenSelected(bool),
 }
 
 #[derive(Clone, Debug)]
@@ -218,6 +369,8 @@ pub(crate) enum Move {
     From,
     To,
     Revert,
+    /// Would bring back an earlier position, which the rules don't allow.
+    Repeat,
     None,
 }
 
@@ -242,6 +395,66 @@ pub(crate) enum Size {
     Giant,
 }
 
+/// One of the limits on who may join a tournament.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Requirement {
+    PlayersMaximum,
+    RatedGames,
+    RatingMaximum,
+    RatingMinimum,
+}
+
+/// Which rating to show in the list of users.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) enum RatingShown {
+    Category(RatingCategory),
+    #[default]
+    Overall,
+}
+
+impl fmt::Display for RatingShown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Category(category) => write!(f, "{category}"),
+            Self::Overall => write!(f, "{}", t!("overall")),
+        }
+    }
+}
+
+/// Which rule set the lobby lists the games of.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) enum RuleSetShown {
+    #[default]
+    All,
+    RuleSet(RuleSet),
+}
+
+impl RuleSetShown {
+    #[must_use]
+    pub(crate) fn all() -> Vec<Self> {
+        let mut rule_sets = vec![Self::All];
+        rule_sets.extend(RuleSet::ALL.map(Self::RuleSet));
+        rule_sets
+    }
+
+    #[must_use]
+    pub(crate) fn shows(self, rule_set: RuleSet) -> bool {
+        match self {
+            Self::All => true,
+            Self::RuleSet(shown) => shown == rule_set,
+        }
+    }
+}
+
+impl fmt::Display for RuleSetShown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => write!(f, "{}", t!("all rule sets")),
+            Self::RuleSet(rule_set) => write!(f, "{rule_set}"),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub(crate) enum SortBy {
     Name,
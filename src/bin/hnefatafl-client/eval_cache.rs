@@ -0,0 +1,70 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! The engine's answers for the positions it has looked at, shared by the
+//! heat map, the trainer, and the kibitz analysis, so going back to a
+//! position doesn't send the engine off to look at it again.
+
+use std::collections::{HashMap, VecDeque};
+
+use hnefatafl_copenhagen::{ai::GenerateMove, board::Board, game::Game, play::Plae, role::Role};
+
+/// How many positions the cache keeps before it forgets the oldest.
+const POSITIONS: usize = 256;
+
+#[derive(Debug, Default)]
+pub(crate) struct EvalCache {
+    /// By the Zobrist hash of the board and whose turn it is. The board is
+    /// kept to tell apart positions with the same hash.
+    evaluations: HashMap<(u64, Role), (Board, GenerateMove)>,
+    /// The oldest first.
+    order: VecDeque<(u64, Role)>,
+}
+
+impl EvalCache {
+    pub fn get(&self, game: &Game) -> Option<&GenerateMove> {
+        self.evaluations
+            .get(&(game.board.zobrist(), game.turn))
+            .filter(|(board, _)| *board == game.board)
+            .map(|(_, generate_move)| generate_move)
+    }
+
+    /// Keeps what the engine found on `board`, before its move was played.
+    pub fn insert(&mut self, board: Board, generate_move: GenerateMove) {
+        let turn = match &generate_move.play {
+            Plae::Play(play) => play.role,
+            Plae::AttackerResigns => Role::Attacker,
+            Plae::DefenderResigns => Role::Defender,
+        };
+        let key = (board.zobrist(), turn);
+
+        if self
+            .evaluations
+            .insert(key, (board, generate_move))
+            .is_none()
+        {
+            self.order.push_back(key);
+        }
+
+        while self.order.len() > POSITIONS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.evaluations.remove(&oldest);
+            }
+        }
+    }
+}
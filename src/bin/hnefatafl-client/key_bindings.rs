@@ -0,0 +1,325 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! The keys that do the same thing on every screen, which you can rebind, and
+//! the control (Ctrl) or command (⌘) plus a letter or number keys used to play
+//! on the board, which you can't.
+
+use std::{collections::BTreeMap, fmt};
+
+use iced::keyboard::{Key, Modifiers, key::Named};
+use serde::{Deserialize, Serialize};
+
+use crate::enums::Message;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub(crate) enum Action {
+    AcceptChallenge,
+    Confirm,
+    DeclineChallenge,
+    Fullscreen,
+    Leave,
+    ReviewBackward,
+    ReviewBackwardAll,
+    ReviewForward,
+    ReviewForwardAll,
+    ReviewNextVariation,
+}
+
+impl Action {
+    pub const ALL: [Self; 10] = [
+        Self::AcceptChallenge,
+        Self::Confirm,
+        Self::DeclineChallenge,
+        Self::Fullscreen,
+        Self::Leave,
+        Self::ReviewBackward,
+        Self::ReviewBackwardAll,
+        Self::ReviewForward,
+        Self::ReviewForwardAll,
+        Self::ReviewNextVariation,
+    ];
+
+    pub fn message(self) -> Message {
+        match self {
+            Self::AcceptChallenge => Message::ChallengeAccept,
+            Self::Confirm => Message::PressEnter,
+            Self::DeclineChallenge => Message::ChallengeDecline,
+            Self::Fullscreen => Message::ToggleFullscreenGetWindow,
+            Self::Leave => Message::Leave,
+            Self::ReviewBackward => Message::ReviewGameBackward,
+            Self::ReviewBackwardAll => Message::ReviewGameBackwardAll,
+            Self::ReviewForward => Message::ReviewGameForward,
+            Self::ReviewForwardAll => Message::ReviewGameForwardAll,
+            Self::ReviewNextVariation => Message::ReviewGameChildNext,
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AcceptChallenge => f.write_str("Accept Challenge"),
+            Self::Confirm => f.write_str("Confirm"),
+            Self::DeclineChallenge => f.write_str("Decline Challenge"),
+            Self::Fullscreen => f.write_str("Fullscreen"),
+            Self::Leave => f.write_str("Leave"),
+            Self::ReviewBackward => f.write_str("Move Back"),
+            Self::ReviewBackwardAll => f.write_str("Move Back to the Start"),
+            Self::ReviewForward => f.write_str("Move Forward"),
+            Self::ReviewForwardAll => f.write_str("Move Forward to the End"),
+            Self::ReviewNextVariation => f.write_str("Next Variation"),
+        }
+    }
+}
+
+/// A key and the modifiers held down with it. Control and command are the
+/// same modifier.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct KeyBinding {
+    pub control: bool,
+    pub alt: bool,
+    pub shift: bool,
+    /// A lowercase character or the name of a named key.
+    pub key: String,
+}
+
+impl KeyBinding {
+    /// Returns `None` for the modifier keys pressed by themselves and for
+    /// tab, which moves the focus.
+    pub fn new(key: &Key, modifiers: Modifiers) -> Option<Self> {
+        let key = match key {
+            Key::Character(ch) => ch.to_lowercase(),
+            Key::Named(
+                Named::Alt
+                | Named::Control
+                | Named::Meta
+                | Named::Shift
+                | Named::Super
+                | Named::Tab,
+            )
+            | Key::Unidentified => return None,
+            Key::Named(name) => format!("{name:?}"),
+        };
+
+        Some(Self {
+            control: modifiers.control() || modifiers.command(),
+            alt: modifiers.alt(),
+            shift: modifiers.shift(),
+            key,
+        })
+    }
+
+    fn named(key: Named) -> Self {
+        Self {
+            key: format!("{key:?}"),
+            ..Self::default()
+        }
+    }
+
+    /// Whether it types into a text input, a character or space without
+    /// control or alt, so it isn't a shortcut while one has the focus.
+    pub fn types(&self) -> bool {
+        !self.control && !self.alt && (self.key.chars().count() == 1 || self.key == "Space")
+    }
+
+    /// `Some(true)` for the up arrow and `Some(false)` for the down arrow,
+    /// pressed by themselves.
+    pub fn up_or_down(&self) -> Option<bool> {
+        if self.control || self.alt || self.shift {
+            return None;
+        }
+
+        match self.key.as_str() {
+            "ArrowUp" => Some(true),
+            "ArrowDown" => Some(false),
+            _ => None,
+        }
+    }
+
+    /// The letters and numbers you play on the board with, and the other
+    /// control (Ctrl) or command (⌘) hotkeys.
+    pub fn board_entry(&self) -> Option<Message> {
+        if !self.control || self.alt {
+            return None;
+        }
+
+        let shift = self.shift;
+
+        let message = match self.key.as_str() {
+            "a" => Message::PressA(shift),
+            "b" => Message::PressB(shift),
+            // Fixme: ctrl + shift + "c" is copy.
+            "c" if !shift => Message::PressC(shift),
+            "d" => Message::PressD(shift),
+            "e" => Message::PressE(shift),
+            "f" => Message::PressF(shift),
+            "g" => Message::PressG(shift),
+            "h" => Message::PressH(shift),
+            "i" => Message::PressI(shift),
+            "j" => Message::PressJ(shift),
+            "k" => Message::PressK(shift),
+            "l" => Message::PressL(shift),
+            "m" => Message::PressM(shift),
+            "n" => Message::PressN(shift),
+            "o" => Message::PressO(shift),
+            "p" => Message::PressP(shift),
+            "q" => Message::PressQ(shift),
+            "r" => Message::PressR(shift),
+            "s" => Message::PressS(shift),
+            "t" => Message::PressT(shift),
+            "u" => Message::PressU(shift),
+            // Fixme: ctrl + shift + "v" is paste.
+            "v" if !shift => Message::PressV(shift),
+            "w" => Message::PressW(shift),
+            "x" => Message::PressX(shift),
+            "y" => Message::PressY(shift),
+            "z" => Message::PressZ(shift),
+            "1" => Message::Press1,
+            "2" => Message::Press2,
+            "3" => Message::Press3,
+            "4" => Message::Press4,
+            "5" => Message::Press5,
+            "6" => Message::Press6,
+            "7" => Message::Press7,
+            "8" => Message::Press8,
+            "9" => Message::Press9,
+            "0" => Message::Press0,
+            "-" => Message::PressMinus,
+            "=" if shift => Message::PressPlus,
+            "+" => Message::PressPlus,
+            _ => return None,
+        };
+
+        Some(message)
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.control {
+            f.write_str("Ctrl+")?;
+        }
+        if self.alt {
+            f.write_str("Alt+")?;
+        }
+        if self.shift {
+            f.write_str("Shift+")?;
+        }
+
+        match self.key.as_str() {
+            "ArrowDown" => f.write_str("↓"),
+            "ArrowLeft" => f.write_str("←"),
+            "ArrowRight" => f.write_str("→"),
+            "ArrowUp" => f.write_str("↑"),
+            "Escape" => f.write_str("Esc"),
+            key if key.chars().count() == 1 => f.write_str(&key.to_uppercase()),
+            key => f.write_str(key),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct KeyBindings(BTreeMap<Action, KeyBinding>);
+
+impl KeyBindings {
+    /// Gives the actions added since the key bindings were saved their
+    /// default keys, unless something else already uses the key.
+    pub fn add_missing(&mut self) {
+        for (action, key_binding) in Self::default().0 {
+            if !self.0.contains_key(&action) && self.action(&key_binding).is_none() {
+                self.0.insert(action, key_binding);
+            }
+        }
+    }
+
+    pub fn action(&self, key_binding: &KeyBinding) -> Option<Action> {
+        self.0
+            .iter()
+            .find_map(|(action, binding)| (binding == key_binding).then_some(*action))
+    }
+
+    /// The key binding as it's shown next to a button, empty if the action
+    /// has no key.
+    pub fn get(&self, action: Action) -> String {
+        self.0
+            .get(&action)
+            .map(ToString::to_string)
+            .unwrap_or_default()
+    }
+
+    /// Binds the key to the action. An action that already had the key gets
+    /// the key the action had, so every action keeps a key.
+    pub fn set(&mut self, action: Action, key_binding: KeyBinding) {
+        let other = self.action(&key_binding);
+        let old = self.0.insert(action, key_binding);
+
+        if let Some(other) = other
+            && other != action
+        {
+            match old {
+                Some(old) => {
+                    self.0.insert(other, old);
+                }
+                None => {
+                    self.0.remove(&other);
+                }
+            }
+        }
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self(BTreeMap::from([
+            (
+                Action::AcceptChallenge,
+                KeyBinding {
+                    alt: true,
+                    key: "a".to_string(),
+                    ..KeyBinding::default()
+                },
+            ),
+            (Action::Confirm, KeyBinding::named(Named::Enter)),
+            (
+                Action::DeclineChallenge,
+                KeyBinding {
+                    alt: true,
+                    key: "d".to_string(),
+                    ..KeyBinding::default()
+                },
+            ),
+            (Action::Fullscreen, KeyBinding::named(Named::F11)),
+            (Action::Leave, KeyBinding::named(Named::Escape)),
+            (Action::ReviewBackward, KeyBinding::named(Named::ArrowLeft)),
+            (Action::ReviewBackwardAll, KeyBinding::named(Named::ArrowUp)),
+            (Action::ReviewForward, KeyBinding::named(Named::ArrowRight)),
+            (
+                Action::ReviewForwardAll,
+                KeyBinding::named(Named::ArrowDown),
+            ),
+            (
+                Action::ReviewNextVariation,
+                KeyBinding {
+                    shift: true,
+                    ..KeyBinding::named(Named::ArrowRight)
+                },
+            ),
+        ]))
+    }
+}
@@ -21,17 +21,33 @@
 #![deny(clippy::unwrap_used)]
 
 mod archived_game_handle;
+mod board_canvas;
+mod bug_report;
+mod chat_input;
 mod command_line;
 mod display_game;
 mod enums;
+mod eval_cache;
+mod key_bindings;
 mod new_game_settings;
+mod notes;
+mod onboarding;
 mod portable_game_notation;
+mod rating_graph;
+mod render;
+mod resume_game;
+mod review_window;
 mod solarized;
 mod tabs;
+mod trainer;
+mod tutorial;
+mod ui_scale;
+mod voice;
 mod volume;
 
 use std::{
-    collections::{HashMap, HashSet, VecDeque},
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     fmt::{self, Write as _},
     fs::{self, File},
     io::{BufRead, BufReader, Cursor, ErrorKind, Read, Write},
@@ -43,73 +59,112 @@ use std::{
     time::Duration,
 };
 
-use ::serde::{Deserialize, Serialize};
+use ::serde::{Deserialize, Serialize, de::DeserializeOwned};
+use base64::{Engine, engine::general_purpose::STANDARD};
 use clap::{CommandFactory, Parser};
 use hnefatafl_copenhagen::{
-    COPYRIGHT, Id, SERVER_PORT, SOFTWARE_ID, VERSION_ID,
-    accounts::{Account, Accounts, AccountsOrUsers, User, Users},
+    ARCHIVED_GAMES_PAGE, COPYRIGHT, Id, SOFTWARE_ID, VERSION_ID,
+    accounts::{
+        Account, Accounts, AccountsOrUsers, KID_SAFE_PHRASES, ProvisionalPool, User, Users,
+    },
+    ai::{Cancel, GenerateMove, Progress},
+    analysis::Analysis,
+    avatar::{AVATAR_BYTES, AVATAR_SIZE},
     board::{Board, BoardSize},
     characters::Characters,
+    club::Clubs,
+    command::Command,
+    community::{COMMUNITY, Score, Votes},
     draw::Draw,
-    email::Email,
+    email::{Email, EmailCategory, EmailPreferences},
     game::{Game, GameTime, LegalMoves},
+    glicko::Rating,
     heat_map::{Heat, HeatMap},
+    hooks::{self, Hooks},
     invalid_username,
     locale::Locale,
+    openings::{self, Opening, opening},
     opentafl::OpenTaflGame,
-    play::{BOARD_LETTERS, Plae, Vertex},
-    rating::Rated,
+    physical_board::{self, LineConnection},
+    play::{BOARD_LETTERS, Plae, Play, Vertex},
+    profile::{BIO_LENGTH, PlayerProfile},
+    proof_of_work,
+    puzzle::{self, Daily, Position, Puzzle},
+    rating::{Rated, RatingCategory, Ratings},
     role::Role,
+    rule_set::RuleSet,
+    scout::Scout,
     server_game::{
-        self, AccountsUpdated, ArchivedGame, GamesUpdated, NewGame, ServerGameLight,
-        ServerGamesLight, UsersUpdated,
+        self, AccountsUpdated, ArchivedGame, GamesUpdated, Kibitz, NewGame, ServerGameLight,
+        ServerGamesLight, ServerStatus, Tags, UsersUpdated,
     },
+    settings::Settings,
+    simul::{Simul, Simuls},
     space::Space,
     status::Status,
     tcp_keep_alive,
-    time::{TimeEnum, TimeSettings, TimeUnix},
-    tournament::{GroupSize, NumberOfGames, TournamentFull},
-    tree::Tree,
+    time::{ServerClock, Speed, TimeEnum, TimeLeft, TimeSettings, TimeUnix},
+    tournament::{Announcement, GroupSize, NumberOfGames, TournamentFull},
     utils::{self, choose_ai, config_file, create_config_folder, create_data_folder, data_file},
 };
 #[cfg(target_os = "linux")]
 use iced::window::settings::PlatformSpecific;
 use iced::{
-    Element, Event, Font, Length, Pixels, Subscription, Task,
-    alignment::{Horizontal, Vertical},
+    Color, Element, Event, Font, Length, Pixels, Point, Subscription, Task,
+    alignment::Vertical,
     color, event,
     font::Weight,
     futures::{SinkExt, Stream, executor},
     keyboard::{self, Key, key::Named},
     stream,
     theme::Palette,
+    touch,
     widget::{
-        self, Button, Column, Container, Row, Scrollable, button, checkbox, column, container,
-        operation::{focus_next, focus_previous},
-        pick_list, radio, responsive, row, scrollable, slider, text, text_editor, tooltip,
+        self, Button, Column, Container, Row, Scrollable, button, canvas, checkbox, column,
+        container,
+        operation::{self, focus_next, focus_previous},
+        pick_list, progress_bar, radio, responsive, row, scrollable, slider, text, text_editor,
+        tooltip,
     },
     window::{self, icon},
 };
 use iced_aw::{
-    ICED_AW_FONT_BYTES, Tabs, date_picker::Date, helpers::date_picker, number_input,
+    ContextMenu, ICED_AW_FONT_BYTES, Tabs, date_picker::Date, helpers::date_picker, number_input,
     style::colors::GREY, widget::LabeledFrame,
 };
 use image::ImageFormat;
-use jiff::Timestamp;
+use jiff::{SignedDuration, Timestamp, civil, tz::TimeZone};
 use log::{debug, error, info, trace};
+use rodio::{Source, source::SineWave};
 use rust_i18n::t;
-use smol_str::ToSmolStr;
 use socket2::{Domain, SockAddr, Socket, Type};
 use sys_locale::{get_locale, get_locales};
 
 use crate::{
     archived_game_handle::ArchivedGameHandle,
+    board_canvas::{BoardCanvas, Square},
+    bug_report::LogTail,
+    chat_input::ChatInput,
     command_line::Args,
     display_game::DisplayGame,
-    enums::{Coordinates, JoinGame, Message, Move, Screen, Size, SortBy, State, Theme},
+    enums::{
+        Coordinates, JoinGame, Message, Move, RatingShown, Requirement, RuleSetShown, Screen, Size,
+        SortBy, State, Theme,
+    },
+    eval_cache::EvalCache,
+    key_bindings::{Action, KeyBinding, KeyBindings},
     new_game_settings::NewGameSettings,
+    notes::Notes,
+    onboarding::Onboarding,
+    rating_graph::RatingGraph,
+    resume_game::ResumeGame,
+    review_window::{REVIEW_WINDOW_HEIGHT, REVIEW_WINDOW_WIDTH, ReviewWindow, WindowGeometry},
     solarized::{blue, green, red, yellow},
     tabs::TabId,
+    trainer::Trainer,
+    tutorial::Tutorial,
+    ui_scale::{TextSize, UiScale},
+    voice::{Program, Voice},
     volume::{MAX_VOLUME, Volume},
 };
 
@@ -146,23 +201,38 @@ const BOARD_LETTERS_LOWERCASE: [char; 13] = [
 
 const ARCHIVED_GAMES_FILE: &str = "archived-games.postcard";
 const USER_CONFIG_FILE: &str = "user.ron";
+const HOOKS_FILE: &str = "hooks.txt";
 
+/// How big the avatars next to the names are.
+const AVATAR_SHOWN: f32 = 20.0;
 const MAX_RATING: f64 = 100_000.0;
 const PADDING: u16 = 8;
 const PADDING_TAB_LABEL: u16 = 2;
 const PADDING_SMALL: u16 = 2;
 const PADDING_MEDIUM: u16 = 4;
+/// Buttons big enough to hit with a finger.
+const PADDING_TOUCH: u16 = 16;
 const SPACING: Pixels = Pixels(8.0);
 const SPACING_MEDIUM: Pixels = Pixels(6.0);
 const SPACING_B: Pixels = Pixels(20.0);
 
+/// The background streaming software keys out in `--overlay` mode.
+const CHROMA_KEY: Color = color!(0x00, 0xFF, 0x00);
+const OVERLAY_TEXT_SIZE: f32 = 48.0;
+
 const HELMET: &[u8] = include_bytes!("assets/helmet.png");
 const SOUND_CAPTURE: &[u8] = include_bytes!("assets/capture.ogg");
 const SOUND_GAME_OVER: &[u8] = include_bytes!("assets/game_over.ogg");
 const SOUND_MOVE: &[u8] = include_bytes!("assets/move.ogg");
+/// The rising notes played when someone asks to join your game, in hertz.
+const SOUND_CHALLENGE: [f32; 3] = [659.25, 880.0, 1318.5];
+/// How long each note of the challenge sound plays, in milliseconds.
+const SOUND_CHALLENGE_NOTE: u64 = 140;
+
+/// How often to ask the server for its status on the Login screen.
+const SERVER_STATUS_SECONDS: u64 = 30;
 
 /// In milliseconds.
-const TICK: i64 = 100;
 const TICK_U: u64 = 100;
 
 const DEFAULT_GROUP_SIZE: f64 = 4.0;
@@ -172,8 +242,23 @@ const DEFAULT_NUMBER_OF_GAMES_USIZE: usize = 1;
 
 rust_i18n::i18n!();
 
+/// The text size saved last time, which is needed before the window opens.
+fn text_size_saved() -> TextSize {
+    #[derive(Deserialize)]
+    struct Saved {
+        #[serde(default)]
+        text_size: TextSize,
+    }
+
+    fs::read_to_string(config_file(USER_CONFIG_FILE))
+        .ok()
+        .and_then(|string| ron::from_str::<Saved>(&string).ok())
+        .map(|saved| saved.text_size)
+        .unwrap_or_default()
+}
+
 #[allow(clippy::too_many_lines)]
-fn init_client() -> Client {
+fn init_client() -> (Client, Task<Message>) {
     let archived_games_file = data_file(ARCHIVED_GAMES_FILE);
     let user_data_file = data_file(USER_CONFIG_FILE);
     let user_config_file = config_file(USER_CONFIG_FILE);
@@ -208,10 +293,7 @@ fn init_client() -> Client {
             },
             Err(err) => {
                 if err.kind() == ErrorKind::NotFound {
-                    error.push(format!(
-                        "Unable to find user configuration file: {}",
-                        user_config_file.display()
-                    ));
+                    client.onboarding = Some(Onboarding::default());
                 } else {
                     error.push(format!(
                         "Error reading the file {}: {err}",
@@ -223,6 +305,7 @@ fn init_client() -> Client {
     }
 
     client.tournament_date = Date::today();
+    client.key_bindings.add_missing();
 
     if let Some(locale) = &client.locale {
         rust_i18n::set_locale(&locale.txt());
@@ -289,13 +372,33 @@ fn init_client() -> Client {
         }
     };
 
+    let hooks_file = config_file(HOOKS_FILE);
+    match fs::read_to_string(&hooks_file) {
+        Ok(script) => match script.parse() {
+            Ok(hooks) => client.hooks = hooks,
+            Err(err) => error.push(format!("{}: {err:#}", hooks_file.display())),
+        },
+        Err(err) if err.kind() == ErrorKind::NotFound => {}
+        Err(err) => error.push(format!(
+            "{} {}: {err}",
+            t!("Error opening the file"),
+            hooks_file.display()
+        )),
+    }
+
     client.archived_games = archived_games;
     client.error_persistent = error;
 
     let args = Args::parse();
-    if args.ascii {
+    if args.ascii || client.ascii {
         client.chars.ascii();
     }
+    client.overlay = args.overlay;
+    client.physical_board = args.physical_board.is_some();
+    if let Some(program) = args.voice_program {
+        client.voice = Voice::new(Box::new(Program(program)));
+    }
+    client.host.clone_from(&args.host);
 
     let mut letters = HashMap::new();
     for ch in BOARD_LETTERS_LOWERCASE {
@@ -316,12 +419,55 @@ fn init_client() -> Client {
     client.number_of_games = DEFAULT_NUMBER_OF_GAMES;
     client.tournament.number_of_games.number = DEFAULT_NUMBER_OF_GAMES_USIZE;
 
-    client
+    let mut settings = window_settings();
+
+    // For screenshots.
+    if args.tiny_window {
+        settings.size = iced::Size {
+            width: 870.0,
+            height: 542.0,
+        };
+    } else if args.social_preview {
+        settings.size = iced::Size {
+            width: 1150.0,
+            height: 482.0,
+        };
+    } else if let Some(geometry) = client.window_geometry {
+        settings.size = iced::Size {
+            width: geometry.width,
+            height: geometry.height,
+        };
+
+        if let (Some(x), Some(y)) = (geometry.x, geometry.y) {
+            settings.position = window::Position::Specific(Point::new(x, y));
+        }
+    }
+
+    let (id, open) = window::open(settings);
+    client.main_window = Some(id);
+
+    (client, open.discard())
+}
+
+fn window_settings() -> window::Settings {
+    window::Settings {
+        #[cfg(target_os = "linux")]
+        platform_specific: PlatformSpecific {
+            application_id: APPLICATION_ID.to_string(),
+            ..PlatformSpecific::default()
+        },
+        icon: icon::from_file_data(HELMET, Some(ImageFormat::Png))
+            .inspect_err(|error| error!("icon: {error}"))
+            .ok(),
+        ..window::Settings::default()
+    }
 }
 
 fn main() -> anyhow::Result<()> {
     let args = Args::parse();
-    utils::init_logger("hnefatafl_client", args.debug, false);
+    utils::logger("hnefatafl_client", args.debug, false)
+        .target(env_logger::Target::Pipe(Box::new(LogTail)))
+        .init();
 
     if args.man {
         let mut buffer: Vec<u8> = Vec::default();
@@ -338,38 +484,48 @@ fn main() -> anyhow::Result<()> {
     create_config_folder()?;
     create_data_folder()?;
 
-    let mut application = iced::application(init_client, Client::update, Client::view)
-        .title("Hnefatafl Copenhagen")
-        .subscription(Client::subscriptions)
-        .window(window::Settings {
-            #[cfg(target_os = "linux")]
-            platform_specific: PlatformSpecific {
-                application_id: APPLICATION_ID.to_string(),
-                ..PlatformSpecific::default()
-            },
-            icon: Some(icon::from_file_data(HELMET, Some(ImageFormat::Png))?),
-            ..window::Settings::default()
+    iced::daemon(init_client, Client::update, Client::view)
+        .settings(iced::Settings {
+            default_text_size: f32::from(text_size_saved().0).into(),
+            ..iced::Settings::default()
         })
+        .title(Client::title)
+        .subscription(Client::subscriptions)
         .font(ICED_AW_FONT_BYTES)
-        .theme(Client::theme);
+        .theme(|client: &Client, _window| client.theme())
+        .scale_factor(|client: &Client, _window| client.ui_scale.factor())
+        .run()?;
 
-    // For screenshots.
-    if args.tiny_window {
-        application = application.window_size(iced::Size {
-            width: 870.0,
-            height: 542.0,
-        });
-    }
+    Ok(())
+}
 
-    if args.social_preview {
-        application = application.window_size(iced::Size {
-            width: 1150.0,
-            height: 482.0,
-        });
-    }
+/// Solves the server's puzzle off of the UI thread, sending how far along it
+/// is until it's done.
+fn proof_of_work_solve(nonce: String, bits: u8) -> impl Stream<Item = Message> {
+    stream::channel(
+        100,
+        move |mut sender: iced::futures::channel::mpsc::Sender<Message>| async move {
+            thread::spawn(move || {
+                // About 2^bits numbers get tried on average.
+                let solution = proof_of_work::solve_progress(&nonce, bits, &mut |tried| {
+                    let percent = (tried.saturating_mul(100) >> bits).min(99);
+                    let percent = u8::try_from(percent).unwrap_or(99);
 
-    application.run()?;
-    Ok(())
+                    if let Err(error) =
+                        executor::block_on(sender.send(Message::ProofOfWorkProgress(percent)))
+                    {
+                        error!("failed to send channel: {error}");
+                    }
+                });
+
+                if let Err(error) =
+                    executor::block_on(sender.send(Message::ProofOfWorkSolved(solution)))
+                {
+                    error!("failed to send channel: {error}");
+                }
+            });
+        },
+    )
 }
 
 fn estimate_score() -> impl Stream<Item = Message> {
@@ -380,26 +536,54 @@ fn estimate_score() -> impl Stream<Item = Message> {
         move |mut sender: iced::futures::channel::mpsc::Sender<Message>| async move {
             let (tx, rx) = mpsc::channel();
 
-            if let Err(error) = sender.send(Message::EstimateScoreConnected(tx)).await {
+            let mut ai = match choose_ai(&args.ai, args.seconds, args.depth, args.memory, true) {
+                Ok(ai) => ai,
+                Err(error) => {
+                    error!("{error}");
+                    exit(1);
+                }
+            };
+            let cancel = ai.cancel_token();
+
+            if let Err(error) = sender
+                .send(Message::EstimateScoreConnected((tx, cancel.clone())))
+                .await
+            {
                 error!("failed to send channel: {error}");
                 exit(1);
             }
 
             thread::spawn(move || {
-                let mut ai = match choose_ai(&args.ai, args.seconds, args.depth, true) {
-                    Ok(ai) => ai,
-                    Err(error) => {
-                        error!("{error}");
-                        exit(1);
-                    }
-                };
+                for mut game in &rx {
+                    let board = game.board.clone();
+                    let generate_move = ai.generate_move_progress(&mut game, &mut |progress| {
+                        if let Err(error) = executor::block_on(
+                            sender.send(Message::EstimateScoreProgress((board.clone(), progress))),
+                        ) {
+                            error!("failed to send channel: {error}");
+                        }
+                    });
+
+                    let generate_move = match generate_move {
+                        Ok(generate_move) => generate_move,
+                        Err(_) if cancel.as_ref().is_some_and(Cancel::is_cancelled) => {
+                            if let Err(error) =
+                                executor::block_on(sender.send(Message::EstimateScoreCancelled))
+                            {
+                                error!("failed to send channel: {error}");
+                                exit(1);
+                            }
 
-                for tree in &rx {
-                    let mut game = Game::from(&tree);
-                    let generate_move = ai.generate_move(&mut game).expect("the game is ongoing");
+                            continue;
+                        }
+                        Err(error) => {
+                            error!("{error}");
+                            exit(1);
+                        }
+                    };
 
                     if let Err(error) = executor::block_on(
-                        sender.send(Message::EstimateScoreDisplay((tree.here(), generate_move))),
+                        sender.send(Message::EstimateScoreDisplay((board, generate_move))),
                     ) {
                         error!("failed to send channel: {error}");
                         exit(1);
@@ -426,14 +610,90 @@ fn open_url(url: &str) {
     }
 }
 
+fn physical_board() -> impl Stream<Item = Message> {
+    let args = Args::parse();
+
+    stream::channel(
+        1,
+        move |mut sender: iced::futures::channel::mpsc::Sender<Message>| async move {
+            thread::spawn(move || {
+                let Some(path) = args.physical_board else {
+                    return;
+                };
+
+                let result = File::open(&path)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|file| {
+                        let mut connection = LineConnection::new(BufReader::new(file));
+                        physical_board::moves(&mut connection, |from, to| {
+                            if let Err(error) = executor::block_on(
+                                sender.send(Message::PhysicalBoardMove(from, to)),
+                            ) {
+                                error!("failed to send channel: {error}");
+                            }
+                        })
+                    });
+
+                match result {
+                    Ok(()) => info!("the physical board {} disconnected", path.display()),
+                    Err(error) => error!("physical board {}: {error}", path.display()),
+                }
+            });
+        },
+    )
+}
+
+fn server_status() -> impl Stream<Item = Message> {
+    stream::channel(
+        1,
+        move |mut sender: iced::futures::channel::mpsc::Sender<Message>| async move {
+            thread::spawn(move || {
+                loop {
+                    let status = server_status_get().map_err(|error| error.to_string());
+
+                    // The Login screen is gone.
+                    if executor::block_on(sender.send(Message::ServerStatus(status))).is_err() {
+                        return;
+                    }
+
+                    sleep(Duration::from_secs(SERVER_STATUS_SECONDS));
+                }
+            });
+        },
+    )
+}
+
+fn server_status_get() -> anyhow::Result<ServerStatus> {
+    let args = Args::parse();
+
+    let Some(address) = (args.host.as_str(), args.port).to_socket_addrs()?.next() else {
+        return Err(anyhow::Error::msg("the host resolves to no IPs"));
+    };
+
+    let mut tcp_stream = TcpStream::connect_timeout(&address, Duration::from_secs(5))?;
+    tcp_stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    tcp_stream.write_all(b"status\n")?;
+
+    let mut buffer = String::new();
+    BufReader::new(tcp_stream).read_line(&mut buffer)?;
+
+    let Some(status) = buffer.trim().strip_prefix("= status ") else {
+        return Err(anyhow::Error::msg(format!(
+            "unexpected reply: {}",
+            buffer.trim()
+        )));
+    };
+
+    Ok(serde_json::from_str(status)?)
+}
+
 #[allow(clippy::too_many_lines)]
 fn pass_messages() -> impl Stream<Item = Message> {
     stream::channel(
         100,
         move |mut sender: iced::futures::channel::mpsc::Sender<Message>| async move {
-            let mut args = Args::parse();
-            args.host.push_str(SERVER_PORT);
-            let address_string = args.host;
+            let args = Args::parse();
+            let address_string = format!("{}:{}", args.host, args.port);
 
             thread::spawn(move || {
                 'start_over: loop {
@@ -539,7 +799,7 @@ fn pass_messages() -> impl Stream<Item = Message> {
                         for message in rx {
                             let message_trim = message.trim();
 
-                            if message_trim == "ping" {
+                            if message_trim == "ping" || message_trim == "pong" {
                                 trace!("<- {message_trim}");
                             } else {
                                 debug!("<- {message_trim}");
@@ -630,6 +890,97 @@ fn message_collect(message: SplitAsciiWhitespace<'_>) -> server_game::Message {
     ron::de::from_str(&message).expect("Deserialization has to work!!")
 }
 
+/// A time in your time zone, like `2026-03-14 19:30 CET`.
+fn local_time(timestamp: Timestamp) -> String {
+    Locale::current().date_time(&timestamp.to_zoned(TimeZone::system()))
+}
+
+/// How long until `timestamp`, or nothing once it passed.
+fn time_until(timestamp: Timestamp) -> TimeLeft {
+    TimeLeft {
+        milliseconds_left: (timestamp.as_millisecond() - Timestamp::now().as_millisecond()).max(0),
+    }
+}
+
+/// The moment `time`, `HH:MM` or midnight when empty, on `date` happens in
+/// your time zone.
+fn local_timestamp(date: Date, time: &str) -> anyhow::Result<Timestamp> {
+    let time = if time.trim().is_empty() {
+        "00:00"
+    } else {
+        time.trim()
+    };
+
+    let date_time: civil::DateTime = format!("{date}T{time}").parse()?;
+    Ok(date_time.to_zoned(TimeZone::system())?.timestamp())
+}
+
+fn menu_item<'a>(label: &str, message: Message) -> Button<'a, Message> {
+    button(text(label.to_string()))
+        .on_press(message)
+        .width(Length::Fill)
+        .style(button::text)
+}
+
+/// A rounded percentage, or `-` if there isn't one yet.
+fn percent(percent: Option<f64>) -> String {
+    percent.map_or_else(
+        || "-".to_string(),
+        |percent| Locale::current().number(percent, 0),
+    )
+}
+
+/// Shows an evaluation as a bar filled with the attacker's share and the
+/// move the engine would play.
+fn kibitz_bar<'a>(kibitz: &Kibitz) -> Row<'a, Message> {
+    let best_play = match &kibitz.best_play {
+        Plae::Play(play) => format!("{} → {}", play.from, play.to),
+        plae => plae.to_string(),
+    };
+
+    row![
+        text!("{}: {}", t!("move"), kibitz.play),
+        progress_bar(0.0..=1.0, kibitz.attacker_part())
+            .length(100)
+            .girth(10),
+        text(best_play),
+    ]
+    .spacing(SPACING)
+    .align_y(Vertical::Center)
+}
+
+fn text_header<'a>(username: &str, timestamp: Timestamp, width: f32) -> Element<'a, Message> {
+    let timestamp = Locale::current().date_time(&timestamp.to_zoned(TimeZone::UTC));
+    let timestamp = text(timestamp).color(GREY);
+
+    let username = text(username.to_string()).font(Font {
+        weight: Weight::Bold,
+        ..Font::DEFAULT
+    });
+
+    if width < 375.0 {
+        column![username, timestamp].into()
+    } else {
+        row![username, timestamp].spacing(SPACING).into()
+    }
+}
+
+/// How often each spectator guessed the next move.
+fn quiz_results<'a>(results: &[(String, Score)]) -> Column<'a, Message> {
+    let mut column = Column::new().spacing(SPACING);
+
+    for (username, score) in results {
+        column = column.push(text!(
+            "{username}: {}/{} ({}%)",
+            score.right,
+            score.guessed,
+            Locale::current().number(score.percent(), 0)
+        ));
+    }
+
+    column
+}
+
 fn messages_collect(message: SplitAsciiWhitespace<'_>) -> VecDeque<server_game::Message> {
     let message: Vec<_> = message.collect();
     let message = message.join(" ");
@@ -650,6 +1001,15 @@ struct Client {
     admin: bool,
     #[serde(skip)]
     admin_tournament: bool,
+    /// Say the moves and when your clock is running low out loud.
+    #[serde(default)]
+    announce: bool,
+    /// The banner about the next tournament.
+    #[serde(skip)]
+    announcement: Option<Announcement>,
+    /// The date of the tournament whose banner was dismissed.
+    #[serde(default)]
+    announcement_dismissed: Option<Timestamp>,
     #[serde(skip)]
     attacker: String,
     #[serde(default)]
@@ -662,25 +1022,120 @@ struct Client {
     archived_game_selected: Option<ArchivedGame>,
     #[serde(skip)]
     archived_game_handle: Option<ArchivedGameHandle>,
+    /// Draw the pieces with letters, as with `--ascii`.
+    #[serde(default)]
+    ascii: bool,
+    /// The avatars asked for, `None` until one comes in or if there isn't
+    /// one.
+    #[serde(skip)]
+    avatars: HashMap<String, Option<widget::image::Handle>>,
+    /// The users who may not send you direct messages.
+    #[serde(skip)]
+    blocks: BTreeSet<String>,
+    /// The bug report being written, `None` when you aren't writing one.
+    #[serde(skip)]
+    bug_report: Option<text_editor::Content>,
+    #[serde(skip)]
+    chat_input: ChatInput,
+    #[serde(skip)]
+    club_description: String,
+    #[serde(skip)]
+    club_invite: String,
+    #[serde(skip)]
+    club_name: String,
+    /// The club whose page is open.
+    #[serde(skip)]
+    club_open: Option<String>,
+    #[serde(skip)]
+    clubs: Clubs,
+    #[serde(default)]
+    confirm_moves: bool,
+    /// The direct messages, by who they are with.
+    #[serde(skip)]
+    conversations: BTreeMap<String, VecDeque<server_game::Message>>,
+    #[serde(skip)]
+    conversation_new: String,
+    #[serde(skip)]
+    conversation_open: Option<String>,
     #[serde(default)]
     coordinates: Coordinates,
     #[serde(skip)]
+    daily_puzzle: Option<Daily>,
+    /// Your guess at the puzzle goes to the server as your one try at the
+    /// daily puzzle.
+    #[serde(skip)]
+    daily_puzzle_solving: bool,
+    /// Your try at the daily puzzle, until the server sends back the solution.
+    #[serde(skip)]
+    daily_puzzle_try: Option<(Vertex, Vertex)>,
+    #[serde(skip)]
     defender: String,
     #[serde(skip)]
     delete_account: bool,
+    /// Who sent you direct messages you haven't read yet.
+    #[serde(skip)]
+    dms_unread: BTreeSet<String>,
+    #[serde(skip)]
+    main_window: Option<window::Id>,
+    #[serde(skip)]
+    review_windows: HashMap<window::Id, ReviewWindow>,
     #[serde(skip)]
     estimate_score: bool,
     #[serde(skip)]
-    estimate_score_tx: Option<mpsc::Sender<Tree>>,
+    estimate_score_cancel: Option<Cancel>,
+    #[serde(skip)]
+    estimate_score_progress: Option<Progress>,
+    #[serde(skip)]
+    estimate_score_tx: Option<mpsc::Sender<Game>>,
+    #[serde(skip)]
+    eval_cache: EvalCache,
+    #[serde(default)]
+    follow_the_action: bool,
+    #[serde(skip)]
+    follow_the_action_pending: bool,
+    /// The players you follow, marked with a star.
+    #[serde(default)]
+    follows: BTreeSet<String>,
     #[serde(skip)]
     captures: HashSet<Vertex>,
     #[serde(skip)]
     counter: u64,
     #[serde(skip)]
     chars: Characters,
+    /// When you can claim the games whose opponent disconnected.
+    #[serde(skip)]
+    claim_at: HashMap<Id, i64>,
+    /// The pending game someone asked to join, until it's accepted or
+    /// declined.
+    #[serde(skip)]
+    challenge: Option<Id>,
+    /// Whether the row of the challenged game is lit up.
+    #[serde(skip)]
+    challenge_flash: bool,
+    /// Who gets told about the game you create next.
+    #[serde(skip)]
+    challenge_user: Option<String>,
     #[serde(skip)]
     challenger: bool,
     #[serde(skip)]
+    command: String,
+    #[serde(skip)]
+    command_error: Option<String>,
+    #[serde(skip)]
+    community_votes: Option<Votes>,
+    #[serde(skip)]
+    kibitz: Option<Kibitz>,
+    #[serde(skip)]
+    kibitzes: VecDeque<Kibitz>,
+    /// Only chats in the kid-safe phrases and plays the approved friends.
+    #[serde(skip)]
+    kid_safe: bool,
+    /// Turn kid-safe mode on for the account about to be made.
+    #[serde(skip)]
+    kid_safe_create: bool,
+    #[serde(skip)]
+    kid_safe_friends: Vec<String>,
+    #[serde(skip)]
     connected_tcp: bool,
     #[serde(skip)]
     connected_to: String,
@@ -691,19 +1146,36 @@ struct Client {
     #[serde(skip)]
     email_input: String,
     #[serde(skip)]
+    email_preferences: EmailPreferences,
+    #[serde(skip)]
     emails_bcc: Vec<String>,
     #[serde(skip)]
     error: Option<String>,
     #[serde(skip)]
+    error_avatar: Option<String>,
+    #[serde(skip)]
     error_email: Option<String>,
     #[serde(skip)]
+    error_vacation: Option<String>,
+    #[serde(skip)]
+    error_puzzle: Option<String>,
+    /// Why the server wouldn't let you join a game.
+    #[serde(skip)]
+    error_join: Option<String>,
+    #[serde(skip)]
     error_persistent: Vec<String>,
     #[serde(skip)]
     game: Option<Game>,
+    /// The coach's summary of the game that just ended.
+    #[serde(skip)]
+    game_analysis: Option<(Id, Analysis)>,
     #[serde(skip)]
     game_id: Id,
     #[serde(skip)]
     games_light: ServerGamesLight,
+    /// What to do when something happens, from the hooks file.
+    #[serde(skip)]
+    hooks: Hooks,
     #[serde(skip)]
     games_light_vec: Vec<ServerGameLight>,
     #[serde(skip)]
@@ -713,23 +1185,59 @@ struct Client {
     #[serde(skip)]
     heat_map: Option<HeatMap>,
     #[serde(skip)]
+    host: String,
+    #[serde(skip)]
     heat_map_display: bool,
     #[serde(default)]
     is_fullscreen: bool,
     #[serde(default)]
+    key_bindings: KeyBindings,
+    #[serde(skip)]
+    key_binding_pending: Option<Action>,
+    #[serde(default)]
     locale: Option<Locale>,
+    #[serde(skip)]
+    mentor: bool,
+    /// The message of the day from the admins.
+    #[serde(skip)]
+    motd: Option<String>,
     #[serde(default)]
     my_games_only: bool,
     #[serde(skip)]
     my_turn: bool,
     #[serde(skip)]
+    note: text_editor::Content,
+    /// The game and opponent of the note you're writing.
+    #[serde(skip)]
+    note_game: Option<(Id, String)>,
+    #[serde(default)]
+    notes: Notes,
+    #[serde(skip)]
+    notes_search: String,
+    #[serde(skip)]
     now: i64,
     #[serde(skip)]
     number_of_games: f64,
     #[serde(skip)]
     now_diff: i64,
+    /// When the times of the players were last right, on the server's clock.
+    #[serde(skip)]
+    clock_at: i64,
+    #[serde(skip)]
+    server_clock: ServerClock,
     #[serde(default)]
     password: String,
+    /// The step of the first launch wizard you're on.
+    #[serde(skip)]
+    onboarding: Option<Onboarding>,
+    #[serde(skip)]
+    overlay: bool,
+    /// Moves come from an electronic board too.
+    #[serde(skip)]
+    physical_board: bool,
+    /// Only the archived games in this opening are listed.
+    #[serde(skip)]
+    opening_filter: Option<String>,
     #[serde(skip)]
     password_ends_with_whitespace: bool,
     #[serde(default)]
@@ -743,53 +1251,170 @@ struct Client {
     #[serde(skip)]
     play_to_previous: Option<Vertex>,
     #[serde(skip)]
+    play_to_pending: Option<Vertex>,
+    #[serde(skip)]
     press_letters: HashSet<char>,
     #[serde(skip)]
     press_numbers: [bool; 13],
+    /// The player whose profile the Users tab shows.
+    #[serde(skip)]
+    profile: Option<PlayerProfile>,
+    #[serde(skip)]
+    profile_bio: String,
+    #[serde(skip)]
+    profile_country: String,
+    /// `Roleless` for either side.
+    #[serde(skip)]
+    profile_role: Role,
+    /// The profile asked for and not in yet.
+    #[serde(skip)]
+    profile_wanted: Option<String>,
+    /// How far along, in percent, solving the puzzle to create an account is.
+    #[serde(skip)]
+    proof_of_work: Option<u8>,
+    /// Guess the next moves of the game you're watching.
+    #[serde(skip)]
+    quiz: bool,
+    #[serde(skip)]
+    quiz_guess: Option<(Vertex, Vertex)>,
+    /// How the spectators did at guessing the moves of the game that ended.
+    #[serde(skip)]
+    quiz_results: Option<Vec<(String, Score)>>,
     #[serde(default)]
     rating_minimum: f64,
     #[serde(default)]
     rating_maximum: f64,
     #[serde(skip)]
+    rating_shown: RatingShown,
+    /// The ratings of the players of the game you're looking over, as they
+    /// are now, to compare with their ratings then.
+    #[serde(skip)]
+    ratings_now: Option<BTreeMap<String, Rating>>,
+    #[serde(skip)]
+    report_reason: String,
+    /// Who you are reporting to the moderators.
+    #[serde(skip)]
+    report_user: Option<String>,
+    #[serde(skip)]
     request_draw: bool,
+    #[serde(default)]
+    resume_game: Option<ResumeGame>,
+    /// Look over the game from the other side of the board.
+    #[serde(skip)]
+    review_flipped: bool,
+    /// Which rule set the lobby lists the games of.
+    #[serde(skip)]
+    rule_set_shown: RuleSetShown,
+    /// The scouting report of a player who challenged you.
+    #[serde(skip)]
+    scout: Option<Scout>,
     #[serde(skip)]
     screen: Screen,
     #[serde(skip)]
     screen_size: Size,
     #[serde(skip)]
+    server_status: Option<Result<ServerStatus, String>>,
+    #[serde(skip)]
     server_version: String,
+    /// The settings that follow you to your other computers, and when each
+    /// was changed.
+    #[serde(default)]
+    settings: Settings,
+    #[serde(skip)]
+    show_profanity: bool,
+    #[serde(skip)]
+    simuls: Simuls,
+    /// When you can send to the chat again, `None` being the global chat.
+    #[serde(skip)]
+    slow_mode: HashMap<Option<Id>, Timestamp>,
     #[serde(skip)]
     spectators: Vec<String>,
     #[serde(skip)]
     status: Status,
+    /// Only show the archived games with the tag.
+    #[serde(skip)]
+    tag_filter: Option<String>,
+    /// The tags of the selected archived game, while you edit them.
+    #[serde(skip)]
+    tags: Tags,
+    /// The labels you're typing, split on commas.
+    #[serde(skip)]
+    tags_labels: String,
+    /// The tags you changed that the server doesn't have yet, sent when you
+    /// log in.
+    #[serde(default)]
+    tags_unsent: BTreeMap<Id, Tags>,
     #[serde(skip)]
     texts: VecDeque<server_game::Message>,
     #[serde(skip)]
+    texts_tournament: VecDeque<server_game::Message>,
+    #[serde(skip)]
+    texts_club: HashMap<String, VecDeque<server_game::Message>>,
+    #[serde(skip)]
     texts_game: VecDeque<server_game::Message>,
+    /// How many of the newest messages in the chat of the game you hadn't
+    /// seen when you came back to it.
+    #[serde(skip)]
+    texts_game_unread: usize,
+    /// The messages you haven't seen in the chat of each of your games.
+    #[serde(skip)]
+    texts_unread: BTreeMap<Id, usize>,
     #[serde(skip)]
     text_input: String,
     #[serde(default)]
+    text_size: TextSize,
+    #[serde(default)]
     theme: Theme,
     #[serde(skip)]
     time_attacker: TimeSettings,
     #[serde(skip)]
     time_defender: TimeSettings,
+    /// Someone touched the screen, so the layout is for fingers when the
+    /// window is small.
+    #[serde(skip)]
+    touch_detected: bool,
     #[serde(skip)]
     tournament: TournamentFull,
     #[serde(skip)]
     tournament_date: Date,
     #[serde(skip)]
     tournament_date_show_picker: bool,
+    /// When the tournament starts on `tournament_date`, in your time zone.
+    #[serde(skip)]
+    tournament_time: String,
+    /// Guessing the moves of a player in the game under review.
+    #[serde(skip)]
+    trainer: Option<Trainer>,
+    /// Moves tried out while waiting for the opponent. They aren't sent, and
+    /// the board goes back to the real position when the opponent moves.
+    #[serde(skip)]
+    trial: Option<Game>,
+    /// A practice game against the AI in the game under review.
+    #[serde(skip)]
+    tutorial: Option<Tutorial>,
     #[serde(skip)]
     tx: Option<mpsc::Sender<String>>,
     #[serde(default)]
+    ui_scale: UiScale,
+    #[serde(default)]
     username: String,
     #[serde(skip)]
     users: Users,
     #[serde(skip)]
     users_sort_by: SortBy,
-    #[serde(default)]
-    volume: Volume,
+    #[serde(skip)]
+    vacation_days: String,
+    #[serde(skip)]
+    vacation_days_left: u32,
+    /// When your vacation ends, in unix milliseconds.
+    #[serde(skip)]
+    vacation_until: Option<i64>,
+    #[serde(skip)]
+    voice: Voice,
+    #[serde(default)]
+    volume: Volume,
+    #[serde(default)]
+    window_geometry: Option<WindowGeometry>,
     #[serde(skip)]
     window_height: f32,
     #[serde(skip)]
@@ -798,10 +1423,40 @@ struct Client {
     window_wide: bool,
 }
 
-impl<'a> Client {
+impl Client {
+    fn server_status_view(&self) -> Row<'_, Message> {
+        let mut row = row![text(&self.host)].spacing(SPACING);
+
+        match &self.server_status {
+            Some(Ok(status)) => {
+                row = row.push(text(t!("reachable")).style(text::success));
+                row = row.push(text!("{}: {}", t!("version"), status.version));
+                row = row.push(text!("{}: {}", t!("logged in"), status.players_online));
+
+                if let Some(date) = status.tournament {
+                    row = row.push(text!("{}: {}", t!("Tournament"), local_time(date)));
+                }
+            }
+            Some(Err(error)) => {
+                row = row.push(text!("{}: {error}", t!("unreachable")).style(text::danger));
+            }
+            None => {}
+        }
+
+        row
+    }
+
+    #[allow(clippy::too_many_lines)]
     fn settings_view(&self) -> Column<'_, Message> {
         let mut columns = Column::new().padding(PADDING).spacing(SPACING);
-        columns = columns.push(button(text!("{} (Esc)", t!("Quit"))).on_press(Message::Leave));
+        columns = columns.push(
+            button(text!(
+                "{} ({})",
+                t!("Quit"),
+                self.key_bindings.get(Action::Leave)
+            ))
+            .on_press(Message::Leave),
+        );
 
         columns = columns.push(text!(
             "{} {} {} {} TCP",
@@ -817,8 +1472,95 @@ impl<'a> Client {
             t!("version"),
             self.server_version
         ));
+        columns = columns.push(button(text(t!("Report Issue"))).on_press(Message::BugReport));
         columns = columns.push(self.theme_selection());
         columns = columns.push(self.locale_selection());
+        columns = columns.push(
+            row![
+                text!("{}: ", t!("UI Scale")),
+                pick_list(UiScale::ALL, Some(self.ui_scale), Message::UiScaleSelected),
+                text!("{}: ", t!("Text Size")),
+                pick_list(
+                    TextSize::ALL,
+                    Some(self.text_size),
+                    Message::TextSizeSelected
+                ),
+                text(t!("(after restarting)")),
+            ]
+            .spacing(SPACING)
+            .align_y(Vertical::Center),
+        );
+        columns = columns.push(
+            row![
+                checkbox(self.confirm_moves).on_toggle(Message::ConfirmMoves),
+                text(t!("confirm moves")),
+            ]
+            .spacing(SPACING),
+        );
+        columns = columns.push(
+            row![
+                checkbox(self.announce).on_toggle(Message::Announce),
+                text(t!("Say the moves and low time out loud")),
+            ]
+            .spacing(SPACING),
+        );
+        columns = columns.push(
+            row![
+                checkbox(self.ascii).on_toggle(Message::Ascii),
+                text(t!("Draw the pieces with letters")),
+            ]
+            .spacing(SPACING),
+        );
+
+        let mut key_bindings = Column::new().spacing(SPACING);
+        for action in Action::ALL {
+            let key_binding = if self.key_binding_pending == Some(action) {
+                "…".to_string()
+            } else {
+                self.key_bindings.get(action)
+            };
+
+            key_bindings = key_bindings.push(
+                row![
+                    button(text(key_binding)).on_press(Message::KeyBindingChange(action)),
+                    text(t!(action.to_string())),
+                ]
+                .spacing(SPACING),
+            );
+        }
+        key_bindings = key_bindings
+            .push(button(text(t!("Reset Key Bindings"))).on_press(Message::KeyBindingsReset));
+
+        columns = columns.push(text(t!("Key Bindings")));
+        columns = columns.push(key_bindings);
+
+        if self.kid_safe {
+            columns = columns.push(text!(
+                "{}: {}",
+                t!("Kid-safe mode, friends"),
+                self.kid_safe_friends.join(", ")
+            ));
+        }
+
+        columns = columns.push(
+            row![
+                checkbox(self.mentor).on_toggle(Message::Mentor),
+                text(t!("Mentor new players in their first rated games")),
+            ]
+            .spacing(SPACING),
+        );
+
+        columns = columns.push(
+            row![
+                checkbox(self.show_profanity).on_toggle(Message::ShowProfanity),
+                text(t!("Show Profanity")),
+            ]
+            .spacing(SPACING),
+        );
+
+        columns = columns.push(self.profile_settings());
+        columns = columns.push(self.avatar_settings());
+        columns = columns.push(self.vacation_settings());
 
         if let Some(email) = &self.email {
             let mut row = Row::new();
@@ -863,6 +1605,30 @@ impl<'a> Client {
             columns = columns.push(row![text!("{}: ", t!("email code"))]);
         }
 
+        if self.email.as_ref().is_some_and(|email| email.verified) {
+            let mut email_preferences = Column::new().spacing(SPACING);
+
+            for category in EmailCategory::ALL {
+                let label = match category {
+                    EmailCategory::Announcements => t!("Announcements"),
+                    EmailCategory::Tournaments => t!("Tournament Reminders"),
+                    EmailCategory::TurnNotifications => t!("Turn Notifications"),
+                };
+
+                email_preferences = email_preferences.push(
+                    row![
+                        checkbox(self.email_preferences.get(category))
+                            .on_toggle(move |send| Message::EmailPreference(category, send)),
+                        text(label),
+                    ]
+                    .spacing(SPACING),
+                );
+            }
+
+            columns = columns.push(text(t!("Emails")));
+            columns = columns.push(email_preferences);
+        }
+
         let mut reset_email = button(text!("{} (0)", t!("Reset Email")));
         if self.email.is_some() {
             reset_email = reset_email.on_press(Message::EmailReset);
@@ -919,159 +1685,254 @@ impl<'a> Client {
 
     #[must_use]
     #[allow(clippy::too_many_lines)]
-    fn board(&self) -> Row<'_, Message> {
+    fn board(&self) -> Element<'_, Message> {
         let (board, heat_map) = self.board_and_heatmap();
         let board_size = board.size();
         let board_size_usize: usize = board_size.into();
-        let letters: Vec<_> = BOARD_LETTERS[..board_size_usize].chars().collect();
-        let mut game_display = Row::new().spacing(2);
         let possible_moves = self.possible_moves();
+        let repetition_moves = self
+            .play_from
+            .and_then(|_| self.playable_game())
+            .map(|game| game.repetition_moves());
 
-        let board_dimension = self.window_height / 13.75;
-        let letter_size = self.window_height / 18.181_818;
-        let piece_size = self.window_height / 13.0;
-        let spacing = 2.5;
+        // The board fits the width of the window when it's under the user
+        // area.
+        let extent = if self.touch() {
+            f32::min(self.window_width * 0.95, self.window_height * 0.6)
+        } else {
+            self.window_height
+        };
 
-        game_display = game_display.push(self.numbers(letter_size, spacing, board_size_usize));
+        let board_dimension = extent / 13.75;
+        let letter_size = extent / 18.181_818;
+        let piece_size = extent / 13.0;
+        let spacing = 2.5;
 
-        for (x, letter) in letters.iter().enumerate() {
-            let mut column = Column::new().spacing(2).align_x(Horizontal::Center);
+        let piece = |space| self.piece(space);
 
-            column = self.letter(*letter, column, letter_size);
+        let mut squares = Vec::new();
 
-            for y in 0..board_size_usize {
+        for y in 0..board_size_usize {
+            for x in 0..board_size_usize {
                 let vertex = Vertex {
-                    size: board.size(),
+                    size: board_size,
                     x,
                     y,
                 };
 
-                let mut txt = match board.get(&vertex) {
-                    Space::Attacker => text(&self.chars.attacker).color(red()),
-                    Space::Defender => text(&self.chars.defender).color(blue()),
+                let (mut glyph, mut color) = match board.get(&vertex) {
                     Space::Empty => {
-                        if let Some(arrow) = self.draw_arrow(y, x) {
-                            text(arrow)
-                        } else if self.captures.contains(&vertex) {
-                            text(&self.chars.captured)
+                        if let Some(arrow) = self.draw_arrow(y, x)
+                            && self.trial().is_none()
+                        {
+                            (arrow.to_string(), None)
+                        } else if self.captures.contains(&vertex) && self.trial().is_none() {
+                            (self.chars.captured.clone(), None)
                         } else if vertex.on_restricted_square() {
-                            text(&self.chars.restricted_square).color(green())
+                            (self.chars.restricted_square.clone(), Some(green()))
                         } else {
-                            text(" ")
+                            (String::new(), None)
                         }
                     }
-                    Space::King => text(&self.chars.king).color(yellow()),
+                    space => piece(space),
                 };
 
+                let mut heat = None;
+
                 if let Some((heat_map_from, heat_map_to)) = &heat_map
                     && possible_moves.is_some()
                 {
+                    let space = board.get(&vertex);
+
                     if let Some(vertex_from) = self.play_from.as_ref() {
-                        let space = board.get(vertex_from);
-                        let turn = Role::from(space);
-                        if let Some(heat_map_to) = heat_map_to.get(&(turn, *vertex_from)) {
-                            let heat = heat_map_to[y * board_size_usize + x];
+                        let from = board.get(vertex_from);
+                        let turn = Role::from(from);
 
-                            if heat == Heat::UnRanked {
-                                let space = board.get(&Vertex {
-                                    size: board_size,
-                                    x,
-                                    y,
-                                });
+                        if let Some(heat_map_to) = heat_map_to.get(&(turn, *vertex_from)) {
+                            let alpha: f32 = heat_map_to[y * board_size_usize + x].into();
+                            (glyph, color) = piece(space);
 
-                                txt = match space {
-                                    Space::Attacker => {
-                                        let mut red = red();
-                                        red.a = heat.into();
-                                        text(&self.chars.attacker).color(red)
-                                    }
-                                    Space::Defender => {
-                                        let mut blue = blue();
-                                        blue.a = heat.into();
-                                        text(&self.chars.defender).color(blue)
-                                    }
-                                    Space::Empty => text(""),
-                                    Space::King => {
-                                        let mut yellow = yellow();
-                                        yellow.a = heat.into();
-                                        text(&self.chars.king).color(yellow)
-                                    }
-                                };
+                            if space == Space::Empty {
+                                heat = piece(from).1.map(|color| color.scale_alpha(alpha));
                             } else {
-                                txt = match space {
-                                    Space::Attacker => {
-                                        let mut red = red();
-                                        red.a = heat.into();
-                                        text(&self.chars.attacker).color(red)
-                                    }
-                                    Space::Defender => {
-                                        let mut blue = blue();
-                                        blue.a = heat.into();
-                                        text(&self.chars.defender).color(blue)
-                                    }
-                                    Space::Empty => text(""),
-                                    Space::King => {
-                                        let mut yellow = yellow();
-                                        yellow.a = heat.into();
-                                        text(&self.chars.king).color(yellow)
-                                    }
-                                };
+                                color = color.map(|color| color.scale_alpha(alpha));
                             }
                         }
                     } else {
-                        let heat = heat_map_from[y * board_size_usize + x];
-                        let space = board.get(&Vertex {
-                            size: board_size,
-                            x,
-                            y,
-                        });
-
-                        txt = match space {
-                            Space::Attacker => {
-                                let mut red = red();
-                                red.a = heat.into();
-                                text(&self.chars.attacker).color(red)
-                            }
-                            Space::Defender => {
-                                let mut blue = blue();
-                                blue.a = heat.into();
-                                text(&self.chars.defender).color(blue)
-                            }
-                            Space::Empty => text(""),
-                            Space::King => {
-                                let mut yellow = yellow();
-                                yellow.a = heat.into();
-                                text(&self.chars.king).color(yellow)
-                            }
-                        };
+                        let alpha: f32 = heat_map_from[y * board_size_usize + x].into();
+                        (glyph, color) = piece(space);
+                        color = color.map(|color| color.scale_alpha(alpha));
                     }
                 }
 
-                txt = txt.font(Font::MONOSPACE).center().size(piece_size);
-                let mut button = button(txt).width(board_dimension).height(board_dimension);
+                if let Some(to) = &self.play_to_pending
+                    && *to == vertex
+                    && let Some(from) = &self.play_from
+                {
+                    (glyph, color) = piece(board.get(from));
+                }
 
-                match self.board_move(&vertex, possible_moves.as_ref()) {
-                    Move::From => button = button.on_press(Message::PlayMoveFrom(vertex)),
-                    Move::To => button = button.on_press(Message::PlayMoveTo(vertex)),
-                    Move::Revert => button = button.on_press(Message::PlayMoveRevert),
-                    Move::None => {}
+                let highlight = self.play_to_pending.is_some()
+                    && (self.play_to_pending.as_ref() == Some(&vertex)
+                        || self.play_from.as_ref() == Some(&vertex));
+
+                let mut board_move = self.board_move(&vertex, possible_moves.as_ref());
+                if let (Move::None, None, Some(from), Some(repetition_moves)) = (
+                    &board_move,
+                    &self.play_to_pending,
+                    &self.play_from,
+                    &repetition_moves,
+                ) && repetition_moves
+                    .moves
+                    .get(from)
+                    .is_some_and(|tos| tos.contains(&vertex))
+                {
+                    board_move = Move::Repeat;
                 }
 
-                column = column.push(button);
+                squares.push(Square {
+                    glyph,
+                    color,
+                    heat,
+                    highlight,
+                    board_move,
+                });
+            }
+        }
+
+        let board_canvas = BoardCanvas {
+            board_size,
+            squares,
+            legal_moves: possible_moves,
+            last_move: self
+                .play_from_previous
+                .zip(self.play_to_previous)
+                .filter(|_| self.trial().is_none()),
+            letters: BOARD_LETTERS[..board_size_usize]
+                .chars()
+                .map(|letter| {
+                    let pressed = self.press_letters.contains(&letter.to_ascii_lowercase());
+                    (letter, pressed)
+                })
+                .collect(),
+            numbers: self.press_numbers[..board_size_usize].to_vec(),
+            square_size: board_dimension,
+            spacing,
+            piece_size,
+            letter_size,
+            touch: self.touch(),
+            flipped: self.screen == Screen::GameReview && self.review_flipped,
+        };
+
+        let width = board_canvas.width();
+        canvas(board_canvas).width(width).height(width).into()
+    }
+
+    fn piece(&self, space: Space) -> (String, Option<Color>) {
+        match space {
+            Space::Attacker => (self.chars.attacker.clone(), Some(red())),
+            Space::Defender => (self.chars.defender.clone(), Some(blue())),
+            Space::Empty => (String::new(), None),
+            Space::King => (self.chars.king.clone(), Some(yellow())),
+        }
+    }
+
+    fn review_window_view(
+        &self,
+        id: window::Id,
+        review_window: &ReviewWindow,
+    ) -> Element<'_, Message> {
+        let handle = &review_window.handle;
+        let board = handle.boards.here_board();
+        let board_size = board.size();
+        let board_size_usize: usize = board_size.into();
+
+        // Leave room for the buttons under the board.
+        let height = review_window.height * 0.85;
+        let mut squares = Vec::new();
+
+        for y in 0..board_size_usize {
+            for x in 0..board_size_usize {
+                let vertex = Vertex {
+                    size: board_size,
+                    x,
+                    y,
+                };
+
+                let (glyph, color) = match board.get(&vertex) {
+                    Space::Empty if vertex.on_restricted_square() => {
+                        (self.chars.restricted_square.clone(), Some(green()))
+                    }
+                    space => self.piece(space),
+                };
+
+                squares.push(Square {
+                    glyph,
+                    color,
+                    heat: None,
+                    highlight: false,
+                    board_move: Move::None,
+                });
             }
+        }
+
+        let board_canvas = BoardCanvas {
+            board_size,
+            squares,
+            legal_moves: None,
+            last_move: None,
+            letters: BOARD_LETTERS[..board_size_usize]
+                .chars()
+                .map(|letter| (letter, false))
+                .collect(),
+            numbers: vec![false; board_size_usize],
+            square_size: height / 13.75,
+            spacing: 2.5,
+            piece_size: height / 13.0,
+            letter_size: height / 18.181_818,
+            touch: self.touch(),
+            flipped: false,
+        };
+
+        let width = board_canvas.width();
 
-            column = self.letter(*letter, column, letter_size);
+        let mut left_all = button(text(&self.chars.double_arrow_left_full));
+        let mut left = button(text(&self.chars.double_arrow_left));
 
-            game_display = game_display.push(column);
+        if handle.play > 0 {
+            left_all = left_all.on_press(Message::ReviewWindowBackwardAll(id));
+            left = left.on_press(Message::ReviewWindowBackward(id));
         }
 
-        game_display = game_display.push(self.numbers(letter_size, spacing, board_size_usize));
+        let mut right = button(text(&self.chars.double_arrow_right));
+        let mut right_all = button(text(&self.chars.double_arrow_right_full));
+
+        if handle.boards.has_children() {
+            right = right.on_press(Message::ReviewWindowForward(id));
+            right_all = right_all.on_press(Message::ReviewWindowForwardAll(id));
+        }
 
-        game_display
+        column![
+            canvas(board_canvas).width(width).height(width),
+            row![
+                left_all,
+                left,
+                right,
+                right_all,
+                text!("{}: {}", t!("move"), handle.play)
+            ]
+            .spacing(SPACING)
+            .align_y(Vertical::Center),
+        ]
+        .spacing(SPACING)
+        .padding(PADDING)
+        .into()
     }
 
     fn board_move(&self, vertex: &Vertex, possible_moves: Option<&LegalMoves>) -> Move {
-        if let Some(legal_moves) = possible_moves {
+        if self.play_to_pending.is_some() {
+            Move::None
+        } else if let Some(legal_moves) = possible_moves {
             if let Some(vertex_from) = self.play_from.as_ref() {
                 if let Some(vertexes) = legal_moves.moves.get(vertex_from) {
                     if vertex == vertex_from {
@@ -1112,7 +1973,10 @@ impl<'a> Client {
                 (node.board.clone(), None)
             }
         } else {
-            let game = self.game.as_ref().expect("we should be in a game");
+            let game = self
+                .trial()
+                .or(self.game.as_ref())
+                .expect("we should be in a game");
 
             (game.board.clone(), None)
         }
@@ -1120,14 +1984,97 @@ impl<'a> Client {
 
     fn chat_view(&self) -> Column<'_, Message> {
         column![
-            button(text!("{} (Esc)", t!("Quit"))).on_press(Message::Leave),
+            button(text!(
+                "{} ({})",
+                t!("Quit"),
+                self.key_bindings.get(Action::Leave)
+            ))
+            .on_press(Message::Leave),
             //
-            self.texting(&self.texts, true, 1_000.0),
+            self.texting(&self.texts, &VecDeque::new(), None, true, 1_000.0),
         ]
         .spacing(SPACING)
         .padding(PADDING)
     }
 
+    fn export_calendar_button<'a>() -> Button<'a, Message> {
+        #[cfg(not(target_os = "redox"))]
+        let export_calendar = button(text(t!("Add to Calendar"))).on_press(Message::ExportCalendar);
+
+        #[cfg(target_os = "redox")]
+        let export_calendar = button(text(t!("Add to Calendar")));
+
+        export_calendar
+    }
+
+    /// Asks where to save the next tournament, then writes it as an iCalendar
+    /// file.
+    #[cfg(not(target_os = "redox"))]
+    fn export_calendar(&self) {
+        let Some(announcement) = &self.announcement else {
+            return;
+        };
+
+        let write = || -> anyhow::Result<()> {
+            let dirs = directories::UserDirs::new()
+                .ok_or(anyhow::Error::msg("failed to get user directories"))?;
+
+            let dir = dirs
+                .document_dir()
+                .ok_or(anyhow::Error::msg("failed to get document directory"))?;
+
+            let path = rfd::FileDialog::new()
+                .set_file_name("tournament.ics")
+                .set_directory(dir)
+                .save_file()
+                .ok_or(anyhow::Error::msg("failed to save file"))?;
+
+            fs::write(path, announcement.icalendar(Timestamp::now()))?;
+
+            Ok(())
+        };
+
+        if let Err(error) = write() {
+            error!("ExportCalendar: {error}");
+        }
+    }
+
+    /// Asks for a PNG file, shrinks it to fit, and sends it as your avatar.
+    #[cfg(not(target_os = "redox"))]
+    fn avatar_upload(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("PNG", &["png"])
+            .pick_file()
+        else {
+            return;
+        };
+
+        let read = || -> anyhow::Result<String> {
+            let picture = image::load_from_memory_with_format(&fs::read(path)?, ImageFormat::Png)?;
+            let mut png = Cursor::new(Vec::new());
+            picture
+                .thumbnail(AVATAR_SIZE, AVATAR_SIZE)
+                .write_to(&mut png, ImageFormat::Png)?;
+
+            let png = png.into_inner();
+            if png.len() > AVATAR_BYTES {
+                return Err(anyhow::Error::msg(format!(
+                    "the avatar is bigger than {AVATAR_BYTES} bytes"
+                )));
+            }
+
+            Ok(STANDARD.encode(png))
+        };
+
+        match read() {
+            Ok(avatar) => {
+                self.error_avatar = None;
+                self.send(&format!("avatar_set {avatar}\n"));
+            }
+            Err(error) => self.error_avatar = Some(error.to_string()),
+        }
+    }
+
     #[cfg(not(target_os = "redox"))]
     fn export_pgn(&self) {
         if let Some(game) = &self.archived_game_selected
@@ -1137,6 +2084,136 @@ impl<'a> Client {
         }
     }
 
+    /// Shows the selected tab above a bar of buttons for choosing the tab,
+    /// in reach of your thumbs.
+    fn touch_navigation<'a>(
+        &self,
+        tabs: impl IntoIterator<Item = (TabId, String, Option<u8>, Element<'a, Message>)>,
+    ) -> Element<'a, Message> {
+        let mut content = None;
+        let mut navigation = Row::new().spacing(SPACING_MEDIUM);
+
+        for (tab, label, _key, view) in tabs {
+            let style = if tab == self.active_tab {
+                content = Some(view);
+                button::primary
+            } else {
+                button::secondary
+            };
+
+            navigation = navigation.push(
+                button(text(label).center())
+                    .padding(PADDING_TOUCH)
+                    .style(style)
+                    .on_press(Message::TabSelected(tab)),
+            );
+        }
+
+        let navigation = scrollable(navigation)
+            .direction(scrollable::Direction::Horizontal(
+                scrollable::Scrollbar::new(),
+            ))
+            .spacing(SPACING_MEDIUM);
+
+        column![
+            container(content.unwrap_or_else(|| Column::new().into()))
+                .padding(PADDING)
+                .height(Length::Fill),
+            navigation,
+        ]
+        .into()
+    }
+
+    fn touch(&self) -> bool {
+        self.touch_detected && self.screen_size == Size::Tiny
+    }
+
+    /// Who you play and when they're back, while they're on vacation.
+    fn opponent_vacation(&self) -> Option<(&str, Timestamp)> {
+        let opponent = if self.username == self.attacker {
+            &self.defender
+        } else if self.username == self.defender {
+            &self.attacker
+        } else {
+            return None;
+        };
+
+        let until = self.users.0.get(opponent)?.vacation_until?;
+        let until = Timestamp::from_millisecond(until).ok()?;
+        (until > Timestamp::now()).then_some((opponent.as_str(), until))
+    }
+
+    /// What you can do while your opponent is disconnected.
+    fn claim_view(&self, claim_at: i64) -> Row<'_, Message> {
+        let wait = claim_at - Timestamp::now().as_millisecond();
+        let mut row = row![text(t!("Your opponent disconnected.")).style(text::warning)]
+            .spacing(SPACING)
+            .align_y(Vertical::Center);
+
+        if wait > 0 {
+            row = row.push(text(t!(
+                "You can claim the game in %{seconds} s.",
+                seconds = (wait + 999) / 1_000
+            )));
+        } else {
+            // The win only while their clock runs.
+            let mut claim_win = self.touch_target(button(text(t!("Claim Win"))));
+            if !self.my_turn {
+                claim_win = claim_win.on_press(Message::ClaimWin);
+            }
+
+            row = row.push(claim_win).push(
+                self.touch_target(button(text(t!("Claim Draw"))))
+                    .on_press(Message::ClaimDraw),
+            );
+        }
+
+        row
+    }
+
+    /// Gives a button more room in the touch layout.
+    fn touch_target<'a>(&self, button: Button<'a, Message>) -> Button<'a, Message> {
+        if self.touch() {
+            button.padding(PADDING_TOUCH)
+        } else {
+            button
+        }
+    }
+
+    fn export_png_button<'a>() -> Button<'a, Message> {
+        #[cfg(not(target_os = "redox"))]
+        let export_png =
+            button(text!("{} (s)", t!("Export Position as PNG"))).on_press(Message::ExportPNG);
+
+        #[cfg(target_os = "redox")]
+        let export_png = button(text!("{} (s)", t!("Export Position as PNG")));
+
+        export_png
+    }
+
+    #[cfg(not(target_os = "redox"))]
+    fn export_png(&self) {
+        if (self.game.is_some() || self.archived_game_handle.is_some())
+            && let Err(error) = render::write_png(&self.display_game_initialize())
+        {
+            error!("ExportPNG: {error}");
+        }
+    }
+
+    /// The coach's summary, once the game is over or when reviewing a game.
+    fn analysis_shown(&self, status: &Status) -> Option<&Analysis> {
+        if let Some(handle) = &self.archived_game_handle {
+            return handle.game.analysis.as_ref();
+        }
+
+        match &self.game_analysis {
+            Some((id, analysis)) if *id == self.game_id && *status != Status::Ongoing => {
+                Some(analysis)
+            }
+            _ => None,
+        }
+    }
+
     fn game_state(&self, game_id: u128) -> State {
         if let Some(game) = self.games_light.0.get(&game_id) {
             if game.challenge_accepted {
@@ -1213,6 +2290,12 @@ impl<'a> Client {
             handle_error(self.save_client_ron());
 
             self.send(&format!("software_id {SOFTWARE_ID}\n"));
+            if let Some(locale) = self.locale {
+                self.send(&format!("locale {}\n", locale.txt()));
+            }
+            if self.kid_safe_create {
+                self.send("kid_safe true\n");
+            }
         }
     }
 
@@ -1234,7 +2317,7 @@ impl<'a> Client {
             let row_2 = text("-".repeat(tournament_string.len())).font(Font::MONOSPACE);
             let row_3 = text!(
                 "[{}] {}: {}, fischer {}: {}, {}: {}",
-                tournament.date.strftime("%F %T UTC"),
+                local_time(tournament.date),
                 t!("board size"),
                 tournament.board_size,
                 t!("time"),
@@ -1243,7 +2326,51 @@ impl<'a> Client {
                 tournament.group_size,
             );
 
-            column![row_1, row_2, row_3]
+            let mut column = column![row_1, row_2, row_3];
+
+            if let Some(deadline) = tournament.round_deadline() {
+                column = column.push(text!("{}: {}", t!("Round ends"), local_time(deadline)));
+            }
+
+            if !tournament.withdrawn.is_empty() {
+                let mut withdrawn: Vec<_> =
+                    tournament.withdrawn.iter().map(String::as_str).collect();
+                withdrawn.sort_unstable();
+
+                column = column.push(text!("{}: {}", t!("Withdrawn"), withdrawn.join(", ")));
+            }
+
+            if let Some(arena) = &tournament.arena {
+                column = column.push(text!("{}: {}", t!("Arena ends"), local_time(arena.ends)));
+
+                let leaderboard = t!("Leaderboard");
+                column = column.push(text(leaderboard.to_string()));
+                column = column.push(text("-".repeat(leaderboard.len())).font(Font::MONOSPACE));
+
+                let over = arena.is_over(Timestamp::now());
+
+                for (i, (player, score)) in arena.leaderboard().into_iter().enumerate() {
+                    let mut line = text!(
+                        "{:3} {:16} {}: {:4} {}: {:3} {}",
+                        i + 1,
+                        player,
+                        t!("points"),
+                        score.points,
+                        t!("games"),
+                        score.games,
+                        if score.on_fire() { "×2" } else { "" },
+                    )
+                    .font(Font::MONOSPACE);
+
+                    if over && i == 0 {
+                        line = line.style(text::success);
+                    }
+
+                    column = column.push(line);
+                }
+            }
+
+            column
         } else {
             Column::new()
         };
@@ -1437,10 +2564,11 @@ impl<'a> Client {
                 .expect("we should have a game handle now");
 
             self.estimate_score = true;
-            self.send_estimate_score(handle.boards.clone());
+            self.send_estimate_score(Game::from(&handle.boards));
         }
     }
 
+    #[allow(clippy::too_many_lines)]
     fn game_new_view(&self) -> Column<'_, Message> {
         let attacker = radio(
             format!("{} (8)", t!("attacker")),
@@ -1456,22 +2584,49 @@ impl<'a> Client {
             Message::RoleSelected,
         );
 
-        let rated = LabeledFrame::new(
-            text(t!("rated")),
-            row![
-                text!("(7)"),
-                checkbox(self.game_settings.rated.into()).on_toggle(Message::RatedSelected)
-            ]
-            .padding(PADDING)
-            .spacing(SPACING),
-        );
+        let mut rated = row![
+            text!("(7)"),
+            checkbox(self.game_settings.rated.into()).on_toggle(Message::RatedSelected)
+        ]
+        .padding(PADDING)
+        .spacing(SPACING);
+
+        if self.game_settings.rated.into() {
+            rated = rated.push(text(t!("established ratings only"))).push(
+                checkbox(self.game_settings.established_only)
+                    .on_toggle(Message::EstablishedOnlySelected),
+            );
+            rated = rated
+                .push(text(t!("zen mode")))
+                .push(checkbox(self.game_settings.zen).on_toggle(Message::ZenSelected));
+        }
+
+        let rated = LabeledFrame::new(text(t!("rated")), rated);
+
+        let mut new_game = button(text!(
+            "{} ({})",
+            t!("New Game"),
+            self.key_bindings.get(Action::Confirm)
+        ));
+        let mut new_simul = button(text(t!("Host Simul")));
+        let mut new_community_game = button(text(t!("Community Game")));
+        let mut new_community_game_ai = button(text(t!("Community Game vs AI")));
+        if self.game_settings.role_selected.is_some() {
+            new_community_game = new_community_game.on_press(Message::CommunityGameNew);
+            new_community_game_ai = new_community_game_ai.on_press(Message::CommunityGameAi);
+        }
 
-        let mut new_game = button(text!("{} (Enter)", t!("New Game")));
         if self.game_settings.role_selected.is_some() && self.game_settings.time.is_some() {
             new_game = new_game.on_press(Message::GameSubmit);
+            new_simul = new_simul.on_press(Message::SimulNew);
         }
 
-        let leave = button(text!("{} (Esc)", t!("Quit"))).on_press(Message::Leave);
+        let leave = button(text!(
+            "{} ({})",
+            t!("Quit"),
+            self.key_bindings.get(Action::Leave)
+        ))
+        .on_press(Message::Leave);
 
         let size_11x11 = radio(
             "11x11 (0)",
@@ -1499,8 +2654,19 @@ impl<'a> Client {
                 .spacing(SPACING),
         );
 
-        let real_time = text(format!("{}:", t!("Real Time")));
-        let blitz = radio(
+        let mut rule_sets = Row::new().padding(PADDING).spacing(SPACING);
+        for rule_set in RuleSet::ALL {
+            rule_sets = rule_sets.push(radio(
+                rule_set.to_string(),
+                rule_set,
+                Some(self.game_settings.rule_set),
+                Message::RuleSetSelected,
+            ));
+        }
+        let row_rule_set = LabeledFrame::new(text(t!("rule set")), rule_sets);
+
+        let real_time = text(format!("{}:", t!("Real Time")));
+        let blitz = radio(
             format!("{} (b)", TimeEnum::Blitz),
             TimeEnum::Blitz,
             self.game_settings.time,
@@ -1551,9 +2717,34 @@ impl<'a> Client {
             row![col_1, col_2, col_3, col_4],
         );
 
-        let leave = row![new_game, leave].padding(PADDING).spacing(SPACING);
+        let leave = row![
+            new_game,
+            new_simul,
+            new_community_game,
+            new_community_game_ai,
+            leave
+        ]
+        .padding(PADDING)
+        .spacing(SPACING);
 
-        column![leave, rated, row_role, row_board_size, row_time]
+        let mut column = column![leave];
+
+        if let Some(username) = &self.challenge_user {
+            column = column.push(
+                text(t!(
+                    "%{username} gets a message about the game you create.",
+                    username = username
+                ))
+                .style(text::primary),
+            );
+        }
+
+        column
+            .push(rated)
+            .push(row_role)
+            .push(row_rule_set)
+            .push(row_board_size)
+            .push(row_time)
     }
 
     fn games_filtered(&mut self) {
@@ -1561,7 +2752,15 @@ impl<'a> Client {
             let max_rating = f64::max(game.attacker_rating.rating, game.defender_rating.rating);
             let min_rating = f64::min(game.attacker_rating.rating, game.defender_rating.rating);
 
-            min_rating >= self.rating_minimum && max_rating <= self.rating_maximum
+            min_rating >= self.rating_minimum
+                && max_rating <= self.rating_maximum
+                && self.opening_filter.as_ref().is_none_or(|name| {
+                    opening(&game.plays.made()).is_some_and(|opening| opening.name() == *name)
+                })
+                && self
+                    .tag_filter
+                    .as_ref()
+                    .is_none_or(|tag| game.tags.has(tag))
         });
 
         if self.my_games_only {
@@ -1577,6 +2776,13 @@ impl<'a> Client {
     }
 
     fn game_submit(&mut self) {
+        let new_game = self.new_game();
+        self.send(&format!("new_game {new_game}\n"));
+
+        self.screen = Screen::Games;
+    }
+
+    fn new_game(&mut self) -> String {
         let Some(role) = self.game_settings.role_selected else {
             error!("No role selected.");
             unreachable!();
@@ -1589,17 +2795,16 @@ impl<'a> Client {
 
         self.game_settings.timed = time_settings.into();
 
-        let new_game = serde_json::ser::to_string(&NewGame {
+        serde_json::ser::to_string(&NewGame {
             role,
             rated: self.game_settings.rated.into(),
             time_settings: self.game_settings.timed,
             board_size: self.game_settings.board_size.into(),
+            established_only: self.game_settings.established_only,
+            zen: self.game_settings.zen,
+            rule_set: self.game_settings.rule_set,
         })
-        .expect("Serializing new_game should work!");
-
-        self.send(&format!("new_game {new_game}\n"));
-
-        self.screen = Screen::Games;
+        .expect("Serializing new_game should work!")
     }
 
     fn press_letter(&mut self, letter: char) {
@@ -1645,7 +2850,7 @@ impl<'a> Client {
                 Move::From => self.play_from = Some(vertex),
                 Move::To => self.play_to(vertex),
                 Move::Revert => self.play_from = None,
-                Move::None => {}
+                Move::None | Move::Repeat => {}
             }
 
             for i in 0..13 {
@@ -1696,6 +2901,19 @@ impl<'a> Client {
         } else if (game.attacker.is_none() || game.defender.is_none())
             && !(Some(&self.username) == game.attacker.as_ref()
                 || Some(&self.username) == game.defender.as_ref())
+            && !(game.established_only
+                && game.rated == Rated::Yes
+                && self.rating_provisional(&RatingCategory::new(
+                    game.rule_set,
+                    game.board_size,
+                    &game.timed,
+                )))
+            && (game.rated == Rated::No || self.provisional_pool_allows(game))
+            && game
+                .attacker
+                .as_ref()
+                .or(game.defender.as_ref())
+                .is_none_or(|creator| !self.kid_safe || self.kid_safe_friends.contains(creator))
         {
             JoinGame::Join
         } else {
@@ -1703,7 +2921,115 @@ impl<'a> Client {
         }
     }
 
+    /// How much your rating goes up or down after a win, a draw, and a loss
+    /// in a rated game you play or could join.
+    #[must_use]
+    fn rating_changes(&self, game: &ServerGameLight, category: RatingCategory) -> Option<[i64; 3]> {
+        if game.rated == Rated::No || game.challenge_accepted {
+            return None;
+        }
+
+        let players = [&game.attacker, &game.defender];
+        if !players.contains(&&None) && !players.contains(&&Some(self.username.clone())) {
+            return None;
+        }
+
+        let mut opponents = players
+            .into_iter()
+            .flatten()
+            .filter(|player| **player != self.username);
+        let opponent = opponents.next()?;
+        if opponents.next().is_some() {
+            return None;
+        }
+
+        let ratings = |username: &str| {
+            if self.admin {
+                self.accounts
+                    .0
+                    .get(username)
+                    .map(|account| account.ratings.get(&category))
+            } else {
+                self.users
+                    .0
+                    .get(username)
+                    .map(|user| user.ratings.get(&category))
+            }
+        };
+
+        #[allow(clippy::cast_possible_truncation)]
+        Some(
+            ratings(&self.username)?
+                .changes(&ratings(opponent)?)
+                .map(|change| change.round() as i64),
+        )
+    }
+
+    #[must_use]
+    fn provisional_pool(&self, username: &str) -> Option<ProvisionalPool> {
+        if self.admin {
+            self.accounts.0.get(username).map(Account::provisional_pool)
+        } else {
+            self.users.0.get(username).map(User::provisional_pool)
+        }
+    }
+
+    /// You can join the rated game unless you or the player who made it is new
+    /// and the other is neither new nor a mentor.
+    #[must_use]
+    fn provisional_pool_allows(&self, game: &ServerGameLight) -> bool {
+        let Some(creator) = game.attacker.as_ref().or(game.defender.as_ref()) else {
+            return true;
+        };
+
+        match (
+            self.provisional_pool(&self.username),
+            self.provisional_pool(creator),
+        ) {
+            (Some(pool_1), Some(pool_2)) => pool_1.allows(pool_2),
+            _ => true,
+        }
+    }
+
+    /// The opponent a zen game hides from you until it ends.
+    fn anonymous(&self) -> Option<Role> {
+        if self.status != Status::Ongoing
+            || !self
+                .games_light
+                .0
+                .get(&self.game_id)
+                .is_some_and(|game| game.zen)
+        {
+            return None;
+        }
+
+        if self.username == self.attacker {
+            Some(Role::Defender)
+        } else if self.username == self.defender {
+            Some(Role::Attacker)
+        } else {
+            None
+        }
+    }
+
+    /// Whether your rating in the `category` is still provisional.
+    #[must_use]
+    fn rating_provisional(&self, category: &RatingCategory) -> bool {
+        if self.admin {
+            self.accounts
+                .0
+                .get(&self.username)
+                .is_none_or(|account| account.ratings.get(category).provisional())
+        } else {
+            self.users
+                .0
+                .get(&self.username)
+                .is_none_or(|user| user.ratings.get(category).provisional())
+        }
+    }
+
     fn join(&mut self, id: u128) {
+        self.error_join = None;
         self.game_id = id;
         self.send(&format!("join_game_pending {id}\n"));
 
@@ -1716,16 +3042,525 @@ impl<'a> Client {
         };
     }
 
+    /// Gets the game from the server again, without saying anything, when
+    /// the board here doesn't match the `checksum` the server sent.
+    fn resync(&mut self, checksum: Option<&str>) {
+        if let (Some(checksum), Some(game)) = (checksum, &self.game)
+            && game.checksum() != checksum
+        {
+            info!("game {} is out of step with the server", self.game_id);
+            self.send(&format!("resume_game_ron {}\n", self.game_id));
+        }
+    }
+
     fn resume(&mut self, id: u128) {
         self.game_id = id;
+        self.texts_game_unread = self.texts_unread.remove(&id).unwrap_or_default();
         self.send(&format!("resume_game_ron {id}\n"));
     }
 
+    /// Switches to the ongoing game with the highest rated players, if
+    /// there is one, once the game you are watching ends.
+    fn follow_the_action_next(&mut self) {
+        let rating = |username: &Option<String>| {
+            username
+                .as_ref()
+                .and_then(|username| self.users.0.get(username))
+                .map_or(0.0, |user| user.rating.rating)
+        };
+
+        let next = self
+            .games_light
+            .0
+            .values()
+            .filter(|game| game.challenge_accepted && !game.game_over && game.id != self.game_id)
+            .max_by(|a, b| {
+                let a = rating(&a.attacker) + rating(&a.defender);
+                let b = rating(&b.attacker) + rating(&b.defender);
+                a.total_cmp(&b)
+            })
+            .map(|game| game.id);
+
+        // Keep waiting for a game to start.
+        let Some(id) = next else {
+            return;
+        };
+
+        if self.screen == Screen::Game {
+            self.leave();
+        }
+
+        self.follow_the_action_pending = false;
+        self.watch(id);
+    }
+
     fn watch(&mut self, id: u128) {
         self.game_id = id;
         self.send(&format!("watch_game_ron {id}\n"));
     }
 
+    fn command_send(&mut self) {
+        let Some(game) = &self.game else {
+            return;
+        };
+
+        let command = match Command::parse(&self.command, game.board.size()) {
+            Ok(command) => command,
+            Err(error) => {
+                self.command_error = Some(error.to_string());
+                return;
+            }
+        };
+
+        match command {
+            Command::Say(text) => self.send(&format!("text_game {} {text}\n", self.game_id)),
+            Command::Play { from, to } => {
+                if !self.legal_move(&from, &to) {
+                    self.command_error = Some(format!("{}: {from}-{to}", t!("illegal move")));
+                    return;
+                }
+
+                // Typing the move is confirmation enough.
+                self.play_from = Some(from);
+                self.play_to(to);
+            }
+            _ if !self.my_turn => {
+                self.command_error = Some(t!("It isn't your turn.").to_string());
+                return;
+            }
+            Command::Draw => self.draw(),
+            Command::Resign => self.resign(),
+        }
+
+        self.command.clear();
+    }
+
+    fn legal_move(&self, from: &Vertex, to: &Vertex) -> bool {
+        self.possible_moves().is_some_and(|possible_moves| {
+            possible_moves
+                .moves
+                .get(from)
+                .is_some_and(|tos| tos.contains(to))
+        })
+    }
+
+    /// Plays a move made on the electronic board. When it isn't your turn
+    /// you're setting up your opponent's move, so it's left alone.
+    fn physical_board_move(&mut self, from: &str, to: &str) {
+        let Some(game) = &self.game else {
+            return;
+        };
+
+        if !self.my_turn {
+            return;
+        }
+
+        match Command::parse(&format!("{from}-{to}"), game.board.size()) {
+            Ok(Command::Play { from, to }) if self.legal_move(&from, &to) => {
+                self.command_error = None;
+                self.play_from = Some(from);
+                self.play_move_to(to);
+            }
+            Ok(_) => self.command_error = Some(format!("{}: {from}-{to}", t!("illegal move"))),
+            Err(error) => self.command_error = Some(error.to_string()),
+        }
+    }
+
+    /// Whether you are watching a community game and may vote on its next move.
+    fn community_voter(&self) -> bool {
+        let Some(game) = &self.game else {
+            return false;
+        };
+
+        let community = if self.attacker == COMMUNITY {
+            Role::Attacker
+        } else if self.defender == COMMUNITY {
+            Role::Defender
+        } else {
+            return false;
+        };
+
+        self.archived_game_handle.is_none()
+            && game.status == Status::Ongoing
+            && game.turn == community
+            && self.username != self.attacker
+            && self.username != self.defender
+    }
+
+    /// Whether your moves on the board are guesses at the next move of the
+    /// game you're watching.
+    fn quiz_guesser(&self) -> bool {
+        self.quiz
+            && self.archived_game_handle.is_none()
+            && self
+                .game
+                .as_ref()
+                .is_some_and(|game| game.status == Status::Ongoing)
+            && self.username != self.attacker
+            && self.username != self.defender
+            && !self.community_voter()
+    }
+
+    /// The game in a simul to resume: the first board waiting on the host or
+    /// the game against the player.
+    fn simul_game(&self, simul: &Simul) -> Option<Id> {
+        if simul.host == self.username {
+            simul
+                .games
+                .iter()
+                .find(|id| self.simul_my_turn(id))
+                .or(simul.games.first())
+                .copied()
+        } else {
+            simul
+                .players
+                .iter()
+                .zip(&simul.games)
+                .find(|(player, _)| **player == self.username)
+                .map(|(_, id)| *id)
+        }
+    }
+
+    fn simul_my_turn(&self, id: &Id) -> bool {
+        self.games_light
+            .0
+            .get(id)
+            .is_some_and(|game| match game.turn {
+                Role::Attacker => game.attacker.as_ref() == Some(&self.username),
+                Role::Defender => game.defender.as_ref() == Some(&self.username),
+                Role::Roleless => false,
+            })
+    }
+
+    /// The next board after the current one where it is the host's move.
+    fn simul_next(&self) -> Option<Id> {
+        let simul = self.simuls.game(&self.game_id)?;
+
+        if simul.host != self.username {
+            return None;
+        }
+
+        let position = simul.games.iter().position(|id| *id == self.game_id)?;
+
+        simul
+            .games
+            .iter()
+            .cycle()
+            .skip(position + 1)
+            .take(simul.games.len() - 1)
+            .find(|id| self.simul_my_turn(id))
+            .copied()
+    }
+
+    fn simuls_view(&self) -> Scrollable<'_, Message> {
+        let mut column = Column::new().padding(PADDING).spacing(SPACING);
+        column = column.push(
+            button(text!(
+                "{} ({})",
+                t!("Quit"),
+                self.key_bindings.get(Action::Leave)
+            ))
+            .on_press(Message::Leave),
+        );
+
+        for simul in self.simuls.0.values() {
+            let rated: bool = simul.rated.into();
+            let rated = if rated { t!("yes") } else { t!("no") };
+            let players: Vec<_> = simul.players.iter().map(String::as_str).collect();
+
+            let settings = text!(
+                "{}: {} ({}), {}: {}, {}: {}, {}: {}",
+                t!("host"),
+                simul.host,
+                t!(simul.role.to_string()),
+                t!("board size"),
+                simul.board_size,
+                t!("time"),
+                simul.time_settings,
+                t!("rated"),
+                rated,
+            );
+
+            let mut buttons = Row::new().spacing(SPACING);
+
+            if simul.started() {
+                if let Some(id) = self.simul_game(simul) {
+                    buttons =
+                        buttons.push(button(text(t!("Resume"))).on_press(Message::GameResume(id)));
+                }
+            } else if simul.host == self.username {
+                let mut start = button(text(t!("Start")));
+                if !simul.players.is_empty() {
+                    start = start.on_press(Message::SimulStart(simul.id));
+                }
+
+                buttons = buttons.push(start);
+                buttons = buttons
+                    .push(button(text(t!("Cancel"))).on_press(Message::SimulLeave(simul.id)));
+            } else if simul.players.contains(&self.username) {
+                buttons =
+                    buttons.push(button(text(t!("Leave"))).on_press(Message::SimulLeave(simul.id)));
+            } else {
+                buttons =
+                    buttons.push(button(text(t!("Join"))).on_press(Message::SimulJoin(simul.id)));
+            }
+
+            let players = text!("{}: {}", t!("Players"), players.join(", "));
+
+            column = column.push(LabeledFrame::new(
+                text(simul.id),
+                column![settings, players, buttons]
+                    .padding(PADDING)
+                    .spacing(SPACING),
+            ));
+        }
+
+        scrollable(column)
+    }
+
+    fn clubs_view(&self) -> Scrollable<'_, Message> {
+        let mut column = Column::new().padding(PADDING).spacing(SPACING);
+        column = column.push(
+            button(text!(
+                "{} ({})",
+                t!("Quit"),
+                self.key_bindings.get(Action::Leave)
+            ))
+            .on_press(Message::Leave),
+        );
+
+        if let Some(club) = self
+            .club_open
+            .as_ref()
+            .and_then(|name| self.clubs.0.get(name))
+        {
+            let owner = club.owner == self.username;
+            let member = club.members.contains(&self.username);
+
+            column =
+                column.push(button(text(t!("Back to the Clubs"))).on_press(Message::ClubClose));
+
+            if owner {
+                column = column.push(
+                    row![
+                        widget::text_input(&t!("description"), &self.club_description)
+                            .on_input(Message::ClubDescriptionChanged)
+                            .on_paste(Message::ClubDescriptionChanged)
+                            .on_submit(Message::ClubDescriptionSave),
+                        button(text(t!("Save"))).on_press(Message::ClubDescriptionSave),
+                        button(text(t!("Delete Club"))).on_press(Message::ClubDelete),
+                    ]
+                    .spacing(SPACING),
+                );
+                column = column.push(
+                    row![
+                        widget::text_input(&t!("username"), &self.club_invite)
+                            .on_input(Message::ClubInviteChanged)
+                            .on_paste(Message::ClubInviteChanged)
+                            .on_submit(Message::ClubInvite),
+                        button(text(t!("Invite"))).on_press(Message::ClubInvite),
+                    ]
+                    .spacing(SPACING),
+                );
+            } else {
+                column = column.push(text(club.description.clone()));
+            }
+
+            column = column.push(text!(
+                "{}: {}, {}: {}",
+                t!("owner"),
+                club.owner,
+                t!("created"),
+                Locale::current().date(club.created.to_zoned(TimeZone::system()).date())
+            ));
+
+            let mut members = Column::new().spacing(SPACING);
+            for name in &club.members {
+                let mut row = row![text(name.clone())].spacing(SPACING);
+
+                if owner && *name != club.owner {
+                    row = row.push(
+                        button(text(t!("Remove"))).on_press(Message::ClubRemove(name.clone())),
+                    );
+                }
+
+                members = members.push(row);
+            }
+
+            column = column.push(LabeledFrame::new(text(t!("Members")), members));
+
+            if member {
+                let texts = self.texts_club.get(&club.name).cloned().unwrap_or_default();
+
+                column = column.push(LabeledFrame::new(
+                    text(t!("Chat")),
+                    self.texting(&texts, &VecDeque::new(), None, true, 1_000.0)
+                        .height(300.0),
+                ));
+            }
+
+            return scrollable(column);
+        }
+
+        column = column.push(
+            row![
+                widget::text_input(&t!("club name"), &self.club_name)
+                    .on_input(Message::ClubNameChanged)
+                    .on_paste(Message::ClubNameChanged)
+                    .on_submit(Message::ClubNew),
+                button(text(t!("Create Club"))).on_press(Message::ClubNew),
+            ]
+            .spacing(SPACING),
+        );
+
+        for club in self.clubs.0.values() {
+            let mut buttons =
+                row![button(text(t!("Open"))).on_press(Message::ClubOpen(club.name.clone()))]
+                    .spacing(SPACING);
+
+            if club.owner != self.username {
+                if club.members.contains(&self.username) {
+                    buttons = buttons.push(
+                        button(text(t!("Leave"))).on_press(Message::ClubLeave(club.name.clone())),
+                    );
+                } else if club.invited.contains(&self.username) {
+                    buttons = buttons.push(
+                        button(text(t!("Join"))).on_press(Message::ClubJoin(club.name.clone())),
+                    );
+                }
+            }
+
+            column = column.push(LabeledFrame::new(
+                text(club.name.clone()),
+                column![
+                    text(club.description.clone()),
+                    text!("{}: {}", t!("Members"), club.members.len()),
+                    buttons,
+                ]
+                .padding(PADDING)
+                .spacing(SPACING),
+            ));
+        }
+
+        scrollable(column)
+    }
+
+    fn conversations_view(&self) -> Scrollable<'_, Message> {
+        let mut column = Column::new().padding(PADDING).spacing(SPACING);
+        column = column.push(
+            button(text!(
+                "{} ({})",
+                t!("Quit"),
+                self.key_bindings.get(Action::Leave)
+            ))
+            .on_press(Message::Leave),
+        );
+
+        if let Some(peer) = &self.conversation_open {
+            let block = if self.blocks.contains(peer) {
+                button(text(t!("Unblock"))).on_press(Message::Unblock(peer.clone()))
+            } else {
+                button(text(t!("Block"))).on_press(Message::Block(peer.clone()))
+            };
+
+            column = column.push(
+                row![
+                    button(text(t!("Back to the Conversations")))
+                        .on_press(Message::ConversationClose),
+                    block,
+                ]
+                .spacing(SPACING),
+            );
+
+            let texts = self.conversations.get(peer).cloned().unwrap_or_default();
+
+            column = column.push(LabeledFrame::new(
+                text(peer.clone()),
+                self.texting(&texts, &VecDeque::new(), None, true, 1_000.0)
+                    .height(400.0),
+            ));
+
+            return scrollable(column);
+        }
+
+        column = column.push(
+            row![
+                widget::text_input(&t!("username"), &self.conversation_new)
+                    .on_input(Message::ConversationNewChanged)
+                    .on_paste(Message::ConversationNewChanged)
+                    .on_submit(Message::ConversationNew),
+                button(text(t!("New Conversation"))).on_press(Message::ConversationNew),
+            ]
+            .spacing(SPACING),
+        );
+
+        for (peer, texts) in &self.conversations {
+            let mut label = peer.clone();
+            if self.dms_unread.contains(peer) {
+                label = format!("{label} *");
+            }
+
+            let mut row =
+                row![button(text(label)).on_press(Message::ConversationOpen(peer.clone()))]
+                    .spacing(SPACING);
+
+            if let Some(message) = texts.front() {
+                row = row.push(text!(
+                    "{}: {}",
+                    message.username,
+                    message.content.chars().take(60).collect::<String>()
+                ));
+            }
+
+            column = column.push(row);
+        }
+
+        if !self.blocks.is_empty() {
+            let mut blocks = Column::new().spacing(SPACING);
+            for username in &self.blocks {
+                blocks = blocks.push(
+                    row![
+                        text(username.clone()),
+                        button(text(t!("Unblock"))).on_press(Message::Unblock(username.clone())),
+                    ]
+                    .spacing(SPACING),
+                );
+            }
+
+            column = column.push(LabeledFrame::new(text(t!("Blocked")), blocks));
+        }
+
+        scrollable(column)
+    }
+
+    /// The host's boards, marking the ones where it is their move.
+    fn simul_boards(&self, simul: &Simul) -> Column<'_, Message> {
+        let mut boards = Row::new().spacing(SPACING);
+
+        for (player, id) in simul.players.iter().zip(&simul.games) {
+            let mut board = button(text(player.clone()));
+
+            if *id != self.game_id {
+                board = board.on_press(Message::GameResume(*id));
+            }
+
+            if self.simul_my_turn(id) {
+                board = board.style(button::success);
+            } else {
+                board = board.style(button::secondary);
+            }
+
+            boards = boards.push(board);
+        }
+
+        let mut next = button(text(t!("Next Board")));
+        if self.simul_next().is_some() {
+            next = next.on_press(Message::SimulNext);
+        }
+
+        column![boards.wrap(), next].spacing(SPACING)
+    }
+
     fn login(&mut self) {
         if !self.connected_tcp {
             self.send("tcp_connect\n");
@@ -1743,11 +3578,33 @@ impl<'a> Client {
         }
 
         self.texts.clear();
+        self.texts_tournament.clear();
+        self.texts_club.clear();
+        self.club_open = None;
+        self.conversations.clear();
+        self.conversation_open = None;
+        self.dms_unread.clear();
         self.text_input.clear();
         self.archived_game_reset();
         handle_error(self.save_client_ron());
 
         self.send(&format!("software_id {SOFTWARE_ID}\n"));
+        if let Some(locale) = self.locale {
+            self.send(&format!("locale {}\n", locale.txt()));
+        }
+    }
+
+    fn play_move_to(&mut self, to: Vertex) {
+        if self.confirm_moves
+            && self.archived_game_handle.is_none()
+            && !self.community_voter()
+            && !self.quiz_guesser()
+            && !self.trial_moves()
+        {
+            self.play_to_pending = Some(to);
+        } else {
+            self.play_to(to);
+        }
     }
 
     fn play_to(&mut self, to: Vertex) {
@@ -1755,16 +3612,49 @@ impl<'a> Client {
             .play_from
             .expect("you have to have a from to get to to");
 
+        if self.community_voter() {
+            self.send(&format!("community_vote {} {from} {to}\n", self.game_id));
+            self.play_from = None;
+            return;
+        }
+
+        if self.quiz_guesser() {
+            self.send(&format!("quiz_guess {} {from} {to}\n", self.game_id));
+            self.quiz_guess = Some((from, to));
+            self.play_from = None;
+            return;
+        }
+
+        if self.trainer.is_some() {
+            self.trainer_guess(from, to);
+            self.play_from = None;
+            return;
+        }
+
+        if self.tutorial.is_some() {
+            self.tutorial_play(from, to);
+            self.play_from = None;
+            return;
+        }
+
+        if self.trial_moves() {
+            self.trial_play(from, to);
+            return;
+        }
+
         let mut turn = Role::Roleless;
+        let mut number = 0;
         if let Some(game) = &self.game {
             turn = game.turn;
+            number = game.plays.made().len();
         }
 
+        self.clock_run();
         self.handle_play(None, &from.to_string(), &to.to_string());
 
         if self.archived_game_handle.is_none() {
             self.send(&format!(
-                "game {} play {} {from} {to}\n",
+                "game {} play {} {from} {to} {number}\n",
                 self.game_id, turn
             ));
 
@@ -1786,6 +3676,11 @@ impl<'a> Client {
             }
 
             self.my_turn = false;
+            self.remember_game();
+
+            if let Some(id) = self.simul_next() {
+                self.resume(id);
+            }
         }
 
         self.play_from_previous = self.play_from;
@@ -1793,23 +3688,198 @@ impl<'a> Client {
         self.play_from = None;
     }
 
+    /// The game you can move the pieces of on the board.
+    fn playable_game(&self) -> Option<Cow<'_, Game>> {
+        if self.my_turn || self.community_voter() || self.quiz_guesser() {
+            self.game.as_ref().map(Cow::Borrowed)
+        } else if let Some(handle) = &self.archived_game_handle {
+            Some(Cow::Owned(Game::from(&handle.boards)))
+        } else if self.trial_moves() {
+            self.trial().or(self.game.as_ref()).map(Cow::Borrowed)
+        } else {
+            None
+        }
+    }
+
     fn possible_moves(&self) -> Option<LegalMoves> {
-        let mut possible_moves = None;
+        self.playable_game().map(|game| game.all_legal_moves())
+    }
+
+    fn trial(&self) -> Option<&Game> {
+        self.trial.as_ref().filter(|_| self.trial_moves())
+    }
+
+    /// Whether the moves you make are only tried out on your board, because
+    /// it's your opponent's turn.
+    fn trial_moves(&self) -> bool {
+        self.archived_game_handle.is_none()
+            && !self.my_turn
+            && self.status == Status::Ongoing
+            && (self.username == self.attacker || self.username == self.defender)
+    }
+
+    fn trial_play(&mut self, from: Vertex, to: Vertex) {
+        self.play_from = None;
+
+        let Some(game) = self.trial().or(self.game.as_ref()) else {
+            return;
+        };
 
-        if self.my_turn {
-            if let Some(game) = self.game.as_ref() {
-                possible_moves = Some(game.all_legal_moves());
+        let mut game = game.clone();
+        // The clocks keep running in the real game only.
+        game.time = TimeUnix::UnTimed;
+
+        let play = Plae::Play(Play {
+            role: game.turn,
+            from,
+            to,
+        });
+
+        if game.play(&play).is_ok() {
+            self.trial = Some(game);
+        }
+    }
+
+    fn trial_reset(&mut self) {
+        self.trial = None;
+        self.play_from = None;
+        self.play_to_pending = None;
+    }
+
+    /// The game you're being challenged to, while it's still waiting for you.
+    fn challenge(&self) -> Option<Id> {
+        self.challenge
+            .filter(|id| self.game_state(*id) == State::Creator)
+    }
+
+    /// Runs the challenge hooks, true if they declined it.
+    fn hooks_challenge(&mut self, id: Id) -> bool {
+        let Some(game) = self.games_light.0.get(&id) else {
+            return false;
+        };
+
+        let category = RatingCategory::new(game.rule_set, game.board_size, &game.timed);
+        let rating = game.challenger.0.as_ref().and_then(|challenger| {
+            if self.admin {
+                self.accounts
+                    .0
+                    .get(challenger)
+                    .map(|account| account.ratings.get(&category).rating)
+            } else {
+                self.users
+                    .0
+                    .get(challenger)
+                    .map(|user| user.ratings.get(&category).rating)
             }
-        } else if let Some(handle) = &self.archived_game_handle {
-            let game = Game::from(&handle.boards);
-            possible_moves = Some(game.all_legal_moves());
+        });
+
+        let board_size: usize = game.board_size.into();
+        #[allow(clippy::cast_precision_loss)]
+        let numbers = [
+            ("rating", rating.unwrap_or_default()),
+            ("rated", f64::from(u8::from(game.rated == Rated::Yes))),
+            ("board_size", board_size as f64),
+        ];
+
+        self.hooks_run(hooks::Event::Challenge, &numbers, id)
+    }
+
+    fn hooks_game_end(&mut self) {
+        let won = match self.status {
+            Status::AttackerWins => self.username == self.attacker,
+            Status::DefenderWins => self.username == self.defender,
+            Status::Draw | Status::Ongoing => false,
+        };
+        let draw = self.status == Status::Draw;
+
+        if self.username != self.attacker && self.username != self.defender {
+            return;
+        }
+
+        let numbers = [
+            ("won", f64::from(u8::from(won))),
+            ("lost", f64::from(u8::from(!won && !draw))),
+            ("draw", f64::from(u8::from(draw))),
+        ];
+
+        self.hooks_run(hooks::Event::GameEnd, &numbers, self.game_id);
+    }
+
+    fn hooks_move(&mut self, role: Role, number: usize) {
+        let mine = match role {
+            Role::Attacker => self.username == self.attacker,
+            Role::Defender => self.username == self.defender,
+            Role::Roleless => false,
+        };
+
+        if self.username != self.attacker && self.username != self.defender {
+            return;
         }
 
-        possible_moves
+        #[allow(clippy::cast_precision_loss)]
+        let numbers = [
+            ("number", number as f64),
+            ("captures", self.captures.len() as f64),
+            ("mine", f64::from(u8::from(mine))),
+        ];
+
+        self.hooks_run(hooks::Event::Move, &numbers, self.game_id);
+    }
+
+    /// Does what the hooks say, true if they declined the challenge.
+    fn hooks_run(&mut self, event: hooks::Event, numbers: &[(&str, f64)], id: Id) -> bool {
+        let actions: Vec<_> = self
+            .hooks
+            .run(event, numbers)
+            .into_iter()
+            .cloned()
+            .collect();
+        let mut declined = false;
+
+        for action in actions {
+            match action {
+                hooks::Action::Decline => {
+                    info!("a hook declined the challenge to game {id}");
+                    self.send(&format!("decline_game {id}\n"));
+                    declined = true;
+                }
+                hooks::Action::Say(text) => self.send(&format!("text_game {id} {text}\n")),
+                hooks::Action::Sound => self.challenge_sound(),
+            }
+        }
+
+        declined
+    }
+
+    fn challenge_sound(&self) {
+        let volume = self.volume.volume();
+
+        thread::spawn(move || {
+            let mut stream = rodio::DeviceSinkBuilder::open_default_sink()?;
+            stream.log_on_drop(false);
+
+            let sound = rodio::Player::connect_new(stream.mixer());
+            for frequency in SOUND_CHALLENGE {
+                let note = Duration::from_millis(SOUND_CHALLENGE_NOTE);
+
+                sound.append(
+                    SineWave::new(frequency)
+                        .take_duration(note)
+                        .fade_out(note)
+                        .amplify(0.3),
+                );
+            }
+
+            sound.set_volume(volume);
+            sound.sleep_until_end();
+
+            Ok::<(), anyhow::Error>(())
+        });
     }
 
     fn change_theme(&mut self, theme: Theme) {
         self.theme = theme;
+        self.settings_sync();
         handle_error(self.save_client_ron());
     }
 
@@ -1821,7 +3891,7 @@ impl<'a> Client {
             let captured = game.board.captured();
 
             let mut row_1 = row![
-                text(game.attacker_rating).center(),
+                text(game.attacker_rating_shown()).center(),
                 text(&self.chars.defender)
                     .color(blue())
                     .font(Font::MONOSPACE),
@@ -1837,10 +3907,15 @@ impl<'a> Client {
             }
 
             let attacker = container(column![
-                text(game.attacker.clone()),
+                row![
+                    self.avatar_shown(game.attacker_avatar()),
+                    text(game.attacker_shown())
+                ]
+                .spacing(SPACING)
+                .align_y(Vertical::Center),
                 row_1.spacing(SPACING),
                 row![
-                    text(game.attacker_time).size(35).center(),
+                    text(game.attacker_time.clone()).size(35).center(),
                     text(&self.chars.dagger).size(35).center(),
                 ]
                 .spacing(SPACING),
@@ -1849,7 +3924,7 @@ impl<'a> Client {
             .style(container::bordered_box);
 
             let mut row_2 = row![
-                text(game.defender_rating).center(),
+                text(game.defender_rating_shown()).center(),
                 text(&self.chars.attacker)
                     .color(red())
                     .font(Font::MONOSPACE),
@@ -1861,7 +3936,12 @@ impl<'a> Client {
             }
 
             let defender = container(column![
-                text(game.defender.clone()),
+                row![
+                    self.avatar_shown(game.defender_avatar()),
+                    text(game.defender_shown())
+                ]
+                .spacing(SPACING)
+                .align_y(Vertical::Center),
                 row_2.spacing(SPACING),
                 row![
                     text(game.defender_time).size(35).center(),
@@ -1896,6 +3976,10 @@ impl<'a> Client {
             let mut watching = false;
             let mut user_area = column![title_bar].spacing(SPACING);
 
+            if let Some(opening) = &game.opening {
+                user_area = user_area.push(text!("{}: {opening}", t!("Opening")));
+            }
+
             if size.width < 475.0 {
                 user_area = user_area.push(column![attacker, defender].spacing(SPACING));
             } else {
@@ -1921,18 +4005,86 @@ impl<'a> Client {
                 spectators = spectators.push(text(spectator));
             }
 
-            let resign = button(text!("{} (p)", t!("Resign"))).on_press(Message::PlayResign);
-            let request_draw =
-                button(text!("{} (q)", t!("Request Draw"))).on_press(Message::PlayDraw);
+            let resign = self
+                .touch_target(button(text!("{} (p)", t!("Resign"))))
+                .on_press(Message::PlayResign);
+            let request_draw = self
+                .touch_target(button(text!("{} (q)", t!("Request Draw"))))
+                .on_press(Message::PlayDraw);
 
             if !watching {
+                let plays = self.game.as_ref().map_or(0, |game| game.plays.len());
+                let moved = if self.username == game.attacker {
+                    plays > 0
+                } else {
+                    plays > 1
+                };
+
+                if !moved
+                    && let Some(tournament) = &self.tournament.tournament
+                    && let Some(arena) = &tournament.arena
+                    && arena.can_berserk(self.game_id, &self.username)
+                {
+                    user_area = user_area.push(
+                        self.touch_target(button(text(t!("Berserk"))))
+                            .on_press(Message::Berserk),
+                    );
+                }
+
                 if self.my_turn {
                     user_area = user_area.push(row![resign, request_draw].spacing(SPACING));
+
+                    if let Some(game) = &self.game {
+                        if game.position_repeated() {
+                            user_area = user_area.push(text(t!("This position came up before.")));
+                        }
+
+                        let repeats: usize =
+                            game.repetition_moves().moves.values().map(Vec::len).sum();
+
+                        if repeats > 0 {
+                            user_area = user_area.push(
+                                text(t!(
+                                    "%{count} of your moves would repeat an earlier position.",
+                                    count = repeats
+                                ))
+                                .style(text::warning),
+                            );
+                        }
+                    }
+
+                    if self.play_to_pending.is_some() {
+                        user_area = user_area.push(
+                            row![
+                                self.touch_target(button(text!(
+                                    "{} ({})",
+                                    t!("Confirm Move"),
+                                    self.key_bindings.get(Action::Confirm)
+                                )))
+                                .on_press(Message::PlayMoveConfirm),
+                                self.touch_target(button(text(t!("Cancel"))))
+                                    .on_press(Message::PlayMoveCancel),
+                            ]
+                            .spacing(SPACING),
+                        );
+                    }
                 } else {
+                    if self.trial().is_some() {
+                        user_area = user_area.push(
+                            row![
+                                text(t!("Trying moves, they aren't sent.")).style(text::warning),
+                                self.touch_target(button(text(t!("Back to the Game"))))
+                                    .on_press(Message::TrialReset),
+                            ]
+                            .spacing(SPACING)
+                            .align_y(Vertical::Center),
+                        );
+                    }
+
                     let row = if self.request_draw {
                         column![
                             row![
-                                button(text!("{} (r)", t!("Accept Draw")))
+                                self.touch_target(button(text!("{} (r)", t!("Accept Draw"))))
                                     .on_press(Message::PlayDrawDecision(Draw::Accept)),
                             ]
                             .spacing(SPACING)
@@ -1942,6 +4094,118 @@ impl<'a> Client {
                     };
                     user_area = user_area.push(row.spacing(SPACING));
                 }
+
+                if let Some(claim_at) = self.claim_at.get(&self.game_id) {
+                    user_area = user_area.push(self.claim_view(*claim_at));
+                }
+
+                if let Some((opponent, until)) = self.opponent_vacation() {
+                    user_area = user_area.push(
+                        text(t!(
+                            "%{username} is on vacation until %{date}.",
+                            username = opponent,
+                            date = local_time(until)
+                        ))
+                        .style(text::warning),
+                    );
+                }
+            }
+
+            if !watching || self.community_voter() {
+                let mut command = column![
+                    widget::text_input("f6-f9, /say, /resign, /draw", &self.command)
+                        .on_input(Message::CommandChanged)
+                        .on_submit(Message::CommandSend)
+                ];
+
+                if let Some(error) = &self.command_error {
+                    command = command.push(text(error).style(text::danger));
+                }
+
+                user_area = user_area.push(command.spacing(SPACING));
+            }
+
+            if let Some(simul) = self.simuls.game(&self.game_id)
+                && simul.host == self.username
+            {
+                user_area = user_area.push(self.simul_boards(simul));
+            }
+
+            if let Some(votes) = &self.community_votes {
+                let seconds = (votes.closes - Timestamp::now().as_millisecond()).max(0) / 1_000;
+                let mut tally = Column::new().spacing(SPACING);
+
+                for (play, count) in votes.tally() {
+                    tally = tally.push(text!("{} → {}: {count}", play.from, play.to));
+                }
+
+                user_area = user_area.push(LabeledFrame::new(
+                    text!("{} ({seconds} s)", t!("Votes")),
+                    tally.padding(PADDING),
+                ));
+            }
+
+            if watching
+                && self.archived_game_handle.is_none()
+                && let Some(live_game) = &self.game
+                && live_game.status == Status::Ongoing
+            {
+                // No one may kibitz during a rated game.
+                if self.game_settings.rated == Rated::No {
+                    let mut analyze = button(text(t!("Analyze")));
+                    if !self.estimate_score {
+                        analyze = analyze.on_press(Message::KibitzAnalyze);
+                    }
+
+                    let mut share = button(text(t!("Share")));
+                    if self
+                        .kibitz
+                        .as_ref()
+                        .is_some_and(|kibitz| kibitz.play == live_game.plays.len())
+                    {
+                        share = share.on_press(Message::KibitzShare);
+                    }
+
+                    user_area = user_area.push(row![analyze, share].spacing(SPACING));
+
+                    if let Some(kibitz) = &self.kibitz {
+                        user_area = user_area.push(kibitz_bar(kibitz));
+                    }
+                }
+
+                let mut quiz = row![
+                    checkbox(self.quiz).on_toggle(Message::QuizToggled),
+                    text(t!("Guess the next move")),
+                ]
+                .spacing(SPACING);
+
+                if self.quiz
+                    && let Some((from, to)) = &self.quiz_guess
+                {
+                    quiz = quiz.push(text!("{}: {from}-{to}", t!("Your guess")));
+                }
+
+                user_area = user_area.push(quiz);
+            }
+
+            if let Some(results) = &self.quiz_results {
+                user_area = user_area.push(LabeledFrame::new(
+                    text(t!("Guess the next move")),
+                    quiz_results(results).padding(PADDING),
+                ));
+            }
+
+            if self.note_game.is_some() {
+                user_area = user_area.push(
+                    column![
+                        text(t!("Notes")),
+                        text_editor(&self.note)
+                            .placeholder(t!("Only you can see these notes."))
+                            .on_action(Message::NoteEdit)
+                            .height(100),
+                    ]
+                    .spacing(SPACING),
+                );
             }
 
             let volume = row![
@@ -1951,8 +4215,16 @@ impl<'a> Client {
             .spacing(SPACING);
 
             user_area = user_area.push(volume);
+            user_area =
+                user_area.push(button(text(t!("Report Issue"))).on_press(Message::BugReport));
 
-            let leave = button(text!("{} (Esc)", t!("Leave"))).on_press(Message::Leave);
+            let leave = self
+                .touch_target(button(text!(
+                    "{} ({})",
+                    t!("Leave"),
+                    self.key_bindings.get(Action::Leave)
+                )))
+                .on_press(Message::Leave);
 
             match game.status {
                 Status::AttackerWins => {
@@ -1967,19 +4239,53 @@ impl<'a> Client {
                 }
             }
 
-            if let Some(handle) = &self.archived_game_handle {
-                let mut heat_map = checkbox(self.heat_map_display).size(32);
-                if self.heat_map.is_some() {
-                    heat_map = heat_map.on_toggle(Message::HeatMap);
+            if let Some(analysis) = self.analysis_shown(&game.status) {
+                for sentence in analysis.summary(&game.attacker, &game.defender) {
+                    user_area = user_area.push(text(sentence));
                 }
+            }
+
+            if let Some(handle) = &self.archived_game_handle {
+                if let Some(trainer) = &self.trainer {
+                    user_area = user_area.push(Self::trainer_view(trainer, handle.play));
+                } else if let Some(tutorial) = &self.tutorial {
+                    user_area = user_area.push(Self::tutorial_view(tutorial, handle.play));
+                } else {
+                    let mut heat_map = checkbox(self.heat_map_display).size(32);
+                    if self.heat_map.is_some() {
+                        heat_map = heat_map.on_toggle(Message::HeatMap);
+                    }
+
+                    let mut heat_map_button = button(text!("{} (p) (q)", t!("Heat Map")));
 
-                let mut heat_map_button = button(text!("{} (p) (q)", t!("Heat Map")));
+                    if !self.estimate_score && game.status == Status::Ongoing {
+                        heat_map_button = heat_map_button.on_press(Message::EstimateScore);
+                    }
 
-                if !self.estimate_score && game.status == Status::Ongoing {
-                    heat_map_button = heat_map_button.on_press(Message::EstimateScore);
+                    user_area = user_area.push(row![heat_map, heat_map_button].spacing(SPACING));
+                    user_area = user_area.push(
+                        row![
+                            checkbox(self.review_flipped)
+                                .size(32)
+                                .on_toggle(Message::ReviewGameFlip),
+                            text(t!("Flip Board")),
+                        ]
+                        .spacing(SPACING),
+                    );
                 }
 
-                user_area = user_area.push(row![heat_map, heat_map_button].spacing(SPACING));
+                if self.trainer.is_none()
+                    && self.estimate_score
+                    && let Some(progress) = &self.estimate_score_progress
+                {
+                    user_area = user_area.push(
+                        row![
+                            progress_bar(0.0..=1.0, progress.done).length(100).girth(10),
+                            text!("{}: {}", t!("playouts"), progress.loops),
+                        ]
+                        .spacing(SPACING),
+                    );
+                }
 
                 let child_number = text(handle.boards.next_child);
                 let child_right = button(
@@ -1992,7 +4298,7 @@ impl<'a> Client {
                 user_area = user_area.push(
                     row![
                         leave,
-                        child_right,
+                        self.key_tooltip(child_right, Action::ReviewNextVariation),
                         container(child_number)
                             .style(container::bordered_box)
                             .padding(PADDING_MEDIUM),
@@ -2000,23 +4306,34 @@ impl<'a> Client {
                     .spacing(SPACING),
                 );
 
-                let mut left_all = button(text(&self.chars.double_arrow_left_full));
-                let mut left = button(text(&self.chars.double_arrow_left));
+                let mut left_all =
+                    self.touch_target(button(text(&self.chars.double_arrow_left_full)));
+                let mut left = self.touch_target(button(text(&self.chars.double_arrow_left)));
 
                 if handle.play > 0 {
                     left_all = left_all.on_press(Message::ReviewGameBackwardAll);
                     left = left.on_press(Message::ReviewGameBackward);
                 }
 
-                let mut right = button(text(&self.chars.double_arrow_right));
-                let mut right_all = button(text(&self.chars.double_arrow_right_full));
+                let mut right = self.touch_target(button(text(&self.chars.double_arrow_right)));
+                let mut right_all =
+                    self.touch_target(button(text(&self.chars.double_arrow_right_full)));
 
-                if handle.boards.has_children() {
+                // The moves to guess stay hidden.
+                if handle.boards.has_children() && self.trainer.is_none() {
                     right = right.on_press(Message::ReviewGameForward);
                     right_all = right_all.on_press(Message::ReviewGameForwardAll);
                 }
 
-                user_area = user_area.push(row![left_all, left, right, right_all].spacing(SPACING));
+                user_area = user_area.push(
+                    row![
+                        self.key_tooltip(left_all, Action::ReviewBackwardAll),
+                        self.key_tooltip(left, Action::ReviewBackward),
+                        self.key_tooltip(right, Action::ReviewForward),
+                        self.key_tooltip(right_all, Action::ReviewForwardAll),
+                    ]
+                    .spacing(SPACING),
+                );
 
                 #[cfg(not(target_os = "redox"))]
                 let export_pgn =
@@ -2025,9 +4342,10 @@ impl<'a> Client {
                 #[cfg(target_os = "redox")]
                 let export_pgn = button(text!("{} (r)", t!("Export PGN File")));
 
-                user_area = user_area.push(export_pgn);
+                user_area =
+                    user_area.push(row![export_pgn, Self::export_png_button()].spacing(SPACING));
             } else {
-                user_area = user_area.push(leave);
+                user_area = user_area.push(row![leave, Self::export_png_button()].spacing(SPACING));
 
                 let spectator = text!(
                     "{} ({}) {}: {seconds:01}.{sub_second:03} s",
@@ -2051,11 +4369,23 @@ impl<'a> Client {
 
             if self.archived_game_handle.is_some() {
                 user_area
-                    .push(self.texting(&game.messages, false, size.width))
+                    .push(self.texting(
+                        &game.messages,
+                        &VecDeque::new(),
+                        Some(self.game_id),
+                        false,
+                        size.width,
+                    ))
                     .into()
             } else {
                 user_area
-                    .push(self.texting(&game.messages, true, size.width))
+                    .push(self.texting(
+                        &game.messages,
+                        &self.kibitzes,
+                        Some(self.game_id),
+                        true,
+                        size.width,
+                    ))
                     .into()
             }
         });
@@ -2068,13 +4398,190 @@ impl<'a> Client {
             .style(container::bordered_box)
             .padding(PADDING);
 
-        row![board, user_area].spacing(SPACING).into()
+        if self.touch() {
+            column![board, user_area.height(Length::Fill)]
+                .spacing(SPACING)
+                .into()
+        } else {
+            row![board, user_area].spacing(SPACING).into()
+        }
+    }
+
+    fn tutorial_view(tutorial: &Tutorial, play: usize) -> Column<'_, Message> {
+        let said = match (&tutorial.status, tutorial.you) {
+            (Status::Ongoing, _) => t!(Tutorial::tip(play)),
+            (Status::Draw, _) => t!("It's a draw."),
+            (Status::AttackerWins, Role::Attacker) | (Status::DefenderWins, Role::Defender) => {
+                t!("You win!")
+            }
+            _ => t!("The AI wins."),
+        };
+
+        column![text(t!("Tutorial")).size(20), text(said)].spacing(SPACING)
+    }
+
+    fn trainer_view(trainer: &Trainer, play: usize) -> Column<'_, Message> {
+        if let Some(theme) = trainer.puzzle {
+            return Self::puzzle_view(trainer, theme);
+        }
+
+        let mut column = column![text(t!(
+            "Guess the moves of %{player}.",
+            player = trainer.player
+        ))]
+        .spacing(SPACING);
+
+        let play_text = |play: &Plae| match play {
+            Plae::Play(play) => format!("{}-{}", play.from, play.to),
+            play => play.to_string(),
+        };
+
+        if let Some(guess) = trainer
+            .guesses
+            .range(..play)
+            .next_back()
+            .map(|(_, guess)| guess)
+        {
+            let engine = guess.engine.as_ref().map_or("-".to_string(), play_text);
+
+            let guessed = text!(
+                "{}: {}, {}: {}, {}: {engine}",
+                t!("you"),
+                play_text(&guess.guessed),
+                trainer.player,
+                play_text(&guess.played),
+                t!("engine"),
+            );
+
+            column = if guess.guessed == guess.played {
+                column.push(guessed.style(text::success))
+            } else {
+                column.push(guessed)
+            };
+        }
+
+        if !trainer.guesses.is_empty() {
+            column = column.push(text(t!(
+                "You found %{found} of %{guesses} moves. The engine agreed with you %{you} times and with %{player} %{them} times.",
+                found = trainer.found(),
+                guesses = trainer.guesses.len(),
+                you = trainer.engine_agreed(),
+                player = trainer.player,
+                them = trainer.engine_agreed_with_the_player(),
+            )));
+        }
+
+        if trainer.next(play).is_none() {
+            column = column.push(text(t!("There are no more moves to guess.")));
+        }
+
+        column
+    }
+
+    fn puzzle_view(trainer: &Trainer, theme: puzzle::Theme) -> Column<'_, Message> {
+        let find = match theme {
+            puzzle::Theme::KingHunt | puzzle::Theme::FortBreak => {
+                t!("Find the one move that wins.")
+            }
+            puzzle::Theme::ShieldWall => t!("Find the one move that doesn't lose."),
+        };
+
+        let mut column = column![text!("{}: {theme}", t!("Puzzle")), text(find)].spacing(SPACING);
+
+        if let Some(guess) = trainer.guesses.values().next() {
+            column = if guess.guessed == guess.played {
+                column.push(text(t!("You solved the puzzle!")).style(text::success))
+            } else {
+                let solution = match &guess.played {
+                    Plae::Play(play) => format!("{}-{}", play.from, play.to),
+                    play => play.to_string(),
+                };
+
+                column.push(text(t!("The solution was %{play}.", play = solution)))
+            };
+
+            column = column.push(button(text(t!("Next Puzzle"))).on_press(Message::Puzzle));
+        }
+
+        column
+    }
+
+    fn overlay_view(&self) -> Element<'_, Message> {
+        let game = self.display_game_initialize();
+
+        let player = |name: String, rating: String, time: String, piece: &str, color: Color| {
+            column![
+                text!("{name} {rating}").size(OVERLAY_TEXT_SIZE),
+                row![
+                    text(piece.to_string())
+                        .color(color)
+                        .font(Font::MONOSPACE)
+                        .size(OVERLAY_TEXT_SIZE),
+                    text(time).font(Font::MONOSPACE).size(OVERLAY_TEXT_SIZE),
+                ]
+                .spacing(SPACING),
+            ]
+        };
+
+        let players = column![
+            player(
+                game.attacker_shown(),
+                game.attacker_rating_shown(),
+                game.attacker_time.clone(),
+                &self.chars.attacker,
+                red(),
+            ),
+            player(
+                game.defender_shown(),
+                game.defender_rating_shown(),
+                game.defender_time.clone(),
+                &self.chars.defender,
+                blue(),
+            ),
+        ]
+        .spacing(SPACING_B);
+
+        let players = if let Some(results) = &self.quiz_results {
+            players.push(quiz_results(results))
+        } else {
+            players
+        };
+
+        container(row![self.board(), players].spacing(SPACING_B))
+            .padding(PADDING)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(|_theme| container::Style {
+                background: Some(CHROMA_KEY.into()),
+                text_color: Some(Color::WHITE),
+                ..container::Style::default()
+            })
+            .into()
     }
 
     fn display_game_initialize(&self) -> DisplayGame {
         if let Some(game_handle) = &self.archived_game_handle {
-            let attacker_rating = game_handle.game.attacker_rating.to_string_rounded();
-            let defender_rating = game_handle.game.defender_rating.to_string_rounded();
+            let rating = |username: &str, then: &Rating| {
+                let then = then.to_string_rounded();
+
+                match self
+                    .ratings_now
+                    .as_ref()
+                    .and_then(|ratings| ratings.get(username))
+                {
+                    Some(now) => format!("{then} ({}: {})", t!("now"), now.to_string_rounded()),
+                    None => then,
+                }
+            };
+
+            let attacker_rating = rating(
+                &game_handle.game.attacker,
+                &game_handle.game.attacker_rating,
+            );
+            let defender_rating = rating(
+                &game_handle.game.defender,
+                &game_handle.game.defender_rating,
+            );
 
             let status = if game_handle.play == game_handle.game.plays.len().saturating_sub(1) {
                 &game_handle.game.status
@@ -2082,6 +4589,8 @@ impl<'a> Client {
                 &Status::Ongoing
             };
 
+            let made = game_handle.game.plays.made();
+
             DisplayGame {
                 game_id: game_handle.game.id,
                 attacker: game_handle.game.attacker.clone(),
@@ -2098,8 +4607,10 @@ impl<'a> Client {
                 defender_rating,
                 board: game_handle.boards.here().board,
                 play: game_handle.play,
+                opening: opening(&made[..game_handle.play.min(made.len())]).map(Opening::name),
                 status: status.clone(),
                 messages: game_handle.game.messages.clone(),
+                anonymous: None,
             }
         } else {
             let mut attacker_rating = String::new();
@@ -2122,6 +4633,21 @@ impl<'a> Client {
             }
 
             let game = self.game.as_ref().expect("we should be in a game");
+            let anonymous = self.anonymous();
+
+            let mut messages = self.texts_game.clone();
+            if let Some(role) = anonymous {
+                let opponent = match role {
+                    Role::Attacker => &self.attacker,
+                    Role::Defender | Role::Roleless => &self.defender,
+                };
+
+                for message in &mut messages {
+                    if &message.username == opponent {
+                        message.username = t!("Anonymous").to_string();
+                    }
+                }
+            }
 
             DisplayGame {
                 game_id: self.game_id,
@@ -2132,11 +4658,82 @@ impl<'a> Client {
                 defender_time: self.time_defender.time_left(),
                 defender_rating,
                 board: game.board.clone(),
-                play: game.previous_boards.0.len() - 1,
+                play: game.previous_boards.len() - 1,
+                opening: opening(&game.plays.made()).map(Opening::name),
                 status: self.status.clone(),
-                messages: self.texts_game.clone(),
+                messages,
+                anonymous,
+            }
+        }
+    }
+
+    fn key_tooltip<'a>(
+        &self,
+        content: impl Into<Element<'a, Message>>,
+        action: Action,
+    ) -> Element<'a, Message> {
+        tooltip(
+            content,
+            container(text(self.key_bindings.get(action)))
+                .style(container::bordered_box)
+                .padding(PADDING),
+            tooltip::Position::Bottom,
+        )
+        .into()
+    }
+
+    /// Keeps the tags of the selected game, to send to the server the next
+    /// time you log in.
+    fn tags_save(&mut self) {
+        let Some(game) = &mut self.archived_game_selected else {
+            return;
+        };
+
+        let mut tags = self.tags.clone();
+        tags.labels = self
+            .tags_labels
+            .split(',')
+            .map(str::trim)
+            .filter(|label| !label.is_empty())
+            .map(ToString::to_string)
+            .collect();
+
+        game.tags = tags.clone();
+        let id = game.id;
+        if let Some(archived) = self
+            .archived_games
+            .iter_mut()
+            .find(|archived| archived.id == id)
+        {
+            archived.tags = tags.clone();
+        }
+
+        // Sent when you're logged in, kept until you are otherwise.
+        if self.connected_tcp && self.screen != Screen::Login {
+            match ron::ser::to_string(&tags) {
+                Ok(tags) => self.send(&format!("game_tags {id} {tags}\n")),
+                Err(error) => error!("game_tags: {error}"),
             }
+        } else {
+            self.tags_unsent.insert(id, tags);
+        }
+
+        self.games_filtered();
+        handle_error(self.save_client_postcard());
+        handle_error(self.save_client_ron());
+    }
+
+    fn key_pressed(&mut self, key_binding: KeyBinding) -> Task<Message> {
+        if let Some(action) = self.key_binding_pending.take() {
+            self.key_bindings.set(action, key_binding);
+            handle_error(self.save_client_ron());
+        } else if let Some(action) = self.key_bindings.action(&key_binding) {
+            return Task::done(action.message());
+        } else if let Some(message) = key_binding.board_entry() {
+            return Task::done(message);
         }
+
+        Task::none()
     }
 
     fn leave(&mut self) {
@@ -2147,8 +4744,12 @@ impl<'a> Client {
             }
             Screen::Game => {
                 self.screen = Screen::Games;
+                self.claim_at.remove(&self.game_id);
+                self.note_close();
                 self.my_turn = false;
+                self.trial = None;
                 self.request_draw = false;
+                self.follow_the_action_pending = false;
 
                 if self.spectators.contains(&self.username) {
                     self.send(&format!("leave_game {}\n", self.game_id));
@@ -2165,9 +4766,17 @@ impl<'a> Client {
                 self.email = None;
             }
             Screen::GameReview => {
+                self.estimate_score_cancel();
+                self.note_close();
+                self.trainer = None;
                 self.heat_map = None;
                 self.heat_map_display = false;
-                self.screen = Screen::Login;
+                self.ratings_now = None;
+                self.review_flipped = false;
+                self.screen = self
+                    .tutorial
+                    .take()
+                    .map_or(Screen::Login, |tutorial| tutorial.back);
             }
             Screen::Login => {}
         }
@@ -2209,13 +4818,189 @@ impl<'a> Client {
         ]
     }
 
+    fn bug_report_gather(&self) -> String {
+        let server = if self.screen == Screen::Login {
+            Args::parse().host
+        } else {
+            format!("{} {}", self.connected_to, self.server_version)
+        };
+
+        if self.screen == Screen::GameReview
+            && let Some(handle) = &self.archived_game_handle
+        {
+            let made = handle.game.plays.made();
+            let plays = made.get(..handle.play).unwrap_or(&made);
+
+            bug_report::gather(&server, Some((handle.game.id, plays)))
+        } else if self.screen == Screen::Game
+            && let Some(game) = &self.game
+        {
+            bug_report::gather(&server, Some((self.game_id, &game.plays.made())))
+        } else {
+            bug_report::gather(&server, None)
+        }
+    }
+
+    fn bug_report_view<'a>(&self, report: &'a text_editor::Content) -> Element<'a, Message> {
+        let mut send = button(text(t!("Send to the Server")));
+        if self.screen != Screen::Login {
+            send = send.on_press(Message::BugReportSend);
+        }
+
+        let column = column![
+            text(t!("Report Issue")).size(32),
+            text(t!(
+                "Say what went wrong at the top. The rest tells us about your computer, the game, and what the client was doing."
+            )),
+            text_editor(report)
+                .on_action(Message::BugReportEdit)
+                .height(Length::Fill),
+            row![
+                button(text(t!("Copy"))).on_press(Message::BugReportCopy),
+                send,
+                button(text(t!("Cancel"))).on_press(Message::BugReportCancel),
+            ]
+            .spacing(SPACING),
+        ]
+        .padding(PADDING)
+        .spacing(SPACING);
+
+        column.into()
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn onboarding_view(&self, step: Onboarding) -> Element<'_, Message> {
+        let content: Element<'_, Message> = match step {
+            Onboarding::Language => self.locale_selection().into(),
+            Onboarding::Theme => self.theme_selection().into(),
+            Onboarding::Characters => {
+                let unicode = Characters::default();
+                let mut ascii = Characters::default();
+                ascii.ascii();
+
+                let mut characters = Column::new().spacing(SPACING);
+                for (chars, letters) in [(unicode, false), (ascii, true)] {
+                    characters = characters.push(radio(
+                        format!("{} {} {}", chars.attacker, chars.defender, chars.king),
+                        letters,
+                        Some(self.ascii),
+                        Message::Ascii,
+                    ));
+                }
+
+                column![
+                    text(t!(
+                        "If the pieces with symbols don't show up right, use the letters."
+                    )),
+                    characters,
+                ]
+                .spacing(SPACING)
+                .into()
+            }
+            Onboarding::Account => {
+                let username = row![
+                    text!("{}:", t!("username")).size(20),
+                    widget::text_input("", &self.text_input)
+                        .on_input(Message::TextChanged)
+                        .on_paste(Message::TextChanged),
+                ]
+                .spacing(SPACING);
+
+                let password = row![
+                    text!("{}:", t!("password")).size(20),
+                    widget::text_input("", &self.password)
+                        .secure(!self.password_show)
+                        .on_input(Message::PasswordChanged)
+                        .on_paste(Message::PasswordChanged),
+                ]
+                .spacing(SPACING);
+
+                let kid_safe = row![
+                    checkbox(self.kid_safe_create).on_toggle(Message::KidSafeCreate),
+                    text(t!("kid-safe account")),
+                ]
+                .spacing(SPACING);
+
+                let mut create_account = button(text(t!("Create Account")));
+                let mut login = button(text(t!("Login")));
+                if !invalid_username(&self.text_input) {
+                    create_account = create_account.on_press(Message::TextSendCreateAccount);
+                    login = login.on_press(Message::TextSendLogin);
+                }
+
+                let mut error = text("");
+                if let Some(error_) = &self.error {
+                    error = text(error_).style(text::danger);
+                } else if let Some(percent) = self.proof_of_work {
+                    error = text!(
+                        "{}... {}%",
+                        t!("Creating the account"),
+                        Locale::current().number(f64::from(percent), 0)
+                    );
+                }
+
+                column![
+                    text(t!(
+                        "You need an account to play online. You can skip this and make one later."
+                    )),
+                    username,
+                    password,
+                    kid_safe,
+                    row![create_account, login].spacing(SPACING),
+                    error,
+                ]
+                .spacing(SPACING)
+                .into()
+            }
+            Onboarding::Tutorial => column![
+                text(t!(
+                    "Learn the rules with a practice game against the easy AI on your own computer."
+                )),
+                button(text(t!("Tutorial Game"))).on_press(Message::Tutorial),
+            ]
+            .spacing(SPACING)
+            .into(),
+        };
+
+        let mut back = button(text(t!("Back")));
+        if let Some(previous) = step.previous() {
+            back = back.on_press(Message::Onboarding(Some(previous)));
+        }
+
+        let next = if let Some(next) = step.next() {
+            button(text(t!("Next"))).on_press(Message::Onboarding(Some(next)))
+        } else {
+            button(text(t!("Finish"))).on_press(Message::Onboarding(None))
+        };
+
+        let skip = button(text(t!("Skip"))).on_press(Message::Onboarding(None));
+
+        let column = column![
+            text(t!("Welcome to Copenhagen Hnefatafl!")).size(32),
+            text!(
+                "{} ({}/{})",
+                t!(step.to_string()),
+                step.number(),
+                Onboarding::ALL.len()
+            )
+            .size(20),
+            content,
+            row![back, next, skip].spacing(SPACING),
+        ]
+        .padding(PADDING)
+        .spacing(SPACING);
+
+        scrollable(column).into()
+    }
+
     fn my_games_only(&mut self) {
         self.my_games_only = !self.my_games_only;
         self.games_filtered();
+        self.settings_sync();
         handle_error(self.save_client_ron());
     }
 
-    #[allow(clippy::too_many_lines)]
+    #[allow(clippy::similar_names, clippy::too_many_lines)]
     pub(crate) fn subscriptions(&self) -> Subscription<Message> {
         let subscription_1 = if let Some(game) = &self.game {
             if let TimeUnix::Time(_) = game.time {
@@ -2231,76 +5016,39 @@ impl<'a> Client {
         let subscription_2 = Subscription::run(pass_messages);
         let subscription_3 = Subscription::run(estimate_score);
 
-        let subscription_4 = event::listen_with(|event, _status, _id| match event {
-            Event::Window(iced::window::Event::Resized(size)) => {
-                Some(Message::WindowResized((size.width, size.height)))
-            }
-            Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => {
-                let shift = modifiers.shift();
-
-                match key {
-                    Key::Character(ch) if modifiers.control() || modifiers.command() => match ch {
-                        ch if *ch == *"a".to_smolstr() => Some(Message::PressA(shift)),
-                        ch if *ch == *"b".to_smolstr() => Some(Message::PressB(shift)),
-                        // Fixme: ctrl + shift + "c" is copy.
-                        ch if *ch == *"c".to_smolstr() && !shift => Some(Message::PressC(shift)),
-                        ch if *ch == *"d".to_smolstr() => Some(Message::PressD(shift)),
-                        ch if *ch == *"e".to_smolstr() => Some(Message::PressE(shift)),
-                        ch if *ch == *"f".to_smolstr() => Some(Message::PressF(shift)),
-                        ch if *ch == *"g".to_smolstr() => Some(Message::PressG(shift)),
-                        ch if *ch == *"h".to_smolstr() => Some(Message::PressH(shift)),
-                        ch if *ch == *"i".to_smolstr() => Some(Message::PressI(shift)),
-                        ch if *ch == *"j".to_smolstr() => Some(Message::PressJ(shift)),
-                        ch if *ch == *"k".to_smolstr() => Some(Message::PressK(shift)),
-                        ch if *ch == *"l".to_smolstr() => Some(Message::PressL(shift)),
-                        ch if *ch == *"m".to_smolstr() => Some(Message::PressM(shift)),
-                        ch if *ch == *"n".to_smolstr() => Some(Message::PressN(shift)),
-                        ch if *ch == *"o".to_smolstr() => Some(Message::PressO(shift)),
-                        ch if *ch == *"p".to_smolstr() => Some(Message::PressP(shift)),
-                        ch if *ch == *"q".to_smolstr() => Some(Message::PressQ(shift)),
-                        ch if *ch == *"r".to_smolstr() => Some(Message::PressR(shift)),
-                        ch if *ch == *"s".to_smolstr() => Some(Message::PressS(shift)),
-                        ch if *ch == *"t".to_smolstr() => Some(Message::PressT(shift)),
-                        ch if *ch == *"u".to_smolstr() => Some(Message::PressU(shift)),
-                        // Fixme: ctrl + shift + "v" is paste.
-                        ch if *ch == *"v".to_smolstr() && !shift => Some(Message::PressV(shift)),
-                        ch if *ch == *"w".to_smolstr() => Some(Message::PressW(shift)),
-                        ch if *ch == *"x".to_smolstr() => Some(Message::PressX(shift)),
-                        ch if *ch == *"y".to_smolstr() => Some(Message::PressY(shift)),
-                        ch if *ch == *"z".to_smolstr() => Some(Message::PressZ(shift)),
-                        ch if *ch == *"1".to_smolstr() => Some(Message::Press1),
-                        ch if *ch == *"2".to_smolstr() => Some(Message::Press2),
-                        ch if *ch == *"3".to_smolstr() => Some(Message::Press3),
-                        ch if *ch == *"4".to_smolstr() => Some(Message::Press4),
-                        ch if *ch == *"5".to_smolstr() => Some(Message::Press5),
-                        ch if *ch == *"6".to_smolstr() => Some(Message::Press6),
-                        ch if *ch == *"7".to_smolstr() => Some(Message::Press7),
-                        ch if *ch == *"8".to_smolstr() => Some(Message::Press8),
-                        ch if *ch == *"9".to_smolstr() => Some(Message::Press9),
-                        ch if *ch == *"0".to_smolstr() => Some(Message::Press0),
-                        ch if *ch == *"-".to_smolstr() => Some(Message::PressMinus),
-                        ch if (*ch == *"=".to_smolstr() && shift) || *ch == *"+".to_smolstr() => {
-                            Some(Message::PressPlus)
-                        }
-                        _ => None,
-                    },
-                    Key::Named(name) => match name {
-                        Named::Enter => Some(Message::PressEnter),
-                        Named::F11 => Some(Message::ToggleFullscreenGetWindow),
-                        Named::Tab if shift => Some(Message::FocusPrevious),
-                        Named::Tab => Some(Message::FocusNext),
-                        Named::ArrowUp => Some(Message::ReviewGameBackwardAll),
-                        Named::ArrowLeft => Some(Message::ReviewGameBackward),
-                        Named::ArrowRight if shift => Some(Message::ReviewGameChildNext),
-                        Named::ArrowRight => Some(Message::ReviewGameForward),
-                        Named::ArrowDown => Some(Message::ReviewGameForwardAll),
-                        Named::Escape => Some(Message::Leave),
-                        _ => None,
-                    },
-                    _ => None,
+        let subscription_4 = event::listen_with(|event, status, id| {
+            let message = match event {
+                Event::Window(window::Event::Closed) => Some(Message::WindowClosed(id)),
+                Event::Window(window::Event::Moved(position)) => {
+                    Some(Message::WindowMoved(id, position))
                 }
-            }
-            _ => None,
+                Event::Window(window::Event::Resized(size)) => {
+                    Some(Message::WindowResized(id, (size.width, size.height)))
+                }
+                Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => match key {
+                    Key::Named(Named::Tab) if modifiers.shift() => Some(Message::FocusPrevious),
+                    Key::Named(Named::Tab) => Some(Message::FocusNext),
+                    // A focused text input takes what you type.
+                    key => KeyBinding::new(&key, modifiers)
+                        .filter(|key_binding| {
+                            status == event::Status::Ignored || !key_binding.types()
+                        })
+                        .map(Message::KeyPressed),
+                },
+                _ => None,
+            };
+
+            message.map(|message| (id, message))
+        })
+        .with(self.main_window)
+        .filter_map(|(main_window, (id, message))| {
+            // The keys only do something in the main window.
+            let window_event = matches!(
+                message,
+                Message::WindowClosed(_) | Message::WindowMoved(..) | Message::WindowResized(..)
+            );
+
+            (window_event || main_window == Some(id)).then_some(message)
         });
 
         let subscription_5 = if self.archived_games_button_pressed {
@@ -2322,6 +5070,59 @@ impl<'a> Client {
             )
         });
 
+        // Give the spectators a moment to see how the game ended.
+        let subscription_7 = if self.follow_the_action_pending {
+            iced::time::every(iced::time::Duration::from_secs(5))
+                .map(|_instant| Message::FollowTheActionNext)
+        } else {
+            Subscription::none()
+        };
+
+        let subscription_8 = if self.screen == Screen::Login {
+            Subscription::run(server_status)
+        } else {
+            Subscription::none()
+        };
+
+        let subscription_9 = if self.slow_mode.is_empty() {
+            Subscription::none()
+        } else {
+            iced::time::every(iced::time::Duration::from_secs(1))
+                .map(|_instant| Message::SlowModeTick)
+        };
+
+        let subscription_10 = if self.touch_detected {
+            Subscription::none()
+        } else {
+            event::listen_with(|event, _status, _id| {
+                matches!(event, Event::Touch(touch::Event::FingerPressed { .. }))
+                    .then_some(Message::TouchDetected)
+            })
+        };
+
+        let subscription_11 = if self.challenge().is_some() {
+            iced::time::every(iced::time::Duration::from_millis(500))
+                .map(|_instant| Message::ChallengeFlash)
+        } else {
+            Subscription::none()
+        };
+
+        // Counts down to the tournament.
+        let subscription_12 = if self.announcement_shown().is_some()
+            || (self.active_tab == TabId::Tournament && self.announcement.is_some())
+        {
+            iced::time::every(iced::time::Duration::from_secs(1))
+                .map(|_instant| Message::AnnouncementTick)
+        } else {
+            Subscription::none()
+        };
+
+        let subscription_13 = if self.physical_board {
+            Subscription::run(physical_board)
+        } else {
+            Subscription::none()
+        };
+
         Subscription::batch(vec![
             subscription_1,
             subscription_2,
@@ -2329,40 +5130,121 @@ impl<'a> Client {
             subscription_4,
             subscription_5,
             subscription_6,
+            subscription_7,
+            subscription_8,
+            subscription_9,
+            subscription_10,
+            subscription_11,
+            subscription_12,
+            subscription_13,
         ])
     }
 
     fn texting(
         &self,
         messages: &VecDeque<server_game::Message>,
+        kibitzes: &VecDeque<Kibitz>,
+        channel: Option<Id>,
         enable_texting: bool,
         width: f32,
     ) -> Container<'_, Message> {
         let mut text_box = Column::new().spacing(SPACING).padding(PADDING_SMALL);
         let mut texts = Column::new();
+        let mut kibitzes = kibitzes.iter().rev().peekable();
 
-        for message in messages.iter().rev() {
-            let timestamp = message.timestamp.strftime("%F %T UTC").to_string();
-            let timestamp = text(timestamp).color(GREY);
+        let unread = if channel.is_some() && channel == Some(self.game_id) {
+            self.texts_game_unread.min(messages.len())
+        } else {
+            0
+        };
 
-            let username = text(message.username.clone()).font(Font {
-                weight: Weight::Bold,
-                ..Font::DEFAULT
-            });
+        for (i, message) in messages.iter().rev().enumerate() {
+            if unread > 0 && i == messages.len() - unread {
+                texts = texts.push(text(t!("Unread")).style(text::success));
+            }
 
-            if width < 375.0 {
-                texts = texts.push(column![username, timestamp]);
-            } else {
-                texts = texts.push(row![username, timestamp].spacing(SPACING));
+            while let Some(kibitz) = kibitzes.next_if(|kibitz| kibitz.timestamp < message.timestamp)
+            {
+                texts = texts.push(self.user_menu(
+                    &kibitz.username,
+                    text_header(&kibitz.username, kibitz.timestamp, width),
+                ));
+                texts = texts.push(kibitz_bar(kibitz));
             }
 
+            texts = texts.push(self.user_menu(
+                &message.username,
+                text_header(&message.username, message.timestamp, width),
+            ));
             texts = texts.push(text(message.content.clone()));
         }
 
+        for kibitz in kibitzes {
+            texts = texts.push(self.user_menu(
+                &kibitz.username,
+                text_header(&kibitz.username, kibitz.timestamp, width),
+            ));
+            texts = texts.push(kibitz_bar(kibitz));
+        }
+
         text_box = text_box.push(texts);
 
-        let text_input = if enable_texting {
+        let slow_mode = self
+            .slow_mode
+            .get(&channel)
+            .map(|until| until.duration_since(Timestamp::now()).as_secs() + 1)
+            .filter(|seconds| *seconds > 0);
+
+        if self.kid_safe {
+            let mut phrases = Row::new().spacing(SPACING);
+            for phrase in KID_SAFE_PHRASES {
+                let mut phrase_button = button(text(t!(phrase)));
+                if enable_texting && slow_mode.is_none() {
+                    phrase_button = phrase_button.on_press(Message::TextPhrase(phrase));
+                }
+
+                phrases = phrases.push(phrase_button);
+            }
+
+            text_box = text_box.push(phrases.wrap());
+
+            return container(scrollable(text_box).anchor_bottom())
+                .padding(PADDING)
+                .style(container::bordered_box);
+        }
+
+        let text_input = if let Some(seconds) = slow_mode {
+            iced::widget::text_input(&format!("{}: {seconds} s", t!("Slow Mode")), "")
+        } else if enable_texting {
+            // Admins get the accounts instead of the users.
+            let usernames: Vec<_> = if self.admin {
+                self.accounts
+                    .0
+                    .iter()
+                    .filter(|(_, account)| account.logged_in.is_some())
+                    .map(|(username, _)| username)
+                    .collect()
+            } else {
+                self.users
+                    .0
+                    .values()
+                    .filter(|user| user.logged_in)
+                    .map(|user| &user.username)
+                    .collect()
+            };
+
+            let mut completions = Row::new().spacing(SPACING);
+            for username in chat_input::completions(&self.text_input, usernames.into_iter()) {
+                completions = completions.push(
+                    button(text(format!("@{username}")))
+                        .on_press(Message::TextComplete(username.to_string())),
+                );
+            }
+
+            text_box = text_box.push(completions.wrap());
+
             iced::widget::text_input(&format!("{}…", t!("message")), &self.text_input)
+                .id(chat_input::id())
                 .on_input(Message::TextChanged)
                 .on_paste(Message::TextChanged)
                 .on_submit(Message::TextSend)
@@ -2421,9 +5303,35 @@ impl<'a> Client {
         self.error = None;
 
         match message {
-            Message::ArchivedGames(mut archived_games) => {
-                archived_games.reverse();
-                self.archived_games = archived_games;
+            Message::Announce(announce) => {
+                self.announce = announce;
+                handle_error(self.save_client_ron());
+            }
+            Message::AnnouncementDismiss => {
+                self.announcement_dismissed = self
+                    .announcement
+                    .as_ref()
+                    .map(|announcement| announcement.date);
+                handle_error(self.save_client_ron());
+            }
+            Message::AnnouncementTick => {}
+            Message::ArchivedGames(archived_games) => {
+                // A full page may have older games before it.
+                if archived_games.len() >= ARCHIVED_GAMES_PAGE
+                    && let Some(oldest) = archived_games.iter().map(|game| game.id).min()
+                    && oldest > 0
+                {
+                    self.send(&format!("archived_games ids 0 {}\n", oldest - 1));
+                }
+
+                // The newer copy of a game wins, the newest game goes first.
+                let games: BTreeMap<_, _> = std::mem::take(&mut self.archived_games)
+                    .into_iter()
+                    .chain(archived_games)
+                    .map(|game| (game.id, game))
+                    .collect();
+
+                self.archived_games = games.into_values().rev().collect();
                 self.archived_games_filtered = None;
                 handle_error(self.save_client_postcard());
             }
@@ -2432,51 +5340,279 @@ impl<'a> Client {
                 self.archived_games_button_pressed = true;
             }
             Message::ArchivedGamesRelease => self.archived_games_button_pressed = false,
-            Message::ArchivedGameSelected(game) => self.archived_game_selected = Some(game),
+            Message::ArchivedGameSelected(game) => {
+                self.tags = game.tags.clone();
+                self.tags_labels = game
+                    .tags
+                    .labels
+                    .iter()
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                self.archived_game_selected = Some(game);
+            }
+            Message::Ascii(ascii) => {
+                self.ascii = ascii;
+                self.chars = Characters::default();
+                if ascii {
+                    self.chars.ascii();
+                }
+                handle_error(self.save_client_ron());
+            }
             Message::CancelGame(id) => self.send(&format!("leave_game {id}\n")),
+            Message::ChallengeAccept => {
+                if let Some(id) = self.challenge() {
+                    return Task::done(Message::GameAccept(id));
+                }
+            }
+            Message::ChallengeDecline => {
+                if let Some(id) = self.challenge() {
+                    return Task::done(Message::GameDecline(id));
+                }
+            }
+            Message::ChallengeFlash => self.challenge_flash = !self.challenge_flash,
+            Message::ChallengeUser(username) => {
+                self.challenge_user = Some(username);
+                self.active_tab = TabId::GameNew;
+            }
+            Message::ClaimDraw => self.send(&format!("claim {} draw\n", self.game_id)),
+            Message::ClaimWin => self.send(&format!("claim {} win\n", self.game_id)),
             Message::ChangeTheme(theme) => self.change_theme(theme),
+            Message::Block(username) => self.send(&format!("block {username}\n")),
+            Message::BugReport => {
+                let report = self.bug_report_gather();
+                self.bug_report = Some(text_editor::Content::with_text(&report));
+            }
+            Message::BugReportCancel => self.bug_report = None,
+            Message::BugReportCopy => {
+                if let Some(report) = &self.bug_report {
+                    return iced::clipboard::write(report.text());
+                }
+            }
+            Message::BugReportEdit(action) => {
+                if let Some(report) = &mut self.bug_report {
+                    report.perform(action);
+                }
+            }
+            Message::BugReportSend => {
+                if let Some(report) = self.bug_report.take() {
+                    match ron::ser::to_string(&report.text()) {
+                        Ok(report) => self.send(&format!("bug_report {report}\n")),
+                        Err(error) => error!("bug_report: {error}"),
+                    }
+                }
+            }
+            Message::ClubClose => self.club_open = None,
+            Message::ClubDelete => {
+                if let Some(name) = self.club_open.take() {
+                    self.send(&format!("club_delete {name}\n"));
+                }
+            }
+            Message::ClubDescriptionChanged(description) => self.club_description = description,
+            Message::ClubDescriptionSave => {
+                if let Some(name) = &self.club_open {
+                    let description = self.club_description.replace('\n', " ");
+                    self.send(&format!("club_description {name} {description}\n"));
+                }
+            }
+            Message::ClubInvite => {
+                if let Some(name) = &self.club_open
+                    && !self.club_invite.trim().is_empty()
+                {
+                    self.send(&format!("club_invite {name} {}\n", self.club_invite.trim()));
+                    self.club_invite.clear();
+                }
+            }
+            Message::ClubInviteChanged(username) => self.club_invite = username,
+            Message::ClubJoin(name) => self.send(&format!("club_join {name}\n")),
+            Message::ClubLeave(name) => {
+                self.send(&format!("club_leave {name}\n"));
+                self.texts_club.remove(&name);
+            }
+            Message::ClubNameChanged(name) => self.club_name = name,
+            Message::ClubNew => {
+                if !self.club_name.trim().is_empty() {
+                    self.send(&format!("club_new {}\n", self.club_name.trim()));
+                    self.club_name.clear();
+                }
+            }
+            Message::ClubOpen(name) => {
+                if let Some(club) = self.clubs.0.get(&name) {
+                    self.club_description.clone_from(&club.description);
+                }
+
+                if self.clubs.is_member(&name, &self.username) {
+                    self.send(&format!("club_texts {name}\n"));
+                }
+
+                self.club_open = Some(name);
+            }
+            Message::ClubRemove(member) => {
+                if let Some(name) = &self.club_open {
+                    self.send(&format!("club_remove {name} {member}\n"));
+                }
+            }
+            Message::Berserk => self.send(&format!("berserk {}\n", self.game_id)),
             Message::BoardSizeSelected(size) => self.game_settings.board_size = size,
+            Message::ConversationClose => self.conversation_open = None,
+            Message::ConversationNew => {
+                let peer = self.conversation_new.trim().to_string();
+
+                if !peer.is_empty() {
+                    self.conversations.entry(peer.clone()).or_default();
+                    self.conversation_new.clear();
+                    return Task::done(Message::ConversationOpen(peer));
+                }
+            }
+            Message::ConversationNewChanged(username) => self.conversation_new = username,
+            Message::ConversationOpen(peer) => {
+                self.dms_unread.remove(&peer);
+                self.conversations.entry(peer.clone()).or_default();
+                self.conversation_open = Some(peer);
+                self.active_tab = TabId::Messages;
+            }
+            Message::ConfirmMoves(confirm_moves) => {
+                self.confirm_moves = confirm_moves;
+                handle_error(self.save_client_ron());
+            }
+            Message::CommandChanged(command) => {
+                self.command = command;
+                self.command_error = None;
+            }
+            Message::CommandSend => self.command_send(),
+            Message::CommunityGameAi => {
+                if let Some(role) = self.game_settings.role_selected {
+                    let board_size: usize = self.game_settings.board_size.into();
+                    self.send(&format!("community_game_new {role} {board_size} ai\n"));
+                }
+            }
+            Message::CommunityGameNew => {
+                if let Some(role) = self.game_settings.role_selected {
+                    let board_size: usize = self.game_settings.board_size.into();
+                    self.send(&format!("community_game_new {role} {board_size}\n"));
+                }
+            }
             Message::ConnectedTo(address) => self.connected_to = address,
             Message::DateCancel => self.tournament_date_show_picker = false,
             Message::DateChoose => self.tournament_date_show_picker = true,
             Message::DateSubmit(date) => {
-                self.send(&format!("tournament_date {date}T00:00:00Z\n"));
+                match local_timestamp(date, &self.tournament_time) {
+                    Ok(timestamp) => self.send(&format!("tournament_date {timestamp}\n")),
+                    Err(error) => error!("DateSubmit: {error}"),
+                }
                 self.tournament_date = date;
                 self.tournament_date_show_picker = false;
             }
+            Message::DailyPuzzle => {
+                if let Some(daily) = &self.daily_puzzle {
+                    let position = daily.position.clone();
+                    let archived_game = position.archived_game(&t!("Attacker"), &t!("Defender"));
+                    self.puzzle_start(&position, &archived_game);
+                    self.daily_puzzle_solving = true;
+                }
+            }
             Message::DeleteAccount => self.delete_account(),
             Message::EmailChanged(email) => self.email_input = email,
             Message::EmailEveryone => {
                 self.screen = Screen::EmailEveryone;
                 self.send("emails_bcc\n");
             }
+            Message::EmailPreference(category, send) => {
+                self.send(&format!("email_preferences {category} {send}\n"));
+            }
             Message::EmailReset => self.reset_email(),
+            Message::EstablishedOnlySelected(established_only) => {
+                self.game_settings.established_only = established_only;
+            }
             Message::EstimateScore => self.estimate_score(),
-            Message::EstimateScoreConnected(tx) => self.estimate_score_tx = Some(tx),
-            Message::EstimateScoreDisplay((node, generate_move)) => {
+            Message::EstimateScoreCancelled => {
+                info!("cancelled the score estimator...");
+
+                self.estimate_score = false;
+                self.estimate_score_progress = None;
+            }
+            Message::EstimateScoreConnected((tx, cancel)) => {
+                self.estimate_score_tx = Some(tx);
+                self.estimate_score_cancel = cancel;
+            }
+            Message::EstimateScoreDisplay((board, generate_move)) => {
                 info!("finish running score estimator...");
 
-                if let Some(handle) = self.archived_game_handle.as_ref()
-                    && handle.boards.here() == node
+                self.estimate_score_display(&board, generate_move.clone());
+                self.eval_cache.insert(board, generate_move);
+            }
+            Message::EstimateScoreProgress((board, progress)) => {
+                if let Some(trainer) = &mut self.trainer {
+                    trainer.engine_set(&board, progress.play);
+                } else if let Some(handle) = self.archived_game_handle.as_ref()
+                    && handle.boards.here_board() == board
                 {
-                    info!("{generate_move}");
-                    debug!("{}", generate_move.heat_map);
-                    self.heat_map = Some(generate_move.heat_map);
+                    self.heat_map = Some(progress.heat_map.clone());
+                    self.estimate_score_progress = Some(progress);
+                }
+            }
+            Message::Exit => {
+                handle_error(self.save_client_ron());
+                return iced::exit();
+            }
+            Message::ProofOfWorkProgress(percent) => {
+                if self.proof_of_work.is_some() {
+                    self.proof_of_work = Some(percent);
                 }
+            }
+            Message::ProofOfWorkSolved(solution) => {
+                self.proof_of_work = None;
 
-                self.estimate_score = false;
+                self.send(&format!("{VERSION_ID} proof_of_work {solution}\n"));
+                self.send(&format!(
+                    "{VERSION_ID} create_account {} {}\n",
+                    self.username, self.password,
+                ));
+            }
+            Message::KibitzAnalyze => {
+                if !self.estimate_score
+                    && let Some(game) = &self.game
+                {
+                    self.estimate_score = true;
+                    self.send_estimate_score(game.clone());
+                }
+            }
+            Message::KibitzShare => {
+                if let Some(kibitz) = &self.kibitz {
+                    let kibitz =
+                        ron::ser::to_string(kibitz).expect("Serializing kibitz should work!");
+                    self.send(&format!("kibitz {} {kibitz}\n", self.game_id));
+                }
             }
-            Message::Exit => return iced::exit(),
+            #[cfg(not(target_os = "redox"))]
+            Message::ExportCalendar => self.export_calendar(),
             #[cfg(not(target_os = "redox"))]
             Message::ExportPGN => self.export_pgn(),
+            #[cfg(not(target_os = "redox"))]
+            Message::ExportPNG => self.export_png(),
             Message::FocusNext => return focus_next(),
             Message::FocusPrevious => return focus_previous(),
+            Message::Follow(username) => {
+                self.follows.insert(username);
+                handle_error(self.save_client_ron());
+            }
+            Message::FollowTheAction(follow) => {
+                self.follow_the_action = follow;
+                self.follow_the_action_pending = false;
+                handle_error(self.save_client_ron());
+            }
+            Message::FollowTheActionNext => self.follow_the_action_next(),
             Message::GameCancel(id) => self.send(&format!("decline_game {id} switch\n")),
             Message::GameAccept(id) => {
+                self.challenge = None;
                 self.game_id = id;
                 self.send(&format!("join_game {id}\n"));
             }
-            Message::GameDecline(id) => self.send(&format!("decline_game {id}\n")),
+            Message::GameDecline(id) => {
+                self.challenge = None;
+                self.send(&format!("decline_game {id}\n"));
+            }
             Message::GameJoin(id) => self.join(id),
             Message::GameWatch(id) => self.watch(id),
             Message::GroupSizeChanged(size) => {
@@ -2497,6 +5633,20 @@ impl<'a> Client {
             Message::HeatMap(_display) => self.heat_map_display = !self.heat_map_display,
             #[cfg(not(target_os = "redox"))]
             Message::ImportPGN => self.import_portable_game_notation(),
+            Message::KeyBindingChange(action) => self.key_binding_pending = Some(action),
+            Message::KeyBindingsReset => {
+                self.key_bindings = KeyBindings::default();
+                self.key_binding_pending = None;
+                handle_error(self.save_client_ron());
+            }
+            Message::KeyPressed(key_binding) => {
+                if self.key_binding_pending.is_none() && key_binding.up_or_down().is_some() {
+                    return operation::is_focused(chat_input::id())
+                        .map(move |focused| Message::TextRecall(key_binding.clone(), focused));
+                }
+
+                return self.key_pressed(key_binding);
+            }
             Message::Leave => {
                 if self.screen == Screen::Login {
                     return iced::exit();
@@ -2508,11 +5658,27 @@ impl<'a> Client {
             Message::LocaleSelected(locale) => {
                 rust_i18n::set_locale(&locale.txt());
                 self.locale = Some(locale);
+                self.settings_sync();
                 handle_error(self.save_client_ron());
+
+                if self.screen != Screen::Login {
+                    self.send(&format!("locale {}\n", locale.txt()));
+                }
             }
+            Message::KidSafeCreate(kid_safe) => self.kid_safe_create = kid_safe,
+            Message::Mentor(mentor) => self.send(&format!("mentor {mentor}\n")),
             Message::MyGamesOnly(_selected) => {
                 self.my_games_only();
             }
+            Message::NoteEdit(action) => {
+                let edit = action.is_edit();
+                self.note.perform(action);
+
+                if edit && let Some((id, opponent)) = &self.note_game {
+                    self.notes.set(*id, opponent, self.note.text());
+                }
+            }
+            Message::NotesSearch(search) => self.notes_search = search,
             Message::NumberOfGames(number) => {
                 self.tournament.number_of_games = NumberOfGames {
                     #[allow(clippy::cast_sign_loss)]
@@ -2528,8 +5694,19 @@ impl<'a> Client {
 
                 self.send(&format!("tournament_number_of_games {ron_string}\n"));
             }
+            Message::Onboarding(step) => {
+                self.onboarding = step;
+                if step.is_none() {
+                    handle_error(self.save_client_ron());
+                }
+            }
+            Message::OpeningFilter(name) => {
+                self.opening_filter = name;
+                self.games_filtered();
+            }
             Message::OpenUrl(string) => open_url(&string),
             Message::GameResume(id) => self.resume(id),
+            Message::GameResumeDismiss => self.forget_game(),
             Message::GameSubmit => {
                 self.game_submit();
                 self.active_tab = TabId::Games;
@@ -2547,14 +5724,29 @@ impl<'a> Client {
             Message::PlayDrawDecision(draw) => {
                 self.send(&format!("draw {} {draw}\n", self.game_id));
             }
+            Message::PlayMoveCancel => {
+                self.play_from = None;
+                self.play_to_pending = None;
+            }
+            Message::PlayMoveConfirm => {
+                if let Some(to) = self.play_to_pending.take() {
+                    self.play_to(to);
+                }
+            }
+            Message::PhysicalBoardMove(from, to) => self.physical_board_move(&from, &to),
             Message::PlayMoveFrom(vertex) => self.play_from = Some(vertex),
-            Message::PlayMoveTo(to) => self.play_to(to),
+            Message::PlayMoveTo(to) => self.play_move_to(to),
             Message::PlayMoveRevert => self.play_from = None,
             Message::PlayResign => self.resign(),
             Message::PressEnter => match self.screen {
+                Screen::Game => {
+                    if let Some(to) = self.play_to_pending.take() {
+                        self.play_to(to);
+                    }
+                }
                 Screen::Games if self.active_tab == TabId::GameNew => self.game_submit(),
                 Screen::Login => self.login(),
-                Screen::EmailEveryone | Screen::Game | Screen::Games | Screen::GameReview => {}
+                Screen::EmailEveryone | Screen::Games | Screen::GameReview => {}
             },
             Message::PressA(shift) => match self.screen {
                 Screen::Game | Screen::GameReview => {
@@ -2750,7 +5942,11 @@ impl<'a> Client {
                 Screen::Games => self.join_game_press(17, shift),
             },
             Message::PressS(shift) => match self.screen {
-                Screen::EmailEveryone | Screen::Game | Screen::GameReview | Screen::Login => {}
+                Screen::EmailEveryone | Screen::Login => {}
+                Screen::Game | Screen::GameReview => {
+                    #[cfg(not(target_os = "redox"))]
+                    self.export_png();
+                }
                 Screen::Games => self.join_game_press(18, shift),
             },
             Message::PressT(shift) => match self.screen {
@@ -2941,7 +6137,7 @@ impl<'a> Client {
                     TabId::GameNew => self.game_settings.role_selected = Some(Role::Defender),
                     TabId::Games | TabId::Users => self.users_sort_by = SortBy::Rating,
                     TabId::Tournament => self.send("leave_tournament\n"),
-                    TabId::Chat => {}
+                    TabId::Chat | TabId::Clubs | TabId::Messages | TabId::Simuls => {}
                 },
                 Screen::Login => open_url("https://discord.gg/h56CAHEBXd"),
                 Screen::Game | Screen::GameReview => {
@@ -2980,31 +6176,103 @@ impl<'a> Client {
             Message::RatingMaximum => {
                 self.rating_max();
                 self.games_filtered();
+                self.settings_sync();
                 handle_error(self.save_client_ron());
             }
             Message::RatingMaximumChanged(rating) => {
                 self.rating_maximum = rating;
                 self.games_filtered();
+                self.settings_sync();
                 handle_error(self.save_client_ron());
             }
             Message::RatingMinimum => {
                 self.rating_min();
                 self.games_filtered();
+                self.settings_sync();
                 handle_error(self.save_client_ron());
             }
             Message::RatingMinimumChanged(rating) => {
                 self.rating_minimum = rating;
                 self.games_filtered();
+                self.settings_sync();
                 handle_error(self.save_client_ron());
             }
+            Message::RatingShownSelected(rating_shown) => self.rating_shown = rating_shown,
             Message::RatedSelected(rated) => self.game_settings.rated = rated.into(),
+            Message::AvatarClear(username) => self.send(&format!("avatar_clear {username}\n")),
+            Message::AvatarRemove => self.send("avatar_remove\n"),
+            #[cfg(not(target_os = "redox"))]
+            Message::AvatarUpload => self.avatar_upload(),
+            Message::ProfileBio(bio) => {
+                if bio.chars().count() <= BIO_LENGTH {
+                    self.profile_bio = bio;
+                }
+            }
+            Message::ProfileClose => self.profile = None,
+            Message::ProfileCountry(country) => {
+                if country.len() <= 2 {
+                    self.profile_country = country.to_ascii_uppercase();
+                }
+            }
+            Message::ProfileRole(role) => self.profile_role = role,
+            Message::ProfileSave => {
+                if !self.kid_safe {
+                    self.send(&format!("profile_set bio {}\n", self.profile_bio.trim()));
+                }
+
+                let country = if self.profile_country.is_empty() {
+                    "-"
+                } else {
+                    &self.profile_country
+                };
+                self.send(&format!("profile_set country {country}\n"));
+
+                let role = match self.profile_role {
+                    Role::Roleless => "none".to_string(),
+                    role => role.to_string(),
+                };
+                self.send(&format!("profile_set role {role}\n"));
+            }
+            Message::ProfileView(username) => {
+                self.send(&format!("profile {username}\n"));
+                self.profile_wanted = Some(username);
+            }
+            Message::Puzzle => self.send("puzzle\n"),
+            Message::QuizToggled(quiz) => self.quiz = quiz,
+            Message::ReportCancel => self.report_user = None,
+            Message::ReportReason(reason) => self.report_reason = reason,
+            Message::ReportSend => {
+                if let Some(username) = self.report_user.take() {
+                    let reason = self.report_reason.trim().to_string();
+                    self.send(&format!("report {username} {reason}\n"));
+                }
+            }
+            Message::ReportUser(username) => {
+                self.report_reason.clear();
+                self.report_user = Some(username);
+            }
             Message::ResetPassword => self.reset_password(),
             Message::ReviewGame => self.review_game(),
+            Message::ReviewGameFlip(flipped) => self.review_flipped = flipped,
             Message::ReviewGameBackward => {
                 if let Some(handle) = &mut self.archived_game_handle {
-                    handle.play = handle.play.saturating_sub(1);
-                    handle.boards.backward();
+                    // Back to the last move to guess, to guess it again.
+                    let to = self
+                        .trainer
+                        .as_ref()
+                        .map_or(handle.play.saturating_sub(1), |trainer| {
+                            trainer.previous(handle.play).unwrap_or_default()
+                        });
+
+                    while handle.play > to {
+                        handle.play -= 1;
+                        handle.boards.backward();
+                    }
+
                     self.reset_markers();
+                    self.estimate_score_cancel();
+                    self.heat_map_cached();
+                    self.trainer_next();
                 }
             }
             Message::ReviewGameBackwardAll => {
@@ -3012,35 +6280,115 @@ impl<'a> Client {
                     handle.play = 0;
                     handle.boards.backward_all();
                     self.reset_markers();
+                    self.estimate_score_cancel();
+                    self.heat_map_cached();
+                    self.trainer_next();
                 }
             }
             Message::ReviewGameChildNext => {
                 if let Some(handle) = &mut self.archived_game_handle {
                     handle.boards.next_child();
                     self.reset_markers();
+                    self.estimate_score_cancel();
+                    self.heat_map_cached();
                 }
             }
             Message::ReviewGameForward => {
                 if let Some(handle) = &mut self.archived_game_handle
                     && handle.boards.has_children()
+                    && self.trainer.is_none()
                 {
                     handle.play += 1;
                     handle.boards.forward();
                     self.reset_markers();
+                    self.estimate_score_cancel();
+                    self.heat_map_cached();
+                }
+            }
+            Message::ReviewGameGuess => self.trainer_start(),
+            Message::ReviewGameWindow => {
+                if let Some(archived_game) = &self.archived_game_selected {
+                    let (id, open) = window::open(window::Settings {
+                        size: iced::Size {
+                            width: REVIEW_WINDOW_WIDTH,
+                            height: REVIEW_WINDOW_HEIGHT,
+                        },
+                        ..window_settings()
+                    });
+
+                    let handle = ArchivedGameHandle::new(archived_game);
+                    self.review_windows.insert(id, ReviewWindow::new(handle));
+                    return open.discard();
+                }
+            }
+            Message::ReviewWindowBackward(id) => {
+                if let Some(review_window) = self.review_windows.get_mut(&id) {
+                    let handle = &mut review_window.handle;
+                    handle.play = handle.play.saturating_sub(1);
+                    handle.boards.backward();
+                }
+            }
+            Message::ReviewWindowBackwardAll(id) => {
+                if let Some(review_window) = self.review_windows.get_mut(&id) {
+                    let handle = &mut review_window.handle;
+                    handle.play = 0;
+                    handle.boards.backward_all();
+                }
+            }
+            Message::ReviewWindowForward(id) => {
+                if let Some(review_window) = self.review_windows.get_mut(&id)
+                    && review_window.handle.boards.has_children()
+                {
+                    let handle = &mut review_window.handle;
+                    handle.play += 1;
+                    handle.boards.forward();
+                }
+            }
+            Message::ReviewWindowForwardAll(id) => {
+                if let Some(review_window) = self.review_windows.get_mut(&id) {
+                    let handle = &mut review_window.handle;
+                    let count = handle.boards.forward_all();
+                    handle.play += count;
                 }
             }
             Message::ReviewGameForwardAll => {
-                if let Some(handle) = &mut self.archived_game_handle {
+                if let Some(handle) = &mut self.archived_game_handle
+                    && self.trainer.is_none()
+                {
                     let count = handle.boards.forward_all();
                     handle.play += count;
                     self.reset_markers();
+                    self.estimate_score_cancel();
+                    self.heat_map_cached();
                 }
             }
             Message::RoleSelected(role) => self.game_settings.role_selected = Some(role),
+            Message::RuleSetSelected(rule_set) => self.game_settings.rule_set = rule_set,
+            Message::RuleSetShownSelected(rule_set_shown) => self.rule_set_shown = rule_set_shown,
+            Message::Scout(username) => self.send(&format!("scout {username}\n")),
+            Message::ScoutClose => self.scout = None,
             Message::ServerShutdown => {
                 self.error_persistent
                     .push(t!("The server was shut down.").to_string());
             }
+            Message::ServerStatus(status) => self.server_status = Some(status),
+            Message::ShowProfanity(show) => self.send(&format!("show_profanity {show}\n")),
+            Message::SimulJoin(id) => self.send(&format!("simul_join {id}\n")),
+            Message::SlowModeTick => {
+                let now = Timestamp::now();
+                self.slow_mode.retain(|_, until| *until > now);
+            }
+            Message::SimulLeave(id) => self.send(&format!("simul_leave {id}\n")),
+            Message::SimulNew => {
+                let new_game = self.new_game();
+                self.send(&format!("simul_new {new_game}\n"));
+            }
+            Message::SimulNext => {
+                if let Some(id) = self.simul_next() {
+                    self.resume(id);
+                }
+            }
+            Message::SimulStart(id) => self.send(&format!("simul_start {id}\n")),
             Message::SetScreenSize(window_id) => {
                 if let Some(window_id) = window_id {
                     let target_mode = if self.is_fullscreen {
@@ -3053,14 +6401,19 @@ impl<'a> Client {
                 }
             }
             Message::SetScreenSizeGetWindow => {
-                return iced::window::latest().map(Message::SetScreenSize);
+                return Task::done(Message::SetScreenSize(self.main_window));
             }
             Message::StreamConnected(tx) => self.tx = Some(tx),
             Message::TcpConnectFailed => {
                 self.error_persistent
                     .push(t!("The TCP connection failed.").to_string());
             }
-            Message::TabSelected(tab) => self.active_tab = tab,
+            Message::TabSelected(tab) => {
+                if tab != TabId::GameNew {
+                    self.challenge_user = None;
+                }
+                self.active_tab = tab;
+            }
             Message::TcpDisconnect => self.connected_tcp = false,
             Message::TournamentBoardSize(board_size) => {
                 if self.admin_tournament {
@@ -3070,6 +6423,45 @@ impl<'a> Client {
                     }
                 }
             }
+            Message::TournamentRated(rated) => {
+                if self.admin_tournament {
+                    self.send(&format!("tournament_rated {}\n", Rated::from(rated)));
+                }
+            }
+            Message::TournamentRequirement(requirement, value) => {
+                if self.admin_tournament {
+                    self.tournament_requirement(requirement, value);
+                }
+            }
+            Message::TournamentArena(minutes) => {
+                if self.admin_tournament {
+                    if minutes > 0.0 {
+                        self.send(&format!("tournament_arena {minutes}\n"));
+                    } else {
+                        self.send("tournament_arena none\n");
+                    }
+                }
+            }
+            Message::TournamentTimeOfDay(time) => self.tournament_time = time,
+            Message::TournamentClub(club) => {
+                if self.admin_tournament {
+                    self.tournament.requirements.club = (!club.is_empty()).then_some(club);
+                    self.tournament_requirements_send();
+                }
+            }
+            Message::TournamentRoundDays(days) => {
+                if self.admin_tournament {
+                    // The server counts whole days.
+                    #[allow(clippy::cast_possible_truncation)]
+                    let days = days as i64;
+
+                    if days > 0 {
+                        self.send(&format!("tournament_round_days {days}\n"));
+                    } else {
+                        self.send("tournament_round_days none\n");
+                    }
+                }
+            }
             Message::TournamentTime(time_settings) => {
                 if self.admin_tournament {
                     let time_settings: TimeSettings = time_settings.into();
@@ -3081,8 +6473,22 @@ impl<'a> Client {
             }
             Message::TournamentJoin => self.send("join_tournament\n"),
             Message::TournamentLeave => self.send("leave_tournament\n"),
+            Message::TournamentWithdraw => self.send("tournament_withdraw\n"),
             Message::TournamentStart => self.send("tournament_start\n"),
+            Message::TextComplete(username) => {
+                self.text_input = chat_input::complete(&self.text_input, &username);
+                self.chat_input.changed();
+                return operation::focus(chat_input::id());
+            }
+            Message::TagFilter(tag) => {
+                self.tag_filter = tag;
+                self.games_filtered();
+            }
+            Message::TagsChanged(tags) => self.tags = tags,
+            Message::TagsLabels(labels) => self.tags_labels = labels,
+            Message::TagsSave => self.tags_save(),
             Message::TextChanged(string) => {
+                self.chat_input.changed();
                 if self.screen == Screen::Login {
                     let string: Vec<_> = string.split_whitespace().collect();
                     if let Some(string) = string.first() {
@@ -3119,14 +6525,78 @@ impl<'a> Client {
                         let text_next = text.next();
                         match text_next {
                             Some(
-                                "archived_games"
-                                | "challenge_requested"
-                                | "change_password"
-                                | "decline_game"
-                                | "email_reset"
-                                | "game"
-                                | "request_draw",
+                                "archived_games" | "bug_report" | "change_password"
+                                | "decline_game" | "game_tags" | "email_reset" | "game"
+                                | "profile_set" | "report" | "request_draw" | "avatar_clear"
+                                | "avatar_remove" | "avatar_set" | "quiz_guess",
                             ) => {}
+                            Some("avatar") => {
+                                if let Some(username) = text.next() {
+                                    let avatar = text
+                                        .next()
+                                        .and_then(|avatar| STANDARD.decode(avatar).ok())
+                                        .map(widget::image::Handle::from_bytes);
+
+                                    self.avatars.insert(username.to_string(), avatar);
+                                }
+                            }
+                            Some("avatar_changed") => {
+                                if let Some(username) = text.next()
+                                    && self.avatars.remove(username).is_some()
+                                {
+                                    self.avatars_request([username.to_string()]);
+                                }
+                            }
+                            Some("claim") => {
+                                if let Some(id) = text.next().and_then(|id| id.parse().ok()) {
+                                    self.claim_at.remove(&id);
+                                }
+                            }
+                            Some("vacation") => {
+                                let until = text.next().and_then(|until| until.parse().ok());
+                                if let Some(days_left) =
+                                    text.next().and_then(|days_left| days_left.parse().ok())
+                                {
+                                    self.vacation_until = until;
+                                    self.vacation_days_left = days_left;
+                                    self.vacation_days.clear();
+                                    self.error_vacation = None;
+                                }
+                            }
+                            Some("opponent_disconnected") => {
+                                if let (Some(Ok(id)), Some(Ok(seconds))) = (
+                                    text.next().map(str::parse),
+                                    text.next().map(str::parse::<i64>),
+                                ) {
+                                    self.claim_at.insert(
+                                        id,
+                                        Timestamp::now().as_millisecond() + seconds * 1_000,
+                                    );
+                                }
+                            }
+                            Some("opponent_reconnected") => {
+                                if let Some(id) = text.next().and_then(|id| id.parse().ok()) {
+                                    self.claim_at.remove(&id);
+                                }
+                            }
+                            Some("challenge_requested") => {
+                                if let Some(id) = text.next().and_then(|id| id.parse().ok())
+                                    && !self.hooks_challenge(id)
+                                {
+                                    self.challenge = Some(id);
+                                    self.challenge_flash = true;
+                                    self.challenge_sound();
+
+                                    if let Some(challenger) = self
+                                        .games_light
+                                        .0
+                                        .get(&id)
+                                        .and_then(|game| game.challenger.0.clone())
+                                    {
+                                        self.send(&format!("scout {challenger}\n"));
+                                    }
+                                }
+                            }
                             Some("initialize_display") => {
                                 self.games_light.0.clear();
                                 self.games_light_vec.clear();
@@ -3181,6 +6651,8 @@ impl<'a> Client {
                                         game.turn = Role::Roleless;
                                     }
 
+                                    self.hooks_game_end();
+
                                     let volume = self.volume.volume();
                                     thread::spawn(move || {
                                         let mut stream =
@@ -3208,6 +6680,13 @@ impl<'a> Client {
                                     });
                                 }
                             }
+                            Some("email_preferences") => {
+                                let text: Vec<_> = text.collect();
+                                if let Ok(email_preferences) = serde_json::from_str(&text.join(" "))
+                                {
+                                    self.email_preferences = email_preferences;
+                                }
+                            }
                             Some("emails_bcc") => {
                                 self.emails_bcc = text.map(ToString::to_string).collect();
                             }
@@ -3216,6 +6695,7 @@ impl<'a> Client {
                                     email.verified = true;
                                 }
                                 self.error_email = None;
+                                self.send("email_preferences\n");
                             }
                             Some("games_updated") => {
                                 let texts: Vec<&str> = text.collect();
@@ -3250,17 +6730,33 @@ impl<'a> Client {
                             }
                             Some("game_over") => {
                                 self.my_turn = false;
+                                self.play_to_pending = None;
                                 if let Some(game) = &mut self.game {
                                     game.turn = Role::Roleless;
                                 }
 
-                                text.next();
+                                let id = text.next().and_then(|id| id.parse::<Id>().ok());
+                                if self.resume_game.as_ref().map(|game| game.id) == id {
+                                    self.forget_game();
+                                }
+
+                                if self.follow_the_action
+                                    && id == Some(self.game_id)
+                                    && self.spectators.contains(&self.username)
+                                {
+                                    self.follow_the_action_pending = true;
+                                }
+
                                 match text.next() {
                                     Some("attacker_wins") => self.status = Status::AttackerWins,
                                     Some("defender_wins") => self.status = Status::DefenderWins,
                                     _ => error!("(1) unexpected text: {}", string.trim()),
                                 }
 
+                                if id == Some(self.game_id) {
+                                    self.hooks_game_end();
+                                }
+
                                 let volume = self.volume.volume();
                                 thread::spawn(move || {
                                     let mut stream = rodio::DeviceSinkBuilder::open_default_sink()?;
@@ -3274,6 +6770,21 @@ impl<'a> Client {
                                     Ok::<(), anyhow::Error>(())
                                 });
                             }
+                            Some("game_analysis") => {
+                                let id = text.next().expect("there should be an id supplied");
+                                let id: Id = id.parse().expect("id should be a valid u128");
+                                let analysis: Vec<&str> = text.collect();
+                                let analysis: Analysis = ron::de::from_str(&analysis.join(" "))
+                                    .expect("Deserialization should work!");
+
+                                if let Some(game) =
+                                    self.archived_games.iter_mut().find(|game| game.id == id)
+                                {
+                                    game.analysis = Some(analysis.clone());
+                                }
+
+                                self.game_analysis = Some((id, analysis));
+                            }
                             Some("game_time") => {
                                 let texts: Vec<&str> = text.collect();
                                 let game_time = texts.join(" ");
@@ -3300,6 +6811,13 @@ impl<'a> Client {
 
                                     attacker_time.milliseconds_left = game_time.attacker_ms_left;
                                     defender_time.milliseconds_left = game_time.defender_ms_left;
+
+                                    self.clock_at = if game_time.timestamp == 0 {
+                                        self.server_clock.now()
+                                    } else {
+                                        game_time.timestamp
+                                    };
+                                    self.clock_run();
                                 }
                             }
                             // = join_game david abby rated fischer 900_000 10
@@ -3308,6 +6826,8 @@ impl<'a> Client {
                                 self.status = Status::Ongoing;
                                 self.captures = HashSet::new();
                                 self.play_from = None;
+                                self.play_to_pending = None;
+                                self.trial = None;
                                 self.play_from_previous = None;
                                 self.play_to_previous = None;
                                 self.texts_game = VecDeque::new();
@@ -3353,6 +6873,7 @@ impl<'a> Client {
 
                                 self.time_attacker = timed;
                                 self.time_defender = timed;
+                                self.clock_at = self.server_clock.now();
 
                                 let texts: Vec<&str> = text.collect();
                                 let texts = texts.join(" ");
@@ -3370,6 +6891,9 @@ impl<'a> Client {
                                 }
 
                                 self.game = Some(game);
+                                self.clock_run();
+                                self.remember_game();
+                                self.note_open(self.game_id, attacker, defender);
                             }
                             Some(
                                 "resume_game_json" | "resume_game_ron" | "watch_game_json"
@@ -3377,8 +6901,16 @@ impl<'a> Client {
                             ) => {
                                 self.screen = Screen::Game;
                                 self.status = Status::Ongoing;
+                                self.my_turn = false;
+                                self.community_votes = None;
+                                self.kibitz = None;
+                                self.kibitzes = VecDeque::new();
+                                self.quiz_guess = None;
+                                self.quiz_results = None;
                                 self.captures = HashSet::new();
                                 self.play_from = None;
+                                self.play_to_pending = None;
+                                self.trial = None;
                                 self.play_from_previous = None;
                                 self.play_to_previous = None;
                                 self.texts_game = VecDeque::new();
@@ -3427,36 +6959,10 @@ impl<'a> Client {
 
                                 self.time_attacker = game.attacker_time;
                                 self.time_defender = game.defender_time;
-
-                                match game.turn {
-                                    Role::Attacker => {
-                                        if let (
-                                            TimeSettings::Timed(time),
-                                            TimeUnix::Time(time_ago),
-                                        ) = (&mut self.time_attacker, &game.time)
-                                        {
-                                            let now = Timestamp::now().as_millisecond();
-                                            time.milliseconds_left -= now - time_ago;
-                                            if time.milliseconds_left < 0 {
-                                                time.milliseconds_left = 0;
-                                            }
-                                        }
-                                    }
-                                    Role::Roleless => {}
-                                    Role::Defender => {
-                                        if let (
-                                            TimeSettings::Timed(time),
-                                            TimeUnix::Time(time_ago),
-                                        ) = (&mut self.time_defender, &game.time)
-                                        {
-                                            let now = Timestamp::now().as_millisecond();
-                                            time.milliseconds_left -= now - time_ago;
-                                            if time.milliseconds_left < 0 {
-                                                time.milliseconds_left = 0;
-                                            }
-                                        }
-                                    }
-                                }
+                                self.clock_at = match game.time {
+                                    TimeUnix::Time(time_ago) => time_ago,
+                                    TimeUnix::UnTimed => self.server_clock.now(),
+                                };
 
                                 if (self.username == attacker && game.turn == Role::Attacker)
                                     || (self.username == defender && game.turn == Role::Defender)
@@ -3465,6 +6971,9 @@ impl<'a> Client {
                                 }
 
                                 self.game = Some(game);
+                                self.clock_run();
+                                self.remember_game();
+                                self.note_open(self.game_id, &attacker, &defender);
                             }
                             Some("join_game_pending") => {
                                 let id = text.next().expect("there should be an id supplied");
@@ -3474,7 +6983,128 @@ impl<'a> Client {
                                 self.challenger = true;
                             }
                             Some("leave_game") => self.game_id = 0,
-                            Some("login") => self.screen = Screen::Games,
+                            Some("login") => {
+                                self.screen = Screen::Games;
+                                // The server only tells a connection about the
+                                // avatars it asked for.
+                                self.avatars.clear();
+                                if self.onboarding == Some(Onboarding::Account) {
+                                    self.onboarding = Some(Onboarding::Tutorial);
+                                }
+                                self.send("show_profanity\n");
+                                self.send("mentor\n");
+                                self.send("kid_safe\n");
+                                self.send("motd\n");
+                                self.send("announcement\n");
+                                self.send("blocks\n");
+                                self.send("texts_unread\n");
+                                self.send("vacation\n");
+                                self.send("daily_puzzle\n");
+                                self.send(&format!("profile {}\n", self.username));
+
+                                self.settings_changed();
+                                self.settings_send();
+
+                                if !self.tags_unsent.is_empty() {
+                                    for (id, tags) in std::mem::take(&mut self.tags_unsent) {
+                                        match ron::ser::to_string(&tags) {
+                                            Ok(tags) => {
+                                                self.send(&format!("game_tags {id} {tags}\n"));
+                                            }
+                                            Err(error) => error!("game_tags: {error}"),
+                                        }
+                                    }
+
+                                    handle_error(self.save_client_ron());
+                                }
+                            }
+                            Some("daily_puzzle") => {
+                                let text: Vec<_> = text.collect();
+                                match ron::from_str(&text.join(" ")) {
+                                    Ok(daily) => self.daily_puzzle = Some(daily),
+                                    Err(error) => error!("daily_puzzle: {error}"),
+                                }
+                            }
+                            Some("daily_puzzle_solve") => {
+                                let text: Vec<_> = text.collect();
+                                if let [_solved, _streak, solution] = text.as_slice()
+                                    && let Some((from, to)) = self.daily_puzzle_try.take()
+                                    && let Some(trainer) = &mut self.trainer
+                                {
+                                    match Plae::from_str_(solution, &trainer.role) {
+                                        Ok(solution) => {
+                                            trainer.solution_set(solution);
+                                            self.trainer_guess(from, to);
+                                        }
+                                        Err(error) => error!("daily_puzzle_solve: {error}"),
+                                    }
+                                }
+
+                                self.send("daily_puzzle\n");
+                            }
+                            Some("settings") => {
+                                let text: Vec<_> = text.collect();
+                                match ron::from_str(&text.join(" ")) {
+                                    Ok(settings) => self.settings_merged(settings),
+                                    Err(error) => error!("settings: {error}"),
+                                }
+                            }
+                            Some("puzzle") => {
+                                let text: Vec<_> = text.collect();
+                                match ron::from_str::<Puzzle>(&text.join(" ")) {
+                                    Ok(puzzle) => {
+                                        let archived_game =
+                                            puzzle.archived_game(&t!("Attacker"), &t!("Defender"));
+                                        self.puzzle_start(&puzzle.position(), &archived_game);
+                                    }
+                                    Err(error) => error!("puzzle: {error}"),
+                                }
+                            }
+                            Some("ratings") => {
+                                let text: Vec<_> = text.collect();
+                                match serde_json::from_str(&text.join(" ")) {
+                                    Ok(ratings) => self.ratings_now = Some(ratings),
+                                    Err(error) => error!("ratings: {error}"),
+                                }
+                            }
+                            Some("scout") => {
+                                let text: Vec<_> = text.collect();
+                                match ron::from_str(&text.join(" ")) {
+                                    Ok(scout) => self.scout = Some(scout),
+                                    Err(error) => error!("scout: {error}"),
+                                }
+                            }
+                            Some("profile") => {
+                                let text: Vec<_> = text.collect();
+                                if let Ok(profile) = ron::from_str::<PlayerProfile>(&text.join(" "))
+                                {
+                                    if profile.username == self.username {
+                                        self.profile_bio.clone_from(&profile.profile.bio);
+                                        self.profile_country =
+                                            profile.profile.country.clone().unwrap_or_default();
+                                        self.profile_role =
+                                            profile.profile.role.unwrap_or(Role::Roleless);
+                                    }
+
+                                    if self.profile_wanted.as_ref() == Some(&profile.username) {
+                                        self.profile_wanted = None;
+                                        self.profile = Some(profile);
+                                        self.active_tab = TabId::Users;
+                                    }
+                                }
+                            }
+                            Some("announcement") => {
+                                self.announcement = text.next().map(|announcement| {
+                                    serde_json::from_str(announcement)
+                                        .expect("This is a valid announcement.")
+                                });
+                            }
+                            Some("motd") => {
+                                let motd: Vec<&str> = text.collect();
+                                let motd = motd.join(" ");
+
+                                self.motd = (!motd.is_empty()).then_some(motd);
+                            }
                             Some("new_game") => {
                                 // = new_game game 15 none david rated fischer 900_000 10
                                 if Some("game") == text.next() {
@@ -3484,11 +7114,171 @@ impl<'a> Client {
 
                                     self.game_id = game_id;
                                     self.challenger = false;
+
+                                    if let Some(username) = self.challenge_user.take() {
+                                        let challenge = t!(
+                                            "I created game %{id} for us. Join it in the lobby!",
+                                            id = game_id
+                                        );
+                                        self.send(&format!("dm {username} {challenge}\n"));
+                                    }
+                                }
+                            }
+                            Some("community_game_new") => {
+                                let id = text.next().expect("there should be an id supplied");
+                                let id = id.parse().expect("id should be a valid u128");
+
+                                // The engine plays for you, so you watch.
+                                if text.next() == Some("ai") {
+                                    self.watch(id);
+                                } else {
+                                    self.resume(id);
+                                }
+                            }
+                            Some("quiz_results") => {
+                                let id = text.next().and_then(|id| id.parse::<Id>().ok());
+                                let results: Vec<&str> = text.collect();
+
+                                if id == Some(self.game_id) {
+                                    self.quiz_results = ron::de::from_str(&results.join(" "))
+                                        .map_err(|error| error!("quiz_results: {error}"))
+                                        .ok();
+                                }
+                            }
+                            Some("community_votes") => {
+                                let id = text.next().expect("there should be an id supplied");
+                                let id: Id = id.parse().expect("id should be a valid u128");
+                                let votes: Vec<&str> = text.collect();
+                                let votes = votes.join(" ");
+
+                                if id == self.game_id {
+                                    self.community_votes = Some(
+                                        ron::de::from_str(&votes)
+                                            .expect("Deserialization should work!"),
+                                    );
+                                }
+                            }
+                            Some("kibitz") => {
+                                let id = text.next().expect("there should be an id supplied");
+                                let id: Id = id.parse().expect("id should be a valid u128");
+                                let kibitz: Vec<&str> = text.collect();
+                                let kibitz = kibitz.join(" ");
+
+                                if id == self.game_id {
+                                    self.kibitzes.push_front(
+                                        ron::de::from_str(&kibitz)
+                                            .expect("Deserialization should work!"),
+                                    );
+                                }
+                            }
+                            Some("simul_new") => self.active_tab = TabId::Simuls,
+                            Some("simul_start") => {
+                                let id = text.next().expect("there should be an id supplied");
+                                let id = id.parse().expect("id should be a valid u64");
+
+                                if self.screen == Screen::Games
+                                    && let Some(simul) = self.simuls.0.get(&id)
+                                    && let Some(game_id) = self.simul_game(simul)
+                                {
+                                    self.resume(game_id);
+                                }
+                            }
+                            Some("kid_safe") => {
+                                let _username = text.next();
+                                if let Some(kid_safe) = text.next() {
+                                    self.kid_safe = handle_error(kid_safe.parse());
+                                }
+                                self.kid_safe_friends = text.map(ToString::to_string).collect();
+                            }
+                            Some("mentor") => {
+                                if let Some(mentor) = text.next() {
+                                    self.mentor = handle_error(mentor.parse());
+                                }
+                            }
+                            Some("show_profanity") => {
+                                if let Some(show) = text.next() {
+                                    self.show_profanity = handle_error(show.parse());
+                                }
+                            }
+                            Some("clubs") => {
+                                let clubs: Vec<&str> = text.collect();
+                                let clubs = clubs.join(" ");
+
+                                self.clubs = ron::de::from_str(&clubs)
+                                    .expect("Deserialization should work!");
+
+                                if let Some(name) = &self.club_open
+                                    && !self.clubs.0.contains_key(name)
+                                {
+                                    self.club_open = None;
+                                }
+                            }
+                            Some("club_text") => {
+                                if let Some(name) = text.next() {
+                                    self.texts_club
+                                        .entry(name.to_string())
+                                        .or_default()
+                                        .push_front(message_collect(text));
+                                }
+                            }
+                            Some("dm") => {
+                                if let Some(peer) = text.next() {
+                                    self.conversations
+                                        .entry(peer.to_string())
+                                        .or_default()
+                                        .push_front(message_collect(text));
+
+                                    if self.active_tab != TabId::Messages
+                                        || self.conversation_open.as_deref() != Some(peer)
+                                    {
+                                        self.dms_unread.insert(peer.to_string());
+                                    }
+                                }
+                            }
+                            Some("blocks") => {
+                                let blocks: Vec<&str> = text.collect();
+                                let blocks = blocks.join(" ");
+
+                                self.blocks = ron::de::from_str(&blocks)
+                                    .expect("Deserialization should work!");
+                            }
+                            Some("club_texts") => {
+                                if let Some(name) = text.next() {
+                                    self.texts_club
+                                        .insert(name.to_string(), messages_collect(text));
+                                }
+                            }
+                            Some("simuls") => {
+                                let simuls: Vec<&str> = text.collect();
+                                let simuls = simuls.join(" ");
+
+                                self.simuls = ron::de::from_str(&simuls)
+                                    .expect("Deserialization should work!");
+                            }
+                            Some("slow_mode") => {
+                                if let (Some(channel), Some(Ok(seconds))) =
+                                    (text.next(), text.next().map(str::parse::<i64>))
+                                {
+                                    let channel = if channel == "text" {
+                                        None
+                                    } else {
+                                        Some(handle_error(channel.parse()))
+                                    };
+
+                                    self.slow_mode.insert(
+                                        channel,
+                                        Timestamp::now() + SignedDuration::from_secs(seconds),
+                                    );
                                 }
                             }
                             Some("ping") => {
                                 let after = Timestamp::now().as_millisecond();
                                 self.now_diff = after - self.now;
+                                self.send("pong\n");
+
+                                if let Some(Ok(server)) = text.next().map(str::parse) {
+                                    self.server_clock.ping(self.now, after, server);
+                                }
                             }
                             Some("text") => {
                                 let message: Vec<_> = text.collect();
@@ -3500,6 +7290,23 @@ impl<'a> Client {
                             }
                             Some("texts") => self.texts = messages_collect(text),
                             Some("text_game") => self.texts_game.push_front(message_collect(text)),
+                            Some("texts_unread") => {
+                                let unread: Vec<&str> = text.collect();
+                                let unread = unread.join(" ");
+
+                                self.texts_unread = ron::de::from_str(&unread)
+                                    .expect("Deserialization should work!");
+                            }
+                            Some("text_tournament") => {
+                                self.texts_tournament.push_front(message_collect(text));
+                            }
+                            Some("texts_tournament") => {
+                                self.texts_tournament = if text.clone().next().is_some() {
+                                    messages_collect(text)
+                                } else {
+                                    VecDeque::new()
+                                };
+                            }
                             Some("tournament_status") => {
                                 if let Some(tournament) = text.next() {
                                     let tournament: TournamentFull =
@@ -3536,6 +7343,15 @@ impl<'a> Client {
                                 for (username, user) in users_updated.updated.0 {
                                     self.users.0.insert(username, user);
                                 }
+
+                                let logged_in: Vec<_> = self
+                                    .users
+                                    .0
+                                    .values()
+                                    .filter(|user| user.logged_in)
+                                    .map(|user| user.username.clone())
+                                    .collect();
+                                self.avatars_request(logged_in);
                             }
                             _ => error!("(2) unexpected text: {}", string.trim()),
                         }
@@ -3557,11 +7373,29 @@ impl<'a> Client {
 
                                         self.error = Some(error);
                                     }
+                                    Some("proof_of_work") => {
+                                        if let (Some(nonce), Some(Ok(bits))) =
+                                            (text.next(), text.next().map(str::parse))
+                                        {
+                                            if bits > proof_of_work::BITS_MAX {
+                                                let error = t!(
+                                                    "The server asks for too much work to create an account."
+                                                );
+                                                self.error = Some(error.to_string());
+                                            } else {
+                                                self.proof_of_work = Some(0);
+
+                                                return Task::stream(proof_of_work_solve(
+                                                    nonce.to_string(),
+                                                    bits,
+                                                ));
+                                            }
+                                        }
+                                    }
                                     Some(message) => self.error = Some(message.to_string()),
                                     None => error!("(6) unexpected text: {}", string.trim()),
                                 }
                             }
-                            // Fixme: translate.
                             Some("email") => {
                                 let text: Vec<_> = text.collect();
                                 let text = text.join(" ");
@@ -3570,9 +7404,70 @@ impl<'a> Client {
                             Some("email_code") => {
                                 self.error_email = Some(t!("invalid email code").to_string());
                             }
+                            Some("join_tournament") => {
+                                let reason = text.next();
+                                let limit = text.next().unwrap_or_default();
+
+                                self.error = Some(match reason {
+                                    Some("club") => t!(
+                                        "You have to be a member of %{club} to join the tournament.",
+                                        club = limit
+                                    )
+                                    .to_string(),
+                                    Some("full") => t!("The tournament is full.").to_string(),
+                                    Some("rated_games") => t!(
+                                        "You need %{count} rated games to join the tournament.",
+                                        count = limit
+                                    )
+                                    .to_string(),
+                                    Some("rating_maximum") => t!(
+                                        "Your rating has to be at most %{rating} to join the tournament.",
+                                        rating = limit
+                                    )
+                                    .to_string(),
+                                    Some("rating_minimum") => t!(
+                                        "Your rating has to be at least %{rating} to join the tournament.",
+                                        rating = limit
+                                    )
+                                    .to_string(),
+                                    _ => t!("You can't join the tournament.").to_string(),
+                                });
+                            }
+                            Some("join_game_pending") => {
+                                let text: Vec<_> = text.collect();
+                                error!("join_game_pending: {}", text.join(" "));
+                                self.game_id = 0;
+                                self.error_join = Some(text.join(" "));
+                            }
+                            Some(
+                                command @ ("bug_report" | "community_game_new" | "community_vote"
+                                | "game_tags" | "kibitz" | "settings" | "simul_join"
+                                | "simul_leave" | "simul_new" | "simul_start"),
+                            ) => {
+                                let text: Vec<_> = text.collect();
+                                error!("{command}: {}", text.join(" "));
+                            }
                             Some("login") => {
                                 let text_next = text.next();
                                 match text_next {
+                                    Some("banned") => {
+                                        let mut error = match text.next() {
+                                            Some("forever") | None => {
+                                                t!("You're banned.").to_string()
+                                            }
+                                            Some(date) => {
+                                                t!("You're banned until %{date}.", date = date)
+                                                    .to_string()
+                                            }
+                                        };
+
+                                        for word in text {
+                                            error.push(' ');
+                                            error.push_str(word);
+                                        }
+
+                                        self.error = Some(error);
+                                    }
                                     Some("multiple_possible_errors") => {
                                         self.error = Some(t!(
                                             "Login password is wrong (try lowercase), account doesn't exist, or you're already logged in."
@@ -3592,6 +7487,32 @@ impl<'a> Client {
                                     None => error!("(5) unexpected text: {}", string.trim()),
                                 }
                             }
+                            Some("avatar_set") => {
+                                let text: Vec<_> = text.collect();
+                                self.error_avatar = Some(text.join(" "));
+                            }
+                            Some("vacation" | "vacation_end") => {
+                                let text: Vec<_> = text.collect();
+                                self.error_vacation = Some(text.join(" "));
+                            }
+                            Some("daily_puzzle") => self.daily_puzzle = None,
+                            Some("puzzle" | "daily_puzzle_solve") => {
+                                let text: Vec<_> = text.collect();
+                                self.error_puzzle = Some(text.join(" "));
+                            }
+                            Some("claim") => {
+                                let text: Vec<_> = text.collect();
+                                if let ["wait", seconds] = text.as_slice()
+                                    && let Ok(seconds) = seconds.parse::<i64>()
+                                {
+                                    self.claim_at.insert(
+                                        self.game_id,
+                                        Timestamp::now().as_millisecond() + seconds * 1_000,
+                                    );
+                                } else {
+                                    error!("claim: {}", text.join(" "));
+                                }
+                            }
                             _ => error!("(3) unexpected text: {}", string.trim()),
                         }
                     }
@@ -3606,12 +7527,14 @@ impl<'a> Client {
                             return Task::none();
                         }
 
-                        // game 0 generate_move attacker
+                        // game 0 generate_move attacker 9e3779b97f4a7c15
                         let text_word = text.next();
                         if text_word == Some("generate_move") {
                             self.request_draw = false;
                             self.my_turn = true;
-                        // game 0 play attacker a3 a4
+                            let _role = text.next();
+                            self.resync(text.next());
+                        // game 0 play attacker a3 a4 9e3779b97f4a7c15
                         } else if text_word == Some("play") {
                             let role = text.next().expect("this should be a role string");
                             let role = Role::from_str(role).expect("this should be a role");
@@ -3623,6 +7546,10 @@ impl<'a> Client {
 
                             let to = text.next().expect("this should be to");
 
+                            if !self.my_turn {
+                                self.trial_reset();
+                            }
+
                             if let (Ok(from), Ok(to)) =
                                 (Vertex::from_str(from), Vertex::from_str(to))
                             {
@@ -3630,7 +7557,9 @@ impl<'a> Client {
                                 self.play_to_previous = Some(to);
                             }
 
+                            self.clock_run();
                             self.handle_play(Some(&role.to_string()), from, to);
+                            self.resync(text.next());
                             let game = self.game.as_ref().expect("you should have a game by now");
 
                             if game.status == Status::Ongoing {
@@ -3668,7 +7597,24 @@ impl<'a> Client {
                     _ => error!("(4) unexpected text: {}", string.trim()),
                 }
             }
+            Message::TextPhrase(phrase) => {
+                self.text_input = phrase.to_string();
+                return Task::done(Message::TextSend);
+            }
             Message::TextSend => {
+                let chatting = match self.screen {
+                    Screen::Game => true,
+                    Screen::Games => matches!(
+                        self.active_tab,
+                        TabId::Chat | TabId::Clubs | TabId::Messages | TabId::Tournament
+                    ),
+                    Screen::EmailEveryone | Screen::GameReview | Screen::Login => false,
+                };
+
+                if chatting {
+                    self.chat_input.sent(&self.text_input);
+                }
+
                 match self.screen {
                     Screen::EmailEveryone => {
                         let email = self.content.text().replace('\n', "\\n");
@@ -3685,6 +7631,22 @@ impl<'a> Client {
                             self.text_input.push('\n');
                             self.send(&format!("text {}", self.text_input));
                         }
+                        TabId::Clubs if !self.text_input.trim().is_empty() => {
+                            if let Some(name) = &self.club_open {
+                                self.text_input.push('\n');
+                                self.send(&format!("club_text {name} {}", self.text_input));
+                            }
+                        }
+                        TabId::Messages if !self.text_input.trim().is_empty() => {
+                            if let Some(peer) = &self.conversation_open {
+                                self.text_input.push('\n');
+                                self.send(&format!("dm {peer} {}", self.text_input));
+                            }
+                        }
+                        TabId::Tournament if !self.text_input.trim().is_empty() => {
+                            self.text_input.push('\n');
+                            self.send(&format!("text_tournament {}", self.text_input));
+                        }
                         TabId::AccountSettings => {
                             self.send(&format!("change_password {}\n", self.password));
                         }
@@ -3695,6 +7657,22 @@ impl<'a> Client {
 
                 self.text_input.clear();
             }
+            Message::TextRecall(key_binding, focused) => {
+                if !focused {
+                    return self.key_pressed(key_binding);
+                }
+
+                let recalled = if key_binding.up_or_down() == Some(true) {
+                    self.chat_input.up(&self.text_input)
+                } else {
+                    self.chat_input.down()
+                };
+
+                if let Some(recalled) = recalled {
+                    self.text_input = recalled;
+                    return operation::move_cursor_to_end(chat_input::id());
+                }
+            }
             Message::TextSendEmail => {
                 self.error_email = None;
 
@@ -3707,6 +7685,10 @@ impl<'a> Client {
             }
             Message::TextSendCreateAccount => self.create_account(),
             Message::TextSendLogin => self.login(),
+            Message::TextSizeSelected(text_size) => {
+                self.text_size = text_size;
+                handle_error(self.save_client_ron());
+            }
             Message::Tick => {
                 self.counter = self.counter.wrapping_add(1);
 
@@ -3716,27 +7698,8 @@ impl<'a> Client {
                     self.send("ping\n");
                 }
 
-                if let Some(game) = &mut self.game {
-                    match game.turn {
-                        Role::Attacker => {
-                            if let TimeSettings::Timed(time) = &mut self.time_attacker {
-                                time.milliseconds_left -= TICK;
-                                if time.milliseconds_left < 0 {
-                                    time.milliseconds_left = 0;
-                                }
-                            }
-                        }
-                        Role::Roleless => {}
-                        Role::Defender => {
-                            if let TimeSettings::Timed(time) = &mut self.time_defender {
-                                time.milliseconds_left -= TICK;
-                                if time.milliseconds_left < 0 {
-                                    time.milliseconds_left = 0;
-                                }
-                            }
-                        }
-                    }
-                }
+                self.clock_run();
+                self.announce_clock();
             }
             Message::ToggleFullscreen(window_id) => {
                 if let Some(window_id) = window_id {
@@ -3753,15 +7716,70 @@ impl<'a> Client {
                 }
             }
             Message::ToggleFullscreenGetWindow => {
-                return iced::window::latest().map(Message::ToggleFullscreen);
+                return Task::done(Message::ToggleFullscreen(self.main_window));
             }
             Message::Time(time) => self.game_settings.time = Some(time),
             Message::Tournaments => open_url("https://hnefatafl.org/tournaments.html"),
             Message::TournamentDelete => self.send("tournament_delete\n"),
             Message::TournamentTreeDelete => self.send("tournament_groups_delete\n"),
+            Message::TouchDetected => self.touch_detected = true,
+            Message::TrialReset => self.trial_reset(),
+            Message::Tutorial => self.tutorial_start(),
+            Message::Unblock(username) => self.send(&format!("unblock {username}\n")),
+            Message::Unfollow(username) => {
+                self.follows.remove(&username);
+                handle_error(self.save_client_ron());
+            }
+            Message::UiScaleSelected(ui_scale) => {
+                self.ui_scale = ui_scale;
+                handle_error(self.save_client_ron());
+            }
             Message::UsersSortedBy(sort_by) => self.users_sort_by = sort_by,
+            Message::VacationDays(days) => {
+                if days.chars().all(|c| c.is_ascii_digit()) {
+                    self.vacation_days = days;
+                }
+            }
+            Message::VacationEnd => self.send("vacation_end\n"),
+            Message::VacationStart => {
+                if !self.vacation_days.is_empty() {
+                    self.send(&format!("vacation {}\n", self.vacation_days));
+                }
+            }
             Message::VolumeChanged(volume) => self.volume.0 = volume,
-            Message::WindowResized((width, height)) => {
+            Message::WindowClosed(id) => {
+                if self.main_window == Some(id) {
+                    self.settings_sync();
+                    handle_error(self.save_client_ron());
+                    return iced::exit();
+                }
+
+                self.review_windows.remove(&id);
+            }
+            Message::WindowMoved(id, position) => {
+                if self.main_window == Some(id)
+                    && !self.is_fullscreen
+                    && let Some(geometry) = &mut self.window_geometry
+                {
+                    geometry.x = Some(position.x);
+                    geometry.y = Some(position.y);
+                }
+            }
+            Message::WindowResized(id, (width, height)) => {
+                if self.main_window != Some(id) {
+                    if let Some(review_window) = self.review_windows.get_mut(&id) {
+                        review_window.height = height;
+                    }
+
+                    return Task::none();
+                }
+
+                if !self.is_fullscreen {
+                    let geometry = self.window_geometry.get_or_insert_default();
+                    geometry.width = width;
+                    geometry.height = height;
+                }
+
                 self.window_width = width;
                 self.window_height = height;
                 self.window_wide = width < 2.0 * height;
@@ -3780,6 +7798,7 @@ impl<'a> Client {
                     self.screen_size = Size::Tiny;
                 }
             }
+            Message::ZenSelected(zen) => self.game_settings.zen = zen,
         }
 
         Task::none()
@@ -3792,10 +7811,13 @@ impl<'a> Client {
         match self.users_sort_by {
             SortBy::Name => {
                 accounts.sort_by(|(_, a_account), (_, b_account)| {
-                    b_account
+                    self.rating_shown(&b_account.rating, &b_account.ratings)
                         .rating
-                        .rating
-                        .partial_cmp(&a_account.rating.rating)
+                        .partial_cmp(
+                            &self
+                                .rating_shown(&a_account.rating, &a_account.ratings)
+                                .rating,
+                        )
                         .expect("The number should be comparable.")
                 });
                 accounts.sort_by(|(a_name, _), (b_name, _)| a_name.cmp(b_name));
@@ -3803,10 +7825,13 @@ impl<'a> Client {
             SortBy::Rating => {
                 accounts.sort_by(|(a_name, _), (b_name, _)| a_name.cmp(b_name));
                 accounts.sort_by(|(_, a_account), (_, b_account)| {
-                    b_account
+                    self.rating_shown(&b_account.rating, &b_account.ratings)
                         .rating
-                        .rating
-                        .partial_cmp(&a_account.rating.rating)
+                        .partial_cmp(
+                            &self
+                                .rating_shown(&a_account.rating, &a_account.ratings)
+                                .rating,
+                        )
                         .expect("The number should be comparable.")
                 });
             }
@@ -3815,6 +7840,44 @@ impl<'a> Client {
         accounts
     }
 
+    #[must_use]
+    fn rating_shown(&self, rating: &Rating, ratings: &Ratings) -> Rating {
+        match &self.rating_shown {
+            RatingShown::Category(category) => ratings.get(category),
+            RatingShown::Overall => rating.clone(),
+        }
+    }
+
+    #[must_use]
+    fn rating_shown_pick_list(&self) -> Row<'_, Message> {
+        let mut ratings_shown = vec![RatingShown::Overall];
+        for rule_set in RuleSet::ALL {
+            for board_size in [BoardSize::_11, BoardSize::_13] {
+                for speed in [
+                    Speed::Blitz,
+                    Speed::Rapid,
+                    Speed::Classical,
+                    Speed::Correspondence,
+                ] {
+                    ratings_shown.push(RatingShown::Category(RatingCategory {
+                        rule_set,
+                        board_size,
+                        speed,
+                    }));
+                }
+            }
+        }
+
+        row![
+            text!("{}: ", t!("rating")),
+            pick_list(
+                ratings_shown,
+                Some(self.rating_shown),
+                Message::RatingShownSelected
+            ),
+        ]
+    }
+
     #[must_use]
     fn users_sorted(&self) -> Vec<User> {
         let mut users: Vec<_> = self.users.0.values().cloned().collect();
@@ -3822,9 +7885,9 @@ impl<'a> Client {
         match self.users_sort_by {
             SortBy::Name => {
                 users.sort_by(|a, b| {
-                    b.rating
+                    self.rating_shown(&b.rating, &b.ratings)
                         .rating
-                        .partial_cmp(&a.rating.rating)
+                        .partial_cmp(&self.rating_shown(&a.rating, &a.ratings).rating)
                         .expect("The number should be comparable.")
                 });
                 users.sort_by(|a, b| a.username.cmp(&b.username));
@@ -3832,9 +7895,9 @@ impl<'a> Client {
             SortBy::Rating => {
                 users.sort_by(|a, b| a.username.cmp(&b.username));
                 users.sort_by(|a, b| {
-                    b.rating
+                    self.rating_shown(&b.rating, &b.ratings)
                         .rating
-                        .partial_cmp(&a.rating.rating)
+                        .partial_cmp(&self.rating_shown(&a.rating, &a.ratings).rating)
                         .expect("The number should be comparable.")
                 });
             }
@@ -3855,6 +7918,10 @@ impl<'a> Client {
         let mut buttons = Column::new().spacing(SPACING);
 
         for (i, game) in self.games_light_vec.iter().enumerate() {
+            if !self.rule_set_shown.shows(game.rule_set) {
+                continue;
+            }
+
             if self.my_games_only {
                 let mut includes_username = false;
                 if let Some(attacker) = &game.attacker
@@ -3875,62 +7942,113 @@ impl<'a> Client {
             }
 
             let id = game.id;
-            game_ids = game_ids.push(text(id));
+            let challenge = self.challenge() == Some(id);
+            let style: fn(&iced::Theme) -> text::Style = if challenge && self.challenge_flash {
+                text::warning
+            } else {
+                text::default
+            };
+
+            game_ids = game_ids.push(self.game_menu(game, text(id).style(style)));
 
-            attackers = if let Some(attacker_str) = &game.attacker {
+            let category = RatingCategory::new(game.rule_set, game.board_size, &game.timed);
+
+            attackers = if let Some(attacker_str) = &game.attacker
+                && game.zen
+                && attacker_str != &self.username
+            {
+                attackers.push(text(t!("Anonymous")).style(style))
+            } else if let Some(attacker_str) = &game.attacker {
                 let mut attacker = if self.admin {
                     if let Some(account) = self.accounts.0.get(attacker_str) {
-                        text!("{attacker_str} ({})", account.rating.to_string_rounded(),)
+                        text!(
+                            "{} ({})",
+                            self.username_shown(attacker_str),
+                            account.ratings.get(&category).to_string_rounded(),
+                        )
                     } else {
-                        text(attacker_str)
+                        text(self.username_shown(attacker_str))
                     }
                 } else {
                     if let Some(user) = self.users.0.get(attacker_str) {
-                        text!("{attacker_str} ({})", user.rating.to_string_rounded())
+                        text!(
+                            "{} ({})",
+                            self.username_shown(attacker_str),
+                            user.ratings.get(&category).to_string_rounded()
+                        )
                     } else {
-                        text(attacker_str)
+                        text(self.username_shown(attacker_str))
                     }
                 };
 
                 if game.challenge_accepted && game.turn == Role::Attacker {
                     attacker = attacker.style(text::success);
+                } else {
+                    attacker = attacker.style(style);
                 }
 
-                attackers.push(attacker)
+                attackers.push(self.user_menu(attacker_str, attacker))
             } else {
                 attackers.push(text(""))
             };
 
-            defenders = if let Some(defender_str) = &game.defender {
+            defenders = if let Some(defender_str) = &game.defender
+                && game.zen
+                && defender_str != &self.username
+            {
+                defenders.push(text(t!("Anonymous")).style(style))
+            } else if let Some(defender_str) = &game.defender {
                 let mut defender = if self.admin {
                     if let Some(account) = self.accounts.0.get(defender_str) {
-                        text!("{defender_str} ({})", account.rating.to_string_rounded(),)
+                        text!(
+                            "{} ({})",
+                            self.username_shown(defender_str),
+                            account.ratings.get(&category).to_string_rounded(),
+                        )
                     } else {
-                        text(defender_str)
+                        text(self.username_shown(defender_str))
                     }
                 } else {
                     if let Some(user) = self.users.0.get(defender_str) {
-                        text!("{defender_str} ({})", user.rating.to_string_rounded())
+                        text!(
+                            "{} ({})",
+                            self.username_shown(defender_str),
+                            user.ratings.get(&category).to_string_rounded()
+                        )
                     } else {
-                        text(defender_str)
+                        text(self.username_shown(defender_str))
                     }
                 };
 
                 if game.challenge_accepted && game.turn == Role::Defender {
                     defender = defender.style(text::success);
+                } else {
+                    defender = defender.style(style);
                 }
 
-                defenders.push(defender)
+                defenders.push(self.user_menu(defender_str, defender))
             } else {
                 defenders.push(text(""))
             };
 
             let rating: bool = game.rated.into();
             let rating = if rating { t!("yes") } else { t!("no") };
-            ratings = ratings.push(text(rating));
+            if let Some([win, draw, loss]) = self.rating_changes(game, category) {
+                ratings = ratings.push(tooltip(
+                    text!("{rating} ({win:+} / {draw:+} / {loss:+})").style(style),
+                    container(text(t!(
+                        "How much your rating changes after a win, a draw, or a loss."
+                    )))
+                    .style(container::bordered_box)
+                    .padding(PADDING),
+                    tooltip::Position::Bottom,
+                ));
+            } else {
+                ratings = ratings.push(text(rating).style(style));
+            }
 
-            timings = timings.push(text(game.timed.to_string()));
-            sizes = sizes.push(text(game.board_size.to_string()));
+            timings = timings.push(text(game.timed.to_string()).style(style));
+            sizes = sizes.push(text(game.board_size.to_string()).style(style));
 
             let mut buttons_row = Row::new().spacing(SPACING);
 
@@ -3955,6 +8073,11 @@ impl<'a> Client {
                     buttons_row = buttons_row.push(
                         button(text!("{}{i}", t!("Resume"))).on_press(Message::GameResume(id)),
                     );
+
+                    if let Some(unread) = self.texts_unread.get(&id) {
+                        buttons_row = buttons_row
+                            .push(text!("{}: {unread}", t!("unread")).style(text::success));
+                    }
                 }
                 JoinGame::Watch => {
                     buttons_row = buttons_row
@@ -3965,13 +8088,30 @@ impl<'a> Client {
             match self.game_state(id) {
                 State::Challenger | State::Spectator => {}
                 State::Creator => {
-                    buttons_row = buttons_row.push(
-                        button(text!("{}{i}", t!("Accept"))).on_press(Message::GameAccept(id)),
-                    );
-                    buttons_row = buttons_row.push(
-                        button(text!("{}{}", t!("Decline"), i.to_ascii_uppercase()))
-                            .on_press(Message::GameDecline(id)),
-                    );
+                    if let Some(challenger) = &game.challenger.0 {
+                        buttons_row = buttons_row.push(
+                            button(text(t!("Scout"))).on_press(Message::Scout(challenger.clone())),
+                        );
+                    }
+
+                    let accept =
+                        button(text!("{}{i}", t!("Accept"))).on_press(Message::GameAccept(id));
+                    let decline = button(text!("{}{}", t!("Decline"), i.to_ascii_uppercase()))
+                        .on_press(Message::GameDecline(id));
+
+                    if challenge {
+                        let accept = if self.challenge_flash {
+                            accept.style(button::warning)
+                        } else {
+                            accept
+                        };
+
+                        buttons_row = buttons_row
+                            .push(self.key_tooltip(accept, Action::AcceptChallenge))
+                            .push(self.key_tooltip(decline, Action::DeclineChallenge));
+                    } else {
+                        buttons_row = buttons_row.push(accept).push(decline);
+                    }
                 }
                 State::CreatorOnly => {
                     buttons_row = buttons_row.push(
@@ -4034,6 +8174,7 @@ impl<'a> Client {
         })
     }
 
+    #[allow(clippy::too_many_lines)]
     fn games_view(&self) -> Column<'_, Message> {
         let username = row![text!("{}: {}", t!("username"), &self.username)].spacing(SPACING);
 
@@ -4049,24 +8190,676 @@ impl<'a> Client {
             get_archived_games = get_archived_games.on_press(Message::ArchivedGamesPress);
         }
 
-        let quit = button(text!("{} (Esc)", t!("Quit"))).on_press(Message::Leave);
+        let quit = button(text!(
+            "{} ({})",
+            t!("Quit"),
+            self.key_bindings.get(Action::Leave)
+        ))
+        .on_press(Message::Leave);
 
-        let mut middle = row![get_archived_games, quit].spacing(SPACING);
+        let puzzle = button(text(t!("Puzzle"))).on_press(Message::Puzzle);
+        let mut middle = row![get_archived_games, puzzle, quit].spacing(SPACING);
 
         if self.admin {
             middle = middle.push(button("Email Everyone").on_press(Message::EmailEveryone));
         }
 
-        let username = row![username, my_games, my_games_text].spacing(SPACING);
+        let follow_the_action_text = text(t!("Follow the Action")).center();
+        let follow_the_action =
+            checkbox(self.follow_the_action).on_toggle(Message::FollowTheAction);
+
+        let rule_set_shown = pick_list(
+            RuleSetShown::all(),
+            Some(self.rule_set_shown),
+            Message::RuleSetShownSelected,
+        );
+
+        let username = row![
+            username,
+            my_games,
+            my_games_text,
+            follow_the_action,
+            follow_the_action_text,
+            rule_set_shown
+        ]
+        .spacing(SPACING);
         let user_area = self.user_area();
 
-        column![middle, username, user_area]
+        let mut column = column![middle, username].spacing(SPACING).padding(PADDING);
+
+        if let Some(error) = &self.error_puzzle {
+            column = column.push(text(error).style(text::danger));
+        }
+
+        if let Some(error) = &self.error_join {
+            column = column.push(text(error).style(text::danger));
+        }
+
+        if let Some(daily) = &self.daily_puzzle {
+            column = column.push(Self::daily_puzzle_view(daily));
+        }
+
+        if let Some(motd) = &self.motd {
+            column = column.push(LabeledFrame::new(
+                text(t!("Message of the Day")),
+                text(motd).style(text::primary),
+            ));
+        }
+
+        if let Some(announcement) = self.announcement_shown() {
+            let mut banner = row![
+                text!(
+                    "[{}] {}: {}, {}, {}: {}, {}",
+                    local_time(announcement.date),
+                    t!(
+                        "Tournament in %{time}",
+                        time = time_until(announcement.date)
+                    ),
+                    announcement.board_size,
+                    announcement.time_setting,
+                    t!("rated"),
+                    if announcement.rated.into() {
+                        t!("yes")
+                    } else {
+                        t!("no")
+                    },
+                    t!("%{count} joined", count = announcement.players),
+                )
+                .style(text::primary)
+            ]
             .spacing(SPACING)
-            .padding(PADDING)
+            .align_y(Vertical::Center);
+
+            if !self.tournament.players.contains(&self.username) {
+                banner = banner
+                    .push(button(text(t!("Join Tournament"))).on_press(Message::TournamentJoin));
+            }
+
+            column = column.push(
+                banner
+                    .push(Self::export_calendar_button())
+                    .push(button(text(t!("Dismiss"))).on_press(Message::AnnouncementDismiss)),
+            );
+        }
+
+        if let Some(scout) = &self.scout {
+            column = column.push(Self::scout_view(scout));
+        }
+
+        if let Some(username) = &self.report_user {
+            column = column.push(
+                row![
+                    text(t!(
+                        "Report %{username} to the moderators:",
+                        username = username
+                    )),
+                    widget::text_input(&t!("reason"), &self.report_reason)
+                        .on_input(Message::ReportReason)
+                        .on_submit(Message::ReportSend)
+                        .width(300),
+                    button(text(t!("Report"))).on_press(Message::ReportSend),
+                    button(text(t!("Cancel"))).on_press(Message::ReportCancel),
+                ]
+                .spacing(SPACING)
+                .align_y(Vertical::Center),
+            );
+        }
+
+        if let Some(resume_game) = &self.resume_game
+            && self
+                .games_light
+                .0
+                .get(&resume_game.id)
+                .is_some_and(|game| !game.game_over)
+        {
+            column = column.push(
+                row![
+                    text!("{} {}?", t!("Resume your game vs"), resume_game.opponent),
+                    button(text(t!("Resume"))).on_press(Message::GameResume(resume_game.id)),
+                    button(text(t!("Dismiss"))).on_press(Message::GameResumeDismiss),
+                ]
+                .spacing(SPACING)
+                .align_y(Vertical::Center),
+            );
+        }
+
+        column.push(user_area)
+    }
+
+    /// What you can do with a player, on a right click on their name.
+    fn user_menu<'a>(
+        &self,
+        username: &str,
+        underlay: impl Into<Element<'a, Message>>,
+    ) -> Element<'a, Message> {
+        if username == self.username || username.is_empty() {
+            return underlay.into();
+        }
+
+        let username = username.to_string();
+        let admin = self.admin;
+        let blocked = self.blocks.contains(&username);
+        let followed = self.follows.contains(&username);
+        let playing = self
+            .games_light
+            .0
+            .values()
+            .find(|game| {
+                game.challenge_accepted
+                    && !game.game_over
+                    && (game.attacker.as_ref() == Some(&username)
+                        || game.defender.as_ref() == Some(&username))
+            })
+            .map(|game| game.id);
+
+        ContextMenu::new(underlay, move || {
+            let mut menu = column![
+                text(username.clone()).font(Font {
+                    weight: Weight::Bold,
+                    ..Font::DEFAULT
+                }),
+                menu_item(&t!("Challenge"), Message::ChallengeUser(username.clone())),
+                menu_item(&t!("Message"), Message::ConversationOpen(username.clone())),
+                menu_item(&t!("View Profile"), Message::ProfileView(username.clone())),
+            ];
+
+            if let Some(id) = playing {
+                menu = menu.push(menu_item(&t!("Watch"), Message::GameWatch(id)));
+            }
+
+            menu = menu.push(if followed {
+                menu_item(&t!("Unfollow"), Message::Unfollow(username.clone()))
+            } else {
+                menu_item(&t!("Follow"), Message::Follow(username.clone()))
+            });
+
+            menu = menu.push(if blocked {
+                menu_item(&t!("Unblock"), Message::Unblock(username.clone()))
+            } else {
+                menu_item(&t!("Block"), Message::Block(username.clone()))
+            });
+
+            menu = menu.push(menu_item(
+                &t!("Report"),
+                Message::ReportUser(username.clone()),
+            ));
+
+            if admin {
+                menu = menu.push(menu_item(
+                    &t!("Remove Avatar"),
+                    Message::AvatarClear(username.clone()),
+                ));
+            }
+
+            container(menu.width(160))
+                .style(container::bordered_box)
+                .padding(PADDING_SMALL)
+                .into()
+        })
+        .into()
+    }
+
+    /// A player's flag, bio, record, rating graph, and latest games.
+    fn profile_view<'a>(&'a self, profile: &'a PlayerProfile) -> Scrollable<'a, Message> {
+        let mut name = profile.username.clone();
+        if let Some(flag) = profile.profile.flag() {
+            name = format!("{flag} {name}");
+        }
+
+        let side = match profile.profile.role {
+            Some(Role::Attacker) => t!("attacker"),
+            Some(Role::Defender) => t!("defender"),
+            Some(Role::Roleless) | None => t!("either side"),
+        };
+
+        let mut column = column![
+            button(text(t!("Back"))).on_press(Message::ProfileClose),
+            text(name).size(20).font(Font {
+                weight: Weight::Bold,
+                ..Font::DEFAULT
+            }),
+            text(profile.profile.bio.clone()),
+            text!("{}: {side}", t!("Prefers to play")),
+            text!(
+                "{}: {}, {}: {}, {}: {}, {}: {}",
+                t!("rating"),
+                profile.rating.to_string_rounded(),
+                t!("wins"),
+                profile.wins,
+                t!("losses"),
+                profile.losses,
+                t!("draws"),
+                profile.draws,
+            ),
+        ]
+        .padding(PADDING)
+        .spacing(SPACING);
+
+        if profile.ratings.len() > 1 {
+            column = column.push(
+                canvas(RatingGraph {
+                    ratings: profile.ratings.clone(),
+                })
+                .width(Length::Fill)
+                .height(160),
+            );
+        }
+
+        let opponent = (profile.username != self.username).then_some(profile.username.as_str());
+        column = column.push(self.notes_view(opponent));
+
+        column = column.push(text(t!("Recent Games")));
+        for game in &profile.games {
+            column = column.push(text!(
+                "{}: {} ({}), {}, {}, {}",
+                game.id,
+                game.opponent,
+                t!(game.role.to_string()),
+                game.status,
+                game.rated,
+                game.board_size,
+            ));
+        }
+
+        let mut row = Row::new().spacing(SPACING);
+        if profile.username != self.username {
+            row = row.push(
+                button(text(t!("Challenge")))
+                    .on_press(Message::ChallengeUser(profile.username.clone())),
+            );
+            row = row.push(
+                button(text(t!("Message")))
+                    .on_press(Message::ConversationOpen(profile.username.clone())),
+            );
+        }
+        column = column.push(row);
+
+        scrollable(column).spacing(SPACING)
+    }
+
+    fn daily_puzzle_view(daily: &Daily) -> Element<'_, Message> {
+        let mut solve = button(text(t!("Solve")));
+        if !daily.tried {
+            solve = solve.on_press(Message::DailyPuzzle);
+        }
+
+        let mut column = column![
+            row![text(daily.position.theme.to_string()), solve]
+                .spacing(SPACING)
+                .align_y(Vertical::Center),
+            text(t!("Your streak: %{days} days", days = daily.streak)),
+        ]
+        .spacing(SPACING);
+
+        if let Some(Plae::Play(play)) = &daily.solution {
+            let solution = format!("{}-{}", play.from, play.to);
+            column = column.push(text(t!("The solution was %{play}.", play = solution)));
+        }
+
+        if !daily.solvers.is_empty() {
+            column = column.push(text!(
+                "{}: {}",
+                t!("Solved First"),
+                daily
+                    .solvers
+                    .iter()
+                    .take(puzzle::STREAKS)
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        if !daily.streaks.is_empty() {
+            let streaks: Vec<_> = daily
+                .streaks
+                .iter()
+                .map(|(username, days)| format!("{username} ({days})"))
+                .collect();
+
+            column = column.push(text!("{}: {}", t!("Longest Streaks"), streaks.join(", ")));
+        }
+
+        LabeledFrame::new(text(t!("Daily Puzzle")), column).into()
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn scout_view(scout: &Scout) -> Element<'_, Message> {
+        let side = match scout.prefers {
+            Some(Role::Attacker) => t!("attacker"),
+            Some(Role::Defender) => t!("defender"),
+            Some(Role::Roleless) | None => t!("either side"),
+        };
+
+        let mut column = column![
+            text!(
+                "{}: {} ({})",
+                t!("rating"),
+                scout.rating.to_string_rounded(),
+                t!(
+                    "%{change} over %{games} games",
+                    change = format!("{:+.0}", scout.rating_trend()),
+                    games = scout.ratings.len() - 1
+                ),
+            ),
+            text!("{}: {side}", t!("Prefers to play")),
+            text(t!(
+                "As the attacker: %{wins} wins in %{games} games",
+                wins = scout.attacker.wins,
+                games = scout.attacker.games
+            )),
+            text(t!(
+                "As the defender: %{wins} wins in %{games} games",
+                wins = scout.defender.wins,
+                games = scout.defender.games
+            )),
+        ]
+        .padding(PADDING)
+        .spacing(SPACING);
+
+        if scout.ratings.len() > 1 {
+            column = column.push(
+                canvas(RatingGraph {
+                    ratings: scout.ratings.clone(),
+                })
+                .width(Length::Fill)
+                .height(80),
+            );
+        }
+
+        if let Some(milliseconds) = scout.move_milliseconds {
+            column = column.push(text(t!(
+                "A move takes %{seconds} s on average.",
+                seconds = Locale::current().number(milliseconds as f64 / 1_000.0, 1)
+            )));
+        }
+
+        if !scout.first_moves.is_empty() {
+            let first_moves = scout
+                .first_moves
+                .iter()
+                .map(|first| {
+                    format!(
+                        "{} {} ({})",
+                        t!(first.role.to_string()),
+                        first.play,
+                        first.games
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            column = column.push(text!("{}: {first_moves}", t!("Most played first moves")));
+        }
+
+        column = column.push(button(text(t!("Dismiss"))).on_press(Message::ScoutClose));
+
+        LabeledFrame::new(
+            text(t!(
+                "Scouting Report: %{username}",
+                username = scout.username.as_str()
+            )),
+            column,
+        )
+        .into()
+    }
+
+    /// Your avatar, with buttons to upload a new one or remove it.
+    fn avatar_settings(&self) -> Column<'_, Message> {
+        let mut row = row![text!("{}: ", t!("Avatar")), self.avatar(&self.username)]
+            .spacing(SPACING)
+            .align_y(Vertical::Center);
+
+        #[cfg(not(target_os = "redox"))]
+        if !self.kid_safe {
+            row = row.push(button(text(t!("Upload Avatar"))).on_press(Message::AvatarUpload));
+        }
+
+        row = row.push(button(text(t!("Remove Avatar"))).on_press(Message::AvatarRemove));
+
+        let mut column = column![row].spacing(SPACING);
+        if let Some(error) = &self.error_avatar {
+            column = column.push(text!("error: {error}").style(text::danger));
+        }
+
+        column
+    }
+
+    /// Editing your own bio, country, and side.
+    fn profile_settings(&self) -> Column<'_, Message> {
+        let mut column = column![text(t!("Profile"))].spacing(SPACING);
+
+        if !self.kid_safe {
+            column = column.push(
+                row![
+                    text!("{}: ", t!("Bio")),
+                    widget::text_input("", &self.profile_bio)
+                        .on_input(Message::ProfileBio)
+                        .on_paste(Message::ProfileBio),
+                ]
+                .spacing(SPACING),
+            );
+        }
+
+        column = column.push(
+            row![
+                text!("{}: ", t!("Country")),
+                widget::text_input("IS", &self.profile_country)
+                    .on_input(Message::ProfileCountry)
+                    .on_paste(Message::ProfileCountry)
+                    .width(60),
+            ]
+            .spacing(SPACING),
+        );
+
+        let mut sides = row![text!("{}: ", t!("Prefers to play"))].spacing(SPACING);
+        for (role, label) in [
+            (Role::Attacker, t!("attacker")),
+            (Role::Defender, t!("defender")),
+            (Role::Roleless, t!("either side")),
+        ] {
+            sides = sides.push(radio(
+                label,
+                role,
+                Some(self.profile_role),
+                Message::ProfileRole,
+            ));
+        }
+        column = column.push(sides);
+
+        column.push(button(text(t!("Save Profile"))).on_press(Message::ProfileSave))
+    }
+
+    fn vacation_settings(&self) -> Column<'_, Message> {
+        let mut column = column![
+            text(t!("Vacation")),
+            text(t!(
+                "Your correspondence clocks stop while you are on vacation. You have %{days} days left this year.",
+                days = self.vacation_days_left
+            )),
+        ]
+        .spacing(SPACING);
+
+        if let Some(until) = self.vacation_until
+            && let Ok(until) = Timestamp::from_millisecond(until)
+        {
+            column = column.push(
+                row![
+                    text(t!("On vacation until %{date}.", date = local_time(until))),
+                    button(text(t!("End Vacation"))).on_press(Message::VacationEnd),
+                ]
+                .spacing(SPACING)
+                .align_y(Vertical::Center),
+            );
+        }
+
+        let mut start = button(text(t!("Take Vacation Days")));
+        if self.vacation_days_left > 0 {
+            start = start.on_press(Message::VacationStart);
+        }
+
+        column = column.push(
+            row![
+                widget::text_input("7", &self.vacation_days)
+                    .on_input(Message::VacationDays)
+                    .on_paste(Message::VacationDays)
+                    .on_submit(Message::VacationStart)
+                    .width(60),
+                start,
+            ]
+            .spacing(SPACING)
+            .align_y(Vertical::Center),
+        );
+
+        if let Some(error) = &self.error_vacation {
+            column = column.push(text!("error: {error}").style(text::danger));
+        }
+
+        column
+    }
+
+    /// What you can do with a game, on a right click on its row.
+    fn game_menu<'a>(
+        &self,
+        game: &ServerGameLight,
+        underlay: impl Into<Element<'a, Message>>,
+    ) -> Element<'a, Message> {
+        let id = game.id;
+        let item = match self.join_game(game) {
+            JoinGame::Cancel => Some((t!("Cancel"), Message::GameCancel(id))),
+            JoinGame::Join => Some((t!("Join"), Message::GameJoin(id))),
+            JoinGame::None => None,
+            JoinGame::Resume => Some((t!("Resume"), Message::GameResume(id))),
+            JoinGame::Watch => Some((t!("Watch"), Message::GameWatch(id))),
+        };
+
+        let Some((label, message)) = item else {
+            return underlay.into();
+        };
+
+        ContextMenu::new(underlay, move || {
+            container(column![menu_item(&label, message.clone())].width(160))
+                .style(container::bordered_box)
+                .padding(PADDING_SMALL)
+                .into()
+        })
+        .into()
+    }
+
+    /// A player's avatar, or the room for one until it comes in.
+    fn avatar<'a>(&self, username: &str) -> Element<'a, Message> {
+        match self.avatars.get(username) {
+            Some(Some(avatar)) => widget::image(avatar.clone())
+                .width(AVATAR_SHOWN)
+                .height(AVATAR_SHOWN)
+                .into(),
+            _ => widget::space().width(AVATAR_SHOWN).into(),
+        }
+    }
+
+    fn avatar_shown<'a>(&self, username: Option<&str>) -> Element<'a, Message> {
+        match username {
+            Some(username) => self.avatar(username),
+            None => widget::space().width(AVATAR_SHOWN).into(),
+        }
+    }
+
+    /// Asks for the avatars it hasn't asked for yet.
+    fn avatars_request(&mut self, usernames: impl IntoIterator<Item = String>) {
+        for username in usernames {
+            if !self.avatars.contains_key(&username) {
+                self.send(&format!("avatar {username}\n"));
+                self.avatars.insert(username, None);
+            }
+        }
+    }
+
+    /// A username, starred if you follow them.
+    fn username_shown(&self, username: &str) -> String {
+        if self.follows.contains(username) {
+            format!("★ {username}")
+        } else {
+            username.to_string()
+        }
+    }
+
+    /// The announcement, unless it was dismissed.
+    fn announcement_shown(&self) -> Option<&Announcement> {
+        self.announcement
+            .as_ref()
+            .filter(|announcement| self.announcement_dismissed != Some(announcement.date))
+    }
+
+    fn forget_game(&mut self) {
+        if self.resume_game.take().is_some() {
+            handle_error(self.save_client_ron());
+        }
+    }
+
+    /// Opens your note on the game `id`, if you're one of its players.
+    fn note_open(&mut self, id: Id, attacker: &str, defender: &str) {
+        let opponent = if self.username == attacker {
+            defender
+        } else if self.username == defender {
+            attacker
+        } else {
+            self.note_game = None;
+            return;
+        };
+
+        self.note = text_editor::Content::with_text(self.notes.get(id));
+        self.note_game = Some((id, opponent.to_string()));
+    }
+
+    fn note_close(&mut self) {
+        if self.note_game.take().is_some() {
+            handle_error(self.save_client_ron());
+        }
+    }
+
+    fn notes_view<'a>(&'a self, opponent: Option<&'a str>) -> Column<'a, Message> {
+        let mut column = column![
+            text(t!("Your Notes")),
+            widget::text_input(&t!("Search"), &self.notes_search)
+                .on_input(Message::NotesSearch)
+                .on_paste(Message::NotesSearch),
+        ]
+        .spacing(SPACING);
+
+        for (id, note) in self.notes.search(opponent, &self.notes_search) {
+            column = column.push(text!("{id}: {} — {}", note.opponent, note.text));
+        }
+
+        column
+    }
+
+    /// Remembers the game you are playing, so you can resume it if the client
+    /// quits.
+    fn remember_game(&mut self) {
+        let opponent = if self.username == self.attacker {
+            &self.defender
+        } else if self.username == self.defender {
+            &self.attacker
+        } else {
+            return;
+        };
+
+        let resume_game = Some(ResumeGame {
+            id: self.game_id,
+            opponent: opponent.clone(),
+        });
+
+        if self.resume_game != resume_game {
+            self.resume_game = resume_game;
+            handle_error(self.save_client_ron());
+        }
     }
 
     fn handle_play(&mut self, role: Option<&str>, from: &str, to: &str) {
         self.captures = HashSet::new();
+        self.community_votes = None;
+        self.kibitz = None;
+        self.quiz_guess = None;
 
         let mut game_handle = None;
         if let Some(handle) = &mut self.archived_game_handle {
@@ -4088,14 +8881,28 @@ impl<'a> Client {
         let play = Plae::try_from(vec!["play", &role.to_string(), from, to])
             .expect("This is a valid plae.");
 
-        let captures = game.play(&play).expect("This should be a legal play.");
+        let Ok(captures) = game.play(&play) else {
+            // The board is out of step with the server's, which resync fixes.
+            error!("{play} isn't legal here");
+            return;
+        };
         for capture in captures.0 {
             self.captures.insert(capture);
         }
+        let number = game.plays.made().len();
+
+        if self.announce && self.archived_game_handle.is_none() {
+            self.voice.play(role, from, to);
+        }
 
         if let Some(handle) = &mut self.archived_game_handle {
             handle.boards.insert(&game.board);
             handle.play += 1;
+            self.estimate_score_cancel();
+        }
+
+        if self.archived_game_handle.is_none() {
+            self.hooks_move(role, number);
         }
 
         let capture = !self.captures.is_empty();
@@ -4159,8 +8966,10 @@ impl<'a> Client {
         let game = self.game.as_ref().expect("you should have a game by now");
 
         self.send(&format!(
-            "game {} play {} resigns _\n",
-            self.game_id, game.turn
+            "game {} play {} resigns _ {}\n",
+            self.game_id,
+            game.turn,
+            game.plays.made().len()
         ));
     }
 
@@ -4178,6 +8987,8 @@ impl<'a> Client {
             let mut losses = Column::new();
             let mut draws = Column::new();
             let mut win_percents = Column::new();
+            let mut accuracies = Column::new();
+            let mut accuracies_recent = Column::new();
             let mut emails = Column::new();
             let mut emails_sent = Column::new();
             let mut send_emails = Column::new();
@@ -4193,8 +9004,17 @@ impl<'a> Client {
                     win_percentage *= 100.0;
                     win_percentage = win_percentage.round_ties_even();
 
-                    ratings = ratings.push(text(account.rating.to_string_rounded()));
-                    usernames = usernames.push(text(name));
+                    ratings = ratings.push(text(
+                        self.rating_shown(&account.rating, &account.ratings)
+                            .to_string_rounded(),
+                    ));
+                    usernames = usernames.push(
+                        self.user_menu(
+                            &name,
+                            row![self.avatar(&name), text(self.username_shown(&name))]
+                                .spacing(SPACING_MEDIUM),
+                        ),
+                    );
                     wins = wins.push(text(account.wins));
                     losses = losses.push(text(account.losses));
                     draws = draws.push(text(account.draws));
@@ -4205,6 +9025,10 @@ impl<'a> Client {
                         win_percents = win_percents.push(text!("{}", win_percentage));
                     }
 
+                    accuracies = accuracies.push(text(percent(account.accuracy.average())));
+                    accuracies_recent =
+                        accuracies_recent.push(text(percent(account.accuracy.recent_average())));
+
                     emails = if let Some(email) = &account.email {
                         emails.push(text(email.address.clone()))
                     } else {
@@ -4298,6 +9122,22 @@ impl<'a> Client {
             ]
             .padding(PADDING);
 
+            let accuracy = t!("accuracy");
+            let accuracies = column![
+                text(accuracy.to_string()),
+                text("-".repeat(accuracy.chars().count())).font(Font::MONOSPACE),
+                accuracies
+            ]
+            .padding(PADDING);
+
+            let accuracy_recent = t!("recent accuracy");
+            let accuracies_recent = column![
+                text(accuracy_recent.to_string()),
+                text("-".repeat(accuracy_recent.chars().count())).font(Font::MONOSPACE),
+                accuracies_recent
+            ]
+            .padding(PADDING);
+
             let email = "email".to_string();
             let hyphens_count = email.chars().count();
             let emails = column![
@@ -4352,7 +9192,16 @@ impl<'a> Client {
             ]
             .padding(PADDING);
 
-            let mut rows = row![ratings, usernames, wins, losses, draws, win_percents];
+            let mut rows = row![
+                ratings,
+                usernames,
+                wins,
+                losses,
+                draws,
+                win_percents,
+                accuracies,
+                accuracies_recent
+            ];
 
             rows = rows.push(emails);
             rows = rows.push(emails_sent);
@@ -4369,6 +9218,8 @@ impl<'a> Client {
             let mut losses = Column::new();
             let mut draws = Column::new();
             let mut win_percents = Column::new();
+            let mut accuracies = Column::new();
+            let mut accuracies_recent = Column::new();
 
             for user in self.users_sorted() {
                 if logged_in == user.logged_in {
@@ -4378,8 +9229,20 @@ impl<'a> Client {
                     win_percentage *= 100.0;
                     win_percentage = win_percentage.round_ties_even();
 
-                    ratings = ratings.push(text(user.rating.to_string_rounded()));
-                    usernames = usernames.push(text(user.username));
+                    ratings = ratings.push(text(
+                        self.rating_shown(&user.rating, &user.ratings)
+                            .to_string_rounded(),
+                    ));
+                    usernames = usernames.push(
+                        self.user_menu(
+                            &user.username,
+                            row![
+                                self.avatar(&user.username),
+                                text(self.username_shown(&user.username))
+                            ]
+                            .spacing(SPACING_MEDIUM),
+                        ),
+                    );
                     wins = wins.push(text(user.wins));
                     losses = losses.push(text(user.losses));
                     draws = draws.push(text(user.draws));
@@ -4389,6 +9252,10 @@ impl<'a> Client {
                     } else {
                         win_percents = win_percents.push(text!("{}", win_percentage));
                     }
+
+                    accuracies = accuracies.push(text(percent(user.accuracy.average())));
+                    accuracies_recent =
+                        accuracies_recent.push(text(percent(user.accuracy.recent_average())));
                 }
             }
 
@@ -4453,7 +9320,32 @@ impl<'a> Client {
             ]
             .padding(PADDING);
 
-            row![ratings, usernames, wins, losses, draws, win_percents]
+            let accuracy = t!("accuracy");
+            let accuracies = column![
+                text(accuracy.to_string()),
+                text("-".repeat(accuracy.chars().count())).font(Font::MONOSPACE),
+                accuracies
+            ]
+            .padding(PADDING);
+
+            let accuracy_recent = t!("recent accuracy");
+            let accuracies_recent = column![
+                text(accuracy_recent.to_string()),
+                text("-".repeat(accuracy_recent.chars().count())).font(Font::MONOSPACE),
+                accuracies_recent
+            ]
+            .padding(PADDING);
+
+            row![
+                ratings,
+                usernames,
+                wins,
+                losses,
+                draws,
+                win_percents,
+                accuracies,
+                accuracies_recent
+            ]
         }
     }
 
@@ -4481,9 +9373,33 @@ impl<'a> Client {
         }
     }
 
+    pub fn title(&self, window: window::Id) -> String {
+        if let Some(review_window) = self.review_windows.get(&window) {
+            let game = &review_window.handle.game;
+            format!(
+                "Hnefatafl Copenhagen: {} vs {}",
+                game.attacker, game.defender
+            )
+        } else {
+            "Hnefatafl Copenhagen".to_string()
+        }
+    }
+
     #[must_use]
     #[allow(clippy::too_many_lines)]
-    pub fn view(&self) -> Element<'_, Message> {
+    pub fn view(&self, window: window::Id) -> Element<'_, Message> {
+        if let Some(review_window) = self.review_windows.get(&window) {
+            return self.review_window_view(window, review_window);
+        }
+
+        if let Some(step) = self.onboarding {
+            return self.onboarding_view(step);
+        }
+
+        if let Some(report) = &self.bug_report {
+            return self.bug_report_view(report);
+        }
+
         match self.screen {
             Screen::EmailEveryone => {
                 let subject = row![
@@ -4499,18 +9415,22 @@ impl<'a> Client {
                     .on_action(Message::TextEdit);
 
                 let send_emails = button("Send Emails").on_press(Message::TextSend);
-                let leave = button(text!("{} (Esc)", t!("Quit"))).on_press(Message::Leave);
+                let leave = button(text!(
+                    "{} ({})",
+                    t!("Quit"),
+                    self.key_bindings.get(Action::Leave)
+                ))
+                .on_press(Message::Leave);
                 let mut column = column![
                     subject,
                     text("From: Hnefatafl Org <noreply@hnefatafl.org>"),
-                    text("Content-Type: text/plain; charset=utf-8"),
-                    text("Content-Transfer-Encoding: 7bit"),
+                    text("Content-Type: multipart/alternative"),
                     text!("Date: {}", Timestamp::now().strftime("%F %T UTC")),
                     text("Body:"),
                     editor,
                     send_emails,
                     leave,
-                    text("Bcc:")
+                    text("To:")
                 ]
                 .spacing(SPACING)
                 .padding(PADDING);
@@ -4521,10 +9441,19 @@ impl<'a> Client {
 
                 scrollable(column).spacing(SPACING).into()
             }
+            Screen::Game if self.overlay && self.spectators.contains(&self.username) => {
+                self.overlay_view()
+            }
             Screen::Game | Screen::GameReview => self.display_game(),
             Screen::Games => {
                 let users = column![
-                    button(text!("{} (Esc)", t!("Quit"))).on_press(Message::Leave),
+                    button(text!(
+                        "{} ({})",
+                        t!("Quit"),
+                        self.key_bindings.get(Action::Leave)
+                    ))
+                    .on_press(Message::Leave),
+                    self.rating_shown_pick_list(),
                     text!("{}", t!("Online")),
                     self.users(true),
                     text!("{}", t!("Offline")),
@@ -4541,37 +9470,87 @@ impl<'a> Client {
                             horizontal: scrollable::Scrollbar::new(),
                         });
 
-                Tabs::new(Message::TabSelected)
-                    .push(
+                let messages_label = if self.dms_unread.is_empty() {
+                    t!("Messages").into()
+                } else {
+                    format!("{} ({})", t!("Messages"), self.dms_unread.len())
+                };
+
+                let tabs: [(TabId, String, Option<u8>, Element<'_, Message>); 9] = [
+                    (
                         TabId::Games,
-                        iced_aw::TabLabel::Text(format!("{} (1)", t!("Games"))),
-                        self.games_view(),
-                    )
-                    .push(
+                        t!("Games").into(),
+                        Some(1),
+                        self.games_view().into(),
+                    ),
+                    (
                         TabId::Chat,
-                        iced_aw::TabLabel::Text(format!("{} (2)", t!("Chat"))),
-                        self.chat_view(),
-                    )
-                    .push(
+                        t!("Chat").into(),
+                        Some(2),
+                        self.chat_view().into(),
+                    ),
+                    (
                         TabId::GameNew,
-                        iced_aw::TabLabel::Text(format!("{} (3)", t!("Create Game"))),
-                        self.game_new_view(),
-                    )
-                    .push(
+                        t!("Create Game").into(),
+                        Some(3),
+                        self.game_new_view().into(),
+                    ),
+                    (
                         TabId::Tournament,
-                        iced_aw::TabLabel::Text(format!("{} (4)", t!("Tournament"))),
-                        self.tournament_view(),
-                    )
-                    .push(
+                        t!("Tournament").into(),
+                        Some(4),
+                        self.tournament_view().into(),
+                    ),
+                    (
+                        TabId::Simuls,
+                        t!("Simuls").into(),
+                        None,
+                        self.simuls_view().into(),
+                    ),
+                    (
+                        TabId::Clubs,
+                        t!("Clubs").into(),
+                        None,
+                        self.clubs_view().into(),
+                    ),
+                    (
+                        TabId::Messages,
+                        messages_label,
+                        None,
+                        self.conversations_view().into(),
+                    ),
+                    (
                         TabId::AccountSettings,
-                        iced_aw::TabLabel::Text(format!("{} (5)", t!("Settings"))),
-                        self.settings_view(),
-                    )
-                    .push(
+                        t!("Settings").into(),
+                        Some(5),
+                        self.settings_view().into(),
+                    ),
+                    (
                         TabId::Users,
-                        iced_aw::TabLabel::Text(format!("{} (6)", t!("Users"))),
-                        users,
-                    )
+                        t!("Users").into(),
+                        Some(6),
+                        match &self.profile {
+                            Some(profile) => self.profile_view(profile).into(),
+                            None => users.into(),
+                        },
+                    ),
+                ];
+
+                if self.touch() {
+                    return self.touch_navigation(tabs);
+                }
+
+                let mut tabs_view = Tabs::new(Message::TabSelected);
+                for (tab, label, key, content) in tabs {
+                    let label = match key {
+                        Some(key) => format!("{label} ({key})"),
+                        None => label,
+                    };
+
+                    tabs_view = tabs_view.push(tab, iced_aw::TabLabel::Text(label), content);
+                }
+
+                tabs_view
                     .tab_label_padding(PADDING_TAB_LABEL)
                     .height(Length::Shrink)
                     .width(Length::Fill)
@@ -4604,13 +9583,20 @@ impl<'a> Client {
                     .padding(PADDING)
                     .style(container::bordered_box);
 
+                let kid_safe_text = text(t!("kid-safe account"));
+                let kid_safe = checkbox(self.kid_safe_create).on_toggle(Message::KidSafeCreate);
+
                 let show_password_text = text!("{} (1)", t!("show password"));
                 let show_password = checkbox(self.password_show).on_toggle(Message::PasswordShow);
 
                 let save_password_text = text!("{} (2)", t!("save password"));
                 let save_password = checkbox(self.password_save).on_toggle(Message::PasswordSave);
 
-                let mut login = button(text!("{} (Enter)", t!("Login")));
+                let mut login = button(text!(
+                    "{} ({})",
+                    t!("Login"),
+                    self.key_bindings.get(Action::Confirm)
+                ));
                 let mut create_account = button(text!("{} (4)", t!("Create Account")));
                 if !invalid_username(&self.text_input) {
                     login = login.on_press(Message::TextSendLogin);
@@ -4625,6 +9611,12 @@ impl<'a> Client {
                 let mut error = text("");
                 if let Some(error_) = &self.error {
                     error = text(error_).style(text::danger);
+                } else if let Some(percent) = self.proof_of_work {
+                    error = text!(
+                        "{}... {}%",
+                        t!("Creating the account"),
+                        Locale::current().number(f64::from(percent), 0)
+                    );
                 }
 
                 let mut error_persistent = Column::new();
@@ -4645,12 +9637,18 @@ impl<'a> Client {
                 );
 
                 let mut review_game = button(text!("{} (a)", t!("Review Game")));
+                let mut review_game_window = button(text(t!("New Window")));
+                let mut review_game_guess = button(text(t!("Guess the Move")));
                 if self.archived_game_selected.is_some() {
                     review_game = review_game.on_press(Message::ReviewGame);
+                    review_game_window = review_game_window.on_press(Message::ReviewGameWindow);
+                    review_game_guess = review_game_guess.on_press(Message::ReviewGameGuess);
                 }
 
                 let review_game = row![
                     review_game,
+                    review_game_window,
+                    review_game_guess,
                     button(text!("{} (b)", t!("Minimum Rating"))).on_press(Message::RatingMinimum),
                     minimum_rating,
                     button(text!("{} (c)", t!("Maximum Rating"))).on_press(Message::RatingMaximum),
@@ -4673,9 +9671,24 @@ impl<'a> Client {
 
                 let my_games_text = text!("{} (3)", t!("My Games Only"));
                 let my_games = checkbox(self.my_games_only).on_toggle(Message::MyGamesOnly);
-                let quit = button(text!("{} (Esc)", t!("Quit"))).on_press(Message::Leave);
-
-                let buttons_1 = row![login, create_account, reset_password, quit].spacing(SPACING);
+                let quit = button(text!(
+                    "{} ({})",
+                    t!("Quit"),
+                    self.key_bindings.get(Action::Leave)
+                ))
+                .on_press(Message::Leave);
+
+                let tutorial = button(text(t!("Tutorial Game"))).on_press(Message::Tutorial);
+                let report_issue = button(text(t!("Report Issue"))).on_press(Message::BugReport);
+                let buttons_1 = row![
+                    login,
+                    create_account,
+                    reset_password,
+                    tutorial,
+                    report_issue,
+                    quit
+                ]
+                .spacing(SPACING);
 
                 let review_game_pick = pick_list(
                     archived_games,
@@ -4684,6 +9697,45 @@ impl<'a> Client {
                 )
                 .placeholder(t!("Archived Games"));
 
+                let opening_pick =
+                    pick_list(openings::names(), self.opening_filter.clone(), |name| {
+                        Message::OpeningFilter(Some(name))
+                    })
+                    .placeholder(t!("Opening"));
+
+                let mut opening_all = button(text(t!("All Openings")));
+                if self.opening_filter.is_some() {
+                    opening_all = opening_all.on_press(Message::OpeningFilter(None));
+                }
+
+                let tags: BTreeSet<_> = self
+                    .archived_games
+                    .iter()
+                    .flat_map(|game| game.tags.values())
+                    .map(ToString::to_string)
+                    .collect();
+
+                let tag_pick = pick_list(
+                    tags.into_iter().collect::<Vec<_>>(),
+                    self.tag_filter.clone(),
+                    |tag| Message::TagFilter(Some(tag)),
+                )
+                .placeholder(t!("Tags"));
+
+                let mut tag_all = button(text(t!("All Tags")));
+                if self.tag_filter.is_some() {
+                    tag_all = tag_all.on_press(Message::TagFilter(None));
+                }
+
+                let review_game_pick = row![
+                    review_game_pick,
+                    opening_pick,
+                    opening_all,
+                    tag_pick,
+                    tag_all
+                ]
+                .spacing(SPACING);
+
                 let locale = self.locale_selection();
                 let theme = self.theme_selection();
                 let discord = button(text!("Discord (9)")).on_press(Message::OpenUrl(
@@ -4728,6 +9780,8 @@ impl<'a> Client {
                         save_password_text,
                         my_games,
                         my_games_text,
+                        kid_safe,
+                        kid_safe_text,
                     ]
                     .spacing(SPACING),
                     buttons_1,
@@ -4735,7 +9789,9 @@ impl<'a> Client {
                     locale,
                     review_game,
                     review_game_pick,
+                    self.tags_view(),
                     import_pgn,
+                    self.server_status_view(),
                     help_text,
                     help_text_2,
                     error,
@@ -4749,11 +9805,153 @@ impl<'a> Client {
         }
     }
 
+    /// Lets you change the tags of the selected game if you played it.
+    fn tags_view(&self) -> Row<'_, Message> {
+        let mut row = Row::new().spacing(SPACING);
+
+        let Some(game) = &self.archived_game_selected else {
+            return row;
+        };
+
+        if game.attacker != self.username && game.defender != self.username {
+            return row;
+        }
+
+        let tags = &self.tags;
+        row = row
+            .push(
+                iced::widget::text_input(&t!("Event"), &tags.event).on_input(|event| {
+                    Message::TagsChanged(Tags {
+                        event,
+                        ..tags.clone()
+                    })
+                }),
+            )
+            .push(
+                iced::widget::text_input(&t!("Round"), &tags.round).on_input(|round| {
+                    Message::TagsChanged(Tags {
+                        round,
+                        ..tags.clone()
+                    })
+                }),
+            )
+            .push(
+                iced::widget::text_input(&t!("Source"), &tags.source).on_input(|source| {
+                    Message::TagsChanged(Tags {
+                        source,
+                        ..tags.clone()
+                    })
+                }),
+            )
+            .push(
+                iced::widget::text_input(&t!("Variant"), &tags.variant).on_input(|variant| {
+                    Message::TagsChanged(Tags {
+                        variant,
+                        ..tags.clone()
+                    })
+                }),
+            )
+            .push(
+                iced::widget::text_input(&t!("Labels"), &self.tags_labels)
+                    .on_input(Message::TagsLabels)
+                    .on_submit(Message::TagsSave),
+            )
+            .push(button(text(t!("Save Tags"))).on_press(Message::TagsSave));
+
+        row
+    }
+
     fn reset_email(&mut self) {
         self.email = None;
         self.send("email_reset\n");
     }
 
+    /// Shows what the engine found on `board`, whether it just looked or the
+    /// position was in the cache.
+    fn estimate_score_display(&mut self, board: &Board, generate_move: GenerateMove) {
+        if let Some(trainer) = &mut self.trainer {
+            trainer.engine_set(board, Some(generate_move.play));
+        } else if let Some(handle) = self.archived_game_handle.as_ref() {
+            if handle.boards.here_board() == *board {
+                info!("{generate_move}");
+                debug!("{}", generate_move.heat_map);
+                self.heat_map = Some(generate_move.heat_map);
+            }
+        } else if let Some(game) = &self.game
+            && game.board == *board
+        {
+            self.kibitz = Some(Kibitz {
+                username: self.username.clone(),
+                timestamp: Timestamp::now(),
+                play: game.plays.len(),
+                score: generate_move.score,
+                best_play: generate_move.play,
+            });
+        }
+
+        self.estimate_score = false;
+        self.estimate_score_progress = None;
+    }
+
+    /// Shows the heat map of the position under review if it was looked at
+    /// before, so stepping back and forth does not wait on the engine.
+    fn heat_map_cached(&mut self) {
+        if let Some(handle) = &self.archived_game_handle
+            && let Some(generate_move) = self.eval_cache.get(&Game::from(&handle.boards))
+        {
+            self.heat_map = Some(generate_move.heat_map.clone());
+        }
+    }
+
+    /// Stops the score estimator when the board it is looking at goes away.
+    fn estimate_score_cancel(&mut self) {
+        if self.estimate_score
+            && let Some(cancel) = &self.estimate_score_cancel
+        {
+            cancel.cancel();
+        }
+    }
+
+    /// Says out loud when your clock runs low, if moves are being announced.
+    fn announce_clock(&mut self) {
+        if !self.announce {
+            return;
+        }
+
+        let time = if self.username == self.attacker {
+            &self.time_attacker
+        } else if self.username == self.defender {
+            &self.time_defender
+        } else {
+            return;
+        };
+
+        if let TimeSettings::Timed(time) = time {
+            self.voice.clock(time.milliseconds_left);
+        }
+    }
+
+    /// Takes the time since the clocks were last right off of the player whose
+    /// turn it is, going by the server's clock so a slow tick or a late message
+    /// doesn't make the time jump.
+    fn clock_run(&mut self) {
+        let now = self.server_clock.now();
+        let elapsed = (now - self.clock_at).max(0);
+        self.clock_at = self.clock_at.max(now);
+
+        if let Some(game) = &self.game {
+            let time = match game.turn {
+                Role::Attacker => &mut self.time_attacker,
+                Role::Defender => &mut self.time_defender,
+                Role::Roleless => return,
+            };
+
+            if let TimeSettings::Timed(time) = time {
+                time.milliseconds_left = (time.milliseconds_left - elapsed).max(0);
+            }
+        }
+    }
+
     fn reset_markers(&mut self) {
         self.captures = HashSet::new();
         self.play_from = None;
@@ -4764,13 +9962,175 @@ impl<'a> Client {
     fn review_game(&mut self) {
         if let Some(archived_game) = &self.archived_game_selected {
             self.archived_game_handle = Some(ArchivedGameHandle::new(archived_game));
+            let (id, attacker, defender) = (
+                archived_game.id,
+                archived_game.attacker.clone(),
+                archived_game.defender.clone(),
+            );
+            self.note_open(id, &attacker, &defender);
+
+            // You can only ask once you're logged in.
+            if self.connected_tcp && self.screen != Screen::Login {
+                self.send(&format!("ratings {attacker} {defender}\n"));
+            }
+
             self.screen = Screen::GameReview;
+            self.trainer = None;
+            self.tutorial = None;
 
             self.captures = HashSet::new();
             self.reset_markers();
         }
     }
 
+    fn tutorial_start(&mut self) {
+        if self.onboarding.take().is_some() {
+            handle_error(self.save_client_ron());
+        }
+
+        let tutorial = Tutorial::new(self.screen.clone());
+        let username = if self.username.is_empty() {
+            t!("You").to_string()
+        } else {
+            self.username.clone()
+        };
+
+        let archived_game = tutorial.archived_game(&username, &t!("AI"));
+        self.archived_game_handle = Some(ArchivedGameHandle::new(&archived_game));
+        self.screen = Screen::GameReview;
+        self.trainer = None;
+        self.tutorial = Some(tutorial);
+
+        self.captures = HashSet::new();
+        self.reset_markers();
+    }
+
+    /// Plays your move and the AI's answer on the board.
+    fn tutorial_play(&mut self, from: Vertex, to: Vertex) {
+        let (Some(tutorial), Some(handle)) = (&mut self.tutorial, &self.archived_game_handle)
+        else {
+            return;
+        };
+
+        let mut game = Game::from(&handle.boards);
+        let play = Play {
+            role: tutorial.you,
+            from,
+            to,
+        };
+
+        let Ok(answer) = tutorial.play(&mut game, play) else {
+            return;
+        };
+
+        self.handle_play(None, &from.to_string(), &to.to_string());
+        self.play_from_previous = Some(from);
+        self.play_to_previous = Some(to);
+
+        if let Some(answer) = answer {
+            self.handle_play(
+                Some(&answer.role.to_string()),
+                &answer.from.to_string(),
+                &answer.to.to_string(),
+            );
+            self.play_from_previous = Some(answer.from);
+            self.play_to_previous = Some(answer.to);
+        }
+    }
+
+    /// Goes through the game up to the puzzle's position, for you to find the
+    /// move.
+    fn puzzle_start(&mut self, position: &Position, archived_game: &ArchivedGame) {
+        self.archived_game_handle = Some(ArchivedGameHandle::new(archived_game));
+        self.screen = Screen::GameReview;
+        self.trainer = Some(Trainer::puzzle(position, archived_game));
+        self.tutorial = None;
+        self.error_puzzle = None;
+        self.daily_puzzle_solving = false;
+        self.daily_puzzle_try = None;
+
+        self.captures = HashSet::new();
+        self.reset_markers();
+        self.trainer_next();
+    }
+
+    fn trainer_start(&mut self) {
+        self.review_game();
+
+        if let Some(archived_game) = &self.archived_game_selected {
+            self.trainer = Some(Trainer::new(archived_game));
+            self.trainer_next();
+        }
+    }
+
+    /// Goes forward to the next of the player's moves and starts the engine
+    /// on it.
+    fn trainer_next(&mut self) {
+        let (Some(trainer), Some(handle)) = (&self.trainer, &mut self.archived_game_handle) else {
+            return;
+        };
+
+        let next = trainer.next(handle.play);
+        while next.is_some_and(|next| handle.play < next) && handle.boards.has_children() {
+            handle.play += 1;
+            handle.boards.forward();
+        }
+
+        let game = Game::from(&handle.boards);
+        self.reset_markers();
+        self.estimate_score_cancel();
+
+        if next.is_some() && self.estimate_score_tx.is_some() {
+            self.estimate_score = true;
+            self.send_estimate_score(game);
+        }
+    }
+
+    fn trainer_guess(&mut self, from: Vertex, to: Vertex) {
+        let (Some(trainer), Some(handle)) = (&mut self.trainer, &mut self.archived_game_handle)
+        else {
+            return;
+        };
+
+        let guessed = Plae::Play(Play {
+            role: trainer.role,
+            from,
+            to,
+        });
+
+        let mut game = Game::from(&handle.boards);
+        if game.play(&guessed).is_err() {
+            return;
+        }
+
+        // The server judges it and sends back the solution.
+        if self.daily_puzzle_solving {
+            self.daily_puzzle_solving = false;
+            self.daily_puzzle_try = Some((from, to));
+            self.send(&format!("daily_puzzle_solve {from}-{to}\n"));
+            return;
+        }
+
+        let board = handle.boards.here_board();
+        let Some(guess) = trainer.guess(handle.play, &board, guessed) else {
+            return;
+        };
+
+        if let Plae::Play(played) = &guess.played {
+            self.play_from_previous = Some(played.from);
+            self.play_to_previous = Some(played.to);
+        }
+
+        if handle.boards.has_children() {
+            handle.play += 1;
+            handle.boards.forward();
+        }
+
+        let markers = (self.play_from_previous, self.play_to_previous);
+        self.trainer_next();
+        (self.play_from_previous, self.play_to_previous) = markers;
+    }
+
     fn save_client_postcard(&self) -> anyhow::Result<()> {
         let postcard_bytes = postcard::to_allocvec(&self.archived_games)?;
         if !postcard_bytes.is_empty() {
@@ -4781,7 +10141,13 @@ impl<'a> Client {
         Ok(())
     }
 
+    /// Waits until the first launch wizard is done, so quitting it part way
+    /// starts it over next time.
     fn save_client_ron(&self) -> anyhow::Result<()> {
+        if self.onboarding.is_some() {
+            return Ok(());
+        }
+
         let password = if self.password_save {
             self.password.clone()
         } else {
@@ -4789,19 +10155,33 @@ impl<'a> Client {
         };
 
         let client = Client {
+            announce: self.announce,
+            announcement_dismissed: self.announcement_dismissed,
             archived_games: Vec::new(),
+            ascii: self.ascii,
+            confirm_moves: self.confirm_moves,
             coordinates: self.coordinates,
+            follow_the_action: self.follow_the_action,
+            follows: self.follows.clone(),
             is_fullscreen: self.is_fullscreen,
+            key_bindings: self.key_bindings.clone(),
             locale: self.locale,
             my_games_only: self.my_games_only,
+            notes: self.notes.clone(),
             password,
             password_save: self.password_save,
             password_show: self.password_show,
             rating_maximum: self.rating_maximum,
             rating_minimum: self.rating_minimum,
+            resume_game: self.resume_game.clone(),
+            settings: self.settings.clone(),
+            tags_unsent: self.tags_unsent.clone(),
+            text_size: self.text_size,
             theme: self.theme,
+            ui_scale: self.ui_scale,
             username: self.username.clone(),
             volume: self.volume.clone(),
+            window_geometry: self.window_geometry,
             ..Client::default()
         };
 
@@ -4814,6 +10194,93 @@ impl<'a> Client {
         Ok(())
     }
 
+    /// The settings that follow you to your other computers, as RON.
+    fn settings_current(&self) -> Vec<(&'static str, String)> {
+        let settings = [
+            ("coordinates", ron::ser::to_string(&self.coordinates)),
+            ("locale", ron::ser::to_string(&self.locale)),
+            ("my_games_only", ron::ser::to_string(&self.my_games_only)),
+            ("rating_maximum", ron::ser::to_string(&self.rating_maximum)),
+            ("rating_minimum", ron::ser::to_string(&self.rating_minimum)),
+            ("theme", ron::ser::to_string(&self.theme)),
+            ("volume", ron::ser::to_string(&self.volume)),
+        ];
+
+        settings
+            .into_iter()
+            .filter_map(|(name, value)| match value {
+                Ok(value) => Some((name, value)),
+                Err(error) => {
+                    error!("settings {name}: {error}");
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Notes when each of the settings was changed.
+    fn settings_changed(&mut self) {
+        let now = Timestamp::now();
+
+        for (name, value) in self.settings_current() {
+            self.settings.set(name, value, now);
+        }
+    }
+
+    /// Notes the settings you changed and sends them to the server, once
+    /// you're logged in.
+    fn settings_sync(&mut self) {
+        let settings = self.settings.clone();
+        self.settings_changed();
+        if self.settings != settings && self.screen != Screen::Login {
+            self.settings_send();
+        }
+    }
+
+    fn settings_send(&mut self) {
+        match ron::ser::to_string(&self.settings) {
+            Ok(settings) => self.send(&format!("settings {settings}\n")),
+            Err(error) => error!("settings: {error}"),
+        }
+    }
+
+    /// Uses the settings merged with the ones from your other computers.
+    fn settings_merged(&mut self, settings: Settings) {
+        fn get<T: DeserializeOwned>(settings: &Settings, name: &str) -> Option<T> {
+            settings
+                .get(name)
+                .and_then(|value| ron::from_str(value).ok())
+        }
+
+        if let Some(coordinates) = get(&settings, "coordinates") {
+            self.coordinates = coordinates;
+        }
+        if let Some(locale) = get::<Option<Locale>>(&settings, "locale") {
+            if let Some(locale) = locale {
+                rust_i18n::set_locale(&locale.txt());
+            }
+            self.locale = locale;
+        }
+        if let Some(my_games_only) = get(&settings, "my_games_only") {
+            self.my_games_only = my_games_only;
+        }
+        if let Some(rating_maximum) = get(&settings, "rating_maximum") {
+            self.rating_maximum = rating_maximum;
+        }
+        if let Some(rating_minimum) = get(&settings, "rating_minimum") {
+            self.rating_minimum = rating_minimum;
+        }
+        if let Some(theme) = get(&settings, "theme") {
+            self.theme = theme;
+        }
+        if let Some(volume) = get(&settings, "volume") {
+            self.volume = volume;
+        }
+
+        self.settings = settings;
+        handle_error(self.save_client_ron());
+    }
+
     fn send(&mut self, string: &str) {
         if let Err(error) = self
             .tx
@@ -4829,15 +10296,21 @@ impl<'a> Client {
         }
     }
 
-    fn send_estimate_score(&mut self, tree: Tree) {
+    fn send_estimate_score(&mut self, game: Game) {
+        if let Some(generate_move) = self.eval_cache.get(&game) {
+            let generate_move = generate_move.clone();
+            self.estimate_score_display(&game.board, generate_move);
+            return;
+        }
+
         handle_error(
             self.estimate_score_tx
                 .as_mut()
                 .unwrap_or_else(|| {
-                    error!("Error sending {tree:?}: you should have a tx available by now");
+                    error!("Error sending {game:?}: you should have a tx available by now");
                     unreachable!();
                 })
-                .send(tree),
+                .send(game),
         );
     }
 
@@ -4851,7 +10324,84 @@ impl<'a> Client {
         handle_error(self.save_client_ron());
     }
 
-    #[allow(clippy::too_many_lines)]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn tournament_requirement(&mut self, requirement: Requirement, value: f64) {
+        let requirements = &mut self.tournament.requirements;
+        let limit = (value > 0.0).then_some(value);
+
+        match requirement {
+            Requirement::PlayersMaximum => {
+                requirements.players_maximum = limit.map(|value| value as usize);
+            }
+            Requirement::RatedGames => requirements.rated_games = value as u64,
+            Requirement::RatingMaximum => requirements.rating_maximum = limit,
+            Requirement::RatingMinimum => requirements.rating_minimum = limit,
+        }
+
+        self.tournament_requirements_send();
+    }
+
+    fn tournament_requirements_send(&mut self) {
+        let ron_string = ron::ser::to_string(&self.tournament.requirements)
+            .expect("you should be able to serialize the requirements");
+
+        self.send(&format!("tournament_requirements {ron_string}\n"));
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn tournament_requirements(&self) -> Row<'_, Message> {
+        let requirements = &self.tournament.requirements;
+
+        let limits = [
+            (
+                "most players",
+                Requirement::PlayersMaximum,
+                requirements.players_maximum.unwrap_or_default() as f64,
+            ),
+            (
+                "fewest rated games",
+                Requirement::RatedGames,
+                requirements.rated_games as f64,
+            ),
+            (
+                "lowest rating",
+                Requirement::RatingMinimum,
+                requirements.rating_minimum.unwrap_or_default(),
+            ),
+            (
+                "highest rating",
+                Requirement::RatingMaximum,
+                requirements.rating_maximum.unwrap_or_default(),
+            ),
+        ];
+
+        let mut row = Row::new().spacing(SPACING);
+
+        for (label, requirement, value) in limits {
+            let input = number_input(&value, 0.0..=10_000.0, move |value| {
+                Message::TournamentRequirement(requirement, value)
+            });
+
+            row = row.push(LabeledFrame::new(label, input));
+        }
+
+        let round_days = self.tournament.round_days.unwrap_or_default() as f64;
+        let round_days = number_input(&round_days, 0.0..=365.0, Message::TournamentRoundDays);
+
+        let mut clubs = vec![String::new()];
+        clubs.extend(self.clubs.0.keys().cloned());
+        let club = pick_list(
+            clubs,
+            Some(requirements.club.clone().unwrap_or_default()),
+            Message::TournamentClub,
+        );
+
+        row.push(LabeledFrame::new("days per round", round_days))
+            .push(LabeledFrame::new("club", club))
+            .push(text("(0 or empty: no limit)"))
+    }
+
+    #[allow(clippy::cast_precision_loss, clippy::too_many_lines)]
     fn tournament_view(&self) -> Scrollable<'_, Message> {
         let mut column = Column::new().padding(PADDING).spacing(SPACING);
 
@@ -4866,8 +10416,14 @@ impl<'a> Client {
                 Message::DateSubmit,
             );
 
+            let time = widget::text_input("HH:MM", &self.tournament_time)
+                .on_input(Message::TournamentTimeOfDay)
+                .width(80);
+
             let delete_button_1 = button("Delete Tournament").on_press(Message::TournamentDelete);
-            let row = row![date_picker, delete_button_1].spacing(SPACING);
+            let row = row![time, date_picker, delete_button_1]
+                .spacing(SPACING)
+                .align_y(Vertical::Center);
 
             column = column.push(row);
         }
@@ -4893,6 +10449,12 @@ impl<'a> Client {
                 .spacing(SPACING),
         );
 
+        let rated = LabeledFrame::new(
+            text(t!("rated")),
+            row![checkbox(self.tournament.rated.into()).on_toggle(Message::TournamentRated)]
+                .padding(PADDING),
+        );
+
         let real_time = text(format!("{}:", t!("Real Time")));
         let blitz = radio(
             TimeEnum::Blitz.to_string(),
@@ -4950,21 +10512,41 @@ impl<'a> Client {
         let mut date = Row::new().spacing(SPACING);
         if let Some(timestamp) = self.tournament.date {
             date = date.push(text!(
-                "[{}] {}: {}, fischer {}: {}, {}: {}",
-                timestamp.strftime("%F %T UTC"),
+                "[{}] {}: {}, fischer {}: {}, {}: {}, {}: {}",
+                local_time(timestamp),
                 t!("board size"),
                 self.tournament.board_size,
                 t!("time"),
                 self.tournament.time_setting,
+                t!("rated"),
+                if self.tournament.rated.into() {
+                    t!("yes")
+                } else {
+                    t!("no")
+                },
                 t!("group size"),
                 self.tournament.group_size.size,
             ));
+
+            if self.tournament.tournament.is_none() && timestamp > Timestamp::now() {
+                date = date
+                    .push(text(t!(
+                        "Tournament in %{time}",
+                        time = time_until(timestamp)
+                    )))
+                    .push(Self::export_calendar_button());
+            }
         }
 
         column = column.push(
             row![
                 button(text!("{} (7)", t!("Tournaments Described"))).on_press(Message::Tournaments),
-                button(text!("{} (Esc)", t!("Quit"))).on_press(Message::Leave),
+                button(text!(
+                    "{} ({})",
+                    t!("Quit"),
+                    self.key_bindings.get(Action::Leave)
+                ))
+                .on_press(Message::Leave),
             ]
             .spacing(SPACING),
         );
@@ -4978,7 +10560,17 @@ impl<'a> Client {
             button_1 = button_1.on_press(Message::TournamentJoin);
         }
 
-        let buttons = row![button_1, button_2,].spacing(SPACING);
+        let mut buttons = row![button_1, button_2,].spacing(SPACING);
+
+        if let Some(tournament) = &self.tournament.tournament
+            && tournament.players.contains(&self.username)
+            && !tournament.withdrawn.contains(&self.username)
+        {
+            buttons = buttons.push(
+                button(text(t!("Withdraw from the Tournament")))
+                    .on_press(Message::TournamentWithdraw),
+            );
+        }
         let mut players = Column::new();
         let mut player_names: Vec<_> = self.tournament.players.iter().collect();
         player_names.sort();
@@ -5003,12 +10595,41 @@ impl<'a> Client {
 
             let number_of_games = LabeledFrame::new("number of games", number_of_games);
 
-            column = column.push(column![row![board_size, group_size, number_of_games], time]);
+            let arena_minutes = self.tournament.arena_minutes.unwrap_or_default() as f64;
+            let arena_minutes =
+                number_input(&arena_minutes, 0.0..=1_440.0, Message::TournamentArena);
+            let arena_minutes = LabeledFrame::new("arena minutes (0: groups)", arena_minutes);
+
+            column = column.push(column![
+                row![
+                    board_size,
+                    rated,
+                    group_size,
+                    number_of_games,
+                    arena_minutes
+                ],
+                self.tournament_requirements(),
+                time
+            ]);
         }
 
         column = column.push(buttons);
         column = column.push(LabeledFrame::new(text(t!("Players")), players));
 
+        if self.tournament.has_player(&self.username) || self.admin_tournament {
+            column = column.push(LabeledFrame::new(
+                text(t!("Chat")),
+                self.texting(
+                    &self.texts_tournament,
+                    &VecDeque::new(),
+                    None,
+                    true,
+                    1_000.0,
+                )
+                .height(300.0),
+            ));
+        }
+
         if self.admin_tournament {
             let mut delete_button_2 = button("Delete Tournament Tree");
 
@@ -5032,35 +10653,4 @@ impl<'a> Client {
         column = column.push(self.display_tournament());
         scrollable(column).spacing(SPACING)
     }
-
-    fn letter(
-        &self,
-        letter: char,
-        column: Column<'a, Message>,
-        letter_size: f32,
-    ) -> Column<'a, Message> {
-        let mut text = text(letter).size(letter_size);
-        if self.press_letters.contains(&letter.to_ascii_lowercase()) {
-            text = text.style(text::success);
-        }
-
-        column.push(text)
-    }
-
-    fn numbers(&self, letter_size: f32, spacing: f32, board_size: usize) -> Column<'a, Message> {
-        let mut column = column![text(" ").size(letter_size)].spacing(spacing);
-
-        for i in 0..board_size {
-            let i = board_size - i;
-            let mut text = text!("{i:2}").size(letter_size).align_y(Vertical::Center);
-
-            if self.press_numbers[i - 1] {
-                text = text.style(text::success);
-            }
-
-            column = column.push(text);
-        }
-
-        column
-    }
 }
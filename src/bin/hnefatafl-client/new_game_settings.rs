@@ -20,6 +20,7 @@ use hnefatafl_copenhagen::{
     board::BoardSize,
     rating::Rated,
     role::Role,
+    rule_set::RuleSet,
     time::{TimeEnum, TimeSettings},
 };
 use serde::{Deserialize, Serialize};
@@ -29,11 +30,17 @@ pub(crate) struct NewGameSettings {
     #[serde(skip)]
     pub board_size: BoardSize,
     #[serde(skip)]
+    pub established_only: bool,
+    #[serde(skip)]
     pub rated: Rated,
     #[serde(skip)]
     pub role_selected: Option<Role>,
     #[serde(skip)]
+    pub rule_set: RuleSet,
+    #[serde(skip)]
     pub timed: TimeSettings,
     #[serde(skip)]
     pub time: Option<TimeEnum>,
+    #[serde(skip)]
+    pub zen: bool,
 }
@@ -0,0 +1,69 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+use std::collections::BTreeMap;
+
+use hnefatafl_copenhagen::Id;
+use serde::{Deserialize, Serialize};
+
+/// What you wrote down about a game you played. It stays on your computer.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct Note {
+    pub opponent: String,
+    pub text: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct Notes(pub BTreeMap<Id, Note>);
+
+impl Notes {
+    pub fn get(&self, id: Id) -> &str {
+        self.0.get(&id).map_or("", |note| note.text.as_str())
+    }
+
+    /// Forgets the note when all of it is erased.
+    pub fn set(&mut self, id: Id, opponent: &str, text: String) {
+        if text.trim().is_empty() {
+            self.0.remove(&id);
+        } else {
+            self.0.insert(
+                id,
+                Note {
+                    opponent: opponent.to_string(),
+                    text,
+                },
+            );
+        }
+    }
+
+    /// The notes about `opponent`, or about everyone, that have `search` in
+    /// them, newest game first.
+    pub fn search<'a>(
+        &'a self,
+        opponent: Option<&'a str>,
+        search: &str,
+    ) -> impl Iterator<Item = (&'a Id, &'a Note)> {
+        let search = search.trim().to_lowercase();
+
+        self.0.iter().rev().filter(move |(_, note)| {
+            opponent.is_none_or(|opponent| note.opponent == opponent)
+                && (note.text.to_lowercase().contains(&search)
+                    || note.opponent.to_lowercase().contains(&search))
+        })
+    }
+}
@@ -0,0 +1,75 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! The steps the client walks you through the first time it starts, before
+//! it has a configuration file. It writes one once you are done.
+
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) enum Onboarding {
+    #[default]
+    Language,
+    Theme,
+    Characters,
+    Account,
+    Tutorial,
+}
+
+impl Onboarding {
+    pub const ALL: [Self; 5] = [
+        Self::Language,
+        Self::Theme,
+        Self::Characters,
+        Self::Account,
+        Self::Tutorial,
+    ];
+
+    /// Counting from one.
+    #[must_use]
+    pub fn number(self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|step| *step == self)
+            .map_or(1, |i| i + 1)
+    }
+
+    #[must_use]
+    pub fn next(self) -> Option<Self> {
+        Self::ALL.get(self.number()).copied()
+    }
+
+    #[must_use]
+    pub fn previous(self) -> Option<Self> {
+        self.number()
+            .checked_sub(2)
+            .and_then(|i| Self::ALL.get(i).copied())
+    }
+}
+
+impl fmt::Display for Onboarding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Language => f.write_str("Language"),
+            Self::Theme => f.write_str("Theme"),
+            Self::Characters => f.write_str("Board Characters"),
+            Self::Account => f.write_str("Account"),
+            Self::Tutorial => f.write_str("Tutorial"),
+        }
+    }
+}
@@ -20,6 +20,7 @@
 
 use std::{
     collections::{HashMap, VecDeque},
+    fmt::Write as _,
     fs::File,
     io::{Read, Write},
     str::FromStr,
@@ -32,7 +33,8 @@ use hnefatafl_copenhagen::{
     play::{Plae, Play, Plays, Vertex},
     rating::Rated,
     role::Role,
-    server_game::{ArchivedGame, ServerGame, ServerGameSerialized},
+    rule_set::RuleSet,
+    server_game::{ArchivedGame, ServerGame, ServerGameSerialized, Tags},
     time::TimeSettings,
 };
 use rfd::FileDialog;
@@ -86,6 +88,7 @@ fn archived_game_from_pgn(mut file: File) -> anyhow::Result<ArchivedGame> {
         let mut key = String::new();
         let mut value = String::new();
         let mut parsing_key = true;
+        let mut escaped = false;
 
         for ch in line.chars().skip(1) {
             if parsing_key {
@@ -94,12 +97,15 @@ fn archived_game_from_pgn(mut file: File) -> anyhow::Result<ArchivedGame> {
                 } else {
                     key.push(ch);
                 }
+            } else if escaped {
+                value.push(ch);
+                escaped = false;
             } else {
-                if ch == '"' || ch == ']' {
-                    continue;
+                match ch {
+                    '\\' => escaped = true,
+                    '"' | ']' => {}
+                    ch => value.push(ch),
                 }
-
-                value.push(ch);
             }
         }
 
@@ -131,6 +137,8 @@ fn archived_game_from_pgn(mut file: File) -> anyhow::Result<ArchivedGame> {
         game,
         messages: VecDeque::new(),
         timed: TimeSettings::UnTimed,
+        rule_set: RuleSet::Copenhagen,
+        zen: false,
     };
 
     let mut game = ServerGame::from(game);
@@ -138,11 +146,18 @@ fn archived_game_from_pgn(mut file: File) -> anyhow::Result<ArchivedGame> {
     game.game.chars.ascii();
     game.game.board.display_ascii = true;
 
-    Ok(ArchivedGame::new(
-        game,
-        Rating::default(),
-        Rating::default(),
-    ))
+    let mut game = ArchivedGame::new(game, Rating::default(), Rating::default());
+    let mut tag = |key| metadata.remove(key).unwrap_or_default();
+
+    game.tags = Tags {
+        event: tag("Event"),
+        round: tag("Round"),
+        source: tag("Site"),
+        variant: tag("Variant"),
+        ..Tags::default()
+    };
+
+    Ok(game)
 }
 
 pub fn write_portable_game_notation(archived_game: &ArchivedGame) -> anyhow::Result<()> {
@@ -201,10 +216,26 @@ fn portable_game_notation_from_archived_game(archived_game: &ArchivedGame) -> St
         plays_2.push(play);
     }
 
-    let mut string = format!(
-        "[Event \"Game {}\"]\n[Site \"hnefatafl.org\"]\n",
-        archived_game.id,
-    );
+    let tags = &archived_game.tags;
+    let mut string = if tags.event.is_empty() {
+        format!("[Event \"Game {}\"]\n", archived_game.id)
+    } else {
+        format!("[Event \"{}\"]\n", escape(&tags.event))
+    };
+
+    if tags.source.is_empty() {
+        string.push_str("[Site \"hnefatafl.org\"]\n");
+    } else {
+        let _ = writeln!(string, "[Site \"{}\"]", escape(&tags.source));
+    }
+
+    if !tags.round.is_empty() {
+        let _ = writeln!(string, "[Round \"{}\"]", escape(&tags.round));
+    }
+
+    if !tags.variant.is_empty() {
+        let _ = writeln!(string, "[Variant \"{}\"]", escape(&tags.variant));
+    }
 
     if let Some(message) = archived_game.messages.front()
         && "𓇳" == &message.username
@@ -220,3 +251,9 @@ fn portable_game_notation_from_archived_game(archived_game: &ArchivedGame) -> St
 
     string
 }
+
+/// A tag's value with its backslashes and quotes escaped, so it stays one PGN
+/// string.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,104 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! A player's rating over their last games, drawn as a line with the highest
+//! and lowest rating written on the left.
+
+use hnefatafl_copenhagen::locale::Locale;
+use iced::{
+    Point, Rectangle, Renderer, Theme, mouse,
+    widget::canvas::{Frame, Geometry, Path, Program, Stroke, Text},
+};
+
+use crate::enums::Message;
+
+/// How much room the ratings on the left get.
+const LABELS: f32 = 48.0;
+
+const TEXT_SIZE: f32 = 12.0;
+
+#[derive(Clone, Debug)]
+pub(crate) struct RatingGraph {
+    pub ratings: Vec<f64>,
+}
+
+impl Program<Message> for RatingGraph {
+    type State = ();
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let palette = theme.extended_palette();
+
+        let (Some(low), Some(high)) = (
+            self.ratings.iter().copied().reduce(f64::min),
+            self.ratings.iter().copied().reduce(f64::max),
+        ) else {
+            return vec![frame.into_geometry()];
+        };
+
+        let top = TEXT_SIZE;
+        let bottom = bounds.height - TEXT_SIZE;
+        let width = bounds.width - LABELS;
+        let range = (high - low).max(1.0);
+        let steps = (self.ratings.len() - 1).max(1) as f32;
+
+        let point = |i: usize, rating: f64| {
+            Point::new(
+                LABELS + width * i as f32 / steps,
+                bottom - (bottom - top) * ((rating - low) / range) as f32,
+            )
+        };
+
+        for (rating, y) in [(high, top), (low, bottom)] {
+            frame.fill_text(Text {
+                content: Locale::current().digits(&format!("{rating:.0}")),
+                position: Point::new(0.0, y - TEXT_SIZE / 2.0),
+                color: palette.background.base.text,
+                size: TEXT_SIZE.into(),
+                ..Text::default()
+            });
+        }
+
+        let line = Path::new(|builder| {
+            for (i, rating) in self.ratings.iter().enumerate() {
+                if i == 0 {
+                    builder.move_to(point(i, *rating));
+                } else {
+                    builder.line_to(point(i, *rating));
+                }
+            }
+        });
+
+        frame.stroke(
+            &line,
+            Stroke::default()
+                .with_width(2.0)
+                .with_color(palette.primary.base.color),
+        );
+
+        vec![frame.into_geometry()]
+    }
+}
@@ -0,0 +1,397 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! Draws a position into an image without a window, so it can be saved as a
+//! PNG: the attacker above the board, the defender below it, each with their
+//! clock, and the move number at the bottom. The text uses the same fonts as
+//! the rest of the client.
+
+use hnefatafl_copenhagen::{
+    play::{BOARD_LETTERS, Vertex},
+    space::Space,
+    status::Status,
+};
+use iced::advanced::graphics::text::{
+    cosmic_text::{Attrs, Buffer, Color, Metrics, Shaping, SwashCache},
+    font_system,
+};
+use image::{Rgba, RgbaImage};
+use rust_i18n::t;
+
+use crate::display_game::DisplayGame;
+
+const SQUARE: f32 = 48.0;
+const SPACING: f32 = 2.0;
+/// Room for the coordinates around the board.
+const MARGIN: f32 = 32.0;
+/// Room for a player's name and clock.
+const PANEL: f32 = 56.0;
+const FOOTER: f32 = 40.0;
+
+const LETTER_SIZE: f32 = 20.0;
+const NAME_SIZE: f32 = 22.0;
+const CLOCK_SIZE: f32 = 28.0;
+
+// Solarized, like the rest of the client.
+const BACKGROUND: [u8; 3] = [253, 246, 227];
+const BOARD: [u8; 3] = [147, 161, 161];
+const TEXT: [u8; 3] = [7, 54, 66];
+const RED: [u8; 3] = [220, 50, 47];
+const BLUE: [u8; 3] = [38, 139, 210];
+const YELLOW: [u8; 3] = [181, 137, 0];
+const GREEN: [u8; 3] = [133, 153, 0];
+
+#[derive(Clone, Copy, Debug)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+struct Canvas {
+    image: RgbaImage,
+}
+
+impl Canvas {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn new(width: f32, height: f32) -> Self {
+        let [r, g, b] = BACKGROUND;
+
+        Self {
+            image: RgbaImage::from_pixel(width as u32, height as u32, Rgba([r, g, b, 255])),
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn blend(&mut self, x: i32, y: i32, color: [u8; 3], alpha: f32) {
+        if x < 0 || y < 0 || alpha <= 0.0 {
+            return;
+        }
+
+        if let Some(Rgba(pixel)) = self.image.get_pixel_mut_checked(x as u32, y as u32) {
+            let alpha = alpha.min(1.0);
+
+            for (channel, color) in pixel.iter_mut().zip(color) {
+                let mixed = f32::from(color) * alpha + f32::from(*channel) * (1.0 - alpha);
+                *channel = mixed.round().clamp(0.0, 255.0) as u8;
+            }
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn rectangle(&mut self, x: f32, y: f32, width: f32, height: f32, color: [u8; 3]) {
+        for py in y.round() as i32..(y + height).round() as i32 {
+            for px in x.round() as i32..(x + width).round() as i32 {
+                self.blend(px, py, color, 1.0);
+            }
+        }
+    }
+
+    fn outline(&mut self, x: f32, y: f32, size: f32, width: f32, color: [u8; 3]) {
+        self.rectangle(x, y, size, width, color);
+        self.rectangle(x, y + size - width, size, width, color);
+        self.rectangle(x, y, width, size, color);
+        self.rectangle(x + size - width, y, width, size, color);
+    }
+
+    /// A circle with smooth edges. With a `width` only the ring gets drawn.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    fn circle(&mut self, x: f32, y: f32, radius: f32, width: Option<f32>, color: [u8; 3]) {
+        let reach = radius + width.unwrap_or_default() + 1.0;
+
+        for py in (y - reach).floor() as i32..=(y + reach).ceil() as i32 {
+            for px in (x - reach).floor() as i32..=(x + reach).ceil() as i32 {
+                let distance = (px as f32 + 0.5 - x).hypot(py as f32 + 0.5 - y);
+
+                let coverage = match width {
+                    Some(width) => width / 2.0 - (distance - radius).abs() + 0.5,
+                    None => radius - distance + 0.5,
+                };
+
+                self.blend(px, py, color, coverage);
+            }
+        }
+    }
+
+    /// Draws a line of text centered on `y`, starting, centered on, or ending
+    /// at `x`.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    fn text(&mut self, string: &str, size: f32, (x, y): (f32, f32), align: Align, color: [u8; 3]) {
+        let mut fonts = font_system()
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let font_system = fonts.raw();
+
+        let mut buffer = Buffer::new(font_system, Metrics::new(size, size * 1.2));
+        buffer.set_size(font_system, None, None);
+        buffer.set_text(font_system, string, &Attrs::new(), Shaping::Advanced, None);
+        buffer.shape_until_scroll(font_system, true);
+
+        let (width, height) = buffer
+            .layout_runs()
+            .fold((0.0, 0.0), |(width, height), run| {
+                (f32::max(width, run.line_w), height + run.line_height)
+            });
+
+        let left = match align {
+            Align::Left => x,
+            Align::Center => x - width / 2.0,
+            Align::Right => x - width,
+        };
+        let top = y - height / 2.0;
+
+        let mut pixels = Vec::new();
+        buffer.draw(
+            font_system,
+            &mut SwashCache::new(),
+            Color::rgb(color[0], color[1], color[2]),
+            |px, py, w, h, color| pixels.push((px, py, w, h, color)),
+        );
+
+        for (px, py, w, h, color) in pixels {
+            let alpha = f32::from(color.a()) / 255.0;
+
+            for dy in 0..h.cast_signed() {
+                for dx in 0..w.cast_signed() {
+                    self.blend(
+                        left.round() as i32 + px + dx,
+                        top.round() as i32 + py + dy,
+                        [color.r(), color.g(), color.b()],
+                        alpha,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Draws the position, with its coordinates, the players and their clocks,
+/// and the move number.
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn render(game: &DisplayGame) -> RgbaImage {
+    let board_size = game.board.size();
+    let size: usize = board_size.into();
+    let cell = SQUARE + SPACING;
+    let width = MARGIN * 2.0 + cell * size as f32 - SPACING;
+    let height = PANEL * 2.0 + width + FOOTER;
+
+    let mut canvas = Canvas::new(width, height);
+    let board_top = PANEL;
+    let origin = |x: usize, y: usize| {
+        (
+            MARGIN + cell * x as f32,
+            board_top + MARGIN + cell * y as f32,
+        )
+    };
+
+    player(
+        &mut canvas,
+        PANEL / 2.0,
+        width,
+        (
+            &game.attacker_shown(),
+            &game.attacker_rating_shown(),
+            &game.attacker_time,
+        ),
+        RED,
+    );
+
+    for (x, letter) in BOARD_LETTERS.chars().take(size).enumerate() {
+        let (left, _) = origin(x, 0);
+        let center = left + SQUARE / 2.0;
+
+        for y in [board_top + MARGIN / 2.0, board_top + width - MARGIN / 2.0] {
+            canvas.text(
+                &letter.to_string(),
+                LETTER_SIZE,
+                (center, y),
+                Align::Center,
+                TEXT,
+            );
+        }
+    }
+
+    for y in 0..size {
+        let (_, top) = origin(0, y);
+        let center = top + SQUARE / 2.0;
+        let number = (size - y).to_string();
+
+        for x in [MARGIN / 2.0, width - MARGIN / 2.0] {
+            canvas.text(&number, LETTER_SIZE, (x, center), Align::Center, TEXT);
+        }
+    }
+
+    for y in 0..size {
+        for x in 0..size {
+            let vertex = Vertex {
+                size: board_size,
+                x,
+                y,
+            };
+
+            let (left, top) = origin(x, y);
+            canvas.rectangle(left, top, SQUARE, SQUARE, BOARD);
+
+            let center = (left + SQUARE / 2.0, top + SQUARE / 2.0);
+            let radius = SQUARE * 0.38;
+
+            match game.board.get(&vertex) {
+                Space::Attacker => canvas.circle(center.0, center.1, radius, None, RED),
+                Space::Defender => canvas.circle(center.0, center.1, radius, None, BLUE),
+                Space::King => {
+                    canvas.circle(center.0, center.1, radius, None, YELLOW);
+                    canvas.circle(center.0, center.1, radius * 0.55, Some(3.0), TEXT);
+                }
+                Space::Empty => {
+                    if vertex.on_restricted_square() {
+                        let inset = SQUARE * 0.2;
+                        canvas.outline(left + inset, top + inset, SQUARE - inset * 2.0, 3.0, GREEN);
+                    }
+                }
+            }
+        }
+    }
+
+    player(
+        &mut canvas,
+        PANEL * 1.5 + width,
+        width,
+        (
+            &game.defender_shown(),
+            &game.defender_rating_shown(),
+            &game.defender_time,
+        ),
+        BLUE,
+    );
+
+    let mut footer = format!("{}: {}", t!("move"), game.play);
+    match game.status {
+        Status::AttackerWins => footer = format!("{footer}, {}", t!("Attacker wins!")),
+        Status::DefenderWins => footer = format!("{footer}, {}", t!("Defender wins!")),
+        Status::Draw => footer = format!("{footer}, {}", t!("It's a draw.")),
+        Status::Ongoing => {}
+    }
+
+    canvas.text(
+        &footer,
+        NAME_SIZE,
+        (width / 2.0, height - FOOTER / 2.0),
+        Align::Center,
+        TEXT,
+    );
+
+    canvas.image
+}
+
+/// A player's piece color, name, and rating on the left and their clock on
+/// the right.
+fn player(
+    canvas: &mut Canvas,
+    y: f32,
+    width: f32,
+    (name, rating, time): (&str, &str, &str),
+    color: [u8; 3],
+) {
+    canvas.circle(MARGIN / 2.0, y, NAME_SIZE / 2.5, None, color);
+
+    let name = if rating.is_empty() {
+        name.to_string()
+    } else {
+        format!("{name} {rating}")
+    };
+
+    canvas.text(&name, NAME_SIZE, (MARGIN, y), Align::Left, TEXT);
+    canvas.text(
+        time,
+        CLOCK_SIZE,
+        (width - MARGIN / 2.0, y),
+        Align::Right,
+        TEXT,
+    );
+}
+
+/// Asks where to save the position, then writes it as a PNG.
+#[cfg(not(target_os = "redox"))]
+pub(crate) fn write_png(game: &DisplayGame) -> anyhow::Result<()> {
+    let dirs =
+        directories::UserDirs::new().ok_or(anyhow::Error::msg("failed to get user directories"))?;
+
+    let dir = dirs
+        .picture_dir()
+        .or_else(|| dirs.document_dir())
+        .ok_or(anyhow::Error::msg("failed to get picture directory"))?;
+
+    let path = rfd::FileDialog::new()
+        .set_file_name(format!("position-{}-{}.png", game.game_id, game.play))
+        .set_directory(dir)
+        .save_file()
+        .ok_or(anyhow::Error::msg("failed to save file"))?;
+
+    render(game).save_with_format(path, image::ImageFormat::Png)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use hnefatafl_copenhagen::{board::Board, status::Status};
+    use image::Rgba;
+
+    use super::{BACKGROUND, BLUE, BOARD, MARGIN, PANEL, RED, SPACING, SQUARE, YELLOW, render};
+    use crate::display_game::DisplayGame;
+
+    #[test]
+    fn render_position() {
+        let game = DisplayGame {
+            game_id: 0,
+            attacker: "david".to_string(),
+            attacker_time: "05:00".to_string(),
+            attacker_rating: "1500".to_string(),
+            defender: "abby".to_string(),
+            defender_time: "05:00".to_string(),
+            defender_rating: "1500".to_string(),
+            board: Board::default(),
+            play: 0,
+            opening: None,
+            status: Status::Ongoing,
+            messages: VecDeque::new(),
+            anonymous: None,
+        };
+
+        let image = render(&game);
+        assert_eq!(image.dimensions(), (612, 764));
+
+        // The middle of a square on the 11x11 board.
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let square = |x: u32, y: u32| {
+            let cell = SQUARE + SPACING;
+            let left = MARGIN + cell * x as f32 + SQUARE / 2.0;
+            let top = PANEL + MARGIN + cell * y as f32 + SQUARE / 2.0;
+            *image.get_pixel(left as u32, top as u32)
+        };
+        let rgb = |[r, g, b]: [u8; 3]| Rgba([r, g, b, 255]);
+
+        assert_eq!(square(3, 10), rgb(RED), "d1");
+        assert_eq!(square(5, 4), rgb(BLUE), "f7");
+        assert_eq!(square(5, 5), rgb(YELLOW), "f6");
+        assert_eq!(square(1, 1), rgb(BOARD), "b10");
+        assert_eq!(*image.get_pixel(1, 1), rgb(BACKGROUND));
+    }
+}
@@ -0,0 +1,51 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+use serde::{Deserialize, Serialize};
+
+use crate::archived_game_handle::ArchivedGameHandle;
+
+pub(crate) const REVIEW_WINDOW_WIDTH: f32 = 700.0;
+pub(crate) const REVIEW_WINDOW_HEIGHT: f32 = 800.0;
+
+/// An archived game reviewed in a window of its own, so it can stay open
+/// while you play in the main window.
+#[derive(Clone, Debug)]
+pub(crate) struct ReviewWindow {
+    pub handle: ArchivedGameHandle,
+    pub height: f32,
+}
+
+impl ReviewWindow {
+    pub(crate) fn new(handle: ArchivedGameHandle) -> Self {
+        Self {
+            handle,
+            height: REVIEW_WINDOW_HEIGHT,
+        }
+    }
+}
+
+/// Where the main window was and how big it was, saved so it opens the same
+/// way next time.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub(crate) struct WindowGeometry {
+    pub width: f32,
+    pub height: f32,
+    pub x: Option<f32>,
+    pub y: Option<f32>,
+}
@@ -22,9 +22,12 @@ use std::fmt;
 pub(crate) enum TabId {
     AccountSettings,
     Chat,
+    Clubs,
     #[default]
     Games,
     GameNew,
+    Messages,
+    Simuls,
     Tournament,
     Users,
 }
@@ -34,8 +37,11 @@ impl fmt::Display for TabId {
         match self {
             Self::AccountSettings => f.write_str("Account Settings"),
             Self::Chat => f.write_str("Chat"),
+            Self::Clubs => f.write_str("Clubs"),
             Self::Games => f.write_str("Games"),
             Self::GameNew => f.write_str("Create Game"),
+            Self::Messages => f.write_str("Messages"),
+            Self::Simuls => f.write_str("Simuls"),
             Self::Tournament => f.write_str("Tournament"),
             Self::Users => f.write_str("Users"),
         }
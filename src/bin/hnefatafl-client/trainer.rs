@@ -0,0 +1,241 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! Guess the move: go through an archived game as the higher rated player,
+//! guess each of their moves before it is shown, and see how the guesses
+//! compare with what they played and with what the engine would play.
+
+use std::collections::BTreeMap;
+
+use hnefatafl_copenhagen::{
+    board::Board,
+    play::Plae,
+    puzzle::{Position, Theme},
+    role::Role,
+    server_game::ArchivedGame,
+};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct Guess {
+    pub guessed: Plae,
+    pub played: Plae,
+    /// The engine's best move when you guessed, if it had one yet.
+    pub engine: Option<Plae>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Trainer {
+    /// The player whose moves you guess.
+    pub player: String,
+    pub role: Role,
+    plays: Vec<Plae>,
+    /// The number of the first play to guess.
+    from: usize,
+    /// What kind of puzzle it is, when there is only the one move to find.
+    pub puzzle: Option<Theme>,
+    /// The guesses by the number of the play guessed.
+    pub guesses: BTreeMap<usize, Guess>,
+    /// The engine's best move so far and the board it is for.
+    engine: Option<(Board, Plae)>,
+}
+
+impl Trainer {
+    pub(crate) fn new(game: &ArchivedGame) -> Self {
+        let (player, role) = if game.defender_rating.rating > game.attacker_rating.rating {
+            (game.defender.clone(), Role::Defender)
+        } else {
+            (game.attacker.clone(), Role::Attacker)
+        };
+
+        Self {
+            player,
+            role,
+            plays: game.plays.made().into_iter().cloned().collect(),
+            from: 0,
+            puzzle: None,
+            guesses: BTreeMap::new(),
+            engine: None,
+        }
+    }
+
+    /// Guess the solution to the puzzle at `position`, gone through as
+    /// `game`. The game ends with the solution, unless the server keeps it
+    /// until you had your try.
+    pub(crate) fn puzzle(position: &Position, game: &ArchivedGame) -> Self {
+        Self {
+            player: position.theme.to_string(),
+            role: position.role,
+            plays: game.plays.made().into_iter().cloned().collect(),
+            from: position.plays.len(),
+            puzzle: Some(position.theme),
+            guesses: BTreeMap::new(),
+            engine: None,
+        }
+    }
+
+    /// Adds the solution the server sent back, so your guess can be judged.
+    pub(crate) fn solution_set(&mut self, solution: Plae) {
+        self.plays.truncate(self.from);
+        self.plays.push(solution);
+    }
+
+    /// The number of the next play at or after `play` that is yours to guess.
+    pub(crate) fn next(&self, play: usize) -> Option<usize> {
+        (play.max(self.from)..self.plays.len()).find(|play| self.to_guess(*play).is_some())
+    }
+
+    /// The number of the last play before `play` that is yours to guess, where
+    /// stepping back goes to.
+    pub(crate) fn previous(&self, play: usize) -> Option<usize> {
+        (self.from..play)
+            .rev()
+            .find(|play| self.to_guess(*play).is_some())
+    }
+
+    /// The move played at `play`, if it is one of the player's moves.
+    pub(crate) fn to_guess(&self, play: usize) -> Option<&Plae> {
+        if play < self.from {
+            return None;
+        }
+
+        self.plays
+            .get(play)
+            .filter(|played| matches!(played, Plae::Play(played) if played.role == self.role))
+    }
+
+    pub(crate) fn engine_set(&mut self, board: &Board, play: Option<Plae>) {
+        if let Some(play) = play {
+            self.engine = Some((board.clone(), play));
+        }
+    }
+
+    /// Guesses that `guessed` is the move played at `play` on `board`.
+    pub(crate) fn guess(&mut self, play: usize, board: &Board, guessed: Plae) -> Option<&Guess> {
+        let played = self.to_guess(play)?.clone();
+        let engine = self
+            .engine
+            .take()
+            .filter(|(engine_board, _)| engine_board == board)
+            .map(|(_, engine)| engine);
+
+        self.guesses.insert(
+            play,
+            Guess {
+                guessed,
+                played,
+                engine,
+            },
+        );
+
+        self.guesses.get(&play)
+    }
+
+    /// How many of the guesses were what the player played.
+    pub(crate) fn found(&self) -> usize {
+        self.guesses
+            .values()
+            .filter(|guess| guess.guessed == guess.played)
+            .count()
+    }
+
+    /// How many of the guesses were what the engine would play.
+    pub(crate) fn engine_agreed(&self) -> usize {
+        self.guesses
+            .values()
+            .filter(|guess| guess.engine.as_ref() == Some(&guess.guessed))
+            .count()
+    }
+
+    /// How many of the player's moves the engine would have played too.
+    pub(crate) fn engine_agreed_with_the_player(&self) -> usize {
+        self.guesses
+            .values()
+            .filter(|guess| guess.engine.as_ref() == Some(&guess.played))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use hnefatafl_copenhagen::{
+        board::BoardSize,
+        game::Game,
+        glicko::Rating,
+        play::Plae,
+        rating::Rated,
+        rule_set::RuleSet,
+        server_game::{ArchivedGame, Tags},
+        status::Status,
+    };
+
+    use super::Trainer;
+
+    #[test]
+    fn step_back() -> anyhow::Result<()> {
+        let mut game = Game::default();
+        for play in [
+            "play attacker a4 b4",
+            "play defender d6 d5",
+            "play attacker a5 b5",
+            "play defender h6 h5",
+        ] {
+            game.read_line(play)?;
+        }
+        let plays: Vec<Plae> = game.plays.made().into_iter().cloned().collect();
+
+        let mut trainer = Trainer::new(&ArchivedGame {
+            id: 0,
+            attacker: "david".to_string(),
+            attacker_rating: Rating::default(),
+            defender: "abby".to_string(),
+            defender_rating: Rating::default(),
+            rated: Rated::default(),
+            plays: game.plays,
+            status: Status::Ongoing,
+            messages: VecDeque::new(),
+            board_size: BoardSize::_11,
+            analysis: None,
+            lag: Vec::new(),
+            tags: Tags::default(),
+            rule_set: RuleSet::default(),
+        });
+        assert_eq!(trainer.player, "david");
+
+        assert_eq!(trainer.next(0), Some(0));
+        assert!(trainer.guess(0, &game.board, plays[0].clone()).is_some());
+        assert!(trainer.guess(1, &game.board, plays[1].clone()).is_none());
+        assert_eq!(trainer.next(1), Some(2));
+        assert!(trainer.guess(2, &game.board, plays[0].clone()).is_some());
+        assert_eq!(trainer.next(3), None);
+
+        // Stepping back from the end lands on the last move to guess, and
+        // guessing it again goes on from there.
+        assert_eq!(trainer.previous(4), Some(2));
+        assert_eq!(trainer.previous(3), Some(2));
+        assert_eq!(trainer.previous(2), Some(0));
+        assert_eq!(trainer.previous(0), None);
+        assert_eq!(trainer.found(), 1);
+        assert!(trainer.guess(2, &game.board, plays[2].clone()).is_some());
+        assert_eq!(trainer.found(), 2);
+        assert_eq!(trainer.guesses.len(), 2);
+
+        Ok(())
+    }
+}
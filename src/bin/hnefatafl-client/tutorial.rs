@@ -0,0 +1,122 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! A practice game on your own computer against the easy AI, with no clock
+//! and a tip about the rules under the board. It is played on the review
+//! screen, like guessing the move, so you can go back over it.
+
+use std::collections::VecDeque;
+
+use hnefatafl_copenhagen::{
+    ai::{AI, AiBasic},
+    board::BoardSize,
+    game::Game,
+    glicko::Rating,
+    play::{Plae, Play, Plays},
+    rating::Rated,
+    role::Role,
+    rule_set::RuleSet,
+    server_game::{ArchivedGame, Tags},
+    status::Status,
+};
+
+use crate::enums::Screen;
+
+/// How far ahead the AI looks, which keeps it easy to beat.
+const DEPTH: u8 = 1;
+
+pub(crate) const TIPS: [&str; 4] = [
+    "Every piece moves like a rook in chess, any number of empty squares in a straight line.",
+    "Capture a piece by moving so that it is between two of yours in a row or a column.",
+    "The defenders win when the king reaches a corner. The attackers win by surrounding the king on all four sides.",
+    "Only the king may stop on the throne in the middle and on the corners.",
+];
+
+#[derive(Clone, Debug)]
+pub(crate) struct Tutorial {
+    /// The side you play, the AI plays the other.
+    pub you: Role,
+    pub status: Status,
+    /// Where leaving the game takes you.
+    pub back: Screen,
+}
+
+impl Tutorial {
+    #[must_use]
+    pub fn new(back: Screen) -> Self {
+        Self {
+            you: Role::Attacker,
+            status: Status::Ongoing,
+            back,
+        }
+    }
+
+    /// The empty game to set the review screen up with.
+    #[must_use]
+    pub fn archived_game(&self, username: &str, ai: &str) -> ArchivedGame {
+        let (attacker, defender) = match self.you {
+            Role::Attacker | Role::Roleless => (username, ai),
+            Role::Defender => (ai, username),
+        };
+
+        ArchivedGame {
+            id: 0,
+            attacker: attacker.to_string(),
+            attacker_rating: Rating::default(),
+            defender: defender.to_string(),
+            defender_rating: Rating::default(),
+            rated: Rated::No,
+            plays: Plays::default(),
+            status: Status::Ongoing,
+            messages: VecDeque::new(),
+            board_size: BoardSize::_11,
+            analysis: None,
+            lag: Vec::new(),
+            tags: Tags::default(),
+            rule_set: RuleSet::default(),
+        }
+    }
+
+    /// The tip to show after `play` moves.
+    #[must_use]
+    pub fn tip(play: usize) -> &'static str {
+        TIPS[(play / 2) % TIPS.len()]
+    }
+
+    /// Plays your move on `game` and then the AI's answer, which it hands
+    /// back unless the game is over or the move isn't legal.
+    pub fn play(&mut self, game: &mut Game, play: Play) -> anyhow::Result<Option<Play>> {
+        if self.status != Status::Ongoing || game.turn != self.you {
+            return Err(anyhow::Error::msg("it isn't your turn"));
+        }
+
+        game.play(&Plae::Play(play))?;
+        self.status = game.status.clone();
+        if self.status != Status::Ongoing {
+            return Ok(None);
+        }
+
+        let generate_move = AiBasic::new(DEPTH, true).generate_move(game)?;
+        self.status = game.status.clone();
+
+        match generate_move.play {
+            Plae::Play(play) => Ok(Some(play)),
+            _ => Ok(None),
+        }
+    }
+}
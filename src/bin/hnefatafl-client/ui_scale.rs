@@ -0,0 +1,139 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! Drawing everything bigger or smaller, for high-DPI screens and for anyone
+//! who wants bigger text.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// How much bigger everything is drawn, in percent. The window sizes the
+/// layout picks between shrink with it, so a bigger scale gets the smaller
+/// layouts sooner. A saved scale outside of the ones offered is brought back
+/// in range.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(from = "UiScaleSaved")]
+pub(crate) struct UiScale(pub u32);
+
+#[derive(Deserialize)]
+#[serde(rename = "UiScale")]
+struct UiScaleSaved(u32);
+
+impl From<UiScaleSaved> for UiScale {
+    fn from(saved: UiScaleSaved) -> Self {
+        Self(
+            saved
+                .0
+                .clamp(Self::ALL[0].0, Self::ALL[Self::ALL.len() - 1].0),
+        )
+    }
+}
+
+impl UiScale {
+    pub const ALL: [Self; 8] = [
+        Self(75),
+        Self(90),
+        Self(100),
+        Self(110),
+        Self(125),
+        Self(150),
+        Self(175),
+        Self(200),
+    ];
+
+    #[allow(clippy::cast_precision_loss)]
+    pub fn factor(self) -> f32 {
+        self.0 as f32 / 100.0
+    }
+}
+
+impl Default for UiScale {
+    fn default() -> Self {
+        Self(100)
+    }
+}
+
+impl fmt::Display for UiScale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}%", self.0)
+    }
+}
+
+/// The size of the text that doesn't set its own, in pixels. It's read when
+/// the client starts, and brought back in range of the sizes offered.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(from = "TextSizeSaved")]
+pub(crate) struct TextSize(pub u16);
+
+#[derive(Deserialize)]
+#[serde(rename = "TextSize")]
+struct TextSizeSaved(u16);
+
+impl From<TextSizeSaved> for TextSize {
+    fn from(saved: TextSizeSaved) -> Self {
+        Self(
+            saved
+                .0
+                .clamp(Self::ALL[0].0, Self::ALL[Self::ALL.len() - 1].0),
+        )
+    }
+}
+
+impl TextSize {
+    pub const ALL: [Self; 7] = [
+        Self(12),
+        Self(14),
+        Self(16),
+        Self(18),
+        Self(20),
+        Self(24),
+        Self(28),
+    ];
+}
+
+impl Default for TextSize {
+    fn default() -> Self {
+        Self(16)
+    }
+}
+
+impl fmt::Display for TextSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TextSize, UiScale};
+
+    #[test]
+    fn clamped_when_loaded() -> anyhow::Result<()> {
+        assert_eq!(ron::from_str::<UiScale>("(0)")?, UiScale(75));
+        assert_eq!(ron::from_str::<UiScale>("UiScale(125)")?, UiScale(125));
+        assert_eq!(ron::from_str::<UiScale>("(1000)")?, UiScale(200));
+        assert_eq!(ron::from_str::<TextSize>("(0)")?, TextSize(12));
+        assert_eq!(ron::from_str::<TextSize>("(500)")?, TextSize(28));
+
+        let saved = ron::to_string(&UiScale(150))?;
+        assert_eq!(ron::from_str::<UiScale>(&saved)?, UiScale(150));
+
+        Ok(())
+    }
+}
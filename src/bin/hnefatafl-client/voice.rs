@@ -0,0 +1,201 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! Says the moves and when your clock is running low out loud, for when you
+//! aren't looking at the board.
+
+use std::{
+    fmt,
+    process::{Command, Stdio},
+    thread,
+};
+
+use hnefatafl_copenhagen::role::Role;
+use log::error;
+use rust_i18n::t;
+
+/// The milliseconds left you're warned at, the most first.
+const CLOCK_WARNINGS: [i64; 2] = [60_000, 10_000];
+
+pub(crate) trait Speech: fmt::Debug {
+    /// Starts saying the words, without waiting for them to be said.
+    ///
+    /// # Errors
+    ///
+    /// If the words can't be said.
+    fn say(&self, words: &str) -> anyhow::Result<()>;
+}
+
+/// Runs a text to speech program with the words as its argument.
+#[derive(Debug)]
+pub(crate) struct Program(pub String);
+
+impl Default for Program {
+    fn default() -> Self {
+        if cfg!(target_os = "macos") {
+            Self("say".to_string())
+        } else {
+            Self("espeak-ng".to_string())
+        }
+    }
+}
+
+impl Speech for Program {
+    fn say(&self, words: &str) -> anyhow::Result<()> {
+        let mut child = Command::new(&self.0)
+            .arg(words)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        thread::spawn(move || child.wait());
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Voice {
+    speech: Box<dyn Speech>,
+    /// The last clock warning given.
+    warned: Option<i64>,
+}
+
+impl Default for Voice {
+    fn default() -> Self {
+        Self::new(Box::new(Program::default()))
+    }
+}
+
+impl Voice {
+    pub fn new(speech: Box<dyn Speech>) -> Self {
+        Self {
+            speech,
+            warned: None,
+        }
+    }
+
+    /// "defender f6 to f9"
+    pub fn play(&self, role: Role, from: &str, to: &str) {
+        let role = match role {
+            Role::Attacker => t!("attacker"),
+            Role::Defender => t!("defender"),
+            Role::Roleless => return,
+        };
+
+        self.say(&t!(
+            "%{role} %{from} to %{to}",
+            role = role,
+            from = from,
+            to = to
+        ));
+    }
+
+    /// Warns you once as your clock passes each of the warnings.
+    pub fn clock(&mut self, milliseconds_left: i64) {
+        let Some(warning) = CLOCK_WARNINGS
+            .into_iter()
+            .rev()
+            .find(|warning| milliseconds_left <= *warning)
+        else {
+            self.warned = None;
+            return;
+        };
+
+        if self.warned.is_some_and(|warned| warned <= warning) {
+            return;
+        }
+
+        self.warned = Some(warning);
+        if warning == CLOCK_WARNINGS[1] {
+            self.say(&t!("ten seconds remaining"));
+        } else {
+            self.say(&t!("one minute remaining"));
+        }
+    }
+
+    fn say(&self, words: &str) {
+        if let Err(error) = self.speech.say(words) {
+            error!("{:?}: {error}", self.speech);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use hnefatafl_copenhagen::role::Role;
+
+    use super::{Speech, Voice};
+
+    /// Keeps what it was told to say.
+    #[derive(Debug, Default)]
+    struct Said(Rc<RefCell<Vec<String>>>);
+
+    impl Speech for Said {
+        fn say(&self, words: &str) -> anyhow::Result<()> {
+            self.0.borrow_mut().push(words.to_string());
+            Ok(())
+        }
+    }
+
+    fn voice() -> (Voice, Rc<RefCell<Vec<String>>>) {
+        let said = Said::default();
+        let words = said.0.clone();
+
+        (Voice::new(Box::new(said)), words)
+    }
+
+    #[test]
+    fn clock_warnings() {
+        let (mut voice, said) = voice();
+
+        for milliseconds_left in [120_000, 60_000, 59_000, 30_000, 10_000, 9_000, 0] {
+            voice.clock(milliseconds_left);
+        }
+        assert_eq!(
+            *said.borrow(),
+            ["one minute remaining", "ten seconds remaining"]
+        );
+    }
+
+    #[test]
+    fn clock_warnings_again() {
+        let (mut voice, said) = voice();
+
+        // Straight past both warnings, then time is added back.
+        voice.clock(5_000);
+        voice.clock(90_000);
+        voice.clock(45_000);
+        voice.clock(30_000);
+        assert_eq!(
+            *said.borrow(),
+            ["ten seconds remaining", "one minute remaining"]
+        );
+    }
+
+    #[test]
+    fn play() {
+        let (voice, said) = voice();
+
+        voice.play(Role::Attacker, "d1", "d3");
+        voice.play(Role::Roleless, "f6", "f9");
+        voice.play(Role::Defender, "f6", "f9");
+        assert_eq!(*said.borrow(), ["attacker d1 to d3", "defender f6 to f9"]);
+    }
+}
@@ -0,0 +1,108 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+#![deny(clippy::expect_used)]
+#![deny(clippy::indexing_slicing)]
+#![deny(clippy::panic)]
+#![deny(clippy::unwrap_used)]
+
+use std::process::ExitCode;
+
+use clap::Parser;
+
+use hnefatafl_copenhagen::{
+    bench::{BASELINES, GauntletSettings, gauntlet},
+    board::BoardSize,
+};
+
+/// Hnefatafl Gauntlet
+///
+/// Plays an AI against the baseline AIs at a few time controls and reports
+/// how it scored, so changes to the engine can be held back until they're
+/// strong enough.
+#[derive(Parser, Debug)]
+#[command(version, about = "Copenhagen Hnefatafl Engine Gauntlet")]
+struct Args {
+    /// The AI to test: banal, basic, or monte-carlo
+    #[arg(long, default_value = "monte-carlo")]
+    ai: String,
+
+    /// How deep in the game tree the AI goes
+    #[arg(long)]
+    depth: Option<u8>,
+
+    /// How many mebibytes the monte-carlo AI may use
+    #[arg(long)]
+    memory: Option<usize>,
+
+    /// The AIs to play against, separated by commas
+    #[arg(long, value_delimiter = ',', default_values_t = BASELINES.map(String::from))]
+    baselines: Vec<String>,
+
+    /// The seconds a move may take, one time control each, separated by
+    /// commas
+    #[arg(long, value_delimiter = ',', default_values_t = [1, 5])]
+    seconds: Vec<u64>,
+
+    /// How many games to play against each AI at each time control
+    #[arg(long, default_value_t = 10)]
+    games: usize,
+
+    /// The board size, 11 or 13
+    #[arg(long, default_value = "11")]
+    board_size: BoardSize,
+
+    /// The most plies to play before calling it a draw
+    #[arg(long, default_value_t = 500)]
+    max_plies: usize,
+
+    /// Where the random openings come from
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Fail when the AI scores less than this percent in all
+    #[arg(long)]
+    min_score: Option<f64>,
+}
+
+fn main() -> anyhow::Result<ExitCode> {
+    let args = Args::parse();
+
+    let report = gauntlet(&GauntletSettings {
+        candidate: args.ai,
+        depth: args.depth,
+        memory: args.memory,
+        baselines: args.baselines,
+        seconds: args.seconds,
+        games: args.games,
+        board_size: args.board_size,
+        max_plies: args.max_plies,
+        seed: args.seed,
+    })?;
+
+    println!("{report}");
+
+    if let Some(min_score) = args.min_score
+        && report.total().score() * 100.0 < min_score
+    {
+        println!("the score is under {min_score}%");
+        return Ok(ExitCode::FAILURE);
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
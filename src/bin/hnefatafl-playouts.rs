@@ -0,0 +1,72 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+#![deny(clippy::expect_used)]
+#![deny(clippy::indexing_slicing)]
+#![deny(clippy::panic)]
+#![deny(clippy::unwrap_used)]
+
+use std::time::Duration;
+
+use clap::Parser;
+
+use hnefatafl_copenhagen::{bench::throughput, board::BoardSize};
+
+/// Hnefatafl Playouts
+///
+/// Plays random games from the opening for a while and reports how many
+/// games and plies it got through a second.
+#[derive(Parser, Debug)]
+#[command(version, about = "Copenhagen Hnefatafl Playout Throughput")]
+struct Args {
+    /// The board size, 11 or 13
+    #[arg(long, default_value = "11")]
+    board_size: BoardSize,
+
+    /// How many seconds to play for
+    #[arg(long, default_value_t = 10)]
+    seconds: u64,
+
+    /// The most plies to play in one game
+    #[arg(long, default_value_t = 500)]
+    max_plies: usize,
+}
+
+fn main() {
+    let args = Args::parse();
+
+    let throughput = throughput(
+        args.board_size,
+        args.max_plies,
+        Duration::from_secs(args.seconds),
+    );
+
+    println!(
+        "{}x{}: {} playouts, {} plies in {:.2} s",
+        args.board_size,
+        args.board_size,
+        throughput.playouts,
+        throughput.plies,
+        throughput.elapsed.as_secs_f64()
+    );
+    println!(
+        "{:.1} playouts/s, {:.1} plies/s",
+        throughput.playouts_per_second(),
+        throughput.plies_per_second()
+    );
+}
@@ -0,0 +1,458 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! The finished games. They stay on disk, the server only remembers where
+//! each one starts in the file and keeps the games it used last in memory, so
+//! it doesn't grow with every game ever played. Asking for the games reads
+//! through the file and only parses the ones asked for. The games each player
+//! played in are indexed too, so a player's last games are read on their own.
+//! The server can be set to keep only each player's last games.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, BufWriter, ErrorKind, Seek, SeekFrom, Write},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+
+use hnefatafl_copenhagen::{ARCHIVED_GAMES_PAGE, Id, rating::Rated, server_game::ArchivedGame};
+
+/// How many games are kept in memory.
+pub(crate) const CACHED: usize = 1_000;
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Archive {
+    /// `None` when the server skips the data files, then every game stays in
+    /// memory.
+    path: Option<PathBuf>,
+    /// The games in the order they were archived.
+    ids: Vec<Id>,
+    /// Where the latest line of each game starts. A game gets written again
+    /// once it has been analyzed.
+    offsets: HashMap<Id, u64>,
+    /// The games each player played in, in the order they were archived.
+    players: HashMap<String, Vec<Id>>,
+    cache: HashMap<Id, ArchivedGame>,
+    /// The least recently used first.
+    used: VecDeque<Id>,
+}
+
+impl Archive {
+    /// Reads where each game starts, but keeps none of them, and parses only
+    /// the lines that it can't read the id off of.
+    pub(crate) fn load(path: &Path) -> anyhow::Result<Self> {
+        let mut archive = Self {
+            path: Some(path.to_path_buf()),
+            ..Self::default()
+        };
+
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(archive),
+            Err(error) => return Err(error.into()),
+        };
+
+        let mut reader = BufReader::new(file);
+        let mut line = String::new();
+        let mut offset = 0;
+
+        loop {
+            line.clear();
+            let length = reader.read_line(&mut line)?;
+            if length == 0 {
+                break;
+            }
+
+            let (id, attacker, defender) = if let Some(head) = head(&line) {
+                head
+            } else {
+                let game: ArchivedGame = ron::from_str(&line).map_err(|error| {
+                    anyhow::Error::msg(format!("RON: {}: {error}", path.display()))
+                })?;
+
+                (game.id, game.attacker, game.defender)
+            };
+
+            archive.index(id, offset, [attacker, defender]);
+            offset += u64::try_from(length)?;
+        }
+
+        Ok(archive)
+    }
+
+    fn index(&mut self, id: Id, offset: u64, players: [String; 2]) {
+        if self.offsets.insert(id, offset).is_none() {
+            self.ids.push(id);
+
+            for player in players {
+                self.players.entry(player).or_default().push(id);
+            }
+        }
+    }
+
+    /// Archives the game, or replaces it if it was archived before.
+    pub(crate) fn push(&mut self, game: ArchivedGame) -> anyhow::Result<()> {
+        if let Some(path) = &self.path {
+            let mut line = ron::ser::to_string(&game)?;
+            line.push('\n');
+
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            let offset = file.seek(SeekFrom::End(0))?;
+            file.write_all(line.as_bytes())?;
+
+            self.index(
+                game.id,
+                offset,
+                [game.attacker.clone(), game.defender.clone()],
+            );
+        } else {
+            self.index(game.id, 0, [game.attacker.clone(), game.defender.clone()]);
+        }
+
+        self.cache(game);
+        Ok(())
+    }
+
+    /// Reads the game from disk unless it is in memory.
+    pub(crate) fn get(&mut self, id: Id) -> anyhow::Result<Option<ArchivedGame>> {
+        if let Some(game) = self.cache.get(&id) {
+            let game = game.clone();
+            self.used.retain(|used| *used != id);
+            self.used.push_back(id);
+
+            return Ok(Some(game));
+        }
+
+        let (Some(path), Some(offset)) = (&self.path, self.offsets.get(&id)) else {
+            return Ok(None);
+        };
+
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(*offset))?;
+
+        let mut line = String::new();
+        BufReader::new(file).read_line(&mut line)?;
+        let game: ArchivedGame = ron::from_str(&line)?;
+
+        self.cache(game.clone());
+        Ok(Some(game))
+    }
+
+    fn cache(&mut self, game: ArchivedGame) {
+        let id = game.id;
+        self.used.retain(|used| *used != id);
+        self.used.push_back(id);
+        self.cache.insert(id, game);
+
+        if self.path.is_some() {
+            while self.used.len() > CACHED
+                && let Some(id) = self.used.pop_front()
+            {
+                self.cache.remove(&id);
+            }
+        }
+    }
+
+    /// The last `count` games the player played in, in the order they were
+    /// archived. Only their lines get read.
+    pub(crate) fn player_games(
+        &self,
+        player: &str,
+        count: usize,
+    ) -> anyhow::Result<Vec<ArchivedGame>> {
+        let Some(ids) = self.players.get(player) else {
+            return Ok(Vec::new());
+        };
+
+        let mut reader = match &self.path {
+            Some(path) => Some(BufReader::new(File::open(path)?)),
+            None => None,
+        };
+
+        let mut games = Vec::new();
+        let mut line = String::new();
+
+        for id in ids.iter().skip(ids.len().saturating_sub(count)) {
+            if let Some(game) = self.cache.get(id) {
+                games.push(game.clone());
+            } else if let (Some(reader), Some(offset)) = (&mut reader, self.offsets.get(id)) {
+                reader.seek(SeekFrom::Start(*offset))?;
+                line.clear();
+                reader.read_line(&mut line)?;
+                games.push(ron::from_str(&line)?);
+            }
+        }
+
+        Ok(games)
+    }
+
+    /// Keeps each player's last `kept` games, a game goes once it is older
+    /// than that for both of its players. The file gets written again with
+    /// the latest line of every game left. Returns how many games went.
+    pub(crate) fn prune(&mut self, kept: usize) -> anyhow::Result<usize> {
+        let mut keep = HashSet::new();
+        for ids in self.players.values() {
+            keep.extend(ids.iter().skip(ids.len().saturating_sub(kept)).copied());
+        }
+
+        let pruned = self.ids.len() - keep.len();
+        if pruned == 0 {
+            return Ok(0);
+        }
+
+        let Some(path) = self.path.clone() else {
+            self.ids.retain(|id| keep.contains(id));
+            self.offsets.retain(|id, _| keep.contains(id));
+            self.cache.retain(|id, _| keep.contains(id));
+            self.used.retain(|id| keep.contains(id));
+            for ids in self.players.values_mut() {
+                ids.retain(|id| keep.contains(id));
+            }
+            self.players.retain(|_, ids| !ids.is_empty());
+
+            return Ok(pruned);
+        };
+
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".prune");
+
+        let mut reader = BufReader::new(File::open(&path)?);
+        let mut writer = BufWriter::new(File::create(&temporary)?);
+        let mut line = String::new();
+        let mut offset = 0;
+
+        loop {
+            line.clear();
+            let length = reader.read_line(&mut line)?;
+            if length == 0 {
+                break;
+            }
+
+            let id = match id(&line) {
+                Some(id) => id,
+                None => ron::from_str::<ArchivedGame>(&line)?.id,
+            };
+
+            if self.offsets.get(&id) == Some(&offset) && keep.contains(&id) {
+                writer.write_all(line.as_bytes())?;
+            }
+
+            offset += u64::try_from(length)?;
+        }
+
+        writer.flush()?;
+        drop((reader, writer));
+        fs::rename(&temporary, &path)?;
+
+        *self = Self::load(&path)?;
+        Ok(pruned)
+    }
+
+    /// How many rated games each player played, read through once.
+    pub(crate) fn rated_games(&self) -> anyhow::Result<HashMap<String, u64>> {
+        let mut rated_games: HashMap<String, u64> = HashMap::new();
+        let mut count = |game: &ArchivedGame| {
+            if game.rated == Rated::Yes {
+                for player in [&game.attacker, &game.defender] {
+                    *rated_games.entry(player.clone()).or_default() += 1;
+                }
+            }
+        };
+
+        let Some(path) = &self.path else {
+            self.cache.values().for_each(&mut count);
+            return Ok(rated_games);
+        };
+
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(rated_games),
+            Err(error) => return Err(error.into()),
+        };
+
+        let mut reader = BufReader::new(file);
+        let mut line = String::new();
+        let mut offset = 0;
+
+        loop {
+            line.clear();
+            let length = reader.read_line(&mut line)?;
+            if length == 0 {
+                break;
+            }
+
+            // Only the latest line of a game counts.
+            if id(&line).is_none_or(|id| self.offsets.get(&id) == Some(&offset)) {
+                let game: ArchivedGame = ron::from_str(&line)?;
+
+                if self.offsets.get(&game.id) == Some(&offset) {
+                    count(&game);
+                }
+            }
+
+            offset += u64::try_from(length)?;
+        }
+
+        Ok(rated_games)
+    }
+
+    /// The last [`ARCHIVED_GAMES_PAGE`] games the filter lets through in the
+    /// order they were archived, read through once. Older games are asked for
+    /// by their ids.
+    pub(crate) fn games(&self, filter: &ArchiveFilter) -> anyhow::Result<Vec<ArchivedGame>> {
+        let Some(path) = &self.path else {
+            let mut games: Vec<_> = self
+                .ids
+                .iter()
+                .rev()
+                .filter_map(|id| self.cache.get(id))
+                .filter(|game| filter.matches(game))
+                .take(ARCHIVED_GAMES_PAGE)
+                .cloned()
+                .collect();
+
+            games.reverse();
+            return Ok(games);
+        };
+
+        let positions: HashMap<_, _> = self
+            .ids
+            .iter()
+            .enumerate()
+            .filter(|(_, id)| filter.matches_id(**id))
+            .map(|(position, id)| (*id, position))
+            .collect();
+
+        let mut games = BTreeMap::new();
+
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error.into()),
+        };
+
+        let mut reader = BufReader::new(file);
+        let mut line = String::new();
+        let mut offset = 0;
+
+        loop {
+            line.clear();
+            let length = reader.read_line(&mut line)?;
+            if length == 0 {
+                break;
+            }
+
+            let skip = id(&line).is_some_and(|id| {
+                self.offsets.get(&id) != Some(&offset) || !positions.contains_key(&id)
+            });
+
+            if !skip {
+                let game: ArchivedGame = ron::from_str(&line)?;
+
+                if self.offsets.get(&game.id) == Some(&offset)
+                    && filter.matches(&game)
+                    && let Some(position) = positions.get(&game.id)
+                {
+                    games.insert(*position, game);
+
+                    if games.len() > ARCHIVED_GAMES_PAGE {
+                        games.pop_first();
+                    }
+                }
+            }
+
+            offset += u64::try_from(length)?;
+        }
+
+        Ok(games.into_values().collect())
+    }
+}
+
+/// The id at the start of a line, without parsing the rest of it.
+fn id(line: &str) -> Option<Id> {
+    line.strip_prefix("(id:")?.split(',').next()?.parse().ok()
+}
+
+/// The id, attacker, and defender at the start of a line, without parsing the
+/// rest of it.
+fn head(line: &str) -> Option<(Id, String, String)> {
+    let (_, rest) = line.split_once(",attacker:\"")?;
+    let (attacker, rest) = rest.split_once('"')?;
+    let (_, rest) = rest.split_once(",defender:\"")?;
+    let (defender, _) = rest.split_once('"')?;
+
+    Some((id(line)?, attacker.to_string(), defender.to_string()))
+}
+
+/// Which of the archived games to send.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct ArchiveFilter {
+    pub ids: Option<RangeInclusive<Id>>,
+    /// Only the games the player played in.
+    pub player: Option<String>,
+    /// Only the games with the tag, which may be more than one word.
+    pub tag: Option<String>,
+}
+
+impl ArchiveFilter {
+    /// Reads `[ids FIRST LAST] [player USERNAME] [tag TAG...]`, the tag last
+    /// as it takes the rest of the words.
+    pub(crate) fn new(mut words: &[&str]) -> anyhow::Result<Self> {
+        let mut filter = Self::default();
+
+        loop {
+            match words {
+                [] => return Ok(filter),
+                ["ids", first, last, rest @ ..] => {
+                    filter.ids = Some(first.parse()?..=last.parse()?);
+                    words = rest;
+                }
+                ["player", username, rest @ ..] => {
+                    filter.player = Some((*username).to_string());
+                    words = rest;
+                }
+                ["tag", tag @ ..] if !tag.is_empty() => {
+                    filter.tag = Some(tag.join(" "));
+                    words = &[];
+                }
+                _ => {
+                    return Err(anyhow::Error::msg(format!(
+                        "{}: isn't a filter",
+                        words.join(" ")
+                    )));
+                }
+            }
+        }
+    }
+
+    #[must_use]
+    fn matches_id(&self, id: Id) -> bool {
+        self.ids.as_ref().is_none_or(|ids| ids.contains(&id))
+    }
+
+    #[must_use]
+    fn matches(&self, game: &ArchivedGame) -> bool {
+        self.matches_id(game.id)
+            && self
+                .player
+                .as_ref()
+                .is_none_or(|player| game.attacker == *player || game.defender == *player)
+            && self.tag.as_ref().is_none_or(|tag| game.tags.has(tag))
+    }
+}
@@ -0,0 +1,142 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! A record of the logins, the changes to accounts, and everything the admins
+//! do, for looking into what happened to an account. The file only ever gets
+//! appended to.
+
+use std::{
+    collections::VecDeque,
+    fs::{self, OpenOptions},
+    io::{ErrorKind, Write},
+    net::IpAddr,
+    path::{Path, PathBuf},
+};
+
+use hnefatafl_copenhagen::clock;
+use jiff::Timestamp;
+use log::error;
+use serde::{Deserialize, Serialize};
+
+/// How many of the latest entries are kept in memory.
+const KEPT: usize = 10_000;
+
+/// How many entries the digest shows.
+const DIGEST: usize = 100;
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct AuditEntry {
+    pub timestamp: Timestamp,
+    pub username: String,
+    /// `None` for the server and the bridges.
+    pub address: Option<IpAddr>,
+    pub event: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct AuditLog {
+    /// `None` when the server skips the data files.
+    path: Option<PathBuf>,
+    /// The oldest first.
+    recent: VecDeque<AuditEntry>,
+}
+
+impl AuditLog {
+    /// Reads the latest entries and appends to the file from then on. A line
+    /// that can't be read, say one cut short by a crash, gets logged and
+    /// skipped.
+    pub(crate) fn load(path: &Path) -> anyhow::Result<Self> {
+        let mut recent = VecDeque::new();
+
+        match fs::read_to_string(path) {
+            Ok(string) => {
+                for (number, line) in string.lines().enumerate() {
+                    match ron::from_str(line) {
+                        Ok(entry) => recent.push_back(entry),
+                        Err(error) => {
+                            error!("{}:{}: RON: {error}", path.display(), number + 1);
+                            continue;
+                        }
+                    }
+
+                    if recent.len() > KEPT {
+                        recent.pop_front();
+                    }
+                }
+            }
+            Err(error) if error.kind() == ErrorKind::NotFound => {}
+            Err(error) => return Err(error.into()),
+        }
+
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            recent,
+        })
+    }
+
+    /// It contains email addresses and IP addresses, so only the owner may
+    /// read the file.
+    pub(crate) fn record(
+        &mut self,
+        username: &str,
+        address: Option<IpAddr>,
+        event: &str,
+    ) -> anyhow::Result<()> {
+        let entry = AuditEntry {
+            timestamp: clock::now(),
+            username: username.to_string(),
+            address,
+            event: event.to_string(),
+        };
+
+        if let Some(path) = &self.path {
+            let mut options = OpenOptions::new();
+            options.create(true).append(true);
+
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+            let mut line = ron::ser::to_string(&entry)?;
+            line.push('\n');
+            options.open(path)?.write_all(line.as_bytes())?;
+        }
+
+        self.recent.push_back(entry);
+        if self.recent.len() > KEPT {
+            self.recent.pop_front();
+        }
+
+        Ok(())
+    }
+
+    /// The latest entries, the oldest first, only those by `username` if
+    /// there is one.
+    #[must_use]
+    pub(crate) fn digest(&self, username: Option<&str>) -> Vec<&AuditEntry> {
+        let mut entries: Vec<_> = self
+            .recent
+            .iter()
+            .rev()
+            .filter(|entry| username.is_none_or(|username| entry.username == username))
+            .take(DIGEST)
+            .collect();
+
+        entries.reverse();
+        entries
+    }
+}
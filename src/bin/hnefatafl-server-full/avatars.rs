@@ -0,0 +1,171 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! The players' avatars, each a PNG file in the avatars folder named after
+//! the player. They are read off the disk when someone asks for one.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    io::{Cursor, ErrorKind},
+    path::PathBuf,
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use hnefatafl_copenhagen::{
+    avatar::{AVATAR_BYTES, AVATAR_SIZE},
+    invalid_username,
+};
+use image::{ImageFormat, ImageReader};
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Avatars {
+    /// `None` when the server skips the data files, then the avatars stay in
+    /// memory.
+    folder: Option<PathBuf>,
+    memory: HashMap<String, Vec<u8>>,
+}
+
+impl Avatars {
+    pub(crate) fn new(folder: PathBuf) -> Self {
+        Self {
+            folder: Some(folder),
+            memory: HashMap::new(),
+        }
+    }
+
+    /// # Errors
+    ///
+    /// If it isn't a username, so that `../` and the like never reach the
+    /// disk.
+    fn path(&self, username: &str) -> anyhow::Result<Option<PathBuf>> {
+        if invalid_username(username) {
+            return Err(anyhow::Error::msg(format!("invalid username: {username}")));
+        }
+
+        Ok(self
+            .folder
+            .as_ref()
+            .map(|folder| folder.join(format!("{username}.png"))))
+    }
+
+    /// The avatar base64 encoded, if the player has one.
+    pub(crate) fn get(&self, username: &str) -> anyhow::Result<Option<String>> {
+        let png = match self.path(username)? {
+            Some(path) => match fs::read(path) {
+                Ok(png) => png,
+                Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+                Err(error) => return Err(error.into()),
+            },
+            None => match self.memory.get(username) {
+                Some(png) => png.clone(),
+                None => return Ok(None),
+            },
+        };
+
+        Ok(Some(STANDARD.encode(png)))
+    }
+
+    /// # Errors
+    ///
+    /// If it isn't a base64 encoded PNG file small enough to be an avatar.
+    pub(crate) fn set(&mut self, username: &str, base64: &str) -> anyhow::Result<()> {
+        let png = decode(base64)?;
+
+        if let Some(path) = self.path(username)? {
+            if let Some(folder) = &self.folder {
+                fs::create_dir_all(folder)?;
+            }
+            fs::write(path, png)?;
+        } else {
+            self.memory.insert(username.to_string(), png);
+        }
+
+        Ok(())
+    }
+
+    /// Every avatar by username, for the backups.
+    pub(crate) fn all(&self) -> anyhow::Result<BTreeMap<String, Vec<u8>>> {
+        let Some(folder) = &self.folder else {
+            return Ok(self.memory.clone().into_iter().collect());
+        };
+
+        let entries = match fs::read_dir(folder) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(error) => return Err(error.into()),
+        };
+
+        let mut avatars = BTreeMap::new();
+        for entry in entries {
+            let path = entry?.path();
+
+            if let Some(username) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_suffix(".png"))
+                .filter(|username| !invalid_username(username))
+            {
+                avatars.insert(username.to_string(), fs::read(&path)?);
+            }
+        }
+
+        Ok(avatars)
+    }
+
+    /// Removes the avatar and tells whether there was one.
+    pub(crate) fn remove(&mut self, username: &str) -> anyhow::Result<bool> {
+        match self.path(username)? {
+            Some(path) => match fs::remove_file(path) {
+                Ok(()) => Ok(true),
+                Err(error) if error.kind() == ErrorKind::NotFound => Ok(false),
+                Err(error) => Err(error.into()),
+            },
+            None => Ok(self.memory.remove(username).is_some()),
+        }
+    }
+}
+
+/// Decodes the whole picture, so that only PNG files that open make it in.
+fn decode(base64: &str) -> anyhow::Result<Vec<u8>> {
+    // Base64 takes four characters for every three bytes.
+    if base64.len() > AVATAR_BYTES.div_ceil(3) * 4 {
+        return Err(anyhow::Error::msg(format!(
+            "the avatar is bigger than {AVATAR_BYTES} bytes"
+        )));
+    }
+
+    let png = STANDARD.decode(base64)?;
+    if png.len() > AVATAR_BYTES {
+        return Err(anyhow::Error::msg(format!(
+            "the avatar is bigger than {AVATAR_BYTES} bytes"
+        )));
+    }
+
+    let mut reader = ImageReader::new(Cursor::new(&png));
+    reader.set_format(ImageFormat::Png);
+    let (width, height) = reader.into_dimensions()?;
+    if width > AVATAR_SIZE || height > AVATAR_SIZE {
+        return Err(anyhow::Error::msg(format!(
+            "the avatar is bigger than {AVATAR_SIZE} by {AVATAR_SIZE} pixels"
+        )));
+    }
+
+    image::load_from_memory_with_format(&png, ImageFormat::Png)?;
+    Ok(png)
+}
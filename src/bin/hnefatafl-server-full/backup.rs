@@ -0,0 +1,230 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+use std::{
+    collections::BTreeMap,
+    fs::{self, DirBuilder, File},
+    io::{ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use hnefatafl_copenhagen::{
+    invalid_username,
+    server_game::{ArchivedGame, ServerGameSerialized},
+};
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::{Server, audit::AuditEntry, avatars::Avatars};
+
+/// How many backups to keep, the oldest get deleted first.
+pub(crate) const BACKUPS_KEPT: usize = 14;
+const BACKUP_EXTENSION: &str = "postcard.gz";
+
+/// A snapshot of every data file the server loads on startup.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct Backup {
+    pub users: String,
+    pub archived_games: String,
+    pub active_games: Vec<u8>,
+    pub audit_log: String,
+    /// The PNG files by username.
+    pub avatars: BTreeMap<String, Vec<u8>>,
+}
+
+impl Backup {
+    /// Reads the data files next to what the server keeps in memory, the users
+    /// and the active games. `data_file` maps a file name to its path.
+    pub(crate) fn new(
+        users: String,
+        active_games: Vec<u8>,
+        avatars: &Avatars,
+        data_file: impl Fn(&str) -> PathBuf,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            users,
+            archived_games: whole_lines(&data_file(crate::ARCHIVED_GAMES_FILE))?,
+            active_games,
+            audit_log: whole_lines(&data_file(crate::AUDIT_LOG_FILE))?,
+            avatars: avatars.all()?,
+        })
+    }
+
+    /// Reads and validates a backup.
+    pub(crate) fn read(path: &Path) -> anyhow::Result<Self> {
+        let mut data = Vec::new();
+        GzDecoder::new(File::open(path)?).read_to_end(&mut data)?;
+        let backup: Self = postcard::from_bytes(&data)?;
+
+        let _server: Server = ron::from_str(&backup.users)
+            .map_err(|error| anyhow::Error::msg(format!("RON: users: {error}")))?;
+
+        for line in backup.archived_games.lines() {
+            let _archived_game: ArchivedGame = ron::from_str(line)
+                .map_err(|error| anyhow::Error::msg(format!("RON: archived games: {error}")))?;
+        }
+
+        if !backup.active_games.is_empty() {
+            let _games = ServerGameSerialized::from_postcard(&backup.active_games)?;
+        }
+
+        for line in backup.audit_log.lines() {
+            let _entry: AuditEntry = ron::from_str(line)
+                .map_err(|error| anyhow::Error::msg(format!("RON: audit log: {error}")))?;
+        }
+
+        // The usernames become file names.
+        for username in backup.avatars.keys() {
+            if invalid_username(username) {
+                return Err(anyhow::Error::msg(format!(
+                    "avatars: invalid username: {username}"
+                )));
+            }
+        }
+
+        Ok(backup)
+    }
+
+    /// Writes the data files back in place, `data_file` maps a file name
+    /// to its path. Everything gets written next to where it goes first, so
+    /// a failed restore leaves the old data files as they were.
+    pub(crate) fn restore(&self, data_file: impl Fn(&str) -> PathBuf) -> anyhow::Result<()> {
+        // The active games and the audit log only exist once there is some.
+        let files = [
+            (data_file(crate::USERS_FILE), self.users.as_bytes(), true),
+            (
+                data_file(crate::ARCHIVED_GAMES_FILE),
+                self.archived_games.as_bytes(),
+                true,
+            ),
+            (
+                data_file(crate::ACTIVE_GAMES_FILE),
+                self.active_games.as_slice(),
+                !self.active_games.is_empty(),
+            ),
+            (
+                data_file(crate::AUDIT_LOG_FILE),
+                self.audit_log.as_bytes(),
+                !self.audit_log.is_empty(),
+            ),
+        ];
+
+        for (file, contents, exists) in &files {
+            if *exists {
+                fs::write(temporary(file), contents)?;
+            }
+        }
+
+        let avatars_folder = data_file(crate::AVATARS_FOLDER);
+        let avatars_temporary = temporary(&avatars_folder);
+        if fs::exists(&avatars_temporary)? {
+            fs::remove_dir_all(&avatars_temporary)?;
+        }
+        DirBuilder::new()
+            .recursive(true)
+            .create(&avatars_temporary)?;
+        for (username, png) in &self.avatars {
+            fs::write(avatars_temporary.join(format!("{username}.png")), png)?;
+        }
+
+        for (file, _, exists) in &files {
+            if *exists {
+                fs::rename(temporary(file), file)?;
+            } else if fs::exists(file)? {
+                fs::remove_file(file)?;
+            }
+        }
+
+        if fs::exists(&avatars_folder)? {
+            fs::remove_dir_all(&avatars_folder)?;
+        }
+        fs::rename(avatars_temporary, avatars_folder)?;
+
+        Ok(())
+    }
+
+    /// Writes the backup to `file` the way [`Backup::read`] reads it.
+    pub(crate) fn encode(&self, file: File) -> anyhow::Result<()> {
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        encoder.write_all(&postcard::to_allocvec(self)?)?;
+        encoder.finish()?;
+
+        Ok(())
+    }
+
+    /// Writes a timestamped backup to `folder`, then deletes all but the
+    /// newest `kept` backups.
+    pub(crate) fn write(
+        &self,
+        folder: &Path,
+        now: Timestamp,
+        kept: usize,
+    ) -> anyhow::Result<PathBuf> {
+        DirBuilder::new().recursive(true).create(folder)?;
+
+        let mut path = folder.to_path_buf();
+        path.push(format!(
+            "backup-{}.{BACKUP_EXTENSION}",
+            now.strftime("%Y-%m-%dT%H-%M-%SZ")
+        ));
+
+        self.encode(File::create(&path)?)?;
+
+        let mut backups = Vec::new();
+        for entry in fs::read_dir(folder)? {
+            let entry = entry?.path();
+
+            if entry
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("backup-") && name.ends_with(BACKUP_EXTENSION))
+            {
+                backups.push(entry);
+            }
+        }
+
+        // The timestamps sort the file names from oldest to newest.
+        backups.sort();
+        for backup in backups.iter().rev().skip(kept) {
+            fs::remove_file(backup)?;
+        }
+
+        Ok(path)
+    }
+}
+
+/// Where a data file gets written before it is moved in place.
+fn temporary(path: &Path) -> PathBuf {
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".restore");
+    PathBuf::from(temporary)
+}
+
+/// The file up to its last whole line, as the server may be adding a line to
+/// it while it gets read.
+fn whole_lines(path: &Path) -> anyhow::Result<String> {
+    let mut string = match fs::read_to_string(path) {
+        Ok(string) => string,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(String::new()),
+        Err(error) => return Err(error.into()),
+    };
+
+    string.truncate(string.rfind('\n').map_or(0, |end| end + 1));
+    Ok(string)
+}
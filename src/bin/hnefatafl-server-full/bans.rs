@@ -0,0 +1,226 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! Keeps people out, by account or by IP address, for a while or for good.
+
+use std::{fmt, net::IpAddr, str::FromStr};
+
+use hnefatafl_copenhagen::clock;
+use jiff::{Span, Timestamp, tz::TimeZone};
+use serde::{Deserialize, Serialize};
+
+/// An IP address or a range of them, such as `192.0.2.0/24`.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct Network {
+    pub address: IpAddr,
+    pub prefix: u8,
+}
+
+impl Network {
+    #[must_use]
+    pub(crate) fn contains(&self, address: IpAddr) -> bool {
+        // The server listens on IPv6, so IPv4 clients show up as mapped
+        // addresses.
+        match (self.address, address.to_canonical(), address) {
+            (IpAddr::V4(network), IpAddr::V4(address), _) => prefix_matches(
+                u32::from(network).into(),
+                u32::from(address).into(),
+                self.prefix,
+                32,
+            ),
+            (IpAddr::V6(network), _, IpAddr::V6(address)) => {
+                prefix_matches(network.into(), address.into(), self.prefix, 128)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn prefix_matches(network: u128, address: u128, prefix: u8, bits: u8) -> bool {
+    prefix == 0
+        || bits
+            .checked_sub(prefix)
+            .is_some_and(|shift| (network ^ address) >> shift == 0)
+}
+
+impl From<IpAddr> for Network {
+    fn from(address: IpAddr) -> Self {
+        let prefix = if address.is_ipv4() { 32 } else { 128 };
+
+        Self { address, prefix }
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefix)
+    }
+}
+
+impl FromStr for Network {
+    type Err = anyhow::Error;
+
+    fn from_str(network: &str) -> anyhow::Result<Self> {
+        let Some((address, prefix)) = network.split_once('/') else {
+            return Ok(IpAddr::from_str(network)?.into());
+        };
+
+        let address = IpAddr::from_str(address)?;
+        let prefix = prefix.parse()?;
+        let bits = if address.is_ipv4() { 32 } else { 128 };
+
+        if prefix > bits {
+            return Err(anyhow::Error::msg(format!(
+                "{network}: the prefix is more than {bits}"
+            )));
+        }
+
+        Ok(Self { address, prefix })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) enum BanTarget {
+    Account(String),
+    Network(Network),
+}
+
+impl fmt::Display for BanTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Account(username) => write!(f, "{username}"),
+            Self::Network(network) => write!(f, "{network}"),
+        }
+    }
+}
+
+impl FromStr for BanTarget {
+    type Err = anyhow::Error;
+
+    /// Anything that isn't an IP address is taken to be a username.
+    fn from_str(target: &str) -> anyhow::Result<Self> {
+        if target.contains(['.', ':', '/']) {
+            Ok(Self::Network(Network::from_str(target)?))
+        } else {
+            Ok(Self::Account(target.to_string()))
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct Ban {
+    pub target: BanTarget,
+    pub reason: String,
+    /// Who set the ban.
+    pub username: String,
+    pub created: Timestamp,
+    /// `None` for a ban that never runs out.
+    pub expires: Option<Timestamp>,
+}
+
+impl Ban {
+    /// `duration` is either `forever` or a span such as `7d` or `12h`.
+    pub(crate) fn new(
+        target: BanTarget,
+        duration: &str,
+        reason: &str,
+        username: &str,
+    ) -> anyhow::Result<Self> {
+        let created = clock::now();
+
+        let expires = if duration == "forever" {
+            None
+        } else {
+            let span = Span::from_str(duration)?;
+            Some(
+                created
+                    .to_zoned(TimeZone::UTC)
+                    .checked_add(span)?
+                    .timestamp(),
+            )
+        };
+
+        Ok(Self {
+            target,
+            reason: reason.to_string(),
+            username: username.to_string(),
+            created,
+            expires,
+        })
+    }
+
+    #[must_use]
+    fn active(&self, now: Timestamp) -> bool {
+        self.expires.is_none_or(|expires| expires > now)
+    }
+
+    /// What the banned client gets told before the server hangs up.
+    #[must_use]
+    pub(crate) fn message(&self) -> String {
+        let mut message = match self.expires {
+            Some(expires) => format!("? login banned {expires}"),
+            None => "? login banned forever".to_string(),
+        };
+
+        if !self.reason.is_empty() {
+            message.push(' ');
+            message.push_str(&self.reason);
+        }
+
+        message
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct Bans(pub Vec<Ban>);
+
+impl Bans {
+    /// Replaces any ban on the same target and forgets the bans that ran
+    /// out.
+    pub(crate) fn add(&mut self, ban: Ban) {
+        let now = clock::now();
+        self.0
+            .retain(|old| old.active(now) && old.target != ban.target);
+
+        self.0.push(ban);
+    }
+
+    /// Whether there was a ban to lift.
+    pub(crate) fn remove(&mut self, target: &BanTarget) -> bool {
+        let length = self.0.len();
+        self.0.retain(|ban| ban.target != *target);
+
+        self.0.len() != length
+    }
+
+    /// The ban that keeps out the account or the address, if any.
+    #[must_use]
+    pub(crate) fn find(&self, username: Option<&str>, address: Option<IpAddr>) -> Option<&Ban> {
+        let now = clock::now();
+
+        self.0.iter().find(|ban| {
+            ban.active(now)
+                && match &ban.target {
+                    BanTarget::Account(banned) => Some(banned.as_str()) == username,
+                    BanTarget::Network(network) => {
+                        address.is_some_and(|address| network.contains(address))
+                    }
+                }
+        })
+    }
+}
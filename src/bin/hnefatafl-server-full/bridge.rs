@@ -0,0 +1,409 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! Bridges carry the global chat to an IRC channel or a Matrix room and back,
+//! so you can chat without launching the client. Like SMTP, they're set up in
+//! the server's data file.
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpStream, ToSocketAddrs},
+    sync::{
+        Arc, Mutex, MutexGuard,
+        mpsc::{self, Sender},
+    },
+    thread,
+    time::Duration,
+};
+
+use hnefatafl_copenhagen::server_game::Message;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+
+use crate::handle_error;
+
+const RETRY_SECONDS: u64 = 30;
+
+/// How long connecting to or writing to the IRC server may take.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long the IRC server may say nothing, it pings us more often than this.
+const READ_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// Somewhere else to carry the global chat.
+pub(crate) trait ChatTransport: Send + Sync {
+    /// Shown after the usernames of the people chatting from the other side.
+    fn name(&self) -> &'static str;
+
+    /// Sends a message from the global chat.
+    fn send(&self, message: &Message) -> anyhow::Result<()>;
+
+    /// Waits for messages from the other side, the username and text of each.
+    fn receive(&self) -> anyhow::Result<Vec<(String, String)>>;
+}
+
+/// A running bridge, where the server sends the global chat.
+#[derive(Clone, Debug)]
+pub(crate) struct Bridge {
+    pub name: &'static str,
+    pub tx: Sender<Message>,
+}
+
+impl Bridge {
+    /// Sends the messages it receives to the server as `bridge_text`, and
+    /// sends the global chat from its own thread, so a slow transport doesn't
+    /// hold up the server.
+    pub(crate) fn start(
+        transport: Arc<dyn ChatTransport>,
+        tx: Sender<(String, Option<Sender<String>>)>,
+    ) -> Self {
+        let name = transport.name();
+        let receiver = transport.clone();
+
+        thread::spawn(move || {
+            loop {
+                match receiver.receive() {
+                    Ok(messages) => {
+                        for (username, text) in messages {
+                            handle_error(tx.send((
+                                format!("0 server bridge_text {name} {username} {text}"),
+                                None,
+                            )));
+                        }
+                    }
+                    Err(error) => {
+                        error!("{name}: {error}");
+                        thread::sleep(Duration::from_secs(RETRY_SECONDS));
+                    }
+                }
+            }
+        });
+
+        let (message_tx, message_rx) = mpsc::channel::<Message>();
+
+        thread::spawn(move || {
+            for message in message_rx {
+                if let Err(error) = transport.send(&message) {
+                    error!("{name}: {error}");
+                }
+            }
+        });
+
+        info!("bridging the global chat to {name}");
+
+        Self {
+            name,
+            tx: message_tx,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct IrcBridge {
+    /// The host and port, such as `irc.libera.chat:6667`.
+    pub address: String,
+    pub nick: String,
+    pub channel: String,
+}
+
+#[derive(Debug)]
+pub(crate) struct Irc {
+    bridge: IrcBridge,
+    reader: Mutex<BufReader<TcpStream>>,
+    writer: Mutex<TcpStream>,
+}
+
+impl Irc {
+    pub(crate) fn connect(bridge: &IrcBridge) -> anyhow::Result<Self> {
+        let (reader, writer) = Self::register(bridge)?;
+
+        Ok(Self {
+            bridge: bridge.clone(),
+            reader: Mutex::new(reader),
+            writer: Mutex::new(writer),
+        })
+    }
+
+    /// Joins the channel once the server welcomes us.
+    fn register(bridge: &IrcBridge) -> anyhow::Result<(BufReader<TcpStream>, TcpStream)> {
+        let mut stream = Self::connect_timeout(&bridge.address)?;
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        let nick = &bridge.nick;
+        write!(stream, "NICK {nick}\r\nUSER {nick} 0 * :Hnefatafl Org\r\n")?;
+
+        Ok((BufReader::new(stream.try_clone()?), stream))
+    }
+
+    /// Tries each address the host resolves to, giving up on each after
+    /// `TIMEOUT`.
+    fn connect_timeout(address: &str) -> anyhow::Result<TcpStream> {
+        let mut error = anyhow::Error::msg(format!("{address} didn't resolve to an address"));
+
+        for address in address.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&address, TIMEOUT) {
+                Ok(stream) => return Ok(stream),
+                Err(error_new) => error = error_new.into(),
+            }
+        }
+
+        Err(error)
+    }
+
+    fn write(&self, line: &str) -> anyhow::Result<()> {
+        let mut writer = lock(&self.writer)?;
+        writer.write_all(format!("{line}\r\n").as_bytes())?;
+
+        Ok(())
+    }
+
+    fn reconnect(&self, reader: &mut BufReader<TcpStream>) -> anyhow::Result<()> {
+        let (reader_new, writer) = Self::register(&self.bridge)?;
+        *reader = reader_new;
+        *lock(&self.writer)? = writer;
+
+        Ok(())
+    }
+
+    /// Handles a line from the IRC server, returning the nick and text of a
+    /// message to the channel.
+    fn line(&self, line: &str) -> anyhow::Result<Option<(String, String)>> {
+        let line = line.trim_end_matches(['\r', '\n']);
+
+        if let Some(ping) = line.strip_prefix("PING ") {
+            self.write(&format!("PONG {ping}"))?;
+            return Ok(None);
+        }
+
+        let Some((prefix, rest)) = line.strip_prefix(':').and_then(|line| line.split_once(' '))
+        else {
+            return Ok(None);
+        };
+
+        let (command, parameters) = rest.split_once(' ').unwrap_or((rest, ""));
+
+        match command {
+            // RPL_WELCOME
+            "001" => self.write(&format!("JOIN {}", self.bridge.channel))?,
+            "PRIVMSG" => {
+                if let Some((target, text)) = parameters.split_once(" :")
+                    && target == self.bridge.channel
+                    && let Some(nick) = prefix.split('!').next()
+                {
+                    return Ok(Some((nick.to_string(), text.to_string())));
+                }
+            }
+            _ => {}
+        }
+
+        Ok(None)
+    }
+}
+
+impl ChatTransport for Irc {
+    fn name(&self) -> &'static str {
+        "IRC"
+    }
+
+    fn send(&self, message: &Message) -> anyhow::Result<()> {
+        // An IRC message is one line.
+        let text = message.content.lines().collect::<Vec<_>>().join(" ");
+        self.write(&format!(
+            "PRIVMSG {} :<{}> {text}",
+            self.bridge.channel, message.username
+        ))
+    }
+
+    fn receive(&self) -> anyhow::Result<Vec<(String, String)>> {
+        let mut reader = lock(&self.reader)?;
+        let mut line = String::new();
+
+        match reader.read_line(&mut line) {
+            Ok(0) => {
+                self.reconnect(&mut reader)?;
+                return Err(anyhow::Error::msg("the IRC server closed the connection"));
+            }
+            Ok(_) => {}
+            Err(error) => {
+                self.reconnect(&mut reader)?;
+                return Err(error.into());
+            }
+        }
+
+        drop(reader);
+        Ok(self.line(&line)?.into_iter().collect())
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct MatrixBridge {
+    /// Such as `https://matrix.org`.
+    pub homeserver: String,
+    /// The room ID, such as `!abcdefghijklmnop:matrix.org`, not an alias.
+    pub room: String,
+    pub access_token: String,
+}
+
+#[cfg(feature = "matrix")]
+pub(crate) use matrix::Matrix;
+
+#[cfg(feature = "matrix")]
+mod matrix {
+    use std::{
+        sync::{
+            Mutex,
+            atomic::{AtomicU64, Ordering},
+        },
+        time::Duration,
+    };
+
+    use hnefatafl_copenhagen::server_game::Message;
+    use reqwest::{Url, blocking::Client};
+    use serde_json::{Value, json};
+
+    use super::{ChatTransport, MatrixBridge, lock};
+
+    /// How long the homeserver may wait for new messages before answering.
+    const SYNC_MILLISECONDS: u64 = 30_000;
+
+    #[derive(Debug)]
+    pub(crate) struct Matrix {
+        bridge: MatrixBridge,
+        client: Client,
+        /// Our own user, so we don't carry our own messages back.
+        user_id: String,
+        since: Mutex<String>,
+        transaction: AtomicU64,
+    }
+
+    impl Matrix {
+        /// Skips what was said in the room before the server started.
+        pub(crate) fn connect(bridge: &MatrixBridge) -> anyhow::Result<Self> {
+            let client = Client::builder()
+                .timeout(Duration::from_millis(SYNC_MILLISECONDS * 2))
+                .build()?;
+
+            let mut matrix = Self {
+                bridge: bridge.clone(),
+                client,
+                user_id: String::new(),
+                since: Mutex::new(String::new()),
+                transaction: AtomicU64::new(0),
+            };
+
+            let whoami = matrix.get("account/whoami", &[])?;
+            matrix.user_id = whoami
+                .pointer("/user_id")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string();
+
+            let filter = json!({ "room": { "timeline": { "limit": 0 } } }).to_string();
+            let sync = matrix.get("sync", &[("filter", &filter), ("timeout", "0")])?;
+            *lock(&matrix.since)? = sync
+                .pointer("/next_batch")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string();
+
+            Ok(matrix)
+        }
+
+        fn get(&self, endpoint: &str, query: &[(&str, &str)]) -> anyhow::Result<Value> {
+            let url = Url::parse_with_params(
+                &format!("{}/_matrix/client/v3/{endpoint}", self.bridge.homeserver),
+                query,
+            )?;
+
+            let response = self
+                .client
+                .get(url)
+                .bearer_auth(&self.bridge.access_token)
+                .send()?
+                .error_for_status()?;
+
+            Ok(serde_json::from_str(&response.text()?)?)
+        }
+    }
+
+    impl ChatTransport for Matrix {
+        fn name(&self) -> &'static str {
+            "Matrix"
+        }
+
+        fn send(&self, message: &Message) -> anyhow::Result<()> {
+            let transaction = self.transaction.fetch_add(1, Ordering::Relaxed);
+            let body = json!({
+                "msgtype": "m.text",
+                "body": format!("<{}> {}", message.username, message.content),
+            });
+
+            self.client
+                .put(format!(
+                    "{}/_matrix/client/v3/rooms/{}/send/m.room.message/{}-{transaction}",
+                    self.bridge.homeserver,
+                    self.bridge.room,
+                    message.timestamp.as_millisecond(),
+                ))
+                .bearer_auth(&self.bridge.access_token)
+                .header("Content-Type", "application/json")
+                .body(body.to_string())
+                .send()?
+                .error_for_status()?;
+
+            Ok(())
+        }
+
+        fn receive(&self) -> anyhow::Result<Vec<(String, String)>> {
+            let since = lock(&self.since)?.clone();
+            let timeout = SYNC_MILLISECONDS.to_string();
+            let sync = self.get("sync", &[("since", &since), ("timeout", &timeout)])?;
+
+            let mut messages = Vec::new();
+            if let Some(events) = sync
+                .pointer("/rooms/join")
+                .and_then(|rooms| rooms.get(&self.bridge.room))
+                .and_then(|room| room.pointer("/timeline/events"))
+                .and_then(Value::as_array)
+            {
+                for event in events {
+                    if let (Some("m.room.message"), Some(sender), Some(body)) = (
+                        event.pointer("/type").and_then(Value::as_str),
+                        event.pointer("/sender").and_then(Value::as_str),
+                        event.pointer("/content/body").and_then(Value::as_str),
+                    ) && sender != self.user_id
+                    {
+                        messages.push((sender.to_string(), body.to_string()));
+                    }
+                }
+            }
+
+            if let Some(next_batch) = sync.pointer("/next_batch").and_then(Value::as_str) {
+                *lock(&self.since)? = next_batch.to_string();
+            }
+
+            Ok(messages)
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> anyhow::Result<MutexGuard<'_, T>> {
+    mutex
+        .lock()
+        .map_err(|error| anyhow::Error::msg(error.to_string()))
+}
@@ -0,0 +1,130 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! What players send in with Report Issue, for the owners to look over with
+//! `bug_reports` and clear once they have dealt with them. They are kept in a
+//! file of their own, as the logs in them are long.
+
+use std::{
+    ffi::OsString,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use hnefatafl_copenhagen::clock;
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// How many bug reports are kept, the oldest go first.
+const KEPT: usize = 100;
+
+/// How long a reporter waits between bug reports, in milliseconds.
+const BETWEEN: i64 = 10 * 60 * 1_000;
+
+/// How long a bug report may be, the log in it makes them long.
+pub(crate) const LENGTH: usize = 16_000;
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct BugReport {
+    pub reporter: String,
+    /// The version, operating system, game, and log the client gathered, and
+    /// what the reporter wrote about it.
+    pub report: String,
+    pub created: Timestamp,
+}
+
+/// How the bug reports were kept in the users file before they got a file of
+/// their own.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub(crate) struct BugReportsOld(pub Vec<BugReport>);
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct BugReports {
+    /// `None` when the server skips the data files.
+    path: Option<PathBuf>,
+    /// The oldest first.
+    pub reports: Vec<BugReport>,
+}
+
+impl BugReports {
+    pub(crate) fn load(path: &Path) -> anyhow::Result<Self> {
+        let reports = match fs::read_to_string(path) {
+            Ok(string) => ron::from_str(&string)?,
+            Err(error) if error.kind() == ErrorKind::NotFound => Vec::new(),
+            Err(error) => return Err(error.into()),
+        };
+
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            reports,
+        })
+    }
+
+    /// Whether `reporter` has waited long enough since their last bug report.
+    #[must_use]
+    pub(crate) fn may_add(&self, reporter: &str, now: Timestamp) -> bool {
+        !self.reports.iter().any(|bug_report| {
+            bug_report.reporter == reporter
+                && now.as_millisecond() - bug_report.created.as_millisecond() < BETWEEN
+        })
+    }
+
+    pub(crate) fn add(&mut self, reporter: &str, report: String) -> anyhow::Result<()> {
+        self.extend(vec![BugReport {
+            reporter: reporter.to_string(),
+            report,
+            created: clock::now(),
+        }])
+    }
+
+    pub(crate) fn extend(&mut self, bug_reports: Vec<BugReport>) -> anyhow::Result<()> {
+        if bug_reports.is_empty() {
+            return Ok(());
+        }
+
+        self.reports.extend(bug_reports);
+        if self.reports.len() > KEPT {
+            self.reports.drain(..self.reports.len() - KEPT);
+        }
+
+        self.save()
+    }
+
+    /// Forgets the bug reports, returning whether there were any.
+    pub(crate) fn clear(&mut self) -> anyhow::Result<bool> {
+        let cleared = !self.reports.is_empty();
+        self.reports.clear();
+        self.save()?;
+
+        Ok(cleared)
+    }
+
+    /// Writes the file next to the old one and moves it over it.
+    fn save(&self) -> anyhow::Result<()> {
+        if let Some(path) = &self.path {
+            let mut new = OsString::from(path);
+            new.push(".new");
+
+            fs::write(&new, ron::ser::to_string(&self.reports)?)?;
+            fs::rename(new, path)?;
+        }
+
+        Ok(())
+    }
+}
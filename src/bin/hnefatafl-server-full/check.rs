@@ -0,0 +1,361 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! Looks over the data files for what would keep the server from starting or
+//! trip it up later. The corrupt records can be moved aside into a
+//! `.quarantine-SECONDS` file next to the one they came from, so the server
+//! starts with the rest. A users file is only moved aside whole when it can't
+//! be parsed once its corrupt accounts are taken out.
+
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::OsString,
+    fs::{self, File},
+    io::{BufRead, BufReader, BufWriter, ErrorKind, Write},
+    path::{Path, PathBuf},
+};
+
+use hnefatafl_copenhagen::{
+    Id,
+    accounts::Account,
+    community::COMMUNITY,
+    server_game::{ArchivedGame, ServerGameSerialized},
+};
+use jiff::Timestamp;
+
+use crate::{ACTIVE_GAMES_FILE, ARCHIVED_GAMES_FILE, Server, USERS_FILE};
+
+struct Check<F> {
+    data_file: F,
+    quarantine: bool,
+    problems: Vec<String>,
+}
+
+/// Returns the problems it found, and fixes them if `quarantine` is set.
+pub(crate) fn check<F: Fn(&str) -> PathBuf>(
+    data_file: F,
+    quarantine: bool,
+) -> anyhow::Result<Vec<String>> {
+    let mut check = Check {
+        data_file,
+        quarantine,
+        problems: Vec::new(),
+    };
+
+    let mut server = check.users()?;
+    let archived = check.archived_games()?;
+    let active = check.active_games(&archived, server.as_ref())?;
+
+    if let Some(server) = &mut server {
+        check.references(server, archived.keys().chain(active.iter()))?;
+    }
+
+    Ok(check.problems)
+}
+
+impl<F: Fn(&str) -> PathBuf> Check<F> {
+    fn problem(&mut self, path: &Path, problem: &str) {
+        self.problems.push(format!("{}: {problem}", path.display()));
+    }
+
+    fn users(&mut self) -> anyhow::Result<Option<Server>> {
+        let path = (self.data_file)(USERS_FILE);
+
+        let string = match fs::read_to_string(&path) {
+            Ok(string) => string,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error.into()),
+        };
+
+        let error = match ron::from_str::<Server>(&string) {
+            Ok(mut server) => {
+                server.migrate()?;
+                return Ok(Some(server));
+            }
+            Err(error) => error,
+        };
+
+        let (kept, corrupt) = accounts_split(&string);
+        if !corrupt.is_empty()
+            && let Ok(mut server) = ron::from_str::<Server>(&kept)
+        {
+            for (username, _) in &corrupt {
+                self.problem(&path, &format!("the account {username} can't be parsed"));
+            }
+
+            if self.quarantine {
+                let corrupt: Vec<_> = corrupt.into_iter().map(|(_, account)| account).collect();
+                fs::write(quarantined(&path), corrupt.join("\n") + "\n")?;
+                replace(&path, kept.as_bytes())?;
+            }
+
+            server.migrate()?;
+            return Ok(Some(server));
+        }
+
+        self.problem(&path, &format!("can't be parsed: {error}"));
+
+        if self.quarantine {
+            fs::rename(&path, quarantined(&path))?;
+        }
+
+        Ok(None)
+    }
+
+    /// The players of each archived game.
+    fn archived_games(&mut self) -> anyhow::Result<HashMap<Id, (String, String)>> {
+        let path = (self.data_file)(ARCHIVED_GAMES_FILE);
+
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(HashMap::new()),
+            Err(error) => return Err(error.into()),
+        };
+
+        let mut games = HashMap::new();
+        let mut corrupt = HashSet::new();
+
+        for (number, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            let number = number + 1;
+
+            let game: ArchivedGame = match ron::from_str(&line) {
+                Ok(game) => game,
+                Err(error) => {
+                    self.problem(&path, &format!("line {number} can't be parsed: {error}"));
+                    corrupt.insert(number);
+                    continue;
+                }
+            };
+
+            // A game gets written again once it has been analyzed, but by the
+            // same players.
+            let players = (game.attacker, game.defender);
+            match games.get(&game.id) {
+                Some(old) if *old != players => {
+                    self.problem(
+                        &path,
+                        &format!("line {number} reuses the id of game {}", game.id),
+                    );
+                    corrupt.insert(number);
+                }
+                _ => {
+                    games.insert(game.id, players);
+                }
+            }
+        }
+
+        if self.quarantine && !corrupt.is_empty() {
+            let checked = with_extension(&path, ".checked");
+            let mut kept = BufWriter::new(File::create(&checked)?);
+            let mut quarantine = BufWriter::new(File::create(quarantined(&path))?);
+
+            for (number, line) in BufReader::new(File::open(&path)?).lines().enumerate() {
+                let line = line?;
+
+                if corrupt.contains(&(number + 1)) {
+                    writeln!(quarantine, "{line}")?;
+                } else {
+                    writeln!(kept, "{line}")?;
+                }
+            }
+
+            kept.flush()?;
+            quarantine.flush()?;
+            fs::rename(checked, &path)?;
+        }
+
+        Ok(games)
+    }
+
+    /// The ids of the games that are still going.
+    fn active_games(
+        &mut self,
+        archived: &HashMap<Id, (String, String)>,
+        server: Option<&Server>,
+    ) -> anyhow::Result<Vec<Id>> {
+        let path = (self.data_file)(ACTIVE_GAMES_FILE);
+
+        let data = match fs::read(&path) {
+            Ok(data) => data,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error.into()),
+        };
+
+        let games = match ServerGameSerialized::from_postcard(&data) {
+            Ok(games) => games,
+            Err(error) => {
+                self.problem(&path, &format!("can't be parsed: {error}"));
+
+                if self.quarantine {
+                    fs::rename(&path, quarantined(&path))?;
+                }
+
+                return Ok(Vec::new());
+            }
+        };
+
+        let mut ids = HashSet::new();
+        let mut kept = Vec::new();
+        let mut corrupt = Vec::new();
+
+        for game in games {
+            let missing = [&game.attacker, &game.defender]
+                .into_iter()
+                .find(|username| {
+                    server.is_some_and(|server| {
+                        username.as_str() != COMMUNITY && !server.accounts.0.contains_key(*username)
+                    })
+                });
+
+            let problem = if !ids.insert(game.id) {
+                Some(format!("game {} is there more than once", game.id))
+            } else if archived.contains_key(&game.id) {
+                Some(format!("game {} is already archived", game.id))
+            } else {
+                missing.map(|username| format!("game {} has no account {username}", game.id))
+            };
+
+            if let Some(problem) = problem {
+                self.problem(&path, &problem);
+                corrupt.push(game);
+            } else {
+                kept.push(game);
+            }
+        }
+
+        if self.quarantine && !corrupt.is_empty() {
+            fs::write(quarantined(&path), postcard::to_allocvec(&corrupt)?)?;
+            replace(&path, &postcard::to_allocvec(&kept)?)?;
+        }
+
+        Ok(kept.iter().map(|game| game.id).collect())
+    }
+
+    /// The roles of accounts that are gone and a next game id that is
+    /// already taken.
+    fn references<'a>(
+        &mut self,
+        server: &mut Server,
+        ids: impl Iterator<Item = &'a Id>,
+    ) -> anyhow::Result<()> {
+        let path = (self.data_file)(USERS_FILE);
+        let mut changed = false;
+
+        let gone: Vec<_> = server
+            .roles
+            .0
+            .keys()
+            .filter(|username| {
+                username.as_str() != "server" && !server.accounts.0.contains_key(*username)
+            })
+            .cloned()
+            .collect();
+
+        for username in gone {
+            self.problem(&path, &format!("{username} has a role but no account"));
+            server.roles.0.remove(&username);
+            changed = true;
+        }
+
+        if let Some(id) = ids.max()
+            && server.game_id <= *id
+        {
+            self.problem(
+                &path,
+                &format!("the next game id {} is already taken", server.game_id),
+            );
+            server.game_id = id + 1;
+            changed = true;
+        }
+
+        if self.quarantine && changed {
+            replace(&path, server.users_ron()?.as_bytes())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Splits the accounts that can't be parsed out of a users file written by
+/// [`Server::users_ron`], where each account starts on a line of its own, and
+/// returns the rest of the file and the usernames with their accounts.
+fn accounts_split(string: &str) -> (String, Vec<(String, String)>) {
+    let mut kept = String::new();
+    let mut corrupt = Vec::new();
+    let mut accounts = false;
+    let mut lines = string.lines();
+
+    while let Some(line) = lines.next() {
+        let indent = line.len() - line.trim_start().len();
+
+        if indent == 4 {
+            accounts = line.trim_start().starts_with("accounts:");
+        } else if accounts && indent == 8 && line.trim_start().starts_with('"') {
+            let mut account = vec![line];
+            if line.ends_with('(') {
+                for line in lines.by_ref() {
+                    account.push(line);
+                    if line.len() - line.trim_start().len() == 8 {
+                        break;
+                    }
+                }
+            }
+
+            let account = account.join("\n");
+            if let Some((username, value)) = account.trim_start().split_once(": ")
+                && ron::from_str::<Account>(value.trim_end_matches(',')).is_err()
+            {
+                corrupt.push((username.trim_matches('"').to_string(), account));
+                continue;
+            }
+
+            kept.push_str(&account);
+            kept.push('\n');
+            continue;
+        }
+
+        kept.push_str(line);
+        kept.push('\n');
+    }
+
+    (kept, corrupt)
+}
+
+/// Writes `contents` next to `path` and moves it over `path`, so a failed
+/// write doesn't leave the file half written.
+fn replace(path: &Path, contents: &[u8]) -> anyhow::Result<()> {
+    let checked = with_extension(path, ".checked");
+    fs::write(&checked, contents)?;
+    fs::rename(checked, path)?;
+
+    Ok(())
+}
+
+fn with_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut path = OsString::from(path);
+    path.push(extension);
+    path.into()
+}
+
+fn quarantined(path: &Path) -> PathBuf {
+    with_extension(
+        path,
+        &format!(".quarantine-{}", Timestamp::now().as_second()),
+    )
+}
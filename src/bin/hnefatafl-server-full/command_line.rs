@@ -16,10 +16,10 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 // SPDX-FileCopyrightText: 2025 David Campbell <david@hnefatafl.org>
 
-use std::io::Write as _;
+use std::{io::Write as _, path::PathBuf};
 
 use clap::{CommandFactory, Parser};
-use hnefatafl_copenhagen::{COPYRIGHT, LONG_VERSION};
+use hnefatafl_copenhagen::{COPYRIGHT, LONG_VERSION, proof_of_work};
 
 /// Copenhagen Hnefatafl Server
 ///
@@ -32,10 +32,61 @@ pub(crate) struct Args {
     #[arg(long)]
     pub autostart_tournament: bool,
 
+    /// An IP address, or an IP address and a port, to listen on, give it more
+    /// than once to listen on more [default: every IPv6 and IPv4 address]
+    #[arg(long, value_name = "ADDRESS")]
+    pub bind: Vec<String>,
+
+    /// The config file to read instead of server.ron in the config folder
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Check the data files, print the problems, and exit
+    #[arg(long)]
+    pub check: bool,
+
     /// Whether to log on the debug level
     #[arg(long)]
     pub debug: bool,
 
+    /// Take over from the server running on the same port, keeping its games
+    /// going
+    #[arg(long)]
+    pub handoff: bool,
+
+    /// Write every message the server handles to a journal, next to a snapshot
+    /// of the data files it starts from. Only the journal of the run before is
+    /// kept, as PATH.old
+    #[arg(long, value_name = "PATH")]
+    pub journal: Option<PathBuf>,
+
+    /// The port to listen on [default: 49152]
+    #[arg(long)]
+    pub port: Option<u16>,
+
+    /// How many leading zero bits the proof of work for creating an account
+    /// needs, zero to turn it off [default: 20]
+    #[arg(
+        long,
+        value_name = "BITS",
+        value_parser = clap::value_parser!(u8).range(..=i64::from(proof_of_work::BITS_MAX))
+    )]
+    pub proof_of_work_bits: Option<u8>,
+
+    /// Move the corrupt records in the data files aside before starting,
+    /// instead of refusing to start
+    #[arg(long)]
+    pub quarantine: bool,
+
+    /// Feed a journal into a server loaded from its snapshot and print the
+    /// replies, then exit
+    #[arg(long, value_name = "PATH")]
+    pub replay: Option<PathBuf>,
+
+    /// Validate a backup and restore the data files from it before starting
+    #[arg(long, value_name = "PATH")]
+    pub restore: Option<PathBuf>,
+
     /// Whether to skip advertising updates
     #[arg(long)]
     pub skip_advertising_updates: bool,
@@ -48,6 +99,16 @@ pub(crate) struct Args {
     #[arg(long)]
     pub systemd: bool,
 
+    /// The port to listen on for the one click unsubscribe links in emails
+    #[arg(long, value_name = "PORT")]
+    pub unsubscribe_port: Option<u16>,
+
+    /// The address the unsubscribe links in emails point to, the web server
+    /// proxies it to the unsubscribe port [default: the unsubscribe page on
+    /// hnefatafl.org]
+    #[arg(long, value_name = "URL")]
+    pub unsubscribe_url: Option<String>,
+
     /// Build the manpage
     #[arg(long)]
     pub man: bool,
@@ -0,0 +1,214 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! The settings in `server.ron` in the config folder. Whatever is left out
+//! keeps its default and the command line flags win over the file. An owner
+//! can reload the file with `config_reload`, but the addresses, the proof of
+//! work, the journal, the data folder, and how many archived games are kept
+//! only change on a restart.
+
+use std::{
+    fs,
+    io::ErrorKind,
+    net::{IpAddr, SocketAddr},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use hnefatafl_copenhagen::{PORT, proof_of_work, utils::config_file};
+use serde::{Deserialize, Serialize};
+
+use crate::{backup::BACKUPS_KEPT, command_line::Args, smtp::Smtp};
+
+pub(crate) const CONFIG_FILE: &str = "server.ron";
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub(crate) struct Config {
+    /// The addresses to listen on, each an IP address or an IP address and a
+    /// port. Every IPv6 and IPv4 address if there are none.
+    pub bind: Vec<String>,
+    /// The port to listen on.
+    pub port: Option<u16>,
+    /// The port to listen on for the one click unsubscribe links in emails.
+    pub unsubscribe_port: Option<u16>,
+    /// The address the unsubscribe links in emails point to.
+    pub unsubscribe_url: String,
+    /// How many leading zero bits the proof of work for creating an account
+    /// needs.
+    pub proof_of_work_bits: u8,
+    pub journal: Option<PathBuf>,
+    /// Where to keep the data files instead of the usual place.
+    pub data_folder: Option<PathBuf>,
+    /// How many messages the global chat keeps.
+    pub keep_texts: usize,
+    /// The longest a chat message may be, in bytes.
+    pub message_length: usize,
+    /// How long to wait between messages in the chat of a tournament game.
+    pub slow_mode_tournament_seconds: u64,
+    /// How long a player may take over a move before they lose the game.
+    pub abandoned_after_days: i64,
+    /// The most of the round trip a player last measured with a ping that
+    /// doesn't count against their time on a move. 0 turns it off.
+    pub lag_compensation_milliseconds: i64,
+    /// How long an opponent has to be disconnected before you can claim the
+    /// game.
+    pub claim_after_seconds: i64,
+    pub backups_kept: usize,
+    /// How many of each player's last games the archive keeps, the older ones
+    /// get pruned on a restart. All of them if there is none.
+    pub archived_games_kept: Option<usize>,
+    /// Used instead of the SMTP settings in the users file.
+    pub smtp: Option<Smtp>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            bind: Vec::new(),
+            port: None,
+            unsubscribe_port: None,
+            unsubscribe_url: "https://hnefatafl.org/unsubscribe".to_string(),
+            proof_of_work_bits: proof_of_work::BITS,
+            journal: None,
+            data_folder: None,
+            keep_texts: 256,
+            message_length: 1024,
+            slow_mode_tournament_seconds: 10,
+            abandoned_after_days: 7,
+            lag_compensation_milliseconds: 1_000,
+            claim_after_seconds: 60,
+            backups_kept: BACKUPS_KEPT,
+            archived_games_kept: None,
+            smtp: None,
+        }
+    }
+}
+
+impl Config {
+    /// Reads `--config` or else `server.ron` in the config folder, which may
+    /// be missing, then applies the command line flags.
+    pub(crate) fn load(args: &Args) -> anyhow::Result<Self> {
+        let mut config = match &args.config {
+            Some(path) => Self::read(path)?,
+            None => match Self::read(&config_file(CONFIG_FILE)) {
+                Err(error)
+                    if error
+                        .downcast_ref::<std::io::Error>()
+                        .is_some_and(|error| error.kind() == ErrorKind::NotFound) =>
+                {
+                    Self::default()
+                }
+                result => result?,
+            },
+        };
+
+        if !args.bind.is_empty() {
+            config.bind.clone_from(&args.bind);
+        }
+        if args.port.is_some() {
+            config.port = args.port;
+        }
+        if args.unsubscribe_port.is_some() {
+            config.unsubscribe_port = args.unsubscribe_port;
+        }
+        if let Some(url) = &args.unsubscribe_url {
+            config.unsubscribe_url.clone_from(url);
+        }
+        if let Some(bits) = args.proof_of_work_bits {
+            config.proof_of_work_bits = bits;
+        }
+        if args.journal.is_some() {
+            config.journal.clone_from(&args.journal);
+        }
+
+        config.listen_addresses()?;
+
+        // The clients won't solve for more.
+        if config.proof_of_work_bits > proof_of_work::BITS_MAX {
+            return Err(anyhow::Error::msg(format!(
+                "proof_of_work_bits has to be from 0 to {}",
+                proof_of_work::BITS_MAX
+            )));
+        }
+
+        Ok(config)
+    }
+
+    fn read(path: &Path) -> anyhow::Result<Self> {
+        let string = fs::read_to_string(path)?;
+
+        ron::from_str(&string)
+            .map_err(|error| anyhow::Error::msg(format!("RON: {}: {error}", path.display())))
+    }
+
+    /// The addresses in `bind`, on `port` unless they have their own.
+    pub(crate) fn listen_addresses(&self) -> anyhow::Result<Vec<SocketAddr>> {
+        let port = self.port.unwrap_or(PORT);
+
+        self.bind
+            .iter()
+            .map(|address| {
+                SocketAddr::from_str(address)
+                    .or_else(|_| IpAddr::from_str(address).map(|ip| SocketAddr::new(ip, port)))
+                    .map_err(|error| anyhow::Error::msg(format!("bind {address}: {error}")))
+            })
+            .collect()
+    }
+
+    /// How long a player may take over a move, in milliseconds.
+    #[must_use]
+    pub(crate) fn abandoned_after(&self) -> i64 {
+        self.abandoned_after_days
+            .saturating_mul(24 * 60 * 60 * 1_000)
+    }
+
+    /// The SMTP settings in the config file win over the ones in the users
+    /// file.
+    #[must_use]
+    pub(crate) fn smtp<'a>(&'a self, users_file: &'a Smtp) -> &'a Smtp {
+        self.smtp.as_ref().unwrap_or(users_file)
+    }
+
+    /// The settings that differ from `old` but only change on a restart.
+    #[must_use]
+    pub(crate) fn needs_restart(&self, old: &Self) -> Vec<&'static str> {
+        let mut settings = Vec::new();
+
+        if self.bind != old.bind {
+            settings.push("bind");
+        }
+        if self.port != old.port {
+            settings.push("port");
+        }
+        if self.unsubscribe_port != old.unsubscribe_port {
+            settings.push("unsubscribe_port");
+        }
+        if self.proof_of_work_bits != old.proof_of_work_bits {
+            settings.push("proof_of_work_bits");
+        }
+        if self.journal != old.journal {
+            settings.push("journal");
+        }
+        if self.data_folder != old.data_folder {
+            settings.push("data_folder");
+        }
+
+        settings
+    }
+}
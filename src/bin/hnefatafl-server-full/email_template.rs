@@ -0,0 +1,260 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! The emails the server sends. Each one has a plain text part and an HTML
+//! part in the same layout, written in the locale of the account it goes to.
+
+use std::fmt::Write as _;
+
+use hnefatafl_copenhagen::{Id, locale::Locale};
+use jiff::Timestamp;
+use lettre::message::{
+    MessageBuilder, MultiPart,
+    header::{Header, HeaderName, HeaderValue},
+};
+use rust_i18n::t;
+
+const FROM: &str = "Hnefatafl Org <noreply@hnefatafl.org>";
+const SITE: &str = "hnefatafl.org";
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum EmailTemplate {
+    AccountVerification {
+        username: String,
+        code: String,
+    },
+    /// Written by an admin, so only the layout gets translated.
+    Announcement {
+        subject: String,
+        text: String,
+    },
+    PasswordReset {
+        username: String,
+        password: String,
+    },
+    TournamentReminder {
+        username: String,
+        date: Timestamp,
+    },
+    TurnNotification {
+        username: String,
+        opponent: String,
+        id: Id,
+    },
+}
+
+impl EmailTemplate {
+    pub(crate) fn subject(&self, locale: Locale) -> String {
+        let locale = locale.txt();
+
+        match self {
+            Self::AccountVerification { .. } => t!("Account Verification", locale = &locale),
+            Self::Announcement { subject, .. } => subject.into(),
+            Self::PasswordReset { .. } => t!("Password Reset", locale = &locale),
+            Self::TournamentReminder { .. } => t!("Tournament Reminder", locale = &locale),
+            Self::TurnNotification { .. } => t!("Your Turn", locale = &locale),
+        }
+        .to_string()
+    }
+
+    fn body(&self, locale: Locale) -> String {
+        let locale = locale.txt();
+
+        match self {
+            Self::AccountVerification { username, code } => t!(
+                "Dear %{username},\nyour email verification code is as follows: %{code}",
+                locale = &locale,
+                username = username,
+                code = code,
+            ),
+            Self::Announcement { text, .. } => text.into(),
+            Self::PasswordReset { username, password } => t!(
+                "Dear %{username},\nyour new password is as follows: %{password}",
+                locale = &locale,
+                username = username,
+                password = password,
+            ),
+            Self::TournamentReminder { username, date } => t!(
+                "Dear %{username},\nthe tournament you joined starts at %{date}.",
+                locale = &locale,
+                username = username,
+                date = date.strftime("%Y-%m-%d %H:%M UTC"),
+            ),
+            Self::TurnNotification {
+                username,
+                opponent,
+                id,
+            } => t!(
+                "Dear %{username},\n%{opponent} moved, it's your turn in game %{id}.",
+                locale = &locale,
+                username = username,
+                opponent = opponent,
+                id = id,
+            ),
+        }
+        .to_string()
+    }
+
+    fn footer(locale: Locale) -> String {
+        t!(
+            "You are receiving this email because you have an account on %{site}.",
+            locale = &locale.txt(),
+            site = SITE,
+        )
+        .to_string()
+    }
+
+    pub(crate) fn text(&self, locale: Locale, unsubscribe: Option<&str>) -> String {
+        let mut text = format!(
+            "{}\n\n-- \nHnefatafl Org\n{}\n",
+            self.body(locale),
+            Self::footer(locale)
+        );
+
+        if let Some(url) = unsubscribe {
+            let _ = writeln!(text, "{}: {url}", t!("Unsubscribe", locale = &locale.txt()));
+        }
+
+        text
+    }
+
+    pub(crate) fn html(&self, locale: Locale, unsubscribe: Option<&str>) -> String {
+        let dir = if locale == Locale::Arabic {
+            "rtl"
+        } else {
+            "ltr"
+        };
+        let lang = locale.txt();
+
+        let mut paragraphs = String::new();
+        for paragraph in self.body(locale).split("\n\n") {
+            let lines: Vec<_> = paragraph.lines().map(escape).collect();
+            let _ = writeln!(paragraphs, "<p>{}</p>", lines.join("<br>\n"));
+        }
+
+        let unsubscribe = unsubscribe.map_or_else(String::new, |url| {
+            format!(
+                "<br>\n<a href=\"{}\">{}</a>",
+                escape(url),
+                escape(&t!("Unsubscribe", locale = &lang))
+            )
+        });
+
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="{lang}" dir="{dir}">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+</head>
+<body style="margin: 0; padding: 0; background-color: #f4f1ea;">
+<div style="max-width: 600px; margin: 0 auto; font-family: sans-serif; color: #222222;">
+<div style="padding: 16px 24px; background-color: #3b2f2f; color: #f4f1ea; font-size: 24px;">Hnefatafl Org</div>
+<div style="padding: 16px 24px; background-color: #ffffff; font-size: 16px;">
+{paragraphs}</div>
+<div style="padding: 16px 24px; font-size: 12px; color: #666666;">{footer} <a href="https://{SITE}/">{SITE}</a>{unsubscribe}</div>
+</div>
+</body>
+</html>
+"#,
+            title = escape(&self.subject(locale)),
+            footer = escape(&Self::footer(locale)),
+        )
+    }
+
+    /// Fills in the sender, subject, and body of a message that already has
+    /// its recipients. Emails someone can unsubscribe from come with a one
+    /// click unsubscribe link.
+    pub(crate) fn message(
+        &self,
+        mut builder: MessageBuilder,
+        locale: Locale,
+        unsubscribe: Option<&str>,
+    ) -> anyhow::Result<lettre::Message> {
+        if let Some(url) = unsubscribe {
+            builder = builder
+                .header(ListUnsubscribe(url.to_string()))
+                .header(ListUnsubscribePost);
+        }
+
+        Ok(builder
+            .from(FROM.parse()?)
+            .subject(self.subject(locale))
+            .multipart(MultiPart::alternative_plain_html(
+                self.text(locale, unsubscribe),
+                self.html(locale, unsubscribe),
+            ))?)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct ListUnsubscribe(String);
+
+impl Header for ListUnsubscribe {
+    fn name() -> HeaderName {
+        HeaderName::new_from_ascii_str("List-Unsubscribe")
+    }
+
+    fn parse(s: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self(
+            s.trim()
+                .trim_start_matches('<')
+                .trim_end_matches('>')
+                .to_string(),
+        ))
+    }
+
+    fn display(&self) -> HeaderValue {
+        HeaderValue::new(Self::name(), format!("<{}>", self.0))
+    }
+}
+
+/// Asks mail clients to unsubscribe with a POST, RFC 8058.
+#[derive(Clone, Debug)]
+struct ListUnsubscribePost;
+
+impl Header for ListUnsubscribePost {
+    fn name() -> HeaderName {
+        HeaderName::new_from_ascii_str("List-Unsubscribe-Post")
+    }
+
+    fn parse(_s: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self)
+    }
+
+    fn display(&self) -> HeaderValue {
+        HeaderValue::new(Self::name(), "List-Unsubscribe=One-Click".to_string())
+    }
+}
+
+fn escape(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len());
+
+    for ch in string.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            ch => escaped.push(ch),
+        }
+    }
+
+    escaped
+}
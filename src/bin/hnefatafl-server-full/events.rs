@@ -0,0 +1,134 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! Recurring events, like a casual arena every Thursday at 19:00 in Berlin.
+//! A day before each one, if no other tournament is set up, the server sets up
+//! the arena for it, which goes out as the announcement in the lobby, and
+//! starts it on time. The arena stays up with its standings until the next
+//! event takes its place.
+
+use std::collections::BTreeMap;
+
+use hnefatafl_copenhagen::{board::BoardSize, rating::Rated, time::TimeSettings};
+use jiff::{
+    SignedDuration, Timestamp,
+    civil::{self, Weekday},
+    tz::TimeZone,
+};
+use serde::{Deserialize, Serialize};
+
+/// How long before an event its arena gets set up.
+pub(crate) const AHEAD: SignedDuration = SignedDuration::from_hours(24);
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) enum Every {
+    Day,
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Every {
+    fn weekday(self) -> Option<Weekday> {
+        match self {
+            Self::Day => None,
+            Self::Monday => Some(Weekday::Monday),
+            Self::Tuesday => Some(Weekday::Tuesday),
+            Self::Wednesday => Some(Weekday::Wednesday),
+            Self::Thursday => Some(Weekday::Thursday),
+            Self::Friday => Some(Weekday::Friday),
+            Self::Saturday => Some(Weekday::Saturday),
+            Self::Sunday => Some(Weekday::Sunday),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct Event {
+    pub name: String,
+    /// Who set it up, the only one besides the tournament directors who may
+    /// change it.
+    #[serde(default)]
+    pub owner: String,
+    pub every: Every,
+    /// When it starts on the day, in `time_zone`.
+    pub time: civil::Time,
+    /// A time zone name like `Europe/Berlin`.
+    pub time_zone: String,
+    /// How long the arena lasts.
+    pub minutes: i64,
+    pub board_size: BoardSize,
+    pub time_setting: TimeSettings,
+    #[serde(default)]
+    pub rated: Rated,
+    /// The club the players have to be members of.
+    #[serde(default)]
+    pub club: Option<String>,
+}
+
+impl Event {
+    /// When it next starts after `after`.
+    ///
+    /// # Errors
+    ///
+    /// If the time zone doesn't exist.
+    pub(crate) fn next(&self, after: Timestamp) -> anyhow::Result<Timestamp> {
+        let time_zone = TimeZone::get(&self.time_zone)?;
+        let mut date = after.to_zoned(time_zone.clone()).date();
+
+        for _ in 0..8 {
+            let start = date
+                .to_datetime(self.time)
+                .to_zoned(time_zone.clone())?
+                .timestamp();
+
+            if start > after
+                && self
+                    .every
+                    .weekday()
+                    .is_none_or(|weekday| date.weekday() == weekday)
+            {
+                return Ok(start);
+            }
+
+            date = date.tomorrow()?;
+        }
+
+        Err(anyhow::Error::msg(format!("{} never starts", self.name)))
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct Events(pub BTreeMap<String, Event>);
+
+impl Events {
+    /// The event that starts the soonest after `now` and within [`AHEAD`] of
+    /// it.
+    #[must_use]
+    pub(crate) fn due(&self, now: Timestamp) -> Option<(&Event, Timestamp)> {
+        self.0
+            .values()
+            .filter_map(|event| event.next(now).ok().map(|start| (event, start)))
+            .filter(|(_, start)| start.duration_since(now) <= AHEAD)
+            .min_by_key(|(_, start)| *start)
+    }
+}
@@ -0,0 +1,295 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! Ratings, results, and tournament standings as CSV or JSON, for
+//! spreadsheets and for embedding on a community website. A CSV file goes out
+//! on one line with `\n` between the rows, the same as the emails to
+//! everyone.
+
+use std::{fmt::Write as _, str::FromStr};
+
+use hnefatafl_copenhagen::{accounts::Accounts, server_game::ArchivedGame, tournament::Tournament};
+use serde_json::{Map, Value, json};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Format {
+    Csv,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(format: &str) -> anyhow::Result<Self> {
+        match format {
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            _ => Err(anyhow::Error::msg(format!("{format} isn't csv or json"))),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Table {
+    header: Vec<&'static str>,
+    rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    /// Everyone with a rated game, the highest rated first.
+    pub(crate) fn ratings(accounts: &Accounts) -> Self {
+        let mut accounts: Vec<_> = accounts
+            .0
+            .iter()
+            .filter(|(_, account)| account.rated_games > 0)
+            .collect();
+
+        accounts.sort_by(|(name_1, account_1), (name_2, account_2)| {
+            account_2
+                .rating
+                .rating
+                .total_cmp(&account_1.rating.rating)
+                .then_with(|| name_1.cmp(name_2))
+        });
+
+        Self {
+            header: vec![
+                "rank",
+                "username",
+                "rating",
+                "deviation",
+                "wins",
+                "losses",
+                "draws",
+                "rated_games",
+            ],
+            rows: accounts
+                .into_iter()
+                .enumerate()
+                .map(|(rank, (username, account))| {
+                    vec![
+                        json!(rank + 1),
+                        json!(username),
+                        json!(account.rating.rating.round()),
+                        json!(account.rating.rd.round()),
+                        json!(account.wins),
+                        json!(account.losses),
+                        json!(account.draws),
+                        json!(account.rated_games),
+                    ]
+                })
+                .collect(),
+        }
+    }
+
+    /// Every account, emails included except for kid-safe accounts, by
+    /// username.
+    pub(crate) fn accounts(accounts: &Accounts) -> Self {
+        let mut accounts: Vec<_> = accounts.0.iter().collect();
+        accounts.sort_by_key(|(name, _)| *name);
+
+        Self {
+            header: vec![
+                "username",
+                "email",
+                "verified",
+                "rating",
+                "deviation",
+                "wins",
+                "losses",
+                "draws",
+                "rated_games",
+                "created",
+                "last_logged_in",
+            ],
+            rows: accounts
+                .into_iter()
+                .map(|(username, account)| {
+                    let (email, verified) = account
+                        .email
+                        .as_ref()
+                        .filter(|_| !account.kid_safe)
+                        .map_or((String::new(), false), |email| {
+                            (email.address.clone(), email.verified)
+                        });
+
+                    vec![
+                        json!(username),
+                        json!(email),
+                        json!(verified),
+                        json!(account.rating.rating.round()),
+                        json!(account.rating.rd.round()),
+                        json!(account.wins),
+                        json!(account.losses),
+                        json!(account.draws),
+                        json!(account.rated_games),
+                        json!(account.creation_date.0.to_string()),
+                        json!(account.last_logged_in.0.to_string()),
+                    ]
+                })
+                .collect(),
+        }
+    }
+
+    pub(crate) fn results(games: &[ArchivedGame]) -> Self {
+        Self {
+            header: vec![
+                "id",
+                "attacker",
+                "attacker_rating",
+                "defender",
+                "defender_rating",
+                "rated",
+                "board_size",
+                "result",
+                "moves",
+            ],
+            rows: games
+                .iter()
+                .map(|game| {
+                    vec![
+                        json!(game.id.to_string()),
+                        json!(game.attacker),
+                        json!(game.attacker_rating.rating.round()),
+                        json!(game.defender),
+                        json!(game.defender_rating.rating.round()),
+                        json!(game.rated.to_string()),
+                        json!(game.board_size.to_string()),
+                        json!(game.status.to_string()),
+                        json!(game.plays.made().len()),
+                    ]
+                })
+                .collect(),
+        }
+    }
+
+    /// The arena leaderboard, or else the players in each group of each
+    /// round, the highest score first.
+    pub(crate) fn standings(tournament: &Tournament) -> Self {
+        if let Some(arena) = &tournament.arena {
+            return Self {
+                header: vec!["rank", "username", "points", "games", "streak"],
+                rows: arena
+                    .leaderboard()
+                    .into_iter()
+                    .enumerate()
+                    .map(|(rank, (username, score))| {
+                        vec![
+                            json!(rank + 1),
+                            json!(username),
+                            json!(score.points),
+                            json!(score.games),
+                            json!(score.streak),
+                        ]
+                    })
+                    .collect(),
+            };
+        }
+
+        let mut rows = Vec::new();
+        for (round, groups) in tournament.groups.iter().enumerate() {
+            for group in groups {
+                let Ok(group) = group.lock() else {
+                    continue;
+                };
+
+                let mut records: Vec<_> = group.records.iter().collect();
+                records.sort_by(|(name_1, record_1), (name_2, record_2)| {
+                    record_2
+                        .score()
+                        .cmp(&record_1.score())
+                        .then_with(|| name_1.cmp(name_2))
+                });
+
+                for (username, record) in records {
+                    rows.push(vec![
+                        json!(round + 1),
+                        json!(group.id),
+                        json!(username),
+                        json!(record.rating.rating.round()),
+                        json!(record.wins),
+                        json!(record.losses),
+                        json!(record.draws),
+                        json!(record.score()),
+                    ]);
+                }
+            }
+        }
+
+        Self {
+            header: vec![
+                "round", "group", "username", "rating", "wins", "losses", "draws", "score",
+            ],
+            rows,
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn write(&self, format: Format) -> String {
+        match format {
+            Format::Csv => self.csv(),
+            Format::Json => self.json(),
+        }
+    }
+
+    fn csv(&self) -> String {
+        let mut csv = self.header.join(",");
+
+        for row in &self.rows {
+            csv.push_str("\\n");
+
+            for (i, value) in row.iter().enumerate() {
+                if i > 0 {
+                    csv.push(',');
+                }
+
+                let field = match value {
+                    Value::String(string) => string.clone(),
+                    value => value.to_string(),
+                };
+
+                if field.contains([',', '"', '\\']) {
+                    let _ok = write!(csv, "\"{}\"", field.replace('"', "\"\""));
+                } else {
+                    csv.push_str(&field);
+                }
+            }
+        }
+
+        csv
+    }
+
+    fn json(&self) -> String {
+        let rows: Vec<_> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let object: Map<_, _> = self
+                    .header
+                    .iter()
+                    .map(|name| (*name).to_string())
+                    .zip(row.iter().cloned())
+                    .collect();
+
+                Value::Object(object)
+            })
+            .collect();
+
+        Value::Array(rows).to_string()
+    }
+}
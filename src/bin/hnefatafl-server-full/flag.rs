@@ -0,0 +1,91 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! Ends timed games the moment a clock runs out instead of on the next sweep
+//! of `display_server`. After every move the server tells the timer when the
+//! player to move runs out of time and the timer sends `flag` back then. The
+//! server looks at the clock again before ending the game, since a move may
+//! have beaten the timer to it.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::mpsc::{self, RecvTimeoutError, Sender},
+    thread,
+    time::Duration,
+};
+
+use hnefatafl_copenhagen::Id;
+use jiff::Timestamp;
+
+#[derive(Clone, Debug)]
+pub(crate) struct FlagTimer(Sender<(Id, i64)>);
+
+impl FlagTimer {
+    pub(crate) fn spawn(tx: Sender<(String, Option<Sender<String>>)>) -> Self {
+        let (timer_tx, timer_rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            let mut deadlines = BTreeSet::new();
+            let mut games: HashMap<Id, i64> = HashMap::new();
+
+            loop {
+                let received = match deadlines.first() {
+                    Some((deadline, _id)) => {
+                        let wait = deadline - Timestamp::now().as_millisecond();
+                        timer_rx.recv_timeout(Duration::from_millis(
+                            u64::try_from(wait).unwrap_or_default(),
+                        ))
+                    }
+                    None => timer_rx
+                        .recv()
+                        .map_err(|_error| RecvTimeoutError::Disconnected),
+                };
+
+                match received {
+                    Ok((id, deadline)) => {
+                        if let Some(old) = games.insert(id, deadline) {
+                            deadlines.remove(&(old, id));
+                        }
+                        deadlines.insert((deadline, id));
+                    }
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+
+                let now = Timestamp::now().as_millisecond();
+                while let Some(&(deadline, id)) = deadlines.first()
+                    && deadline <= now
+                {
+                    deadlines.pop_first();
+                    games.remove(&id);
+
+                    if tx.send((format!("0 server flag {id}"), None)).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+
+        Self(timer_tx)
+    }
+
+    /// Replaces when the game `id` flags, in milliseconds since the epoch.
+    pub(crate) fn set(&self, id: Id, deadline: i64) {
+        let _ok = self.0.send((id, deadline));
+    }
+}
@@ -0,0 +1,196 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! Hands the server over to a newer binary without ending the games. The new
+//! server listens on the same port, the kernel shares the connections out
+//! between the two while both run, then it asks the old server over a Unix
+//! socket in the data folder to save everything and exit. The players who
+//! were connected get a while to reconnect before their clocks run again.
+//!
+//! A server has the port to itself until its Unix socket is listening, so
+//! two servers only share it during a handoff.
+
+#[cfg(unix)]
+use std::{
+    fs,
+    io::{Read, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    thread,
+};
+use std::{
+    net::{IpAddr, SocketAddr, TcpListener},
+    path::PathBuf,
+    sync::mpsc::Sender,
+};
+
+use jiff::Timestamp;
+#[cfg(unix)]
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+#[cfg(unix)]
+use socket2::SockRef;
+use socket2::{Domain, Socket, Type};
+
+pub(crate) const HANDOFF_FILE: &str = "handoff.ron";
+
+#[cfg(unix)]
+const HANDOFF_SOCKET: &str = "handoff.socket";
+
+/// What a new server sends to take over, anything else just checks whether
+/// a server is running.
+#[cfg(unix)]
+const TAKE_OVER: &[u8] = b"take_over";
+
+/// How long the players who were connected have to reconnect, in
+/// milliseconds.
+pub(crate) const GRACE: i64 = 60 * 1_000;
+
+/// What the old server knew that isn't in the data files.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct Snapshot {
+    pub taken: Timestamp,
+    pub sessions: Vec<Session>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct Session {
+    pub username: String,
+    pub address: Option<IpAddr>,
+}
+
+/// Binds the port for this server alone, unless it's `taking_over` from a
+/// server that shares it. An IPv6 socket that isn't `only_v6` takes the IPv4
+/// connections too.
+pub(crate) fn bind(
+    address: SocketAddr,
+    only_v6: bool,
+    taking_over: bool,
+) -> anyhow::Result<TcpListener> {
+    let socket = Socket::new(Domain::for_address(address), Type::STREAM, None)?;
+    socket.set_reuse_address(true)?;
+
+    if address.is_ipv6() {
+        socket.set_only_v6(only_v6)?;
+    }
+
+    #[cfg(unix)]
+    if taking_over {
+        socket.set_reuse_port(true)?;
+    }
+    #[cfg(not(unix))]
+    let _ = taking_over;
+
+    socket.bind(&address.into())?;
+    socket.listen(128)?;
+
+    Ok(socket.into())
+}
+
+/// Waits for a new server to ask to take over, then sends the server a
+/// `handoff`.
+#[cfg(unix)]
+pub(crate) fn listen(
+    data_file: fn(&str) -> PathBuf,
+    tx: Sender<(String, Option<Sender<String>>)>,
+) -> anyhow::Result<()> {
+    let path = data_file(HANDOFF_SOCKET);
+    let _ok = fs::remove_file(&path);
+    let listener = UnixListener::bind(&path)?;
+
+    thread::spawn(move || {
+        // The new server knows the old one is done when it hangs up.
+        let mut streams = Vec::new();
+
+        for stream in listener.incoming() {
+            match stream {
+                Ok(mut stream) => {
+                    let mut take_over = [0; TAKE_OVER.len()];
+                    if stream.read_exact(&mut take_over).is_err() || take_over != TAKE_OVER {
+                        continue;
+                    }
+
+                    info!("handing off to a new server...");
+                    streams.push(stream);
+
+                    if let Err(error) = tx.send(("0 server handoff".to_string(), None)) {
+                        error!("handoff: {error}");
+                    }
+                }
+                Err(error) => error!("handoff: {error}"),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Lets a new server bind the ports now that it can ask this one to leave.
+#[cfg(unix)]
+pub(crate) fn share(listeners: &[(TcpListener, SocketAddr)]) -> anyhow::Result<()> {
+    for (listener, _address) in listeners {
+        SockRef::from(listener).set_reuse_port(true)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub(crate) fn share(_listeners: &[(TcpListener, SocketAddr)]) -> anyhow::Result<()> {
+    Ok(())
+}
+
+/// Whether a server answers on the handoff socket in the data folder.
+#[cfg(unix)]
+pub(crate) fn running(data_file: fn(&str) -> PathBuf) -> bool {
+    UnixStream::connect(data_file(HANDOFF_SOCKET)).is_ok()
+}
+
+#[cfg(not(unix))]
+pub(crate) fn running(_data_file: fn(&str) -> PathBuf) -> bool {
+    false
+}
+
+#[cfg(not(unix))]
+pub(crate) fn listen(
+    _data_file: fn(&str) -> PathBuf,
+    _tx: Sender<(String, Option<Sender<String>>)>,
+) -> anyhow::Result<()> {
+    Ok(())
+}
+
+/// Asks the old server to save everything and exit, then reads what it left
+/// behind.
+#[cfg(unix)]
+pub(crate) fn take_over(data_file: fn(&str) -> PathBuf) -> anyhow::Result<Snapshot> {
+    let path = data_file(HANDOFF_FILE);
+    let _ok = fs::remove_file(&path);
+
+    let mut stream = UnixStream::connect(data_file(HANDOFF_SOCKET))?;
+    stream.write_all(TAKE_OVER)?;
+    stream.read_to_end(&mut Vec::new())?;
+
+    let snapshot = ron::from_str(&fs::read_to_string(&path)?)?;
+    fs::remove_file(path)?;
+
+    Ok(snapshot)
+}
+
+#[cfg(not(unix))]
+pub(crate) fn take_over(_data_file: fn(&str) -> PathBuf) -> anyhow::Result<Snapshot> {
+    Err(anyhow::Error::msg("handing off only works on Unix"))
+}
@@ -0,0 +1,206 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! A journal of every message the server handles, in the order it handled
+//! them, so a bug can be reproduced by replaying the journal into a server
+//! started from the same data files at the same times.
+
+use std::{
+    env,
+    ffi::OsString,
+    fs::{self, DirBuilder, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    process,
+    sync::mpsc::{self, Receiver},
+};
+
+use hnefatafl_copenhagen::clock;
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::{Server, backup::Backup, config::Config, hash_password};
+
+/// Stands in for the passwords, which the journal doesn't keep.
+pub(crate) const PASSWORD: &str = "PASSWORD";
+/// Stands in for a password that was wrong on login.
+pub(crate) const PASSWORD_WRONG: &str = "PASSWORD_WRONG";
+/// Stands in for a password too long to change to.
+pub(crate) const PASSWORD_TOO_LONG: &str = "PASSWORD_IS_GREATER_THAN_32_CHARACTERS";
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct Entry {
+    pub timestamp: Timestamp,
+    pub message: String,
+    /// Whether the message came with a channel to reply to a new client on.
+    pub client: bool,
+}
+
+#[derive(Debug)]
+pub(crate) struct Journal(File);
+
+impl Journal {
+    /// Starts a new journal next to a snapshot of the data files it starts
+    /// from. The journal of the last run is kept as `PATH.old`, replacing the
+    /// one from the run before it, so only two runs are ever kept. The
+    /// snapshot has the password hashes in it, so only the owner may read
+    /// them.
+    pub(crate) fn open(path: &Path, start: &Backup) -> anyhow::Result<Self> {
+        let old = with_suffix(path, ".old");
+        for (from, to) in [
+            (path.to_path_buf(), old.clone()),
+            (snapshot(path), snapshot(&old)),
+        ] {
+            if fs::exists(&from)? {
+                fs::rename(from, to)?;
+            }
+        }
+
+        start.encode(create_owner_only(&snapshot(path))?)?;
+        Ok(Self(create_owner_only(path)?))
+    }
+
+    /// Writes the message with a placeholder for the password, if it has one.
+    /// A login keeps whether it went through, so it gets written once it's
+    /// handled.
+    pub(crate) fn write(
+        &mut self,
+        message: &str,
+        client: bool,
+        logged_in: bool,
+    ) -> anyhow::Result<()> {
+        let entry = Entry {
+            timestamp: Timestamp::now(),
+            message: without_password(message, logged_in),
+            client,
+        };
+
+        let mut entry = ron::ser::to_string(&entry)?;
+        entry.push('\n');
+        self.0.write_all(entry.as_bytes())?;
+
+        Ok(())
+    }
+}
+
+fn create_owner_only(path: &Path) -> anyhow::Result<File> {
+    let mut options = OpenOptions::new();
+    options.create(true).write(true).truncate(true);
+
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    Ok(options.open(path)?)
+}
+
+/// Where the snapshot of the data files the journal starts from is.
+pub(crate) fn snapshot(path: &Path) -> PathBuf {
+    with_suffix(path, ".start.postcard.gz")
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = OsString::from(path);
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+/// Whether the message is a login, which gets written once it's handled.
+pub(crate) fn is_login(message: &str) -> bool {
+    message.split_ascii_whitespace().nth(2) == Some("login")
+}
+
+fn without_password(message: &str, logged_in: bool) -> String {
+    let words: Vec<_> = message.split_ascii_whitespace().collect();
+    let [index, username, command, password @ ..] = words.as_slice() else {
+        return message.to_string();
+    };
+
+    let password = password.join(" ");
+    let placeholder = match *command {
+        "change_password" if password.len() > 32 => PASSWORD_TOO_LONG,
+        "change_password" | "create_account" => PASSWORD,
+        "login" if logged_in => PASSWORD,
+        "login" => PASSWORD_WRONG,
+        _ => return message.to_string(),
+    };
+
+    format!("{index} {username} {command} {placeholder}")
+}
+
+/// Feeds a journal into a server loaded from the snapshot the journal starts
+/// from, on the clock of the journal, and writes what the server sends back to
+/// each client to `out`. The data files aren't touched.
+pub(crate) fn replay(path: &Path, config: &Config, out: &mut impl Write) -> anyhow::Result<()> {
+    let mut entries = Vec::new();
+    for line in fs::read_to_string(path)?.lines() {
+        let entry: Entry = ron::from_str(line)?;
+        entries.push(entry);
+    }
+
+    let folder = env::temp_dir().join(format!("hnefatafl-replay-{}", process::id()));
+    DirBuilder::new().recursive(true).create(&folder)?;
+    Backup::read(&snapshot(path))?.restore(|file| folder.join(file))?;
+
+    // The messages the server sends itself are in the journal already.
+    let (tx, _rx) = mpsc::channel();
+    let mut server = Server::default();
+    server.load_data_files(&tx, |file| folder.join(file))?;
+    server.tx = Some(tx);
+    server.skip_the_data_files = true;
+    server.config = config.clone();
+
+    let password =
+        hash_password(PASSWORD).ok_or_else(|| anyhow::Error::msg("failed to hash the password"))?;
+    for account in server.accounts.0.values_mut() {
+        account.password.clone_from(&password);
+    }
+
+    let mut clients: Vec<(String, Receiver<String>)> = Vec::new();
+
+    for entry in entries {
+        writeln!(out, "{} -> {}", entry.timestamp, entry.message)?;
+
+        let option_tx = if entry.client {
+            let (tx, rx) = mpsc::channel();
+            let index = entry
+                .message
+                .split_ascii_whitespace()
+                .next()
+                .unwrap_or_default();
+
+            clients.push((index.to_string(), rx));
+            Some(tx)
+        } else {
+            None
+        };
+
+        clock::set(Some(entry.timestamp));
+        server.handle_message(&entry.message, option_tx);
+
+        for (index, rx) in &clients {
+            for reply in rx.try_iter() {
+                writeln!(out, "{index} <- {reply}")?;
+            }
+        }
+    }
+
+    clock::set(None);
+    fs::remove_dir_all(folder)?;
+
+    Ok(())
+}
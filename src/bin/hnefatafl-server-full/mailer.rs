@@ -0,0 +1,66 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! Sends email on a thread of its own, so a slow or unreachable SMTP server
+//! doesn't hold up the messages. An email that can't be sent is logged and the
+//! rest still go out.
+
+use std::{
+    sync::mpsc::{self, Sender},
+    thread,
+};
+
+use lettre::Transport;
+use log::{error, info};
+
+use crate::smtp::Smtp;
+
+#[derive(Clone, Debug)]
+pub(crate) struct Mailer(Sender<(Smtp, Vec<(String, lettre::Message)>)>);
+
+impl Mailer {
+    pub(crate) fn spawn() -> Self {
+        let (mailer_tx, mailer_rx) = mpsc::channel::<(Smtp, Vec<(String, lettre::Message)>)>();
+
+        thread::spawn(move || {
+            for (smtp, emails) in mailer_rx {
+                let mailer = match smtp.transport() {
+                    Ok(mailer) => mailer,
+                    Err(error) => {
+                        error!("could not send {} emails: {error}", emails.len());
+                        continue;
+                    }
+                };
+
+                for (address, email) in emails {
+                    match mailer.send(&email) {
+                        Ok(_) => info!("email sent to {address} successfully!"),
+                        Err(error) => error!("could not send an email to {address}: {error}"),
+                    }
+                }
+            }
+        });
+
+        Self(mailer_tx)
+    }
+
+    /// Queues the emails, each with the address it goes to.
+    pub(crate) fn send(&self, smtp: &Smtp, emails: Vec<(String, lettre::Message)>) {
+        let _ok = self.0.send((smtp.clone(), emails));
+    }
+}
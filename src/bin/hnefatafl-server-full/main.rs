@@ -21,22 +21,45 @@
 #![deny(clippy::panic)]
 #![deny(clippy::unwrap_used)]
 
+mod archive;
+mod audit;
+mod avatars;
+mod backup;
+mod bans;
+mod bridge;
+mod bug_reports;
+mod check;
 mod command_line;
+mod config;
+mod email_template;
+mod events;
+mod export;
+mod flag;
+mod handoff;
+mod journal;
+mod mailer;
+mod motd;
+mod profanity;
+mod reports;
+mod roles;
 mod smtp;
 mod tests;
 mod unix_timestamp;
+mod unsubscribe;
 
 use std::{
-    collections::{HashMap, HashSet, VecDeque},
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
     fmt,
-    fs::{self, File, OpenOptions},
+    fs::{self, File},
     io::{BufRead, BufReader, ErrorKind, Read, Write},
     mem::take,
-    net::{IpAddr, TcpListener, TcpStream},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream},
+    path::PathBuf,
     process::exit,
     str::FromStr,
     sync::{
         Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
         mpsc::{self, Receiver, Sender},
     },
     thread::{self, sleep},
@@ -47,60 +70,104 @@ use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
 use badwords_rs::{Censor, MODERATE};
 use clap::Parser;
 use hnefatafl_copenhagen::{
-    Id, SERVER_PORT, VERSION_ID,
-    accounts::{Account, Accounts, AccountsOrUsers, DateTimeUtc, User, Users},
+    Id, PORT, VERSION_ID,
+    accounts::{Account, Accounts, AccountsOrUsers, DateTimeUtc, KID_SAFE_PHRASES, User, Users},
+    ai::{AI as _, AiBasic},
+    analysis::{Analysis, analyze},
     board::{BoardSize, InvalidMove},
+    clock,
+    club::{self, Club, Clubs},
+    community::{self, AI, AI_DEPTH, COMMUNITY, Quiz, Votes},
     draw::Draw,
-    email::Email,
+    email::{Email, EmailCategory},
     game::GameTime,
-    glicko::Outcome,
+    glicko::{Outcome, Rating},
     invalid_username,
+    locale::Locale,
     opentafl::OpenTaflGame,
     play::{Plae, Vertex},
-    rating::Rated,
+    profile::{PlayerProfile, RATING_GAMES},
+    proof_of_work,
+    puzzle::{self, Daily, DailyPuzzle, Puzzle, Puzzles},
+    rating::{Rated, RatingCategory},
     role::Role,
+    rule_set::RuleSet,
+    scout::Scout,
     server_game::{
-        AccountsUpdated, ArchivedGame, Challenger, GamesUpdated, Message, Messenger, NewGame,
-        ServerGame, ServerGameLight, ServerGameSerialized, ServerGames, ServerGamesLight,
-        UsersUpdated,
+        AccountsUpdated, ArchivedGame, Challenger, GamesUpdated, Kibitz, Message, Messenger,
+        NewGame, ServerGame, ServerGameLight, ServerGameSerialized, ServerGames, ServerGamesLight,
+        ServerStatus, Tags, UsersUpdated,
     },
+    settings::Settings,
+    simul::{Simul, Simuls},
     space::Space,
     status::Status,
     time::{
-        Time,
+        Speed, Time,
         TimeSettings::{self, Timed},
         TimeUnix,
     },
-    tournament::{Tournament, TournamentFull},
+    tournament::{Arena, Requirements, Tournament, TournamentFull},
     utils::{self, create_data_folder, data_file},
 };
 use itertools::Itertools;
-use jiff::{Timestamp, Zoned};
-use lettre::{
-    SmtpTransport, Transport,
-    message::{Mailbox, header::ContentType},
-    transport::smtp::authentication::Credentials,
-};
+use jiff::{SignedDuration, Timestamp, Zoned};
+use lettre::{Transport, message::Mailbox};
 use log::{debug, error, info, trace};
-use rand::random;
+use rand::{random, seq::IndexedRandom};
+use rust_i18n::t;
 use rustrict::Type;
 use serde::{Deserialize, Serialize};
 use std::fmt::Write as _;
 
-use crate::{command_line::Args, smtp::Smtp, unix_timestamp::UnixTimestamp};
+use crate::{
+    archive::{Archive, ArchiveFilter},
+    audit::AuditLog,
+    avatars::Avatars,
+    backup::Backup,
+    bans::{Ban, BanTarget, Bans},
+    bridge::{Bridge, ChatTransport, Irc, IrcBridge, MatrixBridge},
+    bug_reports::{BugReports, BugReportsOld},
+    command_line::Args,
+    config::Config,
+    email_template::EmailTemplate,
+    events::{Event, Events},
+    export::{Format, Table},
+    flag::FlagTimer,
+    handoff::{GRACE, HANDOFF_FILE, Session, Snapshot},
+    journal::Journal,
+    mailer::Mailer,
+    motd::Motd,
+    profanity::{ChatShown, ProfanityFilter},
+    reports::Reports,
+    roles::{AdminRole, Permission, Roles},
+    smtp::Smtp,
+    unix_timestamp::UnixTimestamp,
+    unsubscribe::Link,
+};
+
+#[cfg(feature = "matrix")]
+use crate::bridge::Matrix;
 
 const ACTIVE_GAMES_FILE: &str = "active-games.postcard";
 const ARCHIVED_GAMES_FILE: &str = "archived-games.ron";
-const KEEP_TEXTS: usize = 256;
+const AUDIT_LOG_FILE: &str = "audit-log.ron";
+const AVATARS_FOLDER: &str = "avatars";
+const BACKUPS_FOLDER: &str = "backups";
+const BUG_REPORTS_FILE: &str = "bug-reports.ron";
+
+rust_i18n::i18n!();
+
+/// How many of a connection's last round trips its lag is the least of.
+const LAG_ROUND_TRIPS: usize = 5;
 
 const HOUR_IN_SECONDS: u64 = 60 * 60;
 const DAY_IN_SECONDS: u64 = HOUR_IN_SECONDS * 24;
 const DAY_IN_SECONDS_SIGNED: i64 = 24 * 60 * 60;
 
+const RATING_PERIOD_MICRO_SECONDS: i64 = DAY_IN_SECONDS_SIGNED * 1_000_000;
 const TWO_MONTHS_MICRO_SECONDS: i64 = DAY_IN_SECONDS_SIGNED * 30_436_875 * 2;
-const SEVEN_DAYS: i64 = 1000 * DAY_IN_SECONDS_SIGNED * 7;
 const USERS_FILE: &str = "users.ron";
-const MESSAGE_LENGTH: usize = 1024;
 
 const UPDATE_MILLISECONDS: u64 = 250;
 
@@ -115,8 +182,47 @@ fn main() -> anyhow::Result<()> {
         return Args::generate_man_page();
     }
 
+    let config = Config::load(&args)?;
+    if let Some(folder) = &config.data_folder {
+        utils::set_data_folder(folder.clone());
+    }
+
     create_data_folder()?;
 
+    if let Some(path) = &args.restore {
+        Backup::read(path)?.restore(data_file)?;
+        info!("restored the backup {}", path.display());
+    }
+
+    if let Some(path) = &args.replay {
+        return journal::replay(path, &config, &mut std::io::stdout());
+    }
+
+    if args.check || args.quarantine {
+        let problems = check::check(data_file, args.quarantine)?;
+        for problem in &problems {
+            println!("{problem}");
+        }
+
+        if args.check {
+            exit(i32::from(!problems.is_empty() && !args.quarantine));
+        }
+    }
+
+    if !args.handoff && !args.skip_the_data_file && handoff::running(data_file) {
+        return Err(anyhow::Error::msg(
+            "a server is already running on the data folder, start with --handoff to take over",
+        ));
+    }
+
+    // Listen before taking over, so no one gets turned away in between.
+    let listeners = listen(&config, args.handoff)?;
+    let snapshot = if args.handoff {
+        Some(handoff::take_over(data_file)?)
+    } else {
+        None
+    };
+
     let (tx, rx) = mpsc::channel();
     let mut server = Server {
         tx: Some(tx.clone()),
@@ -126,41 +232,113 @@ fn main() -> anyhow::Result<()> {
     if args.skip_the_data_file {
         server.skip_the_data_files = true;
     } else {
-        server.load_data_files(tx.clone(), args.systemd)?;
+        server.load_data_files(&tx, data_file)?;
+        Server::save_on_ctrlc(tx.clone(), args.systemd)?;
+        handoff::listen(data_file, tx.clone())?;
+        handoff::share(&listeners)?;
+    }
+
+    if let Some(snapshot) = snapshot {
+        server.resume(&snapshot);
     }
+    server.config = config.clone();
 
-    let blocked_ips = server.blocked_ips.clone();
-    thread::spawn(move || server.handle_messages(&rx));
+    if let Some(kept) = config.archived_games_kept {
+        let pruned = server.archive.prune(kept)?;
+        info!("pruned {pruned} archived games");
+    }
+
+    if server.unsubscribe_key.is_empty() {
+        server.unsubscribe_key = random::<[u8; unsubscribe::KEY_LENGTH]>().to_vec();
+    }
+    server.unsubscribe_url.clone_from(&config.unsubscribe_url);
+    server.connect_bridges(&tx);
+
+    server.flag_timer = Some(FlagTimer::spawn(tx.clone()));
+    server.mailer = Some(Mailer::spawn());
+
+    let journal = match &config.journal {
+        Some(path) => Some(Journal::open(path, &server.backup()?)?),
+        None => None,
+    };
+    thread::spawn(move || server.handle_messages(&rx, journal));
 
     if !args.skip_advertising_updates {
         Server::advertise_updates(tx.clone());
     }
 
     Server::check_once_a_day(tx.clone());
+    Server::tournament_reminder(tx.clone());
+    Server::events_timer(tx.clone());
+
+    if let Some(port) = config.unsubscribe_port {
+        unsubscribe::listen(port, tx.clone())?;
+    }
 
     if args.autostart_tournament {
         Server::new_tournament(tx.clone());
     }
 
     Server::save(tx.clone());
+    Server::backup(tx.clone());
 
-    let mut address = "[::]".to_string();
-    address.push_str(SERVER_PORT);
+    let next_index = Arc::new(AtomicU64::new(1));
+    let mut handles = Vec::new();
 
-    let listener = match TcpListener::bind(&address) {
-        Ok(listener) => listener,
+    for (listener, address) in listeners {
+        info!("listening on {address} ...");
+
+        let next_index = next_index.clone();
+        let proof_of_work_bits = config.proof_of_work_bits;
+        let tx = tx.clone();
+
+        handles.push(thread::spawn(move || {
+            accept(&listener, &next_index, proof_of_work_bits, &tx);
+        }));
+    }
+
+    for handle in handles {
+        if handle.join().is_err() {
+            error!("a listener panicked");
+        }
+    }
+
+    Ok(())
+}
+
+/// Listens on every address in the config, or else on IPv6 and IPv4 if it
+/// can and on IPv4 alone otherwise.
+fn listen(config: &Config, taking_over: bool) -> anyhow::Result<Vec<(TcpListener, SocketAddr)>> {
+    let addresses = config.listen_addresses()?;
+
+    if !addresses.is_empty() {
+        return addresses
+            .into_iter()
+            .map(|address| Ok((handoff::bind(address, true, taking_over)?, address)))
+            .collect();
+    }
+
+    let port = config.port.unwrap_or(PORT);
+    let address = SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), port);
+    match handoff::bind(address, false, taking_over) {
+        Ok(listener) => Ok(vec![(listener, address)]),
         Err(error) => {
             error!("TcpLister::bind: {error}");
 
-            address = "0.0.0.0".to_string();
-            address.push_str(SERVER_PORT);
-            TcpListener::bind(&address)?
+            let address = SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), port);
+            Ok(vec![(handoff::bind(address, true, taking_over)?, address)])
         }
-    };
-
-    info!("listening on {address} ...");
+    }
+}
 
-    for (index, stream) in (1..).zip(listener.incoming()) {
+/// Every connection, on whichever listener, gets its own index.
+fn accept(
+    listener: &TcpListener,
+    next_index: &AtomicU64,
+    proof_of_work_bits: u8,
+    tx: &mpsc::Sender<(String, Option<mpsc::Sender<String>>)>,
+) {
+    for stream in listener.incoming() {
         let stream = match stream {
             Ok(stream) => stream,
             Err(error) => {
@@ -170,32 +348,24 @@ fn main() -> anyhow::Result<()> {
         };
 
         let peer_address = match stream.peer_addr() {
-            Ok(peer_address) => {
-                let ip = peer_address.ip();
-
-                if blocked_ips.contains(&ip) {
-                    error!("blocked IP address: {ip}");
-                    continue;
-                }
-
-                ip
-            }
+            Ok(peer_address) => peer_address.ip(),
             Err(error) => {
                 error!("peer_address: {error}");
                 continue;
             }
         };
 
+        let index = next_index.fetch_add(1, Ordering::Relaxed);
         let tx = tx.clone();
 
         thread::spawn(move || {
-            if let Err(error) = login(index, stream, peer_address, &tx) {
+            if let Err(error) = login(index.into(), stream, peer_address, proof_of_work_bits, &tx) {
                 error!("peer_address: {peer_address}, login: {error}");
             }
+
+            let _ok = tx.send((format!("0 server client_disconnected {index}"), None));
         });
     }
-
-    Ok(())
 }
 
 #[allow(clippy::too_many_lines)]
@@ -203,6 +373,7 @@ fn login(
     id: Id,
     mut stream: TcpStream,
     peer_address: IpAddr,
+    proof_of_work_bits: u8,
     tx: &mpsc::Sender<(String, Option<mpsc::Sender<String>>)>,
 ) -> anyhow::Result<()> {
     info!("login attempted from {peer_address}");
@@ -210,8 +381,23 @@ fn login(
     let mut reader = BufReader::new(stream.try_clone()?);
     let mut buf = String::new();
     let (client_tx, client_rx) = mpsc::channel();
+
+    tx.send((
+        format!("0 server client_connected {id} {peer_address}"),
+        Some(client_tx.clone()),
+    ))?;
+
+    let mut message = client_rx.recv()?;
+    if message != "= client_connected" {
+        message.push('\n');
+        stream.write_all(message.as_bytes())?;
+
+        return Err(anyhow::Error::msg("the IP address is banned"));
+    }
     let mut username_proper = "_".to_string();
     let mut login_successful = false;
+    let mut nonce: Option<String> = None;
+    let mut proven = proof_of_work_bits == 0;
 
     for _ in 0..100 {
         reader.read_line(&mut buf)?;
@@ -230,6 +416,16 @@ fn login(
             ));
         }
 
+        if buf.trim() == "status" {
+            tx.send(("0 _ status".to_string(), Some(client_tx.clone())))?;
+
+            let mut message = client_rx.recv()?;
+            message.push('\n');
+            stream.write_all(message.as_bytes())?;
+
+            return Ok(());
+        }
+
         let buf_clone = buf.clone();
         let mut username_password_etc = buf_clone.split_ascii_whitespace();
 
@@ -247,6 +443,17 @@ fn login(
                 continue;
             }
 
+            if create_account_login == "proof_of_work" {
+                if let Some(nonce) = &nonce
+                    && let Ok(solution) = username.parse()
+                {
+                    proven = proof_of_work::verify(nonce, proof_of_work_bits, solution);
+                }
+
+                buf.clear();
+                continue;
+            }
+
             let password: Vec<&str> = username_password_etc.collect();
             let password = password.join(" ");
 
@@ -265,7 +472,7 @@ fn login(
 
             if create_account_login == "reset_password" {
                 tx.send((
-                    format!("0 {username} reset_password"),
+                    format!("{id} {username} reset_password"),
                     Some(client_tx.clone()),
                 ))?;
 
@@ -275,6 +482,18 @@ fn login(
                 continue;
             }
 
+            if create_account_login == "create_account" && !proven {
+                let new_nonce = format!("{:x}", random::<u64>());
+                stream.write_all(
+                    format!("? create_account proof_of_work {new_nonce} {proof_of_work_bits}\n")
+                        .as_bytes(),
+                )?;
+                nonce = Some(new_nonce);
+
+                buf.clear();
+                continue;
+            }
+
             tx.send((
                 format!("{id} {username} {create_account_login} {password}"),
                 Some(client_tx.clone()),
@@ -288,6 +507,12 @@ fn login(
                     break;
                 }
 
+                if message.starts_with("? login banned") {
+                    message.push('\n');
+                    stream.write_all(message.as_bytes())?;
+                    continue;
+                }
+
                 stream.write_all(b"? login multiple_possible_errors\n")?;
                 continue;
             } else if create_account_login == "create_account" {
@@ -323,6 +548,10 @@ fn login(
     tx.send((format!("{id} {username_proper} email_get"), None))?;
     tx.send((format!("{id} {username_proper} texts"), None))?;
     tx.send((format!("{id} {username_proper} tournament_status"), None))?;
+    tx.send((format!("{id} {username_proper} texts_tournament"), None))?;
+    tx.send((format!("{id} {username_proper} simuls"), None))?;
+    tx.send((format!("{id} {username_proper} clubs"), None))?;
+    tx.send((format!("{id} {username_proper} dms_pending"), None))?;
     tx.send((format!("{id} {username_proper} version"), None))?;
     tx.send((format!("{id} {username_proper} resume_games"), None))?;
 
@@ -424,42 +653,155 @@ fn hash_password(password: &str) -> Option<String> {
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 struct Server {
+    /// The IP address each client connected from.
+    #[serde(skip)]
+    addresses: HashMap<usize, IpAddr>,
+    #[serde(skip)]
+    audit_log: AuditLog,
+    #[serde(default)]
+    bans: Bans,
+    #[serde(skip)]
+    bridges: Vec<Bridge>,
     #[serde(skip)]
     censor: Censor,
+    /// When each user last sent a message to a channel that's in slow mode,
+    /// `None` being the global chat.
+    #[serde(skip)]
+    chat_last_sent: HashMap<(String, Option<Id>), Timestamp>,
+    /// How long to wait between messages in the global chat, zero for no
+    /// slow mode.
+    #[serde(default)]
+    chat_slow_mode: u64,
+    #[serde(default)]
+    clubs: Clubs,
+    /// The recurring events that set up arenas.
+    #[serde(default)]
+    events: Events,
     #[serde(default)]
     game_id: Id,
     #[serde(default)]
+    ran_rating_period: UnixTimestamp,
+    #[serde(default)]
     ran_update_rd: UnixTimestamp,
+    /// Whether the accounts' rated games were counted from the archived
+    /// games, some of which were played before the accounts kept count.
     #[serde(default)]
+    rated_games_counted: bool,
+    /// Read from older data files, then moved into `roles`.
+    #[serde(default, skip_serializing)]
     admins: HashSet<String>,
-    #[serde(default)]
+    /// Read from older data files, then moved into `roles`.
+    #[serde(default, skip_serializing)]
     admins_tournament: HashSet<String>,
     #[serde(default)]
+    reports: Reports,
+    #[serde(skip)]
+    bug_reports: BugReports,
+    /// Read from older data files, then moved into their own file.
+    #[serde(default, rename = "bug_reports", skip_serializing)]
+    bug_reports_old: BugReportsOld,
+    #[serde(default)]
+    roles: Roles,
+    #[serde(default)]
+    profanity_filter: ProfanityFilter,
+    /// Found in the archived games, for the puzzle trainer.
+    #[serde(default)]
+    puzzles: Puzzles,
+    #[serde(default)]
+    daily_puzzle: Option<DailyPuzzle>,
+    #[serde(default)]
     smtp: Smtp,
+    /// Carries the global chat to an IRC channel.
+    #[serde(default)]
+    irc: Option<IrcBridge>,
+    /// Carries the global chat to a Matrix room.
+    #[serde(default)]
+    matrix: Option<MatrixBridge>,
+    /// The message of the day.
+    #[serde(default)]
+    motd: Motd,
     #[serde(default)]
     tournament: TournamentFull,
+    /// The date of the tournament the players got a reminder for.
+    #[serde(default)]
+    tournament_reminded: Option<Timestamp>,
     #[serde(default)]
     accounts: Accounts,
     #[serde(skip)]
     accounts_old: Accounts,
     #[serde(skip)]
-    archived_games: Vec<ArchivedGame>,
+    archive: Archive,
+    #[serde(skip)]
+    avatars: Avatars,
+    /// The avatars each connection has asked for, so it only hears about
+    /// changes to those.
+    #[serde(skip)]
+    avatars_asked: HashMap<usize, HashSet<String>>,
     #[serde(skip)]
     clients: HashMap<usize, mpsc::Sender<String>>,
     #[serde(skip)]
+    config: Config,
+    #[serde(skip)]
     games: ServerGames,
     #[serde(skip)]
     games_light: ServerGamesLight,
     #[serde(skip)]
     games_light_old: ServerGamesLight,
+    /// The last round trips from answering each connection's ping to getting
+    /// its pong, in milliseconds.
+    #[serde(skip)]
+    lags: HashMap<usize, VecDeque<i64>>,
+    /// When each connection's ping was answered, until it sends a pong.
+    #[serde(skip)]
+    pings: HashMap<usize, Timestamp>,
+    #[serde(skip)]
+    flag_timer: Option<FlagTimer>,
+    #[serde(skip)]
+    mailer: Option<Mailer>,
+    /// When each game's player to move was last emailed that it's their turn.
+    #[serde(skip)]
+    turn_notified: HashMap<Id, Timestamp>,
+    /// The tournament games a resignation was already sent for.
+    #[serde(skip)]
+    tournament_forfeited: HashSet<Id>,
+    /// The players who were connected to the server this one took over
+    /// from, and until when their clocks wait for them.
+    #[serde(skip)]
+    reconnecting: HashMap<String, i64>,
+    #[serde(default)]
+    simul_id: u64,
+    #[serde(default)]
+    simuls: Simuls,
     #[serde(skip)]
     skip_the_data_files: bool,
     #[serde(default)]
     texts: VecDeque<Message>,
+    /// The chat of each club.
+    #[serde(default)]
+    texts_club: HashMap<String, VecDeque<Message>>,
+    /// The direct messages that wait for their users to log in.
+    #[serde(default)]
+    dms_pending: HashMap<String, VecDeque<Message>>,
+    /// The chat of the players in the tournament.
+    #[serde(default)]
+    texts_tournament: VecDeque<Message>,
     #[serde(skip)]
     tx: Option<mpsc::Sender<(String, Option<mpsc::Sender<String>>)>>,
-    #[serde(default)]
+    #[serde(skip)]
+    quizzes: HashMap<Id, Quiz>,
+    /// The community games the engine is looking for a move in.
+    #[serde(skip)]
+    ai_thinking: HashSet<Id>,
+    #[serde(skip)]
+    votes: HashMap<Id, Votes>,
+    /// Read from older data files, then moved into `bans`.
+    #[serde(default, skip_serializing)]
     blocked_ips: HashSet<IpAddr>,
+    /// The key that signs the unsubscribe links.
+    #[serde(default)]
+    unsubscribe_key: Vec<u8>,
+    #[serde(skip)]
+    unsubscribe_url: String,
 }
 
 impl Server {
@@ -473,42 +815,73 @@ impl Server {
     }
 
     fn append_archived_game(&mut self, game: ServerGame) -> anyhow::Result<()> {
-        let Some(attacker) = self.accounts.0.get(&game.attacker) else {
+        self.turn_notified.remove(&game.id);
+
+        let rating = |username: &str| {
+            if community::no_account(username) {
+                Some(Rating::default())
+            } else {
+                self.accounts
+                    .0
+                    .get(username)
+                    .map(|account| account.rating.clone())
+            }
+        };
+
+        let Some(attacker_rating) = rating(&game.attacker) else {
             return Err(anyhow::Error::msg("failed to get rating!"));
         };
-        let Some(defender) = self.accounts.0.get(&game.defender) else {
+        let Some(defender_rating) = rating(&game.defender) else {
             return Err(anyhow::Error::msg("failed to get rating!"));
         };
-        let game = ArchivedGame::new(game, attacker.rating.clone(), defender.rating.clone());
+        let game = ArchivedGame::new(game, attacker_rating, defender_rating);
+
+        // Looking over the game takes a while, so it happens on its own thread.
+        if let Some(tx) = self.tx.clone() {
+            let (id, board_size, plays) = (game.id, game.board_size, game.plays.clone());
 
-        let archived_games_file = data_file(ARCHIVED_GAMES_FILE);
-        let mut game_string = ron::ser::to_string(&game)?;
-        game_string.push('\n');
+            thread::spawn(move || {
+                let moves = analyze(board_size, &plays);
+                let analysis = Analysis::from_moves(&moves);
 
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(archived_games_file)?;
+                match ron::ser::to_string(&analysis) {
+                    Ok(analysis) => handle_error(
+                        tx.send((format!("0 server game_analysis {id} {analysis}"), None)),
+                    ),
+                    Err(error) => error!("game_analysis: {error}"),
+                }
 
-        file.write_all(game_string.as_bytes())?;
+                let puzzles = puzzle::find(id, board_size, &plays, &moves);
+                if !puzzles.is_empty() {
+                    match ron::ser::to_string(&puzzles) {
+                        Ok(puzzles) => handle_error(
+                            tx.send((format!("0 server puzzles_found {puzzles}"), None)),
+                        ),
+                        Err(error) => error!("puzzles_found: {error}"),
+                    }
+                }
+            });
+        }
 
-        self.archived_games.push(game);
+        self.archive.push(game)?;
 
         Ok(())
     }
 
-    fn bcc_mailboxes(&self, username: &str) -> Vec<Mailbox> {
+    fn bcc_mailboxes(&self, username: &str) -> Vec<(&str, Locale, Mailbox)> {
         let mut emails = Vec::new();
 
         if let Some(account) = self.accounts.0.get(username)
             && account.send_emails
         {
-            for account in self.accounts.0.values() {
+            for (username, account) in &self.accounts.0 {
                 if let Some(email) = &account.email
                     && email.verified
+                    && account.email_preferences.announcements
+                    && !account.kid_safe
                     && let Some(email) = email.to_mailbox()
                 {
-                    emails.push(email);
+                    emails.push((username.as_str(), account.locale, email));
                 }
             }
         }
@@ -525,6 +898,8 @@ impl Server {
             for account in self.accounts.0.values() {
                 if let Some(email) = &account.email
                     && email.verified
+                    && account.email_preferences.announcements
+                    && !account.kid_safe
                 {
                     emails.push(email.tx());
                 }
@@ -538,7 +913,7 @@ impl Server {
     // Fixme: Censor::from_str removes the dots ä, but not using censor This allows for  ͬ ͣ p (crap)
     #[must_use]
     pub fn censor(&self, text: &str) -> String {
-        if text.len() > MESSAGE_LENGTH {
+        if text.len() > self.config.message_length {
             return String::new();
         }
 
@@ -555,6 +930,254 @@ impl Server {
         }
     }
 
+    /// How the chat gets shown to the user.
+    fn chat_shown(&self, username: &str) -> ChatShown {
+        let account = self.accounts.0.get(username);
+
+        if account.is_some_and(|account| account.kid_safe) {
+            return ChatShown::Phrases;
+        }
+
+        if !self.profanity_filter.enabled || account.is_some_and(|account| account.show_profanity) {
+            return ChatShown::All;
+        }
+
+        ChatShown::Masked(account.map(|account| account.locale).unwrap_or_default())
+    }
+
+    fn censor_for(&self, shown: ChatShown, text: &str) -> String {
+        match shown {
+            ChatShown::All => text.to_string(),
+            ChatShown::Masked(locale) => self.profanity_filter.mask(&self.censor(text), locale),
+            ChatShown::Phrases => {
+                if KID_SAFE_PHRASES.contains(&text) {
+                    text.to_string()
+                } else {
+                    "…".to_string()
+                }
+            }
+        }
+    }
+
+    /// Kid-safe accounts only say the phrases.
+    fn kid_safe_mutes(&self, username: &str, text: &str) -> bool {
+        self.accounts
+            .0
+            .get(username)
+            .is_some_and(|account| account.kid_safe && !KID_SAFE_PHRASES.contains(&text))
+    }
+
+    /// The chat as the user sees it.
+    fn messages_for(&self, username: &str, messages: &VecDeque<Message>) -> VecDeque<Message> {
+        let shown = self.chat_shown(username);
+
+        messages
+            .iter()
+            .map(|message| Message {
+                content: self.censor_for(shown, &message.content),
+                ..message.clone()
+            })
+            .collect()
+    }
+
+    fn connect_bridges(&mut self, tx: &Sender<(String, Option<Sender<String>>)>) {
+        let mut transports: Vec<Arc<dyn ChatTransport>> = Vec::new();
+
+        if let Some(irc) = &self.irc {
+            match Irc::connect(irc) {
+                Ok(irc) => transports.push(Arc::new(irc)),
+                Err(error) => error!("IRC: {error}"),
+            }
+        }
+
+        if let Some(matrix) = &self.matrix {
+            #[cfg(feature = "matrix")]
+            match Matrix::connect(matrix) {
+                Ok(matrix) => transports.push(Arc::new(matrix)),
+                Err(error) => error!("Matrix: {error}"),
+            }
+
+            #[cfg(not(feature = "matrix"))]
+            error!(
+                "Matrix: the server was built without the matrix feature, not bridging to {}",
+                matrix.room
+            );
+        }
+
+        for transport in transports {
+            self.bridges.push(Bridge::start(transport, tx.clone()));
+        }
+    }
+
+    /// Sends a player's avatar, sets or removes your own, or lets a moderator
+    /// clear someone else's. Whoever asked for an avatar hears when it
+    /// changes, so they can ask for it again. Kid-safe accounts don't get to
+    /// upload one.
+    ///
+    /// ```sh
+    /// # a base64 encoded PNG file
+    /// <- avatar_set iVBORw0KGgo...
+    /// -> = avatar_set
+    /// <- avatar david
+    /// -> = avatar david iVBORw0KGgo...
+    /// <- avatar_remove
+    /// -> = avatar_changed david
+    /// -> = avatar_remove
+    /// <- avatar_clear alice
+    /// -> = avatar_clear
+    /// <- avatar alice
+    /// -> = avatar alice
+    /// ```
+    fn avatar(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let tx = self.clients.get(&index_supplied)?.clone();
+
+        let result = match (command, the_rest) {
+            ("avatar", [player]) => {
+                self.avatars_asked
+                    .entry(index_supplied)
+                    .or_default()
+                    .insert((*player).to_string());
+
+                match self.avatars.get(player) {
+                    Ok(Some(avatar)) => {
+                        return Some((tx, Ok(()), format!("{command} {player} {avatar}")));
+                    }
+                    Ok(None) => return Some((tx, Ok(()), format!("{command} {player}"))),
+                    Err(error) => Err(error),
+                }
+            }
+            ("avatar_clear", [player]) => self.avatars.remove(player).and_then(|removed| {
+                if removed {
+                    Ok((*player).to_string())
+                } else {
+                    Err(anyhow::Error::msg(format!(
+                        "{player} doesn't have an avatar"
+                    )))
+                }
+            }),
+            ("avatar_remove", []) => self.avatars.remove(username).map(|_| username.to_string()),
+            ("avatar_set", [_])
+                if self
+                    .accounts
+                    .0
+                    .get(username)
+                    .is_some_and(|account| account.kid_safe) =>
+            {
+                Err(anyhow::Error::msg("kid-safe accounts don't have an avatar"))
+            }
+            ("avatar_set", [avatar]) => self
+                .avatars
+                .set(username, avatar)
+                .map(|()| username.to_string()),
+            _ => Err(anyhow::Error::msg("wrong arguments")),
+        };
+
+        match result {
+            Ok(player) => {
+                info!("{index_supplied} {username} {command} {player}");
+
+                let message = format!("= avatar_changed {player}");
+                for (index, asked) in &self.avatars_asked {
+                    if asked.contains(&player)
+                        && let Some(tx) = self.clients.get(index)
+                    {
+                        let _ok = tx.send(message.clone());
+                    }
+                }
+
+                Some((tx, Ok(()), command.to_string()))
+            }
+            Err(error) => {
+                error!("{index_supplied} {command}: {error}");
+                Some((tx, Err(InvalidMove::Other), format!("{command} {error}")))
+            }
+        }
+    }
+
+    /// Sets or lifts a ban on an account or an IP address, or lists the
+    /// bans. A ban lasts `forever` or for a span such as `7d` or `12h`. Only
+    /// accounts with a role below yours can be banned.
+    ///
+    /// ```sh
+    /// <- ban alice 7d spamming the chat
+    /// -> = ban
+    /// <- ban 192.0.2.0/24 forever
+    /// -> = ban
+    /// <- unban alice
+    /// -> = unban
+    /// <- bans
+    /// -> = bans [(target:Network((address:"192.0.2.0",prefix:24)),reason:"",username:"david",created:"2026-10-14T12:00:00Z",expires:None)]
+    /// ```
+    fn ban(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let tx = self.clients.get(&index_supplied)?.clone();
+
+        let result = match (command, the_rest) {
+            ("ban", [target, duration, reason @ ..]) => BanTarget::from_str(target)
+                .and_then(|target| {
+                    if let BanTarget::Account(account) = &target
+                        && !self.roles.outranks(username, account)
+                    {
+                        return Err(anyhow::Error::msg(format!(
+                            "{account}'s role isn't below yours"
+                        )));
+                    }
+
+                    Ban::new(target, duration, &reason.join(" "), username)
+                })
+                .map(|ban| self.bans.add(ban)),
+            ("bans", []) => {
+                return Some((
+                    tx,
+                    Ok(()),
+                    format!("{command} {}", ron::ser::to_string(&self.bans.0).ok()?),
+                ));
+            }
+            ("unban", [target]) => BanTarget::from_str(target).and_then(|target| {
+                if self.bans.remove(&target) {
+                    Ok(())
+                } else {
+                    Err(anyhow::Error::msg(format!("{target} isn't banned")))
+                }
+            }),
+            _ => Err(anyhow::Error::msg("wrong arguments")),
+        };
+
+        if let Err(error) = result {
+            error!("{index_supplied} {command}: {error}");
+            return Some((tx, Err(InvalidMove::Other), command.to_string()));
+        }
+
+        Some((tx, Ok(()), command.to_string()))
+    }
+
+    /// What to tell the client if the account or the address it connected
+    /// from is banned.
+    fn banned(&self, index_supplied: usize, username: Option<&str>) -> Option<String> {
+        let address = self.addresses.get(&index_supplied).copied();
+
+        self.bans.find(username, address).map(Ban::message)
+    }
+
+    fn audit(&mut self, index_supplied: usize, username: &str, event: &str) {
+        let address = self.addresses.get(&index_supplied).copied();
+
+        if let Err(error) = self.audit_log.record(username, address, event) {
+            error!("audit_log: {error}");
+        }
+    }
+
     /// ```sh
     /// # PASSWORD can be the empty string.
     /// <- change_password PASSWORD
@@ -581,6 +1204,7 @@ impl Server {
         }
 
         account.password = hash_password(&password)?;
+        self.audit(index_supplied, username, command);
 
         Some((
             self.clients.get(&index_supplied)?.clone(),
@@ -589,6 +1213,30 @@ impl Server {
         ))
     }
 
+    /// ```sh
+    /// # server internal
+    /// ```
+    ///
+    /// The rated games played since the last rating period all count at once.
+    #[must_use]
+    fn check_rating_period(&mut self) -> bool {
+        let now = clock::now();
+        if now.as_microsecond() - self.ran_rating_period.0.as_microsecond()
+            >= RATING_PERIOD_MICRO_SECONDS
+        {
+            for account in self.accounts.0.values_mut() {
+                account
+                    .rating_period
+                    .end(&mut account.rating, &mut account.ratings);
+            }
+
+            self.ran_rating_period = UnixTimestamp(now);
+            true
+        } else {
+            false
+        }
+    }
+
     /// ```sh
     /// # server internal
     /// ```
@@ -599,11 +1247,12 @@ impl Server {
     /// deviation is the same as a new player and that a typical RD is 50.
     #[must_use]
     fn check_update_rd(&mut self) -> bool {
-        let now = Timestamp::now();
+        let now = clock::now();
         if now.as_microsecond() - self.ran_update_rd.0.as_microsecond() >= TWO_MONTHS_MICRO_SECONDS
         {
             for account in self.accounts.0.values_mut() {
                 account.rating.update_rd();
+                account.ratings.update_rd();
             }
 
             self.ran_update_rd = UnixTimestamp(now);
@@ -616,6 +1265,7 @@ impl Server {
     fn check_once_a_day(tx: Sender<(String, Option<Sender<String>>)>) {
         thread::spawn(move || {
             loop {
+                handle_error(tx.send(("0 server check_rating_period".to_string(), None)));
                 handle_error(tx.send(("0 server check_update_rd".to_string(), None)));
 
                 thread::sleep(Duration::from_secs(DAY_IN_SECONDS));
@@ -623,25 +1273,304 @@ impl Server {
         });
     }
 
+    /// Ends a live timed game whose opponent has been disconnected for
+    /// `claim_after_seconds`. You can claim the win while their clock runs,
+    /// or a draw at any time. Correspondence, untimed, and tournament games
+    /// have their own deadlines, so they can't be claimed.
+    ///
     /// ```sh
-    /// # PASSWORD can be the empty string.
-    /// <- VERSION_ID create_account player-1 PASSWORD
-    /// -> = login
+    /// <- claim 6 win
+    /// -> = claim 6 win
     /// ```
-    fn create_account(
+    fn claim(
         &mut self,
         username: &str,
         index_supplied: usize,
         command: &str,
         the_rest: &[&str],
-        option_tx: Option<Sender<String>>,
     ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
-        let password = the_rest.join(" ");
-        let tx = option_tx?;
+        let tx = self.clients.get(&index_supplied)?.clone();
 
-        if self.accounts.0.contains_key(username) || username == "server" {
-            let mut error = (*command).to_string();
-            error.push_str(" already_exists");
+        let (id, claim, opponent, role) = match self.claimable(username, the_rest) {
+            Ok(claimable) => claimable,
+            Err(error) => {
+                return Some((tx, Err(InvalidMove::Other), format!("{command} {error}")));
+            }
+        };
+
+        info!("{index_supplied} {username} claim {id} {claim}");
+        let id_string = id.to_string();
+        if claim == "win" {
+            let role = role.to_string();
+            let _reply = self.game(
+                0,
+                &opponent,
+                "game",
+                &[&id_string, "play", &role, "resigns", "_"],
+            );
+        } else {
+            let _reply = self.draw(0, "draw", &[&id_string, "accept"]);
+        }
+
+        Some((tx, Ok(()), format!("{command} {id} {claim}")))
+    }
+
+    /// The game, the claim, the opponent, and their role, or what stops
+    /// `username` from claiming it.
+    fn claimable<'a>(
+        &self,
+        username: &str,
+        the_rest: &[&'a str],
+    ) -> anyhow::Result<(Id, &'a str, String, Role)> {
+        let (Some(id), Some(claim @ ("win" | "draw"))) = (
+            the_rest.first().and_then(|id| id.parse::<Id>().ok()),
+            the_rest.get(1).copied(),
+        ) else {
+            return Err(anyhow::Error::msg("usage"));
+        };
+
+        let Some(game) = self.games.0.get(&id) else {
+            return Err(anyhow::Error::msg("no_game"));
+        };
+
+        let (opponent, role) = if game.attacker == username {
+            (game.defender.clone(), Role::Defender)
+        } else if game.defender == username {
+            (game.attacker.clone(), Role::Attacker)
+        } else {
+            return Err(anyhow::Error::msg("not_your_game"));
+        };
+
+        let live = self
+            .games_light
+            .0
+            .get(&id)
+            .is_some_and(|game| Speed::from(&game.timed) != Speed::Correspondence);
+        let tournament = self
+            .tournament
+            .tournament
+            .as_ref()
+            .is_some_and(|tournament| tournament.tournament_games.contains_key(&id));
+
+        if !live || tournament {
+            return Err(anyhow::Error::msg("not_live"));
+        }
+
+        if self.clock_held(game) {
+            return Err(anyhow::Error::msg("clock_held"));
+        }
+
+        let Some(disconnected) = self.disconnected_for(&opponent) else {
+            return Err(anyhow::Error::msg("connected"));
+        };
+
+        let wait = self.config.claim_after_seconds * 1_000 - disconnected;
+        if wait > 0 {
+            return Err(anyhow::Error::msg(format!("wait {}", (wait + 999) / 1_000)));
+        }
+
+        if claim == "win" && game.game.turn != role {
+            return Err(anyhow::Error::msg("your_move"));
+        }
+
+        Ok((id, claim, opponent, role))
+    }
+
+    /// Starts an untimed game against the spectators, who vote on the moves
+    /// of the other side. With `ai` the server's engine plays the role instead
+    /// of you.
+    ///
+    /// ```sh
+    /// <- community_game_new attacker 11
+    /// -> = community_game_new 7
+    /// <- community_game_new defender 11 ai
+    /// -> = community_game_new 8 ai
+    /// ```
+    fn community_game_new(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let channel = self.clients.get(&index_supplied)?.clone();
+        let mut command = (*command).to_string();
+
+        let role = the_rest.first().and_then(|role| Role::from_str(role).ok());
+        let board_size = the_rest
+            .get(1)
+            .and_then(|board_size| board_size.parse::<usize>().ok())
+            .and_then(|board_size| BoardSize::try_from(board_size).ok());
+
+        let ai = the_rest.get(2) == Some(&"ai");
+        let player = if ai { AI } else { username };
+
+        let (attacker, defender, board_size) = match (role, board_size) {
+            (Some(Role::Attacker), Some(board_size)) => (player, COMMUNITY, board_size),
+            (Some(Role::Defender), Some(board_size)) => (COMMUNITY, player, board_size),
+            _ => {
+                command.push_str(" invalid role or board size");
+                return Some((channel, Err(InvalidMove::Other), command));
+            }
+        };
+
+        let id = self.new_game_accepted(
+            attacker,
+            defender,
+            Rated::No,
+            TimeSettings::UnTimed,
+            board_size,
+        );
+
+        info!("{index_supplied} {username} community_game_new {id} {player}");
+        let _ = write!(command, " {id}");
+        if ai {
+            command.push_str(" ai");
+        }
+
+        Some((channel, Ok(()), command))
+    }
+
+    /// Looks for the engine's move on its own thread in every community game
+    /// where it is the engine's turn, then plays it.
+    fn community_ai(&mut self) {
+        let ai_turns: Vec<_> = self
+            .games
+            .0
+            .values()
+            .filter(|game| {
+                game.game.status == Status::Ongoing
+                    && match game.game.turn {
+                        Role::Attacker => game.attacker == AI,
+                        Role::Defender => game.defender == AI,
+                        Role::Roleless => false,
+                    }
+            })
+            .map(|game| (game.id, game.game.clone()))
+            .collect();
+
+        self.ai_thinking
+            .retain(|id| ai_turns.iter().any(|(ai_id, _)| ai_id == id));
+
+        for (id, mut game) in ai_turns {
+            if let Some(tx) = self.tx.clone()
+                && self.ai_thinking.insert(id)
+            {
+                thread::spawn(move || {
+                    match AiBasic::new(AI_DEPTH, true).generate_move(&mut game) {
+                        Ok(generate_move) => {
+                            let _ok =
+                                tx.send((format!("0 {AI} game {id} {}", generate_move.play), None));
+                        }
+                        Err(error) => error!("community_ai {id}: {error}"),
+                    }
+                });
+            }
+        }
+    }
+
+    fn community_vote(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let channel = self.clients.get(&index_supplied)?.clone();
+        let mut command = (*command).to_string();
+
+        let (Some(id), Some(from), Some(to)) = (
+            the_rest.first().and_then(|id| id.parse::<Id>().ok()),
+            the_rest.get(1),
+            the_rest.get(2),
+        ) else {
+            command.push_str(" expected a game id, from, and to");
+            return Some((channel, Err(InvalidMove::Other), command));
+        };
+
+        let (Some(game), Some(game_light)) = (self.games.0.get(&id), self.games_light.0.get(&id))
+        else {
+            command.push_str(" there is no such game");
+            return Some((channel, Err(InvalidMove::Other), command));
+        };
+
+        let role = if game.attacker == COMMUNITY {
+            Role::Attacker
+        } else if game.defender == COMMUNITY {
+            Role::Defender
+        } else {
+            command.push_str(" the game is not a community game");
+            return Some((channel, Err(InvalidMove::Other), command));
+        };
+
+        if game.game.turn != role {
+            command.push_str(" it is not the community's turn");
+            return Some((channel, Err(InvalidMove::Turn), command));
+        }
+
+        if username == game.attacker
+            || username == game.defender
+            || !game_light.spectators.contains_key(username)
+        {
+            command.push_str(" only spectators may vote");
+            return Some((channel, Err(InvalidMove::Other), command));
+        }
+
+        let Ok(Plae::Play(play)) = Plae::try_from(vec!["play", &role.to_string(), from, to]) else {
+            command.push_str(" invalid move");
+            return Some((channel, Err(InvalidMove::Other), command));
+        };
+
+        if let Err(error) = game.game.clone().play(&Plae::Play(play.clone())) {
+            return Some((channel, Err(error), command));
+        }
+
+        info!("{index_supplied} {username} community_vote {id} {from} {to}");
+
+        let votes = self.votes.entry(id).or_default();
+        votes.vote(username, play, clock::now().as_millisecond());
+
+        let votes = ron::ser::to_string(&votes).ok()?;
+        let message = format!("= community_votes {id} {votes}");
+
+        for spectator in game_light.spectators() {
+            if let Some(client) = self.clients.get(&spectator) {
+                let _ok = client.send(message.clone());
+            }
+        }
+
+        game.attacker_tx.send(message.clone());
+        game.defender_tx.send(message);
+
+        None
+    }
+
+    /// ```sh
+    /// # PASSWORD can be the empty string.
+    /// <- VERSION_ID create_account player-1 PASSWORD
+    /// -> ? create_account proof_of_work NONCE BITS
+    /// <- VERSION_ID proof_of_work SOLUTION
+    /// <- VERSION_ID create_account player-1 PASSWORD
+    /// -> = login
+    /// ```
+    fn create_account(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+        option_tx: Option<Sender<String>>,
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let password = the_rest.join(" ");
+        let tx = option_tx?;
+
+        if let Some(message) = self.banned(index_supplied, None) {
+            self.audit(index_supplied, username, "create_account banned");
+            tx.send(message).ok()?;
+            None
+        } else if self.accounts.0.contains_key(username) || username == "server" {
+            let mut error = (*command).to_string();
+            error.push_str(" already_exists");
 
             info!("{index_supplied} {username} {error}");
 
@@ -664,6 +1593,7 @@ impl Server {
             Some((tx, Err(InvalidMove::Other), error))
         } else {
             info!("{index_supplied} {username} created user account");
+            self.audit(index_supplied, username, command);
 
             let hash = hash_password(&password)?;
             self.clients.insert(index_supplied, tx);
@@ -735,6 +1665,9 @@ impl Server {
                 challenge_accepted: false,
                 game_over: false,
                 turn: Role::Roleless,
+                established_only: game_old.established_only,
+                zen: game_old.zen,
+                rule_set: game_old.rule_set,
             };
 
             command = format!("{command} {id}");
@@ -746,8 +1679,28 @@ impl Server {
 
     fn delete_account(&mut self, username: &str, index_supplied: usize) {
         info!("{index_supplied} {username} delete_account");
+        self.audit(index_supplied, username, "delete_account");
 
         self.accounts.0.remove(username);
+        if let Err(error) = self.avatars.remove(username) {
+            error!("{index_supplied} {username} delete_account: {error}");
+        }
+        self.clubs.remove_member(username);
+        self.dms_pending.remove(username);
+        self.texts_club
+            .retain(|name, _| self.clubs.0.contains_key(name));
+        self.clubs_status_all();
+    }
+
+    /// How long `username` has been logged out, in milliseconds, or `None`
+    /// while they're logged in or may still reconnect after a handoff.
+    fn disconnected_for(&self, username: &str) -> Option<i64> {
+        let account = self.accounts.0.get(username)?;
+        if account.logged_in.is_some() || self.reconnecting.contains_key(username) {
+            return None;
+        }
+
+        Some(clock::now().as_millisecond() - account.last_logged_in.0.as_millisecond())
     }
 
     #[allow(clippy::too_many_lines)]
@@ -833,8 +1786,12 @@ impl Server {
                                 wins: account_1.wins,
                                 losses: account_1.losses,
                                 draws: account_1.draws,
+                                accuracy: account_1.accuracy.clone(),
                                 rating: account_1.rating.clone(),
+                                ratings: account_1.ratings.clone(),
                                 logged_in: account_1.logged_in.is_some(),
+                                mentor: account_1.mentor,
+                                vacation_until: account_1.vacation.until,
                             },
                         );
                     }
@@ -847,8 +1804,12 @@ impl Server {
                             wins: account_1.wins,
                             losses: account_1.losses,
                             draws: account_1.draws,
+                            accuracy: account_1.accuracy.clone(),
                             rating: account_1.rating.clone(),
+                            ratings: account_1.ratings.clone(),
                             logged_in: account_1.logged_in.is_some(),
+                            mentor: account_1.mentor,
+                            vacation_until: account_1.vacation.until,
                         },
                     );
                 }
@@ -884,7 +1845,7 @@ impl Server {
                 if let Some(id) = account.logged_in
                     && let Some(tx) = self.clients.get(&id)
                 {
-                    if self.admins.contains(name) {
+                    if self.roles.allows(name, Permission::Accounts) {
                         let _ok = tx.send(format!("= accounts_updated {accounts_updated}"));
                     } else {
                         let _ok = tx.send(format!("= users_updated {users_updated}"));
@@ -893,18 +1854,77 @@ impl Server {
             }
         }
 
+        let now = clock::now().as_millisecond();
+        self.votes.retain(|id, _| self.games.0.contains_key(id));
+        self.quizzes.retain(|id, _| self.games.0.contains_key(id));
+        let votes_closed: Vec<_> = self
+            .votes
+            .iter()
+            .filter(|(_, votes)| votes.closes <= now)
+            .map(|(id, _)| *id)
+            .collect();
+
+        for id in votes_closed {
+            if let Some(votes) = self.votes.remove(&id)
+                && let Some(play) = votes.winner()
+                && let Some(tx) = &self.tx
+            {
+                let _ok = tx.send((
+                    format!(
+                        "0 {COMMUNITY} game {id} play {} {} {}",
+                        play.role, play.from, play.to
+                    ),
+                    None,
+                ));
+            }
+        }
+
+        self.community_ai();
+
+        let simuls_length = self.simuls.0.len();
+        self.simuls.0.retain(|_, simul| {
+            !simul.started() || simul.games.iter().any(|id| self.games.0.contains_key(id))
+        });
+
+        if self.simuls.0.len() != simuls_length {
+            self.simuls_status_all();
+        }
+
+        self.tournament_forfeits();
+
+        if self.arena_pair() {
+            self.tournament_status_all();
+        }
+        self.reconnecting.retain(|_, until| *until > now);
+        let abandoned_after = self.config.abandoned_after();
+        let held: HashSet<_> = self
+            .games
+            .0
+            .values()
+            .filter(|game| self.clock_held(game))
+            .map(|game| game.id)
+            .collect();
+
         for game in self.games.0.values_mut() {
+            if held.contains(&game.id) {
+                if let TimeUnix::Time(game_time) = &mut game.game.time {
+                    *game_time = now;
+                }
+
+                continue;
+            }
+
             match game.game.turn {
                 Role::Attacker => {
                     if game.game.status == Status::Ongoing
                         && let TimeUnix::Time(game_time) = &mut game.game.time
                     {
-                        let now = Timestamp::now().as_millisecond();
+                        let now = clock::now().as_millisecond();
                         let elapsed_time = now - *game_time;
                         game.elapsed_time += elapsed_time;
                         *game_time = now;
 
-                        if game.elapsed_time > SEVEN_DAYS
+                        if game.elapsed_time > abandoned_after
                             && let Some(tx) = &mut self.tx
                         {
                             let _ok = tx.send((
@@ -938,12 +1958,12 @@ impl Server {
                     if game.game.status == Status::Ongoing
                         && let TimeUnix::Time(game_time) = &mut game.game.time
                     {
-                        let now = Timestamp::now().as_millisecond();
+                        let now = clock::now().as_millisecond();
                         let elapsed_time = now - *game_time;
                         game.elapsed_time += elapsed_time;
                         *game_time = now;
 
-                        if game.elapsed_time > SEVEN_DAYS
+                        if game.elapsed_time > abandoned_after
                             && let Some(tx) = &mut self.tx
                         {
                             let _ok = tx.send((
@@ -977,6 +1997,7 @@ impl Server {
         None
     }
 
+    #[allow(clippy::too_many_lines)]
     fn draw(
         &mut self,
         index_supplied: usize,
@@ -1042,37 +2063,61 @@ impl Server {
 
             game.game.status = Status::Draw;
 
+            let category = RatingCategory::new(
+                game_light.rule_set,
+                game_light.board_size,
+                &game_light.timed,
+            );
             let accounts = &mut self.accounts.0;
-            let (attacker_rating, defender_rating) = if let (Some(attacker), Some(defender)) =
-                (accounts.get(&game.attacker), accounts.get(&game.defender))
-            {
-                (attacker.rating.rating, defender.rating.rating)
-            } else {
-                unreachable!();
-            };
+            let (attacker_rating, defender_rating, attacker_category, defender_category) =
+                if let (Some(attacker), Some(defender)) =
+                    (accounts.get(&game.attacker), accounts.get(&game.defender))
+                {
+                    (
+                        attacker.rating.clone(),
+                        defender.rating.clone(),
+                        attacker.ratings.get(&category),
+                        defender.ratings.get(&category),
+                    )
+                } else if community::no_account(&game.attacker)
+                    || community::no_account(&game.defender)
+                {
+                    Default::default()
+                } else {
+                    unreachable!();
+                };
 
             if let Some(attacker) = accounts.get_mut(&game.attacker) {
                 attacker.draws += 1;
 
                 if game.rated.into() {
-                    attacker
-                        .rating
-                        .update_rating(defender_rating, &Outcome::Draw);
+                    attacker.rated_games += 1;
+                    attacker.rating_period.push(
+                        category,
+                        &defender_rating,
+                        &defender_category,
+                        Outcome::Draw,
+                    );
                 }
             }
             if let Some(defender) = accounts.get_mut(&game.defender) {
                 defender.draws += 1;
 
                 if game.rated.into() {
-                    defender
-                        .rating
-                        .update_rating(attacker_rating, &Outcome::Draw);
+                    defender.rated_games += 1;
+                    defender.rating_period.push(
+                        category,
+                        &attacker_rating,
+                        &attacker_category,
+                        Outcome::Draw,
+                    );
                 }
             }
 
             if let Some(game) = self.games_light.0.get_mut(&id) {
                 game.game_over = true;
             }
+            self.quiz_results(id);
 
             if !self.skip_the_data_files {
                 self.append_archived_game(game)
@@ -1086,88 +2131,542 @@ impl Server {
         None
     }
 
-    #[allow(clippy::too_many_lines)]
-    fn game(
-        &mut self,
+    /// Sends everyone that didn't unsubscribe their own email, so each one
+    /// has its own unsubscribe link.
+    ///
+    /// ```sh
+    /// <- email_everyone SUBJECT TEXT
+    /// -> = email_everyone
+    /// ```
+    fn email_everyone(
+        &self,
         index_supplied: usize,
         username: &str,
         command: &str,
         the_rest: &[&str],
     ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
-        if the_rest.len() < 5 {
-            return Some((
-                self.clients.get(&index_supplied)?.clone(),
-                Err(InvalidMove::Other),
-                (*command).to_string(),
-            ));
-        }
-
-        let index = the_rest.first()?;
-        let Ok(index) = index.parse() else {
-            return Some((
-                self.clients.get(&index_supplied)?.clone(),
-                Err(InvalidMove::Other),
-                (*command).to_string(),
-            ));
+        let template = EmailTemplate::Announcement {
+            subject: (*the_rest.first()?).to_string(),
+            text: the_rest.get(1..)?.join(" ").replace("\\n", "\n"),
         };
-        let role = the_rest.get(2)?;
-        let Ok(role) = Role::from_str(role) else {
-            return Some((
-                self.clients.get(&index_supplied)?.clone(),
-                Err(InvalidMove::Other),
-                (*command).to_string(),
-            ));
-        };
-        let from = the_rest.get(3)?;
-        let to = the_rest.get(4)?;
-        let mut to = (*to).to_string();
-        if to == "_" {
-            to = String::new();
+
+        let mut emails = Vec::new();
+
+        for (username, locale, mailbox) in self.bcc_mailboxes(username) {
+            let address = mailbox.email.to_string();
+            let unsubscribe = self.unsubscribe_link(username, EmailCategory::Announcements);
+
+            match template.message(
+                lettre::Message::builder().to(mailbox),
+                locale,
+                unsubscribe.as_deref(),
+            ) {
+                Ok(email) => emails.push((address, email)),
+                Err(error) => error!("could not write an email to {username}: {error}"),
+            }
         }
 
-        let Some(game) = self.games.0.get_mut(&index) else {
-            return Some((
-                self.clients.get(&index_supplied)?.clone(),
-                Err(InvalidMove::Other),
-                (*command).to_string(),
-            ));
-        };
+        info!(
+            "{index_supplied} {username} email_everyone {}",
+            emails.len()
+        );
+        self.mail(emails);
 
-        let Some(game_light) = self.games_light.0.get_mut(&index) else {
-            return Some((
-                self.clients.get(&index_supplied)?.clone(),
-                Err(InvalidMove::Other),
-                (*command).to_string(),
-            ));
-        };
+        Some((
+            self.clients.get(&index_supplied)?.clone(),
+            Ok(()),
+            (*command).to_string(),
+        ))
+    }
 
-        game.elapsed_time = 0;
-        game.draw_requested = Role::Roleless;
+    /// Hands the emails to the mailer thread.
+    fn mail(&self, emails: Vec<(String, lettre::Message)>) {
+        match &self.mailer {
+            Some(mailer) => mailer.send(self.config.smtp(&self.smtp), emails),
+            None => error!("there is no mailer to send {} emails", emails.len()),
+        }
+    }
 
-        let mut attackers_turn_next = true;
-        if role == Role::Attacker {
-            if *username == game.attacker {
-                let play =
-                    match Plae::try_from(["play", &role.to_string(), *from, &to, "_"].to_vec()) {
-                        Ok(play) => play,
-                        result @ Err(_) => {
-                            error!("play attacker {from} {to}: {result:?}");
-                            let error = result.map(|_string| ());
+    /// Sends the emails the account wants, after setting whether it wants a
+    /// category of email if one is given.
+    ///
+    /// ```sh
+    /// <- email_preferences turn_notifications false
+    /// -> = email_preferences {"announcements":true,"tournaments":true,"turn_notifications":false}
+    /// ```
+    fn email_preferences(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let account = self.accounts.0.get_mut(username)?;
 
-                            return Some((
-                                self.clients.get(&index_supplied)?.clone(),
-                                error,
-                                (*command).to_string(),
-                            ));
-                        }
-                    };
+        if let (Some(category), Some(send)) = (the_rest.first(), the_rest.get(1)) {
+            let (Ok(category), Ok(send)) = (EmailCategory::from_str(category), send.parse()) else {
+                return Some((
+                    self.clients.get(&index_supplied)?.clone(),
+                    Err(InvalidMove::Other),
+                    (*command).to_string(),
+                ));
+            };
 
-                match game.game.play(&play) {
-                    Ok(_moved) => {
-                        let message = format!("game {index} play attacker {from} {to}");
+            account.email_preferences.set(category, send);
+            info!("{index_supplied} {username} email_preferences {category} {send}");
+        }
 
-                        for spectator in game_light.spectators() {
-                            if let Some(client) = self.clients.get(&spectator) {
+        let preferences = serde_json::to_string(&account.email_preferences).ok()?;
+        Some((
+            self.clients.get(&index_supplied)?.clone(),
+            Ok(()),
+            format!("{command} {preferences}"),
+        ))
+    }
+
+    /// `events` lists the recurring events as JSON, `event_set JSON` adds or
+    /// changes one, and `event_delete NAME` takes one away. A tournament
+    /// director may change any event, a club owner the events of their club,
+    /// and whoever set an event up may go on changing it.
+    fn event(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let channel = self.clients.get(&index_supplied)?.clone();
+        let mut command = command.to_string();
+
+        let may_set_up = |event: &Event| {
+            self.roles.allows(username, Permission::Tournament)
+                || event.club.as_ref().is_some_and(|club| {
+                    self.clubs
+                        .0
+                        .get(club)
+                        .is_some_and(|club| club.owner == username)
+                })
+        };
+        let may_change = |event: &Event| event.owner == username || may_set_up(event);
+
+        match command.as_str() {
+            "event_delete" => {
+                let name = the_rest.join(" ");
+                let Some(event) = self.events.0.get(&name) else {
+                    command.push_str(" there is no such event");
+                    return Some((channel, Err(InvalidMove::Other), command));
+                };
+
+                if !may_change(event) {
+                    command.push_str(" you may not change the event");
+                    return Some((channel, Err(InvalidMove::Other), command));
+                }
+
+                self.events.0.remove(&name);
+                info!("{index_supplied} {username} event_delete {name}");
+                self.audit(index_supplied, username, &format!("event_delete {name}"));
+            }
+            "event_set" => {
+                let mut event: Event = match serde_json::from_str(&the_rest.join(" ")) {
+                    Ok(event) => event,
+                    Err(error) => {
+                        let _ok = write!(command, " {error}");
+                        return Some((channel, Err(InvalidMove::Other), command));
+                    }
+                };
+
+                event.owner = self
+                    .events
+                    .0
+                    .get(&event.name)
+                    .map_or_else(|| username.to_string(), |event| event.owner.clone());
+
+                let allowed = match self.events.0.get(&event.name) {
+                    Some(old) => may_change(old) && (old.club == event.club || may_set_up(&event)),
+                    None => may_set_up(&event),
+                };
+
+                if !allowed {
+                    command.push_str(" you may not change the event");
+                    return Some((channel, Err(InvalidMove::Other), command));
+                }
+
+                if event.minutes <= 0 {
+                    command.push_str(" the arena has to last a minute or more");
+                    return Some((channel, Err(InvalidMove::Other), command));
+                }
+
+                if let Err(error) = event.next(clock::now()) {
+                    let _ok = write!(command, " {error}");
+                    return Some((channel, Err(InvalidMove::Other), command));
+                }
+
+                info!("{index_supplied} {username} event_set {event:?}");
+                self.audit(
+                    index_supplied,
+                    username,
+                    &format!("event_set {}", event.name),
+                );
+                self.events.0.insert(event.name.clone(), event);
+            }
+            _ => {}
+        }
+
+        let events: Vec<_> = self.events.0.values().collect();
+        let events = serde_json::to_string(&events).ok()?;
+
+        Some((channel, Ok(()), format!("events {events}")))
+    }
+
+    /// Puts the arena of the next event in the place of a tournament that
+    /// isn't going on, and starts the arena of an event once its time comes.
+    fn events_run(&mut self, now: Timestamp) {
+        let free = match &self.tournament.tournament {
+            None => self.tournament.date.is_none(),
+            Some(tournament) => {
+                self.tournament.event.is_some()
+                    && tournament
+                        .arena
+                        .as_ref()
+                        .is_some_and(|arena| arena.is_over(now))
+            }
+        };
+
+        if free && let Some((event, start)) = self.events.due(now) {
+            info!(
+                "setting up the arena for the event {} at {start}",
+                event.name
+            );
+
+            self.tournament = TournamentFull {
+                board_size: event.board_size,
+                time_setting: event.time_setting,
+                rated: event.rated,
+                requirements: Requirements {
+                    club: event.club.clone(),
+                    ..Requirements::default()
+                },
+                arena_minutes: Some(event.minutes),
+                date: Some(start),
+                event: Some(event.name.clone()),
+                ..TournamentFull::default()
+            };
+            self.texts_tournament.clear();
+            self.tournament_status_all();
+
+            for tx in self.clients.values() {
+                let _ok = tx.send("= texts_tournament".to_string());
+            }
+        }
+
+        if self.tournament.event.is_some() {
+            self.tournament_start(now);
+        }
+    }
+
+    fn events_timer(tx: Sender<(String, Option<Sender<String>>)>) {
+        thread::spawn(move || {
+            loop {
+                handle_error(tx.send(("0 server events_run".to_string(), None)));
+                thread::sleep(Duration::from_mins(1));
+            }
+        });
+    }
+
+    /// `export_ratings`, `export_results`, `export_standings`, or
+    /// `export_accounts` as `csv` or `json`. The results take the same filter
+    /// as `archived_games`.
+    fn export(
+        &self,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let tx = self.clients.get(&index_supplied)?.clone();
+        let Some((format, the_rest)) = the_rest.split_first() else {
+            return Some((tx, Err(InvalidMove::Other), command.to_string()));
+        };
+
+        let table = Format::from_str(format).and_then(|format| {
+            let table = match command {
+                "export_accounts" => Table::accounts(&self.accounts),
+                "export_ratings" => Table::ratings(&self.accounts),
+                "export_results" => {
+                    Table::results(&self.archive.games(&ArchiveFilter::new(the_rest)?)?)
+                }
+                "export_standings" => Table::standings(
+                    self.tournament
+                        .tournament
+                        .as_ref()
+                        .ok_or_else(|| anyhow::Error::msg("there is no tournament"))?,
+                ),
+                _ => return Err(anyhow::Error::msg(format!("{command} isn't an export"))),
+            };
+
+            Ok(table.write(format))
+        });
+
+        match table {
+            Ok(table) => Some((tx, Ok(()), format!("{command} {format} {table}"))),
+            Err(error) => {
+                error!("{index_supplied} {command}: {error}");
+                Some((tx, Err(InvalidMove::Other), command.to_string()))
+            }
+        }
+    }
+
+    /// Ends the game `id` on time if the player to move has run out of it,
+    /// otherwise sets the timer again for when they will.
+    fn flag(&mut self, the_rest: &[&str]) {
+        let Some(id) = the_rest.first().and_then(|id| id.parse::<Id>().ok()) else {
+            return;
+        };
+        let Some(game) = self.games.0.get(&id) else {
+            return;
+        };
+        let Some(deadline) = game.game.flags_at() else {
+            return;
+        };
+        let (player, role) = match game.game.turn {
+            Role::Attacker => (game.attacker.clone(), "attacker"),
+            Role::Defender => (game.defender.clone(), "defender"),
+            Role::Roleless => return,
+        };
+
+        if self.clock_held(game) {
+            // The sweep in display_server holds their clock.
+            return;
+        }
+
+        if deadline > clock::now().as_millisecond() {
+            self.flag_timer_set(id);
+            return;
+        }
+
+        info!("0 {player} flag {id}");
+        let id = id.to_string();
+        let _reply = self.game(0, &player, "game", &[&id, "play", role, "resigns", "_"]);
+    }
+
+    /// Whether the clock of the player to move waits, because they may still
+    /// reconnect after a handoff or they're on vacation from a correspondence
+    /// game.
+    fn clock_held(&self, game: &ServerGame) -> bool {
+        let player = match game.game.turn {
+            Role::Attacker => &game.attacker,
+            Role::Defender => &game.defender,
+            Role::Roleless => return false,
+        };
+
+        let correspondence = self
+            .games_light
+            .0
+            .get(&game.id)
+            .is_some_and(|game| Speed::from(&game.timed) == Speed::Correspondence);
+
+        self.reconnecting.contains_key(player)
+            || correspondence
+                && self
+                    .accounts
+                    .0
+                    .get(player)
+                    .is_some_and(|account| account.vacation.on(clock::now()))
+    }
+
+    /// The least of the connection's last round trips, up to the most the
+    /// server gives back for a move, in milliseconds.
+    fn lag(&self, index_supplied: usize) -> i64 {
+        self.lags
+            .get(&index_supplied)
+            .and_then(|lags| lags.iter().min())
+            .map_or(0, |lag| {
+                (*lag).min(self.config.lag_compensation_milliseconds)
+            })
+    }
+
+    /// Tells the flag timer when the player to move in the game `id` runs
+    /// out of time.
+    fn flag_timer_set(&self, id: Id) {
+        if let Some(flag_timer) = &self.flag_timer
+            && let Some(game) = self.games.0.get(&id)
+            && let Some(deadline) = game.game.flags_at()
+        {
+            flag_timer.set(id, deadline);
+        }
+    }
+
+    /// Attaches the coach's look over a game to the archived game and sends
+    /// it to the players.
+    ///
+    /// ```sh
+    /// -> = game_analysis GAME_ID ANALYSIS_RON
+    /// ```
+    fn game_analysis(&mut self, the_rest: &[&str]) -> anyhow::Result<()> {
+        let Some((id, analysis)) = the_rest.split_first() else {
+            return Err(anyhow::Error::msg("expected an id and an analysis"));
+        };
+
+        let id: Id = id.parse()?;
+        let analysis_ron = analysis.join(" ");
+        let analysis: Analysis = ron::from_str(&analysis_ron)?;
+
+        let Some(mut game) = self.archive.get(id)? else {
+            return Err(anyhow::Error::msg(format!("no archived game {id}")));
+        };
+
+        for (username, accuracy) in [
+            (&game.attacker, analysis.attacker_accuracy),
+            (&game.defender, analysis.defender_accuracy),
+        ] {
+            if let Some(accuracy) = accuracy
+                && let Some(account) = self.accounts.0.get_mut(username)
+            {
+                account.accuracy.add(accuracy);
+            }
+        }
+
+        game.analysis = Some(analysis);
+        let usernames = [game.attacker.clone(), game.defender.clone()];
+        self.archive.push(game)?;
+
+        for username in usernames {
+            if let Some(account) = self.accounts.0.get(&username)
+                && let Some(index) = account.logged_in
+                && let Some(tx) = self.clients.get(&index)
+            {
+                let _ok = tx.send(format!("= game_analysis {id} {analysis_ron}"));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn game(
+        &mut self,
+        index_supplied: usize,
+        username: &str,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        if the_rest.len() < 5 {
+            return Some((
+                self.clients.get(&index_supplied)?.clone(),
+                Err(InvalidMove::Other),
+                (*command).to_string(),
+            ));
+        }
+
+        let index = the_rest.first()?;
+        let Ok(index) = index.parse() else {
+            return Some((
+                self.clients.get(&index_supplied)?.clone(),
+                Err(InvalidMove::Other),
+                (*command).to_string(),
+            ));
+        };
+        let role = the_rest.get(2)?;
+        let Ok(role) = Role::from_str(role) else {
+            return Some((
+                self.clients.get(&index_supplied)?.clone(),
+                Err(InvalidMove::Other),
+                (*command).to_string(),
+            ));
+        };
+        let from = the_rest.get(3)?;
+        let to = the_rest.get(4)?;
+        let mut to = (*to).to_string();
+        if to == "_" {
+            to = String::new();
+        }
+
+        let lag = self.lag(index_supplied);
+
+        let Some(game) = self.games.0.get_mut(&index) else {
+            return Some((
+                self.clients.get(&index_supplied)?.clone(),
+                Err(InvalidMove::Other),
+                (*command).to_string(),
+            ));
+        };
+
+        let Some(game_light) = self.games_light.0.get_mut(&index) else {
+            return Some((
+                self.clients.get(&index_supplied)?.clone(),
+                Err(InvalidMove::Other),
+                (*command).to_string(),
+            ));
+        };
+
+        // How many plays the game had when the client made the move, so a
+        // move sent again after a hiccup is acknowledged and not played twice.
+        if let Some(number) = the_rest.get(5) {
+            let made = game.game.plays.made();
+            let plays = made.len();
+            let Ok(number) = number.parse::<usize>() else {
+                return Some((
+                    self.clients.get(&index_supplied)?.clone(),
+                    Err(InvalidMove::Other),
+                    (*command).to_string(),
+                ));
+            };
+
+            if number != plays {
+                let resent = Plae::try_from(vec!["play", the_rest.get(2)?, from, the_rest.get(4)?]);
+                let result = match resent {
+                    Ok(play) if made.get(number) == Some(&&play) => {
+                        debug!("{index_supplied} {username} game {index} play {number} again");
+                        Ok(())
+                    }
+                    _ => Err(InvalidMove::MoveNumber(plays)),
+                };
+
+                return Some((
+                    self.clients.get(&index_supplied)?.clone(),
+                    result,
+                    (*command).to_string(),
+                ));
+            }
+        }
+
+        let lag = game.lag_allowed(lag);
+        game.elapsed_time = 0;
+        game.draw_requested = Role::Roleless;
+
+        let mut attackers_turn_next = true;
+        if role == Role::Attacker {
+            if *username == game.attacker {
+                let play =
+                    match Plae::try_from(["play", &role.to_string(), *from, &to, "_"].to_vec()) {
+                        Ok(play) => play,
+                        result @ Err(_) => {
+                            error!("play attacker {from} {to}: {result:?}");
+                            let error = result.map(|_string| ());
+
+                            return Some((
+                                self.clients.get(&index_supplied)?.clone(),
+                                error,
+                                (*command).to_string(),
+                            ));
+                        }
+                    };
+
+                game.lag_compensate(lag);
+                let plays = game.game.plays.len();
+                match game.game.play(&play) {
+                    // They ran out of time before the move got here.
+                    Ok(_moved) if game.game.plays.len() == plays => {
+                        game.lag_compensate_undo(lag);
+                    }
+                    Ok(_moved) => {
+                        let message = format!(
+                            "game {index} play attacker {from} {} {}",
+                            the_rest.get(4)?,
+                            game.game.checksum()
+                        );
+
+                        for spectator in game_light.spectators() {
+                            if let Some(client) = self.clients.get(&spectator) {
                                 let _ok = client.send(message.clone());
                             }
                         }
@@ -1176,6 +2675,7 @@ impl Server {
                     }
                     Err(error) => {
                         error!("play defender {from} {to}: {error:?}");
+                        game.lag_compensate_undo(lag);
 
                         return Some((
                             self.clients.get(&index_supplied)?.clone(),
@@ -1208,9 +2708,19 @@ impl Server {
                 }
             };
 
+            game.lag_compensate(lag);
+            let plays = game.game.plays.len();
             match game.game.play(&play) {
+                // They ran out of time before the move got here.
+                Ok(_moved) if game.game.plays.len() == plays => {
+                    game.lag_compensate_undo(lag);
+                }
                 Ok(_moved) => {
-                    let message = format!("game {index} play defender {from} {to}");
+                    let message = format!(
+                        "game {index} play defender {from} {} {}",
+                        the_rest.get(4)?,
+                        game.game.checksum()
+                    );
 
                     for spectator in game_light.spectators() {
                         if let Some(client) = self.clients.get(&spectator) {
@@ -1222,6 +2732,7 @@ impl Server {
                 }
                 Err(error) => {
                     error!("play defender {from} {to}: {error:?}");
+                    game.lag_compensate_undo(lag);
 
                     return Some((
                         self.clients.get(&index_supplied)?.clone(),
@@ -1238,36 +2749,66 @@ impl Server {
             ));
         }
 
+        if let Some(quiz) = self.quizzes.get_mut(&index)
+            && let Some(Plae::Play(play)) = game.game.plays.made().last()
+        {
+            quiz.played(play);
+        }
+
         let mut game_over = false;
+        let mut turn_notification = None;
         game_light.turn = Role::Roleless;
 
         match game.game.status {
             Status::AttackerWins => {
+                let category = RatingCategory::new(
+                    game_light.rule_set,
+                    game_light.board_size,
+                    &game_light.timed,
+                );
                 let accounts = &mut self.accounts.0;
-                let (attacker_rating, defender_rating) = if let (Some(attacker), Some(defender)) =
-                    (accounts.get(&game.attacker), accounts.get(&game.defender))
-                {
-                    (attacker.rating.rating, defender.rating.rating)
-                } else {
-                    unreachable!();
-                };
+                let (attacker_rating, defender_rating, attacker_category, defender_category) =
+                    if let (Some(attacker), Some(defender)) =
+                        (accounts.get(&game.attacker), accounts.get(&game.defender))
+                    {
+                        (
+                            attacker.rating.clone(),
+                            defender.rating.clone(),
+                            attacker.ratings.get(&category),
+                            defender.ratings.get(&category),
+                        )
+                    } else if community::no_account(&game.attacker)
+                        || community::no_account(&game.defender)
+                    {
+                        Default::default()
+                    } else {
+                        unreachable!();
+                    };
 
                 if let Some(attacker) = accounts.get_mut(&game.attacker) {
                     attacker.wins += 1;
 
                     if game.rated.into() {
-                        attacker
-                            .rating
-                            .update_rating(defender_rating, &Outcome::Win);
+                        attacker.rated_games += 1;
+                        attacker.rating_period.push(
+                            category,
+                            &defender_rating,
+                            &defender_category,
+                            Outcome::Win,
+                        );
                     }
                 }
                 if let Some(defender) = accounts.get_mut(&game.defender) {
                     defender.losses += 1;
 
                     if game.rated.into() {
-                        defender
-                            .rating
-                            .update_rating(attacker_rating, &Outcome::Loss);
+                        defender.rated_games += 1;
+                        defender.rating_period.push(
+                            category,
+                            &attacker_rating,
+                            &attacker_category,
+                            Outcome::Loss,
+                        );
                     }
                 }
 
@@ -1287,42 +2828,76 @@ impl Server {
                 // Handled in the draw fn.
             }
             Status::Ongoing => {
+                if let (Some(flag_timer), Some(deadline)) = (&self.flag_timer, game.game.flags_at())
+                {
+                    flag_timer.set(index, deadline);
+                }
+
                 if attackers_turn_next {
                     game_light.turn = Role::Attacker;
-                    game.attacker_tx
-                        .send(format!("game {index} generate_move attacker"));
+                    game.attacker_tx.send(format!(
+                        "game {index} generate_move attacker {}",
+                        game.game.checksum()
+                    ));
+                    turn_notification = Some((game.attacker.clone(), game.defender.clone()));
                 } else {
                     game_light.turn = Role::Defender;
-                    game.defender_tx
-                        .send(format!("game {index} generate_move defender"));
+                    game.defender_tx.send(format!(
+                        "game {index} generate_move defender {}",
+                        game.game.checksum()
+                    ));
+                    turn_notification = Some((game.defender.clone(), game.attacker.clone()));
                 }
             }
             Status::DefenderWins => {
+                let category = RatingCategory::new(
+                    game_light.rule_set,
+                    game_light.board_size,
+                    &game_light.timed,
+                );
                 let accounts = &mut self.accounts.0;
-                let (attacker_rating, defender_rating) = if let (Some(attacker), Some(defender)) =
-                    (accounts.get(&game.attacker), accounts.get(&game.defender))
-                {
-                    (attacker.rating.rating, defender.rating.rating)
-                } else {
-                    unreachable!()
-                };
-
-                if let Some(attacker) = accounts.get_mut(&game.attacker) {
-                    attacker.losses += 1;
+                let (attacker_rating, defender_rating, attacker_category, defender_category) =
+                    if let (Some(attacker), Some(defender)) =
+                        (accounts.get(&game.attacker), accounts.get(&game.defender))
+                    {
+                        (
+                            attacker.rating.clone(),
+                            defender.rating.clone(),
+                            attacker.ratings.get(&category),
+                            defender.ratings.get(&category),
+                        )
+                    } else if community::no_account(&game.attacker)
+                        || community::no_account(&game.defender)
+                    {
+                        Default::default()
+                    } else {
+                        unreachable!()
+                    };
+
+                if let Some(attacker) = accounts.get_mut(&game.attacker) {
+                    attacker.losses += 1;
 
                     if game.rated.into() {
-                        attacker
-                            .rating
-                            .update_rating(defender_rating, &Outcome::Loss);
+                        attacker.rated_games += 1;
+                        attacker.rating_period.push(
+                            category,
+                            &defender_rating,
+                            &defender_category,
+                            Outcome::Loss,
+                        );
                     }
                 }
                 if let Some(defender) = accounts.get_mut(&game.defender) {
                     defender.wins += 1;
 
                     if game.rated.into() {
-                        defender
-                            .rating
-                            .update_rating(attacker_rating, &Outcome::Win);
+                        defender.rated_games += 1;
+                        defender.rating_period.push(
+                            category,
+                            &attacker_rating,
+                            &attacker_category,
+                            Outcome::Win,
+                        );
                     }
                 }
 
@@ -1347,7 +2922,7 @@ impl Server {
 
             let message = Message {
                 username: "𓇳".to_string(),
-                timestamp: Timestamp::now(),
+                timestamp: clock::now(),
                 content: String::new(),
             };
 
@@ -1366,14 +2941,16 @@ impl Server {
             }
 
             game.messages.push_front(message);
+            self.quiz_results(index);
 
             if let Some(tournament) = &mut self.tournament.tournament
                 && tournament.is_tournament_game(&game.id)
             {
-                if tournament.game_over(&game) {
+                if tournament.game_over(&game, clock::now()) {
                     self.generate_round();
                 }
 
+                self.arena_pair();
                 self.tournament_status_all();
             }
 
@@ -1393,6 +2970,10 @@ impl Server {
                 id: game.id,
                 attacker_ms_left: time_1.milliseconds_left,
                 defender_ms_left: time_2.milliseconds_left,
+                timestamp: match game.game.time {
+                    TimeUnix::Time(timestamp) => timestamp,
+                    TimeUnix::UnTimed => clock::now().as_millisecond(),
+                },
             };
 
             if let Ok(string) = serde_json::ser::to_string(&game_time) {
@@ -1409,6 +2990,10 @@ impl Server {
             }
         }
 
+        if let Some((username, opponent)) = turn_notification {
+            self.turn_notification(&username, &opponent, index);
+        }
+
         Some((
             self.clients.get(&index_supplied)?.clone(),
             Ok(()),
@@ -1416,12 +3001,154 @@ impl Server {
         ))
     }
 
+    /// Starts games for the players in the arena who are logged in and not
+    /// playing a game. Returns whether it started any.
+    fn arena_pair(&mut self) -> bool {
+        let Some(mut tournament) = take(&mut self.tournament.tournament) else {
+            return false;
+        };
+
+        let mut paired = false;
+
+        if let Some(arena) = &tournament.arena {
+            let available = tournament
+                .players
+                .iter()
+                .filter(|player| {
+                    !tournament.withdrawn.contains(*player)
+                        && self
+                            .accounts
+                            .0
+                            .get(*player)
+                            .is_some_and(|account| account.logged_in.is_some())
+                        && !self
+                            .games
+                            .0
+                            .values()
+                            .any(|game| game.attacker == **player || game.defender == **player)
+                })
+                .cloned()
+                .collect();
+
+            for (attacker, defender) in arena.pair(&available, clock::now()) {
+                let id = self.new_game_accepted(
+                    &attacker,
+                    &defender,
+                    tournament.rated,
+                    tournament.time_setting,
+                    tournament.board_size,
+                );
+
+                if let Some(arena) = &mut tournament.arena {
+                    arena.game_started(id, attacker, defender);
+                }
+
+                paired = true;
+            }
+        }
+
+        self.tournament.tournament = Some(tournament);
+        paired
+    }
+
+    /// Halves the player's clock in an arena game and takes away the time
+    /// they get for each move, before they make their first move.
+    ///
+    /// ```sh
+    /// <- berserk ID
+    /// -> = berserk ID
+    /// ```
+    fn berserk(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let channel = self.clients.get(&index_supplied)?.clone();
+
+        let Some(id) = the_rest.first().and_then(|id| id.parse::<Id>().ok()) else {
+            return Some((channel, Err(InvalidMove::Other), (*command).to_string()));
+        };
+
+        let Some(arena) = self
+            .tournament
+            .tournament
+            .as_mut()
+            .and_then(|tournament| tournament.arena.as_mut())
+            .filter(|arena| arena.can_berserk(id, username))
+        else {
+            let reply = format!("{command} {id} you can't go berserk in this game");
+            return Some((channel, Err(InvalidMove::Other), reply));
+        };
+
+        let Some(game) = self.games.0.get_mut(&id) else {
+            return Some((channel, Err(InvalidMove::Other), (*command).to_string()));
+        };
+
+        let (moved, time) = if game.attacker == username {
+            (!game.game.plays.is_empty(), &mut game.game.attacker_time)
+        } else {
+            (game.game.plays.len() > 1, &mut game.game.defender_time)
+        };
+
+        if moved {
+            let reply = format!("{command} {id} you already moved");
+            return Some((channel, Err(InvalidMove::Other), reply));
+        }
+
+        // Without a clock there is nothing to give up for the bonus.
+        let TimeSettings::Timed(time) = time else {
+            let reply = format!("{command} {id} the game isn't timed");
+            return Some((channel, Err(InvalidMove::Other), reply));
+        };
+
+        time.milliseconds_left /= 2;
+        time.add_seconds = 0;
+
+        info!("{index_supplied} {username} berserk {id}");
+        arena.berserk.insert((id, username.to_string()));
+
+        if let (Timed(time_1), Timed(time_2)) = (game.game.attacker_time, game.game.defender_time) {
+            let game_time = GameTime {
+                id,
+                attacker_ms_left: time_1.milliseconds_left,
+                defender_ms_left: time_2.milliseconds_left,
+                timestamp: match game.game.time {
+                    TimeUnix::Time(timestamp) => timestamp,
+                    TimeUnix::UnTimed => clock::now().as_millisecond(),
+                },
+            };
+
+            if let Ok(string) = serde_json::ser::to_string(&game_time) {
+                let message = format!("= game_time {string}");
+
+                game.attacker_tx.send(message.clone());
+                game.defender_tx.send(message.clone());
+
+                if let Some(game_light) = self.games_light.0.get(&id) {
+                    for index in game_light.spectators() {
+                        if let Some(sender) = self.clients.get(&index) {
+                            let _ok = sender.send(message.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        self.flag_timer_set(id);
+        self.tournament_status_all();
+
+        Some((channel, Ok(()), format!("{command} {id}")))
+    }
+
     fn generate_round(&mut self) {
         let Some(mut tournament) = take(&mut self.tournament.tournament) else {
             return;
         };
 
         let groups = tournament.generate_round(&self.accounts);
+        tournament.round_started = clock::now();
         let mut ids = VecDeque::new();
         let mut groups_arc_mutex = Vec::new();
 
@@ -1430,18 +3157,20 @@ impl Server {
                 for _ in 0..tournament.number_of_games {
                     if let (Some(first), Some(second)) = (combination.first(), combination.get(1)) {
                         ids.push_back((
-                            self.new_tournament_game(
+                            self.new_game_accepted(
                                 first,
                                 second,
+                                tournament.rated,
                                 tournament.time_setting,
                                 tournament.board_size,
                             ),
                             i,
                         ));
                         ids.push_back((
-                            self.new_tournament_game(
+                            self.new_game_accepted(
                                 second,
                                 first,
+                                tournament.rated,
                                 tournament.time_setting,
                                 tournament.board_size,
                             ),
@@ -1502,35 +3231,39 @@ impl Server {
 
         info!("{index_supplied} {username} email {}", email.tx());
 
-        let email_send = lettre::Message::builder()
-            .from("Hnefatafl Org <noreply@hnefatafl.org>".parse().ok()?)
-            .to(email.to_mailbox()?)
-            .subject("Account Verification")
-            .header(ContentType::TEXT_PLAIN)
-            .body(format!(
-                "Dear {username},\nyour email verification code is as follows: {random_u32:x}",
-            ))
-            .ok()?;
+        let template = EmailTemplate::AccountVerification {
+            username: username.to_string(),
+            code: format!("{random_u32:x}"),
+        };
 
-        let credentials = Credentials::new(self.smtp.username.clone(), self.smtp.password.clone());
+        let email_send = template
+            .message(
+                lettre::Message::builder().to(email.to_mailbox()?),
+                account.locale,
+                None,
+            )
+            .ok()?;
 
-        let mailer = SmtpTransport::relay(&self.smtp.service)
-            .ok()?
-            .credentials(credentials)
-            .build();
+        let mailer = self.config.smtp(&self.smtp).transport().ok()?;
 
         match mailer.send(&email_send) {
             Ok(_) => {
                 info!("email sent to {address} successfully!");
 
                 account.email = Some(email);
+                self.audit(index_supplied, username, &format!("{command} {address}"));
 
                 let reply = format!("email {address} false");
                 Some((self.clients.get(&index_supplied)?.clone(), Ok(()), reply))
             }
             Err(err) => {
-                let reply = format!("could not send email to {address}");
-                error!("{reply}: {err}");
+                error!("could not send email to {address}: {err}");
+                let reply = t!(
+                    "could not send email to %{address}",
+                    locale = &account.locale.txt(),
+                    address = address,
+                );
+                let reply = format!("{command} {reply}");
 
                 Some((
                     self.clients.get(&index_supplied)?.clone(),
@@ -1541,25 +3274,56 @@ impl Server {
         }
     }
 
-    fn handle_messages(&mut self, rx: &mpsc::Receiver<(String, Option<mpsc::Sender<String>>)>) {
+    fn handle_messages(
+        &mut self,
+        rx: &mpsc::Receiver<(String, Option<mpsc::Sender<String>>)>,
+        mut journal: Option<Journal>,
+    ) {
         loop {
-            if let Ok((message, option_tx)) = rx.recv()
-                && let Some((tx, result, command)) =
-                    self.handle_messages_internal(&message, option_tx)
-            {
-                match result {
-                    Ok(()) => {
-                        if let Err(error) = tx.send(format!("= {command}")) {
-                            error!("handle_messages: {message}: {error}");
-                        }
+            if let Ok((message, option_tx)) = rx.recv() {
+                let client = option_tx.is_some();
+                let login = journal::is_login(&message);
+
+                if !login
+                    && let Some(journal) = &mut journal
+                    && let Err(error) = journal.write(&message, client, false)
+                {
+                    error!("journal: {error}");
+                }
+
+                let handled = self.handle_message(&message, option_tx);
+
+                if login
+                    && let Some(journal) = &mut journal
+                    && let Err(error) = journal.write(&message, client, handled)
+                {
+                    error!("journal: {error}");
+                }
+            }
+        }
+    }
+
+    /// Handles the message and answers it, returns whether it went through.
+    fn handle_message(&mut self, message: &str, option_tx: Option<mpsc::Sender<String>>) -> bool {
+        if let Some((tx, result, command)) = self.handle_messages_internal(message, option_tx) {
+            match result {
+                Ok(()) => {
+                    if let Err(error) = tx.send(format!("= {command}")) {
+                        error!("handle_messages: {message}: {error}");
                     }
-                    Err(error) => {
-                        if let Err(error) = tx.send(format!("? {command} {error}")) {
-                            error!("handle_messages: {message}: {error}");
-                        }
+
+                    true
+                }
+                Err(error) => {
+                    if let Err(error) = tx.send(format!("? {command} {error}")) {
+                        error!("handle_messages: {message}: {error}");
                     }
+
+                    false
                 }
             }
+        } else {
+            false
         }
     }
 
@@ -1579,17 +3343,22 @@ impl Server {
         ) {
             let username = *username;
 
-            if *command != "check_update_rd"
+            if *command != "check_rating_period"
+                && *command != "check_update_rd"
                 && *command != "create_account"
                 && *command != "display_server"
+                && *command != "events_run"
+                && *command != "flag"
                 && *command != "join_game_pending"
                 && *command != "leave_game"
                 && *command != "login"
                 && *command != "logout"
                 && *command != "ping"
+                && *command != "pong"
                 && *command != "resume_game"
                 && *command != "resume_game_json"
                 && *command != "resume_game_ron"
+                && *command != "tournament_reminder"
             {
                 debug!("{index_supplied} {username} {command}");
             }
@@ -1597,9 +3366,27 @@ impl Server {
             let index_supplied = index_supplied.parse::<usize>().ok()?;
             let the_rest: Vec<_> = index_username_command.clone().into_iter().skip(3).collect();
 
+            if let Some(permission) = Permission::required(command) {
+                if !self.roles.allows(username, permission) {
+                    error!("{index_supplied} {username} {command}: not permitted");
+                    self.audit(
+                        index_supplied,
+                        username,
+                        &format!("{command} not_permitted"),
+                    );
+                    return None;
+                }
+
+                self.audit(
+                    index_supplied,
+                    username,
+                    &index_username_command.iter().skip(2).join(" "),
+                );
+            }
+
             match *command {
                 "admin" => {
-                    if self.admins.contains(username) {
+                    if self.roles.allows(username, Permission::Accounts) {
                         self.clients
                             .get(&index_supplied)?
                             .send("= admin".to_string())
@@ -1609,7 +3396,7 @@ impl Server {
                     None
                 }
                 "admin_tournament" => {
-                    if self.admins_tournament.contains(username) {
+                    if self.roles.allows(username, Permission::Tournament) {
                         self.clients
                             .get(&index_supplied)?
                             .send("= admin_tournament".to_string())
@@ -1618,27 +3405,196 @@ impl Server {
 
                     None
                 }
-                "archived_games" => {
+                "announcement" => {
                     self.clients
                         .get(&index_supplied)?
-                        .send("= archived_games".to_string())
+                        .send(self.announcement())
                         .ok()?;
 
-                    self.clients
-                        .get(&index_supplied)?
-                        .send(ron::ser::to_string(&self.archived_games).ok()?)
-                        .ok()?;
+                    None
+                }
+                "archived_games" => {
+                    let tx = self.clients.get(&index_supplied)?;
+
+                    let games = match ArchiveFilter::new(&the_rest)
+                        .and_then(|filter| self.archive.games(&filter))
+                    {
+                        Ok(games) => games,
+                        Err(error) => {
+                            error!("{index_supplied} archived_games: {error}");
+                            return Some((
+                                tx.clone(),
+                                Err(InvalidMove::Other),
+                                (*command).to_string(),
+                            ));
+                        }
+                    };
+
+                    tx.send("= archived_games".to_string()).ok()?;
+                    tx.send(ron::ser::to_string(&games).ok()?).ok()?;
+
+                    None
+                }
+                "audit_log" => Some((
+                    self.clients.get(&index_supplied)?.clone(),
+                    Ok(()),
+                    format!(
+                        "{command} {}",
+                        ron::ser::to_string(&self.audit_log.digest(the_rest.first().copied()))
+                            .ok()?
+                    ),
+                )),
+                "avatar" | "avatar_clear" | "avatar_remove" | "avatar_set" => {
+                    self.avatar(username, index_supplied, command, &the_rest)
+                }
+                "backup" => {
+                    if index_supplied == 0 {
+                        self.write_backup();
+                    }
+
+                    None
+                }
+                "ban" | "bans" | "unban" => self.ban(username, index_supplied, command, &the_rest),
+                "berserk" => self.berserk(username, index_supplied, command, &the_rest),
+                "bridge_text" => {
+                    if index_supplied == 0
+                        && let Some((name, the_rest)) = the_rest.split_first()
+                        && let Some((username, the_rest)) = the_rest.split_first()
+                    {
+                        self.text(
+                            index_supplied,
+                            &format!("{username} ({name})"),
+                            &the_rest.join(" "),
+                            Some(name),
+                        );
+                    }
 
                     None
                 }
                 "change_password" => {
                     self.change_password(username, index_supplied, command, the_rest.as_slice())
                 }
+                "chat_slow_mode" => {
+                    if let Some(seconds) = the_rest.first() {
+                        let Ok(seconds) = seconds.parse() else {
+                            return Some((
+                                self.clients.get(&index_supplied)?.clone(),
+                                Err(InvalidMove::Other),
+                                (*command).to_string(),
+                            ));
+                        };
+
+                        self.chat_slow_mode = seconds;
+                        info!("{index_supplied} {username} chat_slow_mode {seconds}");
+                    }
+
+                    Some((
+                        self.clients.get(&index_supplied)?.clone(),
+                        Ok(()),
+                        format!("{command} {}", self.chat_slow_mode),
+                    ))
+                }
+                "club_delete" | "club_description" | "club_invite" | "club_join" | "club_leave"
+                | "club_new" | "club_remove" => {
+                    self.club(username, index_supplied, command, &the_rest)
+                }
+                "club_text" => self.club_text(username, index_supplied, command, &the_rest),
+                "club_texts" => {
+                    let name = the_rest.first()?;
+
+                    if self.clubs.is_member(name, username) {
+                        let texts = self.texts_club.get(*name).cloned().unwrap_or_default();
+                        let texts = self.messages_for(username, &texts);
+                        let texts = ron::ser::to_string(&texts).ok()?;
+
+                        self.clients
+                            .get(&index_supplied)?
+                            .send(format!("= club_texts {name} {texts}"))
+                            .ok()?;
+                    }
+
+                    None
+                }
+                "clubs" => {
+                    if args.skip_advertising_updates {
+                        None
+                    } else {
+                        let tx = self.clients.get(&index_supplied)?;
+                        let clubs = ron::ser::to_string(&self.clubs).ok()?;
+
+                        Some((tx.clone(), Ok(()), format!("clubs {clubs}")))
+                    }
+                }
+                "check_rating_period" => {
+                    let bool = self.check_rating_period();
+                    info!("0 {username} check_rating_period {bool}");
+                    None
+                }
                 "check_update_rd" => {
                     let bool = self.check_update_rd();
                     info!("0 {username} check_update_rd {bool}");
                     None
                 }
+                "claim" => self.claim(username, index_supplied, command, &the_rest),
+                "client_connected" => {
+                    if index_supplied == 0
+                        && let (Some(index), Some(address)) = (the_rest.first(), the_rest.get(1))
+                        && let (Ok(index), Ok(address)) = (index.parse(), address.parse())
+                    {
+                        self.addresses.insert(index, address);
+
+                        if let Some(message) = self.banned(index, None) {
+                            self.audit(index, "_", "connect banned");
+                            option_tx?.send(message).ok()?;
+                            return None;
+                        }
+
+                        return Some((option_tx?, Ok(()), (*command).to_string()));
+                    }
+
+                    None
+                }
+                "client_disconnected" => {
+                    if index_supplied == 0
+                        && let Some(Ok(index)) = the_rest.first().map(|index| index.parse())
+                    {
+                        self.addresses.remove(&index);
+                    }
+
+                    None
+                }
+                "community_game_new" => {
+                    self.community_game_new(username, index_supplied, command, &the_rest)
+                }
+                "community_vote" => {
+                    self.community_vote(username, index_supplied, command, &the_rest)
+                }
+                "config_reload" => {
+                    let result = Config::load(&args).map(|config| {
+                        let restart = config.needs_restart(&self.config);
+                        if !restart.is_empty() {
+                            info!("config_reload: {} change on a restart", restart.join(", "));
+                        }
+
+                        self.unsubscribe_url.clone_from(&config.unsubscribe_url);
+                        self.config = config;
+                    });
+
+                    if let Err(error) = result {
+                        error!("{index_supplied} {username} config_reload: {error}");
+                        return Some((
+                            self.clients.get(&index_supplied)?.clone(),
+                            Err(InvalidMove::Other),
+                            (*command).to_string(),
+                        ));
+                    }
+
+                    Some((
+                        self.clients.get(&index_supplied)?.clone(),
+                        Ok(()),
+                        (*command).to_string(),
+                    ))
+                }
                 "create_account" => self.create_account(
                     username,
                     index_supplied,
@@ -1646,6 +3602,9 @@ impl Server {
                     the_rest.as_slice(),
                     option_tx,
                 ),
+                "daily_puzzle" | "daily_puzzle_solve" => {
+                    self.daily_puzzle(username, index_supplied, command, &the_rest)
+                }
                 "decline_game" => self.decline_game(
                     username,
                     index_supplied,
@@ -1656,65 +3615,73 @@ impl Server {
                     self.delete_account(username, index_supplied);
                     None
                 }
+                "block" | "blocks" | "unblock" => {
+                    self.block(username, index_supplied, command, &the_rest)
+                }
                 "display_server" => self.display_server(username),
-                "draw" => self.draw(index_supplied, command, the_rest.as_slice()),
-                "game" => self.game(index_supplied, username, command, the_rest.as_slice()),
-                "email" => {
-                    self.set_email(index_supplied, username, command, the_rest.first().copied())
+                "event_delete" | "event_set" | "events" => {
+                    self.event(username, index_supplied, command, &the_rest)
                 }
-                "email_everyone" => {
-                    if self.admins.contains(username) {
-                        info!("{index_supplied} {username} email_everyone");
-                    } else {
-                        error!("{index_supplied} {username} email_everyone");
-                        return None;
+                "events_run" => {
+                    if index_supplied == 0 {
+                        self.events_run(clock::now());
+                        self.daily_puzzle_pick(clock::now());
                     }
 
-                    let emails_bcc = self.bcc_mailboxes(username);
-                    let subject = the_rest.first()?;
-                    let email_string = the_rest.get(1..)?.join(" ").replace("\\n", "\n");
-                    let mut email = lettre::Message::builder();
+                    None
+                }
+                "export_accounts" | "export_ratings" | "export_results" | "export_standings" => {
+                    self.export(index_supplied, command, &the_rest)
+                }
+                "flag" => {
+                    self.flag(&the_rest);
+                    None
+                }
+                "dm" => self.dm(username, index_supplied, command, &the_rest),
+                "dms_pending" => {
+                    let channel = self.clients.get(&index_supplied)?;
+                    let pending = self.dms_pending.remove(username).unwrap_or_default();
+
+                    for message in &pending {
+                        let message = Message {
+                            content: self.censor_for(self.chat_shown(username), &message.content),
+                            ..message.clone()
+                        };
 
-                    for email_bcc in emails_bcc {
-                        email = email.bcc(email_bcc);
+                        if let Ok(message_se) = ron::ser::to_string(&message) {
+                            let _ok =
+                                channel.send(format!("= dm {} {message_se}", message.username));
+                        }
                     }
 
-                    let email = email
-                        .from("Hnefatafl Org <noreply@hnefatafl.org>".parse().ok()?)
-                        .subject(*subject)
-                        .header(ContentType::TEXT_PLAIN)
-                        .body(email_string)
-                        .ok()?;
-
-                    let credentials =
-                        Credentials::new(self.smtp.username.clone(), self.smtp.password.clone());
-
-                    let mailer = SmtpTransport::relay(&self.smtp.service)
-                        .ok()?
-                        .credentials(credentials)
-                        .build();
-
-                    match mailer.send(&email) {
-                        Ok(_) => {
-                            info!("emails sent successfully!");
-
-                            Some((
-                                self.clients.get(&index_supplied)?.clone(),
-                                Ok(()),
-                                (*command).to_string(),
-                            ))
-                        }
-                        Err(err) => {
-                            let reply = "could not send emails";
-                            error!("{reply}: {err}");
+                    if !pending.is_empty() {
+                        self.save_server();
+                    }
 
-                            Some((
-                                self.clients.get(&index_supplied)?.clone(),
-                                Err(InvalidMove::Other),
-                                reply.to_string(),
-                            ))
-                        }
+                    None
+                }
+                "draw" => self.draw(index_supplied, command, the_rest.as_slice()),
+                "game" => self.game(index_supplied, username, command, the_rest.as_slice()),
+                "game_analysis" => {
+                    if index_supplied == 0
+                        && let Err(error) = self.game_analysis(&the_rest)
+                    {
+                        error!("game_analysis: {error}");
                     }
+
+                    None
+                }
+                "game_tags" => {
+                    self.game_tags(username, index_supplied, command, the_rest.as_slice())
+                }
+                "email" => {
+                    self.set_email(index_supplied, username, command, the_rest.first().copied())
+                }
+                "email_everyone" => {
+                    self.email_everyone(index_supplied, username, command, &the_rest)
+                }
+                "email_preferences" => {
+                    self.email_preferences(username, index_supplied, command, &the_rest)
                 }
                 "emails_bcc" => {
                     let emails_bcc = self.bcc_send(username);
@@ -1760,6 +3727,16 @@ impl Server {
                             .get(&index_supplied)?
                             .send(format!("= email {} {}", email.address, email.verified))
                             .ok()?;
+
+                        if email.verified {
+                            let preferences =
+                                serde_json::to_string(&account.email_preferences).ok()?;
+
+                            self.clients
+                                .get(&index_supplied)?
+                                .send(format!("= email_preferences {preferences}"))
+                                .ok()?;
+                        }
                     }
 
                     None
@@ -1767,6 +3744,7 @@ impl Server {
                 "email_reset" => {
                     if let Some(account) = self.accounts.0.get_mut(username) {
                         account.email = None;
+                        self.audit(index_supplied, username, command);
 
                         Some((
                             self.clients.get(&index_supplied)?.clone(),
@@ -1779,16 +3757,7 @@ impl Server {
                 }
                 "exit" => {
                     info!("saving active games...");
-                    let mut active_games = Vec::new();
-                    for game in self.games.0.values() {
-                        let mut serialized_game = ServerGameSerialized::from(game);
-
-                        if let Some(game_light) = self.games_light.0.get(&game.id) {
-                            serialized_game.timed = game_light.timed;
-                        }
-
-                        active_games.push(serialized_game);
-                    }
+                    let active_games = self.active_games();
 
                     let mut file = handle_error(File::create(data_file(ACTIVE_GAMES_FILE)));
                     handle_error(
@@ -1799,16 +3768,29 @@ impl Server {
 
                     exit(0);
                 }
-                "initialize_display" => {
-                    if args.skip_advertising_updates {
-                        return None;
+                "handoff" => {
+                    if index_supplied == 0 {
+                        match self.handoff() {
+                            Ok(()) => {
+                                info!("handed off to the new server");
+                                exit(0);
+                            }
+                            Err(error) => error!("handoff: {error}"),
+                        }
+                    }
+
+                    None
+                }
+                "initialize_display" => {
+                    if args.skip_advertising_updates {
+                        return None;
                     }
 
                     let tx = self.clients.get(&index_supplied)?;
-                    let admin = self.admins.contains(username);
-                    let admin_tournament = self.admins_tournament.contains(username);
+                    let admin = self.roles.allows(username, Permission::Accounts);
+                    let admin_tournament = self.roles.allows(username, Permission::Tournament);
                     let games = self.games_light.display_games(Some(username));
-                    let users = if self.admins.contains(username) {
+                    let users = if admin {
                         AccountsOrUsers::Accounts(self.accounts.clone())
                     } else {
                         AccountsOrUsers::Users(Users::from(&self.accounts))
@@ -1834,11 +3816,25 @@ impl Server {
                     the_rest.as_slice(),
                 ),
                 "join_tournament" => {
+                    let account = self.accounts.0.get(username)?;
+
+                    if let Err(error) = self.tournament.can_join(username, account, &self.clubs) {
+                        return Some((
+                            self.clients.get(&index_supplied)?.clone(),
+                            Err(InvalidMove::Other),
+                            format!("{command} {error}"),
+                        ));
+                    }
+
                     self.tournament.players.insert(username.to_string());
                     self.tournament_status_all();
 
                     None
                 }
+                "kibitz" => self.kibitz(username, index_supplied, command, &the_rest),
+                "kid_safe" | "kid_safe_friends" | "kid_safe_set" => {
+                    self.kid_safe(username, index_supplied, command, &the_rest)
+                }
                 "leave_game" => self.leave_game(
                     username,
                     index_supplied,
@@ -1858,47 +3854,133 @@ impl Server {
                     the_rest.as_slice(),
                     option_tx,
                 ),
+                "locale" => {
+                    if let Some(locale) = the_rest.first()
+                        && let Ok(locale) = Locale::from_str(locale)
+                        && let Some(account) = self.accounts.0.get_mut(username)
+                    {
+                        account.locale = locale;
+                        self.save_server();
+                    }
+
+                    None
+                }
                 "logout" => self.logout(username, index_supplied, command),
+                "mentor" => {
+                    let account = self.accounts.0.get_mut(username)?;
+
+                    if let Some(mentor) = the_rest.first() {
+                        let Ok(mentor) = mentor.parse() else {
+                            return Some((
+                                self.clients.get(&index_supplied)?.clone(),
+                                Err(InvalidMove::Other),
+                                (*command).to_string(),
+                            ));
+                        };
+
+                        account.mentor = mentor;
+                        info!("{index_supplied} {username} mentor {mentor}");
+                    }
+
+                    Some((
+                        self.clients.get(&index_supplied)?.clone(),
+                        Ok(()),
+                        format!("{command} {}", account.mentor),
+                    ))
+                }
+                "motd" => {
+                    self.clients
+                        .get(&index_supplied)?
+                        .send(self.motd.message())
+                        .ok()?;
+
+                    None
+                }
+                "motd_clear" | "motd_history" | "motd_set" => {
+                    self.motd(username, index_supplied, command, &the_rest)
+                }
                 "new_game" => self.new_game(username, index_supplied, command, the_rest.as_slice()),
-                "ping" => Some((
-                    self.clients.get(&index_supplied)?.clone(),
-                    Ok(()),
-                    (*command).to_string(),
-                )),
+                "ping" => {
+                    self.pings.insert(index_supplied, clock::now());
+
+                    Some((
+                        self.clients.get(&index_supplied)?.clone(),
+                        Ok(()),
+                        format!("{command} {}", clock::now().as_millisecond()),
+                    ))
+                }
+                "pong" => {
+                    // The server times the round trip itself, but a client can
+                    // still hold back its pong, so one slow pong only counts
+                    // once the ones around it are as slow.
+                    if let Some(pinged) = self.pings.remove(&index_supplied) {
+                        let lag = clock::now().as_millisecond() - pinged.as_millisecond();
+                        let lags = self.lags.entry(index_supplied).or_default();
+                        lags.push_back(lag.max(0));
+
+                        if lags.len() > LAG_ROUND_TRIPS {
+                            lags.pop_front();
+                        }
+                    }
+
+                    None
+                }
+                "profile" | "profile_set" => {
+                    self.profile(username, index_supplied, command, &the_rest)
+                }
+                "profanity_filter" => {
+                    self.profanity_filter(username, index_supplied, command, &the_rest)
+                }
+                "profanity_words" => {
+                    self.profanity_words(username, index_supplied, command, &the_rest)
+                }
+                "puzzle" => self.puzzle(index_supplied, command),
+                "quiz_guess" => self.quiz_guess(username, index_supplied, command, &the_rest),
+                "puzzles_found" => {
+                    if index_supplied == 0
+                        && let Err(error) = self.puzzles_found(&the_rest)
+                    {
+                        error!("puzzles_found: {error}");
+                    }
+
+                    None
+                }
+                "report" | "reports" | "reports_clear" => {
+                    self.report(username, index_supplied, command, &the_rest)
+                }
+                "bug_report" | "bug_reports" | "bug_reports_clear" => {
+                    self.bug_report(username, index_supplied, command, &the_rest)
+                }
                 "reset_password" => {
                     let account = self.accounts.0.get_mut(username)?;
                     if let Some(email) = &account.email {
                         if email.verified {
                             let day = 60 * 60 * 24;
-                            let now = Timestamp::now().as_second();
+                            let now = clock::now().as_second();
                             if now - account.email_sent > day {
                                 let password = format!("{:x}", random::<u32>());
                                 account.password = hash_password(&password)?;
 
-                                let message = lettre::Message::builder()
-                                .from("Hnefatafl Org <noreply@hnefatafl.org>".parse().ok()?)
-                                .to(email.to_mailbox()?)
-                                .subject("Password Reset")
-                                .header(ContentType::TEXT_PLAIN)
-                                .body(format!(
-                                    "Dear {username},\nyour new password is as follows: {password}",
-                                ))
-                                .ok()?;
+                                let template = EmailTemplate::PasswordReset {
+                                    username: username.to_string(),
+                                    password,
+                                };
 
-                                let credentials = Credentials::new(
-                                    self.smtp.username.clone(),
-                                    self.smtp.password.clone(),
-                                );
+                                let message = template
+                                    .message(
+                                        lettre::Message::builder().to(email.to_mailbox()?),
+                                        account.locale,
+                                        None,
+                                    )
+                                    .ok()?;
 
-                                let mailer = SmtpTransport::relay(&self.smtp.service)
-                                    .ok()?
-                                    .credentials(credentials)
-                                    .build();
+                                let mailer = self.config.smtp(&self.smtp).transport().ok()?;
 
                                 match mailer.send(&message) {
                                     Ok(_) => {
                                         info!("email sent to {} successfully!", email.address);
                                         account.email_sent = now;
+                                        self.audit(index_supplied, username, command);
                                     }
                                     Err(err) => {
                                         error!("could not send email to {}: {err}", email.address);
@@ -1927,14 +4009,22 @@ impl Server {
                         if game.attacker == username {
                             self.clients
                                 .get(&index_supplied)?
-                                .send(format!("game {} generate_move attacker", game.id))
+                                .send(format!(
+                                    "game {} generate_move attacker {}",
+                                    game.id,
+                                    game.game.checksum()
+                                ))
                                 .ok()?;
                         }
 
                         if game.defender == username {
                             self.clients
                                 .get(&index_supplied)?
-                                .send(format!("game {} generate_move defender", game.id))
+                                .send(format!(
+                                    "game {} generate_move defender {}",
+                                    game.id,
+                                    game.game.checksum()
+                                ))
                                 .ok()?;
                         }
                     }
@@ -1942,12 +4032,53 @@ impl Server {
                     None
                 }
                 "request_draw" => self.request_draw(username, index_supplied, command, &the_rest),
+                "role_remove" | "role_set" | "roles" => {
+                    self.roles(index_supplied, command, &the_rest)
+                }
                 "save" => {
                     debug!("saving users file...");
                     self.save_server();
 
                     None
                 }
+                "scout" => self.scout(index_supplied, command, &the_rest),
+                "settings" => self.settings(username, index_supplied, command, &the_rest),
+                "show_profanity" => {
+                    let account = self.accounts.0.get_mut(username)?;
+
+                    if let Some(show) = the_rest.first() {
+                        let Ok(show) = show.parse() else {
+                            return Some((
+                                self.clients.get(&index_supplied)?.clone(),
+                                Err(InvalidMove::Other),
+                                (*command).to_string(),
+                            ));
+                        };
+
+                        account.show_profanity = show;
+                        info!("{index_supplied} {username} show_profanity {show}");
+                    }
+
+                    Some((
+                        self.clients.get(&index_supplied)?.clone(),
+                        Ok(()),
+                        format!("{command} {}", account.show_profanity),
+                    ))
+                }
+                "simul_join" => self.simul_join(username, index_supplied, command, &the_rest),
+                "simul_leave" => self.simul_leave(username, index_supplied, command, &the_rest),
+                "simul_new" => self.simul_new(username, index_supplied, command, &the_rest),
+                "simul_start" => self.simul_start(username, index_supplied, command, &the_rest),
+                "simuls" => {
+                    if args.skip_advertising_updates {
+                        None
+                    } else {
+                        let tx = self.clients.get(&index_supplied)?;
+                        let simuls = ron::ser::to_string(&self.simuls).ok()?;
+
+                        Some((tx.clone(), Ok(()), format!("simuls {simuls}")))
+                    }
+                }
                 "software_id" => {
                     if let Some(software_id) = the_rest.first()
                         && let Some(account) = self.accounts.0.get_mut(username)
@@ -1959,43 +4090,16 @@ impl Server {
                     None
                 }
                 "text" => {
-                    let timestamp = Timestamp::now();
-                    let mut content = the_rest.join(" ");
-                    content = self.censor(&content);
-
-                    let message = Message {
-                        username: username.to_string(),
-                        timestamp,
-                        content,
-                    };
-
-                    info!("{index_supplied} text {message:?}");
-
-                    if message.content.is_empty() {
-                        return None;
-                    }
-
-                    let Ok(message_se) = ron::ser::to_string(&message) else {
-                        return None;
-                    };
-
-                    let message_se = format!("= text {message_se}");
-
-                    if self.texts.len() >= KEEP_TEXTS {
-                        self.texts.pop_front();
-                    }
-
-                    for tx in &mut self.clients.values() {
-                        let _ok = tx.send(message_se.clone());
+                    if !self.slow_mode_wait(index_supplied, username, None) {
+                        self.text(index_supplied, username, &the_rest.join(" "), None);
                     }
 
-                    self.texts.push_back(message);
-
                     None
                 }
                 "texts" => {
                     if !self.texts.is_empty() {
-                        let Ok(string) = ron::ser::to_string(&self.texts) else {
+                        let texts = self.messages_for(username, &self.texts);
+                        let Ok(string) = ron::ser::to_string(&texts) else {
                             unreachable!();
                         };
 
@@ -2008,62 +4112,124 @@ impl Server {
                     None
                 }
                 "text_game" => self.text_game(username, index_supplied, command, the_rest),
+                "text_tournament" => {
+                    self.text_tournament(index_supplied, username, command, &the_rest.join(" "))
+                }
+                "texts_unread" => {
+                    let games = &self.games.0;
+                    let account = self.accounts.0.get_mut(username)?;
+                    account.texts_read.retain(|id, _| games.contains_key(id));
+
+                    let unread = ron::ser::to_string(&self.texts_unread(username)).ok()?;
+
+                    Some((
+                        self.clients.get(&index_supplied)?.clone(),
+                        Ok(()),
+                        format!("{command} {unread}"),
+                    ))
+                }
+                "texts_tournament" => {
+                    if self.tournament.has_player(username)
+                        || self.roles.allows(username, Permission::Tournament)
+                    {
+                        let texts = self.messages_for(username, &self.texts_tournament);
+                        let Ok(string) = ron::ser::to_string(&texts) else {
+                            unreachable!();
+                        };
+
+                        self.clients
+                            .get(&index_supplied)?
+                            .send(format!("= texts_tournament {string}"))
+                            .ok()?;
+                    }
+
+                    None
+                }
+                "tournament_arena" => {
+                    if let Err(error) = self.tournament_arena(&the_rest) {
+                        error!("tournament_arena: {error}");
+                    } else {
+                        self.tournament_status_all();
+                    }
+
+                    None
+                }
                 "tournament_board_size" => {
-                    if self.admins_tournament.contains(username) {
-                        if let Err(error) = self.tournament_board_size(&the_rest) {
-                            error!("tournament_board_size: {error}");
-                        } else {
-                            self.tournament_status_all();
-                        }
+                    if let Err(error) = self.tournament_board_size(&the_rest) {
+                        error!("tournament_board_size: {error}");
+                    } else {
+                        self.tournament_status_all();
                     }
 
                     None
                 }
                 "tournament_group_size" => {
-                    if self.admins_tournament.contains(username) {
-                        if let Err(error) = self.tournament_group_size(&the_rest) {
-                            error!("tournament_group_size: {error}");
-                        } else {
-                            self.tournament_status_all();
-                        }
+                    if let Err(error) = self.tournament_group_size(&the_rest) {
+                        error!("tournament_group_size: {error}");
+                    } else {
+                        self.tournament_status_all();
                     }
 
                     None
                 }
                 "tournament_number_of_games" => {
-                    if self.admins_tournament.contains(username) {
-                        if let Err(error) = self.tournament_number_of_games(&the_rest) {
-                            error!("tournament_number_of_games: {error}");
-                        } else {
-                            self.tournament_status_all();
-                        }
+                    if let Err(error) = self.tournament_number_of_games(&the_rest) {
+                        error!("tournament_number_of_games: {error}");
+                    } else {
+                        self.tournament_status_all();
+                    }
+
+                    None
+                }
+                "tournament_rated" => {
+                    if let Err(error) = self.tournament_rated(&the_rest) {
+                        error!("tournament_rated: {error}");
+                    } else {
+                        self.tournament_status_all();
+                    }
+
+                    None
+                }
+                "tournament_requirements" => {
+                    if let Err(error) = self.tournament_requirements(&the_rest) {
+                        error!("tournament_requirements: {error}");
+                    } else {
+                        self.tournament_status_all();
+                    }
+
+                    None
+                }
+                "tournament_round_days" => {
+                    if let Err(error) = self.tournament_round_days(&the_rest) {
+                        error!("tournament_round_days: {error}");
+                    } else {
+                        self.tournament_status_all();
                     }
 
                     None
                 }
                 "tournament_time" => {
-                    if self.admins_tournament.contains(username) {
-                        if let Err(error) = self.tournament_time(&the_rest) {
-                            error!("tournament_time: {error}");
-                        } else {
-                            self.tournament_status_all();
-                        }
+                    if let Err(error) = self.tournament_time(&the_rest) {
+                        error!("tournament_time: {error}");
+                    } else {
+                        self.tournament_status_all();
                     }
 
                     None
                 }
                 "tournament_delete" => {
-                    if self.admins_tournament.contains(username) {
-                        self.tournament = TournamentFull::default();
-                        self.tournament_status_all();
+                    self.tournament = TournamentFull::default();
+                    self.texts_tournament.clear();
+                    self.tournament_status_all();
+
+                    for tx in self.clients.values() {
+                        let _ok = tx.send("= texts_tournament".to_string());
                     }
 
                     None
                 }
                 "tournament_groups_delete" => {
-                    if self.admins_tournament.contains(username)
-                        && self.tournament.tournament.is_some()
-                    {
+                    if self.tournament.tournament.is_some() {
                         self.tournament.tournament = None;
                         self.tournament_status_all();
                     }
@@ -2071,16 +4237,16 @@ impl Server {
                     None
                 }
                 "tournament_date" => {
-                    if self.admins_tournament.contains(username) {
-                        if let Err(error) = self.tournament_date(&the_rest) {
-                            error!("tournament_date: {error}");
-                        } else {
-                            self.tournament_status_all();
-                        }
+                    if let Err(error) = self.tournament_date(&the_rest) {
+                        error!("tournament_date: {error}");
+                    } else {
+                        self.tournament_status_all();
                     }
 
                     None
                 }
+                "ratings" => self.ratings(username, index_supplied, command, &the_rest),
+                "status" => self.status(option_tx),
                 "tournament_status" => {
                     trace!("tournament_status: {:#?}", self.tournament);
 
@@ -2097,40 +4263,30 @@ impl Server {
                         ))
                     }
                 }
-                "tournament_start" => {
-                    if self.admins_tournament.contains(username)
-                        && self.tournament.tournament.is_none()
-                        && let Some(date) = self.tournament.date
-                        && Timestamp::now() >= date
-                    {
-                        info!("Starting tournament...");
-
-                        if let TimeSettings::Timed(time) = &mut self.tournament.time_setting
-                            && time.milliseconds_left <= 1_000 * DAY_IN_SECONDS_SIGNED
-                            && let Ok(players) = i64::try_from(self.tournament.players.len())
-                        {
-                            time.milliseconds_left *= (players - 1) * 2;
-                            time.add_seconds *= (players - 1) * 2;
-                        }
-
-                        let mut tournament = Tournament {
-                            players: take(&mut self.tournament.players),
-                            board_size: self.tournament.board_size,
-                            time_setting: self.tournament.time_setting,
-                            group_size: self.tournament.group_size.size,
-                            number_of_games: self.tournament.number_of_games.number,
-                            ..Tournament::default()
-                        };
+                "tournament_reminder" => {
+                    if index_supplied == 0 {
+                        self.tournament_reminder_send();
+                    }
 
-                        tournament.date = Timestamp::now();
+                    None
+                }
+                "tournament_start" => {
+                    self.tournament_start(clock::now());
 
-                        self.tournament.tournament = Some(tournament);
-                        self.generate_round();
-                        self.tournament_status_all();
-                    }
+                    None
+                }
+                "tournament_withdraw" => {
+                    self.tournament_withdraw(username);
+                    self.tournament_status_all();
 
                     None
                 }
+                "unsubscribe" => {
+                    self.unsubscribe(username, index_supplied, command, &the_rest, option_tx)
+                }
+                "vacation" | "vacation_end" => {
+                    self.vacation(username, index_supplied, command, &the_rest)
+                }
                 "version" => {
                     if !args.skip_advertising_updates {
                         self.clients
@@ -2247,7 +4403,9 @@ impl Server {
             defender_channel.cloned(),
             game.clone(),
         );
+        let checksum = new_game.game.checksum();
         self.games.0.insert(id, new_game);
+        self.flag_timer_set(id);
 
         if let Some(account) = self.accounts.0.get_mut(username) {
             account.pending_games.remove(&id);
@@ -2255,7 +4413,7 @@ impl Server {
 
         if let Some(channel) = attacker_channel {
             channel
-                .send(format!("game {id} generate_move attacker"))
+                .send(format!("game {id} generate_move attacker {checksum}"))
                 .ok()?;
         }
 
@@ -2284,6 +4442,44 @@ impl Server {
             return Some((channel.clone(), Err(InvalidMove::Other), command));
         };
 
+        // The rating that changes is the one for the rules, board size, and speed.
+        if game.established_only
+            && game.rated == Rated::Yes
+            && let Some(account) = self.accounts.0.get(&username)
+            && account
+                .ratings
+                .get(&RatingCategory::new(
+                    game.rule_set,
+                    game.board_size,
+                    &game.timed,
+                ))
+                .provisional()
+        {
+            command.push_str(" the game requires an established rating");
+            return Some((channel.clone(), Err(InvalidMove::Other), command));
+        }
+
+        if game.rated == Rated::Yes
+            && let Some(creator) = game.attacker.as_ref().or(game.defender.as_ref())
+            && let (Some(account_1), Some(account_2)) =
+                (self.accounts.0.get(&username), self.accounts.0.get(creator))
+            && !account_1
+                .provisional_pool()
+                .allows(account_2.provisional_pool())
+        {
+            command.push_str(" new players only play rated games against mentors and each other");
+            return Some((channel.clone(), Err(InvalidMove::Other), command));
+        }
+
+        if let Some(creator) = game.attacker.as_ref().or(game.defender.as_ref())
+            && let (Some(account_1), Some(account_2)) =
+                (self.accounts.0.get(&username), self.accounts.0.get(creator))
+            && (!account_1.kid_safe_allows(creator) || !account_2.kid_safe_allows(&username))
+        {
+            command.push_str(" kid-safe accounts only play their approved friends");
+            return Some((channel.clone(), Err(InvalidMove::Other), command));
+        }
+
         if game.attacker.is_none() {
             game.attacker = Some(username.clone());
 
@@ -2313,6 +4509,121 @@ impl Server {
         Some((channel.clone(), Ok(()), command))
     }
 
+    fn kibitz(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let channel = self.clients.get(&index_supplied)?.clone();
+        let mut command = (*command).to_string();
+
+        let Some(game) = the_rest
+            .first()
+            .and_then(|id| id.parse::<Id>().ok())
+            .and_then(|id| self.games_light.0.get(&id))
+        else {
+            command.push_str(" there is no such game");
+            return Some((channel, Err(InvalidMove::Other), command));
+        };
+
+        if Some(username) == game.attacker.as_deref() || Some(username) == game.defender.as_deref()
+        {
+            command.push_str(" players may not kibitz");
+            return Some((channel, Err(InvalidMove::Other), command));
+        }
+
+        // An engine's evaluation is an outside help to the players.
+        if game.rated == Rated::Yes && game.challenge_accepted && !game.game_over {
+            command.push_str(" no one may kibitz during a rated game");
+            return Some((channel, Err(InvalidMove::Other), command));
+        }
+
+        let kibitz = the_rest.get(1..).unwrap_or_default().join(" ");
+        let Ok(mut kibitz) = ron::de::from_str::<Kibitz>(&kibitz) else {
+            command.push_str(" invalid evaluation");
+            return Some((channel, Err(InvalidMove::Other), command));
+        };
+
+        kibitz.username = username.to_string();
+        kibitz.timestamp = clock::now();
+
+        let kibitz = ron::ser::to_string(&kibitz).ok()?;
+        info!("{index_supplied} {username} kibitz {} {kibitz}", game.id);
+        let message = format!("= kibitz {} {kibitz}", game.id);
+
+        for index in game.spectators() {
+            if let Some(sender) = self.clients.get(&index) {
+                let _ok = sender.send(message.clone());
+            }
+        }
+
+        None
+    }
+
+    /// `kid_safe [true]`, `kid_safe_set USERNAME BOOL`, or
+    /// `kid_safe_friends USERNAME [FRIEND ...]`. Anyone can turn kid-safe mode
+    /// on for themselves, say when they make the account, but only an admin
+    /// can turn it off or approve friends.
+    fn kid_safe(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let channel = self.clients.get(&index_supplied)?.clone();
+        let mut command = (*command).to_string();
+
+        let (kid, the_rest) = if command == "kid_safe" {
+            (username, the_rest)
+        } else {
+            let Some((kid, the_rest)) = the_rest.split_first() else {
+                return Some((channel, Err(InvalidMove::Other), command));
+            };
+
+            (*kid, the_rest)
+        };
+
+        let Some(account) = self.accounts.0.get_mut(kid) else {
+            command.push_str(" there is no such user");
+            return Some((channel, Err(InvalidMove::Other), command));
+        };
+
+        match (command.as_str(), the_rest) {
+            ("kid_safe", []) => {}
+            ("kid_safe", ["true"]) => account.kid_safe = true,
+            ("kid_safe_set", [kid_safe]) => {
+                let Ok(kid_safe) = kid_safe.parse() else {
+                    return Some((channel, Err(InvalidMove::Other), command));
+                };
+
+                account.kid_safe = kid_safe;
+            }
+            ("kid_safe_friends", friends) => {
+                account.friends = friends.iter().map(ToString::to_string).collect();
+            }
+            _ => {
+                command.push_str(" only an admin can turn kid-safe mode off");
+                return Some((channel, Err(InvalidMove::Other), command));
+            }
+        }
+
+        let reply = format!(
+            "{command} {kid} {} {}",
+            account.kid_safe,
+            account.friends.iter().join(" ")
+        );
+
+        if !the_rest.is_empty() || command != "kid_safe" {
+            info!("{index_supplied} {username} {reply}");
+            self.audit(index_supplied, username, &reply);
+        }
+
+        Some((channel, Ok(()), reply.trim_end().to_string()))
+    }
+
     fn leave_game(
         &mut self,
         username: &str,
@@ -2397,8 +4708,15 @@ impl Server {
     ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
         let password_1 = the_rest.join(" ");
         let tx = option_tx?;
-        if let Some(account) = self.accounts.0.get_mut(username) {
-            // The username is in the database and already logged in.
+
+        if let Some(message) = self.banned(index_supplied, Some(username)) {
+            self.audit(index_supplied, username, "login banned");
+            tx.send(message).ok()?;
+            return None;
+        }
+
+        if let Some(account) = self.accounts.0.get_mut(username) {
+            // The username is in the database and already logged in.
             if let Some(index_database) = account.logged_in {
                 error!("{index_supplied} {username} login failed, {index_database} is logged in");
 
@@ -2410,12 +4728,16 @@ impl Server {
                     Argon2::default().verify_password(password_1.as_bytes(), &hash_2)
                 {
                     error!("{index_supplied} {username} provided the wrong password: {error}");
+                    self.audit(index_supplied, username, "login wrong_password");
                     return Some((tx, Err(InvalidMove::Other), (*command).to_string()));
                 }
 
                 self.clients.insert(index_supplied, tx);
                 account.logged_in = Some(index_supplied);
-                account.last_logged_in = DateTimeUtc(Timestamp::now());
+                account.last_logged_in = DateTimeUtc(clock::now());
+                self.reconnecting.remove(username);
+                self.audit(index_supplied, username, command);
+                self.opponents_connection(username);
 
                 Some((
                     self.clients.get(&index_supplied)?.clone(),
@@ -2425,14 +4747,134 @@ impl Server {
             }
         } else {
             error!("{index_supplied} {username} is not in the database");
+            self.audit(index_supplied, username, "login unknown_username");
             Some((tx, Err(InvalidMove::Other), (*command).to_string()))
         }
     }
 
-    fn load_data_files(
-        &mut self,
+    /// Saves everything for the server taking over, the games stop until it
+    /// loads them.
+    fn handoff(&self) -> anyhow::Result<()> {
+        self.save_server();
+        fs::write(
+            data_file(ACTIVE_GAMES_FILE),
+            postcard::to_allocvec(&self.active_games())?,
+        )?;
+
+        let sessions = self
+            .accounts
+            .0
+            .iter()
+            .filter_map(|(username, account)| {
+                account.logged_in.map(|index| Session {
+                    username: username.clone(),
+                    address: self.addresses.get(&index).copied(),
+                })
+            })
+            .collect();
+
+        let snapshot = Snapshot {
+            taken: clock::now(),
+            sessions,
+        };
+
+        fs::write(data_file(HANDOFF_FILE), ron::ser::to_string(&snapshot)?)?;
+        Ok(())
+    }
+
+    /// Picks up where the old server left off. The clocks didn't run while
+    /// the servers handed off, and they wait a while for the players who were
+    /// connected.
+    fn resume(&mut self, snapshot: &Snapshot) {
+        let now = clock::now();
+
+        for game in self.games.0.values_mut() {
+            if let TimeUnix::Time(game_time) = &mut game.game.time {
+                *game_time = now.as_millisecond();
+            }
+        }
+
+        for session in &snapshot.sessions {
+            self.reconnecting
+                .insert(session.username.clone(), now.as_millisecond() + GRACE);
+
+            if let Err(error) = self
+                .audit_log
+                .record(&session.username, session.address, "handoff")
+            {
+                error!("audit_log: {error}");
+            }
+        }
+
+        info!(
+            "took over in {:#}, {} players to reconnect",
+            now.duration_since(snapshot.taken),
+            snapshot.sessions.len()
+        );
+    }
+
+    /// Moves what older data files kept in `admins`, `admins_tournament`,
+    /// and `blocked_ips` into the roles and the bans, lets the server start
+    /// tournaments, and counts the rated games of the accounts once from the
+    /// archived games.
+    fn migrate(&mut self) -> anyhow::Result<()> {
+        for username in take(&mut self.admins) {
+            self.roles.0.insert(username, AdminRole::Owner);
+        }
+        for username in take(&mut self.admins_tournament) {
+            self.roles
+                .0
+                .entry(username)
+                .or_insert(AdminRole::TournamentDirector);
+        }
+        self.roles
+            .0
+            .entry("server".to_string())
+            .or_insert(AdminRole::TournamentDirector);
+
+        for address in take(&mut self.blocked_ips) {
+            let ban = Ban::new(
+                BanTarget::Network(address.into()),
+                "forever",
+                "blocked",
+                "server",
+            )?;
+            self.bans.add(ban);
+        }
+
+        if !self.rated_games_counted {
+            for (username, rated_games) in self.archive.rated_games()? {
+                if let Some(account) = self.accounts.0.get_mut(&username) {
+                    account.rated_games = account.rated_games.max(rated_games);
+                }
+            }
+
+            self.rated_games_counted = true;
+        }
+
+        Ok(())
+    }
+
+    fn save_on_ctrlc(
         tx: Sender<(String, Option<Sender<String>>)>,
         systemd: bool,
+    ) -> anyhow::Result<()> {
+        ctrlc::set_handler(move || {
+            if !systemd {
+                println!();
+            }
+            handle_error(tx.send(("0 server save".to_string(), None)));
+            handle_error(tx.send(("0 server exit".to_string(), None)));
+        })?;
+
+        Ok(())
+    }
+
+    /// Loads the data files, `data_file` maps a file name to its path.
+    fn load_data_files(
+        &mut self,
+        tx: &Sender<(String, Option<Sender<String>>)>,
+        data_file: impl Fn(&str) -> PathBuf,
     ) -> anyhow::Result<()> {
         let users_file = data_file(USERS_FILE);
         match &fs::read_to_string(&users_file) {
@@ -2445,8 +4887,6 @@ impl Server {
                     if let Some(tournament) = &mut self.tournament.tournament {
                         tournament.remove_duplicate_ids();
                     }
-
-                    self.admins_tournament.insert("server".to_string());
                 }
                 Err(err) => {
                     return Err(anyhow::Error::msg(format!(
@@ -2461,30 +4901,14 @@ impl Server {
             },
         }
 
-        let archived_games_file = data_file(ARCHIVED_GAMES_FILE);
-        match fs::read_to_string(&archived_games_file) {
-            Ok(archived_games_string) => {
-                let mut archived_games = Vec::new();
+        self.audit_log = AuditLog::load(&data_file(AUDIT_LOG_FILE))?;
 
-                for line in archived_games_string.lines() {
-                    let archived_game: ArchivedGame = match ron::from_str(line) {
-                        Ok(archived_game) => archived_game,
-                        Err(err) => {
-                            return Err(anyhow::Error::msg(format!(
-                                "RON: {}: {err}",
-                                archived_games_file.display(),
-                            )));
-                        }
-                    };
-                    archived_games.push(archived_game);
-                }
+        self.bug_reports = BugReports::load(&data_file(BUG_REPORTS_FILE))?;
+        self.bug_reports.extend(take(&mut self.bug_reports_old.0))?;
 
-                self.archived_games = archived_games;
-            }
-            Err(err) => {
-                error!("archived games file not found: {err}");
-            }
-        }
+        self.archive = Archive::load(&data_file(ARCHIVED_GAMES_FILE))?;
+        self.avatars = Avatars::new(data_file(AVATARS_FOLDER));
+        self.migrate()?;
 
         let active_games_file = data_file(ACTIVE_GAMES_FILE);
         if fs::exists(&active_games_file)? {
@@ -2492,7 +4916,7 @@ impl Server {
             let mut data = Vec::new();
             file.read_to_end(&mut data)?;
 
-            let games: Vec<ServerGameSerialized> = postcard::from_bytes(data.as_slice())?;
+            let games = ServerGameSerialized::from_postcard(data.as_slice())?;
             for mut game in games {
                 let id = game.id;
                 let size = game.game.board.size();
@@ -2508,6 +4932,11 @@ impl Server {
                     }
                 }
 
+                // Only the first and the latest boards are saved.
+                if let Some((_, previous_boards)) = game.game.replay(game.game.plays.len()) {
+                    game.game.previous_boards = previous_boards;
+                }
+
                 let server_game_light = ServerGameLight::from(&game);
                 let server_game = ServerGame::from(game);
 
@@ -2516,15 +4945,59 @@ impl Server {
             }
         }
 
-        ctrlc::set_handler(move || {
-            if !systemd {
-                println!();
+        Ok(())
+    }
+
+    /// Tells the opponents of `username` in their games that they left or
+    /// came back, and tells `username` when they come back about the
+    /// opponents who are still gone. The seconds are until the game can be
+    /// claimed.
+    ///
+    /// ```sh
+    /// -> = opponent_disconnected 6 60
+    /// -> = opponent_reconnected 6
+    /// ```
+    fn opponents_connection(&self, username: &str) {
+        let claim_after = self.config.claim_after_seconds * 1_000;
+        let connected = self.disconnected_for(username).is_none();
+        let send = |player: &str, message: String| {
+            if let Some(account) = self.accounts.0.get(player)
+                && let Some(index) = account.logged_in
+                && let Some(tx) = self.clients.get(&index)
+            {
+                let _ok = tx.send(message);
             }
-            handle_error(tx.send(("0 server save".to_string(), None)));
-            handle_error(tx.send(("0 server exit".to_string(), None)));
-        })?;
+        };
 
-        Ok(())
+        for game in self.games.0.values() {
+            let opponent = if game.attacker == username {
+                &game.defender
+            } else if game.defender == username {
+                &game.attacker
+            } else {
+                continue;
+            };
+
+            if !connected {
+                send(
+                    opponent,
+                    format!(
+                        "= opponent_disconnected {} {}",
+                        game.id, self.config.claim_after_seconds
+                    ),
+                );
+                continue;
+            }
+
+            send(opponent, format!("= opponent_reconnected {}", game.id));
+            if let Some(disconnected) = self.disconnected_for(opponent) {
+                let wait = ((claim_after - disconnected).max(0) + 999) / 1_000;
+                send(
+                    username,
+                    format!("= opponent_disconnected {} {wait}", game.id),
+                );
+            }
+        }
     }
 
     fn logout(
@@ -2552,7 +5025,7 @@ impl Server {
                 && index_database == index_supplied
             {
                 account.logged_in = None;
-                account.last_logged_in = DateTimeUtc(Timestamp::now());
+                account.last_logged_in = DateTimeUtc(clock::now());
 
                 self.clients
                     .get(&index_supplied)?
@@ -2560,6 +5033,10 @@ impl Server {
                     .ok()?;
 
                 self.clients.remove(&index_database);
+                self.avatars_asked.remove(&index_database);
+                self.lags.remove(&index_database);
+                self.pings.remove(&index_database);
+                self.opponents_connection(username);
 
                 return None;
             }
@@ -2607,7 +5084,7 @@ impl Server {
             })
             .ok()?;
 
-        let game = ServerGameLight::new(
+        let mut game = ServerGameLight::new(
             self.game_id,
             (*username).to_string(),
             new_game.rated.into(),
@@ -2615,6 +5092,9 @@ impl Server {
             board_size,
             new_game.role,
         );
+        game.established_only = new_game.established_only;
+        game.zen = new_game.rated && new_game.zen;
+        game.rule_set = new_game.rule_set;
 
         let command = format!("{command} {}", self.game_id);
 
@@ -2667,10 +5147,11 @@ impl Server {
     }
 
     #[must_use]
-    fn new_tournament_game(
+    fn new_game_accepted(
         &mut self,
         attacker: &str,
         defender: &str,
+        rated: Rated,
         timed: TimeSettings,
         board_size: BoardSize,
     ) -> Id {
@@ -2683,17 +5164,20 @@ impl Server {
             attacker: Some(attacker.to_string()),
             defender: Some(defender.to_string()),
             challenger: Challenger(None),
-            rated: Rated::Yes,
+            rated,
             timed,
             spectators: HashMap::new(),
             challenge_accepted: true,
             game_over: false,
             board_size,
             turn: Role::Attacker,
+            established_only: false,
+            zen: false,
+            rule_set: RuleSet::default(),
         };
 
         info!(
-            "0 server new_tournament_game {id} {} {:?} {}",
+            "0 server new_game_accepted {id} {} {:?} {}",
             game_light.rated, game_light.timed, game_light.board_size
         );
 
@@ -2701,154 +5185,1537 @@ impl Server {
 
         self.games_light.0.insert(id, game_light);
         self.games.0.insert(id, game);
+        self.flag_timer_set(id);
 
         id
     }
 
-    fn resume_game(
+    /// Reports a player to the moderators, who list the reports and clear the
+    /// ones about a player once they have dealt with them. A reporter may send
+    /// a few reports an hour.
+    ///
+    /// ```sh
+    /// <- report alice spamming the chat
+    /// -> = report
+    /// <- reports
+    /// -> = reports [(username:"alice",reporter:"bob",reason:"spamming the chat",created:"2026-10-14T12:00:00Z",resolved:false)]
+    /// <- reports_clear alice
+    /// -> = reports_clear
+    /// ```
+    fn report(
         &mut self,
         username: &str,
         index_supplied: usize,
         command: &str,
         the_rest: &[&str],
     ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
-        let Some(id) = the_rest.first() else {
-            return Some((
-                self.clients.get(&index_supplied)?.clone(),
-                Err(InvalidMove::Other),
-                (*command).to_string(),
-            ));
-        };
-        let Ok(game_id) = id.parse::<Id>() else {
-            return Some((
-                self.clients.get(&index_supplied)?.clone(),
-                Err(InvalidMove::Other),
-                (*command).to_string(),
-            ));
+        let tx = self.clients.get(&index_supplied)?.clone();
+
+        let result = match (command, the_rest) {
+            ("report", [reported, reason @ ..]) => {
+                if *reported == username {
+                    Err("you can't report yourself")
+                } else if !self.accounts.0.contains_key(*reported) {
+                    Err("there is no such player")
+                } else if !self.reports.may_add(username, clock::now()) {
+                    Err("wait a while before sending another report")
+                } else {
+                    info!("{index_supplied} {username} report {reported}");
+                    self.reports.add(reported, username, &reason.join(" "));
+                    Ok(())
+                }
+            }
+            ("reports", []) => {
+                return Some((
+                    tx,
+                    Ok(()),
+                    format!("{command} {}", ron::ser::to_string(&self.reports.0).ok()?),
+                ));
+            }
+            ("reports_clear", [reported]) => {
+                if self.reports.clear(reported) {
+                    Ok(())
+                } else {
+                    Err("there are no reports about them")
+                }
+            }
+            _ => Err("wrong arguments"),
         };
 
-        let server_game = self.games.0.get_mut(&game_id)?;
-        let game = &server_game.game;
-        let messages = &server_game.messages;
+        match result {
+            Ok(()) => Some((tx, Ok(()), command.to_string())),
+            Err(error) => Some((tx, Err(InvalidMove::Other), format!("{command} {error}"))),
+        }
+    }
 
-        info!("{index_supplied} {username} {command} {id}");
+    /// Sends in a bug report as a RON string, so the lines of the log in it
+    /// stay apart. The owners list the bug reports and clear them once they
+    /// have dealt with them. A reporter waits a while between bug reports.
+    ///
+    /// ```sh
+    /// <- bug_report "hnefatafl-client 6.2.2\nOS: linux x86_64"
+    /// -> = bug_report
+    /// <- bug_reports
+    /// -> = bug_reports [(reporter:"alice",report:"hnefatafl-client 6.2.2\nOS: linux x86_64",created:"2026-10-14T12:00:00Z")]
+    /// <- bug_reports_clear
+    /// -> = bug_reports_clear
+    /// ```
+    fn bug_report(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let tx = self.clients.get(&index_supplied)?.clone();
 
-        let game_light = self.games_light.0.get_mut(&game_id)?;
+        let result = match (command, the_rest) {
+            ("bug_report", report) => match ron::from_str::<String>(&report.join(" ")) {
+                Ok(report) if report.trim().is_empty() => Err("the bug report is empty"),
+                Ok(report) if report.len() > bug_reports::LENGTH => {
+                    Err("the bug report is too long")
+                }
+                Ok(_) if !self.bug_reports.may_add(username, clock::now()) => {
+                    Err("wait a while before sending another bug report")
+                }
+                Ok(report) => {
+                    info!("{index_supplied} {username} bug_report");
+                    if let Err(error) = self.bug_reports.add(username, report) {
+                        error!("bug_report: {error}");
+                    }
+                    Ok(())
+                }
+                Err(_) => Err("the bug report isn't a RON string"),
+            },
+            ("bug_reports", []) => {
+                return Some((
+                    tx,
+                    Ok(()),
+                    format!(
+                        "{command} {}",
+                        ron::ser::to_string(&self.bug_reports.reports).ok()?
+                    ),
+                ));
+            }
+            ("bug_reports_clear", []) => match self.bug_reports.clear() {
+                Ok(true) => Ok(()),
+                Ok(false) => Err("there are no bug reports"),
+                Err(error) => {
+                    error!("bug_reports_clear: {error}");
+                    Err("the bug reports couldn't be cleared")
+                }
+            },
+            _ => Err("wrong arguments"),
+        };
 
-        let mut channel_id = 0;
-        if let Some(account) = self.accounts.0.get(username)
-            && let Some(id) = account.logged_in
-        {
-            channel_id = id;
+        match result {
+            Ok(()) => Some((tx, Ok(()), command.to_string())),
+            Err(error) => Some((tx, Err(InvalidMove::Other), format!("{command} {error}"))),
         }
-        game_light
-            .spectators
-            .insert(username.to_string(), channel_id);
-
-        let mut request_draw = Role::Roleless;
+    }
 
-        if Some(username) == game_light.attacker.as_deref() {
-            server_game.attacker_tx = Messenger::new(self.clients.get(&index_supplied)?.clone());
+    /// Gives a user a role, takes it away, or lists everyone's roles. There is
+    /// always at least one owner left.
+    ///
+    /// ```sh
+    /// <- role_set alice moderator
+    /// -> = role_set
+    /// <- role_remove alice
+    /// -> = role_remove
+    /// <- roles
+    /// -> = roles {"david":Owner,"server":TournamentDirector}
+    /// ```
+    fn roles(
+        &mut self,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let tx = self.clients.get(&index_supplied)?.clone();
 
-            if server_game.draw_requested == Role::Defender {
-                request_draw = Role::Attacker;
+        let result = match (command, the_rest) {
+            ("role_remove", [username]) => self.roles.remove(username),
+            ("role_set", [username, role]) => {
+                if self.accounts.0.contains_key(*username) {
+                    AdminRole::from_str(role).and_then(|role| self.roles.set(username, role))
+                } else {
+                    Err(anyhow::Error::msg(format!(
+                        "{username} doesn't have an account"
+                    )))
+                }
             }
-        } else if Some(username) == game_light.defender.as_deref() {
-            server_game.defender_tx = Messenger::new(self.clients.get(&index_supplied)?.clone());
-
-            if server_game.draw_requested == Role::Attacker {
-                request_draw = Role::Defender;
+            ("roles", []) => {
+                return Some((
+                    tx,
+                    Ok(()),
+                    format!("{command} {}", ron::ser::to_string(&self.roles.0).ok()?),
+                ));
             }
-        }
-
-        let client = self.clients.get(&index_supplied)?;
+            _ => Err(anyhow::Error::msg("wrong arguments")),
+        };
 
-        if command == "resume_game_json" || command == "resume_game_ron" {
-            let opentafl_game = OpenTaflGame::from(&*server_game);
+        if let Err(error) = result {
+            error!("{index_supplied} {command}: {error}");
+            return Some((tx, Err(InvalidMove::Other), command.to_string()));
+        }
 
-            let Ok(resume_game_pretty) = serde_json::to_string_pretty(&opentafl_game) else {
-                unreachable!();
-            };
+        Some((tx, Ok(()), command.to_string()))
+    }
 
-            debug!("\n{resume_game_pretty}");
+    /// Makes a club, joins one you were invited to or leaves one, or, for the
+    /// owner, changes what it says about itself, invites a player, takes a
+    /// member out, or deletes it. Everyone gets the clubs again after a change.
+    ///
+    /// ```sh
+    /// <- club_new vikings
+    /// <- club_description vikings We play every Sunday.
+    /// <- club_invite vikings erik
+    /// <- club_join vikings
+    /// <- club_leave vikings
+    /// <- club_remove vikings erik
+    /// <- club_delete vikings
+    /// -> = clubs (...)
+    /// ```
+    fn club(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let channel = self.clients.get(&index_supplied)?.clone();
 
-            if command == "resume_game_json" {
-                let Ok(opentafl_game) = serde_json::to_string(&opentafl_game) else {
-                    unreachable!();
-                };
+        let Some((name, the_rest)) = the_rest.split_first() else {
+            return Some((channel, Err(InvalidMove::Other), command.to_string()));
+        };
+        let name = name.to_lowercase();
 
-                client
-                    .send(format!("= resume_game_json {opentafl_game}"))
-                    .ok()?;
-            } else {
-                let Ok(resume_game) = ron::to_string(&opentafl_game) else {
-                    unreachable!();
-                };
+        if command == "club_new" {
+            if club::invalid_name(&name) {
+                let reply = format!("{command} {name} isn't a valid name");
+                return Some((channel, Err(InvalidMove::Other), reply));
+            }
 
-                client
-                    .send(format!("= resume_game_ron {resume_game}"))
-                    .ok()?;
+            if self.clubs.0.contains_key(&name) {
+                let reply = format!("{command} {name} is taken");
+                return Some((channel, Err(InvalidMove::Other), reply));
             }
+
+            self.clubs
+                .0
+                .insert(name.clone(), Club::new(&name, username));
         } else {
-            let Ok(game_se) = ron::ser::to_string(&game) else {
-                unreachable!();
-            };
+            let admin = self.roles.allows(username, Permission::Accounts);
 
-            let Ok(messages_se) = ron::ser::to_string(&messages) else {
-                unreachable!();
+            let Some(club) = self.clubs.0.get_mut(&name) else {
+                let reply = format!("{command} there is no club {name}");
+                return Some((channel, Err(InvalidMove::Other), reply));
             };
 
-            client
-                .send(format!(
-                    "= resume_game {} {} {} {:?} {} {game_se} {messages_se}",
-                    game_light.attacker.clone()?,
-                    game_light.defender.clone()?,
-                    game_light.rated,
-                    game_light.timed,
-                    game_light.board_size,
-                ))
-                .ok()?;
-        }
+            let owner = club.owner == username;
 
-        if request_draw != Role::Roleless {
-            client
-                .send(format!("request_draw {} {request_draw}", game_light.id))
-                .ok()?;
+            match (command, the_rest) {
+                ("club_delete", []) if owner || admin => {
+                    self.clubs.0.remove(&name);
+                    self.texts_club.remove(&name);
+                }
+                ("club_description", description) if owner => {
+                    club.description = description.join(" ");
+                }
+                ("club_invite", [invited])
+                    if owner
+                        && self.accounts.0.contains_key(*invited)
+                        && !club.members.contains(*invited) =>
+                {
+                    club.invited.insert((*invited).to_string());
+                }
+                // Only who the owner invited may join, so a club tournament
+                // is only for the players the owner let in.
+                ("club_join", []) if club.invited.contains(username) => {
+                    club.invited.remove(username);
+                    club.members.insert(username.to_string());
+                }
+                ("club_leave", []) if !owner => {
+                    club.members.remove(username);
+                }
+                ("club_remove", [member]) if (owner || admin) && *member != club.owner => {
+                    club.members.remove(*member);
+                }
+                _ => return Some((channel, Err(InvalidMove::Other), command.to_string())),
+            }
         }
 
+        info!("{index_supplied} {username} {command} {name}");
+        self.save_server();
+        self.clubs_status_all();
+
         None
     }
 
-    fn request_draw(
+    /// Sends a message to the members of a club that are logged in.
+    ///
+    /// ```sh
+    /// <- club_text vikings Who is up for a game?
+    /// -> = club_text vikings (username:"david",timestamp:"...",content:"Who is up for a game?")
+    /// ```
+    fn club_text(
         &mut self,
         username: &str,
         index_supplied: usize,
         command: &str,
         the_rest: &[&str],
     ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
-        let Some(id) = the_rest.first() else {
+        let Some((name, content)) = the_rest.split_first() else {
             return Some((
                 self.clients.get(&index_supplied)?.clone(),
                 Err(InvalidMove::Other),
-                (*command).to_string(),
+                command.to_string(),
             ));
         };
-        let Ok(id) = id.parse::<Id>() else {
+
+        let Some(club) = self
+            .clubs
+            .0
+            .get(*name)
+            .filter(|club| club.members.contains(username))
+        else {
             return Some((
                 self.clients.get(&index_supplied)?.clone(),
                 Err(InvalidMove::Other),
-                (*command).to_string(),
+                format!("{command} you aren't a member of {name}"),
             ));
         };
 
-        let Some(role) = the_rest.get(1) else {
-            return Some((
+        let content = content.join(" ");
+        if content.is_empty()
+            || content.len() > self.config.message_length
+            || self.kid_safe_mutes(username, &content)
+        {
+            return None;
+        }
+
+        let message = Message {
+            username: username.to_string(),
+            timestamp: clock::now(),
+            content,
+        };
+
+        info!("{index_supplied} club_text {name} {message:?}");
+
+        for member in &club.members {
+            if let Some(account) = self.accounts.0.get(member)
+                && let Some(index) = account.logged_in
+                && let Some(tx) = self.clients.get(&index)
+            {
+                let message = Message {
+                    content: self.censor_for(self.chat_shown(member), &message.content),
+                    ..message.clone()
+                };
+
+                if let Ok(message_se) = ron::ser::to_string(&message) {
+                    let _ok = tx.send(format!("= club_text {name} {message_se}"));
+                }
+            }
+        }
+
+        let texts = self.texts_club.entry((*name).to_string()).or_default();
+        if texts.len() >= self.config.keep_texts {
+            texts.pop_front();
+        }
+        texts.push_back(message);
+
+        None
+    }
+
+    /// Keeps a user from sending you direct messages and you from sending
+    /// them any, or lists the users you blocked.
+    ///
+    /// ```sh
+    /// <- block erik
+    /// <- unblock erik
+    /// <- blocks
+    /// -> = blocks ["erik"]
+    /// ```
+    fn block(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let channel = self.clients.get(&index_supplied)?.clone();
+        let account = self.accounts.0.get_mut(username)?;
+
+        match (command, the_rest) {
+            ("block", [blocked]) if *blocked != username => {
+                account.blocked.insert((*blocked).to_string());
+            }
+            ("unblock", [blocked]) => {
+                account.blocked.remove(*blocked);
+            }
+            ("blocks", []) => {}
+            _ => return Some((channel, Err(InvalidMove::Other), command.to_string())),
+        }
+
+        let blocks = ron::ser::to_string(&account.blocked).ok()?;
+        if command != "blocks" {
+            info!(
+                "{index_supplied} {username} {command} {}",
+                the_rest.join(" ")
+            );
+            self.save_server();
+        }
+
+        Some((channel, Ok(()), format!("blocks {blocks}")))
+    }
+
+    /// Sends a message to one user, or keeps it until they log in. Both of
+    /// them get it as from the other one.
+    ///
+    /// ```sh
+    /// <- dm erik Good game!
+    /// -> = dm erik (username:"david",timestamp:"...",content:"Good game!")
+    /// ```
+    fn dm(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let channel = self.clients.get(&index_supplied)?.clone();
+
+        let Some((to, content)) = the_rest.split_first() else {
+            return Some((channel, Err(InvalidMove::Other), command.to_string()));
+        };
+
+        let content = content.join(" ");
+        if content.is_empty()
+            || content.len() > self.config.message_length
+            || self.kid_safe_mutes(username, &content)
+        {
+            return None;
+        }
+
+        let Some(account) = self.accounts.0.get(*to) else {
+            let reply = format!("{command} {to} there is no such user");
+            return Some((channel, Err(InvalidMove::Other), reply));
+        };
+
+        let blocked = account.blocked.contains(username)
+            || !account.kid_safe_allows(username)
+            || self.accounts.0.get(username).is_some_and(|account| {
+                account.blocked.contains(*to) || !account.kid_safe_allows(to)
+            });
+
+        if blocked || *to == username {
+            let reply = format!("{command} {to} you can't send them messages");
+            return Some((channel, Err(InvalidMove::Other), reply));
+        }
+
+        let message = Message {
+            username: username.to_string(),
+            timestamp: clock::now(),
+            content,
+        };
+
+        info!("{index_supplied} {username} dm {to}");
+
+        if let Some(index) = account.logged_in
+            && let Some(tx) = self.clients.get(&index)
+        {
+            let masked = Message {
+                content: self.censor_for(self.chat_shown(to), &message.content),
+                ..message.clone()
+            };
+
+            if let Ok(masked) = ron::ser::to_string(&masked) {
+                let _ok = tx.send(format!("= dm {username} {masked}"));
+            }
+        } else {
+            let pending = self.dms_pending.entry((*to).to_string()).or_default();
+            if pending.len() >= self.config.keep_texts {
+                pending.pop_front();
+            }
+            // The hourly save writes them with everything else.
+            pending.push_back(message.clone());
+        }
+
+        let message = Message {
+            content: self.censor_for(self.chat_shown(username), &message.content),
+            ..message
+        };
+        let message = ron::ser::to_string(&message).ok()?;
+
+        Some((channel, Ok(()), format!("{command} {to} {message}")))
+    }
+
+    /// The banner about the next tournament, `= announcement` by itself when
+    /// there is none.
+    fn announcement(&self) -> String {
+        self.tournament
+            .announcement()
+            .and_then(|announcement| serde_json::to_string(&announcement).ok())
+            .map_or_else(
+                || "= announcement".to_string(),
+                |announcement| format!("= announcement {announcement}"),
+            )
+    }
+
+    /// Sets or clears the message of the day, which goes out to everyone
+    /// right away, or lists the past messages.
+    ///
+    /// ```sh
+    /// <- motd_set Welcome!
+    /// -> = motd Welcome!
+    /// <- motd_clear
+    /// -> = motd
+    /// <- motd_history
+    /// -> = motd_history [(text:"Welcome!",username:"david",set:"2026-10-14T12:00:00Z",cleared:Some("2026-10-14T13:00:00Z"))]
+    /// ```
+    fn motd(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        match command {
+            "motd_clear" => self.motd.clear(),
+            "motd_set" if !the_rest.is_empty() => self.motd.set(username, &the_rest.join(" ")),
+            "motd_history" => {
+                return Some((
+                    self.clients.get(&index_supplied)?.clone(),
+                    Ok(()),
+                    format!(
+                        "{command} {}",
+                        ron::ser::to_string(&self.motd.history).ok()?
+                    ),
+                ));
+            }
+            _ => {
+                return Some((
+                    self.clients.get(&index_supplied)?.clone(),
+                    Err(InvalidMove::Other),
+                    command.to_string(),
+                ));
+            }
+        }
+
+        let message = self.motd.message();
+        for tx in self.clients.values() {
+            let _ok = tx.send(message.clone());
+        }
+
+        None
+    }
+
+    /// Sends a player's profile, or sets a bit of your own. A bio is masked
+    /// like the chat, and kid-safe accounts don't get to write one.
+    ///
+    /// ```sh
+    /// <- profile_set bio I like to play the defenders.
+    /// -> = profile_set
+    /// <- profile_set country IS
+    /// -> = profile_set
+    /// # attacker, defender, or none
+    /// <- profile_set role defender
+    /// -> = profile_set
+    /// <- profile david
+    /// -> = profile (username:"david",profile:(bio:"I like to play the defenders.",country:Some("IS"),role:Some(Defender)),...)
+    /// ```
+    fn profile(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let tx = self.clients.get(&index_supplied)?.clone();
+
+        if command == "profile" {
+            let player = the_rest.first().copied().unwrap_or(username);
+            let Some(account) = self.accounts.0.get(player) else {
+                return Some((
+                    tx,
+                    Err(InvalidMove::Other),
+                    format!("{command} no such player"),
+                ));
+            };
+
+            let mut profile = account.profile.clone();
+            profile.bio = self.censor_for(self.chat_shown(username), &profile.bio);
+
+            let mut player_profile = PlayerProfile {
+                username: player.to_string(),
+                profile,
+                rating: account.rating.clone(),
+                wins: account.wins,
+                losses: account.losses,
+                draws: account.draws,
+                ratings: Vec::new(),
+                games: Vec::new(),
+            };
+
+            player_profile.games(&self.player_games(index_supplied, command, player));
+
+            return Some((
+                tx,
+                Ok(()),
+                format!("{command} {}", ron::ser::to_string(&player_profile).ok()?),
+            ));
+        }
+
+        let account = self.accounts.0.get_mut(username)?;
+        let result = match the_rest {
+            ["bio", ..] if account.kid_safe => {
+                Err(anyhow::Error::msg("kid-safe accounts don't have a bio"))
+            }
+            ["bio", bio @ ..] => account.profile.set_bio(&bio.join(" ")),
+            ["country", country] => account.profile.set_country(country),
+            ["role", "none"] => {
+                account.profile.role = None;
+                Ok(())
+            }
+            ["role", role] => Role::from_str(role).map(|role| account.profile.role = Some(role)),
+            _ => Err(anyhow::Error::msg("wrong arguments")),
+        };
+
+        if let Err(error) = result {
+            error!("{index_supplied} {command}: {error}");
+            return Some((tx, Err(InvalidMove::Other), format!("{command} {error}")));
+        }
+
+        info!(
+            "{index_supplied} {username} {command} {}",
+            the_rest.join(" ")
+        );
+        Some((tx, Ok(()), command.to_string()))
+    }
+
+    /// The daily puzzle, and trying to solve it. You get one try a day, and
+    /// solving it on days in a row makes a streak. The solution is only sent
+    /// once you had your try.
+    ///
+    /// ```sh
+    /// <- daily_puzzle
+    /// -> = daily_puzzle (position:(...),solution:None,solvers:[],streak:0,tried:false,streaks:[])
+    /// <- daily_puzzle_solve a3-a1
+    /// -> = daily_puzzle_solve true 1 a3-a1
+    /// ```
+    fn daily_puzzle(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let tx = self.clients.get(&index_supplied)?.clone();
+        let now = clock::now();
+        let today = puzzle::day(now);
+        self.daily_puzzle_pick(now);
+
+        let Some(daily_puzzle) = &mut self.daily_puzzle else {
+            return Some((
+                tx,
+                Err(InvalidMove::Other),
+                format!("{command} there are no puzzles yet"),
+            ));
+        };
+
+        if command == "daily_puzzle" {
+            let daily = self.daily(username)?;
+            return Some((
+                tx,
+                Ok(()),
+                format!("{command} {}", ron::ser::to_string(&daily).ok()?),
+            ));
+        }
+
+        let account = self.accounts.0.get_mut(username)?;
+        if account.puzzle_streak.tried(today) {
+            return Some((
+                tx,
+                Err(InvalidMove::Other),
+                format!("{command} you already tried today's puzzle"),
+            ));
+        }
+
+        let puzzle = &daily_puzzle.puzzle;
+        let solved = the_rest.first().is_some_and(|play| {
+            Plae::from_str_(play, &puzzle.role()).is_ok_and(|play| play == puzzle.solution)
+        });
+
+        if solved {
+            account.puzzle_streak.solve(today);
+            daily_puzzle.solvers.push(username.to_string());
+        } else {
+            account.puzzle_streak.miss(today);
+        }
+
+        let streak = account.puzzle_streak.current(today);
+        let solution = match &puzzle.solution {
+            Plae::Play(play) => format!("{}-{}", play.from, play.to),
+            play => play.to_string(),
+        };
+        self.save_server();
+
+        Some((
+            tx,
+            Ok(()),
+            format!("{command} {solved} {streak} {solution}"),
+        ))
+    }
+
+    /// Picks a new daily puzzle when the day changes and sends it to everyone
+    /// logged in.
+    fn daily_puzzle_pick(&mut self, now: Timestamp) {
+        let today = puzzle::day(now);
+        if self
+            .daily_puzzle
+            .as_ref()
+            .is_some_and(|daily_puzzle| daily_puzzle.day == today)
+        {
+            return;
+        }
+
+        let Some(puzzle) = self.puzzles.0.choose(&mut rand::rng()) else {
+            return;
+        };
+
+        self.daily_puzzle = Some(DailyPuzzle {
+            day: today,
+            puzzle: puzzle.clone(),
+            solvers: Vec::new(),
+        });
+        self.save_server();
+
+        for (username, account) in &self.accounts.0 {
+            if let Some(index) = account.logged_in
+                && let Some(tx) = self.clients.get(&index)
+                && let Some(daily) = self.daily(username)
+                && let Ok(daily) = ron::ser::to_string(&daily)
+            {
+                let _ok = tx.send(format!("= daily_puzzle {daily}"));
+            }
+        }
+    }
+
+    /// Today's puzzle as `username` sees it.
+    fn daily(&self, username: &str) -> Option<Daily> {
+        let daily_puzzle = self.daily_puzzle.as_ref()?;
+        let streak = &self.accounts.0.get(username)?.puzzle_streak;
+
+        let mut streaks: Vec<_> = self
+            .accounts
+            .0
+            .iter()
+            .map(|(username, account)| {
+                (
+                    username.clone(),
+                    account.puzzle_streak.current(daily_puzzle.day),
+                )
+            })
+            .filter(|(_, days)| *days > 0)
+            .collect();
+
+        streaks.sort_by(|(name_1, days_1), (name_2, days_2)| {
+            days_2.cmp(days_1).then(name_1.cmp(name_2))
+        });
+        streaks.truncate(puzzle::STREAKS);
+
+        let tried = streak.tried(daily_puzzle.day);
+        Some(Daily {
+            position: daily_puzzle.puzzle.position(),
+            solution: tried.then(|| daily_puzzle.puzzle.solution.clone()),
+            solvers: daily_puzzle.solvers.clone(),
+            streak: streak.current(daily_puzzle.day),
+            tried,
+            streaks,
+        })
+    }
+
+    /// Merges the client's settings with the account's copy, the newer of
+    /// each setting winning, and sends back the merged settings for the
+    /// client to use. Settings the server doesn't know, or that are too long,
+    /// are turned away.
+    ///
+    /// ```sh
+    /// <- settings ({"theme":(changed:"2026-10-14T12:00:00Z",value:"Dark")})
+    /// -> = settings ({"theme":(changed:"2026-10-14T12:00:00Z",value:"Dark")})
+    /// ```
+    fn settings(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let channel = self.clients.get(&index_supplied)?.clone();
+        let mut command = (*command).to_string();
+        let account = self.accounts.0.get_mut(username)?;
+
+        let Ok(settings) = ron::from_str::<Settings>(&the_rest.join(" ")) else {
+            command.push_str(" expected settings");
+            return Some((channel, Err(InvalidMove::Other), command));
+        };
+
+        if !settings.valid() {
+            command.push_str(" unknown setting or value too long");
+            return Some((channel, Err(InvalidMove::Other), command));
+        }
+
+        account.settings.merge(settings);
+        let Ok(settings) = ron::ser::to_string(&account.settings) else {
+            return Some((channel, Err(InvalidMove::Other), command));
+        };
+
+        self.save_server();
+        let _ = write!(command, " {settings}");
+        Some((channel, Ok(()), command))
+    }
+
+    /// Sets the tags of one of your archived games.
+    ///
+    /// ```sh
+    /// <- game_tags 7 (event:"Spring Open",round:"3",labels:["sharp"])
+    /// -> = game_tags 7
+    /// ```
+    fn game_tags(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let channel = self.clients.get(&index_supplied)?.clone();
+        let mut command = (*command).to_string();
+
+        let Some((Ok(id), Ok(tags))) = the_rest
+            .split_first()
+            .map(|(id, tags)| (id.parse::<Id>(), ron::from_str::<Tags>(&tags.join(" "))))
+        else {
+            command.push_str(" expected a game id and tags");
+            return Some((channel, Err(InvalidMove::Other), command));
+        };
+
+        let mut game = match self.archive.get(id) {
+            Ok(Some(game)) => game,
+            Ok(None) => {
+                command.push_str(" there is no such game");
+                return Some((channel, Err(InvalidMove::Other), command));
+            }
+            Err(error) => {
+                error!("game_tags: {error}");
+                return Some((channel, Err(InvalidMove::Other), command));
+            }
+        };
+
+        if username != game.attacker && username != game.defender {
+            command.push_str(" only the players may tag a game");
+            return Some((channel, Err(InvalidMove::Other), command));
+        }
+
+        game.tags = tags;
+        if let Err(error) = self.archive.push(game) {
+            error!("game_tags: {error}");
+            return Some((channel, Err(InvalidMove::Other), command));
+        }
+
+        let _ = write!(command, " {id}");
+        Some((channel, Ok(()), command))
+    }
+
+    /// A spectator guesses the next move. It doesn't change the game, the
+    /// spectators get how often they guessed right when it ends.
+    ///
+    /// ```sh
+    /// <- quiz_guess 7 f4 f2
+    /// -> = quiz_guess 7 f4 f2
+    /// -> = quiz_results 7 [("player-3",(right:5,guessed:8))]
+    /// ```
+    fn quiz_guess(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let channel = self.clients.get(&index_supplied)?.clone();
+        let mut command = (*command).to_string();
+
+        let (Some(id), Some(from), Some(to)) = (
+            the_rest.first().and_then(|id| id.parse::<Id>().ok()),
+            the_rest.get(1),
+            the_rest.get(2),
+        ) else {
+            command.push_str(" expected a game id, from, and to");
+            return Some((channel, Err(InvalidMove::Other), command));
+        };
+
+        let (Some(game), Some(game_light)) = (self.games.0.get(&id), self.games_light.0.get(&id))
+        else {
+            command.push_str(" there is no such game");
+            return Some((channel, Err(InvalidMove::Other), command));
+        };
+
+        if username == game.attacker
+            || username == game.defender
+            || !game_light.spectators.contains_key(username)
+        {
+            command.push_str(" only spectators may guess");
+            return Some((channel, Err(InvalidMove::Other), command));
+        }
+
+        let role = game.game.turn;
+        let Ok(Plae::Play(play)) = Plae::try_from(vec!["play", &role.to_string(), from, to]) else {
+            command.push_str(" invalid move");
+            return Some((channel, Err(InvalidMove::Other), command));
+        };
+
+        if let Err(error) = game.game.clone().play(&Plae::Play(play.clone())) {
+            return Some((channel, Err(error), command));
+        }
+
+        self.quizzes.entry(id).or_default().guess(username, play);
+        let _ = write!(command, " {id} {from} {to}");
+
+        Some((channel, Ok(()), command))
+    }
+
+    /// Tells the spectators how they did in the quiz.
+    fn quiz_results(&mut self, id: Id) {
+        let Some(quiz) = self.quizzes.remove(&id) else {
+            return;
+        };
+
+        let Ok(results) = ron::ser::to_string(&quiz.results()) else {
+            return;
+        };
+
+        if let Some(game_light) = self.games_light.0.get(&id) {
+            let message = format!("= quiz_results {id} {results}");
+
+            for spectator in game_light.spectators() {
+                if let Some(client) = self.clients.get(&spectator) {
+                    let _ok = client.send(message.clone());
+                }
+            }
+        }
+    }
+
+    /// A puzzle from the archived games: find the one move that wins, or the
+    /// one move that doesn't lose.
+    ///
+    /// ```sh
+    /// <- puzzle
+    /// -> = puzzle (game_id:7,board_size:_11,plays:[...],solution:Play((role:Defender,...)),theme:FortBreak)
+    /// ```
+    fn puzzle(
+        &self,
+        index_supplied: usize,
+        command: &str,
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let tx = self.clients.get(&index_supplied)?.clone();
+
+        let Some(puzzle) = self.puzzles.0.choose(&mut rand::rng()) else {
+            return Some((
+                tx,
+                Err(InvalidMove::Other),
+                format!("{command} there are no puzzles yet"),
+            ));
+        };
+
+        Some((
+            tx,
+            Ok(()),
+            format!("{command} {}", ron::ser::to_string(puzzle).ok()?),
+        ))
+    }
+
+    /// Keeps the puzzles found in a game that was just archived.
+    fn puzzles_found(&mut self, the_rest: &[&str]) -> anyhow::Result<()> {
+        let puzzles: Vec<Puzzle> = ron::from_str(&the_rest.join(" "))?;
+        self.puzzles.add(puzzles);
+        self.save_server();
+
+        Ok(())
+    }
+
+    /// The player's last [`RATING_GAMES`] archived games, for their profile
+    /// and scouting report.
+    fn player_games(
+        &self,
+        index_supplied: usize,
+        command: &str,
+        player: &str,
+    ) -> Vec<ArchivedGame> {
+        self.archive
+            .player_games(player, RATING_GAMES)
+            .unwrap_or_else(|error| {
+                error!("{index_supplied} {command}: {error}");
+                Vec::new()
+            })
+    }
+
+    /// How a player has been playing, from their last archived games.
+    ///
+    /// ```sh
+    /// <- scout david
+    /// -> = scout (username:"david",rating:(rating:1500.0,rd:350.0),ratings:[1500.0],prefers:None,...)
+    /// ```
+    fn scout(
+        &self,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let tx = self.clients.get(&index_supplied)?.clone();
+
+        let player = the_rest.first().copied().unwrap_or_default();
+        let Some(account) = self.accounts.0.get(player) else {
+            return Some((
+                tx,
+                Err(InvalidMove::Other),
+                format!("{command} no such player"),
+            ));
+        };
+
+        let scout = Scout::new(
+            player,
+            account.rating.clone(),
+            account.profile.role,
+            &self.player_games(index_supplied, command, player),
+        );
+
+        Some((
+            tx,
+            Ok(()),
+            format!("{command} {}", ron::ser::to_string(&scout).ok()?),
+        ))
+    }
+
+    /// ```sh
+    /// <- profanity_filter [BOOL]
+    /// -> = profanity_filter BOOL
+    /// ```
+    fn profanity_filter(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        if let Some(enabled) = the_rest.first() {
+            let Ok(enabled) = enabled.parse() else {
+                return Some((
+                    self.clients.get(&index_supplied)?.clone(),
+                    Err(InvalidMove::Other),
+                    (*command).to_string(),
+                ));
+            };
+
+            self.profanity_filter.enabled = enabled;
+            info!("{index_supplied} {username} profanity_filter {enabled}");
+        }
+
+        Some((
+            self.clients.get(&index_supplied)?.clone(),
+            Ok(()),
+            format!("{command} {}", self.profanity_filter.enabled),
+        ))
+    }
+
+    /// The words masked for the people using the locale.
+    ///
+    /// ```sh
+    /// <- profanity_words LOCALE [add | remove] [WORD ...]
+    /// -> = profanity_words LOCALE WORD ...
+    /// ```
+    fn profanity_words(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let Some(Ok(locale)) = the_rest.first().map(|locale| Locale::from_str(locale)) else {
+            return Some((
+                self.clients.get(&index_supplied)?.clone(),
+                Err(InvalidMove::Other),
+                (*command).to_string(),
+            ));
+        };
+
+        let words = the_rest.get(2..).unwrap_or_default();
+        match the_rest.get(1) {
+            Some(&"add") => {
+                for word in words {
+                    self.profanity_filter.add(locale, word);
+                }
+            }
+            Some(&"remove") => {
+                for word in words {
+                    self.profanity_filter.remove(locale, word);
+                }
+            }
+            Some(_) => {
+                return Some((
+                    self.clients.get(&index_supplied)?.clone(),
+                    Err(InvalidMove::Other),
+                    (*command).to_string(),
+                ));
+            }
+            None => {}
+        }
+
+        if the_rest.len() > 1 {
+            info!("{index_supplied} {username} {}", the_rest.join(" "));
+        }
+
+        let words: Vec<_> = self
+            .profanity_filter
+            .words
+            .get(&locale)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect();
+
+        Some((
+            self.clients.get(&index_supplied)?.clone(),
+            Ok(()),
+            format!("{command} {} {}", locale.txt(), words.join(" ")),
+        ))
+    }
+
+    fn resume_game(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let Some(id) = the_rest.first() else {
+            return Some((
+                self.clients.get(&index_supplied)?.clone(),
+                Err(InvalidMove::Other),
+                (*command).to_string(),
+            ));
+        };
+        let Ok(game_id) = id.parse::<Id>() else {
+            return Some((
+                self.clients.get(&index_supplied)?.clone(),
+                Err(InvalidMove::Other),
+                (*command).to_string(),
+            ));
+        };
+
+        let messages = self.messages_for(username, &self.games.0.get(&game_id)?.messages);
+        self.texts_seen(username, game_id);
+        let server_game = self.games.0.get_mut(&game_id)?;
+        let game = &server_game.game;
+
+        info!("{index_supplied} {username} {command} {id}");
+
+        let game_light = self.games_light.0.get_mut(&game_id)?;
+
+        let mut channel_id = 0;
+        if let Some(account) = self.accounts.0.get(username)
+            && let Some(id) = account.logged_in
+        {
+            channel_id = id;
+        }
+        game_light
+            .spectators
+            .insert(username.to_string(), channel_id);
+
+        let mut request_draw = Role::Roleless;
+
+        if Some(username) == game_light.attacker.as_deref() {
+            server_game.attacker_tx = Messenger::new(self.clients.get(&index_supplied)?.clone());
+
+            if server_game.draw_requested == Role::Defender {
+                request_draw = Role::Attacker;
+            }
+        } else if Some(username) == game_light.defender.as_deref() {
+            server_game.defender_tx = Messenger::new(self.clients.get(&index_supplied)?.clone());
+
+            if server_game.draw_requested == Role::Attacker {
+                request_draw = Role::Defender;
+            }
+        }
+
+        let client = self.clients.get(&index_supplied)?;
+
+        if command == "resume_game_json" || command == "resume_game_ron" {
+            let mut opentafl_game = OpenTaflGame::from(&*server_game);
+            opentafl_game.messages = messages;
+
+            let Ok(resume_game_pretty) = serde_json::to_string_pretty(&opentafl_game) else {
+                unreachable!();
+            };
+
+            debug!("\n{resume_game_pretty}");
+
+            if command == "resume_game_json" {
+                let Ok(opentafl_game) = serde_json::to_string(&opentafl_game) else {
+                    unreachable!();
+                };
+
+                client
+                    .send(format!("= resume_game_json {opentafl_game}"))
+                    .ok()?;
+            } else {
+                let Ok(resume_game) = ron::to_string(&opentafl_game) else {
+                    unreachable!();
+                };
+
+                client
+                    .send(format!("= resume_game_ron {resume_game}"))
+                    .ok()?;
+            }
+        } else {
+            let Ok(game_se) = ron::ser::to_string(&game) else {
+                unreachable!();
+            };
+
+            let Ok(messages_se) = ron::ser::to_string(&messages) else {
+                unreachable!();
+            };
+
+            client
+                .send(format!(
+                    "= resume_game {} {} {} {:?} {} {game_se} {messages_se}",
+                    game_light.attacker.clone()?,
+                    game_light.defender.clone()?,
+                    game_light.rated,
+                    game_light.timed,
+                    game_light.board_size,
+                ))
+                .ok()?;
+        }
+
+        if request_draw != Role::Roleless {
+            client
+                .send(format!("request_draw {} {request_draw}", game_light.id))
+                .ok()?;
+        }
+
+        None
+    }
+
+    fn request_draw(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let Some(id) = the_rest.first() else {
+            return Some((
+                self.clients.get(&index_supplied)?.clone(),
+                Err(InvalidMove::Other),
+                (*command).to_string(),
+            ));
+        };
+        let Ok(id) = id.parse::<Id>() else {
+            return Some((
+                self.clients.get(&index_supplied)?.clone(),
+                Err(InvalidMove::Other),
+                (*command).to_string(),
+            ));
+        };
+
+        let Some(role) = the_rest.get(1) else {
+            return Some((
+                self.clients.get(&index_supplied)?.clone(),
+                Err(InvalidMove::Other),
+                (*command).to_string(),
+            ));
+        };
+        let Ok(role) = Role::from_str(role) else {
+            return Some((
+                self.clients.get(&index_supplied)?.clone(),
+                Err(InvalidMove::Other),
+                (*command).to_string(),
+            ));
+        };
+
+        info!("{index_supplied} {username} request_draw {id} {role}");
+
+        if let Some(server_game) = self.games.0.get_mut(&id) {
+            server_game.draw_requested = role;
+        }
+
+        let message = format!("request_draw {id} {role}");
+        if let Some(game) = self.games.0.get(&id) {
+            match role {
+                Role::Attacker => {
+                    game.defender_tx.send(message);
+                }
+                Role::Roleless => {}
+                Role::Defender => {
+                    game.attacker_tx.send(message);
+                }
+            }
+        }
+
+        Some((
+            self.clients.get(&index_supplied)?.clone(),
+            Ok(()),
+            (*command).to_string(),
+        ))
+    }
+
+    fn active_games(&self) -> Vec<ServerGameSerialized> {
+        let mut active_games = Vec::new();
+        for game in self.games.0.values() {
+            let mut serialized_game = ServerGameSerialized::from(game);
+
+            if let Some(game_light) = self.games_light.0.get(&game.id) {
+                serialized_game.timed = game_light.timed;
+                serialized_game.zen = game_light.zen;
+            }
+
+            active_games.push(serialized_game);
+        }
+
+        active_games
+    }
+
+    fn backup(tx: Sender<(String, Option<Sender<String>>)>) {
+        thread::spawn(move || {
+            loop {
+                thread::sleep(Duration::from_secs(DAY_IN_SECONDS));
+                handle_error(tx.send(("0 server backup".to_string(), None)));
+            }
+        });
+    }
+
+    /// The data files as they are now.
+    fn backup(&self) -> anyhow::Result<Backup> {
+        Backup::new(
+            self.users_ron()?,
+            postcard::to_allocvec(&self.active_games())?,
+            &self.avatars,
+            data_file,
+        )
+    }
+
+    /// Takes what the server keeps in memory, then reads the data files and
+    /// writes the backup on its own thread.
+    fn write_backup(&self) {
+        if self.skip_the_data_files {
+            return;
+        }
+
+        let memory = || -> anyhow::Result<(String, Vec<u8>)> {
+            Ok((
+                self.users_ron()?,
+                postcard::to_allocvec(&self.active_games())?,
+            ))
+        };
+
+        let (users, active_games) = match memory() {
+            Ok(memory) => memory,
+            Err(error) => {
+                error!("backup: {error}");
+                return;
+            }
+        };
+
+        let avatars = self.avatars.clone();
+        let kept = self.config.backups_kept;
+
+        thread::spawn(move || {
+            let backup = || -> anyhow::Result<PathBuf> {
+                Backup::new(users, active_games, &avatars, data_file)?.write(
+                    &data_file(BACKUPS_FOLDER),
+                    clock::now(),
+                    kept,
+                )
+            };
+
+            match backup() {
+                Ok(path) => info!("wrote the backup {}", path.display()),
+                Err(error) => error!("backup: {error}"),
+            }
+        });
+    }
+
+    fn save(tx: Sender<(String, Option<Sender<String>>)>) {
+        thread::spawn(move || {
+            loop {
+                thread::sleep(Duration::from_secs(HOUR_IN_SECONDS));
+                handle_error(tx.send(("0 server save".to_string(), None)));
+            }
+        });
+    }
+
+    fn save_server(&self) {
+        if !self.skip_the_data_files {
+            match self.users_ron() {
+                Ok(string) => {
+                    if !string.trim().is_empty() {
+                        let users_file = data_file(USERS_FILE);
+
+                        match File::create(&users_file) {
+                            Ok(mut file) => {
+                                if let Err(error) = file.write_all(string.as_bytes()) {
+                                    error!("save file (3): {error}");
+                                }
+                            }
+                            Err(error) => error!("save file (2): {error}"),
+                        }
+                    }
+                }
+                Err(error) => error!("save file (1): {error}"),
+            }
+        }
+    }
+
+    /// Turns off a category of emails for an account, from a link in one of
+    /// the emails.
+    ///
+    /// ```sh
+    /// <- unsubscribe announcements TOKEN
+    /// -> = unsubscribe
+    /// ```
+    fn unsubscribe(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+        option_tx: Option<Sender<String>>,
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let tx = match option_tx {
+            Some(tx) => tx,
+            None => self.clients.get(&index_supplied)?.clone(),
+        };
+
+        let link = match (the_rest.first(), the_rest.get(1)) {
+            (Some(category), Some(token)) => {
+                EmailCategory::from_str(category).ok().map(|category| Link {
+                    username: username.to_string(),
+                    category,
+                    token: (*token).to_string(),
+                })
+            }
+            _ => None,
+        };
+
+        if let Some(link) = link
+            && link.verify(&self.unsubscribe_key)
+            && let Some(account) = self.accounts.0.get_mut(username)
+        {
+            account.email_preferences.set(link.category, false);
+            info!("{index_supplied} {username} unsubscribe {}", link.category);
+            self.save_server();
+
+            Some((tx, Ok(()), (*command).to_string()))
+        } else {
+            error!("{index_supplied} {username} unsubscribe: invalid link");
+            Some((tx, Err(InvalidMove::Other), (*command).to_string()))
+        }
+    }
+
+    fn unsubscribe_link(&self, username: &str, category: EmailCategory) -> Option<String> {
+        if self.unsubscribe_url.is_empty() {
+            return None;
+        }
+
+        Link::new(&self.unsubscribe_key, username, category)
+            .map(|link| link.url(&self.unsubscribe_url))
+    }
+
+    fn users_ron(&self) -> Result<String, ron::Error> {
+        let mut server = self.clone();
+
+        for account in server.accounts.0.values_mut() {
+            account.logged_in = None;
+        }
+
+        ron::ser::to_string_pretty(&server, ron::ser::PrettyConfig::default())
+    }
+
+    /// Sends a message to everyone in the global chat and over every bridge,
+    /// but the one it came from.
+    fn text(&mut self, index_supplied: usize, username: &str, content: &str, bridge: Option<&str>) {
+        let message = Message {
+            username: username.to_string(),
+            timestamp: clock::now(),
+            content: content.to_string(),
+        };
+
+        info!("{index_supplied} text {message:?}");
+
+        if message.content.is_empty()
+            || message.content.len() > self.config.message_length
+            || self.kid_safe_mutes(username, &message.content)
+        {
+            return;
+        }
+
+        if self.texts.len() >= self.config.keep_texts {
+            self.texts.pop_front();
+        }
+
+        let mut usernames = HashMap::new();
+        for (username, account) in &self.accounts.0 {
+            if let Some(index) = account.logged_in {
+                usernames.insert(index, username.as_str());
+            }
+        }
+
+        // Most people see the same message, so it only gets masked once per locale.
+        let mut masked: HashMap<ChatShown, String> = HashMap::new();
+        for (index, tx) in &self.clients {
+            let shown = self.chat_shown(usernames.get(index).copied().unwrap_or_default());
+            let message_se = masked.entry(shown).or_insert_with(|| {
+                let message = Message {
+                    content: self.censor_for(shown, &message.content),
+                    ..message.clone()
+                };
+
+                ron::ser::to_string(&message)
+                    .map(|message| format!("= text {message}"))
+                    .unwrap_or_default()
+            });
+
+            let _ok = tx.send(message_se.clone());
+        }
+
+        let public = Message {
+            content: self.censor_for(self.chat_shown(""), &message.content),
+            ..message.clone()
+        };
+        for to in &self.bridges {
+            if Some(to.name) != bridge {
+                let _ok = to.tx.send(public.clone());
+            }
+        }
+
+        self.texts.push_back(message);
+    }
+
+    fn text_game(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        mut the_rest: Vec<&str>,
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let Some(id) = the_rest.first() else {
+            return Some((
                 self.clients.get(&index_supplied)?.clone(),
                 Err(InvalidMove::Other),
                 (*command).to_string(),
             ));
         };
-        let Ok(role) = Role::from_str(role) else {
+
+        let Ok(id) = id.parse::<Id>() else {
             return Some((
                 self.clients.get(&index_supplied)?.clone(),
                 Err(InvalidMove::Other),
@@ -2856,149 +6723,619 @@ impl Server {
             ));
         };
 
-        info!("{index_supplied} {username} request_draw {id} {role}");
+        let content = the_rest.split_off(1).join(" ");
 
-        if let Some(server_game) = self.games.0.get_mut(&id) {
-            server_game.draw_requested = role;
+        if content.is_empty()
+            || content.len() > self.config.message_length
+            || self.kid_safe_mutes(username, &content)
+            || self.slow_mode_wait(index_supplied, username, Some(id))
+        {
+            return None;
         }
 
-        let message = format!("request_draw {id} {role}");
-        if let Some(game) = self.games.0.get(&id) {
-            match role {
-                Role::Attacker => {
-                    game.defender_tx.send(message);
+        let message = Message {
+            username: username.to_string(),
+            timestamp: clock::now(),
+            content,
+        };
+
+        info!("{index_supplied} {username} text_game {id} {message:?}");
+
+        if let Some(game) = self.games_light.0.get(&id) {
+            for (spectator, index) in &game.spectators {
+                if let Some(sender) = self.clients.get(index) {
+                    let message = Message {
+                        content: self.censor_for(self.chat_shown(spectator), &message.content),
+                        ..message.clone()
+                    };
+
+                    if let Ok(message_se) = ron::ser::to_string(&message) {
+                        let _ok = sender.send(format!("= text_game {message_se}"));
+                    }
                 }
-                Role::Roleless => {}
-                Role::Defender => {
-                    game.attacker_tx.send(message);
+            }
+        }
+
+        if let Some(game) = self.games.0.get_mut(&id) {
+            game.messages.push_front(message);
+        }
+
+        self.texts_unread_send(id);
+
+        None
+    }
+
+    /// Marks the chat of the game as read by `username`, if they play in it.
+    fn texts_seen(&mut self, username: &str, id: Id) {
+        let Some(game) = self.games.0.get(&id) else {
+            return;
+        };
+
+        if game.attacker == username || game.defender == username {
+            let seen = game.messages.len();
+
+            if let Some(account) = self.accounts.0.get_mut(username) {
+                account.texts_read.insert(id, seen);
+            }
+        }
+    }
+
+    /// How many messages there are in the chat of each of the games
+    /// `username` plays in that they haven't seen.
+    fn texts_unread(&self, username: &str) -> BTreeMap<Id, usize> {
+        let Some(account) = self.accounts.0.get(username) else {
+            return BTreeMap::new();
+        };
+
+        self.games
+            .0
+            .values()
+            .filter(|game| game.attacker == username || game.defender == username)
+            .filter_map(|game| {
+                let read = account
+                    .texts_read
+                    .get(&game.id)
+                    .copied()
+                    .unwrap_or_default();
+                let unread = game.messages.len().saturating_sub(read);
+                (unread > 0).then_some((game.id, unread))
+            })
+            .collect()
+    }
+
+    /// After a message in the chat of a game, the players that are watching
+    /// it have seen it and the ones that aren't get their unread counts.
+    fn texts_unread_send(&mut self, id: Id) {
+        let Some(game) = self.games.0.get(&id) else {
+            return;
+        };
+        let watching = self
+            .games_light
+            .0
+            .get(&id)
+            .map(|game| game.spectators.clone())
+            .unwrap_or_default();
+
+        for username in [game.attacker.clone(), game.defender.clone()] {
+            if watching.contains_key(&username) {
+                self.texts_seen(&username, id);
+            } else if let Some(index) = self
+                .accounts
+                .0
+                .get(&username)
+                .and_then(|account| account.logged_in)
+                && let Some(tx) = self.clients.get(&index)
+                && let Ok(unread) = ron::ser::to_string(&self.texts_unread(&username))
+            {
+                let _ok = tx.send(format!("= texts_unread {unread}"));
+            }
+        }
+    }
+
+    /// Sends a message to the players in the tournament and the tournament
+    /// directors that are logged in.
+    fn text_tournament(
+        &mut self,
+        index_supplied: usize,
+        username: &str,
+        command: &str,
+        content: &str,
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        if !self.tournament.has_player(username)
+            && !self.roles.allows(username, Permission::Tournament)
+        {
+            return Some((
+                self.clients.get(&index_supplied)?.clone(),
+                Err(InvalidMove::Other),
+                format!("{command} you are not in the tournament"),
+            ));
+        }
+
+        if content.is_empty()
+            || content.len() > self.config.message_length
+            || self.kid_safe_mutes(username, content)
+        {
+            return None;
+        }
+
+        let message = Message {
+            username: username.to_string(),
+            timestamp: clock::now(),
+            content: content.to_string(),
+        };
+
+        info!("{index_supplied} text_tournament {message:?}");
+
+        if self.texts_tournament.len() >= self.config.keep_texts {
+            self.texts_tournament.pop_front();
+        }
+
+        for (username, account) in &self.accounts.0 {
+            if let Some(index) = account.logged_in
+                && let Some(tx) = self.clients.get(&index)
+                && (self.tournament.has_player(username)
+                    || self.roles.allows(username, Permission::Tournament))
+            {
+                let message = Message {
+                    content: self.censor_for(self.chat_shown(username), &message.content),
+                    ..message.clone()
+                };
+
+                if let Ok(message_se) = ron::ser::to_string(&message) {
+                    let _ok = tx.send(format!("= text_tournament {message_se}"));
+                }
+            }
+        }
+
+        self.texts_tournament.push_back(message);
+
+        None
+    }
+
+    /// Emails a player that isn't logged in that it's their turn. They can't
+    /// move until they log in, so they get at most one email per game until
+    /// they have been back.
+    fn turn_notification(&mut self, username: &str, opponent: &str, id: Id) {
+        let Some(account) = self.accounts.0.get(username) else {
+            return;
+        };
+
+        if account.logged_in.is_some() || !account.email_preferences.turn_notifications {
+            return;
+        }
+
+        if self
+            .turn_notified
+            .get(&id)
+            .is_some_and(|notified| *notified > account.last_logged_in.0)
+        {
+            return;
+        }
+
+        if let Some(email) = &account.email
+            && email.verified
+            && let Some(mailbox) = email.to_mailbox()
+        {
+            let template = EmailTemplate::TurnNotification {
+                username: username.to_string(),
+                opponent: opponent.to_string(),
+                id,
+            };
+
+            let unsubscribe = self.unsubscribe_link(username, EmailCategory::TurnNotifications);
+            match template.message(
+                lettre::Message::builder().to(mailbox),
+                account.locale,
+                unsubscribe.as_deref(),
+            ) {
+                Ok(message) => {
+                    self.mail(vec![(email.address.clone(), message)]);
+                    self.turn_notified.insert(id, clock::now());
+                }
+                Err(err) => error!("could not write an email to {}: {err}", email.address),
+            }
+        }
+    }
+
+    /// ```sh
+    /// <- tournament_arena MINUTES|none
+    /// ```
+    fn tournament_arena(&mut self, the_rest: &[&str]) -> anyhow::Result<()> {
+        self.tournament.arena_minutes = match the_rest.first() {
+            Some(&"none") => None,
+            Some(minutes) => Some(minutes.parse()?),
+            None => return Err(anyhow::Error::msg("minutes are empty")),
+        };
+
+        Ok(())
+    }
+
+    fn tournament_board_size(&mut self, the_rest: &[&str]) -> anyhow::Result<()> {
+        let Some(date) = the_rest.first() else {
+            return Err(anyhow::Error::msg("tournament_board_size: size is empty"));
+        };
+
+        self.tournament.board_size = match date.parse::<u8>() {
+            Ok(11) => BoardSize::_11,
+            Ok(13) => BoardSize::_13,
+            Ok(i) => {
+                return Err(anyhow::Error::msg(format!(
+                    "tournament_board_size: {i} is an invalid size"
+                )));
+            }
+            Err(error) => {
+                return Err(anyhow::Error::msg(format!(
+                    "tournament_board_size: {error}"
+                )));
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Returns true if the user has to wait before sending another message to
+    /// the channel. Either way, a user in slow mode gets told how long to wait,
+    /// so the client can hold the message input until then.
+    ///
+    /// ```sh
+    /// -> = slow_mode text SECONDS
+    /// -> = slow_mode GAME_ID SECONDS
+    /// ```
+    fn slow_mode_wait(
+        &mut self,
+        index_supplied: usize,
+        username: &str,
+        channel: Option<Id>,
+    ) -> bool {
+        let seconds = match channel {
+            None => self.chat_slow_mode,
+            Some(id) => {
+                if self
+                    .tournament
+                    .tournament
+                    .as_ref()
+                    .is_some_and(|tournament| tournament.is_tournament_game(&id))
+                {
+                    self.config.slow_mode_tournament_seconds
+                } else {
+                    0
                 }
             }
+        };
+
+        // The server and the bridges don't wait.
+        if seconds == 0 || index_supplied == 0 {
+            return false;
+        }
+
+        let now = clock::now();
+        let longest = i64::try_from(
+            self.chat_slow_mode
+                .max(self.config.slow_mode_tournament_seconds),
+        )
+        .unwrap_or(i64::MAX);
+        self.chat_last_sent
+            .retain(|_, sent| now.duration_since(*sent).as_secs() < longest);
+
+        let key = (username.to_string(), channel);
+        let elapsed = self
+            .chat_last_sent
+            .get(&key)
+            .map(|sent| now.duration_since(*sent).as_secs().unsigned_abs());
+
+        let (wait, seconds_left) = match elapsed {
+            Some(elapsed) if elapsed < seconds => (true, seconds - elapsed),
+            _ => {
+                self.chat_last_sent.insert(key, now);
+                (false, seconds)
+            }
+        };
+
+        let channel = channel.map_or_else(|| "text".to_string(), |id| id.to_string());
+        if let Some(tx) = self.clients.get(&index_supplied) {
+            let _ok = tx.send(format!("= slow_mode {channel} {seconds_left}"));
+        }
+
+        wait
+    }
+
+    fn simul_join(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let channel = self.clients.get(&index_supplied)?;
+        let mut command = (*command).to_string();
+
+        let Some(simul) = the_rest
+            .first()
+            .and_then(|id| id.parse::<u64>().ok())
+            .and_then(|id| self.simuls.0.get_mut(&id))
+        else {
+            command.push_str(" there is no such simul");
+            return Some((channel.clone(), Err(InvalidMove::Other), command));
+        };
+
+        if simul.started() {
+            command.push_str(" the simul already started");
+            return Some((channel.clone(), Err(InvalidMove::Other), command));
+        }
+
+        if simul.host == username {
+            command.push_str(" you are the host");
+            return Some((channel.clone(), Err(InvalidMove::Other), command));
+        }
+
+        if let (Some(account_1), Some(account_2)) = (
+            self.accounts.0.get(username),
+            self.accounts.0.get(&simul.host),
+        ) && (!account_1.kid_safe_allows(&simul.host) || !account_2.kid_safe_allows(username))
+        {
+            command.push_str(" kid-safe accounts only play their approved friends");
+            return Some((channel.clone(), Err(InvalidMove::Other), command));
+        }
+
+        info!("{index_supplied} {username} simul_join {}", simul.id);
+        simul.players.insert(username.to_string());
+        self.simuls_status_all();
+
+        None
+    }
+
+    fn simul_leave(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let channel = self.clients.get(&index_supplied)?;
+        let mut command = (*command).to_string();
+
+        let Some(id) = the_rest.first().and_then(|id| id.parse::<u64>().ok()) else {
+            command.push_str(" there is no such simul");
+            return Some((channel.clone(), Err(InvalidMove::Other), command));
+        };
+
+        let Some(simul) = self.simuls.0.get_mut(&id) else {
+            command.push_str(" there is no such simul");
+            return Some((channel.clone(), Err(InvalidMove::Other), command));
+        };
+
+        if simul.started() {
+            command.push_str(" the simul already started");
+            return Some((channel.clone(), Err(InvalidMove::Other), command));
+        }
+
+        info!("{index_supplied} {username} simul_leave {id}");
+
+        if simul.host == username {
+            self.simuls.0.remove(&id);
+        } else {
+            simul.players.remove(username);
         }
 
-        Some((
-            self.clients.get(&index_supplied)?.clone(),
-            Ok(()),
-            (*command).to_string(),
-        ))
-    }
+        self.simuls_status_all();
 
-    fn save(tx: Sender<(String, Option<Sender<String>>)>) {
-        thread::spawn(move || {
-            loop {
-                thread::sleep(Duration::from_secs(HOUR_IN_SECONDS));
-                handle_error(tx.send(("0 server save".to_string(), None)));
-            }
-        });
+        None
     }
 
-    fn save_server(&self) {
-        if !self.skip_the_data_files {
-            let mut server = self.clone();
+    fn simul_new(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let channel = self.clients.get(&index_supplied)?;
+        let mut command = (*command).to_string();
 
-            for account in server.accounts.0.values_mut() {
-                account.logged_in = None;
-            }
+        if self.simuls.0.values().any(|simul| simul.host == username) {
+            command.push_str(" you are already hosting a simul");
+            return Some((channel.clone(), Err(InvalidMove::Other), command));
+        }
 
-            match ron::ser::to_string_pretty(&server, ron::ser::PrettyConfig::default()) {
-                Ok(string) => {
-                    if !string.trim().is_empty() {
-                        let users_file = data_file(USERS_FILE);
+        let new_game = the_rest.join(" ");
+        let simul = serde_json::de::from_str::<NewGame>(&new_game)
+            .map_err(anyhow::Error::from)
+            .and_then(|new_game| Simul::new(self.simul_id, username, &new_game));
 
-                        match File::create(&users_file) {
-                            Ok(mut file) => {
-                                if let Err(error) = file.write_all(string.as_bytes()) {
-                                    error!("save file (3): {error}");
-                                }
-                            }
-                            Err(error) => error!("save file (2): {error}"),
-                        }
-                    }
-                }
-                Err(error) => error!("save file (1): {error}"),
+        let simul = match simul {
+            Ok(simul) => simul,
+            Err(error) => {
+                error!("simul_new: {error}");
+                command.push_str(" invalid simul settings");
+                return Some((channel.clone(), Err(InvalidMove::Other), command));
             }
-        }
+        };
+
+        info!(
+            "{index_supplied} {username} simul_new {} {} {} {:?} {}",
+            simul.id, simul.role, simul.rated, simul.time_settings, simul.board_size,
+        );
+
+        let channel = channel.clone();
+        let _ = write!(command, " {}", simul.id);
+
+        self.simuls.0.insert(simul.id, simul);
+        self.simul_id += 1;
+        self.simuls_status_all();
+
+        Some((channel, Ok(()), command))
     }
 
-    fn text_game(
+    fn simul_start(
         &mut self,
         username: &str,
         index_supplied: usize,
         command: &str,
-        mut the_rest: Vec<&str>,
+        the_rest: &[&str],
     ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
-        let Some(id) = the_rest.first() else {
-            return Some((
-                self.clients.get(&index_supplied)?.clone(),
-                Err(InvalidMove::Other),
-                (*command).to_string(),
-            ));
+        let channel = self.clients.get(&index_supplied)?.clone();
+        let mut command = (*command).to_string();
+
+        let Some(mut simul) = the_rest
+            .first()
+            .and_then(|id| id.parse::<u64>().ok())
+            .and_then(|id| self.simuls.0.remove(&id))
+        else {
+            command.push_str(" there is no such simul");
+            return Some((channel, Err(InvalidMove::Other), command));
         };
 
-        let Ok(id) = id.parse::<Id>() else {
-            return Some((
-                self.clients.get(&index_supplied)?.clone(),
-                Err(InvalidMove::Other),
-                (*command).to_string(),
-            ));
+        let error = if simul.host != username {
+            Some(" you are not the host")
+        } else if simul.started() {
+            Some(" the simul already started")
+        } else if simul.players.is_empty() {
+            Some(" nobody joined the simul")
+        } else {
+            None
         };
 
-        let mut content = the_rest.split_off(1).join(" ");
+        if let Some(error) = error {
+            self.simuls.0.insert(simul.id, simul);
+            command.push_str(error);
+            return Some((channel, Err(InvalidMove::Other), command));
+        }
+
+        info!("{index_supplied} {username} simul_start {}", simul.id);
 
-        if content.is_empty() {
-            return None;
+        for player in &simul.players {
+            let (attacker, defender) = simul.attacker_defender(player);
+            let id = self.new_game_accepted(
+                attacker,
+                defender,
+                simul.rated,
+                simul.time_settings,
+                simul.board_size,
+            );
+
+            simul.games.push(id);
         }
 
-        content = self.censor(&content);
+        let _ = write!(command, " {}", simul.id);
+        let message = format!("= {command}");
+        let players = simul.players.clone();
 
-        let message = Message {
-            username: username.to_string(),
-            timestamp: Timestamp::now(),
-            content,
-        };
+        self.simuls.0.insert(simul.id, simul);
+        self.simuls_status_all();
 
-        let Ok(message_se) = ron::ser::to_string(&message) else {
-            unreachable!();
-        };
+        for name in &players {
+            if let Some(account) = self.accounts.0.get(name)
+                && let Some(index) = account.logged_in
+                && let Some(tx) = self.clients.get(&index)
+            {
+                let _ok = tx.send(message.clone());
+            }
+        }
 
-        let message_string = format!("= text_game {message_se}");
+        Some((channel, Ok(()), command))
+    }
 
-        info!("{index_supplied} {username} text_game {id} {message_se}");
-        if let Some(game) = self.games.0.get_mut(&id) {
-            game.messages.push_front(message);
+    fn clubs_status_all(&self) {
+        trace!("clubs: {:#?}", self.clubs);
+
+        if let Ok(mut clubs) = ron::ser::to_string(&self.clubs) {
+            clubs = format!("= clubs {clubs}");
+
+            for tx in self.clients.values() {
+                let _ok = tx.send(clubs.clone());
+            }
         }
+    }
 
-        if let Some(game) = self.games_light.0.get(&id) {
-            for index in game.spectators.values() {
-                if let Some(sender) = self.clients.get(index) {
-                    let _ok = sender.send(message_string.clone());
-                }
+    fn simuls_status_all(&self) {
+        trace!("simuls: {:#?}", self.simuls);
+
+        if let Ok(mut simuls) = ron::ser::to_string(&self.simuls) {
+            simuls = format!("= simuls {simuls}");
+
+            for tx in self.clients.values() {
+                let _ok = tx.send(simuls.clone());
             }
         }
+    }
 
-        None
+    /// The ratings now of a game's two players, so someone looking over an old
+    /// game can compare them with the ratings then. Unknown players, and those
+    /// kid-safe mode keeps apart from you, are left out.
+    ///
+    /// ```sh
+    /// <- ratings david alice
+    /// -> = ratings {"alice":{"rating":1500.0,"rd":350.0,"volatility":0.06}}
+    /// ```
+    fn ratings(
+        &self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        usernames: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let tx = self.clients.get(&index_supplied)?.clone();
+        let account = self.accounts.0.get(username)?;
+
+        if usernames.len() > 2 {
+            return Some((tx, Err(InvalidMove::Other), command.to_string()));
+        }
+
+        let ratings: BTreeMap<_, _> = usernames
+            .iter()
+            .filter_map(|player| {
+                self.accounts
+                    .0
+                    .get(*player)
+                    .filter(|player_account| {
+                        *player == username
+                            || account.kid_safe_allows(player)
+                                && player_account.kid_safe_allows(username)
+                    })
+                    .map(|player_account| (*player, &player_account.rating))
+            })
+            .collect();
+
+        Some((
+            tx,
+            Ok(()),
+            format!("{command} {}", serde_json::to_string(&ratings).ok()?),
+        ))
     }
 
-    fn tournament_board_size(&mut self, the_rest: &[&str]) -> anyhow::Result<()> {
-        let Some(date) = the_rest.first() else {
-            return Err(anyhow::Error::msg("tournament_board_size: size is empty"));
+    /// Anyone may ask for the status before logging in, the server closes the
+    /// connection after answering.
+    ///
+    /// ```sh
+    /// <- status
+    /// -> = status {"version":"6.2.2","players_online":2,"tournament":null}
+    /// ```
+    fn status(
+        &self,
+        option_tx: Option<Sender<String>>,
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let tournament = if self.tournament.tournament.is_none() {
+            self.tournament.date
+        } else {
+            None
         };
 
-        self.tournament.board_size = match date.parse::<u8>() {
-            Ok(11) => BoardSize::_11,
-            Ok(13) => BoardSize::_13,
-            Ok(i) => {
-                return Err(anyhow::Error::msg(format!(
-                    "tournament_board_size: {i} is an invalid size"
-                )));
-            }
-            Err(error) => {
-                return Err(anyhow::Error::msg(format!(
-                    "tournament_board_size: {error}"
-                )));
-            }
+        let status = ServerStatus {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            players_online: self
+                .accounts
+                .0
+                .values()
+                .filter(|account| account.logged_in.is_some())
+                .count(),
+            tournament,
         };
 
-        Ok(())
+        option_tx?
+            .send(format!("= status {}", serde_json::to_string(&status).ok()?))
+            .ok()?;
+
+        None
     }
 
     fn tournament_date(&mut self, the_rest: &[&str]) -> anyhow::Result<()> {
@@ -3050,6 +7387,97 @@ impl Server {
         Ok(())
     }
 
+    fn tournament_rated(&mut self, the_rest: &[&str]) -> anyhow::Result<()> {
+        let Some(rated) = the_rest.first() else {
+            return Err(anyhow::Error::msg("tournament_rated: rated is empty"));
+        };
+
+        self.tournament.rated = rated.parse()?;
+
+        Ok(())
+    }
+
+    fn tournament_reminder(tx: Sender<(String, Option<Sender<String>>)>) {
+        thread::spawn(move || {
+            loop {
+                handle_error(tx.send(("0 server tournament_reminder".to_string(), None)));
+                thread::sleep(Duration::from_secs(HOUR_IN_SECONDS));
+            }
+        });
+    }
+
+    /// Emails the players that joined the tournament once, within a day of it
+    /// starting.
+    fn tournament_reminder_send(&mut self) {
+        let Some(date) = self.tournament.date else {
+            return;
+        };
+
+        let seconds_left = date.as_second() - clock::now().as_second();
+
+        if self.tournament.tournament.is_some()
+            || self.tournament_reminded == Some(date)
+            || !(0..=DAY_IN_SECONDS_SIGNED).contains(&seconds_left)
+        {
+            return;
+        }
+
+        self.tournament_reminded = Some(date);
+        let mut emails = Vec::new();
+
+        for username in &self.tournament.players {
+            if let Some(account) = self.accounts.0.get(username)
+                && account.email_preferences.tournaments
+                && let Some(email) = &account.email
+                && email.verified
+                && let Some(mailbox) = email.to_mailbox()
+            {
+                let template = EmailTemplate::TournamentReminder {
+                    username: username.clone(),
+                    date,
+                };
+
+                let unsubscribe = self.unsubscribe_link(username, EmailCategory::Tournaments);
+                match template.message(
+                    lettre::Message::builder().to(mailbox),
+                    account.locale,
+                    unsubscribe.as_deref(),
+                ) {
+                    Ok(message) => emails.push((email.address.clone(), message)),
+                    Err(err) => error!("could not write an email to {}: {err}", email.address),
+                }
+            }
+        }
+
+        self.mail(emails);
+    }
+
+    /// ```sh
+    /// <- tournament_requirements (rated_games:10,rating_minimum:Some(1400.0),rating_maximum:None,players_maximum:Some(32))
+    /// ```
+    fn tournament_requirements(&mut self, the_rest: &[&str]) -> anyhow::Result<()> {
+        if the_rest.is_empty() {
+            return Err(anyhow::Error::msg("requirements are empty"));
+        }
+
+        self.tournament.requirements = ron::de::from_str(&the_rest.join(" "))?;
+
+        Ok(())
+    }
+
+    /// ```sh
+    /// <- tournament_round_days DAYS|none
+    /// ```
+    fn tournament_round_days(&mut self, the_rest: &[&str]) -> anyhow::Result<()> {
+        self.tournament.round_days = match the_rest.first() {
+            Some(&"none") => None,
+            Some(days) => Some(days.parse()?),
+            None => return Err(anyhow::Error::msg("days are empty")),
+        };
+
+        Ok(())
+    }
+
     fn tournament_time(&mut self, the_rest: &[&str]) -> anyhow::Result<()> {
         let Some(time_settings) = the_rest.first() else {
             return Err(anyhow::Error::msg("tournament_time: time is empty"));
@@ -3073,6 +7501,165 @@ impl Server {
                 let _ok = tx.send(tournament.clone());
             }
         }
+
+        let announcement = self.announcement();
+        for tx in self.clients.values() {
+            let _ok = tx.send(announcement.clone());
+        }
+    }
+
+    /// Starts the tournament once its date comes.
+    fn tournament_start(&mut self, now: Timestamp) {
+        if self.tournament.tournament.is_none()
+            && let Some(date) = self.tournament.date
+            && now >= date
+        {
+            info!("Starting tournament...");
+
+            if self.tournament.arena_minutes.is_none()
+                && let TimeSettings::Timed(time) = &mut self.tournament.time_setting
+                && time.milliseconds_left <= 1_000 * DAY_IN_SECONDS_SIGNED
+                && let Ok(players) = i64::try_from(self.tournament.players.len())
+            {
+                time.milliseconds_left *= (players - 1) * 2;
+                time.add_seconds *= (players - 1) * 2;
+            }
+
+            let mut tournament = Tournament {
+                players: take(&mut self.tournament.players),
+                board_size: self.tournament.board_size,
+                time_setting: self.tournament.time_setting,
+                rated: self.tournament.rated,
+                round_days: self.tournament.round_days,
+                group_size: self.tournament.group_size.size,
+                number_of_games: self.tournament.number_of_games.number,
+                ..Tournament::default()
+            };
+
+            tournament.date = now;
+
+            if let Some(minutes) = self.tournament.arena_minutes {
+                let ends = tournament
+                    .date
+                    .checked_add(SignedDuration::from_mins(minutes))
+                    .unwrap_or(Timestamp::MAX);
+
+                tournament.arena = Some(Arena::new(&tournament.players, ends));
+                self.tournament.tournament = Some(tournament);
+                self.arena_pair();
+            } else {
+                self.tournament.tournament = Some(tournament);
+                self.generate_round();
+            }
+
+            self.tournament_status_all();
+        }
+    }
+
+    /// Leaves the tournament before it starts, otherwise forfeits the games
+    /// left.
+    fn tournament_withdraw(&mut self, username: &str) {
+        match &mut self.tournament.tournament {
+            Some(tournament) if tournament.players.contains(username) => {
+                info!("{username} withdrew from the tournament");
+                tournament.withdrawn.insert(username.to_string());
+            }
+            Some(_) => {}
+            None => {
+                self.tournament.players.remove(username);
+            }
+        }
+    }
+
+    /// The player to move resigns the tournament games of the players who
+    /// withdrew and every game left once the round is over.
+    fn tournament_forfeits(&mut self) {
+        self.tournament_forfeited
+            .retain(|id| self.games.0.contains_key(id));
+
+        let (Some(tournament), Some(tx)) = (&self.tournament.tournament, &self.tx) else {
+            return;
+        };
+
+        let round_over = tournament
+            .round_deadline()
+            .is_some_and(|deadline| deadline <= clock::now());
+
+        for id in tournament.game_ids() {
+            let Some(game) = self.games.0.get(&id) else {
+                continue;
+            };
+
+            let player = match game.game.turn {
+                Role::Attacker => &game.attacker,
+                Role::Defender => &game.defender,
+                Role::Roleless => continue,
+            };
+
+            if game.game.status == Status::Ongoing
+                && (round_over || tournament.withdrawn.contains(player))
+                && self.tournament_forfeited.insert(id)
+            {
+                let _ok = tx.send((
+                    format!("0 {player} game {id} play {} resigns _", game.game.turn),
+                    None,
+                ));
+            }
+        }
+    }
+
+    /// Takes vacation days, comes back early, or says how many days you have
+    /// left this year. Your correspondence clocks don't run while you are away.
+    ///
+    /// ```sh
+    /// <- vacation 7
+    /// -> = vacation UNTIL_UNIX_MILLISECONDS 23
+    /// <- vacation_end
+    /// -> = vacation _ 30
+    /// <- vacation
+    /// -> = vacation _ 30
+    /// ```
+    fn vacation(
+        &mut self,
+        username: &str,
+        index_supplied: usize,
+        command: &str,
+        the_rest: &[&str],
+    ) -> Option<(mpsc::Sender<String>, Result<(), InvalidMove>, String)> {
+        let tx = self.clients.get(&index_supplied)?.clone();
+        let now = clock::now();
+        let account = self.accounts.0.get_mut(username)?;
+
+        let result = match (command, the_rest) {
+            ("vacation", []) => Ok(()),
+            ("vacation", [days]) => days
+                .parse()
+                .map_err(anyhow::Error::from)
+                .and_then(|days| account.vacation.start(days, now)),
+            ("vacation_end", []) => {
+                account.vacation.end(now);
+                Ok(())
+            }
+            _ => Err(anyhow::Error::msg("wrong arguments")),
+        };
+
+        if let Err(error) = result {
+            error!("{index_supplied} {username} {command}: {error}");
+            return Some((tx, Err(InvalidMove::Other), format!("{command} {error}")));
+        }
+
+        let until = match account.vacation.until {
+            Some(until) if account.vacation.on(now) => until.to_string(),
+            _ => "_".to_string(),
+        };
+        let days_left = account.vacation.days_left(now);
+
+        if !the_rest.is_empty() || command == "vacation_end" {
+            info!("{index_supplied} {username} {command} {until} {days_left}");
+            self.save_server();
+        }
+
+        Some((tx, Ok(()), format!("vacation {until} {days_left}")))
     }
 
     fn watch_game(
@@ -3107,8 +7694,8 @@ impl Server {
         let Ok(board) = ron::ser::to_string(game) else {
             unreachable!();
         };
-        let messages = &server_game.messages;
-        let Ok(texts_se) = ron::ser::to_string(messages) else {
+        let messages = self.messages_for(username, &server_game.messages);
+        let Ok(texts_se) = ron::ser::to_string(&messages) else {
             unreachable!();
         };
 
@@ -3119,7 +7706,8 @@ impl Server {
         };
 
         if command == "watch_game_json" {
-            let opentafl_game = OpenTaflGame::from(server_game);
+            let mut opentafl_game = OpenTaflGame::from(server_game);
+            opentafl_game.messages = messages;
 
             let Ok(resume_game) = serde_json::to_string(&opentafl_game) else {
                 unreachable!();
@@ -3130,7 +7718,8 @@ impl Server {
                 .send(format!("= watch_game_json {resume_game}"))
                 .ok()?;
         } else if command == "watch_game_ron" {
-            let opentafl_game = OpenTaflGame::from(server_game);
+            let mut opentafl_game = OpenTaflGame::from(server_game);
+            opentafl_game.messages = messages;
 
             let Ok(opentafl_game) = ron::to_string(&opentafl_game) else {
                 unreachable!();
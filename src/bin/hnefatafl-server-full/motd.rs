@@ -0,0 +1,81 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! The message of the day the admins set, which everyone sees when they log
+//! in. The messages that were replaced or cleared are kept, so the admins can
+//! look back on what was said.
+
+use std::collections::VecDeque;
+
+use hnefatafl_copenhagen::clock;
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// How many of the past messages are kept.
+const HISTORY: usize = 100;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct Motd {
+    #[serde(default)]
+    pub current: Option<MotdEntry>,
+    /// The oldest first.
+    #[serde(default)]
+    pub history: VecDeque<MotdEntry>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct MotdEntry {
+    pub text: String,
+    pub username: String,
+    pub set: Timestamp,
+    #[serde(default)]
+    pub cleared: Option<Timestamp>,
+}
+
+impl Motd {
+    pub(crate) fn set(&mut self, username: &str, text: &str) {
+        self.clear();
+
+        self.current = Some(MotdEntry {
+            text: text.to_string(),
+            username: username.to_string(),
+            set: clock::now(),
+            cleared: None,
+        });
+    }
+
+    pub(crate) fn clear(&mut self) {
+        if let Some(mut entry) = self.current.take() {
+            entry.cleared = Some(clock::now());
+            self.history.push_back(entry);
+
+            if self.history.len() > HISTORY {
+                self.history.pop_front();
+            }
+        }
+    }
+
+    /// The message as it's sent to the clients, `= motd` by itself when there
+    /// is none.
+    pub(crate) fn message(&self) -> String {
+        match &self.current {
+            Some(entry) => format!("= motd {}", entry.text),
+            None => "= motd".to_string(),
+        }
+    }
+}
@@ -0,0 +1,106 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! The chat is kept as it was written and masked for each person it's sent to,
+//! so someone can turn the filter off for themselves. On top of the built in
+//! filters, the admins keep a list of words for each locale, which are masked
+//! for the people using that locale.
+
+use std::collections::{BTreeSet, HashMap};
+
+use hnefatafl_copenhagen::locale::Locale;
+use serde::{Deserialize, Serialize};
+
+/// How the chat gets shown to someone.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(crate) enum ChatShown {
+    All,
+    /// With the words masked for the locale.
+    Masked(Locale),
+    /// Only the phrases a kid-safe account may say.
+    Phrases,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(crate) struct ProfanityFilter {
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+    /// Lowercase words.
+    #[serde(default)]
+    pub words: HashMap<Locale, BTreeSet<String>>,
+}
+
+impl Default for ProfanityFilter {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            words: HashMap::new(),
+        }
+    }
+}
+
+impl ProfanityFilter {
+    pub(crate) fn add(&mut self, locale: Locale, word: &str) {
+        self.words
+            .entry(locale)
+            .or_default()
+            .insert(word.to_lowercase());
+    }
+
+    pub(crate) fn remove(&mut self, locale: Locale, word: &str) {
+        if let Some(words) = self.words.get_mut(&locale) {
+            words.remove(&word.to_lowercase());
+
+            if words.is_empty() {
+                self.words.remove(&locale);
+            }
+        }
+    }
+
+    /// Replaces each letter of the locale's words with a `*`, ignoring case.
+    pub(crate) fn mask(&self, text: &str, locale: Locale) -> String {
+        let Some(words) = self.words.get(&locale) else {
+            return text.to_string();
+        };
+
+        let mut masked = String::with_capacity(text.len());
+        let mut word = String::new();
+
+        for ch in text.chars().chain(Some(' ')) {
+            if ch.is_alphanumeric() {
+                word.push(ch);
+                continue;
+            }
+
+            if words.contains(&word.to_lowercase()) {
+                masked.extend(word.chars().map(|_| '*'));
+            } else {
+                masked.push_str(&word);
+            }
+            word.clear();
+            masked.push(ch);
+        }
+
+        masked.pop();
+        masked
+    }
+}
+
+fn enabled() -> bool {
+    true
+}
@@ -0,0 +1,98 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! Players reporting each other to the moderators, who look them over with
+//! `reports` and clear them once they have dealt with them. Cleared reports
+//! are kept, so the moderators can see who was reported before.
+
+use hnefatafl_copenhagen::clock;
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// How many reports are kept, the oldest cleared ones go first.
+const KEPT: usize = 1_000;
+
+/// How many reports a reporter may send in an hour.
+const PER_HOUR: usize = 10;
+
+const HOUR: i64 = 60 * 60 * 1_000;
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct Report {
+    /// Who was reported.
+    pub username: String,
+    pub reporter: String,
+    pub reason: String,
+    pub created: Timestamp,
+    /// Whether the moderators have dealt with it.
+    #[serde(default)]
+    pub resolved: bool,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct Reports(pub Vec<Report>);
+
+impl Reports {
+    /// Whether `reporter` has sent fewer than `PER_HOUR` reports in the last
+    /// hour.
+    #[must_use]
+    pub(crate) fn may_add(&self, reporter: &str, now: Timestamp) -> bool {
+        self.0
+            .iter()
+            .filter(|report| {
+                report.reporter == reporter
+                    && now.as_millisecond() - report.created.as_millisecond() < HOUR
+            })
+            .count()
+            < PER_HOUR
+    }
+
+    /// Replaces an earlier report by the same reporter about the same player,
+    /// so reporting again doesn't pile up.
+    pub(crate) fn add(&mut self, username: &str, reporter: &str, reason: &str) {
+        self.0
+            .retain(|report| report.username != username || report.reporter != reporter);
+
+        self.0.push(Report {
+            username: username.to_string(),
+            reporter: reporter.to_string(),
+            reason: reason.to_string(),
+            created: clock::now(),
+            resolved: false,
+        });
+
+        if self.0.len() > KEPT {
+            let oldest = self.0.iter().position(|report| report.resolved);
+            self.0.remove(oldest.unwrap_or(0));
+        }
+    }
+
+    /// Marks the reports about `username` resolved, returning whether there
+    /// were any that weren't.
+    pub(crate) fn clear(&mut self, username: &str) -> bool {
+        let mut cleared = false;
+        for report in &mut self.0 {
+            if report.username == username && !report.resolved {
+                report.resolved = true;
+                cleared = true;
+            }
+        }
+
+        cleared
+    }
+}
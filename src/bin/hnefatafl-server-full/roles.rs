@@ -0,0 +1,184 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! Who may run the privileged commands. An owner may do anything, a moderator
+//! looks after the chat, the bans, and the message of the day, and a
+//! tournament director runs the tournament.
+
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) enum AdminRole {
+    Owner,
+    Moderator,
+    TournamentDirector,
+}
+
+impl AdminRole {
+    #[must_use]
+    pub(crate) fn allows(self, permission: Permission) -> bool {
+        match self {
+            Self::Owner => true,
+            Self::Moderator => matches!(
+                permission,
+                Permission::Bans | Permission::Chat | Permission::Motd
+            ),
+            Self::TournamentDirector => permission == Permission::Tournament,
+        }
+    }
+
+    /// Owners come before moderators, who come before tournament directors.
+    fn rank(self) -> u8 {
+        match self {
+            Self::Owner => 3,
+            Self::Moderator => 2,
+            Self::TournamentDirector => 1,
+        }
+    }
+}
+
+impl fmt::Display for AdminRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Owner => write!(f, "owner"),
+            Self::Moderator => write!(f, "moderator"),
+            Self::TournamentDirector => write!(f, "tournament_director"),
+        }
+    }
+}
+
+impl FromStr for AdminRole {
+    type Err = anyhow::Error;
+
+    fn from_str(role: &str) -> anyhow::Result<Self> {
+        match role {
+            "owner" => Ok(Self::Owner),
+            "moderator" => Ok(Self::Moderator),
+            "tournament_director" => Ok(Self::TournamentDirector),
+            _ => Err(anyhow::Error::msg(format!("{role} isn't a role"))),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Permission {
+    /// Seeing everyone's account, emails included.
+    Accounts,
+    Audit,
+    Bans,
+    /// Looking over the bug reports players send in.
+    BugReports,
+    Chat,
+    Config,
+    Email,
+    Motd,
+    Roles,
+    Tournament,
+}
+
+impl Permission {
+    /// The permission a command needs, `None` if anyone may run it.
+    #[must_use]
+    pub(crate) fn required(command: &str) -> Option<Self> {
+        match command {
+            "audit_log" => Some(Self::Audit),
+            "avatar_clear" | "ban" | "bans" | "reports" | "reports_clear" | "unban" => {
+                Some(Self::Bans)
+            }
+            "bug_reports" | "bug_reports_clear" => Some(Self::BugReports),
+            "chat_slow_mode" | "profanity_filter" | "profanity_words" => Some(Self::Chat),
+            "config_reload" => Some(Self::Config),
+            "email_everyone" => Some(Self::Email),
+            "export_accounts" | "kid_safe_friends" | "kid_safe_set" => Some(Self::Accounts),
+            "motd_clear" | "motd_history" | "motd_set" => Some(Self::Motd),
+            "role_remove" | "role_set" | "roles" => Some(Self::Roles),
+            "tournament_arena"
+            | "tournament_board_size"
+            | "tournament_date"
+            | "tournament_delete"
+            | "tournament_group_size"
+            | "tournament_groups_delete"
+            | "tournament_number_of_games"
+            | "tournament_rated"
+            | "tournament_requirements"
+            | "tournament_round_days"
+            | "tournament_start"
+            | "tournament_time" => Some(Self::Tournament),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct Roles(pub BTreeMap<String, AdminRole>);
+
+impl Roles {
+    #[must_use]
+    pub(crate) fn allows(&self, username: &str, permission: Permission) -> bool {
+        self.0
+            .get(username)
+            .is_some_and(|role| role.allows(permission))
+    }
+
+    /// Whether `username` has a role above the one of `other`, who may have
+    /// none.
+    #[must_use]
+    pub(crate) fn outranks(&self, username: &str, other: &str) -> bool {
+        let rank = |username| self.0.get(username).map_or(0, |role| role.rank());
+
+        rank(username) > rank(other)
+    }
+
+    /// # Errors
+    ///
+    /// If it would take away the last owner.
+    pub(crate) fn set(&mut self, username: &str, role: AdminRole) -> anyhow::Result<()> {
+        if role != AdminRole::Owner {
+            self.last_owner(username)?;
+        }
+
+        self.0.insert(username.to_string(), role);
+        Ok(())
+    }
+
+    /// # Errors
+    ///
+    /// If it would take away the last owner.
+    pub(crate) fn remove(&mut self, username: &str) -> anyhow::Result<()> {
+        self.last_owner(username)?;
+
+        self.0.remove(username);
+        Ok(())
+    }
+
+    fn last_owner(&self, username: &str) -> anyhow::Result<()> {
+        let owners = self
+            .0
+            .iter()
+            .filter(|(_, role)| **role == AdminRole::Owner)
+            .count();
+
+        if owners == 1 && self.0.get(username) == Some(&AdminRole::Owner) {
+            return Err(anyhow::Error::msg(format!("{username} is the last owner")));
+        }
+
+        Ok(())
+    }
+}
@@ -16,11 +16,22 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 // SPDX-FileCopyrightText: 2025 David Campbell <david@hnefatafl.org>
 
+use lettre::{SmtpTransport, transport::smtp::authentication::Credentials};
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub(crate) struct Smtp {
     pub username: String,
     pub password: String,
     pub service: String,
 }
+
+impl Smtp {
+    pub(crate) fn transport(&self) -> anyhow::Result<SmtpTransport> {
+        let credentials = Credentials::new(self.username.clone(), self.password.clone());
+
+        Ok(SmtpTransport::relay(&self.service)?
+            .credentials(credentials)
+            .build())
+    }
+}
@@ -20,10 +20,22 @@
 #![cfg(test)]
 
 use crate::Server as ServerFull;
+use crate::archive::{Archive, ArchiveFilter, CACHED};
+use crate::audit::{AuditEntry, AuditLog};
+use crate::bans::Network;
+use crate::bug_reports::BugReport;
+use crate::events::{Event, Every};
+use crate::reports::{Report, Reports};
 
+use anyhow::Context;
 use argon2::{PasswordHash, PasswordVerifier};
 
+use hnefatafl_copenhagen::ARCHIVED_GAMES_PAGE;
 use hnefatafl_copenhagen::accounts::{Account, Accounts};
+use hnefatafl_copenhagen::game::Game;
+use hnefatafl_copenhagen::play::Plays;
+use hnefatafl_copenhagen::time::DAY;
+use hnefatafl_copenhagen::tournament::Group;
 use jiff::ToSpan;
 
 use super::*;
@@ -45,6 +57,8 @@ impl Server {
                 .stderr(Stdio::null())
                 .arg("--skip-the-data-file")
                 .arg("--skip-advertising-updates")
+                .arg("--proof-of-work-bits")
+                .arg("8")
                 .spawn()?
         } else {
             std::process::Command::new("./target/debug/hnefatafl-server-full")
@@ -52,6 +66,8 @@ impl Server {
                 .stderr(Stdio::null())
                 .arg("--skip-the-data-file")
                 .arg("--skip-advertising-updates")
+                .arg("--proof-of-work-bits")
+                .arg("8")
                 .spawn()?
         };
 
@@ -114,10 +130,10 @@ fn server_full() -> anyhow::Result<()> {
     let mut socket_1 = TcpStream::connect(ADDRESS)?;
     let mut reader_1 = BufReader::new(socket_1.try_clone()?);
 
-    socket_1.write_all(format!("{VERSION_ID} create_account player-1\n").as_bytes())?;
-    reader_1.read_line(&mut buf)?;
-    assert_eq!(buf, "= login\n");
-    buf.clear();
+    assert_eq!(
+        create_account_tcp(&mut socket_1, &mut reader_1, "player-1")?,
+        "= login\n"
+    );
 
     socket_1.write_all(b"change_password\n")?;
     reader_1.read_line(&mut buf)?;
@@ -132,6 +148,9 @@ fn server_full() -> anyhow::Result<()> {
             milliseconds_left: 900_000,
         }),
         board_size: 11,
+        established_only: false,
+        zen: false,
+        rule_set: RuleSet::default(),
     };
     let new_game = serde_json::ser::to_string(&new_game)?;
 
@@ -143,10 +162,10 @@ fn server_full() -> anyhow::Result<()> {
     let mut socket_2 = TcpStream::connect(ADDRESS)?;
     let mut reader_2 = BufReader::new(socket_2.try_clone()?);
 
-    socket_2.write_all(format!("{VERSION_ID} create_account player-2\n").as_bytes())?;
-    reader_2.read_line(&mut buf)?;
-    assert_eq!(buf, "= login\n");
-    buf.clear();
+    assert_eq!(
+        create_account_tcp(&mut socket_2, &mut reader_2, "player-2")?,
+        "= login\n"
+    );
 
     socket_2.write_all(b"join_game_pending 0\n")?;
     reader_2.read_line(&mut buf)?;
@@ -173,8 +192,9 @@ fn server_full() -> anyhow::Result<()> {
     );
     buf.clear();
 
+    let checksum = Game::default().checksum();
     reader_1.read_line(&mut buf)?;
-    assert_eq!(buf, "game 0 generate_move attacker\n");
+    assert_eq!(buf, format!("game 0 generate_move attacker {checksum}\n"));
     buf.clear();
 
     socket_1.write_all(b"game 0 play attacker resigns _\n")?;
@@ -182,9 +202,8 @@ fn server_full() -> anyhow::Result<()> {
     assert_eq!(buf, "= game_over 0 defender_wins\n");
     buf.clear();
 
-    // Fixme: Why is the '_' missing?
     reader_2.read_line(&mut buf)?;
-    assert_eq!(buf, "game 0 play attacker resigns \n");
+    assert_eq!(buf, format!("game 0 play attacker resigns _ {checksum}\n"));
     buf.clear();
 
     reader_2.read_line(&mut buf)?;
@@ -238,6 +257,30 @@ fn many_clients() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Creates the account, solving the proof of work the server asks for first.
+fn create_account_tcp(
+    socket: &mut TcpStream,
+    reader: &mut BufReader<TcpStream>,
+    username: &str,
+) -> anyhow::Result<String> {
+    let mut buf = String::new();
+    socket.write_all(format!("{VERSION_ID} create_account {username}\n").as_bytes())?;
+    reader.read_line(&mut buf)?;
+
+    let challenge: Vec<_> = buf.split_ascii_whitespace().collect();
+    let ["?", "create_account", "proof_of_work", nonce, bits] = challenge.as_slice() else {
+        return Err(anyhow::Error::msg(format!("unexpected reply: {buf}")));
+    };
+    let solution = proof_of_work::solve(nonce, bits.parse()?);
+
+    socket.write_all(format!("{VERSION_ID} proof_of_work {solution}\n").as_bytes())?;
+    socket.write_all(format!("{VERSION_ID} create_account {username}\n").as_bytes())?;
+
+    buf.clear();
+    reader.read_line(&mut buf)?;
+    Ok(buf)
+}
+
 fn create_account(server: &mut ServerFull, tx: Sender<String>) -> anyhow::Result<()> {
     if let Some((_, result, message)) =
         server.handle_messages_internal("0 david create_account PASSWORD", Some(tx))
@@ -264,6 +307,68 @@ fn login(server: &mut ServerFull, tx: Sender<String>, password: &str) -> anyhow:
     }
 }
 
+/// A server with the users logged in on connections 1, 2, and so on, and what
+/// each of them gets sent. david gets the role if there is one.
+fn logged_in<const N: usize>(
+    usernames: [&str; N],
+    role: Option<AdminRole>,
+) -> (ServerFull, [mpsc::Receiver<String>; N]) {
+    let mut server = ServerFull::default();
+    if let Some(role) = role {
+        server.roles.0.insert("david".to_string(), role);
+    }
+
+    let mut index = 0;
+    let receivers = usernames.map(|username| {
+        index += 1;
+        let (tx, rx) = mpsc::channel();
+        server.accounts.0.insert(
+            username.to_string(),
+            Account {
+                logged_in: Some(index),
+                ..Account::default()
+            },
+        );
+        server.clients.insert(index, tx);
+        rx
+    });
+
+    (server, receivers)
+}
+
+/// A server where david plays the attackers against abby in game 0, and what
+/// each of them gets sent.
+fn game_started(
+    time_settings: TimeSettings,
+) -> anyhow::Result<(ServerFull, mpsc::Receiver<String>, mpsc::Receiver<String>)> {
+    let mut server = ServerFull {
+        skip_the_data_files: true,
+        ..ServerFull::default()
+    };
+
+    let (tx, david_rx) = mpsc::channel();
+    create_account(&mut server, tx)?;
+
+    let (tx, abby_rx) = mpsc::channel();
+    server.handle_messages_internal("1 abby create_account PASSWORD", Some(tx));
+
+    let new_game = NewGame {
+        role: Role::Attacker,
+        rated: false,
+        time_settings,
+        board_size: 11,
+        established_only: false,
+        zen: false,
+        rule_set: RuleSet::default(),
+    };
+    let new_game = serde_json::ser::to_string(&new_game)?;
+    server.handle_messages_internal(&format!("0 david new_game {new_game}"), None);
+    server.handle_messages_internal("1 abby join_game_pending 0", None);
+    server.handle_messages_internal("0 david join_game 0", None);
+
+    Ok((server, david_rx, abby_rx))
+}
+
 #[test]
 fn admin() -> anyhow::Result<()> {
     let mut server = ServerFull {
@@ -279,7 +384,7 @@ fn admin() -> anyhow::Result<()> {
             .is_none()
     );
 
-    server.admins.insert("david".to_string());
+    server.roles.0.insert("david".to_string(), AdminRole::Owner);
     assert!(
         server
             .handle_messages_internal("0 david admin", None)
@@ -305,7 +410,10 @@ fn admin_tournament() -> anyhow::Result<()> {
             .is_none()
     );
 
-    server.admins_tournament.insert("david".to_string());
+    server
+        .roles
+        .0
+        .insert("david".to_string(), AdminRole::TournamentDirector);
     assert!(
         server
             .handle_messages_internal("0 david admin_tournament", None)
@@ -337,6 +445,414 @@ fn archived_games() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn exports() -> anyhow::Result<()> {
+    let (mut server, _receivers) = logged_in(["david", "alice"], Some(AdminRole::Owner));
+    let alice = server
+        .accounts
+        .0
+        .get_mut("alice")
+        .context("expected alice")?;
+    alice.wins = 1;
+    alice.rated_games = 1;
+
+    let (_, result, message) = server
+        .handle_messages_internal("2 alice export_ratings csv", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert_eq!(
+        message,
+        "export_ratings csv rank,username,rating,deviation,wins,losses,draws,rated_games\\n\
+         1,alice,1500.0,350.0,1,0,0,1"
+    );
+
+    let (_, result, message) = server
+        .handle_messages_internal("2 alice export_results json player alice", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert_eq!(message, "export_results json []");
+
+    let (_, result, _) = server
+        .handle_messages_internal("2 alice export_standings json", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+
+    let (_, result, _) = server
+        .handle_messages_internal("2 alice export_ratings xlsx", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+
+    assert!(
+        server
+            .handle_messages_internal("2 alice export_accounts json", None)
+            .is_none()
+    );
+
+    let (_, result, message) = server
+        .handle_messages_internal("1 david export_accounts json", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    let accounts: serde_json::Value =
+        serde_json::from_str(message.trim_start_matches("export_accounts json "))?;
+    assert_eq!(accounts.as_array().map(Vec::len), Some(2));
+
+    Ok(())
+}
+
+fn archived_game(id: Id) -> ArchivedGame {
+    ArchivedGame {
+        id,
+        attacker: "david".to_string(),
+        attacker_rating: Rating::default(),
+        defender: "abby".to_string(),
+        defender_rating: Rating::default(),
+        rated: Rated::default(),
+        plays: Plays::default(),
+        status: Status::Ongoing,
+        messages: VecDeque::new(),
+        board_size: BoardSize::_11,
+        analysis: None,
+        lag: Vec::new(),
+        tags: Tags::default(),
+        rule_set: RuleSet::default(),
+    }
+}
+
+#[test]
+fn archive() -> anyhow::Result<()> {
+    let mut path = std::env::temp_dir();
+    path.push(format!("hnefatafl-archive-{}.ron", rand::random::<u64>()));
+
+    let mut archive = Archive::load(&path)?;
+    for id in 0..=CACHED {
+        archive.push(archived_game(id as Id))?;
+    }
+
+    // The id gets read off the start of the line.
+    assert!(fs::read_to_string(&path)?.starts_with("(id:0,"));
+
+    // The first game is no longer in memory, so it comes from the file.
+    let mut first = archive.get(0)?.context("expected the archived game")?;
+    assert_eq!(first.attacker, "david");
+
+    first.status = Status::AttackerWins;
+    archive.push(first)?;
+    assert!(archive.get(Id::MAX)?.is_none());
+
+    let mut archive = Archive::load(&path)?;
+    assert_eq!(
+        archive
+            .get(0)?
+            .context("expected the archived game")?
+            .status,
+        Status::AttackerWins
+    );
+
+    // Only the last page of games gets sent.
+    let games = archive.games(&ArchiveFilter::default())?;
+    assert_eq!(games.len(), ARCHIVED_GAMES_PAGE);
+    assert_eq!(
+        games.first().context("expected a game")?.id,
+        (CACHED + 1 - ARCHIVED_GAMES_PAGE) as Id
+    );
+    assert_eq!(games.last().context("expected a game")?.id, CACHED as Id);
+
+    let filter = ArchiveFilter::new(&["ids", "0", "0"])?;
+    let games = archive.games(&filter)?;
+    assert_eq!(
+        games.first().context("expected a game")?.status,
+        Status::AttackerWins
+    );
+
+    let filter = ArchiveFilter::new(&["ids", "0", "9"])?;
+    assert_eq!(archive.games(&filter)?.len(), 10);
+
+    let filter = ArchiveFilter::new(&["player", "abby", "ids", "5", "5"])?;
+    let games = archive.games(&filter)?;
+    assert_eq!(games.len(), 1);
+    assert_eq!(games.first().context("expected a game")?.id, 5);
+
+    let filter = ArchiveFilter::new(&["player", "brenda"])?;
+    assert!(archive.games(&filter)?.is_empty());
+    assert!(ArchiveFilter::new(&["ids", "0"]).is_err());
+
+    // The player's games are indexed when the archive loads.
+    let games = archive.player_games("abby", 3)?;
+    assert_eq!(
+        games.iter().map(|game| game.id).collect::<Vec<_>>(),
+        [CACHED as Id - 2, CACHED as Id - 1, CACHED as Id]
+    );
+    assert!(archive.player_games("brenda", 3)?.is_empty());
+
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn archive_prune() -> anyhow::Result<()> {
+    let mut path = std::env::temp_dir();
+    path.push(format!("hnefatafl-archive-{}.ron", rand::random::<u64>()));
+
+    let mut archive = Archive::load(&path)?;
+    archive.push(archived_game(0))?;
+    archive.push(ArchivedGame {
+        attacker: "carl".to_string(),
+        defender: "bob".to_string(),
+        ..archived_game(1)
+    })?;
+    archive.push(archived_game(2))?;
+    archive.push(ArchivedGame {
+        status: Status::AttackerWins,
+        ..archived_game(2)
+    })?;
+
+    // Game 0 is no longer one of the last games of david nor abby.
+    assert_eq!(archive.prune(1)?, 1);
+    assert_eq!(archive.prune(1)?, 0);
+    assert_eq!(fs::read_to_string(&path)?.lines().count(), 2);
+
+    let mut archive = Archive::load(&path)?;
+    assert!(archive.get(0)?.is_none());
+    assert_eq!(archive.player_games("bob", 3)?.len(), 1);
+    assert_eq!(
+        archive
+            .get(2)?
+            .context("expected the archived game")?
+            .status,
+        Status::AttackerWins
+    );
+
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn rated_games_counted() -> anyhow::Result<()> {
+    let (mut server, _receivers) = logged_in(["david", "abby"], None);
+    server.archive.push(archived_game(0))?;
+    server.archive.push(ArchivedGame {
+        rated: Rated::No,
+        ..archived_game(1)
+    })?;
+
+    server.migrate()?;
+    for username in ["david", "abby"] {
+        let account = server
+            .accounts
+            .0
+            .get(username)
+            .context("expected an account")?;
+        assert_eq!(account.rated_games, 1);
+    }
+
+    // The games only get counted once.
+    server.archive.push(archived_game(2))?;
+    server.migrate()?;
+    let account = server
+        .accounts
+        .0
+        .get("david")
+        .context("expected an account")?;
+    assert_eq!(account.rated_games, 1);
+
+    Ok(())
+}
+
+#[test]
+fn game_tags() -> anyhow::Result<()> {
+    let (mut server, _receivers) = logged_in(["david", "brenda"], None);
+    server.archive.push(archived_game(7))?;
+    server.archive.push(archived_game(8))?;
+
+    let (_, result, message) = server
+        .handle_messages_internal(
+            r#"1 david game_tags 7 (event:"Spring Open",labels:["sharp"])"#,
+            None,
+        )
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert_eq!(message, "game_tags 7");
+
+    let (_, result, _) = server
+        .handle_messages_internal(r#"2 brenda game_tags 8 (event:"Spring Open")"#, None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+
+    let (_, result, _) = server
+        .handle_messages_internal("1 david game_tags 9 ()", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+
+    let tags = &server
+        .archive
+        .get(7)?
+        .context("expected the archived game")?
+        .tags;
+    assert_eq!(tags.event, "Spring Open");
+    assert_eq!(tags.to_string(), "Spring Open, sharp");
+
+    let games = server
+        .archive
+        .games(&ArchiveFilter::new(&["tag", "SHARP"])?)?;
+    assert_eq!(games.len(), 1);
+    assert_eq!(games.first().context("expected a game")?.id, 7);
+
+    let games = server
+        .archive
+        .games(&ArchiveFilter::new(&["tag", "spring", "open"])?)?;
+    assert_eq!(games.len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn settings() -> anyhow::Result<()> {
+    let (mut server, _receivers) = logged_in(["david"], None);
+
+    let (_, result, _) = server
+        .handle_messages_internal(
+            r#"1 david settings ({"theme":(changed:"2026-10-14T12:00:00Z",value:"Dark")})"#,
+            None,
+        )
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+
+    // An older change loses, a new setting is kept.
+    let (_, result, message) = server
+        .handle_messages_internal(
+            r#"1 david settings ({"theme":(changed:"2026-10-13T12:00:00Z",value:"Light"),"locale":(changed:"2026-10-13T12:00:00Z",value:"German")})"#,
+            None,
+        )
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+
+    let settings: Settings = ron::from_str(message.trim_start_matches("settings "))?;
+    assert_eq!(settings.get("theme"), Some("Dark"));
+    assert_eq!(settings.get("locale"), Some("German"));
+    assert_eq!(
+        server
+            .accounts
+            .0
+            .get("david")
+            .context("expected the account david")?
+            .settings,
+        settings
+    );
+
+    let (_, result, _) = server
+        .handle_messages_internal("1 david settings dark", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+
+    let (_, result, _) = server
+        .handle_messages_internal(
+            r#"1 david settings ({"password":(changed:"2026-10-14T12:00:00Z",value:"A")})"#,
+            None,
+        )
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+
+    let long = "A".repeat(hnefatafl_copenhagen::settings::VALUE_LENGTH + 1);
+    let (_, result, _) = server
+        .handle_messages_internal(
+            &format!(
+                r#"1 david settings ({{"theme":(changed:"2026-10-15T12:00:00Z",value:"{long}")}})"#
+            ),
+            None,
+        )
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(
+        server
+            .accounts
+            .0
+            .get("david")
+            .context("expected the account david")?
+            .settings
+            .get("theme"),
+        Some("Dark")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn status() -> anyhow::Result<()> {
+    let mut server = ServerFull {
+        ..ServerFull::default()
+    };
+
+    let (tx, _rx) = mpsc::channel();
+    create_account(&mut server, tx)?;
+
+    let (tx, rx) = mpsc::channel();
+    assert!(
+        server
+            .handle_messages_internal("0 _ status", Some(tx))
+            .is_none()
+    );
+
+    let status = rx.recv()?;
+    let status: ServerStatus = serde_json::from_str(
+        status
+            .strip_prefix("= status ")
+            .ok_or_else(|| anyhow::Error::msg("expected: = status"))?,
+    )?;
+
+    assert_eq!(status.version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(status.players_online, 1);
+    assert_eq!(status.tournament, None);
+
+    Ok(())
+}
+
+#[test]
+fn ratings() -> anyhow::Result<()> {
+    let mut server = ServerFull::default();
+
+    let (tx, _rx) = mpsc::channel();
+    create_account(&mut server, tx)?;
+
+    let (tx, _rx) = mpsc::channel();
+    server.handle_messages_internal("1 abby create_account PASSWORD", Some(tx));
+
+    let (_, result, message) = server
+        .handle_messages_internal("0 david ratings david nobody", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    let ratings: BTreeMap<String, Rating> = serde_json::from_str(
+        message
+            .strip_prefix("ratings ")
+            .context("expected: ratings")?,
+    )?;
+    assert_eq!(ratings.len(), 1);
+    assert_eq!(ratings.get("david"), Some(&Rating::default()));
+
+    let (_, result, _) = server
+        .handle_messages_internal("0 david ratings david abby nobody", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+
+    server
+        .accounts
+        .0
+        .get_mut("abby")
+        .context("expected the account abby")?
+        .kid_safe = true;
+
+    let (_, _, message) = server
+        .handle_messages_internal("0 david ratings david abby", None)
+        .context("didn't get a response")?;
+    let ratings: BTreeMap<String, Rating> = serde_json::from_str(
+        message
+            .strip_prefix("ratings ")
+            .context("expected: ratings")?,
+    )?;
+    assert!(!ratings.contains_key("abby"));
+
+    Ok(())
+}
+
 #[test]
 fn change_password() -> anyhow::Result<()> {
     let mut server = ServerFull {
@@ -383,3 +899,3381 @@ fn check_update_rd() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn check_rating_period() -> anyhow::Result<()> {
+    let mut server = ServerFull {
+        ..ServerFull::default()
+    };
+
+    let (tx, _rx) = mpsc::channel();
+    create_account(&mut server, tx)?;
+
+    let account = server
+        .accounts
+        .0
+        .get_mut("david")
+        .context("expected the account david")?;
+    let category = RatingCategory::new(RuleSet::default(), BoardSize::_11, &TimeSettings::UnTimed);
+    account.rating_period.push(
+        category,
+        &Rating::default(),
+        &Rating::default(),
+        Outcome::Win,
+    );
+    assert_eq!(account.rating.rating, 1_500.0);
+
+    assert!(!server.check_rating_period());
+    server.ran_rating_period.0 = Timestamp::now() - RATING_PERIOD_MICRO_SECONDS.microseconds();
+    assert!(server.check_rating_period());
+
+    let account = server
+        .accounts
+        .0
+        .get("david")
+        .context("expected the account david")?;
+    assert_eq!(account.rating.rating.round(), 1_662.0);
+    assert_eq!(account.ratings.get(&category).rating.round(), 1_662.0);
+    assert!(account.rating_period.0.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn established_only() -> anyhow::Result<()> {
+    let mut server = ServerFull {
+        ..ServerFull::default()
+    };
+
+    let (tx, _rx) = mpsc::channel();
+    create_account(&mut server, tx)?;
+
+    let (tx, _rx) = mpsc::channel();
+    server.handle_messages_internal("1 abby create_account PASSWORD", Some(tx));
+
+    let new_game = NewGame {
+        role: Role::Attacker,
+        rated: true,
+        time_settings: TimeSettings::UnTimed,
+        board_size: 11,
+        established_only: true,
+        zen: false,
+        rule_set: RuleSet::default(),
+    };
+    let new_game = serde_json::ser::to_string(&new_game)?;
+
+    let option = server.handle_messages_internal(&format!("0 david new_game {new_game}"), None);
+    assert!(option.is_some());
+    if let Some((_, result, message)) = option {
+        assert_eq!(Ok(()), result);
+        assert_eq!(message, "new_game 0");
+    }
+
+    let option = server.handle_messages_internal("1 abby join_game_pending 0", None);
+    assert!(option.is_some());
+    if let Some((_, result, message)) = option {
+        assert_eq!(Err(InvalidMove::Other), result);
+        assert_eq!(
+            message,
+            "join_game_pending the game requires an established rating"
+        );
+    }
+
+    // Only the rating for 11x11 untimed games counts.
+    if let Some(account) = server.accounts.0.get_mut("abby") {
+        account.rating.rd = 50.0;
+        account
+            .ratings
+            .get_mut(RatingCategory::new(
+                RuleSet::default(),
+                BoardSize::_13,
+                &TimeSettings::UnTimed,
+            ))
+            .rd = 50.0;
+    }
+
+    let option = server.handle_messages_internal("1 abby join_game_pending 0", None);
+    assert!(option.is_some());
+    if let Some((_, result, _)) = option {
+        assert_eq!(Err(InvalidMove::Other), result);
+    }
+
+    if let Some(account) = server.accounts.0.get_mut("abby") {
+        account
+            .ratings
+            .get_mut(RatingCategory::new(
+                RuleSet::default(),
+                BoardSize::_11,
+                &TimeSettings::UnTimed,
+            ))
+            .rd = 50.0;
+    }
+
+    let option = server.handle_messages_internal("1 abby join_game_pending 0", None);
+    assert!(option.is_some());
+    if let Some((_, result, message)) = option {
+        assert_eq!(Ok(()), result);
+        assert_eq!(message, "join_game_pending 0");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn zen() -> anyhow::Result<()> {
+    let mut server = ServerFull {
+        ..ServerFull::default()
+    };
+
+    let (tx, _rx) = mpsc::channel();
+    create_account(&mut server, tx)?;
+
+    for rated in [true, false] {
+        let new_game = NewGame {
+            role: Role::Attacker,
+            rated,
+            time_settings: TimeSettings::UnTimed,
+            board_size: 11,
+            established_only: false,
+            zen: true,
+            rule_set: RuleSet::default(),
+        };
+        let new_game = serde_json::ser::to_string(&new_game)?;
+
+        let (_, result, _) = server
+            .handle_messages_internal(&format!("0 david new_game {new_game}"), None)
+            .context("didn't get a response")?;
+        assert_eq!(Ok(()), result);
+    }
+
+    assert!(
+        server
+            .games_light
+            .0
+            .get(&0)
+            .context("expected the game")?
+            .zen
+    );
+    assert!(
+        !server
+            .games_light
+            .0
+            .get(&1)
+            .context("expected the game")?
+            .zen
+    );
+
+    // Zen mode stays on when the server saves the game and loads it again.
+    let (tx, _rx) = mpsc::channel();
+    server.handle_messages_internal("1 abby create_account PASSWORD", Some(tx));
+    server.handle_messages_internal("1 abby join_game_pending 0", None);
+    server.handle_messages_internal("0 david join_game 0", None);
+
+    let games =
+        ServerGameSerialized::from_postcard(&postcard::to_allocvec(&server.active_games())?)?;
+    let game = games.first().context("expected the game")?;
+    assert!(ServerGameLight::from(game).zen);
+
+    Ok(())
+}
+
+#[test]
+fn provisional_pool() -> anyhow::Result<()> {
+    let mut server = ServerFull {
+        ..ServerFull::default()
+    };
+
+    let (tx, _rx) = mpsc::channel();
+    create_account(&mut server, tx)?;
+
+    let (tx, _rx) = mpsc::channel();
+    server.handle_messages_internal("1 abby create_account PASSWORD", Some(tx));
+    if let Some(account) = server.accounts.0.get_mut("abby") {
+        account.rating.rd = 50.0;
+    }
+
+    let new_game = NewGame {
+        role: Role::Attacker,
+        rated: true,
+        time_settings: TimeSettings::UnTimed,
+        board_size: 11,
+        established_only: false,
+        zen: false,
+        rule_set: RuleSet::default(),
+    };
+    let new_game = serde_json::ser::to_string(&new_game)?;
+    server.handle_messages_internal(&format!("0 david new_game {new_game}"), None);
+
+    let (_, result, message) = server
+        .handle_messages_internal("1 abby join_game_pending 0", None)
+        .context("didn't get a response")?;
+    assert_eq!(Err(InvalidMove::Other), result);
+    assert_eq!(
+        message,
+        "join_game_pending new players only play rated games against mentors and each other"
+    );
+
+    let (_, result, message) = server
+        .handle_messages_internal("1 abby mentor true", None)
+        .context("didn't get a response")?;
+    assert_eq!(Ok(()), result);
+    assert_eq!(message, "mentor true");
+
+    let (_, result, message) = server
+        .handle_messages_internal("1 abby join_game_pending 0", None)
+        .context("didn't get a response")?;
+    assert_eq!(Ok(()), result);
+    assert_eq!(message, "join_game_pending 0");
+
+    Ok(())
+}
+
+#[test]
+fn kid_safe() -> anyhow::Result<()> {
+    let (mut server, [_david_rx, abby_rx, kid_rx]) =
+        logged_in(["david", "abby", "kid"], Some(AdminRole::Owner));
+
+    let (_, result, message) = server
+        .handle_messages_internal("3 kid kid_safe true", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert_eq!(message, "kid_safe kid true");
+
+    let (_, result, _) = server
+        .handle_messages_internal("3 kid kid_safe false", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert!(
+        server
+            .handle_messages_internal("3 kid kid_safe_set kid false", None)
+            .is_none()
+    );
+
+    server.handle_messages_internal("3 kid text hi there", None);
+    assert!(kid_rx.try_recv().is_err());
+    server.handle_messages_internal("3 kid text Good luck!", None);
+    assert_eq!(
+        kid_rx.recv().map(|text| text.contains("Good luck!")),
+        Ok(true)
+    );
+    abby_rx.recv()?;
+
+    server.handle_messages_internal("2 abby text hi kid", None);
+    assert_eq!(kid_rx.recv().map(|text| text.contains('…')), Ok(true));
+    abby_rx.recv()?;
+
+    let (_, result, _) = server
+        .handle_messages_internal("2 abby dm kid Hello!", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+
+    let (_, result, message) = server
+        .handle_messages_internal("1 david kid_safe_friends kid abby", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert_eq!(message, "kid_safe_friends kid true abby");
+
+    let (_, result, _) = server
+        .handle_messages_internal("2 abby dm kid Hello!", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert_eq!(
+        kid_rx.recv().map(|text| text.starts_with("= dm abby")),
+        Ok(true)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn recurring_events() -> anyhow::Result<()> {
+    let (mut server, _receivers) =
+        logged_in(["david", "abby"], Some(AdminRole::TournamentDirector));
+    server
+        .clubs
+        .0
+        .insert("tafl".to_string(), Club::new("tafl", "abby"));
+
+    let now = Timestamp::now();
+    let mut event = Event {
+        name: "casual arena".to_string(),
+        owner: String::new(),
+        every: Every::Day,
+        time: now
+            .checked_add(SignedDuration::from_hours(1))?
+            .to_zoned(jiff::tz::TimeZone::UTC)
+            .time(),
+        time_zone: "UTC".to_string(),
+        minutes: 60,
+        board_size: BoardSize::_11,
+        time_setting: TimeSettings::UnTimed,
+        rated: Rated::No,
+        club: None,
+    };
+
+    let (_, result, _) = server
+        .handle_messages_internal(
+            &format!("2 abby event_set {}", serde_json::to_string(&event)?),
+            None,
+        )
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+
+    event.club = Some("tafl".to_string());
+    let (_, result, _) = server
+        .handle_messages_internal(
+            &format!("2 abby event_set {}", serde_json::to_string(&event)?),
+            None,
+        )
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert_eq!(
+        server
+            .events
+            .0
+            .get("casual arena")
+            .map(|event| &event.owner),
+        Some(&"abby".to_string())
+    );
+
+    let start = event.next(now)?;
+    server.events_run(now);
+    assert_eq!(server.tournament.event.as_deref(), Some("casual arena"));
+    assert_eq!(server.tournament.date, Some(start));
+    assert_eq!(server.tournament.requirements.club.as_deref(), Some("tafl"));
+    assert!(server.tournament.tournament.is_none());
+
+    server.events_run(start);
+    assert!(
+        server
+            .tournament
+            .tournament
+            .as_ref()
+            .is_some_and(|tournament| tournament.arena.is_some())
+    );
+
+    let (_, result, message) = server
+        .handle_messages_internal("1 david event_delete casual arena", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert_eq!(message, "events []");
+
+    Ok(())
+}
+
+#[test]
+fn recurring_events_owner() -> anyhow::Result<()> {
+    let (mut server, _receivers) = logged_in(["david", "abby", "bob"], None);
+    server
+        .clubs
+        .0
+        .insert("tafl".to_string(), Club::new("tafl", "abby"));
+
+    let event = Event {
+        name: "casual arena".to_string(),
+        owner: String::new(),
+        every: Every::Day,
+        time: jiff::civil::time(12, 0, 0, 0),
+        time_zone: "UTC".to_string(),
+        minutes: 60,
+        board_size: BoardSize::_11,
+        time_setting: TimeSettings::UnTimed,
+        rated: Rated::No,
+        club: Some("tafl".to_string()),
+    };
+    let event_set = |username, event: &Event| -> anyhow::Result<String> {
+        Ok(format!(
+            "{} {username} event_set {}",
+            if username == "abby" { 2 } else { 3 },
+            serde_json::to_string(event)?
+        ))
+    };
+
+    let (_, result, _) = server
+        .handle_messages_internal(&event_set("abby", &event)?, None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+
+    // abby hands the club over to bob, but may still change the event.
+    if let Some(club) = server.clubs.0.get_mut("tafl") {
+        club.owner = "bob".to_string();
+    }
+
+    let mut event_changed = event.clone();
+    event_changed.minutes = 30;
+    let (_, result, _) = server
+        .handle_messages_internal(&event_set("abby", &event_changed)?, None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+
+    event_changed.club = Some("other".to_string());
+    let (_, result, _) = server
+        .handle_messages_internal(&event_set("abby", &event_changed)?, None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+
+    let (_, result, _) = server
+        .handle_messages_internal("1 david event_delete casual arena", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    let (_, result, message) = server
+        .handle_messages_internal("2 abby event_delete casual arena", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert_eq!(message, "events []");
+
+    Ok(())
+}
+
+#[test]
+fn simul() -> anyhow::Result<()> {
+    let mut server = ServerFull {
+        ..ServerFull::default()
+    };
+
+    let (tx, _rx) = mpsc::channel();
+    create_account(&mut server, tx)?;
+
+    let (tx, _rx) = mpsc::channel();
+    server.handle_messages_internal("1 abby create_account PASSWORD", Some(tx));
+
+    let (tx, _rx) = mpsc::channel();
+    server.handle_messages_internal("2 bob create_account PASSWORD", Some(tx));
+
+    let new_game = NewGame {
+        role: Role::Defender,
+        rated: false,
+        time_settings: TimeSettings::UnTimed,
+        board_size: 11,
+        established_only: false,
+        zen: false,
+        rule_set: RuleSet::default(),
+    };
+    let new_game = serde_json::ser::to_string(&new_game)?;
+
+    let option = server.handle_messages_internal(&format!("0 david simul_new {new_game}"), None);
+    assert!(option.is_some());
+    if let Some((_, result, message)) = option {
+        assert_eq!(Ok(()), result);
+        assert_eq!(message, "simul_new 0");
+    }
+
+    let option = server.handle_messages_internal("0 david simul_start 0", None);
+    assert!(option.is_some());
+    if let Some((_, result, message)) = option {
+        assert_eq!(Err(InvalidMove::Other), result);
+        assert_eq!(message, "simul_start nobody joined the simul");
+    }
+
+    assert!(
+        server
+            .handle_messages_internal("1 abby simul_join 0", None)
+            .is_none()
+    );
+    assert!(
+        server
+            .handle_messages_internal("2 bob simul_join 0", None)
+            .is_none()
+    );
+
+    let option = server.handle_messages_internal("1 abby simul_start 0", None);
+    assert!(option.is_some());
+    if let Some((_, result, message)) = option {
+        assert_eq!(Err(InvalidMove::Other), result);
+        assert_eq!(message, "simul_start you are not the host");
+    }
+
+    let option = server.handle_messages_internal("0 david simul_start 0", None);
+    assert!(option.is_some());
+    if let Some((_, result, message)) = option {
+        assert_eq!(Ok(()), result);
+        assert_eq!(message, "simul_start 0");
+    }
+
+    let simul = server.simuls.0.get(&0).context("expected the simul")?;
+    assert_eq!(simul.games, vec![0, 1]);
+
+    let game = server.games_light.0.get(&0).context("expected the game")?;
+    assert_eq!(game.attacker.as_deref(), Some("abby"));
+    assert_eq!(game.defender.as_deref(), Some("david"));
+    assert_eq!(game.rated, Rated::No);
+    assert!(game.challenge_accepted);
+
+    let option = server.handle_messages_internal("2 bob simul_join 0", None);
+    assert!(option.is_some());
+    if let Some((_, result, message)) = option {
+        assert_eq!(Err(InvalidMove::Other), result);
+        assert_eq!(message, "simul_join the simul already started");
+    }
+
+    // The simul and its games outlast a restart of the server.
+    let server: ServerFull = ron::from_str(&server.users_ron()?)?;
+    let simul = server.simuls.0.get(&0).context("expected the simul")?;
+    assert_eq!(simul.games, vec![0, 1]);
+    assert_eq!(server.simul_id, 1);
+
+    Ok(())
+}
+
+#[test]
+fn community_game() -> anyhow::Result<()> {
+    let mut server = ServerFull {
+        ..ServerFull::default()
+    };
+
+    let (tx, _rx) = mpsc::channel();
+    create_account(&mut server, tx)?;
+
+    let (tx, _rx) = mpsc::channel();
+    server.handle_messages_internal("1 abby create_account PASSWORD", Some(tx));
+
+    let option = server.handle_messages_internal("0 david community_game_new defender 11", None);
+    assert!(option.is_some());
+    if let Some((_, result, message)) = option {
+        assert_eq!(Ok(()), result);
+        assert_eq!(message, "community_game_new 0");
+    }
+
+    let option = server.handle_messages_internal("1 abby community_vote 0 d1 d3", None);
+    assert!(option.is_some());
+    if let Some((_, result, message)) = option {
+        assert_eq!(Err(InvalidMove::Other), result);
+        assert_eq!(message, "community_vote only spectators may vote");
+    }
+
+    server.handle_messages_internal("1 abby watch_game 0", None);
+
+    let option = server.handle_messages_internal("1 abby community_vote 0 d1 d11", None);
+    assert!(option.is_some());
+    if let Some((_, result, _)) = option {
+        assert!(result.is_err());
+    }
+
+    assert!(
+        server
+            .handle_messages_internal("1 abby community_vote 0 d1 d3", None)
+            .is_none()
+    );
+    assert_eq!(server.votes.get(&0).map(|votes| votes.votes.len()), Some(1));
+
+    if let Some(votes) = server.votes.get_mut(&0) {
+        votes.closes = 0;
+    }
+
+    let (tx, rx) = mpsc::channel();
+    server.tx = Some(tx);
+    server.handle_messages_internal("0 server display_server", None);
+
+    let (message, _) = rx.recv()?;
+    assert_eq!(message, "0 (community) game 0 play attacker d1 d3");
+    assert!(server.votes.is_empty());
+
+    server.handle_messages_internal(&message, None);
+    let game = server.games.0.get(&0).context("expected the game")?;
+    assert_eq!(game.game.turn, Role::Defender);
+
+    Ok(())
+}
+
+#[test]
+fn community_game_ai() -> anyhow::Result<()> {
+    let mut server = ServerFull {
+        ..ServerFull::default()
+    };
+
+    let (tx, _rx) = mpsc::channel();
+    create_account(&mut server, tx)?;
+
+    let (_, result, message) = server
+        .handle_messages_internal("0 david community_game_new attacker 11 ai", None)
+        .context("didn't get a response")?;
+    assert_eq!(Ok(()), result);
+    assert_eq!(message, "community_game_new 0 ai");
+
+    let game = server.games.0.get(&0).context("expected the game")?;
+    assert_eq!(game.attacker, "(ai)");
+    assert_eq!(game.defender, "(community)");
+
+    let (tx, rx) = mpsc::channel();
+    server.tx = Some(tx);
+    server.handle_messages_internal("0 server display_server", None);
+    server.handle_messages_internal("0 server display_server", None);
+    assert_eq!(server.ai_thinking.len(), 1);
+
+    let (message, _) = rx.recv_timeout(Duration::from_secs(120))?;
+    assert!(message.starts_with("0 (ai) game 0 play attacker "));
+    assert!(rx.try_recv().is_err());
+
+    server.handle_messages_internal(&message, None);
+    let game = server.games.0.get(&0).context("expected the game")?;
+    assert_eq!(game.game.turn, Role::Defender);
+
+    server.handle_messages_internal("0 server display_server", None);
+    assert!(server.ai_thinking.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn quiz() -> anyhow::Result<()> {
+    let mut server = ServerFull {
+        ..ServerFull::default()
+    };
+
+    let (tx, _rx) = mpsc::channel();
+    create_account(&mut server, tx)?;
+
+    let (tx, _rx) = mpsc::channel();
+    server.handle_messages_internal("1 abby create_account PASSWORD", Some(tx));
+    server.handle_messages_internal("0 david community_game_new defender 11", None);
+
+    let (_, result, message) = server
+        .handle_messages_internal("0 david quiz_guess 0 d1 d3", None)
+        .context("didn't get a response")?;
+    assert_eq!(Err(InvalidMove::Other), result);
+    assert_eq!(message, "quiz_guess only spectators may guess");
+
+    server.handle_messages_internal("1 abby watch_game 0", None);
+
+    let (_, result, message) = server
+        .handle_messages_internal("1 abby quiz_guess 0 d1 d3", None)
+        .context("didn't get a response")?;
+    assert_eq!(Ok(()), result);
+    assert_eq!(message, "quiz_guess 0 d1 d3");
+
+    server.handle_messages_internal("0 (community) game 0 play attacker d1 d3", None);
+    server.handle_messages_internal("1 abby quiz_guess 0 e5 c5", None);
+    server.handle_messages_internal("0 david game 0 play defender f4 c4", None);
+
+    let score = server
+        .quizzes
+        .get(&0)
+        .context("expected the quiz")?
+        .scores
+        .get("abby")
+        .context("expected a score")?;
+    assert_eq!((score.right, score.guessed), (1, 2));
+
+    Ok(())
+}
+
+#[test]
+fn kibitz() -> anyhow::Result<()> {
+    let mut server = ServerFull {
+        ..ServerFull::default()
+    };
+
+    let (tx, _rx) = mpsc::channel();
+    create_account(&mut server, tx)?;
+
+    let (tx, rx) = mpsc::channel();
+    server.handle_messages_internal("1 abby create_account PASSWORD", Some(tx));
+    server.handle_messages_internal("0 david community_game_new defender 11", None);
+
+    let kibitz = ron::ser::to_string(&Kibitz {
+        username: "someone else".to_string(),
+        timestamp: Timestamp::default(),
+        play: 0,
+        score: 0.5,
+        best_play: Plae::try_from(vec!["play", "attacker", "d1", "d3"])?,
+    })?;
+
+    let option = server.handle_messages_internal(&format!("0 david kibitz 0 {kibitz}"), None);
+    assert!(option.is_some());
+    if let Some((_, result, message)) = option {
+        assert_eq!(Err(InvalidMove::Other), result);
+        assert_eq!(message, "kibitz players may not kibitz");
+    }
+
+    server.handle_messages_internal("1 abby watch_game 0", None);
+    let _messages: Vec<_> = rx.try_iter().collect();
+
+    let option = server.handle_messages_internal("1 abby kibitz 0 (", None);
+    assert!(option.is_some());
+    if let Some((_, result, message)) = option {
+        assert_eq!(Err(InvalidMove::Other), result);
+        assert_eq!(message, "kibitz invalid evaluation");
+    }
+
+    assert!(
+        server
+            .handle_messages_internal(&format!("1 abby kibitz 0 {kibitz}"), None)
+            .is_none()
+    );
+
+    let message = rx.try_recv()?;
+    let kibitz = message
+        .strip_prefix("= kibitz 0 ")
+        .context("expected: kibitz")?;
+    let kibitz: Kibitz = ron::de::from_str(kibitz)?;
+    assert_eq!(kibitz.username, "abby");
+    assert!((kibitz.attacker_part() - 0.75).abs() < f32::EPSILON);
+
+    let (tx, _rx) = mpsc::channel();
+    server.handle_messages_internal("2 carl create_account PASSWORD", Some(tx));
+
+    let new_game = serde_json::ser::to_string(&NewGame {
+        role: Role::Attacker,
+        rated: true,
+        time_settings: TimeSettings::default(),
+        board_size: 11,
+        established_only: false,
+        zen: false,
+        rule_set: RuleSet::default(),
+    })?;
+    server.handle_messages_internal(&format!("0 david new_game {new_game}"), None);
+    server.handle_messages_internal("1 abby join_game_pending 1", None);
+    server.handle_messages_internal("0 david join_game 1", None);
+    server.handle_messages_internal("2 carl watch_game 1", None);
+
+    let kibitz = ron::ser::to_string(&Kibitz {
+        username: "carl".to_string(),
+        timestamp: Timestamp::default(),
+        play: 0,
+        score: 0.5,
+        best_play: Plae::try_from(vec!["play", "attacker", "d1", "d3"])?,
+    })?;
+
+    let option = server.handle_messages_internal(&format!("2 carl kibitz 1 {kibitz}"), None);
+    assert!(option.is_some());
+    if let Some((_, result, message)) = option {
+        assert_eq!(Err(InvalidMove::Other), result);
+        assert_eq!(message, "kibitz no one may kibitz during a rated game");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn backup_restore() -> anyhow::Result<()> {
+    let mut folder = std::env::temp_dir();
+    folder.push(format!("hnefatafl-backup-{}", rand::random::<u64>()));
+
+    let mut server = ServerFull::default();
+    let (tx, _rx) = mpsc::channel();
+    create_account(&mut server, tx)?;
+
+    let audit_log = format!(
+        "{}\n",
+        ron::to_string(&AuditEntry {
+            timestamp: Timestamp::from_second(0)?,
+            username: "david".to_string(),
+            address: None,
+            event: "login".to_string(),
+        })?
+    );
+
+    let backup = Backup {
+        users: server.users_ron()?,
+        archived_games: String::new(),
+        active_games: postcard::to_allocvec(&server.active_games())?,
+        audit_log: audit_log.clone(),
+        avatars: BTreeMap::from([("david".to_string(), vec![1, 2, 3])]),
+    };
+
+    let mut backups = folder.clone();
+    backups.push(BACKUPS_FOLDER);
+
+    let mut path = backups.clone();
+    for second in 0..3 {
+        path = backup.write(&backups, Timestamp::from_second(second)?, 2)?;
+    }
+    assert_eq!(fs::read_dir(&backups)?.count(), 2);
+
+    let restored = Backup::read(&path)?;
+    assert_eq!(restored, backup);
+
+    fs::create_dir_all(folder.join(AVATARS_FOLDER))?;
+    fs::write(folder.join(AVATARS_FOLDER).join("bob.png"), [4, 5, 6])?;
+    restored.restore(|file| folder.join(file))?;
+    let server: ServerFull = ron::from_str(&fs::read_to_string(folder.join(USERS_FILE))?)?;
+    assert!(server.accounts.0.contains_key("david"));
+    assert_eq!(fs::read_to_string(folder.join(AUDIT_LOG_FILE))?, audit_log);
+    assert_eq!(
+        Avatars::new(folder.join(AVATARS_FOLDER)).all()?,
+        backup.avatars
+    );
+
+    // Nothing gets left next to the data files.
+    for entry in fs::read_dir(&folder)? {
+        assert!(!entry?.path().to_string_lossy().ends_with(".restore"));
+    }
+
+    // A line the server is still adding gets left out.
+    fs::write(
+        folder.join(AUDIT_LOG_FILE),
+        format!("{audit_log}(timestamp:"),
+    )?;
+    let avatars = Avatars::new(folder.join(AVATARS_FOLDER));
+    let backup_new = Backup::new(String::new(), Vec::new(), &avatars, |file| {
+        folder.join(file)
+    })?;
+    assert_eq!(backup_new.audit_log, audit_log);
+
+    let mut bad = backup.clone();
+    bad.avatars = BTreeMap::from([("../david".to_string(), vec![1, 2, 3])]);
+    path = bad.write(&backups, Timestamp::from_second(3)?, 2)?;
+    assert!(Backup::read(&path).is_err());
+
+    fs::write(&path, "not a backup")?;
+    assert!(Backup::read(&path).is_err());
+
+    fs::remove_dir_all(folder)?;
+    Ok(())
+}
+
+#[test]
+fn check_data_files() -> anyhow::Result<()> {
+    let mut folder = std::env::temp_dir();
+    folder.push(format!("hnefatafl-check-{}", rand::random::<u64>()));
+    fs::create_dir_all(&folder)?;
+    let data_file = |file: &str| folder.join(file);
+
+    let mut server = ServerFull::default();
+    let (tx, _rx) = mpsc::channel();
+    create_account(&mut server, tx)?;
+    server
+        .roles
+        .0
+        .insert("gone".to_string(), AdminRole::Moderator);
+    fs::write(data_file(USERS_FILE), server.users_ron()?)?;
+
+    let mut other_players = archived_game(0);
+    other_players.attacker = "abby".to_string();
+    let archived_games = [
+        ron::ser::to_string(&archived_game(0))?,
+        "not a game".to_string(),
+        ron::ser::to_string(&other_players)?,
+        ron::ser::to_string(&archived_game(1))?,
+    ];
+    fs::write(
+        data_file(ARCHIVED_GAMES_FILE),
+        archived_games.join("\n") + "\n",
+    )?;
+
+    let active_game = |id| ServerGameSerialized {
+        id,
+        attacker: "david".to_string(),
+        defender: COMMUNITY.to_string(),
+        rated: Rated::default(),
+        game: Game::default(),
+        messages: VecDeque::new(),
+        timed: TimeSettings::default(),
+        rule_set: RuleSet::default(),
+        zen: false,
+    };
+    let mut no_account = active_game(3);
+    no_account.defender = "gone".to_string();
+    fs::write(
+        data_file(ACTIVE_GAMES_FILE),
+        postcard::to_allocvec(&vec![
+            active_game(1),
+            active_game(2),
+            active_game(2),
+            no_account,
+        ])?,
+    )?;
+
+    // The unparseable line, the reused id, the archived game, the duplicate
+    // game, the missing account, the role, and the next game id.
+    assert_eq!(check::check(data_file, false)?.len(), 7);
+    assert_eq!(check::check(data_file, true)?.len(), 7);
+    assert!(check::check(data_file, false)?.is_empty());
+
+    let server: ServerFull = ron::from_str(&fs::read_to_string(data_file(USERS_FILE))?)?;
+    assert_eq!(server.game_id, 3);
+    assert!(!server.roles.0.contains_key("gone"));
+    assert_eq!(
+        fs::read_to_string(data_file(ARCHIVED_GAMES_FILE))?
+            .lines()
+            .count(),
+        2
+    );
+
+    let archive = Archive::load(&data_file(ARCHIVED_GAMES_FILE))?;
+    assert_eq!(archive.games(&ArchiveFilter::default())?.len(), 2);
+
+    let quarantined = fs::read_dir(&folder)?
+        .filter(|entry| {
+            entry
+                .as_ref()
+                .is_ok_and(|entry| entry.file_name().to_string_lossy().contains(".quarantine-"))
+        })
+        .count();
+    assert_eq!(quarantined, 2);
+
+    fs::remove_dir_all(folder)?;
+    Ok(())
+}
+
+#[test]
+fn check_quarantines_only_the_corrupt_accounts() -> anyhow::Result<()> {
+    let mut folder = std::env::temp_dir();
+    folder.push(format!("hnefatafl-check-{}", rand::random::<u64>()));
+    fs::create_dir_all(&folder)?;
+    let data_file = |file: &str| folder.join(file);
+
+    let mut server = ServerFull::default();
+    let (tx, _rx) = mpsc::channel();
+    create_account(&mut server, tx)?;
+    let (tx, _rx) = mpsc::channel();
+    server.handle_messages_internal("1 abby create_account PASSWORD", Some(tx));
+
+    let users = server.users_ron()?.replacen(
+        "\"abby\": (\n",
+        "\"abby\": (\n            not an account\n",
+        1,
+    );
+    fs::write(data_file(USERS_FILE), users)?;
+
+    assert_eq!(
+        check::check(data_file, true)?,
+        [format!(
+            "{}: the account abby can't be parsed",
+            data_file(USERS_FILE).display()
+        )]
+    );
+    assert!(check::check(data_file, false)?.is_empty());
+
+    let server: ServerFull = ron::from_str(&fs::read_to_string(data_file(USERS_FILE))?)?;
+    assert!(server.accounts.0.contains_key("david"));
+    assert!(!server.accounts.0.contains_key("abby"));
+
+    let quarantined: Vec<_> = fs::read_dir(&folder)?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_name().to_string_lossy().contains(".quarantine-"))
+        .collect();
+    assert_eq!(quarantined.len(), 1);
+    assert!(fs::read_to_string(quarantined[0].path())?.contains("not an account"));
+
+    fs::remove_dir_all(folder)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn port_shared_for_a_handoff() -> anyhow::Result<()> {
+    let listener = handoff::bind(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 0), true, false)?;
+    let address = listener.local_addr()?;
+
+    assert!(handoff::bind(address, true, true).is_err());
+    assert!(handoff::bind(address, true, false).is_err());
+
+    let listeners = [(listener, address)];
+    handoff::share(&listeners)?;
+    assert!(handoff::bind(address, true, false).is_err());
+    let _new_server = handoff::bind(address, true, true)?;
+
+    Ok(())
+}
+
+#[test]
+fn resume_after_handoff() -> anyhow::Result<()> {
+    let mut server = ServerFull::default();
+    let (tx, _rx) = mpsc::channel();
+    create_account(&mut server, tx.clone())?;
+    server.handle_messages_internal("0 david logout", None);
+
+    server.resume(&Snapshot {
+        taken: Timestamp::now(),
+        sessions: vec![Session {
+            username: "david".to_string(),
+            address: None,
+        }],
+    });
+    assert!(server.reconnecting.contains_key("david"));
+
+    login(&mut server, tx, "PASSWORD")?;
+    assert!(server.reconnecting.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn config_file() -> anyhow::Result<()> {
+    let mut path = std::env::temp_dir();
+    path.push(format!("hnefatafl-config-{}.ron", rand::random::<u64>()));
+    fs::write(&path, "(port: Some(1), keep_texts: 3, message_length: 10)")?;
+
+    let args = Args::parse_from([
+        "hnefatafl-server-full",
+        "--config",
+        path.to_str().context("expected a UTF-8 path")?,
+        "--port",
+        "9",
+        "--bind",
+        "::1",
+        "--bind",
+        "0.0.0.0:7",
+    ]);
+    assert!(
+        Config::load(&Args::parse_from([
+            "hnefatafl-server-full",
+            "--bind",
+            "localhost"
+        ]))
+        .is_err()
+    );
+    let config = Config::load(&args)?;
+
+    assert_eq!(
+        config.listen_addresses()?,
+        vec![
+            SocketAddr::new(Ipv6Addr::LOCALHOST.into(), 9),
+            SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 7),
+        ]
+    );
+    assert_eq!(config.port, Some(9));
+    assert_eq!(config.keep_texts, 3);
+    assert_eq!(config.message_length, 10);
+    assert_eq!(config.backups_kept, Config::default().backups_kept);
+    assert_eq!(
+        config.needs_restart(&Config::default()),
+        vec!["bind", "port"]
+    );
+
+    let mut server = ServerFull {
+        config,
+        ..ServerFull::default()
+    };
+    assert_eq!(server.censor("a message that is too long"), "");
+
+    for _ in 0..5 {
+        server.text(0, "server", "hi", None);
+    }
+    assert_eq!(server.texts.len(), 3);
+
+    fs::write(&path, "(keep_texts: \"three\")")?;
+    assert!(Config::load(&args).is_err());
+
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn journal_replay() -> anyhow::Result<()> {
+    let mut path = std::env::temp_dir();
+    path.push(format!("hnefatafl-journal-{}.ron", rand::random::<u64>()));
+
+    // abby's account is in the data files the journal starts from.
+    let mut server = ServerFull::default();
+    server.accounts.0.insert(
+        "abby".to_string(),
+        Account {
+            password: hash_password("SECRET").context("failed to hash the password")?,
+            ..Account::default()
+        },
+    );
+    let start = Backup {
+        users: server.users_ron()?,
+        ..Backup::default()
+    };
+
+    let new_game = serde_json::to_string(&NewGame {
+        role: Role::Attacker,
+        rated: false,
+        time_settings: TimeSettings::UnTimed,
+        board_size: 11,
+        established_only: false,
+        zen: false,
+        rule_set: RuleSet::default(),
+    })?;
+
+    let mut journal = Journal::open(&path, &start)?;
+    journal.write("1 david create_account SECRET", true, false)?;
+    journal.write(&format!("1 david new_game {new_game}"), false, false)?;
+    journal.write("1 david game 0 play attacker d1 d3", false, false)?;
+    // Whether a login went through comes from handling it.
+    journal.write("2 abby login WRONG", true, false)?;
+    journal.write("3 abby login SECRET", true, true)?;
+    assert!(journal::is_login("3 abby login SECRET"));
+    assert!(!journal::is_login("3 abby logout"));
+
+    assert!(!fs::read_to_string(&path)?.contains("SECRET"));
+
+    let mut out = Vec::new();
+    journal::replay(&path, &Config::default(), &mut out)?;
+    let out = String::from_utf8(out)?;
+
+    assert!(out.contains("-> 1 david create_account PASSWORD\n1 <- = create_account\n"));
+    assert!(out.contains("1 <- = new_game 0\n"));
+    assert!(out.contains("1 <- ? game"));
+    assert!(out.contains("-> 2 abby login PASSWORD_WRONG\n2 <- ? login"));
+    assert!(out.contains("-> 3 abby login PASSWORD\n3 <- = login\n"));
+
+    fs::remove_file(journal::snapshot(&path))?;
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn email_templates() -> anyhow::Result<()> {
+    let template = EmailTemplate::AccountVerification {
+        username: "<david>".to_string(),
+        code: "c0de".to_string(),
+    };
+
+    assert_eq!(template.subject(Locale::English), "Account Verification");
+    assert!(template.text(Locale::English, None).starts_with(
+        "Dear <david>,\nyour email verification code is as follows: c0de\n\n-- \nHnefatafl Org\n"
+    ));
+
+    let html = template.html(Locale::English, None);
+    assert!(html.contains("<p>Dear &lt;david&gt;,<br>\nyour email verification code"));
+    assert!(!html.contains("<david>"));
+    assert!(
+        template
+            .html(Locale::Arabic, None)
+            .contains(r#"<html lang="ar" dir="rtl">"#)
+    );
+
+    let message = template.message(
+        lettre::Message::builder().to("david@hnefatafl.org".parse()?),
+        Locale::German,
+        None,
+    )?;
+    let message = String::from_utf8(message.formatted())?;
+    assert!(message.contains("multipart/alternative"));
+    assert!(message.contains("text/plain"));
+    assert!(message.contains("text/html"));
+
+    Ok(())
+}
+
+#[test]
+fn unsubscribe_links() -> anyhow::Result<()> {
+    let key = [7; unsubscribe::KEY_LENGTH];
+    let link = Link::new(&key, "dävid", EmailCategory::Announcements).context("expected a link")?;
+
+    assert!(link.verify(&key));
+    assert!(!link.verify(&[8; unsubscribe::KEY_LENGTH]));
+    assert!(Link::new(&[], "dävid", EmailCategory::Announcements).is_none());
+
+    let url = link.url("https://hnefatafl.org/unsubscribe");
+    assert!(url.starts_with(
+        "https://hnefatafl.org/unsubscribe?user=d%C3%A4vid&category=announcements&token="
+    ));
+    assert_eq!(
+        Link::parse(url.trim_start_matches("https://hnefatafl.org")),
+        Some(link.clone())
+    );
+    assert_eq!(
+        Link::parse("/unsubscribe?user=d%20avid&category=announcements&token=00"),
+        None
+    );
+
+    let link = Link {
+        category: EmailCategory::Tournaments,
+        ..link
+    };
+    assert!(!link.verify(&key));
+
+    Ok(())
+}
+
+#[test]
+fn email_preferences() -> anyhow::Result<()> {
+    let mut server = ServerFull {
+        unsubscribe_key: vec![7; unsubscribe::KEY_LENGTH],
+        ..ServerFull::default()
+    };
+
+    let (tx, _rx) = mpsc::channel();
+    create_account(&mut server, tx)?;
+
+    let (_, result, message) = server
+        .handle_messages_internal("0 david email_preferences tournaments false", None)
+        .context("didn't get a response")?;
+    assert_eq!(result, Ok(()));
+    assert_eq!(
+        message,
+        r#"email_preferences {"announcements":true,"tournaments":false,"turn_notifications":true}"#
+    );
+
+    let (tx, _rx) = mpsc::channel();
+    let (_, result, _) = server
+        .handle_messages_internal("0 david unsubscribe announcements 00", Some(tx))
+        .context("didn't get a response")?;
+    assert_eq!(result, Err(InvalidMove::Other));
+
+    let link = Link::new(
+        &server.unsubscribe_key,
+        "david",
+        EmailCategory::Announcements,
+    )
+    .context("expected a link")?;
+    let (tx, _rx) = mpsc::channel();
+    let (_, result, message) = server
+        .handle_messages_internal(
+            &format!("0 david unsubscribe announcements {}", link.token),
+            Some(tx),
+        )
+        .context("didn't get a response")?;
+    assert_eq!(result, Ok(()));
+    assert_eq!(message, "unsubscribe");
+
+    let preferences = &server
+        .accounts
+        .0
+        .get("david")
+        .context("expected the account david")?
+        .email_preferences;
+    assert!(!preferences.announcements);
+    assert!(!preferences.tournaments);
+    assert!(preferences.turn_notifications);
+
+    Ok(())
+}
+
+#[test]
+fn bridge_text() -> anyhow::Result<()> {
+    let (irc_tx, irc_rx) = mpsc::channel();
+    let (matrix_tx, matrix_rx) = mpsc::channel();
+    let (client_tx, client_rx) = mpsc::channel();
+
+    let mut server = ServerFull {
+        bridges: vec![
+            Bridge {
+                name: "IRC",
+                tx: irc_tx,
+            },
+            Bridge {
+                name: "Matrix",
+                tx: matrix_tx,
+            },
+        ],
+        ..ServerFull::default()
+    };
+    server.clients.insert(1, client_tx);
+
+    server.handle_messages_internal("0 server bridge_text IRC alice hello there", None);
+    assert!(client_rx.recv()?.starts_with("= text"));
+    assert!(irc_rx.try_recv().is_err());
+
+    let message = matrix_rx.try_recv()?;
+    assert_eq!(message.username, "alice (IRC)");
+    assert_eq!(message.content, "hello there");
+
+    // Only the server can say it came from a bridge.
+    server.handle_messages_internal("1 david bridge_text IRC alice hi", None);
+    assert!(client_rx.try_recv().is_err());
+
+    server.handle_messages_internal("1 david text hi", None);
+    assert_eq!(irc_rx.try_recv()?.content, "hi");
+    assert_eq!(matrix_rx.try_recv()?.content, "hi");
+
+    Ok(())
+}
+
+#[test]
+fn irc() -> anyhow::Result<()> {
+    let listener = std::net::TcpListener::bind("localhost:0")?;
+    let bridge = IrcBridge {
+        address: listener.local_addr()?.to_string(),
+        nick: "hnefatafl".to_string(),
+        channel: "#hnefatafl".to_string(),
+    };
+
+    let irc = Irc::connect(&bridge)?;
+    let (stream, _) = listener.accept()?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut stream = stream;
+
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    assert_eq!(line, "NICK hnefatafl\r\n");
+    line.clear();
+    reader.read_line(&mut line)?;
+    assert!(line.starts_with("USER hnefatafl"));
+
+    stream.write_all(b":irc.example.org 001 hnefatafl :Welcome\r\n")?;
+    assert!(irc.receive()?.is_empty());
+    line.clear();
+    reader.read_line(&mut line)?;
+    assert_eq!(line, "JOIN #hnefatafl\r\n");
+
+    stream.write_all(b"PING :irc.example.org\r\n")?;
+    assert!(irc.receive()?.is_empty());
+    line.clear();
+    reader.read_line(&mut line)?;
+    assert_eq!(line, "PONG :irc.example.org\r\n");
+
+    stream.write_all(b":alice!alice@example.org PRIVMSG #hnefatafl :hello there\r\n")?;
+    assert_eq!(
+        irc.receive()?,
+        vec![("alice".to_string(), "hello there".to_string())]
+    );
+
+    stream.write_all(b":alice!alice@example.org PRIVMSG hnefatafl :hi\r\n")?;
+    assert!(irc.receive()?.is_empty());
+
+    irc.send(&Message {
+        username: "david".to_string(),
+        timestamp: Timestamp::now(),
+        content: "hello".to_string(),
+    })?;
+    line.clear();
+    reader.read_line(&mut line)?;
+    assert_eq!(line, "PRIVMSG #hnefatafl :<david> hello\r\n");
+
+    Ok(())
+}
+
+#[test]
+fn profanity_filter() -> anyhow::Result<()> {
+    let (mut server, [david_rx, alice_rx]) = logged_in(["david", "alice"], Some(AdminRole::Owner));
+    server
+        .accounts
+        .0
+        .get_mut("alice")
+        .context("expected alice")?
+        .show_profanity = true;
+
+    let (_, result, message) = server
+        .handle_messages_internal("1 david profanity_words en-US add Grimble snorf", None)
+        .context("didn't get a response")?;
+    assert_eq!(result, Ok(()));
+    assert_eq!(message, "profanity_words en-US grimble snorf");
+    assert!(
+        server
+            .handle_messages_internal("2 alice profanity_words en-US remove grimble", None)
+            .is_none()
+    );
+
+    server.handle_messages_internal("2 alice text what a GRIMBLE, grimbles", None);
+    assert!(david_rx.recv()?.contains("what a *******, grimbles"));
+    assert!(alice_rx.recv()?.contains("what a GRIMBLE, grimbles"));
+    assert_eq!(
+        server.texts.front().context("expected a message")?.content,
+        "what a GRIMBLE, grimbles"
+    );
+
+    server.handle_messages_internal("1 david texts", None);
+    assert!(david_rx.recv()?.contains("what a *******, grimbles"));
+
+    server.handle_messages_internal("1 david show_profanity true", None);
+    server.handle_messages_internal("1 david texts", None);
+    assert!(david_rx.recv()?.contains("what a GRIMBLE, grimbles"));
+
+    server.handle_messages_internal("1 david show_profanity false", None);
+    server.handle_messages_internal("1 david profanity_filter false", None);
+    server.handle_messages_internal("1 david texts", None);
+    assert!(david_rx.recv()?.contains("what a GRIMBLE, grimbles"));
+
+    Ok(())
+}
+
+#[test]
+fn slow_mode() -> anyhow::Result<()> {
+    let (tx, rx) = mpsc::channel();
+    let mut server = ServerFull {
+        chat_slow_mode: 60,
+        ..ServerFull::default()
+    };
+    server.clients.insert(1, tx);
+
+    server.handle_messages_internal("1 david text hello", None);
+    assert_eq!(rx.recv()?, "= slow_mode text 60");
+    assert!(rx.recv()?.starts_with("= text"));
+
+    server.handle_messages_internal("1 david text hello again", None);
+    assert!(rx.recv()?.starts_with("= slow_mode text "));
+    assert!(rx.try_recv().is_err());
+    assert_eq!(server.texts.len(), 1);
+
+    // The bridges don't wait.
+    server.handle_messages_internal("0 server bridge_text IRC alice hi", None);
+    assert!(rx.recv()?.starts_with("= text"));
+
+    let mut tournament = Tournament::default();
+    tournament
+        .tournament_games
+        .insert(5, Arc::new(Mutex::new(Group::default())));
+    server.tournament.tournament = Some(tournament);
+
+    assert!(!server.slow_mode_wait(1, "david", Some(5)));
+    assert_eq!(
+        rx.recv()?,
+        format!(
+            "= slow_mode 5 {}",
+            server.config.slow_mode_tournament_seconds
+        )
+    );
+    assert!(server.slow_mode_wait(1, "david", Some(5)));
+    assert!(!server.slow_mode_wait(1, "alice", Some(5)));
+    assert!(!server.slow_mode_wait(1, "david", Some(6)));
+
+    Ok(())
+}
+
+#[test]
+fn motd() -> anyhow::Result<()> {
+    let (mut server, [david_rx, alice_rx]) = logged_in(["david", "alice"], Some(AdminRole::Owner));
+
+    server.handle_messages_internal("2 alice motd", None);
+    assert_eq!(alice_rx.recv()?, "= motd");
+
+    assert!(
+        server
+            .handle_messages_internal("2 alice motd_set hi", None)
+            .is_none()
+    );
+    assert!(server.motd.current.is_none());
+
+    server.handle_messages_internal("1 david motd_set Welcome  to the arena!", None);
+    assert_eq!(david_rx.recv()?, "= motd Welcome to the arena!");
+    assert_eq!(alice_rx.recv()?, "= motd Welcome to the arena!");
+
+    server.handle_messages_internal("1 david motd_set Be kind.", None);
+    david_rx.recv()?;
+    assert_eq!(alice_rx.recv()?, "= motd Be kind.");
+
+    server.handle_messages_internal("1 david motd_clear", None);
+    david_rx.recv()?;
+    assert_eq!(alice_rx.recv()?, "= motd");
+    assert!(server.motd.current.is_none());
+
+    let (_, result, message) = server
+        .handle_messages_internal("1 david motd_history", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert!(message.starts_with("motd_history "));
+
+    let history: Vec<_> = server
+        .motd
+        .history
+        .iter()
+        .map(|entry| entry.text.as_str())
+        .collect();
+    assert_eq!(history, ["Welcome to the arena!", "Be kind."]);
+    assert!(
+        server
+            .motd
+            .history
+            .iter()
+            .all(|entry| entry.cleared.is_some())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn roles() -> anyhow::Result<()> {
+    let (mut server, [david_rx, _alice_rx]) = logged_in(["david", "alice"], Some(AdminRole::Owner));
+
+    assert!(
+        server
+            .handle_messages_internal("2 alice role_set alice owner", None)
+            .is_none()
+    );
+
+    let (_, result, _) = server
+        .handle_messages_internal("1 david role_set alice moderator", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert_eq!(server.roles.0.get("alice"), Some(&AdminRole::Moderator));
+
+    server.handle_messages_internal("2 alice motd_set Be kind.", None);
+    assert!(server.motd.current.is_some());
+    david_rx.recv()?;
+
+    assert!(
+        server
+            .handle_messages_internal("2 alice tournament_delete", None)
+            .is_none()
+    );
+    assert!(
+        server
+            .handle_messages_internal("2 alice role_remove david", None)
+            .is_none()
+    );
+
+    let (_, result, _) = server
+        .handle_messages_internal("1 david role_set bob moderator", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+
+    let (_, result, _) = server
+        .handle_messages_internal("1 david role_remove david", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+
+    let (_, result, _) = server
+        .handle_messages_internal("1 david role_remove alice", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+
+    let (_, result, message) = server
+        .handle_messages_internal("1 david roles", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert_eq!(message, r#"roles {"david":Owner}"#);
+
+    Ok(())
+}
+
+#[test]
+fn audit_log_load() -> anyhow::Result<()> {
+    let mut path = std::env::temp_dir();
+    path.push(format!("hnefatafl-audit-log-{}.ron", rand::random::<u64>()));
+
+    let entry = |username: &str| -> anyhow::Result<String> {
+        Ok(ron::to_string(&AuditEntry {
+            timestamp: Timestamp::from_second(0)?,
+            username: username.to_string(),
+            address: None,
+            event: "login".to_string(),
+        })?)
+    };
+
+    fs::write(
+        &path,
+        format!("{}\n(timestamp:\n{}\n", entry("david")?, entry("alice")?),
+    )?;
+
+    let audit_log = AuditLog::load(&path)?;
+    let usernames: Vec<_> = audit_log
+        .digest(None)
+        .into_iter()
+        .map(|entry| entry.username.as_str())
+        .collect();
+    assert_eq!(usernames, ["david", "alice"]);
+
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn audit_log() -> anyhow::Result<()> {
+    let (david_tx, david_rx) = mpsc::channel();
+    let (alice_tx, _alice_rx) = mpsc::channel();
+
+    let mut server = ServerFull::default();
+    server.roles.0.insert("david".to_string(), AdminRole::Owner);
+    server.handle_messages_internal("0 server client_connected 1 192.0.2.1", None);
+    server.handle_messages_internal("1 david create_account password", Some(david_tx));
+    server.handle_messages_internal("0 server client_connected 2 192.0.2.2", None);
+    server.handle_messages_internal("2 alice create_account password", Some(alice_tx));
+
+    server.handle_messages_internal("2 alice change_password secret", None);
+    server.handle_messages_internal("2 alice audit_log", None);
+    server.handle_messages_internal("1 david motd_set Hello!", None);
+    david_rx.recv()?;
+    server.handle_messages_internal("0 server client_disconnected 2", None);
+    assert!(!server.addresses.contains_key(&2));
+
+    let events: Vec<_> = server
+        .audit_log
+        .digest(Some("alice"))
+        .into_iter()
+        .map(|entry| (entry.address, entry.event.as_str()))
+        .collect();
+    let alice = Some(IpAddr::from([192, 0, 2, 2]));
+    assert_eq!(
+        events,
+        [
+            (alice, "create_account"),
+            (alice, "change_password"),
+            (alice, "audit_log not_permitted"),
+        ]
+    );
+
+    let (_, result, message) = server
+        .handle_messages_internal("1 david audit_log david", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+
+    let entries: Vec<AuditEntry> = ron::from_str(
+        message
+            .strip_prefix("audit_log ")
+            .context("expected: audit_log")?,
+    )?;
+    let events: Vec<_> = entries.iter().map(|entry| entry.event.as_str()).collect();
+    assert_eq!(
+        events,
+        ["create_account", "motd_set Hello!", "audit_log david"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn ban_roles() -> anyhow::Result<()> {
+    let (mut server, _receivers) = logged_in(
+        ["david", "alice", "bob", "carl"],
+        Some(AdminRole::Moderator),
+    );
+    server.roles.0.insert("alice".to_string(), AdminRole::Owner);
+    server
+        .roles
+        .0
+        .insert("bob".to_string(), AdminRole::Moderator);
+    server
+        .roles
+        .0
+        .insert("carl".to_string(), AdminRole::TournamentDirector);
+
+    for target in ["alice", "bob", "david"] {
+        let (_, result, _) = server
+            .handle_messages_internal(&format!("1 david ban {target} forever"), None)
+            .context("didn't get a response")?;
+        assert_eq!(result, Err(InvalidMove::Other));
+    }
+    assert!(server.bans.0.is_empty());
+
+    let (_, result, _) = server
+        .handle_messages_internal("1 david ban carl forever", None)
+        .context("didn't get a response")?;
+    assert_eq!(result, Ok(()));
+
+    let (_, result, _) = server
+        .handle_messages_internal("2 alice ban bob forever", None)
+        .context("didn't get a response")?;
+    assert_eq!(result, Ok(()));
+    assert_eq!(server.bans.0.len(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn bans() -> anyhow::Result<()> {
+    let network = Network::from_str("192.0.2.0/24")?;
+    assert!(network.contains(IpAddr::from([192, 0, 2, 7])));
+    assert!(network.contains(IpAddr::from_str("::ffff:192.0.2.7")?));
+    assert!(!network.contains(IpAddr::from([192, 0, 3, 7])));
+    assert!(Network::from_str("2001:db8::/32")?.contains(IpAddr::from_str("2001:db8:1::1")?));
+    assert!(Network::from_str("192.0.2.0/33").is_err());
+
+    let (alice_tx, alice_rx) = mpsc::channel();
+    let (mut server, [david_rx]) = logged_in(["david"], Some(AdminRole::Owner));
+
+    let (_, result, _) = server
+        .handle_messages_internal("1 david ban alice 7d spamming the chat", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    let (_, result, _) = server
+        .handle_messages_internal("1 david ban 198.51.100.0/24 forever", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    let (_, result, _) = server
+        .handle_messages_internal("1 david ban bob sometime", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(server.bans.0.len(), 2);
+
+    server.handle_messages_internal("0 server client_connected 2 192.0.2.1", None);
+    server.handle_messages_internal("2 alice login password", Some(alice_tx.clone()));
+    let message = alice_rx.recv()?;
+    assert!(message.starts_with("? login banned "));
+    assert!(message.ends_with(" spamming the chat"));
+
+    server.handle_messages_internal("0 server client_connected 3 198.51.100.9", Some(alice_tx));
+    assert_eq!(alice_rx.recv()?, "? login banned forever");
+
+    let (_, result, _) = server
+        .handle_messages_internal("1 david unban alice", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert!(server.bans.find(Some("alice"), None).is_none());
+
+    let events: Vec<_> = server
+        .audit_log
+        .digest(None)
+        .into_iter()
+        .map(|entry| entry.event.as_str())
+        .collect();
+    assert!(events.contains(&"ban alice 7d spamming the chat"));
+    assert!(events.contains(&"login banned"));
+    assert!(events.contains(&"connect banned"));
+    assert!(events.contains(&"unban alice"));
+
+    assert!(david_rx.try_recv().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn reports() -> anyhow::Result<()> {
+    let (mut server, _receivers) = logged_in(["david", "bob"], Some(AdminRole::Moderator));
+    server
+        .accounts
+        .0
+        .insert("alice".to_string(), Account::default());
+
+    let (_, result, _) = server
+        .handle_messages_internal("2 bob report alice rude", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    let (_, result, _) = server
+        .handle_messages_internal("2 bob report alice spamming the chat", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    let (_, result, message) = server
+        .handle_messages_internal("2 bob report bob", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(message, "report you can't report yourself");
+    let (_, result, _) = server
+        .handle_messages_internal("2 bob report carol", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert!(
+        server
+            .handle_messages_internal("2 bob reports", None)
+            .is_none()
+    );
+
+    let (_, result, message) = server
+        .handle_messages_internal("1 david reports", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    let reports: Vec<Report> = ron::from_str(
+        message
+            .strip_prefix("reports ")
+            .context("expected: reports")?,
+    )?;
+    let reported: Vec<_> = reports
+        .iter()
+        .map(|report| {
+            (
+                report.username.as_str(),
+                report.reporter.as_str(),
+                report.reason.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(reported, [("alice", "bob", "spamming the chat")]);
+
+    let (_, result, _) = server
+        .handle_messages_internal("1 david reports_clear alice", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert!(server.reports.0.iter().all(|report| report.resolved));
+    let (_, result, _) = server
+        .handle_messages_internal("1 david reports_clear alice", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn reports_limits() -> anyhow::Result<()> {
+    let (mut server, _receivers) = logged_in(["david", "bob"], Some(AdminRole::Moderator));
+    for n in 0..=10 {
+        server
+            .accounts
+            .0
+            .insert(format!("player-{n}"), Account::default());
+    }
+
+    for n in 0..10 {
+        let (_, result, _) = server
+            .handle_messages_internal(&format!("2 bob report player-{n} rude"), None)
+            .context("didn't get a response")?;
+        assert!(result.is_ok());
+    }
+    let (_, result, message) = server
+        .handle_messages_internal("2 bob report player-10 rude", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(message, "report wait a while before sending another report");
+
+    let mut reports = Reports::default();
+    for n in 0..1_000 {
+        reports.add(&format!("player-{n}"), &format!("reporter-{n}"), "rude");
+    }
+    assert!(reports.clear("player-500"));
+    reports.add("player-1000", "reporter-1000", "rude");
+    assert_eq!(reports.0.len(), 1_000);
+    assert_eq!(reports.0[0].username, "player-0");
+    assert!(
+        !reports
+            .0
+            .iter()
+            .any(|report| report.username == "player-500")
+    );
+
+    reports.add("player-1001", "reporter-1001", "rude");
+    assert_eq!(reports.0[0].username, "player-1");
+
+    Ok(())
+}
+
+#[test]
+fn bug_reports() -> anyhow::Result<()> {
+    let (mut server, _receivers) = logged_in(["david", "bob"], Some(AdminRole::Owner));
+
+    let (_, result, _) = server
+        .handle_messages_internal(
+            r#"2 bob bug_report "hnefatafl-client 6.2.2\nthe board froze""#,
+            None,
+        )
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    let (_, result, message) = server
+        .handle_messages_internal(r#"2 bob bug_report "the board froze again""#, None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(
+        message,
+        "bug_report wait a while before sending another bug report"
+    );
+    let (_, result, message) = server
+        .handle_messages_internal(r#"2 bob bug_report " ""#, None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(message, "bug_report the bug report is empty");
+    let (_, result, _) = server
+        .handle_messages_internal("2 bob bug_report not RON", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert!(
+        server
+            .handle_messages_internal("2 bob bug_reports", None)
+            .is_none()
+    );
+
+    let (_, result, message) = server
+        .handle_messages_internal("1 david bug_reports", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    let bug_reports: Vec<BugReport> = ron::from_str(
+        message
+            .strip_prefix("bug_reports ")
+            .context("expected: bug_reports")?,
+    )?;
+    let reported: Vec<_> = bug_reports
+        .iter()
+        .map(|bug_report| (bug_report.reporter.as_str(), bug_report.report.as_str()))
+        .collect();
+    assert_eq!(
+        reported,
+        [("bob", "hnefatafl-client 6.2.2\nthe board froze")]
+    );
+
+    let (_, result, _) = server
+        .handle_messages_internal("1 david bug_reports_clear", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert!(server.bug_reports.reports.is_empty());
+    let (_, result, _) = server
+        .handle_messages_internal("1 david bug_reports_clear", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn avatars() -> anyhow::Result<()> {
+    use base64::{Engine, engine::general_purpose::STANDARD};
+    use image::{ImageFormat, RgbaImage};
+    use std::io::Cursor;
+
+    let png = |side| -> anyhow::Result<String> {
+        let mut png = Cursor::new(Vec::new());
+        RgbaImage::new(side, side).write_to(&mut png, ImageFormat::Png)?;
+        Ok(STANDARD.encode(png.into_inner()))
+    };
+
+    let (mut server, [david_rx, bob_rx]) = logged_in(["david", "bob"], Some(AdminRole::Moderator));
+
+    let (_, result, message) = server
+        .handle_messages_internal("1 david avatar bob", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert_eq!(message, "avatar bob");
+
+    let avatar = png(32)?;
+    let (_, result, _) = server
+        .handle_messages_internal(&format!("2 bob avatar_set {avatar}"), None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert_eq!(david_rx.try_recv()?, "= avatar_changed bob");
+    assert!(bob_rx.try_recv().is_err());
+
+    let (_, result, message) = server
+        .handle_messages_internal("1 david avatar bob", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert_eq!(message, format!("avatar bob {avatar}"));
+
+    for avatar in [png(65)?, "not-a-png".to_string(), STANDARD.encode("GIF89a")] {
+        let (_, result, _) = server
+            .handle_messages_internal(&format!("2 bob avatar_set {avatar}"), None)
+            .context("didn't get a response")?;
+        assert!(result.is_err());
+    }
+
+    assert!(
+        server
+            .handle_messages_internal("2 bob avatar_clear david", None)
+            .is_none()
+    );
+    let (_, result, _) = server
+        .handle_messages_internal("1 david avatar_clear bob", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    let (_, result, message) = server
+        .handle_messages_internal("1 david avatar bob", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert_eq!(message, "avatar bob");
+    let (_, result, _) = server
+        .handle_messages_internal("1 david avatar_clear bob", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+
+    server
+        .accounts
+        .0
+        .get_mut("bob")
+        .context("expected the account bob")?
+        .kid_safe = true;
+    let (_, result, _) = server
+        .handle_messages_internal(&format!("2 bob avatar_set {avatar}"), None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+
+    let mut folder = std::env::temp_dir();
+    folder.push(format!("hnefatafl-avatars-{}", rand::random::<u64>()));
+    let outside = folder.join("outside.png");
+    server.avatars = Avatars::new(folder.join(AVATARS_FOLDER));
+    fs::create_dir_all(&folder)?;
+    fs::write(&outside, STANDARD.decode(&avatar)?)?;
+
+    let outside = outside.to_str().context("expected a UTF-8 path")?;
+    let outside = outside.strip_suffix(".png").context("expected: .png")?;
+    for player in ["../outside", outside] {
+        assert!(server.avatars.get(player).is_err());
+        assert!(server.avatars.remove(player).is_err());
+
+        let (_, result, _) = server
+            .handle_messages_internal(&format!("1 david avatar {player}"), None)
+            .context("didn't get a response")?;
+        assert!(result.is_err());
+
+        let (_, result, _) = server
+            .handle_messages_internal(&format!("1 david avatar_clear {player}"), None)
+            .context("didn't get a response")?;
+        assert!(result.is_err());
+    }
+    assert!(folder.join("outside.png").exists());
+
+    fs::remove_dir_all(folder)?;
+    Ok(())
+}
+
+#[test]
+fn profile() -> anyhow::Result<()> {
+    let (mut server, _receivers) = logged_in(["david"], None);
+
+    for command in [
+        "1 david profile_set bio I like to play the defenders.",
+        "1 david profile_set country is",
+        "1 david profile_set role defender",
+    ] {
+        let (_, result, message) = server
+            .handle_messages_internal(command, None)
+            .context("didn't get a response")?;
+        assert!(result.is_ok());
+        assert_eq!(message, "profile_set");
+    }
+    for command in [
+        "1 david profile_set country iceland",
+        "1 david profile_set role king",
+        "1 david profile_set colour blue",
+    ] {
+        let (_, result, _) = server
+            .handle_messages_internal(command, None)
+            .context("didn't get a response")?;
+        assert!(result.is_err());
+    }
+
+    let (_, result, message) = server
+        .handle_messages_internal("1 david profile david", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    let profile: PlayerProfile = ron::from_str(
+        message
+            .strip_prefix("profile ")
+            .context("expected: profile")?,
+    )?;
+    assert_eq!(profile.profile.bio, "I like to play the defenders.");
+    assert_eq!(profile.profile.country.as_deref(), Some("IS"));
+    assert_eq!(profile.profile.role, Some(Role::Defender));
+    assert_eq!(profile.ratings.len(), 1);
+
+    let (_, result, _) = server
+        .handle_messages_internal("1 david profile_set role none", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert_eq!(
+        server
+            .accounts
+            .0
+            .get("david")
+            .context("expected the account david")?
+            .profile
+            .role,
+        None
+    );
+
+    server
+        .accounts
+        .0
+        .get_mut("david")
+        .context("expected the account david")?
+        .kid_safe = true;
+    let (_, result, _) = server
+        .handle_messages_internal("1 david profile_set bio hello", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+
+    let (_, result, _) = server
+        .handle_messages_internal("1 david profile carol", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn scout() -> anyhow::Result<()> {
+    use hnefatafl_copenhagen::play::{Play, PlayRecordTimed};
+    use hnefatafl_copenhagen::time::TimeLeft;
+
+    let record = |play: Option<(Role, &str, &str)>, attacker, defender| {
+        anyhow::Ok(PlayRecordTimed {
+            play: match play {
+                Some((role, from, to)) => Some(Plae::Play(Play {
+                    role,
+                    from: Vertex::from_str(from)?,
+                    to: Vertex::from_str(to)?,
+                })),
+                None => None,
+            },
+            attacker_time: TimeLeft {
+                milliseconds_left: attacker,
+            },
+            defender_time: TimeLeft {
+                milliseconds_left: defender,
+            },
+        })
+    };
+
+    let mut won = archived_game(0);
+    won.status = Status::AttackerWins;
+    won.plays = Plays::PlayRecordsTimed(vec![
+        record(None, 60_000, 60_000)?,
+        record(Some((Role::Attacker, "d1", "d3")), 58_000, 60_000)?,
+        record(Some((Role::Defender, "f4", "c4")), 58_000, 55_000)?,
+        record(Some((Role::Attacker, "h1", "h3")), 54_000, 55_000)?,
+    ]);
+
+    let mut lost = archived_game(1);
+    lost.status = Status::AttackerWins;
+    lost.attacker = "abby".to_string();
+    lost.defender = "david".to_string();
+    lost.plays = Plays::PlayRecordsTimed(vec![
+        record(None, 60_000, 60_000)?,
+        record(Some((Role::Attacker, "d1", "d3")), 59_000, 60_000)?,
+        record(Some((Role::Defender, "f4", "c4")), 59_000, 57_000)?,
+    ]);
+
+    let scout = Scout::new("david", Rating::default(), None, &[won, lost]);
+    assert_eq!(scout.ratings.len(), 3);
+    assert_eq!(
+        scout.attacker,
+        hnefatafl_copenhagen::scout::Record { games: 1, wins: 1 }
+    );
+    assert_eq!(scout.defender.games, 1);
+    assert_eq!(scout.defender.wins, 0);
+    assert_eq!(scout.move_milliseconds, Some(3_000));
+
+    let first_moves: Vec<_> = scout
+        .first_moves
+        .iter()
+        .map(|first| (first.role, first.play.as_str(), first.games))
+        .collect();
+    assert_eq!(
+        first_moves,
+        [(Role::Attacker, "d1-d3", 1), (Role::Defender, "f4-c4", 1)]
+    );
+
+    let mut server = ServerFull {
+        skip_the_data_files: true,
+        ..ServerFull::default()
+    };
+    let (tx, _rx) = mpsc::channel();
+    create_account(&mut server, tx)?;
+
+    let (_, result, message) = server
+        .handle_messages_internal("0 david scout david", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    let scout: Scout = ron::from_str(message.strip_prefix("scout ").context("expected: scout")?)?;
+    assert_eq!(scout.username, "david");
+
+    let (_, result, message) = server
+        .handle_messages_internal("0 david scout carol", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(message, "scout no such player");
+
+    Ok(())
+}
+
+#[test]
+fn puzzle() -> anyhow::Result<()> {
+    use hnefatafl_copenhagen::puzzle::{Puzzle, Theme};
+
+    let mut server = ServerFull {
+        skip_the_data_files: true,
+        ..ServerFull::default()
+    };
+    let (tx, _rx) = mpsc::channel();
+    create_account(&mut server, tx)?;
+
+    let (_, result, message) = server
+        .handle_messages_internal("0 david puzzle", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(message, "puzzle there are no puzzles yet");
+
+    let found = Puzzle {
+        game_id: 7,
+        board_size: BoardSize::_11,
+        plays: Vec::new(),
+        solution: Plae::from_str_("d1-d3", &Role::Attacker)?,
+        theme: Theme::KingHunt,
+    };
+    let puzzles = ron::ser::to_string(&vec![found.clone(), found.clone()])?;
+    server.handle_messages_internal(&format!("0 server puzzles_found {puzzles}"), None);
+    assert_eq!(server.puzzles.0.len(), 1);
+
+    let (_, result, message) = server
+        .handle_messages_internal("0 david puzzle", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    let puzzle: Puzzle = ron::from_str(
+        message
+            .strip_prefix("puzzle ")
+            .context("expected: puzzle")?,
+    )?;
+    assert_eq!(puzzle, found);
+
+    Ok(())
+}
+
+#[test]
+fn daily_puzzle() -> anyhow::Result<()> {
+    use hnefatafl_copenhagen::puzzle::{Daily, Puzzle, Theme};
+
+    let mut server = ServerFull {
+        skip_the_data_files: true,
+        ..ServerFull::default()
+    };
+    let (tx, _rx) = mpsc::channel();
+    create_account(&mut server, tx)?;
+
+    let (_, result, message) = server
+        .handle_messages_internal("0 david daily_puzzle", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(message, "daily_puzzle there are no puzzles yet");
+
+    server.puzzles.add(vec![Puzzle {
+        game_id: 7,
+        board_size: BoardSize::_11,
+        plays: Vec::new(),
+        solution: Plae::from_str_("d1-d3", &Role::Attacker)?,
+        theme: Theme::KingHunt,
+    }]);
+
+    let (_, result, message) = server
+        .handle_messages_internal("0 david daily_puzzle", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    let daily: Daily = ron::from_str(
+        message
+            .strip_prefix("daily_puzzle ")
+            .context("expected: daily_puzzle")?,
+    )?;
+    assert_eq!(daily.position.role, Role::Attacker);
+    assert_eq!(daily.solution, None);
+    assert!(!daily.tried);
+    assert_eq!(daily.streak, 0);
+
+    let (_, _, message) = server
+        .handle_messages_internal("0 david daily_puzzle_solve d1-d3", None)
+        .context("didn't get a response")?;
+    assert_eq!(message, "daily_puzzle_solve true 1 d1-d3");
+
+    let (_, result, message) = server
+        .handle_messages_internal("0 david daily_puzzle_solve d1-d3", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(
+        message,
+        "daily_puzzle_solve you already tried today's puzzle"
+    );
+
+    let (_, _, message) = server
+        .handle_messages_internal("0 david daily_puzzle", None)
+        .context("didn't get a response")?;
+    let daily: Daily = ron::from_str(
+        message
+            .strip_prefix("daily_puzzle ")
+            .context("expected: daily_puzzle")?,
+    )?;
+    assert!(daily.tried);
+    assert_eq!(
+        daily.solution,
+        Some(Plae::from_str_("d1-d3", &Role::Attacker)?)
+    );
+    assert_eq!(daily.solvers, ["david"]);
+    assert_eq!(daily.streaks, [("david".to_string(), 1)]);
+
+    Ok(())
+}
+
+#[test]
+fn tournament_announcement() -> anyhow::Result<()> {
+    let mut server = ServerFull::default();
+    assert_eq!(server.announcement(), "= announcement");
+
+    let date: Timestamp = "2030-01-01T00:00:00Z".parse()?;
+    server.tournament.date = Some(date);
+    server.tournament.players.insert("david".to_string());
+
+    let announcement = server.announcement();
+    let json = announcement
+        .strip_prefix("= announcement ")
+        .context("expected: announcement")?;
+    let announcement: hnefatafl_copenhagen::tournament::Announcement = serde_json::from_str(json)?;
+    assert_eq!(announcement.date, date);
+    assert_eq!(announcement.players, 1);
+
+    server.tournament.tournament = Some(Tournament::default());
+    assert_eq!(server.announcement(), "= announcement");
+
+    Ok(())
+}
+
+#[test]
+fn tournament_rated() -> anyhow::Result<()> {
+    let mut server = ServerFull::default();
+    server.migrate()?;
+    server.tournament.players.insert("abby".to_string());
+    server.tournament.players.insert("david".to_string());
+
+    for username in &server.tournament.players {
+        server
+            .accounts
+            .0
+            .insert(username.clone(), Account::default());
+    }
+
+    server.tournament_rated(&["unrated"])?;
+    assert!(server.tournament_rated(&["sometimes"]).is_err());
+    assert!(server.tournament_rated(&[]).is_err());
+
+    server.tournament.date = Some(Timestamp::now());
+    server.handle_messages_internal("0 server tournament_start", None);
+
+    assert!(!server.games_light.0.is_empty());
+    for game in server.games_light.0.values() {
+        assert_eq!(game.rated, Rated::No);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn tournament_requirements() -> anyhow::Result<()> {
+    let (mut server, [david_rx, _abby_rx]) = logged_in(["david", "abby"], None);
+    server.migrate()?;
+
+    assert!(
+        server
+            .handle_messages_internal(
+                "0 server tournament_requirements (rated_games:1,players_maximum:Some(1))",
+                None,
+            )
+            .is_none()
+    );
+    assert_eq!(server.tournament.requirements.rated_games, 1);
+
+    let (_, result, message) = server
+        .handle_messages_internal("1 david join_tournament", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(message, "join_tournament rated_games 1");
+
+    server
+        .accounts
+        .0
+        .get_mut("david")
+        .context("expected the account david")?
+        .rated_games = 1;
+    assert!(
+        server
+            .handle_messages_internal("1 david join_tournament", None)
+            .is_none()
+    );
+    assert!(server.tournament.players.contains("david"));
+    while david_rx.try_recv().is_ok() {}
+
+    server
+        .accounts
+        .0
+        .get_mut("abby")
+        .context("expected the account abby")?
+        .rated_games = 1;
+    let (_, result, message) = server
+        .handle_messages_internal("2 abby join_tournament", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(message, "join_tournament full");
+
+    Ok(())
+}
+
+#[test]
+fn tournament_withdraw() -> anyhow::Result<()> {
+    let mut server = ServerFull::default();
+    server.migrate()?;
+
+    let (tx, rx) = mpsc::channel();
+    server.tx = Some(tx);
+
+    for username in ["abby", "david"] {
+        server.tournament.players.insert(username.to_string());
+        server
+            .accounts
+            .0
+            .insert(username.to_string(), Account::default());
+    }
+
+    server.tournament.date = Some(Timestamp::now());
+    server.handle_messages_internal("0 server tournament_start", None);
+
+    server.tournament_withdraw("david");
+    server.tournament_forfeits();
+
+    let (message, _) = rx.try_recv()?;
+    assert!(message.starts_with("0 david game "));
+    assert!(message.ends_with(" play attacker resigns _"));
+    assert!(rx.try_recv().is_err());
+
+    // The resignation only gets sent once.
+    server.tournament_forfeits();
+    assert!(rx.try_recv().is_err());
+
+    // Without a tx, so the game analysis has nowhere to go.
+    let tx = server.tx.take();
+    server.handle_messages_internal(&message, None);
+    server.tx = tx;
+
+    let tournament = server
+        .tournament
+        .tournament
+        .as_ref()
+        .context("expected a tournament")?;
+    assert_eq!(tournament.tournament_games.len(), 1);
+
+    if let Some(tournament) = &mut server.tournament.tournament {
+        tournament.round_days = Some(0);
+    }
+    server.tournament_forfeits();
+    assert_eq!(rx.try_iter().count(), 1);
+    server.tournament_forfeits();
+    assert!(rx.try_recv().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn tournament_texts() -> anyhow::Result<()> {
+    let (mut server, [abby_rx, carol_rx]) = logged_in(["abby", "carol"], None);
+    server.migrate()?;
+    server.tournament.players.insert("abby".to_string());
+
+    assert!(
+        server
+            .handle_messages_internal("1 abby text_tournament hello there", None)
+            .is_none()
+    );
+    assert!(abby_rx.try_recv()?.starts_with("= text_tournament "));
+    assert!(carol_rx.try_recv().is_err());
+    assert_eq!(server.texts_tournament.len(), 1);
+    assert_eq!(
+        server
+            .texts_tournament
+            .front()
+            .context("expected a message")?
+            .content,
+        "hello there"
+    );
+
+    let (_, result, message) = server
+        .handle_messages_internal("2 carol text_tournament hello", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(message, "text_tournament you are not in the tournament");
+    assert_eq!(server.texts_tournament.len(), 1);
+
+    server.handle_messages_internal("0 server tournament_delete", None);
+    assert!(server.texts_tournament.is_empty());
+    assert!(
+        carol_rx
+            .try_iter()
+            .any(|message| message == "= texts_tournament")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn tournament_arena() -> anyhow::Result<()> {
+    let mut server = ServerFull::default();
+    server.migrate()?;
+    server.skip_the_data_files = true;
+
+    for (index, username) in ["abby", "carol", "david", "erin"].into_iter().enumerate() {
+        let account = Account {
+            logged_in: Some(index + 1),
+            ..Account::default()
+        };
+        server.accounts.0.insert(username.to_string(), account);
+        server.tournament.players.insert(username.to_string());
+    }
+
+    server.handle_messages_internal("0 server tournament_arena 60", None);
+    assert_eq!(server.tournament.arena_minutes, Some(60));
+
+    server.tournament.date = Some(Timestamp::now());
+    server.handle_messages_internal("0 server tournament_start", None);
+
+    let arena = server
+        .tournament
+        .tournament
+        .as_ref()
+        .context("expected a tournament")?
+        .arena
+        .as_ref()
+        .context("expected an arena")?;
+    assert_eq!(arena.games.len(), 2);
+    assert_eq!(server.games.0.len(), 2);
+
+    let (id, (attacker, defender)) = arena
+        .games
+        .iter()
+        .next()
+        .context("expected an arena game")?;
+    let (id, attacker, defender) = (*id, attacker.clone(), defender.clone());
+
+    server.handle_messages_internal(
+        &format!("0 {attacker} game {id} play attacker resigns _"),
+        None,
+    );
+
+    // The two who finished are the only ones free, so they play again.
+    let arena = server
+        .tournament
+        .tournament
+        .as_ref()
+        .context("expected a tournament")?
+        .arena
+        .as_ref()
+        .context("expected an arena")?;
+    assert_eq!(arena.games.len(), 2);
+    assert!(!arena.games.contains_key(&id));
+    assert_eq!(
+        arena
+            .scores
+            .get(&defender)
+            .context("expected a score")?
+            .points,
+        2
+    );
+    assert_eq!(
+        arena
+            .scores
+            .get(&attacker)
+            .context("expected a score")?
+            .points,
+        0
+    );
+    assert_eq!(
+        arena.leaderboard().first().context("expected a leader")?.0,
+        &defender
+    );
+
+    // No one gets paired once the arena is over.
+    let everyone: HashSet<_> = arena.scores.keys().cloned().collect();
+    let mut arena = arena.clone();
+    arena.games.clear();
+    assert_eq!(arena.pair(&everyone, Timestamp::now()).len(), 2);
+
+    arena.ends = Timestamp::now();
+    assert!(arena.pair(&everyone, Timestamp::now()).is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn tournament_arena_deadline() -> anyhow::Result<()> {
+    let mut server = ServerFull::default();
+    server.migrate()?;
+    server.skip_the_data_files = true;
+
+    for (index, username) in ["abby", "carol", "david"].into_iter().enumerate() {
+        let account = Account {
+            logged_in: Some(index + 1),
+            ..Account::default()
+        };
+        server.accounts.0.insert(username.to_string(), account);
+        server.tournament.players.insert(username.to_string());
+    }
+
+    // david is busy with a game outside the arena.
+    let mut busy = ServerGameLight::new(
+        99,
+        "david".to_string(),
+        Rated::No,
+        TimeSettings::UnTimed,
+        BoardSize::_11,
+        Role::Attacker,
+    );
+    busy.defender = Some("erin".to_string());
+    server.games.0.insert(99, ServerGame::new(None, None, busy));
+
+    server.tournament.arena_minutes = Some(60);
+    server.tournament.date = Some(Timestamp::now());
+    server.handle_messages_internal("0 server tournament_start", None);
+
+    let arena = server
+        .tournament
+        .tournament
+        .as_mut()
+        .context("expected a tournament")?
+        .arena
+        .as_mut()
+        .context("expected an arena")?;
+    assert_eq!(arena.games.len(), 1);
+    let (id, (attacker, defender)) = arena
+        .games
+        .iter()
+        .next()
+        .context("expected an arena game")?;
+    let (id, attacker, defender) = (*id, attacker.clone(), defender.clone());
+    assert!(![&attacker, &defender].contains(&&"david".to_string()));
+
+    // The game still going when the time runs out doesn't count.
+    arena.ends = Timestamp::now() - 1.second();
+    assert!(arena.is_over(Timestamp::now()));
+
+    server.handle_messages_internal(
+        &format!("0 {attacker} game {id} play attacker resigns _"),
+        None,
+    );
+
+    let arena = server
+        .tournament
+        .tournament
+        .as_ref()
+        .context("expected a tournament")?
+        .arena
+        .as_ref()
+        .context("expected an arena")?;
+    assert!(arena.games.is_empty());
+    assert_eq!(
+        arena
+            .scores
+            .get(&defender)
+            .context("expected a score")?
+            .points,
+        0
+    );
+
+    Ok(())
+}
+
+#[test]
+fn tournament_berserk() -> anyhow::Result<()> {
+    let (mut server, _receivers) = logged_in(["abby", "david"], None);
+    server.migrate()?;
+    server.skip_the_data_files = true;
+
+    for username in ["abby", "david"] {
+        server.tournament.players.insert(username.to_string());
+    }
+
+    server.tournament.arena_minutes = Some(60);
+    server.tournament.time_setting = TimeSettings::Timed(Time {
+        add_seconds: 10,
+        milliseconds_left: 60_000,
+    });
+    server.tournament.date = Some(Timestamp::now());
+    server.handle_messages_internal("0 server tournament_start", None);
+
+    let arena = server
+        .tournament
+        .tournament
+        .as_ref()
+        .context("expected a tournament")?
+        .arena
+        .as_ref()
+        .context("expected an arena")?;
+    let (id, (attacker, defender)) = arena
+        .games
+        .iter()
+        .next()
+        .context("expected an arena game")?;
+    let (id, attacker, defender) = (*id, attacker.clone(), defender.clone());
+    let index = if defender == "abby" { 1 } else { 2 };
+
+    let (_, result, _) = server
+        .handle_messages_internal(&format!("{index} {defender} berserk {id}"), None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+
+    let game = server.games.0.get(&id).context("expected the game")?;
+    assert_eq!(
+        game.game.defender_time,
+        TimeSettings::Timed(Time {
+            add_seconds: 0,
+            milliseconds_left: 30_000,
+        })
+    );
+
+    let (_, result, message) = server
+        .handle_messages_internal(&format!("{index} {defender} berserk {id}"), None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(
+        message,
+        format!("berserk {id} you can't go berserk in this game")
+    );
+
+    server.handle_messages_internal(
+        &format!("0 {attacker} game {id} play attacker resigns _"),
+        None,
+    );
+
+    let arena = server
+        .tournament
+        .tournament
+        .as_ref()
+        .context("expected a tournament")?
+        .arena
+        .as_ref()
+        .context("expected an arena")?;
+    assert_eq!(
+        arena
+            .scores
+            .get(&defender)
+            .context("expected a score")?
+            .points,
+        3
+    );
+    assert!(arena.berserk.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn tournament_berserk_untimed() -> anyhow::Result<()> {
+    let (mut server, _receivers) = logged_in(["abby", "david"], None);
+    server.migrate()?;
+    server.skip_the_data_files = true;
+
+    for username in ["abby", "david"] {
+        server.tournament.players.insert(username.to_string());
+    }
+
+    server.tournament.arena_minutes = Some(60);
+    server.tournament.time_setting = TimeSettings::UnTimed;
+    server.tournament.date = Some(Timestamp::now());
+    server.handle_messages_internal("0 server tournament_start", None);
+
+    let arena = server
+        .tournament
+        .tournament
+        .as_ref()
+        .context("expected a tournament")?
+        .arena
+        .as_ref()
+        .context("expected an arena")?;
+    let (id, (_, defender)) = arena
+        .games
+        .iter()
+        .next()
+        .context("expected an arena game")?;
+    let (id, defender) = (*id, defender.clone());
+    let index = if defender == "abby" { 1 } else { 2 };
+
+    let (_, result, message) = server
+        .handle_messages_internal(&format!("{index} {defender} berserk {id}"), None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(message, format!("berserk {id} the game isn't timed"));
+
+    let arena = server
+        .tournament
+        .tournament
+        .as_ref()
+        .context("expected a tournament")?
+        .arena
+        .as_ref()
+        .context("expected an arena")?;
+    assert!(arena.berserk.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn clubs() -> anyhow::Result<()> {
+    let (mut server, [abby_rx, david_rx]) = logged_in(["abby", "david"], None);
+    server.migrate()?;
+    server.skip_the_data_files = true;
+
+    server.handle_messages_internal("1 abby club_new Vikings", None);
+    server.handle_messages_internal("1 abby club_description vikings We play on Sundays.", None);
+    let club = server.clubs.0.get("vikings").context("expected the club")?;
+    assert_eq!(club.owner, "abby");
+    assert_eq!(club.description, "We play on Sundays.");
+
+    let (_, result, message) = server
+        .handle_messages_internal("2 david club_new vikings", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(message, "club_new vikings is taken");
+
+    let (_, result, _) = server
+        .handle_messages_internal("2 david club_description vikings Mine now.", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+
+    let (_, result, message) = server
+        .handle_messages_internal("2 david club_text vikings hello", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(message, "club_text you aren't a member of vikings");
+
+    server.tournament.requirements.club = Some("vikings".to_string());
+    let (_, result, message) = server
+        .handle_messages_internal("2 david join_tournament", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(message, "join_tournament club vikings");
+
+    server.handle_messages_internal("1 abby club_invite vikings david", None);
+    server.handle_messages_internal("2 david club_join vikings", None);
+    assert!(server.clubs.is_member("vikings", "david"));
+    assert!(
+        server
+            .handle_messages_internal("2 david join_tournament", None)
+            .is_none()
+    );
+
+    while abby_rx.try_recv().is_ok() {}
+    while david_rx.try_recv().is_ok() {}
+
+    server.handle_messages_internal("2 david club_text vikings hello", None);
+    assert!(abby_rx.try_recv()?.starts_with("= club_text vikings "));
+    assert_eq!(
+        server
+            .texts_club
+            .get("vikings")
+            .context("expected the club chat")?
+            .len(),
+        1
+    );
+
+    server.handle_messages_internal("1 abby club_remove vikings david", None);
+    assert!(!server.clubs.is_member("vikings", "david"));
+
+    server.handle_messages_internal("1 abby club_delete vikings", None);
+    assert!(server.clubs.0.is_empty());
+    assert!(server.texts_club.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn club_join_needs_an_invite() -> anyhow::Result<()> {
+    let (mut server, _rx) = logged_in(["abby", "david", "erik"], None);
+    server.skip_the_data_files = true;
+
+    server.handle_messages_internal("1 abby club_new vikings", None);
+    server.tournament.requirements.club = Some("vikings".to_string());
+
+    let (_, result, _) = server
+        .handle_messages_internal("2 david club_join vikings", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert!(!server.clubs.is_member("vikings", "david"));
+
+    let (_, result, message) = server
+        .handle_messages_internal("2 david join_tournament", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(message, "join_tournament club vikings");
+
+    // Only the owner invites, and only players with an account.
+    let (_, result, _) = server
+        .handle_messages_internal("3 erik club_invite vikings david", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    let (_, result, _) = server
+        .handle_messages_internal("1 abby club_invite vikings nobody", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+
+    server.handle_messages_internal("1 abby club_invite vikings david", None);
+    server.handle_messages_internal("2 david club_join vikings", None);
+    assert!(server.clubs.is_member("vikings", "david"));
+    assert!(
+        server
+            .handle_messages_internal("2 david join_tournament", None)
+            .is_none()
+    );
+
+    // Taken out, a member needs another invite to get back in.
+    server.handle_messages_internal("1 abby club_remove vikings david", None);
+    let (_, result, _) = server
+        .handle_messages_internal("2 david club_join vikings", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert!(!server.clubs.is_member("vikings", "david"));
+
+    Ok(())
+}
+
+#[test]
+fn direct_messages() -> anyhow::Result<()> {
+    let (mut server, [_abby_rx, david_rx]) = logged_in(["abby", "david"], None);
+    server.migrate()?;
+    server.skip_the_data_files = true;
+    server
+        .accounts
+        .0
+        .insert("erik".to_string(), Account::default());
+
+    let (_, result, message) = server
+        .handle_messages_internal("1 abby dm david Good game!", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert!(message.starts_with("dm david "));
+    let received = david_rx.try_recv()?;
+    assert!(received.starts_with("= dm abby "));
+    assert!(received.contains("Good game!"));
+
+    let (_, result, message) = server
+        .handle_messages_internal("1 abby dm nobody hi", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(message, "dm nobody there is no such user");
+
+    server.handle_messages_internal("1 abby dm erik See you Sunday.", None);
+    assert_eq!(
+        server
+            .dms_pending
+            .get("erik")
+            .context("expected direct messages")?
+            .len(),
+        1
+    );
+
+    let (erik_tx, erik_rx) = mpsc::channel();
+    server.clients.insert(3, erik_tx);
+    server
+        .accounts
+        .0
+        .get_mut("erik")
+        .context("expected the account erik")?
+        .logged_in = Some(3);
+    server.handle_messages_internal("3 erik dms_pending", None);
+    let received = erik_rx.try_recv()?;
+    assert!(received.starts_with("= dm abby "));
+    assert!(received.contains("See you Sunday."));
+    assert!(!server.dms_pending.contains_key("erik"));
+
+    let (_, result, message) = server
+        .handle_messages_internal("2 david block abby", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert_eq!(message, "blocks [\"abby\"]");
+
+    for command in ["1 abby dm david hello", "2 david dm abby hello"] {
+        let (_, result, _) = server
+            .handle_messages_internal(command, None)
+            .context("didn't get a response")?;
+        assert!(result.is_err());
+    }
+
+    server.handle_messages_internal("2 david unblock abby", None);
+    let (_, result, _) = server
+        .handle_messages_internal("1 abby dm david hello", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn texts_unread() -> anyhow::Result<()> {
+    let (mut server, _david_rx, abby_rx) = game_started(TimeSettings::UnTimed)?;
+    assert!(server.games.0.contains_key(&0));
+
+    server
+        .games_light
+        .0
+        .get_mut(&0)
+        .context("expected the game")?
+        .spectators
+        .remove("abby");
+    while abby_rx.try_recv().is_ok() {}
+
+    server.handle_messages_internal("0 david text_game 0 Your move.", None);
+    assert_eq!(abby_rx.try_recv()?, "= texts_unread {0:1}");
+
+    let (_, _, message) = server
+        .handle_messages_internal("1 abby texts_unread", None)
+        .context("didn't get a response")?;
+    assert_eq!(message, "texts_unread {0:1}");
+    let (_, _, message) = server
+        .handle_messages_internal("0 david texts_unread", None)
+        .context("didn't get a response")?;
+    assert_eq!(message, "texts_unread {}");
+
+    server.handle_messages_internal("1 abby resume_game_ron 0", None);
+    let (_, _, message) = server
+        .handle_messages_internal("1 abby texts_unread", None)
+        .context("didn't get a response")?;
+    assert_eq!(message, "texts_unread {}");
+
+    Ok(())
+}
+
+#[test]
+fn lag_compensation() -> anyhow::Result<()> {
+    let (mut server, _david_rx, _abby_rx) = game_started(TimeSettings::Timed(Time {
+        add_seconds: 0,
+        milliseconds_left: 60_000,
+    }))?;
+
+    // What the client says doesn't count, only the round trip does.
+    server.handle_messages_internal("0 david ping 5000", None);
+    server.handle_messages_internal("0 david pong", None);
+    assert!(server.lag(0) < 300);
+
+    let pinged = |server: &mut ServerFull, index, username, milliseconds| {
+        server.handle_messages_internal(&format!("{index} {username} ping"), None);
+        server.pings.insert(
+            index,
+            Timestamp::now() - SignedDuration::from_millis(milliseconds),
+        );
+        server.handle_messages_internal(&format!("{index} {username} pong"), None);
+    };
+
+    // One held back pong doesn't raise the lag.
+    pinged(&mut server, 0, "david", 300);
+    assert!(server.lag(0) < 300);
+
+    for _ in 1..LAG_ROUND_TRIPS {
+        pinged(&mut server, 0, "david", 300);
+    }
+    pinged(&mut server, 1, "abby", 5000);
+    let david = server.lag(0);
+    assert!((300..400).contains(&david));
+
+    // A pong without a ping doesn't change the lag.
+    server.handle_messages_internal("0 david pong", None);
+    assert_eq!(server.lag(0), david);
+
+    let took = |server: &mut ServerFull, milliseconds| {
+        if let Some(game) = server.games.0.get_mut(&0) {
+            game.game.time = TimeUnix::Time(Timestamp::now().as_millisecond() - milliseconds);
+        }
+    };
+
+    took(&mut server, 2_000);
+    server.handle_messages_internal("0 david game 0 play attacker c1 c2", None);
+    assert!(
+        server
+            .games
+            .0
+            .get(&0)
+            .context("expected the game")?
+            .lag
+            .is_empty()
+    );
+
+    took(&mut server, 2_000);
+    server.handle_messages_internal("0 david game 0 play attacker d1 d3", None);
+    assert_eq!(
+        server.games.0.get(&0).context("expected the game")?.lag,
+        [david]
+    );
+
+    if let TimeSettings::Timed(time) = server
+        .games
+        .0
+        .get(&0)
+        .context("expected the game")?
+        .game
+        .attacker_time
+    {
+        assert!((56_000..57_000).contains(&time.milliseconds_left));
+    }
+
+    // Abby gets back no more than the move took, which is a little over 500
+    // milliseconds by the time the server looks at it.
+    took(&mut server, 500);
+    server.handle_messages_internal("1 abby game 0 play defender d6 d4", None);
+    let lag = &server.games.0.get(&0).context("expected the game")?.lag;
+    let abby = *lag.get(1).context("expected: lag")?;
+    assert!((500..600).contains(&abby));
+    assert_eq!(lag, &[david, abby]);
+
+    took(&mut server, 3_000);
+    server.handle_messages_internal("1 abby game 0 play attacker d3 d2", None);
+    server.handle_messages_internal("0 david game 0 play attacker d3 c3", None);
+    assert_eq!(
+        server.games.0.get(&0).context("expected the game")?.lag,
+        [david, abby, david]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn flagging() -> anyhow::Result<()> {
+    let mut server = ServerFull {
+        skip_the_data_files: true,
+        ..ServerFull::default()
+    };
+
+    let (tx, _rx) = mpsc::channel();
+    create_account(&mut server, tx)?;
+
+    let (tx, abby_rx) = mpsc::channel();
+    server.handle_messages_internal("1 abby create_account PASSWORD", Some(tx));
+
+    let new_game = NewGame {
+        role: Role::Attacker,
+        rated: false,
+        time_settings: TimeSettings::Timed(Time {
+            add_seconds: 0,
+            milliseconds_left: 10_000,
+        }),
+        board_size: 11,
+        established_only: false,
+        zen: false,
+        rule_set: RuleSet::default(),
+    };
+    let new_game = serde_json::ser::to_string(&new_game)?;
+
+    let took = |server: &mut ServerFull, id, milliseconds| {
+        if let Some(game) = server.games.0.get_mut(&id) {
+            game.game.time = TimeUnix::Time(Timestamp::now().as_millisecond() - milliseconds);
+        }
+    };
+
+    server.handle_messages_internal(&format!("0 david new_game {new_game}"), None);
+    server.handle_messages_internal("1 abby join_game_pending 0", None);
+    server.handle_messages_internal("0 david join_game 0", None);
+    let _messages: Vec<_> = abby_rx.try_iter().collect();
+
+    took(&mut server, 0, 11_000);
+    server.handle_messages_internal("0 david game 0 play attacker d1 d3", None);
+    assert!(!server.games.0.contains_key(&0));
+
+    let messages: Vec<_> = abby_rx.try_iter().collect();
+    assert!(
+        !messages
+            .iter()
+            .any(|message| message.contains("play attacker"))
+    );
+    assert!(messages.contains(&"= game_over 0 defender_wins".to_string()));
+
+    server.handle_messages_internal(&format!("0 david new_game {new_game}"), None);
+    server.handle_messages_internal("1 abby join_game_pending 1", None);
+    server.handle_messages_internal("0 david join_game 1", None);
+
+    took(&mut server, 1, 5_000);
+    server.handle_messages_internal("0 server flag 1", None);
+    assert!(server.games.0.contains_key(&1));
+
+    took(&mut server, 1, 10_000);
+    server.handle_messages_internal("0 server flag 1", None);
+    assert!(!server.games.0.contains_key(&1));
+
+    Ok(())
+}
+
+#[test]
+fn claims() -> anyhow::Result<()> {
+    let mut server = ServerFull {
+        skip_the_data_files: true,
+        ..ServerFull::default()
+    };
+
+    let (tx, david_rx) = mpsc::channel();
+    create_account(&mut server, tx)?;
+
+    let (tx, _rx) = mpsc::channel();
+    server.handle_messages_internal("1 abby create_account PASSWORD", Some(tx));
+
+    let new_game = NewGame {
+        role: Role::Attacker,
+        rated: false,
+        time_settings: TimeSettings::Timed(Time {
+            add_seconds: 0,
+            milliseconds_left: 60_000,
+        }),
+        board_size: 11,
+        established_only: false,
+        zen: false,
+        rule_set: RuleSet::default(),
+    };
+    let new_game = serde_json::ser::to_string(&new_game)?;
+    server.handle_messages_internal(&format!("0 david new_game {new_game}"), None);
+    server.handle_messages_internal("1 abby join_game_pending 0", None);
+    server.handle_messages_internal("0 david join_game 0", None);
+    server.handle_messages_internal("0 david game 0 play attacker d1 d3", None);
+
+    let (_, result, message) = server
+        .handle_messages_internal("0 david claim 0 win", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(message, "claim connected");
+
+    let _messages: Vec<_> = david_rx.try_iter().collect();
+    server.handle_messages_internal("1 abby logout", None);
+    let messages: Vec<_> = david_rx.try_iter().collect();
+    assert!(messages.contains(&"= opponent_disconnected 0 60".to_string()));
+
+    let (_, result, message) = server
+        .handle_messages_internal("0 david claim 0 win", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(message, "claim wait 60");
+
+    server.config.claim_after_seconds = 0;
+    let (_, result, message) = server
+        .handle_messages_internal("0 david claim 0 win", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert_eq!(message, "claim 0 win");
+    assert!(!server.games.0.contains_key(&0));
+
+    let messages: Vec<_> = david_rx.try_iter().collect();
+    assert!(messages.contains(&"= game_over 0 attacker_wins".to_string()));
+
+    let (tx, _rx) = mpsc::channel();
+    server.handle_messages_internal("2 abby login PASSWORD", Some(tx));
+    server.handle_messages_internal(&format!("0 david new_game {new_game}"), None);
+    server.handle_messages_internal("2 abby join_game_pending 1", None);
+    server.handle_messages_internal("0 david join_game 1", None);
+    server.handle_messages_internal("2 abby logout", None);
+
+    let (_, result, message) = server
+        .handle_messages_internal("0 david claim 1 win", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(message, "claim your_move");
+
+    let (_, result, _) = server
+        .handle_messages_internal("0 david claim 1 draw", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert!(!server.games.0.contains_key(&1));
+    assert_eq!(
+        server
+            .accounts
+            .0
+            .get("david")
+            .context("expected the account david")?
+            .draws,
+        1
+    );
+
+    Ok(())
+}
+
+#[test]
+fn claims_live_games_only() -> anyhow::Result<()> {
+    let (mut server, _david_rx, _abby_rx) = game_started(TimeSettings::UnTimed)?;
+    server.config.claim_after_seconds = 0;
+    server.handle_messages_internal("1 abby logout", None);
+
+    let (_, result, message) = server
+        .handle_messages_internal("0 david claim 0 draw", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(message, "claim not_live");
+
+    let (mut server, _david_rx, _abby_rx) = game_started(TimeSettings::Timed(Time {
+        add_seconds: 0,
+        milliseconds_left: 60_000,
+    }))?;
+    server.config.claim_after_seconds = 0;
+    server.handle_messages_internal("1 abby logout", None);
+
+    let mut tournament = Tournament::default();
+    tournament
+        .tournament_games
+        .insert(0, Arc::new(Mutex::new(Group::default())));
+    server.tournament.tournament = Some(tournament);
+
+    let (_, result, message) = server
+        .handle_messages_internal("0 david claim 0 draw", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(message, "claim not_live");
+
+    // The clock waits for a player coming back after a handoff.
+    server.tournament.tournament = None;
+    server.handle_messages_internal("0 david game 0 play attacker d1 d3", None);
+    server.reconnecting.insert("abby".to_string(), i64::MAX);
+
+    let (_, result, message) = server
+        .handle_messages_internal("0 david claim 0 win", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(message, "claim clock_held");
+
+    Ok(())
+}
+
+#[test]
+fn vacation() -> anyhow::Result<()> {
+    let (mut server, _david_rx, _abby_rx) = game_started(TimeSettings::Timed(Time {
+        add_seconds: 0,
+        milliseconds_left: 3 * DAY,
+    }))?;
+    assert!(!server.clock_held(server.games.0.get(&0).context("expected the game")?));
+
+    let (_, result, message) = server
+        .handle_messages_internal("0 david vacation 0", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+    assert_eq!(message, "vacation you can take from 1 to 30 vacation days");
+
+    let (_, result, _) = server
+        .handle_messages_internal("0 david vacation 31", None)
+        .context("didn't get a response")?;
+    assert!(result.is_err());
+
+    let (_, result, message) = server
+        .handle_messages_internal("0 david vacation 7", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert!(message.ends_with(" 23"));
+    assert!(server.clock_held(server.games.0.get(&0).context("expected the game")?));
+
+    server.handle_messages_internal("0 david game 0 play attacker d1 d3", None);
+    assert!(!server.clock_held(server.games.0.get(&0).context("expected the game")?));
+
+    let (_, result, message) = server
+        .handle_messages_internal("0 david vacation_end", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert_eq!(message, "vacation _ 30");
+    assert_eq!(
+        server
+            .accounts
+            .0
+            .get("david")
+            .context("expected the account david")?
+            .vacation
+            .until,
+        None
+    );
+
+    Ok(())
+}
+
+#[test]
+fn move_numbers() -> anyhow::Result<()> {
+    let (mut server, _david_rx, abby_rx) = game_started(TimeSettings::UnTimed)?;
+    let _messages: Vec<_> = abby_rx.try_iter().collect();
+
+    let (_, result, _) = server
+        .handle_messages_internal("0 david game 0 play attacker d1 d3 0", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+
+    let (_, result, _) = server
+        .handle_messages_internal("0 david game 0 play attacker d1 d3 0", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert_eq!(
+        server
+            .games
+            .0
+            .get(&0)
+            .context("expected the game")?
+            .game
+            .plays
+            .made()
+            .len(),
+        1
+    );
+
+    let messages: Vec<_> = abby_rx.try_iter().collect();
+    let plays = messages
+        .iter()
+        .filter(|message| message.starts_with("game 0 play attacker d1 d3 "));
+    assert_eq!(plays.count(), 1);
+
+    let (_, result, _) = server
+        .handle_messages_internal("0 david game 0 play attacker d1 d2 0", None)
+        .context("didn't get a response")?;
+    assert_eq!(result, Err(InvalidMove::MoveNumber(1)));
+
+    let (_, result, _) = server
+        .handle_messages_internal("1 abby game 0 play defender d6 d4 2", None)
+        .context("didn't get a response")?;
+    assert_eq!(result, Err(InvalidMove::MoveNumber(1)));
+
+    let (_, result, _) = server
+        .handle_messages_internal("1 abby game 0 play defender d6 d4 1", None)
+        .context("didn't get a response")?;
+    assert!(result.is_ok());
+    assert_eq!(
+        server
+            .games
+            .0
+            .get(&0)
+            .context("expected the game")?
+            .game
+            .plays
+            .made()
+            .len(),
+        2
+    );
+
+    server.handle_messages_internal("0 david game 0 play attacker d3 d2", None);
+    assert_eq!(
+        server
+            .games
+            .0
+            .get(&0)
+            .context("expected the game")?
+            .game
+            .plays
+            .made()
+            .len(),
+        3
+    );
+
+    Ok(())
+}
@@ -16,6 +16,7 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 // SPDX-FileCopyrightText: 2025 David Campbell <david@hnefatafl.org>
 
+use hnefatafl_copenhagen::clock;
 use jiff::Timestamp;
 use serde::{Deserialize, Serialize};
 
@@ -24,6 +25,6 @@ pub(crate) struct UnixTimestamp(pub Timestamp);
 
 impl Default for UnixTimestamp {
     fn default() -> Self {
-        Self(Timestamp::now())
+        Self(clock::now())
     }
 }
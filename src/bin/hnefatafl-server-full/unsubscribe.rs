@@ -0,0 +1,308 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! One click unsubscribe links. A link holds the username, the category of
+//! email, and a token signed with the server's key, so it works without
+//! logging in. The web server proxies the unsubscribe URL to a small HTTP
+//! listener here, which hands the link to the server to check.
+
+use std::{
+    fmt::Write as _,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    str::FromStr,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Sender},
+    },
+    thread,
+    time::Duration,
+};
+
+use blake2::{
+    Blake2bMac,
+    digest::{KeyInit, Mac, consts::U32},
+};
+use hnefatafl_copenhagen::email::EmailCategory;
+use log::{error, info};
+
+pub(crate) const KEY_LENGTH: usize = 32;
+
+/// How many requests get answered at the same time, the rest are closed.
+const CONNECTIONS: usize = 16;
+
+const REPLY_SECONDS: u64 = 5;
+const REQUEST_LENGTH: u64 = 8 * 1024;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct Link {
+    pub username: String,
+    pub category: EmailCategory,
+    pub token: String,
+}
+
+impl Link {
+    pub(crate) fn new(key: &[u8], username: &str, category: EmailCategory) -> Option<Self> {
+        let token = mac(key, username, category)?.finalize().into_bytes();
+
+        let mut hex = String::with_capacity(token.len() * 2);
+        for byte in token {
+            let _ = write!(hex, "{byte:02x}");
+        }
+
+        Some(Self {
+            username: username.to_string(),
+            category,
+            token: hex,
+        })
+    }
+
+    /// Parses the path and query of a request, such as
+    /// `/unsubscribe?user=david&category=announcements&token=...`.
+    pub(crate) fn parse(target: &str) -> Option<Self> {
+        let (_path, query) = target.split_once('?')?;
+
+        let mut username = None;
+        let mut category = None;
+        let mut token = None;
+
+        for pair in query.split('&') {
+            let (name, value) = pair.split_once('=')?;
+            let value = percent_decode(value)?;
+
+            match name {
+                "user" => username = Some(value),
+                "category" => category = EmailCategory::from_str(&value).ok(),
+                "token" => token = Some(value),
+                _ => {}
+            }
+        }
+
+        let username = username?;
+        let token = token?;
+
+        // The username and token go into a message split on whitespace.
+        if username.is_empty()
+            || username.contains(char::is_whitespace)
+            || !token.chars().all(|ch| ch.is_ascii_hexdigit())
+        {
+            return None;
+        }
+
+        Some(Self {
+            username,
+            category: category?,
+            token,
+        })
+    }
+
+    pub(crate) fn url(&self, base: &str) -> String {
+        format!(
+            "{base}?user={}&category={}&token={}",
+            percent_encode(&self.username),
+            self.category,
+            self.token
+        )
+    }
+
+    pub(crate) fn verify(&self, key: &[u8]) -> bool {
+        let Some(mac) = mac(key, &self.username, self.category) else {
+            return false;
+        };
+
+        let mut token = Vec::with_capacity(self.token.len() / 2);
+        for i in (0..self.token.len()).step_by(2) {
+            match self
+                .token
+                .get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+            {
+                Some(byte) => token.push(byte),
+                None => return false,
+            }
+        }
+
+        mac.verify_slice(&token).is_ok()
+    }
+}
+
+/// Without a key anyone could make a link, so there are no links.
+fn mac(key: &[u8], username: &str, category: EmailCategory) -> Option<Blake2bMac<U32>> {
+    if key.is_empty() {
+        return None;
+    }
+
+    let mut mac = Blake2bMac::<U32>::new_from_slice(key).ok()?;
+    mac.update(username.as_bytes());
+    mac.update(&[0]);
+    mac.update(category.to_string().as_bytes());
+
+    Some(mac)
+}
+
+pub(crate) fn listen(
+    port: u16,
+    tx: Sender<(String, Option<Sender<String>>)>,
+) -> anyhow::Result<()> {
+    let listener = match TcpListener::bind(format!("[::]:{port}")) {
+        Ok(listener) => listener,
+        Err(error) => {
+            error!("TcpLister::bind: {error}");
+            TcpListener::bind(format!("0.0.0.0:{port}"))?
+        }
+    };
+
+    info!("listening for unsubscribe links on port {port} ...");
+
+    let connections = Arc::new(AtomicUsize::new(0));
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    if connections.fetch_add(1, Ordering::SeqCst) >= CONNECTIONS {
+                        connections.fetch_sub(1, Ordering::SeqCst);
+                        error!("unsubscribe: too many connections");
+                        continue;
+                    }
+
+                    let tx = tx.clone();
+                    let connections = connections.clone();
+
+                    thread::spawn(move || {
+                        if let Err(error) = respond(stream, &tx) {
+                            error!("unsubscribe: {error}");
+                        }
+
+                        connections.fetch_sub(1, Ordering::SeqCst);
+                    });
+                }
+                Err(error) => error!("unsubscribe: stream: {error}"),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// A GET shows a button, so a mail scanner that follows links doesn't
+/// unsubscribe anyone, and a POST, from the button or from a mail client's
+/// one click unsubscribe, unsubscribes.
+fn respond(
+    mut stream: TcpStream,
+    tx: &Sender<(String, Option<Sender<String>>)>,
+) -> anyhow::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(REPLY_SECONDS)))?;
+    stream.set_write_timeout(Some(Duration::from_secs(REPLY_SECONDS)))?;
+    let mut reader = BufReader::new(stream.try_clone()?.take(REQUEST_LENGTH));
+
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            content_length = value.trim().parse().unwrap_or_default();
+        }
+    }
+
+    // The body of a one click unsubscribe is always the same.
+    let mut body = Vec::new();
+    reader.take(content_length).read_to_end(&mut body)?;
+
+    let mut request = request.split_ascii_whitespace();
+    let link = request.clone().nth(1).and_then(Link::parse);
+
+    let (status, text) = match (request.next(), link) {
+        (Some("GET"), Some(_)) => (
+            "200 OK",
+            r#"<form method="post"><button type="submit">Unsubscribe</button></form>"#,
+        ),
+        (Some("POST"), Some(link)) => {
+            let (reply_tx, reply_rx) = mpsc::channel();
+            tx.send((
+                format!(
+                    "0 {} unsubscribe {} {}",
+                    link.username, link.category, link.token
+                ),
+                Some(reply_tx),
+            ))?;
+
+            match reply_rx.recv_timeout(Duration::from_secs(REPLY_SECONDS)) {
+                Ok(reply) if reply.starts_with("= unsubscribe") => {
+                    ("200 OK", "<p>You have been unsubscribed.</p>")
+                }
+                _ => ("400 Bad Request", "<p>The unsubscribe link is invalid.</p>"),
+            }
+        }
+        _ => ("400 Bad Request", "<p>The unsubscribe link is invalid.</p>"),
+    };
+
+    let page = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>Unsubscribe</title>\n</head>\n<body>\n<h1>Hnefatafl Org</h1>\n{text}\n</body>\n</html>\n"
+    );
+
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{page}",
+        page.len()
+    )?;
+
+    Ok(())
+}
+
+fn percent_decode(string: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(string.len());
+    let mut chars = string.bytes();
+
+    while let Some(byte) = chars.next() {
+        match byte {
+            b'%' => {
+                let high = char::from(chars.next()?).to_digit(16)?;
+                let low = char::from(chars.next()?).to_digit(16)?;
+                bytes.push(u8::try_from(high * 16 + low).ok()?);
+            }
+            b'+' => bytes.push(b' '),
+            byte => bytes.push(byte),
+        }
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+fn percent_encode(string: &str) -> String {
+    let mut encoded = String::with_capacity(string.len());
+
+    for byte in string.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+
+    encoded
+}
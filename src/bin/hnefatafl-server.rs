@@ -30,8 +30,7 @@ use std::{
 use clap::{self, CommandFactory, Parser};
 
 use hnefatafl_copenhagen::{
-    COPYRIGHT, SERVER_PORT, game::Game, read_response, status::Status, utils::clear_screen,
-    write_command,
+    COPYRIGHT, PORT, game::Game, read_response, status::Status, utils::clear_screen, write_command,
 };
 
 /// A Hnefatafl Copenhagen Server
@@ -45,6 +44,10 @@ struct Args {
     #[arg(default_value = "0.0.0.0", index = 1, value_name = "host")]
     host: String,
 
+    /// Listen on port
+    #[arg(default_value_t = PORT, long)]
+    port: u16,
+
     /// Build the manpage
     #[arg(long)]
     man: bool,
@@ -65,9 +68,7 @@ fn main() -> anyhow::Result<()> {
         return Ok(());
     }
 
-    let mut address = args.host;
-    address.push_str(SERVER_PORT);
-    start(&address)
+    start(&format!("{}:{}", args.host, args.port))
 }
 
 struct Htp {
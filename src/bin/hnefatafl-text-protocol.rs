@@ -29,7 +29,7 @@ use std::{
 use clap::{self, CommandFactory, Parser};
 
 use hnefatafl_copenhagen::{
-    COPYRIGHT, SERVER_PORT,
+    COPYRIGHT, PORT,
     ai::AI,
     game::Game,
     play::Plae,
@@ -61,10 +61,18 @@ struct Args {
     #[arg(long)]
     depth: Option<u8>,
 
+    /// How many mebibytes the monte-carlo AI may use
+    #[arg(long)]
+    memory: Option<usize>,
+
     /// Listen for HTP drivers on host
     #[arg(long)]
     host: Option<String>,
 
+    /// Listen for HTP drivers on port
+    #[arg(default_value_t = PORT, long)]
+    port: u16,
+
     /// Allow the defender to repeat moves
     #[arg(long)]
     repeat_moves: bool,
@@ -97,21 +105,21 @@ fn main() -> anyhow::Result<()> {
     game.chars.ascii();
     game.board.display_ascii = true;
 
-    if let Some(board) = game.previous_boards.0.first_mut() {
+    for board in game.previous_boards.boards_mut() {
         board.display_ascii = true;
     }
 
-    if let Some(mut address) = args.host {
-        address.push_str(SERVER_PORT);
+    if let Some(host) = args.host {
+        let address = format!("{host}:{}", args.port);
 
         let ai = match args.ai {
-            Some(ai) => choose_ai(&ai, args.seconds, args.depth, true)?,
-            None => choose_ai("basic", args.seconds, args.depth, true)?,
+            Some(ai) => choose_ai(&ai, args.seconds, args.depth, args.memory, true)?,
+            None => choose_ai("basic", args.seconds, args.depth, args.memory, true)?,
         };
 
         play_tcp(game, ai, &address, args.display_game)?;
     } else if let Some(ai) = args.ai {
-        let ai = choose_ai(&ai, args.seconds, args.depth, true)?;
+        let ai = choose_ai(&ai, args.seconds, args.depth, args.memory, true)?;
 
         play_ai(game, ai, args.display_game)?;
     } else {
@@ -139,7 +147,7 @@ fn play(mut game: Game, display_game: bool, repeat_moves: bool) -> anyhow::Resul
         }
 
         if repeat_moves {
-            game.previous_boards.0 = Vec::new();
+            game.previous_boards.clear();
         }
 
         let result = game.read_line(&buffer);
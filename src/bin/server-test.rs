@@ -0,0 +1,365 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+#![deny(clippy::expect_used)]
+#![deny(clippy::indexing_slicing)]
+#![deny(clippy::panic)]
+#![deny(clippy::unwrap_used)]
+
+use std::{
+    env, fs,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::PathBuf,
+    process::{Child, Command, Stdio},
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::{Duration, Instant},
+};
+
+use clap::Parser;
+use hnefatafl_copenhagen::{
+    ARCHIVED_GAMES_PAGE, Id, VERSION_ID,
+    board::BoardSize,
+    game::Game,
+    play::Plae,
+    role::Role,
+    rule_set::RuleSet,
+    server_game::{ArchivedGame, NewGame},
+    status::Status,
+    time::TimeSettings,
+};
+
+/// Copenhagen Hnefatafl Server Test
+///
+/// Starts a hnefatafl-server-full on a free port with an empty data folder,
+/// then has pairs of bots log in, create and join a game, chat, play and log
+/// out. Finally it checks that the server archived every game.
+#[derive(Parser, Debug)]
+#[command(about = "Copenhagen Hnefatafl Server Test")]
+struct Args {
+    /// How many games to play at the same time, each game has two bots
+    #[arg(default_value_t = 100, long)]
+    games: usize,
+
+    /// How many moves each bot makes before resigning
+    #[arg(default_value_t = 10, long)]
+    moves: usize,
+
+    /// The server binary to test
+    #[arg(default_value = "./target/debug/hnefatafl-server-full", long)]
+    server: PathBuf,
+}
+
+/// The server process, killed when dropped.
+struct Server(Child);
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        if let Err(error) = self.0.kill() {
+            eprintln!("kill server: {error}");
+        }
+    }
+}
+
+enum Side {
+    Attacker(Sender<Id>),
+    Defender(Receiver<Id>),
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    let mut data = env::temp_dir();
+    data.push(format!("hnefatafl-server-test-{}", rand::random::<u64>()));
+    fs::create_dir_all(&data)?;
+
+    let result = run(&args, &data);
+    fs::remove_dir_all(&data)?;
+    result
+}
+
+fn run(args: &Args, data: &PathBuf) -> anyhow::Result<()> {
+    // Another process could take the port before the server binds it, but it
+    // is unlikely.
+    let port = TcpListener::bind("localhost:0")?.local_addr()?.port();
+    let address = format!("localhost:{port}");
+
+    let _server = Server(
+        Command::new(&args.server)
+            .arg("--skip-advertising-updates")
+            // The bots would spend their time on the proof of work.
+            .arg("--proof-of-work-bits")
+            .arg("0")
+            .arg("--port")
+            .arg(port.to_string())
+            .env("HOME", data)
+            .env("XDG_DATA_HOME", data)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?,
+    );
+
+    wait_for_server(&address)?;
+
+    let t0 = Instant::now();
+    let mut handles = Vec::new();
+
+    for i in 0..args.games {
+        let (tx, rx) = mpsc::channel();
+
+        for (username, side) in [
+            (format!("attacker-{i}"), Side::Attacker(tx.clone())),
+            (format!("defender-{i}"), Side::Defender(rx)),
+        ] {
+            let address = address.clone();
+            let moves = args.moves;
+
+            handles.push(thread::spawn(move || {
+                bot(&address, &username, side, moves)
+                    .map_err(|error| anyhow::Error::msg(format!("{username}: {error}")))
+            }));
+        }
+    }
+
+    for handle in handles {
+        match handle.join() {
+            Ok(result) => result?,
+            Err(_) => return Err(anyhow::Error::msg("a bot panicked")),
+        }
+    }
+
+    let t1 = Instant::now();
+    println!(
+        "{} bots played {} games: {:?}",
+        args.games * 2,
+        args.games,
+        t1 - t0
+    );
+
+    check_archived_games(&address, args.games)?;
+    println!("the server archived every game");
+
+    Ok(())
+}
+
+fn wait_for_server(address: &str) -> anyhow::Result<()> {
+    for _ in 0..100 {
+        if TcpStream::connect(address).is_ok() {
+            return Ok(());
+        }
+
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    Err(anyhow::Error::msg(format!(
+        "the server never started listening on {address}"
+    )))
+}
+
+fn bot(address: &str, username: &str, side: Side, moves: usize) -> anyhow::Result<()> {
+    let mut tcp = TcpStream::connect(address)?;
+    let mut reader = BufReader::new(tcp.try_clone()?);
+
+    tcp.write_all(format!("{VERSION_ID} create_account {username}\n").as_bytes())?;
+    read_until(&mut reader, "= login")?;
+
+    let id = match side {
+        Side::Attacker(tx) => {
+            let new_game = NewGame {
+                role: Role::Attacker,
+                rated: false,
+                time_settings: TimeSettings::UnTimed,
+                board_size: 11,
+                established_only: false,
+                zen: false,
+                rule_set: RuleSet::default(),
+            };
+            let new_game = serde_json::to_string(&new_game)?;
+
+            tcp.write_all(format!("new_game {new_game}\n").as_bytes())?;
+            let line = read_until(&mut reader, "= new_game ")?;
+            let id: Id = line.trim_start_matches("= new_game ").trim().parse()?;
+            tx.send(id)?;
+
+            read_until(&mut reader, &format!("= challenge_requested {id}"))?;
+            tcp.write_all(format!("join_game {id}\n").as_bytes())?;
+            id
+        }
+        Side::Defender(rx) => {
+            let id = rx.recv()?;
+            tcp.write_all(format!("join_game_pending {id}\n").as_bytes())?;
+            id
+        }
+    };
+
+    read_until(&mut reader, "= join_game ")?;
+    let id_string = id.to_string();
+    tcp.write_all(format!("text_game {id} hello from {username}\n").as_bytes())?;
+
+    let mut game = Game::make(BoardSize::_11, &TimeSettings::UnTimed);
+    let mut played = 0;
+
+    loop {
+        let line = read_line(&mut reader)?;
+        let words: Vec<_> = line.split_ascii_whitespace().collect();
+
+        match words.as_slice() {
+            ["game", game_id, "generate_move", role, ..] if *game_id == id_string => {
+                let plays = game.all_legal_plays();
+                let random = if plays.is_empty() {
+                    None
+                } else {
+                    plays.get(rand::random_range(0..plays.len()))
+                };
+
+                let play = if played < moves
+                    && let Some(play) = random
+                {
+                    play.clone()
+                } else if *role == "attacker" {
+                    Plae::AttackerResigns
+                } else {
+                    Plae::DefenderResigns
+                };
+
+                if let Plae::Play(_) = play {
+                    game.play(&play)?;
+                }
+
+                played += 1;
+                tcp.write_all(format!("game {id} {play}\n").as_bytes())?;
+            }
+            ["game", game_id, "play", role, from, to, ..] if *game_id == id_string => {
+                game.play(&Plae::try_from(vec!["play", role, from, to])?)?;
+            }
+            ["=", "game_over", game_id, ..] if *game_id == id_string => break,
+            _ => {}
+        }
+    }
+
+    tcp.write_all(b"logout\n")?;
+    Ok(())
+}
+
+fn check_archived_games(address: &str, games: usize) -> anyhow::Result<()> {
+    let mut tcp = TcpStream::connect(address)?;
+    let mut reader = BufReader::new(tcp.try_clone()?);
+
+    tcp.write_all(format!("{VERSION_ID} create_account checker\n").as_bytes())?;
+    read_until(&mut reader, "= login")?;
+
+    // The server sends a page of games at a time, so ask for them by id.
+    let page = Id::try_from(ARCHIVED_GAMES_PAGE)?;
+    let mut archived_games = Vec::new();
+    let mut first = 0;
+
+    loop {
+        tcp.write_all(format!("archived_games ids {first} {}\n", first + page - 1).as_bytes())?;
+        let line = read_until(&mut reader, "= archived_games ")?;
+        let length: usize = line
+            .trim_start_matches("= archived_games ")
+            .trim()
+            .parse()?;
+
+        let mut buf = vec![0; length];
+        reader.read_exact(&mut buf)?;
+        let games: Vec<ArchivedGame> = postcard::from_bytes(&buf)?;
+
+        if games.is_empty() {
+            break;
+        }
+
+        archived_games.extend(games);
+        first += page;
+    }
+
+    if archived_games.len() != games {
+        return Err(anyhow::Error::msg(format!(
+            "expected {games} archived games, found {}",
+            archived_games.len()
+        )));
+    }
+
+    for game in &archived_games {
+        let Some(i) = game.attacker.strip_prefix("attacker-") else {
+            return Err(anyhow::Error::msg(format!(
+                "unexpected attacker {}",
+                game.attacker
+            )));
+        };
+
+        if game.defender != format!("defender-{i}") {
+            return Err(anyhow::Error::msg(format!(
+                "{} played {}",
+                game.attacker, game.defender
+            )));
+        }
+
+        if game.status == Status::Ongoing {
+            return Err(anyhow::Error::msg(format!("game {} never ended", game.id)));
+        }
+
+        let texts = game
+            .messages
+            .iter()
+            .filter(|message| {
+                message.username == game.attacker || message.username == game.defender
+            })
+            .count();
+
+        if texts != 2 {
+            return Err(anyhow::Error::msg(format!(
+                "game {} has {texts} messages from the players, expected 2",
+                game.id,
+            )));
+        }
+    }
+
+    tcp.write_all(b"logout\n")?;
+    Ok(())
+}
+
+fn read_line(reader: &mut BufReader<TcpStream>) -> anyhow::Result<String> {
+    let mut buf = String::new();
+
+    if reader.read_line(&mut buf)? == 0 {
+        return Err(anyhow::Error::msg("the server closed the connection"));
+    }
+
+    if buf.starts_with('?') {
+        return Err(anyhow::Error::msg(format!(
+            "the server replied: {}",
+            buf.trim()
+        )));
+    }
+
+    Ok(buf)
+}
+
+/// Reads lines until one starts with `prefix`, skipping the updates the server
+/// sends to everyone.
+fn read_until(reader: &mut BufReader<TcpStream>, prefix: &str) -> anyhow::Result<String> {
+    loop {
+        let line = read_line(reader)?;
+
+        if line.starts_with(prefix) {
+            return Ok(line);
+        }
+    }
+}
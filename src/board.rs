@@ -42,6 +42,35 @@ use crate::{
 
 pub const BOARD_LETTERS: &str = " A B C D E F G H I J K L M ";
 
+/// A random number for each kind of piece on each square, the same every
+/// time, so a position hashes to the XOR of the ones for its pieces.
+static ZOBRIST: [[u64; 3]; 13 * 13] = zobrist_keys();
+
+const fn zobrist_keys() -> [[u64; 3]; 13 * 13] {
+    let mut keys = [[0; 3]; 13 * 13];
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+    let mut square = 0;
+
+    while square < 13 * 13 {
+        let mut piece = 0;
+
+        while piece < 3 {
+            // SplitMix64.
+            state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut key = state;
+            key = (key ^ (key >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            key = (key ^ (key >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            keys[square][piece] = key ^ (key >> 31);
+
+            piece += 1;
+        }
+
+        square += 1;
+    }
+
+    keys
+}
+
 pub const STARTING_POSITION_11X11: [&str; 11] = [
     "...XXXXX...",
     ".....X.....",
@@ -301,6 +330,24 @@ impl Board {
         }
     }
 
+    /// The Zobrist hash of the position, which only depends on where the
+    /// pieces are.
+    #[must_use]
+    pub fn zobrist(&self) -> u64 {
+        let mut hash = 0;
+
+        for (keys, space) in ZOBRIST.iter().zip(&self.spaces) {
+            match space {
+                Space::Empty => {}
+                Space::Attacker => hash ^= keys[0],
+                Space::Defender => hash ^= keys[1],
+                Space::King => hash ^= keys[2],
+            }
+        }
+
+        hash
+    }
+
     fn able_to_move(&self, play_from: &Vertex) -> bool {
         if let Some(vertex) = play_from.up()
             && self.get(&vertex) == Space::Empty
@@ -1007,6 +1054,43 @@ impl Board {
         }
     }
 
+    /// Whether `play`, already made on the board, could have surrounded the
+    /// king and every defender when they weren't surrounded before it, so the
+    /// flood fill only runs when it might find something new.
+    ///
+    /// A defender's move leaves the same squares free of attackers, and its
+    /// captures only free up more. For an attacker's move, taking a square
+    /// that isn't on the edge and has at most one free square next to it
+    /// can't split the free squares apart. The square it left can join the
+    /// king's pocket to one holding the last defenders, unless it has at most
+    /// one free square next to it or is on the edge, where the joined pocket
+    /// would reach the edge too.
+    fn may_surround(&self, play: &Play, captures: &FxHashSet<Vertex>) -> bool {
+        if play.role != Role::Attacker {
+            return false;
+        }
+
+        if !captures.is_empty() {
+            return true;
+        }
+
+        self.free_neighbors(&play.to).is_none_or(|free| free > 1)
+            || self.free_neighbors(&play.from).is_some_and(|free| free > 1)
+    }
+
+    /// How many of the squares next to `vertex` hold no attacker, or `None`
+    /// if `vertex` is on the edge.
+    fn free_neighbors(&self, vertex: &Vertex) -> Option<usize> {
+        let mut free = 0;
+        for vertex in [vertex.up(), vertex.left(), vertex.down(), vertex.right()] {
+            if Role::from(self.get(&vertex?)) != Role::Attacker {
+                free += 1;
+            }
+        }
+
+        Some(free)
+    }
+
     #[inline]
     fn flood_fill_attacker_wins(&self) -> bool {
         let size = self.size();
@@ -1014,7 +1098,8 @@ impl Board {
 
         match self.king {
             Some(kings_vertex) => {
-                let mut already_checked = vec![0; board_size_usize * board_size_usize];
+                // On the stack, as this runs after most of the attackers' moves.
+                let mut already_checked = [0; 13 * 13];
                 already_checked[usize::from(&kings_vertex)] += 1;
 
                 let mut stack = Vec::with_capacity(32);
@@ -1361,7 +1446,7 @@ impl Board {
             board.king = Some(play.to);
         }
 
-        if turn == &Role::Defender && previous_boards.0.contains(&board) {
+        if turn == &Role::Defender && previous_boards.contains(&board) {
             return Err(InvalidMove::RepeatMove);
         }
 
@@ -1396,7 +1481,7 @@ impl Board {
         previous_boards: &mut PreviousBoards,
     ) -> Result<(FxHashSet<Vertex>, Status), InvalidMove> {
         let (board, captures, status) = self.play_internal(play, status, turn, previous_boards)?;
-        previous_boards.0.push(board.clone());
+        previous_boards.push(&board);
         *self = board;
 
         Ok((captures, status))
@@ -1533,14 +1618,12 @@ impl Board {
 
                     spaces.push(Space::King);
                 }
-                '/' => {
-                    if !number.is_empty() {
-                        let empty_spaces = number.parse()?;
-                        for _ in 0..empty_spaces {
-                            spaces.push(Space::Empty);
-                        }
-                        number = String::new();
+                '/' if !number.is_empty() => {
+                    let empty_spaces = number.parse()?;
+                    for _ in 0..empty_spaces {
+                        spaces.push(Space::Empty);
                     }
+                    number = String::new();
                 }
                 ch if ch.is_ascii_digit() => number.push(ch),
                 _ => {}
@@ -1625,7 +1708,7 @@ impl Board {
             return Ok((board, captures, Status::DefenderWins));
         }
 
-        if board.flood_fill_attacker_wins() {
+        if board.may_surround(play, &captures) && board.flood_fill_attacker_wins() {
             return Ok((board, captures, Status::AttackerWins));
         }
 
@@ -1636,6 +1719,27 @@ impl Board {
         Ok((board, captures, Status::Ongoing))
     }
 
+    /// Takes the pieces `play` captures off a board where it was already
+    /// made, the same as a move does, so it can be measured on its own.
+    #[cfg(feature = "bench")]
+    pub fn resolve_captures(&mut self, play: &Play) -> FxHashSet<Vertex> {
+        let role_from = play.role;
+        let mut captures = FxHashSet::default();
+
+        self.captures(&play.to, role_from, &mut captures);
+        self.captures_shield_wall(role_from, &play.to, &mut captures);
+        self.capture_the_king(role_from, &play.to, &mut captures);
+
+        captures
+    }
+
+    /// Whether the attackers surround the king and every defender.
+    #[cfg(feature = "bench")]
+    #[must_use]
+    pub fn surrounded(&self) -> bool {
+        self.flood_fill_attacker_wins()
+    }
+
     fn set(&mut self, vertex: &Vertex, space: Space) {
         let board_size: usize = self.size().into();
         self.spaces[vertex.y * board_size + vertex.x] = space;
@@ -2313,6 +2417,8 @@ pub enum InvalidMove {
     Turn,
     #[error("play: the game has to be ongoing to play")]
     NotOngoing,
+    #[error("play: the game is at move {0}")]
+    MoveNumber(usize),
     #[error("play: you can't resign for the other player")]
     InvalidResign,
     #[error("play: invalid vertex")]
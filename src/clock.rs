@@ -0,0 +1,40 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! The time the server goes by. It's the system time, except while a journal
+//! is replayed, when it's the time each message was handled.
+
+use std::cell::Cell;
+
+use jiff::Timestamp;
+
+thread_local! {
+    static REPLAYED: Cell<Option<Timestamp>> = const { Cell::new(None) };
+}
+
+/// The time now, or the time the message being replayed was handled.
+#[must_use]
+pub fn now() -> Timestamp {
+    REPLAYED.with(Cell::get).unwrap_or_else(Timestamp::now)
+}
+
+/// Stops the clock at `timestamp` on this thread, or starts it again with
+/// `None`.
+pub fn set(timestamp: Option<Timestamp>) {
+    REPLAYED.with(|replayed| replayed.set(timestamp));
+}
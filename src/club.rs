@@ -0,0 +1,84 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! Clubs: players who band together, with a page that says what the club is
+//! about, a chat of their own, and tournaments only the members may join.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::{clock, invalid_username};
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Club {
+    pub name: String,
+    /// Who made the club, the only one who may change it.
+    pub owner: String,
+    pub description: String,
+    /// The owner is a member too.
+    pub members: BTreeSet<String>,
+    /// Who the owner invited, only they may join.
+    #[serde(default)]
+    pub invited: BTreeSet<String>,
+    pub created: Timestamp,
+}
+
+impl Club {
+    #[must_use]
+    pub fn new(name: &str, owner: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            owner: owner.to_string(),
+            description: String::new(),
+            members: BTreeSet::from([owner.to_string()]),
+            invited: BTreeSet::new(),
+            created: clock::now(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Clubs(pub BTreeMap<String, Club>);
+
+impl Clubs {
+    /// Whether `username` is a member of the club called `name`.
+    #[must_use]
+    pub fn is_member(&self, name: &str, username: &str) -> bool {
+        self.0
+            .get(name)
+            .is_some_and(|club| club.members.contains(username))
+    }
+
+    /// Takes `username` out of every club, and gets rid of the ones they own.
+    pub fn remove_member(&mut self, username: &str) {
+        self.0.retain(|_, club| club.owner != username);
+
+        for club in self.0.values_mut() {
+            club.members.remove(username);
+            club.invited.remove(username);
+        }
+    }
+}
+
+/// Club names follow the same rules as usernames.
+#[must_use]
+pub fn invalid_name(name: &str) -> bool {
+    name.is_empty() || invalid_username(name)
+}
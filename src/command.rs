@@ -0,0 +1,97 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! The commands a player can type instead of clicking: `f6-f9` moves a
+//! piece, `/say hi` chats, `/resign` resigns and `/draw` offers a draw.
+
+use anyhow::Context;
+
+use crate::{
+    board::BoardSize,
+    play::{BOARD_LETTERS, Vertex},
+};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Command {
+    /// `/draw`
+    Draw,
+
+    /// `FROM-TO`
+    Play { from: Vertex, to: Vertex },
+
+    /// `/resign`
+    Resign,
+
+    /// `/say TEXT`
+    Say(String),
+}
+
+impl Command {
+    /// Parses a command, the coordinates of a move may be uppercase or
+    /// lowercase whatever the size of the board.
+    ///
+    /// # Errors
+    ///
+    /// If the command is unknown, `/say` has nothing to say, or a move is not
+    /// `FROM-TO` with both squares on the board.
+    pub fn parse(command: &str, board_size: BoardSize) -> anyhow::Result<Self> {
+        let command = command.trim();
+
+        if let Some(command) = command.strip_prefix('/') {
+            let (name, text) = command.split_once(' ').unwrap_or((command, ""));
+
+            return match name {
+                "draw" => Ok(Self::Draw),
+                "resign" => Ok(Self::Resign),
+                "say" if !text.trim().is_empty() => Ok(Self::Say(text.trim().to_string())),
+                "say" => Err(anyhow::Error::msg("expected: /say TEXT")),
+                name => Err(anyhow::Error::msg(format!("unknown command: /{name}"))),
+            };
+        }
+
+        let (from, to) = command
+            .split_once('-')
+            .context("expected: FROM-TO, /draw, /resign, or /say TEXT")?;
+
+        Ok(Self::Play {
+            from: vertex(from, board_size)?,
+            to: vertex(to, board_size)?,
+        })
+    }
+}
+
+fn vertex(vertex: &str, board_size: BoardSize) -> anyhow::Result<Vertex> {
+    let size: usize = board_size.into();
+    let mut chars = vertex.trim().chars();
+
+    let ch = chars.next().context("expected: a square")?;
+    let x = BOARD_LETTERS[..size]
+        .find(ch.to_ascii_uppercase())
+        .with_context(|| format!("{vertex} is not on the board"))?;
+
+    let y: usize = chars.as_str().parse()?;
+    if y == 0 || y > size {
+        return Err(anyhow::Error::msg(format!("{vertex} is not on the board")));
+    }
+
+    Ok(Vertex {
+        size: board_size,
+        x,
+        y: size - y,
+    })
+}
@@ -0,0 +1,152 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! Community games: the spectators vote on the moves for one side and the
+//! server plays the move with the most votes. The other side is a player or
+//! the server's engine. In any game the spectators can
+//! also guess the next moves in a [`Quiz`].
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::play::Play;
+
+/// The player name of the side the spectators vote for. It is not a valid
+/// username, so nobody can create an account with it.
+pub const COMMUNITY: &str = "(community)";
+
+/// The player name of the engine the spectators play against. It is not a
+/// valid username either.
+pub const AI: &str = "(ai)";
+
+/// How deep the engine searches for its moves.
+pub const AI_DEPTH: u8 = 4;
+
+/// Whether the player is the community or the engine, neither of which has an
+/// account.
+#[must_use]
+pub fn no_account(username: &str) -> bool {
+    username == COMMUNITY || username == AI
+}
+
+/// How long the voting stays open after the first vote.
+pub const VOTE_MILLISECONDS: i64 = 20_000;
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Votes {
+    /// When the voting closes, in milliseconds since the Unix epoch.
+    pub closes: i64,
+    pub votes: HashMap<String, Play>,
+}
+
+impl Votes {
+    /// Counts every move voted for, the most popular first.
+    #[must_use]
+    pub fn tally(&self) -> Vec<(Play, usize)> {
+        let mut tally = BTreeMap::new();
+
+        for play in self.votes.values() {
+            *tally.entry(play.clone()).or_insert(0) += 1;
+        }
+
+        let mut tally: Vec<_> = tally.into_iter().collect();
+        tally.sort_by(|(_, count_1), (_, count_2)| count_2.cmp(count_1));
+        tally
+    }
+
+    /// Records or changes a spectator's vote, opening the voting on the
+    /// first one.
+    pub fn vote(&mut self, username: &str, play: Play, now: i64) {
+        if self.votes.is_empty() {
+            self.closes = now + VOTE_MILLISECONDS;
+        }
+
+        self.votes.insert(username.to_string(), play);
+    }
+
+    /// The move with the most votes. Ties go to the move that sorts first.
+    #[must_use]
+    pub fn winner(&self) -> Option<Play> {
+        self.tally().into_iter().next().map(|(play, _)| play)
+    }
+}
+
+/// The spectators guessing the next move, for fun. Unlike [`Votes`], the
+/// guesses never change the game.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Quiz {
+    /// The guesses at the move being thought about.
+    pub guesses: HashMap<String, Play>,
+    pub scores: BTreeMap<String, Score>,
+}
+
+impl Quiz {
+    /// Records or changes a spectator's guess at the next move.
+    pub fn guess(&mut self, username: &str, play: Play) {
+        self.guesses.insert(username.to_string(), play);
+    }
+
+    /// Scores the guesses at the move that was played.
+    pub fn played(&mut self, play: &Play) {
+        for (username, guess) in self.guesses.drain() {
+            let score = self.scores.entry(username).or_default();
+            score.guessed += 1;
+
+            if guess == *play {
+                score.right += 1;
+            }
+        }
+    }
+
+    /// Everybody's score, the most right first.
+    #[must_use]
+    pub fn results(&self) -> Vec<(String, Score)> {
+        let mut results: Vec<_> = self
+            .scores
+            .iter()
+            .map(|(username, score)| (username.clone(), *score))
+            .collect();
+
+        results.sort_by(|(_, score_1), (_, score_2)| {
+            score_2
+                .percent()
+                .total_cmp(&score_1.percent())
+                .then(score_2.right.cmp(&score_1.right))
+        });
+
+        results
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Score {
+    pub right: u32,
+    pub guessed: u32,
+}
+
+impl Score {
+    #[must_use]
+    pub fn percent(&self) -> f64 {
+        if self.guessed == 0 {
+            0.0
+        } else {
+            100.0 * f64::from(self.right) / f64::from(self.guessed)
+        }
+    }
+}
@@ -16,6 +16,8 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 // SPDX-FileCopyrightText: 2025 David Campbell <david@hnefatafl.org>
 
+use std::{fmt, str::FromStr};
+
 #[cfg(feature = "server")]
 use lettre::message::Mailbox;
 use serde::{Deserialize, Serialize};
@@ -49,3 +51,83 @@ impl Email {
         format!("{} <{}>", self.username, self.address)
     }
 }
+
+/// The emails you can unsubscribe from. Account verification and password
+/// reset emails always get sent.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum EmailCategory {
+    Announcements,
+    Tournaments,
+    TurnNotifications,
+}
+
+impl EmailCategory {
+    pub const ALL: [Self; 3] = [
+        Self::Announcements,
+        Self::Tournaments,
+        Self::TurnNotifications,
+    ];
+}
+
+impl fmt::Display for EmailCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Announcements => write!(f, "announcements"),
+            Self::Tournaments => write!(f, "tournaments"),
+            Self::TurnNotifications => write!(f, "turn_notifications"),
+        }
+    }
+}
+
+impl FromStr for EmailCategory {
+    type Err = anyhow::Error;
+
+    fn from_str(category: &str) -> anyhow::Result<Self> {
+        match category {
+            "announcements" => Ok(Self::Announcements),
+            "tournaments" => Ok(Self::Tournaments),
+            "turn_notifications" => Ok(Self::TurnNotifications),
+            _ => Err(anyhow::Error::msg(format!(
+                "unknown email category: {category}"
+            ))),
+        }
+    }
+}
+
+/// Which emails an account wants, everything unless they unsubscribe.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct EmailPreferences {
+    pub announcements: bool,
+    pub tournaments: bool,
+    pub turn_notifications: bool,
+}
+
+impl EmailPreferences {
+    #[must_use]
+    pub fn get(&self, category: EmailCategory) -> bool {
+        match category {
+            EmailCategory::Announcements => self.announcements,
+            EmailCategory::Tournaments => self.tournaments,
+            EmailCategory::TurnNotifications => self.turn_notifications,
+        }
+    }
+
+    pub fn set(&mut self, category: EmailCategory, send: bool) {
+        match category {
+            EmailCategory::Announcements => self.announcements = send,
+            EmailCategory::Tournaments => self.tournaments = send,
+            EmailCategory::TurnNotifications => self.turn_notifications = send,
+        }
+    }
+}
+
+impl Default for EmailPreferences {
+    fn default() -> Self {
+        Self {
+            announcements: true,
+            tournaments: true,
+            turn_notifications: true,
+        }
+    }
+}
@@ -18,7 +18,7 @@
 
 use std::{
     borrow::Cow,
-    collections::{BinaryHeap, HashMap},
+    collections::{BinaryHeap, HashMap, VecDeque},
     fmt,
     hash::{DefaultHasher, Hash, Hasher},
     process::exit,
@@ -26,8 +26,8 @@ use std::{
 };
 
 use colored::Colorize;
-use jiff::Timestamp;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use rust_i18n::t;
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "js")]
@@ -37,9 +37,11 @@ use crate::{
     ai::{AI, AiBasic},
     board::{Board, BoardSize, Captured, InvalidMove},
     characters::Characters,
+    clock,
     message::{COMMANDS, Message},
     play::{Captures, Plae, Play, PlayRecordTimed, Plays, Vertex},
     role::Role,
+    space::Space,
     status::Status,
     time::{TimeSettings, TimeUnix},
     tree::Tree,
@@ -86,16 +88,177 @@ pub struct Game {
     pub chars: Characters,
 }
 
+/// How many of the latest boards [`PreviousBoards`] keeps whole.
+const RECENT_BOARDS: usize = 8;
+
+/// How many plays the opening lasts, if nothing gets captured first.
+const OPENING_PLAYS: usize = 12;
+
+/// The king is loose in the endgame when it has this many moves.
+const ENDGAME_KING_MOVES: usize = 10;
+
+/// The king is in reach of a corner in the endgame.
+const ENDGAME_MOVES_TO_ESCAPE: u8 = 2;
+
+/// Every position in the game so far, for finding repeated ones. Only the
+/// first board and the latest few are kept whole, the rest are Zobrist
+/// hashes. A hash that matches one of the boards kept whole is checked
+/// against the board.
+///
+/// It serializes as the boards it keeps, so [`Game::replay`] gets the rest
+/// back from the plays.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
-pub struct PreviousBoards(pub Vec<Board>);
+#[serde(from = "Vec<Board>", into = "Vec<Board>")]
+pub struct PreviousBoards {
+    first: Board,
+    recent: VecDeque<Board>,
+    hashes: Vec<u64>,
+    counts: FxHashMap<u64, usize>,
+}
 
 impl PreviousBoards {
     #[must_use]
     pub fn new(board_size: BoardSize) -> Self {
-        let mut boards = Vec::with_capacity(128);
+        Self::from(Board::new(board_size))
+    }
+
+    /// Forgets every position, so none of them count as repeated.
+    pub fn clear(&mut self) {
+        self.recent.clear();
+        self.hashes.clear();
+        self.counts.clear();
+    }
+
+    /// Whether the position on `board` came up before.
+    #[must_use]
+    pub fn contains(&self, board: &Board) -> bool {
+        self.count(board) > 0
+    }
+
+    /// How many times the position on `board` came up.
+    #[must_use]
+    pub fn count(&self, board: &Board) -> usize {
+        let hash = board.zobrist();
+        let Some(count) = self.counts.get(&hash) else {
+            return 0;
+        };
+
+        // The hashes of the recent boards not on `board` are collisions.
+        let collisions = self
+            .recent
+            .iter()
+            .filter(|recent| recent.zobrist() == hash && *recent != board)
+            .count();
 
-        boards.push(Board::new(board_size));
-        Self(boards)
+        count.saturating_sub(collisions)
+    }
+
+    /// The position the game started from.
+    #[must_use]
+    pub fn first(&self) -> &Board {
+        &self.first
+    }
+
+    /// The boards kept whole.
+    pub fn boards_mut(&mut self) -> impl Iterator<Item = &mut Board> {
+        std::iter::once(&mut self.first).chain(self.recent.iter_mut())
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// Forgets the latest position and returns the one before it, if it is
+    /// still kept whole.
+    pub fn pop(&mut self) -> Option<Board> {
+        let hash = self.hashes.pop()?;
+
+        if let Some(count) = self.counts.get_mut(&hash) {
+            *count -= 1;
+
+            if *count == 0 {
+                self.counts.remove(&hash);
+            }
+        }
+
+        self.recent.pop_back();
+
+        match self.recent.back() {
+            Some(board) => Some(board.clone()),
+            None if self.hashes.len() == 1 => Some(self.first.clone()),
+            None => None,
+        }
+    }
+
+    pub fn push(&mut self, board: &Board) {
+        let hash = board.zobrist();
+
+        self.hashes.push(hash);
+        *self.counts.entry(hash).or_default() += 1;
+
+        if self.recent.len() < RECENT_BOARDS {
+            self.recent.push_back(board.clone());
+        } else if let Some(mut oldest) = self.recent.pop_front() {
+            oldest.clone_from(board);
+            self.recent.push_back(oldest);
+        }
+    }
+}
+
+impl From<Board> for PreviousBoards {
+    fn from(board: Board) -> Self {
+        let mut previous_boards = Self {
+            first: board.clone(),
+            recent: VecDeque::with_capacity(RECENT_BOARDS),
+            hashes: Vec::with_capacity(128),
+            counts: FxHashMap::default(),
+        };
+
+        previous_boards.push(&board);
+        previous_boards
+    }
+}
+
+impl From<Vec<Board>> for PreviousBoards {
+    fn from(boards: Vec<Board>) -> Self {
+        let mut boards = boards.into_iter();
+        let Some(first) = boards.next() else {
+            let mut previous_boards = Self::default();
+            previous_boards.clear();
+            return previous_boards;
+        };
+
+        let mut previous_boards = Self::from(first);
+        for board in boards {
+            previous_boards.push(&board);
+        }
+
+        previous_boards
+    }
+}
+
+impl From<PreviousBoards> for Vec<Board> {
+    fn from(previous_boards: PreviousBoards) -> Self {
+        if previous_boards.is_empty() {
+            return Vec::new();
+        }
+
+        // The first board is also the first recent one until there are more.
+        let skip = usize::from(
+            previous_boards.hashes.len() <= RECENT_BOARDS
+                && previous_boards.recent.front() == Some(&previous_boards.first),
+        );
+
+        let mut boards = vec![previous_boards.first];
+        boards.extend(previous_boards.recent.into_iter().skip(skip));
+
+        boards
     }
 }
 
@@ -425,6 +588,93 @@ impl Game {
 
     #[must_use]
     pub fn all_legal_moves(&self) -> LegalMoves {
+        self.moves_where(Result::is_ok)
+    }
+
+    /// When the player whose turn it is runs out of time, in milliseconds
+    /// since the epoch, if the game is timed and still going.
+    #[must_use]
+    pub fn flags_at(&self) -> Option<i64> {
+        let timer = match self.turn {
+            Role::Attacker => &self.attacker_time,
+            Role::Defender => &self.defender_time,
+            Role::Roleless => return None,
+        };
+
+        if self.status == Status::Ongoing
+            && let (TimeSettings::Timed(timer), TimeUnix::Time(time)) = (timer, self.time)
+        {
+            Some(time + timer.milliseconds_left)
+        } else {
+            None
+        }
+    }
+
+    /// The Zobrist hash of the board in hexadecimal, which the server sends
+    /// with its game messages so a client can tell when its board went out of
+    /// step.
+    #[must_use]
+    pub fn checksum(&self) -> String {
+        format!("{:016x}", self.board.zobrist())
+    }
+
+    /// Whether the position on the board came up earlier in the game.
+    #[must_use]
+    pub fn position_repeated(&self) -> bool {
+        self.previous_boards.count(&self.board) > 1
+    }
+
+    /// Plays the first `plays` plays again from the position the game
+    /// started from, returning the board after them and every position
+    /// along the way.
+    #[must_use]
+    pub fn replay(&self, plays: usize) -> Option<(Board, PreviousBoards)> {
+        let mut board = self.previous_boards.first().clone();
+        let size = board.size();
+        let size_usize: usize = size.into();
+
+        // The king isn't serialized.
+        board.king = (0..size_usize * size_usize)
+            .map(|i| Vertex {
+                size,
+                x: i % size_usize,
+                y: i / size_usize,
+            })
+            .find(|vertex| board.get(vertex) == Space::King);
+
+        let mut previous_boards = PreviousBoards::from(board.clone());
+
+        let moves: Vec<_> = match &self.plays {
+            Plays::PlayRecordsTimed(records) => records
+                .iter()
+                .filter_map(|record| record.play.as_ref())
+                .collect(),
+            Plays::PlayRecords(records) => records.iter().flatten().collect(),
+        };
+
+        for plae in moves.into_iter().take(plays) {
+            let Plae::Play(play) = plae else {
+                break;
+            };
+
+            board
+                .play(plae, &Status::Ongoing, &play.role, &mut previous_boards)
+                .ok()?;
+        }
+
+        Some((board, previous_boards))
+    }
+
+    /// The moves that would be legal if they didn't bring back a position
+    /// from earlier in the game.
+    #[must_use]
+    pub fn repetition_moves(&self) -> LegalMoves {
+        self.moves_where(|result| matches!(result, Err(InvalidMove::RepeatMove)))
+    }
+
+    /// The moves of the player whose turn it is where `keep` is true of the
+    /// result of checking the move.
+    fn moves_where(&self, keep: impl Fn(&Result<Board, InvalidMove>) -> bool) -> LegalMoves {
         let size = self.board.size();
         let board_size_usize = size.into();
         let vec_capacity = match size {
@@ -462,11 +712,12 @@ impl Game {
                     to: vertex_to,
                 };
 
-                if self
-                    .board
-                    .legal_move(&play, &self.status, &self.turn, &self.previous_boards)
-                    .is_ok()
-                {
+                if keep(&self.board.legal_move(
+                    &play,
+                    &self.status,
+                    &self.turn,
+                    &self.previous_boards,
+                )) {
                     vertexes_to.push(vertex_to);
                 }
             }
@@ -483,11 +734,12 @@ impl Game {
                     to: vertex_to,
                 };
 
-                if self
-                    .board
-                    .legal_move(&play, &self.status, &self.turn, &self.previous_boards)
-                    .is_ok()
-                {
+                if keep(&self.board.legal_move(
+                    &play,
+                    &self.status,
+                    &self.turn,
+                    &self.previous_boards,
+                )) {
                     vertexes_to.push(vertex_to);
                 }
             }
@@ -681,6 +933,32 @@ impl Game {
         (MovesToEscape::CanNotEscape, escape_vec)
     }
 
+    /// A guess at how far along the game is: the endgame once half of the
+    /// pieces are gone, the king is loose, or it is close to a corner.
+    #[must_use]
+    pub fn phase(&self) -> Phase {
+        let captured = self.board.captured();
+        let taken = captured.attacker + captured.defender;
+        let pieces = match self.board.size() {
+            BoardSize::_11 => 36,
+            BoardSize::_13 => 48,
+        };
+
+        let king_moves = self.kings_legal_moves().map_or(0, |(_, moves)| moves.len());
+        let escape_close = matches!(
+            self.moves_to_escape().0,
+            MovesToEscape::Moves(moves) if moves <= ENDGAME_MOVES_TO_ESCAPE
+        );
+
+        if 2 * taken >= pieces || king_moves >= ENDGAME_KING_MOVES || escape_close {
+            Phase::Endgame
+        } else if taken == 0 && self.plays.made().len() < OPENING_PLAYS {
+            Phase::Opening
+        } else {
+            Phase::Middlegame
+        }
+    }
+
     #[allow(clippy::missing_panics_doc)]
     #[must_use]
     pub fn obvious_play(&self) -> Option<Plae> {
@@ -736,7 +1014,7 @@ impl Game {
                     &mut self.time,
                 ),
             } {
-                let now = Timestamp::now().as_millisecond();
+                let now = clock::now().as_millisecond();
                 timer.milliseconds_left -= now - *time;
                 *time = now;
 
@@ -873,7 +1151,7 @@ impl Game {
             Message::BoardSize(size) => {
                 let board_size = BoardSize::try_from(size)?;
 
-                let time_settings = if self.previous_boards.0.len() == 1 {
+                let time_settings = if self.previous_boards.len() == 1 {
                     self.defender_time
                 } else {
                     TimeSettings::UnTimed
@@ -886,7 +1164,7 @@ impl Game {
             Message::Empty => Ok(None),
             Message::FinalStatus => Ok(Some(format!("{}", self.status))),
             Message::FirstMove(role) => {
-                let time_settings = if self.previous_boards.0.len() == 1 {
+                let time_settings = if self.previous_boards.len() == 1 {
                     self.defender_time
                 } else {
                     TimeSettings::UnTimed
@@ -952,9 +1230,17 @@ impl Game {
                 }
             }
             Message::PlayUndo => {
-                if self.previous_boards.0.len() > 1 && self.previous_boards.0.pop().is_some() {
-                    if let Some(board) = self.previous_boards.0.last() {
-                        self.board = board.clone();
+                if self.previous_boards.len() > 1 {
+                    match self.previous_boards.pop() {
+                        Some(board) => self.board = board,
+                        None => {
+                            if let Some((board, previous_boards)) =
+                                self.replay(self.plays.len().saturating_sub(1))
+                            {
+                                self.board = board;
+                                self.previous_boards = previous_boards;
+                            }
+                        }
                     }
 
                     self.turn = if self.plays.len().is_multiple_of(2) {
@@ -1047,6 +1333,9 @@ pub struct GameTime {
     pub id: u128,
     pub attacker_ms_left: i64,
     pub defender_ms_left: i64,
+    /// When the times were right on the server, in milliseconds.
+    #[serde(default)]
+    pub timestamp: i64,
 }
 
 #[derive(Clone, Debug)]
@@ -1104,6 +1393,38 @@ impl fmt::Display for EscapeVec {
     }
 }
 
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+)]
+pub enum Phase {
+    Opening,
+    #[default]
+    Middlegame,
+    Endgame,
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Opening => write!(f, "{}", t!("Opening")),
+            Self::Middlegame => write!(f, "{}", t!("Middlegame")),
+            Self::Endgame => write!(f, "{}", t!("Endgame")),
+        }
+    }
+}
+
+impl Phase {
+    /// How much of its thinking time the AI spends on a move: the opening
+    /// doesn't need a long think.
+    #[must_use]
+    pub fn time_share(self) -> f64 {
+        match self {
+            Self::Opening => 0.5,
+            Self::Middlegame | Self::Endgame => 1.0,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum MovesToEscape {
     CanNotEscape,
@@ -18,6 +18,7 @@
 
 use std::{
     fmt,
+    mem::size_of,
     time::{Duration, Instant},
 };
 
@@ -26,17 +27,32 @@ use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    ai::Cancel,
     board::BoardSize,
     game::Game,
     play::{Plae, Plays},
     status::Status,
 };
 
+/// How many bytes a tree may use unless it is told otherwise.
+pub const MEMORY: usize = 256 * 1024 * 1024;
+
+/// About how many bytes a node takes up: its entry in the arena, with the hash
+/// map filled to seven eighths, and a few children.
+const NODE_BYTES: usize = size_of::<(u64, Node)>() * 8 / 7 + 1 + 4 * size_of::<u64>();
+
 #[derive(Clone, Debug)]
 pub struct Tree {
     here: u64,
     pub game: Game,
     arena: FxHashMap<u64, Node>,
+    /// When the arena would grow past this, the least visited subtrees get
+    /// pruned.
+    max_nodes: usize,
+    /// The children of pruned nodes, emptied, to be used again.
+    spare_children: Vec<Vec<u64>>,
+    /// Checked before every playout.
+    pub cancel: Cancel,
 }
 
 impl Tree {
@@ -48,6 +64,7 @@ impl Tree {
 
         node.children.push(child_index);
         let board_size = node.board_size;
+        let children = self.spare_children.pop().unwrap_or_default();
 
         self.arena.insert(
             child_index,
@@ -57,18 +74,80 @@ impl Tree {
                 score: 0.0,
                 count: 1.0,
                 parent: Some(parent_index),
-                children: Vec::new(),
+                children,
             },
         );
     }
 
+    /// Throws out the least visited subtrees until a playout of `depth` more
+    /// nodes fits, leaving room for a quarter more so it doesn't happen every
+    /// loop.
+    fn prune(&mut self, depth: usize) {
+        if self.arena.len() + depth <= self.max_nodes {
+            return;
+        }
+
+        let keep = self.max_nodes.saturating_sub(depth) * 3 / 4;
+        let mut nodes: Vec<_> = self
+            .arena
+            .iter()
+            .filter(|(hash, node)| **hash != self.here && node.parent.is_some())
+            .map(|(hash, node)| (node.count, *hash))
+            .collect();
+
+        nodes.sort_unstable_by(|(count_1, hash_1), (count_2, hash_2)| {
+            count_1.total_cmp(count_2).then(hash_1.cmp(hash_2))
+        });
+
+        for (_, hash) in nodes {
+            if self.arena.len() <= keep {
+                break;
+            }
+
+            self.remove_subtree(hash);
+        }
+    }
+
+    /// Removes the node and everything it is the parent of. A node reached
+    /// another way only belongs to the parent it was first reached from.
+    fn remove_subtree(&mut self, hash: u64) {
+        let Some(node) = self.arena.remove(&hash) else {
+            return;
+        };
+
+        if let Some(parent) = node.parent
+            && let Some(parent) = self.arena.get_mut(&parent)
+        {
+            parent.children.retain(|child| *child != hash);
+        }
+
+        let mut stack = vec![(hash, node.children)];
+        while let Some((parent, mut children)) = stack.pop() {
+            for child in children.drain(..) {
+                if self
+                    .arena
+                    .get(&child)
+                    .is_some_and(|node| node.parent == Some(parent))
+                    && let Some(node) = self.arena.remove(&child)
+                {
+                    stack.push((child, node.children));
+                }
+            }
+
+            self.spare_children.push(children);
+        }
+    }
+
+    /// Searches for `duration`, or until it is cancelled, and returns the
+    /// children of here with their average scores. Searching again carries on
+    /// with the same tree.
     #[allow(clippy::expect_used)]
     #[allow(clippy::missing_panics_doc)]
     #[must_use]
     pub fn monte_carlo_tree_search(&mut self, duration: Duration, depth: u8) -> (u64, Vec<Node>) {
         // Doesn't seem to do much... and makes the overall search slower, about 5%.
         /*
-        if self.game.previous_boards.0.len() == 1 {
+        if self.game.previous_boards.len() == 1 {
 
         }
         */
@@ -81,10 +160,11 @@ impl Tree {
             let t1 = Instant::now();
             let elapsed_time = t1 - t0;
 
-            if duration < elapsed_time {
+            if duration < elapsed_time || self.cancel.is_cancelled() {
                 break;
             }
             loops += 1;
+            self.prune(depth.into());
 
             let mut game = self.game.clone();
             let mut here = self.here;
@@ -160,23 +240,41 @@ impl Tree {
             }
         }
 
-        for node in self.arena.values_mut() {
-            node.score /= node.count;
-            node.count = 1.0;
-        }
-
         let children = &self.arena[&self.here].children;
         (
             loops,
             children
                 .iter()
-                .map(|child| self.arena[child].clone())
+                .filter_map(|child| self.arena.get(child).cloned())
+                .map(|mut node| {
+                    node.score /= node.count;
+                    node.count = 1.0;
+                    node
+                })
                 .collect::<Vec<_>>(),
         )
     }
 
     #[must_use]
     pub fn new(game: Game) -> Self {
+        Self::with_memory(game, MEMORY)
+    }
+
+    /// How many nodes the tree may hold.
+    #[must_use]
+    pub fn max_nodes(&self) -> usize {
+        self.max_nodes
+    }
+
+    /// How many nodes are in the tree.
+    #[must_use]
+    pub fn nodes(&self) -> usize {
+        self.arena.len()
+    }
+
+    /// A tree that keeps to about `memory` bytes, not counting the game.
+    #[must_use]
+    pub fn with_memory(game: Game, memory: usize) -> Self {
         let hash = game.calculate_hash();
         let mut arena = FxHashMap::default();
         arena.insert(
@@ -195,6 +293,9 @@ impl Tree {
             here: hash,
             game,
             arena,
+            max_nodes: memory / NODE_BYTES,
+            spare_children: Vec::new(),
+            cancel: Cancel::default(),
         }
     }
 }
@@ -237,6 +338,9 @@ impl From<Game> for Tree {
             here: hash,
             game,
             arena,
+            max_nodes: MEMORY / NODE_BYTES,
+            spare_children: Vec::new(),
+            cancel: Cancel::default(),
         }
     }
 }
@@ -16,6 +16,11 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 // SPDX-FileCopyrightText: 2025 David Campbell <david@hnefatafl.org>
 
+//! [Glicko-2](http://www.glicko.net/glicko/glicko2.pdf)
+//!
+//! Ratings are stored on the original Glicko scale, so ratings saved before
+//! the upgrade from Glicko-1 load unchanged and get the default volatility.
+
 use std::{
     f64::consts::{LOG10_2, PI},
     fmt,
@@ -23,72 +28,185 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 
-/// ln 10 / 400
-const Q: f64 = 0.005_756_5;
+use crate::locale::Locale;
+
 pub const CONFIDENCE_INTERVAL_95: f64 = 1.96;
+/// A rating with a ratings deviation above this is provisional.
+pub const PROVISIONAL_RD: f64 = 110.0;
+
+/// The volatility of a new player.
+pub const VOLATILITY: f64 = 0.06;
+
+/// Converts between the Glicko and the Glicko-2 scale: 400 / ln 10.
+const SCALE: f64 = 173.717_8;
+/// The system constant, constrains the change in volatility over time.
+const TAU: f64 = 0.5;
+/// The convergence tolerance when computing the new volatility.
+const EPSILON: f64 = 0.000_001;
+
+/// How much the ratings deviation grows every 2 months without a rated game.
+///
+/// This assumes 30 2 month periods must pass before one's rating deviation is
+/// the same as a new player and that a typical RD is 50.
+const C: f64 = 63.2;
+
+const RATING_DEFAULT: f64 = 1_500.0;
+const RD_MAX: f64 = 350.0;
+const RD_MIN: f64 = 30.0;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Rating {
     pub rating: f64,
     /// Ratings Deviation
     pub rd: f64,
+    /// The degree of expected fluctuation in a player's rating.
+    #[serde(default = "volatility_default")]
+    pub volatility: f64,
 }
 
 impl Rating {
+    /// The rating is not yet trustworthy, because the player has not played
+    /// enough rated games (or has not played in a long time).
+    #[must_use]
+    pub fn provisional(&self) -> bool {
+        self.rd > PROVISIONAL_RD
+    }
+
     #[must_use]
     pub fn rd_sq(&self) -> f64 {
         self.rd * self.rd
     }
 
+    /// The rating with the digits of the messages' locale. Ratings aren't
+    /// grouped in thousands, in any locale.
     #[must_use]
     pub fn to_string_rounded(&self) -> String {
         // Note: We use a FIGURE SPACE before and after the ± so
         // .split_ascii_whitespace() does not treat it as a space.
-        format!(
-            "{} ± {}",
+        Locale::current().digits(&format!(
+            "{}{} ± {}",
             self.rating.round(),
+            if self.provisional() { "?" } else { "" },
             (CONFIDENCE_INTERVAL_95 * self.rd).round()
-        )
+        ))
     }
 
-    /// This assumes 30 2 month periods must pass before one's rating
-    /// deviation is the same as a new player and that a typical RD is 50.
-    pub fn update_rd(&mut self) {
-        let c = 63.2;
+    /// How much the rating would go up or down after a win, a draw, and a
+    /// loss against `opponent`, if it were the only game in the rating period.
+    #[must_use]
+    pub fn changes(&self, opponent: &Rating) -> [f64; 3] {
+        [Outcome::Win, Outcome::Draw, Outcome::Loss].map(|outcome| {
+            let mut rating = self.clone();
+            rating.update_rating_period(&[(opponent.clone(), outcome)]);
+            rating.rating - self.rating
+        })
+    }
 
-        let rd_new = f64::sqrt(self.rd_sq() + (c * c));
-        self.rd = rd_new.clamp(30.0, 350.0);
+    /// We run this every 2 months.
+    pub fn update_rd(&mut self) {
+        let rd_new = f64::sqrt(self.rd_sq() + (C * C));
+        self.rd = rd_new.clamp(RD_MIN, RD_MAX);
     }
 
+    /// The game is treated as a rating period of its own, against an opponent
+    /// with the ratings deviation of a new player.
     pub fn update_rating(&mut self, rating: f64, outcome: &Outcome) {
-        self.rating += (Q / (1.0 / self.rd_sq()) + (1.0 / self.d_sq(rating)))
-            * self.g()
-            * (outcome.score() - self.e(rating));
-
-        self.rd = f64::sqrt(1.0 / ((1.0 / self.rd_sq()) + (1.0 / self.d_sq(rating))));
+        let opponent = Rating {
+            rating,
+            ..Rating::default()
+        };
+        self.update_rating_period(&[(opponent, outcome.clone())]);
     }
 
-    #[must_use]
-    fn d_sq(&self, rating: f64) -> f64 {
-        1.0 / ((Q * Q) * (self.g() * self.g()) * self.e(rating) * (1.0 - self.e(rating)))
-    }
+    /// Updates the rating from all of the games played in a rating period.
+    ///
+    /// The opponents' ratings are the ones they had at the start of the period.
+    pub fn update_rating_period(&mut self, results: &[(Rating, Outcome)]) {
+        let mu = (self.rating - RATING_DEFAULT) / SCALE;
+        let phi = self.rd / SCALE;
+
+        if results.is_empty() {
+            let phi_new = f64::sqrt(phi * phi + self.volatility * self.volatility);
+            self.rd = (SCALE * phi_new).clamp(RD_MIN, RD_MAX);
+            return;
+        }
 
-    #[must_use]
-    fn e(&self, rating: f64) -> f64 {
-        1.0 / (1.0 + exp10(-self.g() * ((self.rating - rating) / 400.0)))
+        let mut v_inverse = 0.0;
+        let mut improvement = 0.0;
+
+        for (opponent, outcome) in results {
+            let mu_j = (opponent.rating - RATING_DEFAULT) / SCALE;
+            let g = g(opponent.rd / SCALE);
+            let e = e(mu, mu_j, g);
+
+            v_inverse += g * g * e * (1.0 - e);
+            improvement += g * (outcome.score() - e);
+        }
+
+        let v = 1.0 / v_inverse;
+        let delta = v * improvement;
+
+        let volatility = self.volatility_new(phi, v, delta);
+        let phi_star = f64::sqrt(phi * phi + volatility * volatility);
+        let phi_new = 1.0 / f64::sqrt(1.0 / (phi_star * phi_star) + 1.0 / v);
+        let mu_new = mu + phi_new * phi_new * improvement;
+
+        self.rating = SCALE * mu_new + RATING_DEFAULT;
+        self.rd = (SCALE * phi_new).clamp(RD_MIN, RD_MAX);
+        self.volatility = volatility;
     }
 
+    /// Step 5 of the Glicko-2 paper, the Illinois algorithm.
     #[must_use]
-    fn g(&self) -> f64 {
-        1.0 / f64::sqrt(1.0 + ((3.0 * Q * Q * self.rd_sq()) / (PI * PI)))
+    fn volatility_new(&self, phi: f64, v: f64, delta: f64) -> f64 {
+        let a = f64::ln(self.volatility * self.volatility);
+        let f = |x: f64| {
+            let e_x = x.exp();
+            let denominator = phi * phi + v + e_x;
+
+            (e_x * (delta * delta - phi * phi - v - e_x)) / (2.0 * denominator * denominator)
+                - (x - a) / (TAU * TAU)
+        };
+
+        let mut a_big = a;
+        let mut b_big = if delta * delta > phi * phi + v {
+            f64::ln(delta * delta - phi * phi - v)
+        } else {
+            let mut k = 1.0;
+            while f(a - k * TAU) < 0.0 {
+                k += 1.0;
+            }
+            a - k * TAU
+        };
+
+        let mut f_a = f(a_big);
+        let mut f_b = f(b_big);
+
+        while (b_big - a_big).abs() > EPSILON {
+            let c_big = a_big + (a_big - b_big) * f_a / (f_b - f_a);
+            let f_c = f(c_big);
+
+            if f_c * f_b <= 0.0 {
+                a_big = b_big;
+                f_a = f_b;
+            } else {
+                f_a /= 2.0;
+            }
+
+            b_big = c_big;
+            f_b = f_c;
+        }
+
+        f64::exp(a_big / 2.0)
     }
 }
 
 impl Default for Rating {
     fn default() -> Self {
         Self {
-            rating: 1_500.0,
-            rd: 350.0,
+            rating: RATING_DEFAULT,
+            rd: RD_MAX,
+            volatility: VOLATILITY,
         }
     }
 }
@@ -97,11 +215,11 @@ impl fmt::Display for Rating {
     // Note: We use a FIGURE SPACE before and after the ± so
     // .split_ascii_whitespace() does not treat it as a space.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{} ± {}", self.rating, CONFIDENCE_INTERVAL_95 * self.rd)
+        write!(f, "{} ± {}", self.rating, CONFIDENCE_INTERVAL_95 * self.rd)
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub enum Outcome {
     Draw,
     Loss,
@@ -125,6 +243,20 @@ pub fn exp10(mut exponent: f64) -> f64 {
     exponent.exp2()
 }
 
+#[must_use]
+fn e(mu: f64, mu_j: f64, g: f64) -> f64 {
+    1.0 / (1.0 + f64::exp(-g * (mu - mu_j)))
+}
+
+#[must_use]
+fn g(phi: f64) -> f64 {
+    1.0 / f64::sqrt(1.0 + (3.0 * phi * phi) / (PI * PI))
+}
+
+fn volatility_default() -> f64 {
+    VOLATILITY
+}
+
 #[cfg(test)]
 mod tests {
     use crate::glicko::Outcome;
@@ -155,6 +287,75 @@ mod tests {
         assert_eq!(rating.rd.round(), 307.0);
     }
 
+    #[allow(clippy::float_cmp)]
+    #[test]
+    fn changes() {
+        let rating = Rating::default();
+        let [win, draw, loss] = rating.changes(&Rating::default()).map(f64::round);
+        assert_eq!((win, draw, loss), (162.0, 0.0, -162.0));
+
+        let stronger = Rating {
+            rating: 1_800.0,
+            rd: 50.0,
+            ..Rating::default()
+        };
+        let [win, draw, loss] = rating.changes(&stronger);
+        assert!(win > -loss);
+        assert!(draw > 0.0);
+    }
+
+    #[test]
+    fn provisional() {
+        let mut rating = Rating::default();
+        assert!(rating.provisional());
+        assert_eq!(rating.to_string_rounded(), "1500? ± 686");
+
+        rating.rd = 50.0;
+        assert!(!rating.provisional());
+        assert_eq!(rating.to_string_rounded(), "1500 ± 98");
+    }
+
+    #[allow(clippy::float_cmp)]
+    #[test]
+    fn glicko_1_ratings_load() -> anyhow::Result<()> {
+        let rating: Rating = ron::from_str("(rating: 1600.0, rd: 100.0)")?;
+        assert_eq!(rating.rating, 1_600.0);
+        assert_eq!(rating.rd, 100.0);
+        assert_eq!(rating.volatility, super::VOLATILITY);
+
+        Ok(())
+    }
+
+    /// The example from the Glicko-2 paper.
+    #[allow(clippy::float_cmp)]
+    #[test]
+    fn glicko_2_example() {
+        let mut rating = Rating {
+            rating: 1_500.0,
+            rd: 200.0,
+            volatility: 0.06,
+        };
+
+        let opponent = |rating, rd| Rating {
+            rating,
+            rd,
+            ..Rating::default()
+        };
+
+        rating.update_rating_period(&[
+            (opponent(1_400.0, 30.0), Outcome::Win),
+            (opponent(1_550.0, 100.0), Outcome::Loss),
+            (opponent(1_700.0, 300.0), Outcome::Loss),
+        ]);
+
+        assert_eq!((rating.rating * 100.0).round() / 100.0, 1_464.05);
+        assert_eq!((rating.rd * 100.0).round() / 100.0, 151.52);
+        assert_eq!(
+            (rating.volatility * 1_000_000.0).round() / 1_000_000.0,
+            0.059_996
+        );
+    }
+
     #[allow(clippy::float_cmp)]
     #[test]
     fn rating_and_rd_changes() {
@@ -162,30 +363,21 @@ mod tests {
         assert_eq!(rating.rating, 1_500.0);
 
         let mut rating_1 = rating.clone();
-        rating_1.update_rating(1_600.0, &Outcome::Win);
-        assert_eq!(rating_1.rating.round(), 1_781.0);
+        rating_1.update_rating(1_500.0, &Outcome::Win);
+        assert_eq!(rating_1.rating.round(), 1_662.0);
+        assert_eq!(rating_1.rd.round(), 290.0);
 
         let mut rating_2 = rating.clone();
-        rating_2.update_rating(1_500.0, &Outcome::Win);
-        assert_eq!(rating_2.rating.round(), 1_736.0);
+        rating_2.update_rating(1_500.0, &Outcome::Loss);
+        assert_eq!(rating_2.rating.round(), 1_338.0);
+        assert_eq!(rating_2.rd.round(), 290.0);
 
         let mut rating_3 = rating.clone();
-        rating_3.update_rating(1_500.0, &Outcome::Loss);
-        assert_eq!(rating_3.rating.round(), 1_264.0);
+        rating_3.update_rating(1_500.0, &Outcome::Draw);
+        assert_eq!(rating_3.rating.round(), 1_500.0);
 
         let mut rating_4 = rating.clone();
-        rating_4.update_rating(1_500.0, &Outcome::Loss);
-        assert_eq!(rating_4.rating.round(), 1_264.0);
-        assert_eq!(rating_4.rd.round(), 299.0);
-        rating_4.update_rd();
-        assert_eq!(rating_4.rd.round(), 305.0);
-
-        let mut rating_5 = rating.clone();
-        rating_5.update_rating(1_500.0, &Outcome::Draw);
-        assert_eq!(rating_5.rating.round(), 1_500.0);
-
-        let mut rating_6 = rating.clone();
-        rating_6.update_rating(1_600.0, &Outcome::Draw);
-        assert_eq!(rating_6.rating.round(), 1545.0);
+        rating_4.update_rating(1_600.0, &Outcome::Draw);
+        assert!(rating_4.rating > 1_500.0);
     }
 }
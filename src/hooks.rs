@@ -0,0 +1,273 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! Scripts the client runs when something happens, one hook a line:
+//!
+//! ```text
+//! # Only take on players near your rating.
+//! on challenge if rating < 1400 then decline
+//! on challenge if rated = 0 and board_size = 13 then decline
+//! on move if captures > 1 then sound
+//! on game_end if won = 1 then say Good game!
+//! ```
+//!
+//! A script can only look at the numbers it's handed and do one of a few
+//! actions, so it can't do anything to your computer or your account.
+//!
+//! | event      | numbers                         |
+//! |------------|---------------------------------|
+//! | challenge  | `rating`, `rated`, `board_size` |
+//! | move       | `number`, `captures`, `mine`    |
+//! | game_end   | `won`, `lost`, `draw`           |
+
+use std::{fmt, str::FromStr};
+
+use anyhow::Context;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Event {
+    /// Someone asked to join your game.
+    Challenge,
+    /// A game you're playing ended.
+    GameEnd,
+    /// A move was made in a game you're playing.
+    Move,
+}
+
+impl Event {
+    #[must_use]
+    pub fn variables(self) -> &'static [&'static str] {
+        match self {
+            Self::Challenge => &["rating", "rated", "board_size"],
+            Self::GameEnd => &["won", "lost", "draw"],
+            Self::Move => &["number", "captures", "mine"],
+        }
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Challenge => write!(f, "challenge"),
+            Self::GameEnd => write!(f, "game_end"),
+            Self::Move => write!(f, "move"),
+        }
+    }
+}
+
+impl FromStr for Event {
+    type Err = anyhow::Error;
+
+    fn from_str(event: &str) -> anyhow::Result<Self> {
+        match event {
+            "challenge" => Ok(Self::Challenge),
+            "game_end" => Ok(Self::GameEnd),
+            "move" => Ok(Self::Move),
+            event => Err(anyhow::Error::msg(format!(
+                "unknown event: {event}, expected: challenge, game_end, or move"
+            ))),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Action {
+    /// Declines the challenge.
+    Decline,
+    /// Says the text in the game's chat.
+    Say(String),
+    /// Plays the challenge sound.
+    Sound,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    NotEqual,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl Comparison {
+    fn compare(self, left: f64, right: f64) -> bool {
+        match self {
+            Self::Less => left < right,
+            Self::LessOrEqual => left <= right,
+            Self::Equal => (left - right).abs() < f64::EPSILON,
+            Self::NotEqual => (left - right).abs() >= f64::EPSILON,
+            Self::GreaterOrEqual => left >= right,
+            Self::Greater => left > right,
+        }
+    }
+}
+
+impl FromStr for Comparison {
+    type Err = anyhow::Error;
+
+    fn from_str(comparison: &str) -> anyhow::Result<Self> {
+        match comparison {
+            "<" => Ok(Self::Less),
+            "<=" => Ok(Self::LessOrEqual),
+            "=" => Ok(Self::Equal),
+            "!=" => Ok(Self::NotEqual),
+            ">=" => Ok(Self::GreaterOrEqual),
+            ">" => Ok(Self::Greater),
+            comparison => Err(anyhow::Error::msg(format!(
+                "unknown comparison: {comparison}, expected: <, <=, =, !=, >=, or >"
+            ))),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Condition {
+    variable: String,
+    comparison: Comparison,
+    value: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hook {
+    pub event: Event,
+    conditions: Vec<Condition>,
+    pub action: Action,
+}
+
+impl FromStr for Hook {
+    type Err = anyhow::Error;
+
+    fn from_str(line: &str) -> anyhow::Result<Self> {
+        let (when, action) = line
+            .split_once(" then ")
+            .context("expected: on EVENT [if CONDITION] then ACTION")?;
+
+        let mut words = when.split_whitespace();
+        if words.next() != Some("on") {
+            return Err(anyhow::Error::msg("a hook starts with: on EVENT"));
+        }
+
+        let event: Event = words.next().context("expected: on EVENT")?.parse()?;
+
+        let mut conditions = Vec::new();
+        match words.next() {
+            None => {}
+            Some("if") => loop {
+                let (Some(variable), Some(comparison), Some(value)) =
+                    (words.next(), words.next(), words.next())
+                else {
+                    return Err(anyhow::Error::msg("expected: if NAME COMPARISON NUMBER"));
+                };
+
+                if !event.variables().contains(&variable) {
+                    return Err(anyhow::Error::msg(format!(
+                        "{event} doesn't have {variable}, it has: {}",
+                        event.variables().join(", ")
+                    )));
+                }
+
+                conditions.push(Condition {
+                    variable: variable.to_string(),
+                    comparison: comparison.parse()?,
+                    value: value
+                        .parse()
+                        .with_context(|| format!("{value} is not a number"))?,
+                });
+
+                match words.next() {
+                    None => break,
+                    Some("and") => {}
+                    Some(word) => {
+                        return Err(anyhow::Error::msg(format!("expected: and, got: {word}")));
+                    }
+                }
+            },
+            Some(word) => return Err(anyhow::Error::msg(format!("expected: if, got: {word}"))),
+        }
+
+        let action = action.trim();
+        let action = match action.split_once(' ') {
+            Some(("say", text)) if !text.trim().is_empty() => Action::Say(text.trim().to_string()),
+            None if action == "decline" && event == Event::Challenge => Action::Decline,
+            None if action == "decline" => {
+                return Err(anyhow::Error::msg("you can only decline a challenge"));
+            }
+            None if action == "sound" => Action::Sound,
+            _ => {
+                return Err(anyhow::Error::msg(format!(
+                    "unknown action: {action}, expected: decline, say TEXT, or sound"
+                )));
+            }
+        };
+
+        Ok(Self {
+            event,
+            conditions,
+            action,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Hooks(pub Vec<Hook>);
+
+impl Hooks {
+    /// What to do about the event, given its numbers.
+    #[must_use]
+    pub fn run(&self, event: Event, numbers: &[(&str, f64)]) -> Vec<&Action> {
+        self.0
+            .iter()
+            .filter(|hook| hook.event == event)
+            .filter(|hook| {
+                hook.conditions.iter().all(|condition| {
+                    numbers
+                        .iter()
+                        .find(|(variable, _)| *variable == condition.variable)
+                        .is_some_and(|(_, number)| {
+                            condition.comparison.compare(*number, condition.value)
+                        })
+                })
+            })
+            .map(|hook| &hook.action)
+            .collect()
+    }
+}
+
+impl FromStr for Hooks {
+    type Err = anyhow::Error;
+
+    /// Skips blank lines and the ones starting with `#`.
+    fn from_str(script: &str) -> anyhow::Result<Self> {
+        let mut hooks = Vec::new();
+
+        for (i, line) in script.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            hooks.push(
+                line.parse()
+                    .with_context(|| format!("line {}: {line}", i + 1))?,
+            );
+        }
+
+        Ok(Self(hooks))
+    }
+}
@@ -93,21 +93,39 @@ rust_i18n::i18n!();
 
 pub mod accounts;
 pub mod ai;
+pub mod analysis;
+pub mod avatar;
+#[cfg(feature = "bench")]
+pub mod bench;
 pub mod board;
 pub mod characters;
+pub mod clock;
+pub mod club;
+pub mod command;
+pub mod community;
 pub mod draw;
 pub mod email;
 pub mod game;
 pub mod game_tree;
 pub mod glicko;
 pub mod heat_map;
+pub mod hooks;
 pub mod locale;
 pub mod message;
+pub mod openings;
 pub mod opentafl;
+pub mod physical_board;
 pub mod play;
+pub mod profile;
+pub mod proof_of_work;
+pub mod puzzle;
 pub mod rating;
 pub mod role;
+pub mod rule_set;
+pub mod scout;
 pub mod server_game;
+pub mod settings;
+pub mod simul;
 pub mod space;
 pub mod status;
 pub mod tests;
@@ -115,12 +133,16 @@ pub mod time;
 pub mod tournament;
 pub mod tree;
 pub mod utils;
+pub mod vacation;
 
 pub type Id = u128;
 pub const HOME: &str = "hnefatafl-copenhagen";
-pub const SERVER_PORT: &str = ":49152";
+/// The port the server listens on unless told otherwise.
+pub const PORT: u16 = 49_152;
+/// The most archived games the server sends for one request.
+pub const ARCHIVED_GAMES_PAGE: usize = 1_000;
 pub const SOCKET_PATH: &str = "/tmp/hnefatafl.sock";
-pub const VERSION_ID: &str = "32d9a140";
+pub const VERSION_ID: &str = "3a89522f";
 
 pub const COPYRIGHT: &str = r".SH COPYRIGHT
 Copyright (C) 2025-2026 Developers of the hnefatafl-copenhagen project
@@ -16,11 +16,12 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 // SPDX-FileCopyrightText: 2025 David Campbell <david@hnefatafl.org>
 
-use std::fmt;
+use std::{fmt, str::FromStr};
 
+use jiff::{Zoned, civil};
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub enum Locale {
     #[default]
     English,
@@ -61,6 +62,144 @@ impl Locale {
             Self::Korean => "ko".to_string(),
         }
     }
+
+    /// The locale the messages are shown in.
+    #[must_use]
+    pub fn current() -> Self {
+        rust_i18n::locale().parse().unwrap_or_default()
+    }
+
+    /// The decimal separator, the thousands separator, and how many digits
+    /// the whole part needs past the first three before it gets grouped.
+    fn separators(self) -> (char, char, usize) {
+        match self {
+            Self::English | Self::Chinese | Self::Japanese | Self::Korean => ('.', ',', 1),
+            Self::Arabic => ('\u{66B}', '\u{66C}', 1),
+            Self::Spanish => (',', '.', 2),
+            Self::PortuguesePt => (',', '\u{A0}', 2),
+            Self::French => (',', '\u{202F}', 1),
+            Self::Russian | Self::Swedish => (',', '\u{A0}', 1),
+            Self::Indonesian
+            | Self::PortugueseBr
+            | Self::German
+            | Self::Icelandic
+            | Self::IcelandicRunic => (',', '.', 1),
+        }
+    }
+
+    /// Writes the digits the way the locale does, Arabic with Arabic-Indic
+    /// digits and the rest as they are.
+    #[must_use]
+    pub fn digits(self, string: &str) -> String {
+        if self == Self::Arabic {
+            string
+                .chars()
+                .map(|ch| match ch.to_digit(10) {
+                    Some(digit) => char::from_u32(0x660 + digit).unwrap_or(ch),
+                    None => ch,
+                })
+                .collect()
+        } else {
+            string.to_string()
+        }
+    }
+
+    /// `number` rounded to `decimals` places, with the separators and digits
+    /// of the locale.
+    #[must_use]
+    pub fn number(self, number: f64, decimals: usize) -> String {
+        let (decimal, group, minimum_grouping) = self.separators();
+        let rounded = format!("{:.decimals$}", number.abs());
+        let (whole, fraction) = rounded.split_once('.').unwrap_or((&rounded, ""));
+
+        let mut string = String::new();
+        if number < 0.0 && rounded.chars().any(|ch| ch != '0' && ch != '.') {
+            string.push('-');
+        }
+
+        let grouped = whole.len() >= 3 + minimum_grouping;
+        for (i, digit) in whole.chars().enumerate() {
+            let left = whole.len() - i;
+            if grouped && i > 0 && left % 3 == 0 {
+                string.push(group);
+            }
+
+            string.push(digit);
+        }
+
+        if !fraction.is_empty() {
+            string.push(decimal);
+            string.push_str(fraction);
+        }
+
+        self.digits(&string)
+    }
+
+    /// How the locale writes a day.
+    #[must_use]
+    pub fn date(self, date: civil::Date) -> String {
+        let format = match self {
+            Self::English => "%-m/%-d/%Y",
+            Self::Chinese | Self::Japanese => "%Y/%-m/%-d",
+            Self::Spanish | Self::Arabic | Self::Indonesian => "%-d/%-m/%Y",
+            Self::PortugueseBr | Self::PortuguesePt | Self::French => "%d/%m/%Y",
+            Self::Russian | Self::German => "%d.%m.%Y",
+            Self::Icelandic | Self::IcelandicRunic => "%-d.%-m.%Y",
+            Self::Swedish => "%Y-%m-%d",
+            Self::Korean => "%Y. %-m. %-d.",
+        };
+
+        self.digits(&date.strftime(format).to_string())
+    }
+
+    /// How the locale writes a time of day, to the minute.
+    #[must_use]
+    pub fn time(self, time: civil::Time) -> String {
+        let format = match self {
+            Self::English => "%-I:%M %p",
+            Self::Indonesian => "%H.%M",
+            _ => "%H:%M",
+        };
+
+        self.digits(&time.strftime(format).to_string())
+    }
+
+    /// The day and time, with the time zone it's in.
+    #[must_use]
+    pub fn date_time(self, zoned: &Zoned) -> String {
+        format!(
+            "{} {} {}",
+            self.date(zoned.date()),
+            self.time(zoned.time()),
+            zoned.strftime("%Z")
+        )
+    }
+}
+
+/// Parses what [`Locale::txt`] returns.
+impl FromStr for Locale {
+    type Err = anyhow::Error;
+
+    fn from_str(locale: &str) -> anyhow::Result<Self> {
+        match locale {
+            "en-US" => Ok(Self::English),
+            "zh-CN" => Ok(Self::Chinese),
+            "es" => Ok(Self::Spanish),
+            "ar" => Ok(Self::Arabic),
+            "id" => Ok(Self::Indonesian),
+            "pt-BR" => Ok(Self::PortugueseBr),
+            "pt-PT" => Ok(Self::PortuguesePt),
+            "fr" => Ok(Self::French),
+            "ja" => Ok(Self::Japanese),
+            "ru" => Ok(Self::Russian),
+            "de" => Ok(Self::German),
+            "is-IS" => Ok(Self::Icelandic),
+            "is-RU" => Ok(Self::IcelandicRunic),
+            "sv-SE" => Ok(Self::Swedish),
+            "ko" => Ok(Self::Korean),
+            _ => Err(anyhow::Error::msg(format!("unknown locale: {locale}"))),
+        }
+    }
 }
 
 impl TryFrom<&str> for Locale {
@@ -0,0 +1,122 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! The usual ways a game of Copenhagen hnefatafl on the 11x11 board starts.
+//! Most of them have no established name, so each goes by its moves, with the
+//! board turned so the attackers start from the bottom edge. A game is in an
+//! opening whatever way the board is turned or flipped, so `h1-h3` is the same
+//! opening as `d1-d3`.
+
+use std::str::FromStr;
+
+use crate::{
+    board::BoardSize,
+    play::{Plae, Vertex},
+};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Opening {
+    /// Starting with the attacker's first move, such as `d1-d3`.
+    pub plays: &'static [&'static str],
+}
+
+pub const OPENINGS: [Opening; 7] = [
+    Opening { plays: &["d1-b1"] },
+    Opening {
+        plays: &["d1-b1", "h6-h9"],
+    },
+    Opening { plays: &["d1-d3"] },
+    Opening {
+        plays: &["d1-d3", "d6-d4"],
+    },
+    Opening { plays: &["f2-c2"] },
+    Opening {
+        plays: &["f2-c2", "f4-c4"],
+    },
+    Opening { plays: &["e1-e3"] },
+];
+
+/// The names of all the openings, to pick from.
+#[must_use]
+pub fn names() -> Vec<String> {
+    OPENINGS.iter().map(Opening::name).collect()
+}
+
+/// The opening with the most moves that `plays` starts with.
+#[must_use]
+pub fn opening(plays: &[&Plae]) -> Option<&'static Opening> {
+    OPENINGS
+        .iter()
+        .filter(|opening| (0..8).any(|symmetry| opening.starts(plays, symmetry)))
+        .max_by_key(|opening| opening.plays.len())
+}
+
+impl Opening {
+    /// The plays, such as `d1-d3 d6-d4`.
+    #[must_use]
+    pub fn name(&self) -> String {
+        self.plays.join(" ")
+    }
+
+    fn starts(&self, plays: &[&Plae], symmetry: u8) -> bool {
+        if plays.len() < self.plays.len() {
+            return false;
+        }
+
+        self.plays.iter().zip(plays).all(|(expected, play)| {
+            let Plae::Play(play) = play else {
+                return false;
+            };
+
+            let Some((from, to)) = expected.split_once('-') else {
+                return false;
+            };
+
+            let (Ok(from), Ok(to)) = (Vertex::from_str(from), Vertex::from_str(to)) else {
+                return false;
+            };
+
+            play.from.size == BoardSize::_11
+                && turn(from, symmetry) == play.from
+                && turn(to, symmetry) == play.to
+        })
+    }
+}
+
+/// One of the eight ways to turn and flip the board.
+fn turn(vertex: Vertex, symmetry: u8) -> Vertex {
+    let last = usize::from(vertex.size) - 1;
+    let (x, y) = (vertex.x, vertex.y);
+
+    let (x, y) = match symmetry {
+        0 => (x, y),
+        1 => (last - x, y),
+        2 => (x, last - y),
+        3 => (last - x, last - y),
+        4 => (y, x),
+        5 => (last - y, x),
+        6 => (y, last - x),
+        _ => (last - y, last - x),
+    };
+
+    Vertex {
+        size: vertex.size,
+        x,
+        y,
+    }
+}
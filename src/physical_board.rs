@@ -0,0 +1,132 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! Moves from an electronic board, so you can play online opponents over a
+//! real board.
+//!
+//! A board tells you a square whenever a piece is lifted off it or placed on
+//! it. A [`Connection`] hands out those events however the board sends them,
+//! and [`Tracker`] turns them into moves. [`LineConnection`] reads one event a
+//! line, `lift f6` or `place f9`, from anything you can read lines from: a
+//! serial port like `/dev/ttyUSB0`, a Bluetooth serial port like
+//! `/dev/rfcomm0`, or a pipe.
+
+use std::io::BufRead;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Event {
+    /// A piece was lifted off the square.
+    Lift(String),
+    /// A piece was placed on the square.
+    Place(String),
+}
+
+pub trait Connection {
+    /// The next thing that happened on the board, `None` once the board is
+    /// disconnected.
+    ///
+    /// # Errors
+    ///
+    /// If the board can't be read or sent something that isn't an event.
+    fn event(&mut self) -> anyhow::Result<Option<Event>>;
+}
+
+/// Reads `lift SQUARE` and `place SQUARE` lines, skipping blank ones.
+#[derive(Debug)]
+pub struct LineConnection<R> {
+    reader: R,
+}
+
+impl<R: BufRead> LineConnection<R> {
+    #[must_use]
+    pub fn new(reader: R) -> Self {
+        Self { reader }
+    }
+}
+
+impl<R: BufRead> Connection for LineConnection<R> {
+    fn event(&mut self) -> anyhow::Result<Option<Event>> {
+        let mut line = String::new();
+
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+
+            let words: Vec<_> = line.split_whitespace().collect();
+            match words.as_slice() {
+                [] => {}
+                ["lift", square] => return Ok(Some(Event::Lift((*square).to_string()))),
+                ["place", square] => return Ok(Some(Event::Place((*square).to_string()))),
+                _ => {
+                    return Err(anyhow::Error::msg(format!(
+                        "expected: lift SQUARE or place SQUARE, got: {}",
+                        line.trim()
+                    )));
+                }
+            }
+        }
+    }
+}
+
+/// Remembers the last piece lifted, so placing it somewhere else is a move.
+///
+/// Taking captured pieces off the board is a lift without a place, so it's
+/// forgotten as soon as the next piece is lifted.
+#[derive(Clone, Debug, Default)]
+pub struct Tracker {
+    lifted: Option<String>,
+}
+
+impl Tracker {
+    /// The move `(from, to)` the event finishes, if any.
+    pub fn event(&mut self, event: Event) -> Option<(String, String)> {
+        match event {
+            Event::Lift(square) => {
+                self.lifted = Some(square);
+                None
+            }
+            Event::Place(to) => {
+                let from = self.lifted.take()?;
+                (from != to).then_some((from, to))
+            }
+        }
+    }
+}
+
+/// Hands every move made on the board to `on_move` until the board is
+/// disconnected.
+///
+/// # Errors
+///
+/// If the [`Connection`] fails.
+pub fn moves(
+    connection: &mut impl Connection,
+    mut on_move: impl FnMut(String, String),
+) -> anyhow::Result<()> {
+    let mut tracker = Tracker::default();
+
+    while let Some(event) = connection.event()? {
+        if let Some((from, to)) = tracker.event(event) {
+            on_move(from, to);
+        }
+    }
+
+    Ok(())
+}
@@ -247,6 +247,18 @@ impl Plays {
         }
     }
 
+    /// The plays made so far, leaving out the empty record servers start a
+    /// game with.
+    #[must_use]
+    pub fn made(&self) -> Vec<&Plae> {
+        match self {
+            Plays::PlayRecordsTimed(plays) => {
+                plays.iter().filter_map(|play| play.play.as_ref()).collect()
+            }
+            Plays::PlayRecords(plays) => plays.iter().flatten().collect(),
+        }
+    }
+
     #[must_use]
     pub fn new(time_settings: &TimeSettings) -> Self {
         match time_settings {
@@ -0,0 +1,176 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! What players tell everyone about themselves, and what `profile USERNAME`
+//! sends back: that, their record, their rating over their last games, and
+//! the games themselves.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Id, board::BoardSize, glicko::Rating, rating::Rated, role::Role, server_game::ArchivedGame,
+    status::Status,
+};
+
+/// The longest a bio may be, in characters.
+pub const BIO_LENGTH: usize = 280;
+
+/// How many of the latest games a profile lists.
+pub const RECENT_GAMES: usize = 10;
+
+/// How many of the latest games the rating graph goes back.
+pub const RATING_GAMES: usize = 50;
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Profile {
+    #[serde(default)]
+    pub bio: String,
+    /// An ISO 3166-1 alpha-2 code such as `IS`.
+    #[serde(default)]
+    pub country: Option<String>,
+    /// The side they like to play, `None` for either.
+    #[serde(default)]
+    pub role: Option<Role>,
+}
+
+impl Profile {
+    /// # Errors
+    ///
+    /// If the bio is longer than [`BIO_LENGTH`].
+    pub fn set_bio(&mut self, bio: &str) -> anyhow::Result<()> {
+        let bio = bio.trim();
+
+        if bio.chars().count() > BIO_LENGTH {
+            return Err(anyhow::Error::msg(format!(
+                "the bio is longer than {BIO_LENGTH} characters"
+            )));
+        }
+
+        self.bio = bio.to_string();
+        Ok(())
+    }
+
+    /// `-` clears the country.
+    ///
+    /// # Errors
+    ///
+    /// If the country isn't two letters.
+    pub fn set_country(&mut self, country: &str) -> anyhow::Result<()> {
+        if country == "-" {
+            self.country = None;
+            return Ok(());
+        }
+
+        if country.len() != 2 || !country.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(anyhow::Error::msg(format!(
+                "{country} isn't a two letter country code"
+            )));
+        }
+
+        self.country = Some(country.to_ascii_uppercase());
+        Ok(())
+    }
+
+    /// The country as a flag emoji, made of two regional indicator symbols.
+    #[must_use]
+    pub fn flag(&self) -> Option<String> {
+        self.country.as_ref().map(|country| {
+            country
+                .chars()
+                .filter_map(|c| char::from_u32(0x1F1E6 + u32::from(c) - u32::from('A')))
+                .collect()
+        })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RecentGame {
+    pub id: Id,
+    pub opponent: String,
+    /// The side the player played.
+    pub role: Role,
+    pub status: Status,
+    pub rated: Rated,
+    pub board_size: BoardSize,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PlayerProfile {
+    pub username: String,
+    pub profile: Profile,
+    pub rating: Rating,
+    pub wins: u64,
+    pub losses: u64,
+    pub draws: u64,
+    /// The rating going into each of the last [`RATING_GAMES`] games, then
+    /// the rating now.
+    pub ratings: Vec<f64>,
+    /// The latest first.
+    pub games: Vec<RecentGame>,
+}
+
+impl PlayerProfile {
+    /// Fills in the graph and the recent games from `archived`, the games
+    /// the player played in the order they were archived.
+    pub fn games(&mut self, archived: &[ArchivedGame]) {
+        self.ratings = ratings(&self.username, &self.rating, archived);
+
+        self.games = archived
+            .iter()
+            .rev()
+            .take(RECENT_GAMES)
+            .map(|game| {
+                let (role, opponent) = if game.attacker == self.username {
+                    (Role::Attacker, game.defender.clone())
+                } else {
+                    (Role::Defender, game.attacker.clone())
+                };
+
+                RecentGame {
+                    id: game.id,
+                    opponent,
+                    role,
+                    status: game.status.clone(),
+                    rated: game.rated,
+                    board_size: game.board_size,
+                }
+            })
+            .collect();
+    }
+}
+
+/// The rating `username` had going into each of the last [`RATING_GAMES`] of
+/// `archived`, then `rating`, the rating now.
+#[must_use]
+pub fn ratings(username: &str, rating: &Rating, archived: &[ArchivedGame]) -> Vec<f64> {
+    let start = archived.len().saturating_sub(RATING_GAMES);
+    let mut ratings: Vec<_> = archived
+        .iter()
+        .skip(start)
+        .map(|game| {
+            if game.attacker == username {
+                game.attacker_rating.rating
+            } else {
+                game.defender_rating.rating
+            }
+        })
+        .collect();
+
+    ratings.push(rating.rating);
+    ratings
+}
@@ -0,0 +1,74 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! A puzzle the client solves before the server creates an account for it, so
+//! creating accounts in bulk costs a bot real work. The client looks for a
+//! number that, hashed with the nonce the server sent, starts with enough zero
+//! bits.
+
+use blake2::{Blake2s256, Digest};
+
+/// How many leading zero bits the hash needs, about a million hashes on
+/// average.
+pub const BITS: u8 = 20;
+
+/// The most bits a client solves for, a server asking for more could keep it
+/// busy for hours.
+pub const BITS_MAX: u8 = BITS + 4;
+
+/// How many numbers get tried between calls to `progress`.
+const PROGRESS_EVERY: u64 = 1 << 16;
+
+/// The first number that solves the puzzle.
+#[must_use]
+pub fn solve(nonce: &str, bits: u8) -> u64 {
+    solve_progress(nonce, bits, &mut |_tried| {})
+}
+
+/// The first number that solves the puzzle, telling `progress` how many
+/// numbers it has tried every so often.
+pub fn solve_progress(nonce: &str, bits: u8, progress: &mut impl FnMut(u64)) -> u64 {
+    (0..=u64::MAX)
+        .find(|solution| {
+            if *solution > 0 && solution.is_multiple_of(PROGRESS_EVERY) {
+                progress(*solution);
+            }
+
+            verify(nonce, bits, *solution)
+        })
+        .unwrap_or_default()
+}
+
+#[must_use]
+pub fn verify(nonce: &str, bits: u8, solution: u64) -> bool {
+    let hash = Blake2s256::new()
+        .chain_update(nonce.as_bytes())
+        .chain_update(solution.to_le_bytes())
+        .finalize();
+
+    let mut zeros = 0;
+    for byte in hash {
+        zeros += byte.leading_zeros();
+
+        if byte != 0 {
+            break;
+        }
+    }
+
+    zeros >= u32::from(bits)
+}
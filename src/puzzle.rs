@@ -0,0 +1,312 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! Positions from archived games where exactly one move wins, or exactly one
+//! move keeps from losing, found by the engine after each game is archived.
+
+use std::{collections::VecDeque, fmt};
+
+use jiff::Timestamp;
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Id,
+    analysis::{Judgement, MoveAnalysis, value_after},
+    board::BoardSize,
+    game::Game,
+    glicko::Rating,
+    play::{Plae, Plays},
+    rating::Rated,
+    role::Role,
+    rule_set::RuleSet,
+    server_game::{ArchivedGame, Tags},
+    status::Status,
+    time::{DAY, TimeSettings},
+};
+
+/// How many puzzles are kept, the oldest go first.
+const KEPT: usize = 10_000;
+
+/// How many of the longest streaks the daily puzzle lists.
+pub const STREAKS: usize = 10;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum Theme {
+    /// The attacker captures the king.
+    KingHunt,
+    /// The king breaks out to a corner.
+    FortBreak,
+    /// Only one move holds off the loss.
+    ShieldWall,
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::KingHunt => write!(f, "{}", t!("King Hunt")),
+            Self::FortBreak => write!(f, "{}", t!("Fort Break")),
+            Self::ShieldWall => write!(f, "{}", t!("Shield Wall")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Puzzle {
+    /// The archived game the position came up in.
+    pub game_id: Id,
+    pub board_size: BoardSize,
+    /// The plays that lead up to the position.
+    pub plays: Vec<Plae>,
+    pub solution: Plae,
+    pub theme: Theme,
+}
+
+impl Puzzle {
+    /// The side to find the move for.
+    #[must_use]
+    pub fn role(&self) -> Role {
+        match &self.solution {
+            Plae::Play(play) => play.role,
+            Plae::AttackerResigns => Role::Attacker,
+            Plae::DefenderResigns => Role::Defender,
+        }
+    }
+
+    /// The puzzle as a game to go through, ending with the solution.
+    #[must_use]
+    pub fn archived_game(&self, attacker: &str, defender: &str) -> ArchivedGame {
+        let mut archived_game = self.position().archived_game(attacker, defender);
+        archived_game.id = self.game_id;
+        if let Plays::PlayRecords(plays) = &mut archived_game.plays {
+            plays.push(Some(self.solution.clone()));
+        }
+
+        archived_game
+    }
+
+    /// The puzzle without its solution.
+    #[must_use]
+    pub fn position(&self) -> Position {
+        Position {
+            board_size: self.board_size,
+            plays: self.plays.clone(),
+            role: self.role(),
+            theme: self.theme,
+        }
+    }
+}
+
+/// A puzzle's position and the side to find the move for, so the solution
+/// stays on the server.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Position {
+    pub board_size: BoardSize,
+    /// The plays that lead up to the position.
+    pub plays: Vec<Plae>,
+    pub role: Role,
+    pub theme: Theme,
+}
+
+impl Position {
+    /// The plays up to the position as a game to go through.
+    #[must_use]
+    pub fn archived_game(&self, attacker: &str, defender: &str) -> ArchivedGame {
+        ArchivedGame {
+            id: 0,
+            attacker: attacker.to_string(),
+            attacker_rating: Rating::default(),
+            defender: defender.to_string(),
+            defender_rating: Rating::default(),
+            rated: Rated::No,
+            plays: Plays::PlayRecords(self.plays.iter().cloned().map(Some).collect()),
+            status: Status::Ongoing,
+            messages: VecDeque::new(),
+            board_size: self.board_size,
+            analysis: None,
+            lag: Vec::new(),
+            tags: Tags::default(),
+            rule_set: RuleSet::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Puzzles(pub Vec<Puzzle>);
+
+impl Puzzles {
+    /// Adds the puzzles that aren't already kept.
+    pub fn add(&mut self, puzzles: Vec<Puzzle>) {
+        for puzzle in puzzles {
+            if !self.0.iter().any(|kept| {
+                kept.game_id == puzzle.game_id && kept.plays.len() == puzzle.plays.len()
+            }) {
+                self.0.push(puzzle);
+            }
+        }
+
+        if self.0.len() > KEPT {
+            self.0.drain(..self.0.len() - KEPT);
+        }
+    }
+}
+
+/// The day since the epoch, in UTC.
+#[must_use]
+pub fn day(now: Timestamp) -> i64 {
+    now.as_millisecond().div_euclid(DAY)
+}
+
+/// The puzzle everyone gets on a day.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DailyPuzzle {
+    /// See [`day`].
+    pub day: i64,
+    pub puzzle: Puzzle,
+    /// In the order they solved it.
+    pub solvers: Vec<String>,
+}
+
+/// What `daily_puzzle` sends back to a player.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Daily {
+    pub position: Position,
+    /// Only sent once you had your try.
+    pub solution: Option<Plae>,
+    /// In the order they solved it.
+    pub solvers: Vec<String>,
+    /// Your streak.
+    pub streak: u64,
+    /// Whether you had your one try at today's puzzle.
+    pub tried: bool,
+    /// The longest streaks going, the longest first.
+    pub streaks: Vec<(String, u64)>,
+}
+
+/// How many days in a row a player solved the daily puzzle.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Streak {
+    pub days: u64,
+    pub best: u64,
+    pub solved: Option<i64>,
+    pub tried: Option<i64>,
+}
+
+impl Streak {
+    /// The streak, zero if they didn't solve yesterday's or today's puzzle.
+    #[must_use]
+    pub fn current(&self, today: i64) -> u64 {
+        match self.solved {
+            Some(solved) if solved >= today - 1 => self.days,
+            Some(_) | None => 0,
+        }
+    }
+
+    pub fn solve(&mut self, today: i64) {
+        if self.solved == Some(today - 1) {
+            self.days += 1;
+        } else {
+            self.days = 1;
+        }
+
+        self.best = self.best.max(self.days);
+        self.solved = Some(today);
+        self.tried = Some(today);
+    }
+
+    pub fn miss(&mut self, today: i64) {
+        self.days = 0;
+        self.tried = Some(today);
+    }
+
+    #[must_use]
+    pub fn tried(&self, today: i64) -> bool {
+        self.tried == Some(today)
+    }
+}
+
+/// Goes over the plays [`analyze`](crate::analysis::analyze) judged a missed
+/// win or a blunder and keeps the positions where only one move does.
+#[must_use]
+pub fn find(
+    game_id: Id,
+    board_size: BoardSize,
+    plays: &Plays,
+    moves: &[MoveAnalysis],
+) -> Vec<Puzzle> {
+    let made: Vec<Plae> = plays.made().into_iter().cloned().collect();
+    let mut puzzles = Vec::new();
+
+    for analysis in moves {
+        let wins = match analysis.judgement {
+            Judgement::MissedWin => true,
+            Judgement::Blunder => false,
+            Judgement::Best | Judgement::Good | Judgement::Inaccuracy | Judgement::Mistake => {
+                continue;
+            }
+        };
+
+        let Some(before) = made.get(..analysis.number - 1) else {
+            continue;
+        };
+
+        let mut game = Game::make(board_size, &TimeSettings::UnTimed);
+        if before.iter().any(|play| game.play(play).is_err()) {
+            continue;
+        }
+
+        let role = game.turn;
+        let sign = match role {
+            Role::Attacker => 1.0,
+            Role::Defender | Role::Roleless => -1.0,
+        };
+
+        let answers: Vec<Plae> = game
+            .all_legal_plays()
+            .into_iter()
+            .filter(|play| {
+                value_after(&game, play).is_some_and(|(_, value)| {
+                    let value = sign * value;
+                    if wins {
+                        value == f64::INFINITY
+                    } else {
+                        value != -f64::INFINITY
+                    }
+                })
+            })
+            .collect();
+
+        if let [solution] = answers.as_slice() {
+            let theme = match (wins, role) {
+                (true, Role::Attacker) => Theme::KingHunt,
+                (true, Role::Defender | Role::Roleless) => Theme::FortBreak,
+                (false, _) => Theme::ShieldWall,
+            };
+
+            puzzles.push(Puzzle {
+                game_id,
+                board_size,
+                plays: before.to_vec(),
+                solution: solution.clone(),
+                theme,
+            });
+        }
+    }
+
+    puzzles
+}
@@ -16,10 +16,17 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 // SPDX-FileCopyrightText: 2025 David Campbell <david@hnefatafl.org>
 
-use std::{fmt, ops::Not, str::FromStr};
+use std::{collections::HashMap, fmt, ops::Not, str::FromStr};
 
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    board::BoardSize,
+    glicko::{Outcome, Rating},
+    rule_set::RuleSet,
+    time::{Speed, TimeSettings},
+};
+
 #[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub enum Rated {
     No,
@@ -75,3 +82,114 @@ impl FromStr for Rated {
         }
     }
 }
+
+/// The rules, board size, and speed a rated game was played at.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+)]
+pub struct RatingCategory {
+    #[serde(default)]
+    pub rule_set: RuleSet,
+    pub board_size: BoardSize,
+    pub speed: Speed,
+}
+
+impl RatingCategory {
+    #[must_use]
+    pub fn new(rule_set: RuleSet, board_size: BoardSize, time_settings: &TimeSettings) -> Self {
+        Self {
+            rule_set,
+            board_size,
+            speed: time_settings.into(),
+        }
+    }
+}
+
+impl fmt::Display for RatingCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.rule_set {
+            RuleSet::Copenhagen => write!(f, "{0}x{0} {1}", self.board_size, self.speed),
+        }
+    }
+}
+
+/// A rating for every category a player has played a rated game in.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Ratings(pub HashMap<RatingCategory, Rating>);
+
+impl Ratings {
+    /// Returns the rating for the category or the default rating if no rated
+    /// games have been played in it.
+    #[must_use]
+    pub fn get(&self, category: &RatingCategory) -> Rating {
+        self.0.get(category).cloned().unwrap_or_default()
+    }
+
+    pub fn get_mut(&mut self, category: RatingCategory) -> &mut Rating {
+        self.0.entry(category).or_default()
+    }
+
+    pub fn update_rd(&mut self) {
+        for rating in self.0.values_mut() {
+            rating.update_rd();
+        }
+    }
+}
+
+/// A rated game, with the opponent's ratings at the start of the rating period.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RatedGame {
+    pub category: RatingCategory,
+    pub opponent: Rating,
+    pub opponent_category: Rating,
+    pub outcome: Outcome,
+}
+
+/// The rated games played since the last rating period ended.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct RatingPeriod(pub Vec<RatedGame>);
+
+impl RatingPeriod {
+    pub fn push(
+        &mut self,
+        category: RatingCategory,
+        opponent: &Rating,
+        opponent_category: &Rating,
+        outcome: Outcome,
+    ) {
+        self.0.push(RatedGame {
+            category,
+            opponent: opponent.clone(),
+            opponent_category: opponent_category.clone(),
+            outcome,
+        });
+    }
+
+    /// Updates the rating and the ratings by category from all of the games
+    /// played in the rating period at once.
+    pub fn end(&mut self, rating: &mut Rating, ratings: &mut Ratings) {
+        if self.0.is_empty() {
+            return;
+        }
+
+        let results: Vec<_> = self
+            .0
+            .iter()
+            .map(|game| (game.opponent.clone(), game.outcome.clone()))
+            .collect();
+
+        rating.update_rating_period(&results);
+
+        let mut categories: HashMap<RatingCategory, Vec<(Rating, Outcome)>> = HashMap::new();
+        for game in self.0.drain(..) {
+            categories
+                .entry(game.category)
+                .or_default()
+                .push((game.opponent_category, game.outcome));
+        }
+
+        for (category, results) in categories {
+            ratings.get_mut(category).update_rating_period(&results);
+        }
+    }
+}
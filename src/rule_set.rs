@@ -0,0 +1,60 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// The rules a game is played by. Every game carries one and the ratings are
+/// kept apart for each, so a game played by other rules never moves your
+/// Copenhagen rating.
+///
+/// Copenhagen is the only one the engine plays so far. Tablut, Brandubh, and
+/// the others get added here as the engine learns them.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+)]
+pub enum RuleSet {
+    #[default]
+    Copenhagen,
+}
+
+impl RuleSet {
+    pub const ALL: [Self; 1] = [Self::Copenhagen];
+}
+
+impl fmt::Display for RuleSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Copenhagen => write!(f, "copenhagen"),
+        }
+    }
+}
+
+impl FromStr for RuleSet {
+    type Err = anyhow::Error;
+
+    fn from_str(rule_set: &str) -> anyhow::Result<Self> {
+        match rule_set {
+            "copenhagen" => Ok(Self::Copenhagen),
+            rule_set => Err(anyhow::Error::msg(format!(
+                "unknown rule set: {rule_set}, expected: copenhagen"
+            ))),
+        }
+    }
+}
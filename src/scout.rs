@@ -0,0 +1,167 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! What `scout USERNAME` sends back: how a player has been playing lately,
+//! to look over before you accept their challenge.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    glicko::Rating,
+    play::{Plae, Plays},
+    profile::{self, RATING_GAMES},
+    role::Role,
+    server_game::ArchivedGame,
+    status::Status,
+};
+
+/// How many of the most common first moves a report lists.
+pub const FIRST_MOVES: usize = 3;
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Record {
+    pub games: u64,
+    pub wins: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FirstMove {
+    pub role: Role,
+    /// Such as `d1-d3`.
+    pub play: String,
+    pub games: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Scout {
+    pub username: String,
+    pub rating: Rating,
+    /// The rating going into each of the last [`RATING_GAMES`] games, then
+    /// the rating now.
+    pub ratings: Vec<f64>,
+    /// The side they say they like to play, `None` for either.
+    pub prefers: Option<Role>,
+    pub attacker: Record,
+    pub defender: Record,
+    /// How much of their clock a move took on average, in timed games.
+    /// Time added back after a move isn't counted.
+    pub move_milliseconds: Option<i64>,
+    /// The most played first.
+    pub first_moves: Vec<FirstMove>,
+}
+
+impl Scout {
+    /// Looks over `archived`, the player's last [`RATING_GAMES`] games in the
+    /// order they were archived.
+    #[must_use]
+    pub fn new(
+        username: &str,
+        rating: Rating,
+        prefers: Option<Role>,
+        archived: &[ArchivedGame],
+    ) -> Self {
+        let mut scout = Scout {
+            username: username.to_string(),
+            ratings: profile::ratings(username, &rating, archived),
+            rating,
+            prefers,
+            attacker: Record::default(),
+            defender: Record::default(),
+            move_milliseconds: None,
+            first_moves: Vec::new(),
+        };
+
+        let mut first_moves: HashMap<(Role, String), u64> = HashMap::new();
+        let mut milliseconds = 0;
+        let mut moves = 0;
+
+        for game in archived {
+            let (role, record, won) = if game.attacker == username {
+                (
+                    Role::Attacker,
+                    &mut scout.attacker,
+                    game.status == Status::AttackerWins,
+                )
+            } else {
+                (
+                    Role::Defender,
+                    &mut scout.defender,
+                    game.status == Status::DefenderWins,
+                )
+            };
+
+            record.games += 1;
+            if won {
+                record.wins += 1;
+            }
+
+            if let Some(play) = game.plays.made().into_iter().find_map(|play| match play {
+                Plae::Play(play) if play.role == role => Some(play),
+                _ => None,
+            }) {
+                *first_moves
+                    .entry((role, format!("{}-{}", play.from, play.to)))
+                    .or_default() += 1;
+            }
+
+            if let Plays::PlayRecordsTimed(plays) = &game.plays {
+                for pair in plays.windows(2) {
+                    if let [before, after] = pair
+                        && let Some(Plae::Play(play)) = &after.play
+                        && play.role == role
+                    {
+                        let (before, after) = match role {
+                            Role::Attacker => (&before.attacker_time, &after.attacker_time),
+                            Role::Defender | Role::Roleless => {
+                                (&before.defender_time, &after.defender_time)
+                            }
+                        };
+
+                        milliseconds += (before.milliseconds_left - after.milliseconds_left).max(0);
+                        moves += 1;
+                    }
+                }
+            }
+        }
+
+        if moves > 0 {
+            scout.move_milliseconds = Some(milliseconds / moves);
+        }
+
+        let mut first_moves: Vec<_> = first_moves
+            .into_iter()
+            .map(|((role, play), games)| FirstMove { role, play, games })
+            .collect();
+        first_moves.sort_by(|a, b| b.games.cmp(&a.games).then_with(|| a.play.cmp(&b.play)));
+        first_moves.truncate(FIRST_MOVES);
+        scout.first_moves = first_moves;
+
+        scout
+    }
+
+    /// How much the rating went up or down over the games looked at.
+    #[must_use]
+    pub fn rating_trend(&self) -> f64 {
+        match (self.ratings.first(), self.ratings.last()) {
+            (Some(first), Some(last)) => last - first,
+            _ => 0.0,
+        }
+    }
+}
@@ -17,7 +17,7 @@
 // SPDX-FileCopyrightText: 2025 David Campbell <david@hnefatafl.org>
 
 use std::{
-    collections::{HashMap, HashSet, VecDeque},
+    collections::{BTreeSet, HashMap, HashSet, VecDeque},
     fmt,
     str::FromStr,
     sync::mpsc::Sender,
@@ -30,12 +30,16 @@ use serde::{Deserialize, Serialize};
 use crate::{
     Id,
     accounts::{Account, Accounts, Users},
+    analysis::Analysis,
     board::{Board, BoardSize},
+    clock,
     game::Game,
     glicko::Rating,
-    play::{PlayRecordTimed, Plays},
+    openings::opening,
+    play::{Plae, PlayRecordTimed, Plays},
     rating::Rated,
     role::Role,
+    rule_set::RuleSet,
     status::Status,
     time::{Time, TimeSettings, TimeUnix},
 };
@@ -53,6 +57,16 @@ pub struct ArchivedGame {
     #[serde(default)]
     pub messages: VecDeque<Message>,
     pub board_size: BoardSize,
+    /// The coach's look over the game, done after the game is archived.
+    #[serde(default)]
+    pub analysis: Option<Analysis>,
+    /// The milliseconds given back for lag on each move.
+    #[serde(default)]
+    pub lag: Vec<i64>,
+    #[serde(default)]
+    pub tags: Tags,
+    #[serde(default)]
+    pub rule_set: RuleSet,
 }
 
 impl ArchivedGame {
@@ -69,6 +83,10 @@ impl ArchivedGame {
             status: game.game.status,
             messages: game.messages,
             board_size: game.game.board.size(),
+            analysis: None,
+            lag: game.lag,
+            tags: Tags::default(),
+            rule_set: game.rule_set,
         }
     }
 }
@@ -77,7 +95,7 @@ impl fmt::Display for ArchivedGame {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.status {
             Status::AttackerWins => {
-                writeln!(
+                write!(
                     f,
                     "# {}: [{}: {} {}], {}: {} {}, {}: {}",
                     self.id,
@@ -92,7 +110,7 @@ impl fmt::Display for ArchivedGame {
                 )?;
             }
             Status::Draw | Status::Ongoing => {
-                writeln!(
+                write!(
                     f,
                     "# {}: {}: {} {}, {}: {} {}, {}: {}",
                     self.id,
@@ -107,7 +125,7 @@ impl fmt::Display for ArchivedGame {
                 )?;
             }
             Status::DefenderWins => {
-                writeln!(
+                write!(
                     f,
                     "# {}: {}: {} {}, [{}: {} {}], {}: {}",
                     self.id,
@@ -123,7 +141,60 @@ impl fmt::Display for ArchivedGame {
             }
         }
 
-        Ok(())
+        if let Some(opening) = opening(&self.plays.made()) {
+            write!(f, ", {}: {}", t!("Opening"), opening.name())?;
+        }
+
+        if !self.tags.is_empty() {
+            write!(f, ", {}: {}", t!("Tags"), self.tags)?;
+        }
+
+        writeln!(f)
+    }
+}
+
+/// Where a game comes from and what it was part of, so the games brought in
+/// from elsewhere can be told apart from the ones played here. The players
+/// can change the tags of their own games.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Tags {
+    /// The tournament or match, "World Championship 2026".
+    pub event: String,
+    pub round: String,
+    /// Where the game was played, "hnefatafl.org" or "playtafl.com".
+    pub source: String,
+    pub variant: String,
+    /// Anything else you want to find the game by.
+    pub labels: BTreeSet<String>,
+}
+
+impl Tags {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.values().next().is_none()
+    }
+
+    /// Whether any of the tags is `tag`, ignoring case.
+    #[must_use]
+    pub fn has(&self, tag: &str) -> bool {
+        self.values().any(|value| value.eq_ignore_ascii_case(tag))
+    }
+
+    /// The tags that are set, the labels last.
+    pub fn values(&self) -> impl Iterator<Item = &str> {
+        [&self.event, &self.round, &self.source, &self.variant]
+            .into_iter()
+            .chain(&self.labels)
+            .map(String::as_str)
+            .filter(|value| !value.is_empty())
+    }
+}
+
+impl fmt::Display for Tags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let values: Vec<_> = self.values().collect();
+        write!(f, "{}", values.join(", "))
     }
 }
 
@@ -158,6 +229,29 @@ pub struct Message {
     pub content: String,
 }
 
+/// An evaluation a spectator shares with the other spectators. The players
+/// never get these.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Kibitz {
+    pub username: String,
+    pub timestamp: Timestamp,
+    /// The number of plays made in the position evaluated.
+    pub play: usize,
+    /// From -1.0, the defender is winning, to 1.0, the attacker is winning.
+    pub score: f64,
+    pub best_play: Plae,
+}
+
+impl Kibitz {
+    /// The score as the attacker's part of an evaluation bar from 0.0 to 1.0.
+    #[must_use]
+    pub fn attacker_part(&self) -> f32 {
+        #[allow(clippy::cast_possible_truncation)]
+        let score = self.score.clamp(-1.0, 1.0) as f32;
+        f32::midpoint(score, 1.0)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ServerGame {
     pub id: Id,
@@ -167,7 +261,10 @@ pub struct ServerGame {
     pub defender_tx: Messenger,
     pub draw_requested: Role,
     pub elapsed_time: i64,
+    /// The milliseconds given back for lag on each move of a timed game.
+    pub lag: Vec<i64>,
     pub rated: Rated,
+    pub rule_set: RuleSet,
     pub game: Game,
     pub messages: VecDeque<Message>,
 }
@@ -182,7 +279,9 @@ impl From<ServerGameSerialized> for ServerGame {
             defender_tx: Messenger(None),
             draw_requested: Role::Roleless,
             elapsed_time: 0,
+            lag: Vec::new(),
             rated: server_game.rated,
+            rule_set: server_game.rule_set,
             game: server_game.game,
             messages: server_game.messages.clone(),
         }
@@ -234,7 +333,9 @@ impl ServerGame {
             defender_tx: Messenger(defender_tx),
             draw_requested: Role::Roleless,
             elapsed_time: 0,
+            lag: Vec::new(),
             rated: game.rated,
+            rule_set: game.rule_set,
             game: Game {
                 attacker_time: game.timed,
                 defender_time: game.timed,
@@ -248,6 +349,54 @@ impl ServerGame {
     }
 }
 
+impl ServerGame {
+    /// How much of `lag` the player whose turn it is gets back: no more than
+    /// the move has taken and nothing in a game without time.
+    #[must_use]
+    pub fn lag_allowed(&self, lag: i64) -> Option<i64> {
+        let TimeUnix::Time(turn_started) = self.game.time else {
+            return None;
+        };
+
+        let taken = self.elapsed_time + (clock::now().as_millisecond() - turn_started);
+        Some(lag.min(taken).max(0))
+    }
+
+    /// Gives the player whose turn it is `lag` back and keeps it with the
+    /// move.
+    pub fn lag_compensate(&mut self, lag: Option<i64>) {
+        if let Some(lag) = lag
+            && let Some(time) = self.time_of_turn()
+        {
+            time.milliseconds_left += lag;
+            self.lag.push(lag);
+        }
+    }
+
+    /// Takes back the lag given for a move that didn't go through.
+    pub fn lag_compensate_undo(&mut self, lag: Option<i64>) {
+        if let Some(lag) = lag
+            && let Some(time) = self.time_of_turn()
+        {
+            time.milliseconds_left -= lag;
+            self.lag.pop();
+        }
+    }
+
+    fn time_of_turn(&mut self) -> Option<&mut Time> {
+        let time = match self.game.turn {
+            Role::Attacker => &mut self.game.attacker_time,
+            Role::Defender => &mut self.game.defender_time,
+            Role::Roleless => return None,
+        };
+
+        match time {
+            TimeSettings::Timed(time) => Some(time),
+            TimeSettings::UnTimed => None,
+        }
+    }
+}
+
 impl fmt::Display for ServerGame {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -268,6 +417,25 @@ pub struct ServerGameSerialized {
     #[serde(default)]
     pub messages: VecDeque<Message>,
     pub timed: TimeSettings,
+    pub rule_set: RuleSet,
+    pub zen: bool,
+}
+
+impl ServerGameSerialized {
+    /// Reads the active games saved as postcard, the games saved before they
+    /// kept a rule set and zen mode are Copenhagen without zen mode.
+    ///
+    /// # Errors
+    ///
+    /// If the data is neither the current nor the older format.
+    pub fn from_postcard(data: &[u8]) -> anyhow::Result<Vec<Self>> {
+        if let Ok((games, [])) = postcard::take_from_bytes::<Vec<Self>>(data) {
+            return Ok(games);
+        }
+
+        let games: Vec<ServerGameSerializedOld> = postcard::from_bytes(data)?;
+        Ok(games.into_iter().map(Into::into).collect())
+    }
 }
 
 impl From<&ServerGame> for ServerGameSerialized {
@@ -280,6 +448,37 @@ impl From<&ServerGame> for ServerGameSerialized {
             game: game.game.clone(),
             messages: game.messages.clone(),
             timed: TimeSettings::default(),
+            rule_set: game.rule_set,
+            zen: false,
+        }
+    }
+}
+
+/// The active games as they were saved before they kept a rule set and zen
+/// mode.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ServerGameSerializedOld {
+    pub id: Id,
+    pub attacker: String,
+    pub defender: String,
+    pub rated: Rated,
+    pub game: Game,
+    pub messages: VecDeque<Message>,
+    pub timed: TimeSettings,
+}
+
+impl From<ServerGameSerializedOld> for ServerGameSerialized {
+    fn from(game: ServerGameSerializedOld) -> Self {
+        Self {
+            id: game.id,
+            attacker: game.attacker,
+            defender: game.defender,
+            rated: game.rated,
+            game: game.game,
+            messages: game.messages,
+            timed: game.timed,
+            rule_set: RuleSet::Copenhagen,
+            zen: false,
         }
     }
 }
@@ -296,8 +495,18 @@ pub struct NewGame {
     pub rated: bool,
     pub time_settings: TimeSettings,
     pub board_size: usize,
+    /// Only players without a provisional rating may join a rated game.
+    #[serde(default)]
+    pub established_only: bool,
+    /// The players don't see each other's names or ratings until the game
+    /// ends.
+    #[serde(default)]
+    pub zen: bool,
+    #[serde(default)]
+    pub rule_set: RuleSet,
 }
 
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct ServerGameLight {
     pub id: Id,
@@ -311,6 +520,12 @@ pub struct ServerGameLight {
     pub game_over: bool,
     pub board_size: BoardSize,
     pub turn: Role,
+    #[serde(default)]
+    pub established_only: bool,
+    #[serde(default)]
+    pub zen: bool,
+    #[serde(default)]
+    pub rule_set: RuleSet,
 }
 
 impl ServerGameLight {
@@ -336,6 +551,9 @@ impl ServerGameLight {
                 challenge_accepted: false,
                 game_over: false,
                 turn: Role::Roleless,
+                established_only: false,
+                zen: false,
+                rule_set: RuleSet::default(),
             }
         } else {
             Self {
@@ -350,6 +568,9 @@ impl ServerGameLight {
                 challenge_accepted: false,
                 game_over: false,
                 turn: Role::Roleless,
+                established_only: false,
+                zen: false,
+                rule_set: RuleSet::default(),
             }
         }
     }
@@ -382,14 +603,17 @@ impl From<&ServerGameSerialized> for ServerGameLight {
             challenge_accepted: true,
             game_over: false,
             turn: game.game.turn,
+            established_only: false,
+            zen: game.zen,
+            rule_set: game.rule_set,
         }
     }
 }
 
-impl TryFrom<&[&str; 12]> for ServerGameLight {
+impl TryFrom<&[&str; 13]> for ServerGameLight {
     type Error = anyhow::Error;
 
-    fn try_from(vector: &[&str; 12]) -> anyhow::Result<Self> {
+    fn try_from(vector: &[&str; 13]) -> anyhow::Result<Self> {
         let [
             _,
             id,
@@ -403,6 +627,7 @@ impl TryFrom<&[&str; 12]> for ServerGameLight {
             challenger,
             challenge_accepted,
             spectators,
+            rule_set,
         ] = *vector;
 
         let id = id.parse::<Id>()?;
@@ -447,6 +672,9 @@ impl TryFrom<&[&str; 12]> for ServerGameLight {
             challenge_accepted,
             game_over: false,
             turn: Role::Roleless,
+            established_only: false,
+            zen: false,
+            rule_set: RuleSet::from_str(rule_set)?,
         };
 
         if challenger != "_" {
@@ -535,3 +763,12 @@ pub struct UsersUpdated {
     pub removed: HashSet<String>,
     pub updated: Users,
 }
+
+/// What the server tells anyone who asks, even without logging in.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ServerStatus {
+    pub version: String,
+    pub players_online: usize,
+    /// When the next tournament starts, if one is scheduled.
+    pub tournament: Option<Timestamp>,
+}
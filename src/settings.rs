@@ -0,0 +1,105 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! The client settings the server keeps a copy of, so they follow you to your
+//! other computers.
+//!
+//! Each setting remembers when it was last changed. Merging two copies keeps
+//! the newer of each setting, so changing the theme on one computer and the
+//! volume on another keeps both changes.
+
+use std::collections::BTreeMap;
+
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// The settings the server keeps, it turns away any others.
+pub const NAMES: [&str; 7] = [
+    "coordinates",
+    "locale",
+    "my_games_only",
+    "rating_maximum",
+    "rating_minimum",
+    "theme",
+    "volume",
+];
+
+/// The longest a setting's value may be.
+pub const VALUE_LENGTH: usize = 64;
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Setting {
+    pub changed: Timestamp,
+    /// The setting as RON.
+    pub value: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Settings(pub BTreeMap<String, Setting>);
+
+impl Settings {
+    /// Whether every setting is one of [`NAMES`] with a value no longer than
+    /// [`VALUE_LENGTH`], which also keeps there from being more than
+    /// [`NAMES`] of them.
+    #[must_use]
+    pub fn valid(&self) -> bool {
+        self.0.iter().all(|(name, setting)| {
+            NAMES.contains(&name.as_str()) && setting.value.len() <= VALUE_LENGTH
+        })
+    }
+
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(|setting| setting.value.as_str())
+    }
+
+    /// Remembers the value as changed at `now` if it's different. A setting
+    /// seen for the first time counts as never changed, so it doesn't win over
+    /// a copy that has changed it.
+    pub fn set(&mut self, name: &str, value: String, now: Timestamp) {
+        match self.0.get_mut(name) {
+            Some(setting) if setting.value == value => {}
+            Some(setting) => {
+                setting.changed = now;
+                setting.value = value;
+            }
+            None => {
+                self.0.insert(
+                    name.to_string(),
+                    Setting {
+                        changed: Timestamp::UNIX_EPOCH,
+                        value,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Keeps the newer of each setting.
+    pub fn merge(&mut self, other: Self) {
+        for (name, setting) in other.0 {
+            match self.0.get_mut(&name) {
+                Some(ours) if ours.changed >= setting.changed => {}
+                Some(ours) => *ours = setting,
+                None => {
+                    self.0.insert(name, setting);
+                }
+            }
+        }
+    }
+}
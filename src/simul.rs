@@ -0,0 +1,90 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! Simultaneous exhibitions: a host plays one game against every opponent
+//! that joined, and all of the games start together.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Id, board::BoardSize, rating::Rated, role::Role, server_game::NewGame, time::TimeSettings,
+};
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Simul {
+    pub id: u64,
+    pub host: String,
+    /// The role the host plays on every board.
+    pub role: Role,
+    pub rated: Rated,
+    pub board_size: BoardSize,
+    pub time_settings: TimeSettings,
+    pub players: BTreeSet<String>,
+    /// The games in the order the host plays them, empty until the simul starts.
+    pub games: Vec<Id>,
+}
+
+impl Simul {
+    /// # Errors
+    ///
+    /// If the board size or the host's role is invalid.
+    pub fn new(id: u64, host: &str, new_game: &NewGame) -> anyhow::Result<Self> {
+        if new_game.role == Role::Roleless {
+            return Err(anyhow::Error::msg("the host has to play a role"));
+        }
+
+        Ok(Self {
+            id,
+            host: host.to_string(),
+            role: new_game.role,
+            rated: new_game.rated.into(),
+            board_size: new_game.board_size.try_into()?,
+            time_settings: new_game.time_settings,
+            players: BTreeSet::new(),
+            games: Vec::new(),
+        })
+    }
+
+    /// Returns the attacker and the defender of the game against `player`.
+    #[must_use]
+    pub fn attacker_defender<'a>(&'a self, player: &'a str) -> (&'a str, &'a str) {
+        if self.role == Role::Attacker {
+            (&self.host, player)
+        } else {
+            (player, &self.host)
+        }
+    }
+
+    #[must_use]
+    pub fn started(&self) -> bool {
+        !self.games.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Simuls(pub BTreeMap<u64, Simul>);
+
+impl Simuls {
+    /// Returns the simul the game is a part of.
+    #[must_use]
+    pub fn game(&self, id: &Id) -> Option<&Simul> {
+        self.0.values().find(|simul| simul.games.contains(id))
+    }
+}
@@ -18,12 +18,17 @@
 
 #![cfg(test)]
 
-use std::{fmt, str::FromStr, time::Duration};
+use std::{
+    fmt,
+    str::FromStr,
+    time::{Duration, Instant},
+};
 
 use crate::{
-    ai::{AI, AiBanal},
+    ai::{AI, AiBanal, AiMonteCarlo},
     board::{BoardSize, InvalidMove},
-    game_tree::Tree,
+    game_tree::{MEMORY, Tree},
+    locale::Locale,
     time::TimeSettings,
 };
 
@@ -41,6 +46,13 @@ fn assert_error_str<T: fmt::Debug>(result: anyhow::Result<T>, string: &str) {
     }
 }
 
+fn attacker_play(from: &str, to: &str) -> anyhow::Result<play::Play> {
+    match play::Plae::try_from(vec!["play", "attacker", from, to])? {
+        play::Plae::Play(play) => Ok(play),
+        _ => Err(anyhow::Error::msg("expected a play")),
+    }
+}
+
 #[test]
 fn monte_carlo() {
     let mut tree = Tree::new(Game::make(BoardSize::_11, &TimeSettings::UnTimed));
@@ -58,6 +70,72 @@ fn monte_carlo_long() {
     println!("{loops}");
 }
 
+#[test]
+fn monte_carlo_progress() -> anyhow::Result<()> {
+    let mut game = Game::default();
+    let mut ai = AiMonteCarlo::new(Duration::from_secs(1), 20, MEMORY);
+    let mut reports = Vec::new();
+
+    let generate_move = ai.generate_move_progress(&mut game, &mut |progress| {
+        reports.push(progress);
+    })?;
+
+    assert!(!reports.is_empty());
+    assert!(
+        reports
+            .iter()
+            .all(|progress| 0.0 < progress.done && progress.done < 1.0 && progress.play.is_some())
+    );
+    assert!(
+        reports
+            .array_windows()
+            .all(|[progress_1, progress_2]| progress_1.loops < progress_2.loops)
+    );
+    assert!(
+        reports
+            .iter()
+            .all(|progress| progress.loops < generate_move.loops)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn monte_carlo_cancel() -> anyhow::Result<()> {
+    let mut game = Game::default();
+    let mut ai = AiMonteCarlo::new(Duration::from_secs(50), 20, MEMORY);
+    let cancel = ai
+        .cancel_token()
+        .ok_or_else(|| anyhow::Error::msg("expected a cancel token"))?;
+
+    let started = Instant::now();
+    let result = ai.generate_move_progress(&mut game, &mut |_progress| cancel.cancel());
+
+    assert!(result.is_err());
+    assert!(started.elapsed() < Duration::from_secs(10));
+    assert_eq!(game.plays.len(), 0);
+
+    let mut ai = AiMonteCarlo::new(Duration::from_secs(1), 20, MEMORY);
+    ai.cancel_token()
+        .ok_or_else(|| anyhow::Error::msg("expected a cancel token"))?
+        .cancel();
+
+    ai.generate_move(&mut game)?;
+    assert_eq!(game.plays.len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn monte_carlo_memory() {
+    let memory = 64 * 1024;
+    let mut tree = Tree::with_memory(Game::make(BoardSize::_11, &TimeSettings::UnTimed), memory);
+    let (_loops, plays) = tree.monte_carlo_tree_search(Duration::from_secs(1), 80);
+
+    assert!(tree.nodes() <= tree.max_nodes());
+    assert!(!plays.is_empty());
+}
+
 #[test]
 fn flood_fill_1() -> anyhow::Result<()> {
     let board_1 = [
@@ -1961,6 +2039,33 @@ fn kings_captured_surround_2() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn kings_captured_surround_joining_pockets() -> anyhow::Result<()> {
+    let board = [
+        "...........",
+        "...........",
+        "...........",
+        "...........",
+        "...........",
+        "...........",
+        ".....X.....",
+        "..XXX.XXX..",
+        ".X.K.X.O.X.",
+        "..XXXXXXX..",
+        "...........",
+    ];
+
+    let mut game = game::Game {
+        board: board.try_into()?,
+        ..Default::default()
+    };
+
+    game.read_line("play attacker f3 f4")?;
+    assert_eq!(game.status, Status::AttackerWins);
+
+    Ok(())
+}
+
 // Eight
 
 #[test]
@@ -2035,6 +2140,33 @@ fn defender_automatically_loses_1() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn defender_automatically_loses_on_the_edge() -> anyhow::Result<()> {
+    let board = [
+        "...........",
+        "...........",
+        "...........",
+        "...........",
+        "...........",
+        "...........",
+        "...........",
+        ".....X.....",
+        "....X.X....",
+        "....XKX....",
+        ".........X.",
+    ];
+
+    let mut game = game::Game {
+        board: board.try_into()?,
+        ..Default::default()
+    };
+
+    game.read_line("play attacker j1 f1")?;
+    assert_eq!(game.status, Status::AttackerWins);
+
+    Ok(())
+}
+
 #[test]
 fn defender_automatically_loses_2() -> anyhow::Result<()> {
     let board = [
@@ -3297,3 +3429,886 @@ fn someone_wins() {
 
     assert!(game.status == Status::AttackerWins || game.status == Status::DefenderWins);
 }
+
+#[test]
+fn rating_categories() -> anyhow::Result<()> {
+    use crate::{
+        rating::{RatingCategory, Ratings},
+        rule_set::RuleSet,
+        time::{Speed, TimeEnum},
+    };
+
+    for (time, speed) in [
+        (TimeEnum::AI, Speed::Blitz),
+        (TimeEnum::Blitz, Speed::Blitz),
+        (TimeEnum::Rapid, Speed::Rapid),
+        (TimeEnum::Classical, Speed::Classical),
+        (TimeEnum::Long, Speed::Correspondence),
+        (TimeEnum::VeryLong, Speed::Correspondence),
+        (TimeEnum::Infinity, Speed::Correspondence),
+    ] {
+        let time_settings: TimeSettings = time.into();
+        assert_eq!(Speed::from(&time_settings), speed);
+    }
+
+    let blitz_11 =
+        RatingCategory::new(RuleSet::Copenhagen, BoardSize::_11, &TimeEnum::Blitz.into());
+    let blitz_13 =
+        RatingCategory::new(RuleSet::Copenhagen, BoardSize::_13, &TimeEnum::Blitz.into());
+    assert_eq!(blitz_11.to_string(), "11x11 blitz");
+
+    let mut ratings = Ratings::default();
+    ratings
+        .get_mut(blitz_11)
+        .update_rating(1_500.0, &glicko::Outcome::Win);
+
+    assert!(ratings.get(&blitz_11).rating > 1_500.0);
+    assert_eq!(ratings.get(&blitz_13), glicko::Rating::default());
+
+    let ratings_ron = ron::ser::to_string(&ratings)?;
+    assert_eq!(ron::de::from_str::<Ratings>(&ratings_ron)?, ratings);
+
+    // The categories saved before there were rule sets are Copenhagen.
+    assert_eq!(
+        ron::de::from_str::<RatingCategory>("(board_size:_11,speed:Blitz)")?,
+        blitz_11
+    );
+    assert_eq!("copenhagen".parse::<RuleSet>()?, RuleSet::Copenhagen);
+    assert!("tablut".parse::<RuleSet>().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn rule_sets_on_games() -> anyhow::Result<()> {
+    use std::collections::VecDeque;
+
+    use crate::{
+        rating::Rated,
+        rule_set::RuleSet,
+        server_game::{ServerGameLight, ServerGameSerialized, ServerGameSerializedOld},
+    };
+
+    let old = |id| ServerGameSerializedOld {
+        id,
+        attacker: "david".to_string(),
+        defender: "abby".to_string(),
+        rated: Rated::Yes,
+        game: Game::default(),
+        messages: VecDeque::new(),
+        timed: TimeSettings::default(),
+    };
+
+    // The active games saved before they kept a rule set still load.
+    let games =
+        ServerGameSerialized::from_postcard(&postcard::to_allocvec(&vec![old(1), old(2)])?)?;
+    assert_eq!(games.len(), 2);
+    assert!(
+        games
+            .iter()
+            .all(|game| game.rule_set == RuleSet::Copenhagen)
+    );
+
+    let games = ServerGameSerialized::from_postcard(&postcard::to_allocvec(&games)?)?;
+    assert_eq!(games.iter().map(|game| game.id).collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(
+        ServerGameLight::from(&games[0]).rule_set,
+        RuleSet::Copenhagen
+    );
+
+    let mut text = [
+        "game", "6", "david", "_", "rated", "fischer", "900000", "10", "11", "_", "false", "{}",
+        "tablut",
+    ];
+    assert!(ServerGameLight::try_from(&text).is_err());
+    text[12] = "copenhagen";
+    assert_eq!(
+        ServerGameLight::try_from(&text)?.rule_set,
+        RuleSet::Copenhagen
+    );
+
+    Ok(())
+}
+
+#[test]
+fn community_votes() -> anyhow::Result<()> {
+    use crate::community::{VOTE_MILLISECONDS, Votes};
+
+    let mut votes = Votes::default();
+    assert_eq!(votes.winner(), None);
+
+    votes.vote("abby", attacker_play("d1", "d4")?, 1_000);
+    assert_eq!(votes.closes, 1_000 + VOTE_MILLISECONDS);
+
+    votes.vote("bob", attacker_play("h1", "h4")?, 2_000);
+    votes.vote("carl", attacker_play("h1", "h4")?, 3_000);
+    assert_eq!(votes.closes, 1_000 + VOTE_MILLISECONDS);
+    assert_eq!(votes.winner(), Some(attacker_play("h1", "h4")?));
+
+    votes.vote("bob", attacker_play("d1", "d4")?, 4_000);
+    assert_eq!(
+        votes.tally(),
+        vec![
+            (attacker_play("d1", "d4")?, 2),
+            (attacker_play("h1", "h4")?, 1)
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn commands() -> anyhow::Result<()> {
+    use crate::command::Command;
+
+    assert_eq!(
+        Command::parse("f6-f9", BoardSize::_13)?,
+        Command::Play {
+            from: Vertex {
+                size: BoardSize::_13,
+                x: 5,
+                y: 7,
+            },
+            to: Vertex {
+                size: BoardSize::_13,
+                x: 5,
+                y: 4,
+            },
+        }
+    );
+
+    assert_eq!(
+        Command::parse("F6-f9", BoardSize::_11)?,
+        Command::parse("f6-F9", BoardSize::_11)?
+    );
+
+    assert_eq!(
+        Command::parse(" /say hi there ", BoardSize::_11)?,
+        Command::Say("hi there".to_string())
+    );
+    assert_eq!(Command::parse("/resign", BoardSize::_11)?, Command::Resign);
+    assert_eq!(Command::parse("/draw", BoardSize::_11)?, Command::Draw);
+
+    assert!(Command::parse("/say", BoardSize::_11).is_err());
+    assert!(Command::parse("/dance", BoardSize::_11).is_err());
+    assert!(Command::parse("l1-l5", BoardSize::_11).is_err());
+    assert!(Command::parse("a12-a5", BoardSize::_11).is_err());
+    assert!(Command::parse("a1", BoardSize::_11).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn locales() -> anyhow::Result<()> {
+    for locale in [
+        Locale::English,
+        Locale::Chinese,
+        Locale::Spanish,
+        Locale::Arabic,
+        Locale::Indonesian,
+        Locale::PortugueseBr,
+        Locale::PortuguesePt,
+        Locale::French,
+        Locale::Japanese,
+        Locale::Russian,
+        Locale::German,
+        Locale::Icelandic,
+        Locale::IcelandicRunic,
+        Locale::Swedish,
+        Locale::Korean,
+    ] {
+        assert_eq!(Locale::from_str(&locale.txt())?, locale);
+    }
+
+    assert!(Locale::from_str("en").is_err());
+
+    let body = rust_i18n::t!(
+        "Dear %{username},\nyour new password is as follows: %{password}",
+        locale = &Locale::German.txt(),
+        username = "david",
+        password = "1234abcd",
+    );
+    assert_eq!(
+        body,
+        "Liebe(r) david,\ndein neues Passwort lautet: 1234abcd"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn repetition() -> anyhow::Result<()> {
+    let mut game = Game::make(BoardSize::_11, &TimeSettings::UnTimed);
+    let f3 = Vertex::from_str("f3")?;
+    let f4 = Vertex::from_str("f4")?;
+
+    game.read_line("play attacker a4 b4")?;
+    game.read_line("play defender f4 f3")?;
+    game.read_line("play attacker b4 c4")?;
+    game.read_line("play defender f3 f4")?;
+    assert!(!game.position_repeated());
+
+    game.read_line("play attacker c4 b4")?;
+    assert!(game.position_repeated());
+
+    let repetition_moves = game.repetition_moves();
+    assert_eq!(repetition_moves.moves.get(&f4), Some(&vec![f3]));
+    assert_eq!(repetition_moves.moves.len(), 1);
+    assert!(
+        !game
+            .all_legal_moves()
+            .moves
+            .get(&f4)
+            .is_some_and(|tos| tos.contains(&f3))
+    );
+    assert!(game.read_line("play defender f4 f3").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn analysis() -> anyhow::Result<()> {
+    let mut game = Game::make(BoardSize::_11, &TimeSettings::UnTimed);
+    game.read_line("play attacker d1 d3")?;
+    game.read_line("play defender f4 c4")?;
+    game.read_line("play attacker k4 j4")?;
+    game.read_line("play defender resigns _")?;
+
+    let moves = analysis::analyze(BoardSize::_11, &game.plays);
+    assert_eq!(moves.len(), 3);
+    assert_eq!(
+        moves
+            .iter()
+            .map(|analysis| analysis.number)
+            .collect::<Vec<_>>(),
+        [1, 2, 3]
+    );
+
+    for analysis in &moves {
+        assert!(analysis.loss >= 0.0 && analysis.loss <= analysis::LOSS_MAX);
+        assert!(analysis.accuracy() > 0.0 && analysis.accuracy() <= 100.0);
+    }
+
+    let analysis = analysis::Analysis::new(BoardSize::_11, &game.plays);
+    assert!(analysis.attacker_accuracy.is_some());
+    assert!(analysis.defender_accuracy.is_some());
+
+    assert_eq!(analysis.phases.len(), 1);
+    assert_eq!(analysis.phases[0].phase, game::Phase::Opening);
+    assert!(analysis.phases[0].attacker_accuracy.is_some());
+
+    let summary = analysis.summary("attacker", "defender");
+    assert!(summary.len() > 2);
+    assert!(
+        summary
+            .iter()
+            .any(|sentence| sentence.starts_with("attacker played with"))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn accuracy() {
+    let mut accuracy = analysis::Accuracy::default();
+    assert_eq!(accuracy.average(), None);
+    assert_eq!(accuracy.recent_average(), None);
+
+    for _ in 0..20 {
+        accuracy.add(50.0);
+    }
+    for _ in 0..20 {
+        accuracy.add(100.0);
+    }
+
+    assert_eq!(accuracy.games, 40);
+    assert_eq!(accuracy.average(), Some(75.0));
+    assert_eq!(accuracy.recent_average(), Some(100.0));
+}
+
+#[test]
+fn proof_of_work() {
+    let solution = proof_of_work::solve("5eed", 8);
+
+    assert!(proof_of_work::verify("5eed", 8, solution));
+    assert!(proof_of_work::verify("5eed", 0, 0));
+    assert!(
+        (0..solution).all(|wrong| !proof_of_work::verify("5eed", 8, wrong)),
+        "the first solution"
+    );
+}
+
+#[test]
+fn previous_boards_past_the_recent_ones() -> anyhow::Result<()> {
+    let mut game = Game::default();
+    let mut boards = vec![game.board.clone()];
+
+    for _ in 0..20 {
+        let legal_moves = game.all_legal_moves();
+        let mut plays: Vec<_> = legal_moves
+            .moves
+            .iter()
+            .flat_map(|(from, tos)| tos.iter().map(|to| (*from, *to)))
+            .collect();
+        plays.sort();
+
+        let (from, to) = plays
+            .first()
+            .ok_or_else(|| anyhow::Error::msg("expected a legal move"))?;
+        game.read_line(&format!("play {} {from} {to}", legal_moves.role))?;
+        boards.push(game.board.clone());
+    }
+
+    assert_eq!(game.previous_boards.len(), boards.len());
+    assert!(
+        boards
+            .iter()
+            .all(|board| game.previous_boards.contains(board))
+    );
+    assert!(!game.previous_boards.contains(&Board::new(BoardSize::_13)));
+
+    let saved = ron::to_string(&game)?;
+    let mut restored: Game = ron::from_str(&saved)?;
+    assert!(restored.previous_boards.len() < boards.len());
+
+    let (board, previous_boards) = restored
+        .replay(restored.plays.len())
+        .ok_or_else(|| anyhow::Error::msg("expected the plays to replay"))?;
+    assert_eq!(board, game.board);
+    assert_eq!(previous_boards.len(), boards.len());
+    restored.previous_boards = previous_boards;
+
+    for board in boards.iter().rev().skip(1).take(15) {
+        restored.read_line("play_undo")?;
+        assert_eq!(&restored.board, board);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn server_clock() {
+    use crate::time::ServerClock;
+
+    let mut clock = ServerClock::default();
+    assert_eq!(clock.offset(), 0);
+
+    // The server is 5 seconds ahead and the first ping took long on the way
+    // back.
+    clock.ping(1_000, 1_900, 6_100);
+    assert_eq!(clock.offset(), 4_650);
+
+    clock.ping(2_000, 2_100, 7_050);
+    assert_eq!(clock.offset(), 5_000);
+
+    // A ping that came back before it was sent is thrown out.
+    clock.ping(3_000, 2_000, 0);
+    assert_eq!(clock.offset(), 5_000);
+
+    // Only the latest pings count.
+    for sent in 0..8 {
+        clock.ping(10_000 + sent, 10_300 + sent, 20_000);
+    }
+    assert_eq!(clock.offset(), 9_850);
+}
+
+#[test]
+fn checksum() -> anyhow::Result<()> {
+    let mut game_1 = Game::default();
+    let mut game_2 = Game::default();
+    let started = game_1.checksum();
+    assert_eq!(started.len(), 16);
+
+    game_1.read_line("play attacker d1 d3")?;
+    assert_ne!(game_1.checksum(), started);
+    assert_ne!(game_1.checksum(), game_2.checksum());
+
+    game_2.read_line("play attacker d1 d3")?;
+    assert_eq!(game_1.checksum(), game_2.checksum());
+
+    game_1.read_line("play_undo")?;
+    assert_eq!(game_1.checksum(), started);
+
+    Ok(())
+}
+
+#[test]
+fn icalendar() -> anyhow::Result<()> {
+    use crate::{rating::Rated, tournament::Announcement};
+
+    let announcement = Announcement {
+        date: "2026-03-14T18:30:00Z".parse()?,
+        board_size: BoardSize::_11,
+        time_setting: TimeSettings::UnTimed,
+        rated: Rated::Yes,
+        players: 3,
+    };
+
+    let icalendar = announcement.icalendar("2026-03-01T12:00:00Z".parse()?);
+    let lines: Vec<_> = icalendar.split("\r\n").collect();
+
+    assert_eq!(lines.first(), Some(&"BEGIN:VCALENDAR"));
+    assert!(lines.contains(&"DTSTART:20260314T183000Z"));
+    assert!(lines.contains(&"DTSTAMP:20260301T120000Z"));
+    assert!(lines.contains(&"DESCRIPTION:board size: 11\\, time: ∞\\, rated"));
+    assert!(icalendar.ends_with("END:VCALENDAR\r\n"));
+
+    Ok(())
+}
+
+#[test]
+fn profile() -> anyhow::Result<()> {
+    use crate::profile::{BIO_LENGTH, Profile};
+
+    let mut profile = Profile::default();
+    profile.set_country("is")?;
+    assert_eq!(profile.country.as_deref(), Some("IS"));
+    assert_eq!(profile.flag().as_deref(), Some("🇮🇸"));
+
+    assert!(profile.set_country("ISL").is_err());
+    assert!(profile.set_country("1s").is_err());
+    profile.set_country("-")?;
+    assert_eq!(profile.flag(), None);
+
+    profile.set_bio(&"a".repeat(BIO_LENGTH))?;
+    assert!(profile.set_bio(&"a".repeat(BIO_LENGTH + 1)).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn vacation() -> anyhow::Result<()> {
+    use crate::{
+        time::DAY,
+        vacation::{VACATION_DAYS, Vacation},
+    };
+
+    let now: jiff::Timestamp = "2026-12-30T12:00:00Z".parse()?;
+    let mut vacation = Vacation::default();
+    assert_eq!(vacation.days_left(now), VACATION_DAYS);
+
+    assert_error_str(
+        vacation.start(0, now),
+        "you can take from 1 to 30 vacation days",
+    );
+    assert!(vacation.start(VACATION_DAYS + 1, now).is_err());
+
+    vacation.start(5, now)?;
+    vacation.start(5, now)?;
+    assert!(vacation.on(now));
+    assert_eq!(vacation.until, Some(now.as_millisecond() + 10 * DAY));
+    assert_eq!(vacation.days_left(now), 20);
+
+    let later = jiff::Timestamp::from_millisecond(now.as_millisecond() + 3 * DAY + 1)?;
+    assert_eq!(vacation.days_left(later), VACATION_DAYS);
+    vacation.end(now);
+    assert!(!vacation.on(now));
+    assert_eq!(vacation.days_left(now), VACATION_DAYS);
+
+    Ok(())
+}
+
+#[test]
+fn openings() -> anyhow::Result<()> {
+    use crate::openings::{OPENINGS, Opening, opening};
+
+    for expected in &OPENINGS {
+        let mut game = Game::default();
+        for (i, play) in expected.plays.iter().enumerate() {
+            let role = if i % 2 == 0 { "attacker" } else { "defender" };
+            game.read_line(&format!("play {role} {}", play.replace('-', " ")))?;
+        }
+
+        let plays = game.plays.made();
+        assert_eq!(opening(&plays), Some(expected));
+    }
+
+    let mut game = Game::default();
+    game.read_line("play attacker h1 h3")?;
+    game.read_line("play defender h6 h4")?;
+    game.read_line("play attacker a5 c5")?;
+    assert_eq!(
+        opening(&game.plays.made()).map(Opening::name),
+        Some("d1-d3 d6-d4".to_string())
+    );
+
+    let mut game = Game::default();
+    game.read_line("play attacker a4 b4")?;
+    assert_eq!(opening(&game.plays.made()), None);
+
+    Ok(())
+}
+
+#[test]
+fn puzzles() -> anyhow::Result<()> {
+    use crate::{
+        analysis::{Judgement, analyze},
+        puzzle::{Puzzles, Theme, find},
+    };
+
+    let mut game = Game::make(BoardSize::_11, &TimeSettings::UnTimed);
+    for play in [
+        "attacker b6 b3",
+        "defender f4 d4",
+        "attacker d1 d2",
+        "defender f8 e8",
+        "attacker b3 e3",
+        "defender g5 g2",
+        "attacker a6 c6",
+        "defender f5 h5",
+        "attacker e3 e2",
+        "defender f6 f3",
+        "attacker h1 h2",
+        "defender e8 f8",
+        "attacker c6 c8",
+        "defender e7 e10",
+        "attacker k5 i5",
+        "defender f3 a3",
+        "attacker g11 g10",
+        "defender d6 d10",
+    ] {
+        game.read_line(&format!("play {play}"))?;
+    }
+
+    let moves = analyze(BoardSize::_11, &game.plays);
+    assert_eq!(moves[16].judgement, Judgement::Blunder);
+    assert_eq!(moves[17].judgement, Judgement::MissedWin);
+
+    let found = find(7, BoardSize::_11, &game.plays, &moves);
+    assert_eq!(found.len(), 2);
+
+    let puzzle = &found[0];
+    assert_eq!(puzzle.plays.len(), 16);
+    assert_eq!(puzzle.role(), Role::Attacker);
+    assert_eq!(puzzle.theme, Theme::ShieldWall);
+    assert_eq!(puzzle.solution.to_string(), "play attacker d2 a2");
+
+    let puzzle = &found[1];
+    assert_eq!(puzzle.game_id, 7);
+    assert_eq!(puzzle.plays.len(), 17);
+    assert_eq!(puzzle.role(), Role::Defender);
+    assert_eq!(puzzle.theme, Theme::FortBreak);
+    assert_eq!(puzzle.solution.to_string(), "play defender a3 a1");
+    assert_eq!(puzzle.archived_game("a", "d").plays.made().len(), 18);
+
+    let mut puzzles = Puzzles::default();
+    puzzles.add(found.clone());
+    puzzles.add(found);
+    assert_eq!(puzzles.0.len(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn puzzle_streak() -> anyhow::Result<()> {
+    use crate::puzzle::{Streak, day};
+
+    assert_eq!(day("1970-01-02T00:00:00Z".parse()?), 1);
+    assert_eq!(day("1969-12-31T23:59:59Z".parse()?), -1);
+
+    let mut streak = Streak::default();
+    streak.solve(10);
+    streak.solve(11);
+    assert!(streak.tried(11));
+    assert!(!streak.tried(12));
+    assert_eq!(streak.current(12), 2);
+    assert_eq!(streak.current(13), 0);
+
+    streak.solve(13);
+    assert_eq!(streak.current(13), 1);
+    assert_eq!(streak.best, 2);
+
+    streak.miss(14);
+    assert_eq!(streak.current(14), 0);
+    assert_eq!(streak.best, 2);
+
+    Ok(())
+}
+
+#[test]
+fn phases() -> anyhow::Result<()> {
+    use crate::game::Phase;
+
+    let mut game = Game::default();
+    assert_eq!(game.phase(), Phase::Opening);
+
+    for play in [
+        "attacker a4 b4",
+        "defender e7 c7",
+        "attacker k4 j4",
+        "defender g7 i7",
+        "attacker a8 b8",
+        "defender e5 c5",
+        "attacker k8 j8",
+        "defender g5 i5",
+        "attacker d11 d10",
+        "defender f8 c8",
+        "attacker h1 h2",
+    ] {
+        game.read_line(&format!("play {play}"))?;
+    }
+
+    assert_eq!(game.phase(), Phase::Opening);
+    game.read_line("play defender f4 c4")?;
+    assert_eq!(game.phase(), Phase::Middlegame);
+
+    let mut game = Game::default();
+    for play in [
+        "attacker b6 b3",
+        "defender f4 d4",
+        "attacker d1 d2",
+        "defender f8 e8",
+        "attacker b3 e3",
+        "defender g5 g2",
+        "attacker a6 c6",
+        "defender f5 h5",
+        "attacker e3 e2",
+        "defender f6 f3",
+        "attacker h1 h2",
+        "defender e8 f8",
+        "attacker c6 c8",
+        "defender e7 e10",
+        "attacker k5 i5",
+        "defender f3 a3",
+    ] {
+        game.read_line(&format!("play {play}"))?;
+    }
+
+    assert_eq!(game.phase(), Phase::Endgame);
+    assert!(Phase::Opening.time_share() < Phase::Middlegame.time_share());
+
+    Ok(())
+}
+
+#[test]
+fn physical_board() -> anyhow::Result<()> {
+    use crate::physical_board::{self, LineConnection};
+
+    let events =
+        "lift f4\nplace f2\n\nlift d1\nplace d1\nlift a4\nplace a2\nlift e2\nlift f6\nplace f4\n";
+    let mut connection = LineConnection::new(events.as_bytes());
+
+    let mut moves = Vec::new();
+    physical_board::moves(&mut connection, |from, to| {
+        moves.push(format!("{from}-{to}"));
+    })?;
+    assert_eq!(moves, ["f4-f2", "a4-a2", "f6-f4"]);
+
+    let mut connection = LineConnection::new("lift f4\npress f4\n".as_bytes());
+    assert!(physical_board::moves(&mut connection, |_, _| {}).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn hooks() -> anyhow::Result<()> {
+    use crate::hooks::{Action, Event, Hooks};
+
+    let hooks: Hooks = "
+        # Only play people near your rating.
+        on challenge if rating < 1400 then decline
+        on challenge if rated = 0 and board_size = 13 then decline
+        on move if captures > 1 then sound
+        on game_end if won = 1 then say Good game!
+    "
+    .parse()?;
+
+    assert_eq!(
+        hooks.run(
+            Event::Challenge,
+            &[("rating", 1300.0), ("rated", 1.0), ("board_size", 11.0)]
+        ),
+        [&Action::Decline]
+    );
+    assert!(
+        hooks
+            .run(
+                Event::Challenge,
+                &[("rating", 1500.0), ("rated", 0.0), ("board_size", 11.0)]
+            )
+            .is_empty()
+    );
+    assert_eq!(
+        hooks.run(
+            Event::GameEnd,
+            &[("won", 1.0), ("lost", 0.0), ("draw", 0.0)]
+        ),
+        [&Action::Say("Good game!".to_string())]
+    );
+    assert!(hooks.run(Event::Move, &[("captures", 1.0)]).is_empty());
+
+    assert!("on move then decline".parse::<Hooks>().is_err());
+    assert!(
+        "on challenge if captures > 1 then sound"
+            .parse::<Hooks>()
+            .is_err()
+    );
+    assert!(
+        "on challenge if rating ~ 1 then sound"
+            .parse::<Hooks>()
+            .is_err()
+    );
+    assert!("on resign then sound".parse::<Hooks>().is_err());
+    assert!("on move then run rm -rf".parse::<Hooks>().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn quiz() -> anyhow::Result<()> {
+    use crate::community::Quiz;
+
+    let mut quiz = Quiz::default();
+    quiz.guess("abby", attacker_play("d1", "d3")?);
+    quiz.guess("bob", attacker_play("d1", "d2")?);
+    quiz.guess("bob", attacker_play("d1", "d3")?);
+    quiz.guess("carl", attacker_play("a4", "b4")?);
+    quiz.played(&attacker_play("d1", "d3")?);
+    assert!(quiz.guesses.is_empty());
+
+    quiz.guess("abby", attacker_play("a4", "b4")?);
+    quiz.played(&attacker_play("a4", "c4")?);
+
+    let results: Vec<_> = quiz
+        .results()
+        .into_iter()
+        .map(|(username, score)| (username, score.right, score.guessed))
+        .collect();
+
+    assert_eq!(
+        results,
+        [
+            ("bob".to_string(), 1, 1),
+            ("abby".to_string(), 1, 2),
+            ("carl".to_string(), 0, 1),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn settings() -> anyhow::Result<()> {
+    use crate::settings::Settings;
+    use jiff::Timestamp;
+
+    let later = Timestamp::from_second(100)?;
+
+    let mut desktop = Settings::default();
+    desktop.set("theme", "Dark".to_string(), later);
+    desktop.set("volume", "(0.5)".to_string(), later);
+
+    let mut laptop = desktop.clone();
+    desktop.set("theme", "Light".to_string(), later);
+    laptop.set("volume", "(1.0)".to_string(), later);
+    laptop.set("locale", "German".to_string(), later);
+
+    // The first value seen counts as never changed.
+    assert_eq!(
+        desktop.0.get("volume").map(|setting| setting.changed),
+        Some(Timestamp::UNIX_EPOCH)
+    );
+
+    desktop.merge(laptop.clone());
+    laptop.merge(desktop.clone());
+    assert_eq!(desktop, laptop);
+    assert_eq!(desktop.get("theme"), Some("Light"));
+    assert_eq!(desktop.get("volume"), Some("(1.0)"));
+    assert_eq!(desktop.get("locale"), Some("German"));
+
+    Ok(())
+}
+
+#[test]
+fn locale_formatting() -> anyhow::Result<()> {
+    use jiff::{civil, tz::TimeZone};
+
+    assert_eq!(Locale::English.number(1_234_567.891, 2), "1,234,567.89");
+    assert_eq!(Locale::German.number(1_234.5, 1), "1.234,5");
+    assert_eq!(Locale::French.number(-1_234.0, 0), "-1\u{202F}234");
+    assert_eq!(Locale::Spanish.number(1_234.0, 0), "1234");
+    assert_eq!(Locale::Spanish.number(12_345.0, 0), "12.345");
+    assert_eq!(Locale::English.number(-0.001, 1), "0.0");
+    assert_eq!(Locale::Arabic.number(1_500.25, 1), "١٬٥٠٠٫٢");
+
+    let date = civil::date(2026, 3, 7);
+    assert_eq!(Locale::English.date(date), "3/7/2026");
+    assert_eq!(Locale::German.date(date), "07.03.2026");
+    assert_eq!(Locale::Swedish.date(date), "2026-03-07");
+    assert_eq!(Locale::Korean.date(date), "2026. 3. 7.");
+
+    let time = civil::time(21, 5, 0, 0);
+    assert_eq!(Locale::English.time(time), "9:05 PM");
+    assert_eq!(Locale::French.time(time), "21:05");
+    assert_eq!(Locale::Indonesian.time(time), "21.05");
+
+    let zoned = date.at(21, 5, 0, 0).to_zoned(TimeZone::UTC)?;
+    assert_eq!(Locale::Japanese.date_time(&zoned), "2026/3/7 21:05 UTC");
+
+    Ok(())
+}
+
+#[test]
+fn gauntlet_score() {
+    use crate::bench::{Gauntlet, Pairing};
+
+    let pairing = Pairing {
+        baseline: "banal".to_string(),
+        seconds: 1,
+        wins: 3,
+        draws: 2,
+        losses: 1,
+    };
+    assert_eq!(pairing.games(), 6);
+    assert!((pairing.score() - 4.0 / 6.0).abs() < f64::EPSILON);
+    assert_eq!(pairing.to_string(), "banal at 1 s: +3 =2 -1, 66.7%");
+
+    assert!(Pairing::default().score().abs() < f64::EPSILON);
+
+    let other = Pairing {
+        baseline: "basic".to_string(),
+        seconds: 1,
+        wins: 0,
+        draws: 0,
+        losses: 2,
+    };
+    let gauntlet = Gauntlet(vec![pairing, other]);
+    let total = gauntlet.total();
+    assert_eq!((total.wins, total.draws, total.losses), (3, 2, 3));
+    assert!((total.score() - 0.5).abs() < f64::EPSILON);
+    assert_eq!(
+        gauntlet.to_string(),
+        "banal at 1 s: +3 =2 -1, 66.7%\nbasic at 1 s: +0 =0 -2, 0.0%\ntotal: +3 =2 -3, 50.0%"
+    );
+}
+
+#[test]
+fn gauntlet_pairings() -> anyhow::Result<()> {
+    use crate::bench::{GauntletSettings, gauntlet};
+
+    let mut settings = GauntletSettings {
+        candidate: "banal".to_string(),
+        depth: None,
+        memory: None,
+        baselines: vec!["banal".to_string()],
+        seconds: vec![3, 1],
+        games: 4,
+        board_size: BoardSize::_11,
+        max_plies: 10,
+        seed: 7,
+    };
+
+    let report = gauntlet(&settings)?;
+    let pairings: Vec<_> = report
+        .0
+        .iter()
+        .map(|pairing| (pairing.baseline.as_str(), pairing.seconds, pairing.games()))
+        .collect();
+    assert_eq!(pairings, [("banal", 3, 4), ("banal", 1, 4)]);
+    assert_eq!(gauntlet(&settings)?, report);
+
+    settings.baselines.push("nobody".to_string());
+    assert!(gauntlet(&settings).is_err());
+
+    Ok(())
+}
@@ -16,18 +16,22 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 // SPDX-FileCopyrightText: 2025 David Campbell <david@hnefatafl.org>
 
-use std::fmt;
+use std::{collections::VecDeque, fmt};
 
 use jiff::Timestamp;
+use rust_i18n::t;
 use serde::{Deserialize, Serialize};
 
-use crate::board::InvalidMove;
+use crate::{board::InvalidMove, clock, locale::Locale};
 
 pub const DAY: i64 = 24 * 60 * 60 * 1_000;
 pub const HOUR: i64 = 60 * 60 * 1_000;
 pub const MINUTE: i64 = 60 * 1_000;
 pub const SECOND: i64 = 1_000;
 
+/// How many pings the server clock remembers.
+const PINGS_KEPT: usize = 8;
+
 #[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
 pub struct Time {
     pub add_seconds: i64,
@@ -197,7 +201,7 @@ fn time_left(milliseconds_left: i64) -> String {
     let minutes = (milliseconds_left % HOUR) / MINUTE;
     let seconds = (milliseconds_left % MINUTE) / SECOND;
 
-    if days == 0 {
+    let time_left = if days == 0 {
         if hours == 0 {
             if minutes == 0 {
                 format!("{seconds:02}")
@@ -209,7 +213,9 @@ fn time_left(milliseconds_left: i64) -> String {
         }
     } else {
         format!("{days} {hours:02}:{minutes:02}:{seconds:02}")
-    }
+    };
+
+    Locale::current().digits(&time_left)
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -311,6 +317,53 @@ impl fmt::Display for TimeEnum {
     }
 }
 
+/// The number of moves each player is expected to make in a game.
+const EXPECTED_MOVES: i64 = 40;
+
+/// How fast a game is played, used to keep separate ratings.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+)]
+pub enum Speed {
+    Blitz,
+    #[default]
+    Rapid,
+    Classical,
+    Correspondence,
+}
+
+impl fmt::Display for Speed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Blitz => write!(f, "{}", t!("blitz")),
+            Self::Rapid => write!(f, "{}", t!("rapid")),
+            Self::Classical => write!(f, "{}", t!("classical")),
+            Self::Correspondence => write!(f, "{}", t!("correspondence")),
+        }
+    }
+}
+
+impl From<&TimeSettings> for Speed {
+    fn from(time_settings: &TimeSettings) -> Self {
+        match time_settings {
+            TimeSettings::Timed(time) => {
+                let expected = time.milliseconds_left + EXPECTED_MOVES * time.add_seconds * SECOND;
+
+                if expected < 10 * MINUTE {
+                    Self::Blitz
+                } else if expected < 30 * MINUTE {
+                    Self::Rapid
+                } else if expected < DAY {
+                    Self::Classical
+                } else {
+                    Self::Correspondence
+                }
+            }
+            TimeSettings::UnTimed => Self::Correspondence,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
 pub enum TimeUnix {
     Time(i64),
@@ -321,6 +374,49 @@ pub enum TimeUnix {
 impl TimeUnix {
     #[must_use]
     pub fn timed() -> Self {
-        Self::Time(Timestamp::now().as_millisecond())
+        Self::Time(clock::now().as_millisecond())
+    }
+}
+
+/// How far the server's clock is from ours, worked out from pings that carry
+/// the server's time, so the clocks of a game can be run forward between the
+/// times the server says what they are.
+#[derive(Clone, Debug, Default)]
+pub struct ServerClock {
+    /// The latest pings as round trips and offsets, in milliseconds.
+    pings: VecDeque<(i64, i64)>,
+}
+
+impl ServerClock {
+    /// The server's time minus ours, from the ping with the shortest round
+    /// trip, since it had the least room to be off by.
+    #[must_use]
+    pub fn offset(&self) -> i64 {
+        self.pings
+            .iter()
+            .min_by_key(|(round_trip, _)| *round_trip)
+            .map_or(0, |(_, offset)| *offset)
+    }
+
+    /// The server's time now, in milliseconds.
+    #[must_use]
+    pub fn now(&self) -> i64 {
+        Timestamp::now().as_millisecond() + self.offset()
+    }
+
+    /// A ping sent at `sent` came back at `received` saying it was `server` on
+    /// the server. The server's time is taken to be from halfway through.
+    pub fn ping(&mut self, sent: i64, received: i64, server: i64) {
+        let round_trip = received - sent;
+        if round_trip < 0 {
+            return;
+        }
+
+        if self.pings.len() == PINGS_KEPT {
+            self.pings.pop_front();
+        }
+
+        self.pings
+            .push_back((round_trip, server - (sent + round_trip / 2)));
     }
 }
@@ -17,17 +17,26 @@
 // SPDX-FileCopyrightText: 2025 David Campbell <david@hnefatafl.org>
 
 use std::{
+    cmp::Reverse,
     collections::{HashMap, HashSet},
     sync::{Arc, Mutex},
 };
 
-use jiff::Timestamp;
+use jiff::{SignedDuration, Timestamp};
 use rand::seq::SliceRandom;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 use crate::{
-    Id, accounts::Accounts, board::BoardSize, glicko::Rating, server_game::ServerGame,
-    status::Status, time::TimeSettings,
+    Id,
+    accounts::{Account, Accounts},
+    board::BoardSize,
+    club::Clubs,
+    glicko::Rating,
+    rating::Rated,
+    server_game::ServerGame,
+    status::Status,
+    time::TimeSettings,
 };
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -57,10 +66,174 @@ pub struct TournamentFull {
     pub players: HashSet<String>,
     pub board_size: BoardSize,
     pub time_setting: TimeSettings,
+    /// Whether the games count towards the players' ratings.
+    #[serde(default)]
+    pub rated: Rated,
+    #[serde(default)]
+    pub requirements: Requirements,
+    /// How many days the players have for each round, after which the player
+    /// to move forfeits the games left.
+    #[serde(default)]
+    pub round_days: Option<i64>,
+    /// How many minutes an arena lasts, `None` for the group format.
+    #[serde(default)]
+    pub arena_minutes: Option<i64>,
     pub date: Option<Timestamp>,
     pub group_size: GroupSize,
     pub number_of_games: NumberOfGames,
     pub tournament: Option<Tournament>,
+    /// The recurring event that set up the tournament.
+    #[serde(default)]
+    pub event: Option<String>,
+}
+
+/// Who may join a tournament.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Requirements {
+    /// The fewest rated games a player has to have played.
+    #[serde(default)]
+    pub rated_games: u64,
+    #[serde(default)]
+    pub rating_minimum: Option<f64>,
+    #[serde(default)]
+    pub rating_maximum: Option<f64>,
+    /// The most players that may join.
+    #[serde(default)]
+    pub players_maximum: Option<usize>,
+    /// The club the players have to be members of.
+    #[serde(default)]
+    pub club: Option<String>,
+}
+
+/// Why a player can't join, as it's sent after `? join_tournament`.
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum JoinError {
+    #[error("full")]
+    Full,
+    #[error("rated_games {0}")]
+    RatedGames(u64),
+    #[error("rating_minimum {0}")]
+    RatingMinimum(f64),
+    #[error("rating_maximum {0}")]
+    RatingMaximum(f64),
+    #[error("club {0}")]
+    Club(String),
+}
+
+impl TournamentFull {
+    /// # Errors
+    ///
+    /// If the player doesn't meet the requirements or the tournament is full.
+    pub fn can_join(
+        &self,
+        username: &str,
+        account: &Account,
+        clubs: &Clubs,
+    ) -> Result<(), JoinError> {
+        let requirements = &self.requirements;
+
+        if let Some(maximum) = requirements.players_maximum
+            && !self.players.contains(username)
+            && self.players.len() >= maximum
+        {
+            return Err(JoinError::Full);
+        }
+
+        if account.rated_games < requirements.rated_games {
+            return Err(JoinError::RatedGames(requirements.rated_games));
+        }
+
+        let rating = account.rating.rating;
+
+        if let Some(minimum) = requirements.rating_minimum
+            && rating < minimum
+        {
+            return Err(JoinError::RatingMinimum(minimum));
+        }
+
+        if let Some(maximum) = requirements.rating_maximum
+            && rating > maximum
+        {
+            return Err(JoinError::RatingMaximum(maximum));
+        }
+
+        if let Some(club) = &requirements.club
+            && !clubs.is_member(club, username)
+        {
+            return Err(JoinError::Club(club.clone()));
+        }
+
+        Ok(())
+    }
+
+    /// What the lobby banner says about the next tournament, `None` when there
+    /// is no date set or it already started.
+    #[must_use]
+    pub fn announcement(&self) -> Option<Announcement> {
+        if self.tournament.is_some() {
+            return None;
+        }
+
+        Some(Announcement {
+            date: self.date?,
+            board_size: self.board_size,
+            time_setting: self.time_setting,
+            rated: self.rated,
+            players: self.players.len(),
+        })
+    }
+
+    /// Whether `username` joined the tournament, before or after it started.
+    #[must_use]
+    pub fn has_player(&self, username: &str) -> bool {
+        match &self.tournament {
+            Some(tournament) => tournament.players.contains(username),
+            None => self.players.contains(username),
+        }
+    }
+}
+
+/// Sent to everyone in the lobby as `= announcement JSON`, or `= announcement`
+/// by itself once there is nothing to announce.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Announcement {
+    pub date: Timestamp,
+    pub board_size: BoardSize,
+    pub time_setting: TimeSettings,
+    pub rated: Rated,
+    /// How many players have joined so far.
+    pub players: usize,
+}
+
+impl Announcement {
+    /// The tournament as an iCalendar event, for adding it to a calendar.
+    /// `now` is when the event was made.
+    #[must_use]
+    pub fn icalendar(&self, now: Timestamp) -> String {
+        let description = format!(
+            "board size: {}, time: {}, {}",
+            self.board_size, self.time_setting, self.rated
+        )
+        .replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace(';', "\\;");
+
+        [
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//hnefatafl.org//hnefatafl-copenhagen//EN".to_string(),
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:tournament-{}@hnefatafl.org", self.date.as_second()),
+            format!("DTSTAMP:{}", now.strftime("%Y%m%dT%H%M%SZ")),
+            format!("DTSTART:{}", self.date.strftime("%Y%m%dT%H%M%SZ")),
+            "SUMMARY:Copenhagen Hnefatafl Tournament".to_string(),
+            format!("DESCRIPTION:{description}"),
+            "END:VEVENT".to_string(),
+            "END:VCALENDAR".to_string(),
+            String::new(),
+        ]
+        .join("\r\n")
+    }
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -69,7 +242,19 @@ pub struct Tournament {
     pub players: HashSet<String>,
     pub board_size: BoardSize,
     pub time_setting: TimeSettings,
+    #[serde(default)]
+    pub rated: Rated,
+    #[serde(default)]
+    pub round_days: Option<i64>,
     pub date: Timestamp,
+    #[serde(default)]
+    pub round_started: Timestamp,
+    /// The players who left, who forfeit the games left and don't go on to
+    /// the next round.
+    #[serde(default)]
+    pub withdrawn: HashSet<String>,
+    #[serde(default)]
+    pub arena: Option<Arena>,
     pub group_size: usize,
     pub number_of_games: usize,
     pub groups: Vec<Vec<Arc<Mutex<Group>>>>,
@@ -79,7 +264,12 @@ pub struct Tournament {
 impl Tournament {
     #[allow(clippy::float_cmp, clippy::too_many_lines)]
     #[must_use]
-    pub fn game_over(&mut self, game: &ServerGame) -> bool {
+    pub fn game_over(&mut self, game: &ServerGame, now: Timestamp) -> bool {
+        if let Some(arena) = &mut self.arena {
+            arena.game_over(game, now);
+            return false;
+        }
+
         let mut next_round = false;
 
         if let Some(group) = self.tournament_games.get_mut(&game.id) {
@@ -170,7 +360,7 @@ impl Tournament {
                             && let Some(top_score) = group.records.values().map(Record::score).max()
                         {
                             for (name, record) in &group.records {
-                                if record.score() == top_score {
+                                if record.score() == top_score && !self.withdrawn.contains(name) {
                                     players.insert(name.clone());
                                 } else {
                                     next_round = true;
@@ -282,9 +472,35 @@ impl Tournament {
         groups
     }
 
+    /// When the player to move forfeits the games left in the round.
+    #[must_use]
+    pub fn round_deadline(&self) -> Option<Timestamp> {
+        let days = self.round_days?;
+
+        self.round_started
+            .checked_add(SignedDuration::from_hours(days.checked_mul(24)?))
+            .ok()
+    }
+
     #[must_use]
     pub fn is_tournament_game(&self, id: &Id) -> bool {
         self.tournament_games.contains_key(id)
+            || self
+                .arena
+                .as_ref()
+                .is_some_and(|arena| arena.games.contains_key(id))
+    }
+
+    /// The tournament games that are still going.
+    #[must_use]
+    pub fn game_ids(&self) -> Vec<Id> {
+        let mut ids: Vec<_> = self.tournament_games.keys().copied().collect();
+
+        if let Some(arena) = &self.arena {
+            ids.extend(arena.games.keys());
+        }
+
+        ids
     }
 
     pub fn remove_duplicate_ids(&mut self) {
@@ -314,6 +530,196 @@ impl Tournament {
     }
 }
 
+/// A tournament where the players who are free get paired again as soon as
+/// they finish a game, until the time runs out.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Arena {
+    /// The arena is over: no one gets paired after this and the games still
+    /// going on don't count.
+    pub ends: Timestamp,
+    pub scores: HashMap<String, ArenaScore>,
+    /// The attacker and the defender of each game going on.
+    pub games: HashMap<Id, (String, String)>,
+    /// The players who halved their clock in a game for an extra point if
+    /// they win it.
+    #[serde(default)]
+    pub berserk: HashSet<(Id, String)>,
+}
+
+impl Arena {
+    #[must_use]
+    pub fn new(players: &HashSet<String>, ends: Timestamp) -> Self {
+        Self {
+            ends,
+            scores: players
+                .iter()
+                .map(|player| (player.clone(), ArenaScore::default()))
+                .collect(),
+            games: HashMap::new(),
+            berserk: HashSet::new(),
+        }
+    }
+
+    /// Whether `username` plays in the game and hasn't gone berserk in it
+    /// already.
+    #[must_use]
+    pub fn can_berserk(&self, id: Id, username: &str) -> bool {
+        self.games
+            .get(&id)
+            .is_some_and(|(attacker, defender)| attacker == username || defender == username)
+            && !self.berserk.contains(&(id, username.to_string()))
+    }
+
+    /// Whether the time ran out.
+    #[must_use]
+    pub fn is_over(&self, now: Timestamp) -> bool {
+        now >= self.ends
+    }
+
+    /// Pairs the players in `available` that aren't playing, the closest
+    /// scores first, and not against the player they just played if there is
+    /// anyone else. Whoever was the attacker less often is the attacker.
+    #[must_use]
+    pub fn pair(&self, available: &HashSet<String>, now: Timestamp) -> Vec<(String, String)> {
+        if self.is_over(now) {
+            return Vec::new();
+        }
+
+        let playing: HashSet<_> = self
+            .games
+            .values()
+            .flat_map(|(attacker, defender)| [attacker, defender])
+            .collect();
+
+        let mut free: Vec<_> = self
+            .scores
+            .iter()
+            .filter(|(player, _)| available.contains(*player) && !playing.contains(player))
+            .collect();
+
+        free.shuffle(&mut rand::rng());
+        free.sort_by_key(|(_, score)| Reverse(score.points));
+
+        let mut pairs = Vec::new();
+
+        while !free.is_empty() {
+            let (player_1, score_1) = free.remove(0);
+
+            if free.is_empty() {
+                break;
+            }
+
+            let index = free
+                .iter()
+                .position(|(player, _)| score_1.last_opponent.as_ref() != Some(*player))
+                .unwrap_or(0);
+
+            let (player_2, score_2) = free.remove(index);
+
+            if score_1.attacker <= score_2.attacker {
+                pairs.push((player_1.clone(), player_2.clone()));
+            } else {
+                pairs.push((player_2.clone(), player_1.clone()));
+            }
+        }
+
+        pairs
+    }
+
+    /// Keeps track of a game that was just paired.
+    pub fn game_started(&mut self, id: Id, attacker: String, defender: String) {
+        if let Some(score) = self.scores.get_mut(&attacker) {
+            score.attacker += 1;
+        }
+
+        self.games.insert(id, (attacker, defender));
+    }
+
+    /// Scores a game that ended before the arena did.
+    fn game_over(&mut self, game: &ServerGame, now: Timestamp) {
+        let Some((attacker, defender)) = self.games.remove(&game.id) else {
+            return;
+        };
+
+        let attacker_berserk = self.berserk.remove(&(game.id, attacker.clone()));
+        let defender_berserk = self.berserk.remove(&(game.id, defender.clone()));
+
+        if self.is_over(now) {
+            return;
+        }
+
+        let (attacker_points, defender_points) = match game.game.status {
+            Status::AttackerWins => (2, 0),
+            Status::DefenderWins => (0, 2),
+            Status::Draw => (1, 1),
+            Status::Ongoing => return,
+        };
+
+        if let Some(score) = self.scores.get_mut(&attacker) {
+            score.add(attacker_points, &defender, attacker_berserk);
+        }
+        if let Some(score) = self.scores.get_mut(&defender) {
+            score.add(defender_points, &attacker, defender_berserk);
+        }
+    }
+
+    /// The players with the most points first.
+    #[must_use]
+    pub fn leaderboard(&self) -> Vec<(&String, &ArenaScore)> {
+        let mut leaderboard: Vec<_> = self.scores.iter().collect();
+
+        leaderboard.sort_by(|(player_1, score_1), (player_2, score_2)| {
+            score_2
+                .points
+                .cmp(&score_1.points)
+                .then_with(|| player_1.cmp(player_2))
+        });
+
+        leaderboard
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ArenaScore {
+    pub points: u64,
+    /// How many games in a row they won.
+    pub streak: u64,
+    pub games: u64,
+    pub attacker: u64,
+    pub last_opponent: Option<String>,
+}
+
+impl ArenaScore {
+    /// After two wins in a row the points count double until they don't win.
+    #[must_use]
+    pub fn on_fire(&self) -> bool {
+        self.streak >= 2
+    }
+
+    /// A win is 2 points and a draw 1, and going berserk is worth one more on
+    /// a win.
+    fn add(&mut self, points: u64, opponent: &str, berserk: bool) {
+        if self.on_fire() {
+            self.points += 2 * points;
+        } else {
+            self.points += points;
+        }
+
+        if points == 2 && berserk {
+            self.points += 1;
+        }
+
+        if points == 2 {
+            self.streak += 1;
+        } else {
+            self.streak = 0;
+        }
+
+        self.games += 1;
+        self.last_opponent = Some(opponent.to_string());
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 pub struct Group {
     pub id: u64,
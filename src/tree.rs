@@ -134,18 +134,20 @@ impl Tree {
     #[must_use]
     pub fn previous_boards(&self) -> (Plays, PreviousBoards) {
         let mut node = &self.here();
-        let mut previous_boards = PreviousBoards::new(node.board.size());
         let mut boards = VecDeque::new();
 
-        previous_boards.0.push(node.board.clone());
         boards.push_front(node.board.clone());
 
         while let Some(parent) = node.parent {
             node = &self.arena[parent];
-            previous_boards.0.push(node.board.clone());
             boards.push_front(node.board.clone());
         }
 
+        let mut previous_boards = PreviousBoards::from(node.board.clone());
+        for board in boards.iter().skip(1) {
+            previous_boards.push(board);
+        }
+
         let plays = boards
             .iter()
             .collect::<Vec<_>>()
@@ -18,13 +18,20 @@
 
 #[cfg(any(target_family = "unix", target_family = "windows"))]
 use std::process::Command;
-use std::{env, fs::DirBuilder, path::PathBuf, process::ExitStatus, time::Duration};
+use std::{
+    env, fs::DirBuilder, path::PathBuf, process::ExitStatus, sync::OnceLock, time::Duration,
+};
 
 use directories::ProjectDirs;
 use env_logger::Builder;
 use log::LevelFilter;
 
-use crate::ai::{AI, AiBanal, AiBasic, AiMonteCarlo};
+use crate::{
+    ai::{AI, AiBanal, AiBasic, AiMonteCarlo},
+    game_tree::MEMORY,
+};
+
+static DATA_FOLDER: OnceLock<PathBuf> = OnceLock::new();
 
 /// # Errors
 ///
@@ -33,6 +40,7 @@ pub fn choose_ai(
     ai: &str,
     seconds: Option<u64>,
     depth: Option<u8>,
+    memory: Option<usize>,
     sequential: bool,
 ) -> anyhow::Result<Box<dyn AI>> {
     match ai {
@@ -45,10 +53,12 @@ pub fn choose_ai(
         "monte-carlo" => {
             let seconds = seconds.unwrap_or(10);
             let depth = depth.unwrap_or(20);
+            let memory = memory.map_or(MEMORY, |mebibytes| mebibytes.saturating_mul(1024 * 1024));
 
             Ok(Box::new(AiMonteCarlo::new(
                 Duration::from_secs(seconds),
                 depth,
+                memory,
             )))
         }
         _ => Err(anyhow::Error::msg(
@@ -93,7 +103,9 @@ pub fn create_config_folder() -> anyhow::Result<()> {
 ///
 /// If it fails to create the directory or the directory does not already exist.
 pub fn create_data_folder() -> anyhow::Result<()> {
-    if let Some(project_dir) = initialize_project_dirs() {
+    if let Some(folder) = DATA_FOLDER.get() {
+        DirBuilder::new().recursive(true).create(folder)?;
+    } else if let Some(project_dir) = initialize_project_dirs() {
         DirBuilder::new()
             .recursive(true)
             .create(project_dir.data_local_dir())?;
@@ -102,6 +114,12 @@ pub fn create_data_folder() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Keeps the data files in `folder` instead of the usual place. Only the
+/// first call counts.
+pub fn set_data_folder(folder: PathBuf) {
+    let _ok = DATA_FOLDER.set(folder);
+}
+
 #[must_use]
 pub fn config_file(file: &str) -> PathBuf {
     let mut project_dir = if let Some(project_dir) = initialize_project_dirs() {
@@ -116,7 +134,9 @@ pub fn config_file(file: &str) -> PathBuf {
 
 #[must_use]
 pub fn data_file(file: &str) -> PathBuf {
-    let mut project_dir = if let Some(project_dir) = initialize_project_dirs() {
+    let mut project_dir = if let Some(folder) = DATA_FOLDER.get() {
+        folder.clone()
+    } else if let Some(project_dir) = initialize_project_dirs() {
         project_dir.data_local_dir().to_path_buf()
     } else {
         PathBuf::new()
@@ -127,6 +147,12 @@ pub fn data_file(file: &str) -> PathBuf {
 }
 
 pub fn init_logger(module: &str, debug: bool, systemd: bool) {
+    logger(module, debug, systemd).init();
+}
+
+/// The logger [`init_logger`] starts, for when the log goes somewhere else.
+#[must_use]
+pub fn logger(module: &str, debug: bool, systemd: bool) -> Builder {
     let mut builder = Builder::new();
 
     if systemd {
@@ -146,7 +172,7 @@ pub fn init_logger(module: &str, debug: bool, systemd: bool) {
         builder.filter(Some(module), LevelFilter::Debug);
     }
 
-    builder.init();
+    builder
 }
 
 #[must_use]
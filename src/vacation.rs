@@ -0,0 +1,99 @@
+// This file is part of hnefatafl-copenhagen.
+//
+// hnefatafl-copenhagen is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hnefatafl-copenhagen is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2026 David Campbell <david@hnefatafl.org>
+
+//! Days off from correspondence games. While a player is on vacation the
+//! clocks of their correspondence games don't run on their move.
+
+use jiff::{Timestamp, tz::TimeZone};
+use serde::{Deserialize, Serialize};
+
+use crate::time::DAY;
+
+/// The vacation days every account gets each year.
+pub const VACATION_DAYS: u32 = 30;
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Vacation {
+    /// The year `days_used` counts the days of.
+    #[serde(default)]
+    pub year: i16,
+    #[serde(default)]
+    pub days_used: u32,
+    /// When the vacation ends, in unix milliseconds.
+    #[serde(default)]
+    pub until: Option<i64>,
+}
+
+impl Vacation {
+    #[must_use]
+    pub fn days_left(&self, now: Timestamp) -> u32 {
+        if self.year == year(now) {
+            VACATION_DAYS.saturating_sub(self.days_used)
+        } else {
+            VACATION_DAYS
+        }
+    }
+
+    #[must_use]
+    pub fn on(&self, now: Timestamp) -> bool {
+        self.until.is_some_and(|until| until > now.as_millisecond())
+    }
+
+    /// Takes `days` off starting `now`, or adds them to the vacation you're
+    /// on.
+    ///
+    /// # Errors
+    ///
+    /// If `days` is 0 or more than you have left this year.
+    pub fn start(&mut self, days: u32, now: Timestamp) -> anyhow::Result<()> {
+        let left = self.days_left(now);
+        if days == 0 || days > left {
+            return Err(anyhow::Error::msg(format!(
+                "you can take from 1 to {left} vacation days"
+            )));
+        }
+
+        if self.year != year(now) {
+            self.year = year(now);
+            self.days_used = 0;
+        }
+
+        let from = self
+            .until
+            .filter(|until| *until > now.as_millisecond())
+            .unwrap_or_else(|| now.as_millisecond());
+
+        self.days_used += days;
+        self.until = Some(from + i64::from(days) * DAY);
+        Ok(())
+    }
+
+    /// Comes back early and gets back the whole days that are left.
+    pub fn end(&mut self, now: Timestamp) {
+        if let Some(until) = self.until.take()
+            && self.year == year(now)
+        {
+            let days = u32::try_from((until - now.as_millisecond()) / DAY).unwrap_or_default();
+            self.days_used = self.days_used.saturating_sub(days);
+        }
+    }
+}
+
+fn year(now: Timestamp) -> i16 {
+    now.to_zoned(TimeZone::UTC).year()
+}